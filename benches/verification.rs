@@ -0,0 +1,149 @@
+//! # Verification Benchmarks
+//!
+//! Batch signature verification against the equivalent serial loop, for
+//! 256 independent signatures, and `batch_verify` over 500.
+
+use blvm_sdk::governance::verification::{
+    batch_verify, batch_verify_fail_fast, verify_signature, verify_signatures_batch,
+};
+use blvm_sdk::governance::{GovernanceKeypair, Multisig, PublicKey, Signature};
+use blvm_sdk::sign_message;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const PAIRS: usize = 256;
+
+struct Fixture {
+    messages: Vec<Vec<u8>>,
+    signatures: Vec<Signature>,
+    public_keys: Vec<PublicKey>,
+}
+
+fn fixture() -> Fixture {
+    let keypairs: Vec<_> = (0..PAIRS)
+        .map(|_| GovernanceKeypair::generate().unwrap())
+        .collect();
+    let messages: Vec<Vec<u8>> = (0..PAIRS)
+        .map(|i| format!("release:v1.0.{}:abc123", i).into_bytes())
+        .collect();
+    let signatures = keypairs
+        .iter()
+        .zip(&messages)
+        .map(|(keypair, message)| sign_message(keypair.secret_key.expose(), message).unwrap())
+        .collect();
+    Fixture {
+        messages,
+        signatures,
+        public_keys: keypairs.iter().map(|kp| kp.public_key()).collect(),
+    }
+}
+
+fn bench_signatures(c: &mut Criterion) {
+    let fixture = fixture();
+    let pairs: Vec<_> = (0..PAIRS)
+        .map(|i| {
+            (
+                &fixture.signatures[i],
+                fixture.messages[i].as_slice(),
+                &fixture.public_keys[i],
+            )
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("verify_256_signatures");
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            pairs
+                .iter()
+                .map(|(signature, message, key)| verify_signature(signature, message, key))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        })
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| verify_signatures_batch(&pairs).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_batch_verify(c: &mut Criterion) {
+    let keypairs: Vec<_> = (0..10)
+        .map(|_| GovernanceKeypair::generate().unwrap())
+        .collect();
+    let messages: Vec<Vec<u8>> = (0..500)
+        .map(|i| format!("release:v1.0.{}:abc123", i).into_bytes())
+        .collect();
+    let items: Vec<_> = messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| {
+            let keypair = &keypairs[i % keypairs.len()];
+            (
+                sign_message(keypair.secret_key.expose(), message).unwrap(),
+                message.as_slice(),
+                keypair.public_key(),
+            )
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("batch_verify_500_signatures");
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            items
+                .iter()
+                .all(|(signature, message, key)| verify_signature(signature, message, key).unwrap())
+        })
+    });
+    group.bench_function("batch", |b| b.iter(|| batch_verify(&items).unwrap()));
+    group.bench_function("fail_fast", |b| {
+        b.iter(|| batch_verify_fail_fast(&items).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_multisig(c: &mut Criterion) {
+    let keypairs: Vec<_> = (0..3)
+        .map(|_| GovernanceKeypair::generate().unwrap())
+        .collect();
+    let multisig =
+        Multisig::new(2, 3, keypairs.iter().map(|kp| kp.public_key()).collect()).unwrap();
+    let messages: Vec<Vec<u8>> = (0..PAIRS / 2)
+        .map(|i| format!("release:v1.0.{}:abc123", i).into_bytes())
+        .collect();
+    let signatures: Vec<Vec<Signature>> = messages
+        .iter()
+        .map(|message| {
+            keypairs[..2]
+                .iter()
+                .map(|kp| sign_message(kp.secret_key.expose(), message).unwrap())
+                .collect()
+        })
+        .collect();
+    let batch: Vec<(&[u8], &[Signature])> = messages
+        .iter()
+        .zip(&signatures)
+        .map(|(message, signatures)| (message.as_slice(), signatures.as_slice()))
+        .collect();
+
+    let mut group = c.benchmark_group("multisig_128_messages");
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            batch
+                .iter()
+                .map(|(message, signatures)| multisig.verify(message, signatures))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        })
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| multisig.verify_batch(&batch).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_signatures,
+    bench_batch_verify,
+    bench_multisig
+);
+criterion_main!(benches);
@@ -2,8 +2,8 @@
 //!
 //! Example of signing governance messages.
 
-use bllvm_sdk::governance::verify_signature;
-use bllvm_sdk::{sign_message, GovernanceKeypair, GovernanceMessage};
+use blvm_sdk::governance::verify_signature;
+use blvm_sdk::{sign_message, GovernanceKeypair, GovernanceMessage};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=== Governance Signing Example ===\n");
@@ -33,7 +33,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Sign the message
     println!("3. Signing message...");
-    let signature = sign_message(&keypair.secret_key, &message.to_signing_bytes())?;
+    let signature = sign_message(keypair.secret_key.expose(), &message.to_signing_bytes())?;
     println!("   Signature: {}", signature);
     println!();
 
@@ -2,8 +2,8 @@
 //!
 //! Example of verifying governance signatures.
 
-use bllvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Multisig};
-use bllvm_sdk::{sign_message, verify_signature};
+use blvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Multisig};
+use blvm_sdk::{sign_message, verify_signature};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=== Governance Verification Example ===\n");
@@ -44,7 +44,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let signatures: Vec<_> = keypairs[0..3]
         .iter()
         .map(|kp| {
-            let sig = sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap();
+            let sig = sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap();
             println!("   Signed with key: {}", kp.public_key());
             sig
         })
@@ -62,7 +62,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("6. Testing with insufficient signatures (2 keys)...");
     let insufficient_signatures: Vec<_> = keypairs[0..2]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let insufficient_verified =
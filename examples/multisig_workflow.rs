@@ -2,8 +2,8 @@
 //!
 //! Complete multisig workflow example.
 
-use bllvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Multisig};
-use bllvm_sdk::{sign_message, verify_signature};
+use blvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Multisig};
+use blvm_sdk::{sign_message, verify_signature};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=== Multisig Workflow Example ===\n");
@@ -32,7 +32,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create different types of messages
     println!("3. Creating governance messages...");
-    let messages = vec![
+    let messages = [
         GovernanceMessage::Release {
             version: "v1.0.0".to_string(),
             commit_hash: "abc123def456".to_string(),
@@ -59,7 +59,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let signatures: Vec<_> = keypairs[0..6]
             .iter()
             .map(|kp| {
-                let sig = sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap();
+                let sig =
+                    sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap();
                 println!("     Signed with key: {}", kp.public_key());
                 sig
             })
@@ -78,7 +79,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Add 4 valid signatures
     for kp in &keypairs[0..4] {
-        let sig = sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap();
+        let sig = sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap();
         signatures.push(sig);
         println!("   Added valid signature from: {}", kp.public_key());
     }
@@ -89,13 +90,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         commit_hash: "def456ghi789".to_string(),
     };
     for kp in &keypairs[4..6] {
-        let sig = sign_message(&kp.secret_key, &wrong_message.to_signing_bytes()).unwrap();
+        let sig = sign_message(kp.secret_key.expose(), &wrong_message.to_signing_bytes()).unwrap();
         signatures.push(sig);
         println!("   Added invalid signature from: {}", kp.public_key());
     }
 
     // Add 1 more valid signature
-    let sig = sign_message(&keypairs[6].secret_key, &message.to_signing_bytes()).unwrap();
+    let sig = sign_message(keypairs[6].secret_key.expose(), &message.to_signing_bytes()).unwrap();
     signatures.push(sig);
     println!(
         "   Added valid signature from: {}",
@@ -139,7 +140,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test with exactly threshold number of signatures
     let exact_signatures: Vec<_> = keypairs[0..6]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
     let exact_verified = multisig.verify(&message.to_signing_bytes(), &exact_signatures)?;
     println!(
@@ -150,7 +151,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test with all signatures
     let all_signatures: Vec<_> = keypairs
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
     let all_verified = multisig.verify(&message.to_signing_bytes(), &all_signatures)?;
     println!("   All signatures verified: {}", all_verified);
@@ -0,0 +1,18 @@
+//! Arbitrary bytes never panic the DER signature parsers, and whatever the
+//! strict parser accepts is its own canonical encoding.
+
+#![no_main]
+
+use blvm_sdk::governance::Signature;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(signature) = Signature::from_der_bytes(data) {
+        assert_eq!(signature.to_der_bytes(), data);
+        assert_eq!(
+            Signature::to_compact_from_der(data).unwrap(),
+            signature.to_bytes()
+        );
+    }
+    let _ = Signature::from_der_lax(data);
+});
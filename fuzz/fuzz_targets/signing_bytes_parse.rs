@@ -0,0 +1,13 @@
+//! Arbitrary bytes never panic the signed message parser, and whatever
+//! parses is the exact encoding of the parsed message.
+
+#![no_main]
+
+use blvm_sdk::governance::GovernanceMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = GovernanceMessage::from_signing_bytes(data) {
+        assert_eq!(message.to_signing_bytes(), data);
+    }
+});
@@ -0,0 +1,34 @@
+//! Every message parses back from its signing bytes.
+
+#![no_main]
+
+use blvm_sdk::governance::GovernanceMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u8, String, String, String, u64)| {
+    let (variant, a, b, c, amount) = input;
+    let message = match variant % 5 {
+        0 => GovernanceMessage::Release {
+            version: a,
+            commit_hash: b,
+        },
+        1 => GovernanceMessage::ModuleApproval {
+            module_name: a,
+            version: b,
+        },
+        2 => GovernanceMessage::ModuleBinaryApproval {
+            module_name: a,
+            version: b,
+            binary_hash: c,
+        },
+        3 => GovernanceMessage::BudgetDecision { amount, purpose: a },
+        _ => GovernanceMessage::ConfigUpdate {
+            module_name: a,
+            config_hash: b,
+        },
+    };
+
+    let parsed = GovernanceMessage::from_signing_bytes(&message.to_signing_bytes())
+        .expect("signing bytes parse");
+    assert_eq!(parsed, message);
+});
@@ -4,12 +4,24 @@
 //!
 //! This tool collects signatures from multiple maintainers and creates a
 //! single signature file that can be verified against a multisig threshold.
+//! Existing aggregates can be merged in; they are read and written one entry
+//! at a time, so aggregates of any size fit in bounded memory. The output
+//! replaces its file only once complete, so an input may also be the output.
 
-use blvm_sdk::cli::input::parse_comma_separated;
+use blvm_sdk::cli::input::{load_public_keys, parse_comma_separated};
+use blvm_sdk::cli::notify::notify_report;
 use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
+use blvm_sdk::governance::aggregation::{
+    entry_signer, read_signature_json, signature_entry, AggregateReader, AggregateWriter,
+    DEFAULT_MAX_SIGNATURES,
+};
+use blvm_sdk::governance::{PublicKey, SignedArtifactMetadata, Threshold};
+use blvm_sdk::util::fs::write_atomic_with;
+use blvm_sdk::util::CancelToken;
 use clap::Parser;
 use serde_json::Value;
-use std::fs;
+use std::collections::HashSet;
+use std::io::BufWriter;
 use std::path::Path;
 
 /// Aggregate multiple signatures into a single file
@@ -21,21 +33,41 @@ struct Args {
     #[arg(short, long, default_value = "signatures.json")]
     output: String,
 
-    /// Output format (text, json)
+    /// Output format (text, json, jsonl)
     #[arg(short, long, default_value = "text")]
     format: OutputFormat,
 
     /// Signature files to aggregate (comma-separated)
-    #[arg(short, long, required = true)]
-    signatures: String,
+    #[arg(short, long, required_unless_present = "merge")]
+    signatures: Option<String>,
+
+    /// Existing aggregate files to merge in (comma-separated)
+    #[arg(short, long)]
+    merge: Option<String>,
 
     /// Threshold (e.g., "6-of-7")
     #[arg(short, long)]
-    threshold: Option<String>,
+    threshold: Option<Threshold>,
 
-    /// Public key files (comma-separated, for verification)
+    /// Public key files (comma-separated); the threshold total must match
+    /// the number of distinct keys
     #[arg(short, long)]
     pubkeys: Option<String>,
+
+    /// Most signatures to aggregate; more signature files, or more distinct
+    /// signers across merged aggregates, abort the aggregation
+    #[arg(long, default_value_t = DEFAULT_MAX_SIGNATURES)]
+    max_signatures: usize,
+
+    /// POST the JSON report to this URL when done (repeatable; needs the
+    /// net feature). Failed notifications are warnings and never change
+    /// the exit code
+    #[arg(long = "notify-url", value_name = "URL")]
+    notify_urls: Vec<String>,
+
+    /// File holding the shared secret for the notification HMAC header
+    #[arg(long, requires = "notify_urls")]
+    notify_secret_file: Option<String>,
 }
 
 fn main() {
@@ -46,6 +78,11 @@ fn main() {
         Ok(result) => {
             let output = format_aggregation_output(&result, &args, &formatter);
             println!("{}", output);
+            notify_report(
+                &args.notify_urls,
+                args.notify_secret_file.as_deref(),
+                &aggregation_json(&result),
+            );
         }
         Err(e) => {
             eprintln!("{}", formatter.format_error(&*e));
@@ -57,78 +94,152 @@ fn main() {
 #[derive(Debug)]
 struct AggregationResult {
     signature_count: usize,
+    duplicates_skipped: usize,
     output_file: String,
     threshold_met: bool,
-    signatures: Vec<Value>,
+    /// Keys of the signers, for entries that name one
+    signer_keys: Vec<PublicKey>,
 }
 
 fn aggregate_signatures(args: &Args) -> Result<AggregationResult, Box<dyn std::error::Error>> {
+    // The threshold must describe the committee given by --pubkeys
+    if let Some(pubkeys) = &args.pubkeys {
+        let public_keys = load_public_keys(&parse_comma_separated(pubkeys))?;
+        if let Some(threshold) = args.threshold {
+            threshold.check_committee(public_keys.len())?;
+        }
+    }
+
+    // Ctrl-C stops reading and merging
+    let cancel = CancelToken::on_interrupt().unwrap_or_default();
+
     // Parse signature files
-    let signature_files = parse_comma_separated(&args.signatures);
+    let signature_files = args
+        .signatures
+        .as_deref()
+        .map(parse_comma_separated)
+        .unwrap_or_default();
+    // Refuse before reading anything
+    if signature_files.len() > args.max_signatures {
+        return Err(too_many_signatures(args.max_signatures));
+    }
     let mut signatures = Vec::new();
     let mut metadata = None;
 
     for file_path in &signature_files {
+        cancel.check("aggregation")?;
         if !Path::new(file_path).exists() {
             return Err(format!("Signature file not found: {}", file_path).into());
         }
 
-        let sig_data = fs::read_to_string(file_path)?;
-        let sig_json: Value = serde_json::from_str(&sig_data)?;
+        // Transient read errors (network filesystems) are retried
+        let sig_json = read_signature_json(file_path)?;
 
         // Extract signature
-        let signature_entry = serde_json::json!({
-            "signature": sig_json.get("signature"),
-            "signer": sig_json.get("signer").or_else(|| sig_json.get("metadata").and_then(|m| m.get("signer"))),
-            "signed_at": sig_json.get("created_at").or_else(|| sig_json.get("metadata").and_then(|m| m.get("signed_at"))),
-            "public_key": sig_json.get("public_key"),
-        });
-
-        signatures.push(signature_entry);
+        signatures.push(signature_entry(&sig_json));
 
-        // Use first signature's metadata as base
+        // Every file's metadata must be complete; the first one's is kept
+        let file_metadata = SignedArtifactMetadata::from_field(sig_json.get("metadata"))
+            .map_err(|e| format!("{}: {}", file_path, e))?;
         if metadata.is_none() {
-            metadata = sig_json.get("metadata").cloned();
+            metadata = file_metadata;
         }
     }
 
-    // Create aggregated signature file
-    let aggregated = serde_json::json!({
-        "version": "1.0",
-        "signature_count": signatures.len(),
-        "signatures": signatures,
-        "threshold": args.threshold,
-        "metadata": metadata,
-        "aggregated_at": chrono::Utc::now().to_rfc3339(),
-    });
+    let mut aggregates = Vec::new();
+    for file_path in args
+        .merge
+        .as_deref()
+        .map(parse_comma_separated)
+        .unwrap_or_default()
+    {
+        if !Path::new(&file_path).exists() {
+            return Err(format!("Aggregate file not found: {}", file_path).into());
+        }
+        let reader = AggregateReader::open(&file_path)?;
+        // Without signature files, use the first aggregate's metadata
+        let file_metadata = SignedArtifactMetadata::from_field(reader.header().get("metadata"))
+            .map_err(|e| format!("{}: {}", file_path, e))?;
+        if metadata.is_none() {
+            metadata = file_metadata;
+        }
+        aggregates.push(reader);
+    }
 
-    // Save aggregated signatures
-    let json_str = serde_json::to_string_pretty(&aggregated)?;
-    fs::write(&args.output, json_str)?;
+    // Write the aggregate, skipping entries by a signer already present;
+    // the output replaces its file once complete
+    let mut signers = HashSet::new();
+    let mut signer_keys = Vec::new();
+    let mut duplicates_skipped = 0;
+    let metadata = metadata.map(|metadata| metadata.to_value());
+    let signature_count = write_atomic_with(
+        Path::new(&args.output),
+        |file| -> Result<usize, Box<dyn std::error::Error>> {
+            let mut writer =
+                AggregateWriter::new(BufWriter::new(file), args.threshold, metadata.as_ref())?;
+            let mut append = |entry: &Value| -> Result<(), Box<dyn std::error::Error>> {
+                let signer = entry_signer(entry)?;
+                if signers.contains(&signer) {
+                    duplicates_skipped += 1;
+                } else if signers.len() >= args.max_signatures {
+                    return Err(too_many_signatures(args.max_signatures));
+                } else {
+                    // Entries without a key are told apart by signature
+                    if entry.get("public_key").is_some_and(|key| !key.is_null()) {
+                        signer_keys.push(PublicKey::from_bytes(&signer)?);
+                    }
+                    signers.insert(signer);
+                    writer.append(entry)?;
+                }
+                Ok(())
+            };
+            for entry in &signatures {
+                append(entry)?;
+            }
+            for mut reader in aggregates {
+                while let Some(entry) = reader.next_entry()? {
+                    cancel.check("aggregation")?;
+                    append(&entry)?;
+                }
+                reader.finish()?;
+            }
+            let signature_count = writer.signature_count();
+            writer.finish()?;
+            Ok(signature_count)
+        },
+    )?;
 
     // Check threshold if provided
-    let threshold_met = if let Some(threshold_str) = &args.threshold {
-        let parts: Vec<&str> = threshold_str.split("-of-").collect();
-        if parts.len() == 2 {
-            if let (Ok(required), Ok(_total)) =
-                (parts[0].parse::<usize>(), parts[1].parse::<usize>())
-            {
-                signatures.len() >= required
-            } else {
-                false
-            }
-        } else {
-            false
-        }
-    } else {
-        true // No threshold specified, assume met if we have signatures
+    let threshold_met = match args.threshold {
+        Some(threshold) => threshold.is_met(signature_count),
+        None => true, // No threshold specified, assume met if we have signatures
     };
 
     Ok(AggregationResult {
-        signature_count: signatures.len(),
+        signature_count,
+        duplicates_skipped,
         output_file: args.output.clone(),
         threshold_met,
-        signatures,
+        signer_keys,
+    })
+}
+
+fn too_many_signatures(max: usize) -> Box<dyn std::error::Error> {
+    format!(
+        "More than {} signatures to aggregate; raise --max-signatures to allow more",
+        max
+    )
+    .into()
+}
+
+/// JSON output, also posted to `--notify-url`
+fn aggregation_json(result: &AggregationResult) -> serde_json::Value {
+    serde_json::json!({
+        "success": true,
+        "signature_count": result.signature_count,
+        "duplicates_skipped": result.duplicates_skipped,
+        "threshold_met": result.threshold_met,
+        "output_file": result.output_file,
     })
 }
 
@@ -137,24 +248,30 @@ fn format_aggregation_output(
     args: &Args,
     formatter: &OutputFormatter,
 ) -> String {
-    if args.format == OutputFormat::Json {
-        let output_data = serde_json::json!({
-            "success": true,
-            "signature_count": result.signature_count,
-            "threshold_met": result.threshold_met,
-            "output_file": result.output_file,
-        });
-        formatter
-            .format(&output_data)
-            .unwrap_or_else(|_| "{}".to_string())
-    } else {
-        format!(
-            "Aggregated {} signatures\n\
-             Threshold met: {}\n\
-             Saved to: {}\n",
-            result.signature_count,
-            if result.threshold_met { "Yes" } else { "No" },
-            result.output_file
-        )
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = aggregation_json(result);
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            let mut output = format!("Aggregated {} signatures\n", result.signature_count);
+            for key in &result.signer_keys {
+                output.push_str(&format!("  {}  {}\n", key.short(), key));
+            }
+            if result.duplicates_skipped > 0 {
+                output.push_str(&format!(
+                    "Skipped {} duplicate signature(s)\n",
+                    result.duplicates_skipped
+                ));
+            }
+            output.push_str(&format!(
+                "Threshold met: {}\nSaved to: {}\n",
+                if result.threshold_met { "Yes" } else { "No" },
+                result.output_file
+            ));
+            output
+        }
     }
 }
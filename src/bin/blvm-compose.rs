@@ -3,7 +3,8 @@
 //! Command-line interface for composing Bitcoin nodes from modules.
 
 use blvm_sdk::composition::*;
-use clap::{Parser, Subcommand};
+use blvm_sdk::util::CancelToken;
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -25,6 +26,23 @@ enum Commands {
         /// Configuration file path
         #[arg(short, long)]
         config: PathBuf,
+
+        /// Start modules whose data is in a newer layout than they use
+        #[arg(long)]
+        allow_data_downgrade: bool,
+
+        #[command(flatten)]
+        features: FeatureArgs,
+    },
+
+    /// Show the modules a configuration resolves to, without starting them
+    Plan {
+        /// Configuration file path
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[command(flatten)]
+        features: FeatureArgs,
     },
 
     /// Validate a composition configuration
@@ -32,6 +50,27 @@ enum Commands {
         /// Configuration file path
         #[arg(short, long)]
         config: PathBuf,
+
+        #[command(flatten)]
+        features: FeatureArgs,
+    },
+
+    /// Export the composition as systemd units
+    ExportSystemd {
+        /// Configuration file path
+        #[arg(short, long)]
+        config: PathBuf,
+
+        /// Output directory for the unit files
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Verify the generated units with systemd-analyze (when available)
+        #[arg(long)]
+        check: bool,
+
+        #[command(flatten)]
+        features: FeatureArgs,
     },
 
     /// Generate a configuration template
@@ -46,6 +85,27 @@ enum Commands {
     Modules(ModuleCommands),
 }
 
+/// Composition feature selection
+#[derive(Args)]
+struct FeatureArgs {
+    /// Activate a feature (repeatable)
+    #[arg(long = "feature", value_name = "FEATURE")]
+    features: Vec<String>,
+
+    /// Do not activate the features marked as default
+    #[arg(long)]
+    no_default_features: bool,
+}
+
+impl FeatureArgs {
+    fn selection(&self) -> FeatureSelection {
+        FeatureSelection {
+            default_features: !self.no_default_features,
+            features: self.features.clone(),
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum ModuleCommands {
     /// List available modules
@@ -84,24 +144,109 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     let mut composer = NodeComposer::new(&cli.modules_dir);
 
     match cli.command {
-        Some(Commands::Compose { config }) => {
+        Some(Commands::Compose {
+            config,
+            allow_data_downgrade,
+            features,
+        }) => {
             println!("Composing node from configuration: {:?}", config);
-            let composed = composer.compose_from_config(&config).await?;
+            composer
+                .lifecycle_mut()
+                .data_dirs_mut()
+                .set_allow_downgrade(allow_data_downgrade);
+            // Ctrl-C unwinds the composition instead of killing the process
+            let cancel = CancelToken::on_interrupt().unwrap_or_default();
+
+            let composed = match composer
+                .compose_from_config_with_features(&config, &features.selection(), &cancel)
+                .await
+            {
+                Err(CompositionError::Cancelled(reason)) => {
+                    eprintln!(
+                        "Composition cancelled ({}); started modules were stopped",
+                        reason
+                    );
+                    std::process::exit(130)
+                }
+                result => result?,
+            };
             println!("Successfully composed node: {}", composed.spec.name);
+            println!("Composition hash: {}", composed.composition_hash);
             println!("Modules: {}", composed.modules.len());
             for module in &composed.modules {
                 println!(
                     "  - {} ({}): {:?}",
                     module.info.name, module.info.version, module.status
                 );
+                if let Some(data_dir) = &module.data_dir {
+                    println!(
+                        "    data: {} (layout version {})",
+                        data_dir.path.display(),
+                        data_dir.layout_version
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        Some(Commands::Plan { config, features }) => {
+            let node_config = NodeConfig::from_file(&config)?;
+            let resolved = node_config.resolve(&features.selection())?;
+            composer.registry_mut().discover_modules()?;
+
+            let active: Vec<&str> = resolved.features.iter().map(String::as_str).collect();
+            if active.is_empty() {
+                println!("Features: (none)");
+            } else {
+                println!("Features: {}", active.join(", "));
+            }
+            println!("Modules:");
+            for module in &resolved.spec.modules {
+                println!(
+                    "  - {} ({})",
+                    module.name,
+                    module.version.as_deref().unwrap_or("latest")
+                );
             }
+            if !resolved.excluded.is_empty() {
+                println!("Excluded:");
+                for (module, feature) in &resolved.excluded {
+                    println!("  - {} (needs feature {})", module, feature);
+                }
+            }
+            println!("Composition hash: {}", resolved.composition_hash()?);
+
+            resolved.check_dependencies(composer.registry())?;
             Ok(())
         }
 
-        Some(Commands::Validate { config }) => {
+        Some(Commands::Validate { config, features }) => {
             println!("Validating configuration: {:?}", config);
             let node_config = NodeConfig::from_file(&config)?;
-            let validation = composer.validate_composition(&node_config.to_spec()?)?;
+            let resolved = node_config.resolve(&features.selection())?;
+            resolved.check_dependencies(composer.registry())?;
+            let validation = composer.validate_composition(&resolved.spec)?;
+
+            // Modules are sorted by name
+            let external: Vec<_> = resolved
+                .spec
+                .modules
+                .iter()
+                .filter(|module| !module.external_dependencies.is_empty())
+                .collect();
+            if !external.is_empty() {
+                println!("External dependencies:");
+                for module in external {
+                    for dependency in &module.external_dependencies {
+                        println!(
+                            "  - {}: {} (deadline {})",
+                            module.name,
+                            dependency.describe(),
+                            dependency.deadline
+                        );
+                    }
+                }
+            }
 
             if validation.valid {
                 println!("✓ Configuration is valid");
@@ -121,6 +266,44 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        Some(Commands::ExportSystemd {
+            config,
+            out,
+            check,
+            features,
+        }) => {
+            let node_config = NodeConfig::from_file(&config)?;
+            let resolved = node_config.resolve(&features.selection())?;
+            composer.registry_mut().discover_modules()?;
+            resolved.check_dependencies(composer.registry())?;
+            let spec = resolved.spec;
+
+            let validation = composer.validate_composition(&spec)?;
+            if !validation.valid {
+                println!("✗ Configuration is invalid:");
+                for error in &validation.errors {
+                    println!("  - {}", error);
+                }
+                std::process::exit(1)
+            }
+
+            let units = export_systemd(&spec, composer.registry())?;
+            let paths = systemd::write_units(&units, &out)?;
+            println!("Wrote {} unit(s) to {:?}:", paths.len(), out);
+            for unit in &units {
+                println!("  - {}", unit.name);
+            }
+
+            if check {
+                if systemd::verify_units(&paths)? {
+                    println!("✓ Units verified with systemd-analyze");
+                } else {
+                    println!("systemd-analyze not found; skipping verification");
+                }
+            }
+            Ok(())
+        }
+
         Some(Commands::GenerateTemplate { output }) => {
             let template = composer.generate_config();
 
@@ -164,7 +347,10 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             };
 
             println!("Installing module from: {:?}", module_source);
-            let module = composer.registry_mut().install_module(module_source)?;
+            let cancel = CancelToken::on_interrupt().unwrap_or_default();
+            let module = composer
+                .registry_mut()
+                .install_module_with_cancel(module_source, &cancel)?;
             println!(
                 "Successfully installed: {} ({})",
                 module.name, module.version
@@ -174,7 +360,10 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
 
         Some(Commands::Modules(ModuleCommands::Update { name, version })) => {
             println!("Updating module {} to version {}", name, version);
-            let module = composer.registry_mut().update_module(&name, &version)?;
+            let cancel = CancelToken::on_interrupt().unwrap_or_default();
+            let module = composer
+                .registry_mut()
+                .update_module_with_cancel(&name, &version, &cancel)?;
             println!("Successfully updated: {} ({})", module.name, module.version);
             Ok(())
         }
@@ -0,0 +1,126 @@
+//! # Bitcoin Commons BLLVM Keypair Generator
+//!
+//! Generate a governance keypair backed up by a BIP39 mnemonic phrase.
+//!
+//! The mnemonic is the backup: it is printed, or written to `--output`, and
+//! the secret key itself is only written to disk with `--export-key`.
+
+use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
+use blvm_sdk::governance::bip39::EntropyStrength;
+use blvm_sdk::governance::GovernanceKeypair;
+use clap::Parser;
+use std::fs;
+use std::io::{self, Write};
+
+/// Generate a governance keypair with a mnemonic backup
+#[derive(Parser, Debug)]
+#[command(name = "blvm-generate-keypair")]
+#[command(about = "Generate a governance keypair backed up by a BIP39 mnemonic phrase")]
+struct Args {
+    /// Number of mnemonic words (12, 15, 18, 21 or 24)
+    #[arg(short, long, default_value_t = 24)]
+    words: usize,
+
+    /// Write the mnemonic to this new file (owner-readable only) instead
+    /// of stdout
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Also write the secret key to this new key file (owner-readable only)
+    #[arg(long)]
+    export_key: Option<String>,
+
+    /// Output format (text, json, jsonl)
+    #[arg(short, long, default_value = "text")]
+    format: OutputFormat,
+}
+
+fn main() {
+    let args = Args::parse();
+    let formatter = OutputFormatter::new(args.format.clone());
+
+    match generate(&args, &formatter) {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            eprintln!("{}", formatter.format_error(&*e));
+            std::process::exit(1);
+        }
+    }
+}
+
+fn generate(
+    args: &Args,
+    formatter: &OutputFormatter,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let strength = match args.words {
+        12 => EntropyStrength::Bits128,
+        15 => EntropyStrength::Bits160,
+        18 => EntropyStrength::Bits192,
+        21 => EntropyStrength::Bits224,
+        24 => EntropyStrength::Bits256,
+        other => {
+            return Err(
+                format!("Mnemonic must be 12, 15, 18, 21 or 24 words, got {}", other).into(),
+            )
+        }
+    };
+
+    let keypair = GovernanceKeypair::generate_with_mnemonic(strength)?;
+    let mnemonic = keypair.to_mnemonic()?;
+
+    if let Some(path) = &args.output {
+        write_private(path, format!("{}\n", mnemonic).as_bytes())?;
+    }
+    if let Some(path) = &args.export_key {
+        let key_file = serde_json::json!({
+            "public_key": hex::encode(keypair.public_key().to_bytes()),
+            "secret_key": hex::encode(keypair.secret_key_bytes()),
+            "created_at": chrono::Utc::now().to_rfc3339(),
+        });
+        write_private(path, serde_json::to_string_pretty(&key_file)?.as_bytes())?;
+    }
+
+    // The mnemonic goes to stdout only when it was not written to a file
+    let shown_mnemonic = args.output.is_none().then_some(mnemonic.as_str());
+    Ok(match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = serde_json::json!({
+                "success": true,
+                "public_key": hex::encode(keypair.public_key().to_bytes()),
+                "words": args.words,
+                "mnemonic": shown_mnemonic,
+                "mnemonic_file": args.output,
+                "key_file": args.export_key,
+            });
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            let mut output = "Generated governance keypair\n".to_string();
+            output.push_str(&format!("Public key: {}\n", keypair.public_key()));
+            match &args.output {
+                Some(path) => output.push_str(&format!("Mnemonic saved to: {}\n", path)),
+                None => {
+                    output.push_str(&format!("Mnemonic ({} words): {}\n", args.words, mnemonic))
+                }
+            }
+            if let Some(path) = &args.export_key {
+                output.push_str(&format!("Secret key saved to: {}\n", path));
+            }
+            output
+        }
+    })
+}
+
+/// Write a new file readable by its owner only
+fn write_private(path: &str, contents: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
@@ -0,0 +1,138 @@
+//! # Bitcoin Commons BLLVM Signature Inspector
+//!
+//! Inspect signature files and aggregates.
+//!
+//! `diff` compares two signature files or aggregates structurally, so
+//! maintainers can see why two aggregates for the same release disagree.
+//! `rename` brings the signature files in a directory to the naming
+//! convention (`<artifact>.<fpr8>.sig.json`, `.minisig`, `.sshsig`).
+
+use blvm_sdk::cli::inspect::{diff_signature_files, SignatureFile, SignatureFileDiff};
+use blvm_sdk::cli::output::OutputFormat;
+use blvm_sdk::governance::sigfile::{apply_renames, plan_renames};
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::path::Path;
+
+/// Inspect signature files and aggregates
+#[derive(Parser, Debug)]
+#[command(name = "blvm-inspect")]
+#[command(about = "Inspect signature files and aggregates")]
+struct Args {
+    /// Output format (text, json, jsonl)
+    #[arg(short, long, default_value = "text")]
+    format: OutputFormat,
+
+    #[command(subcommand)]
+    command: InspectCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum InspectCommand {
+    /// Compare two signature files or aggregates
+    Diff {
+        /// First signature file
+        a: String,
+
+        /// Second signature file
+        b: String,
+    },
+    /// Rename signature files to the naming convention (dry run without
+    /// --apply)
+    Rename {
+        /// Directory holding the signature files
+        #[arg(default_value = ".")]
+        dir: String,
+
+        /// Rename the files, instead of only listing the renames
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let result = match &args.command {
+        InspectCommand::Diff { a, b } => diff_files(a, b).map(|diff| {
+            match args.format {
+                OutputFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&diff.to_json()).unwrap_or_default()
+                ),
+                OutputFormat::JsonLines => println!("{}", diff.to_json()),
+                OutputFormat::Text => println!("{}", diff),
+            }
+            diff.is_equivalent()
+        }),
+        InspectCommand::Rename { dir, apply } => rename(Path::new(dir), *apply, &args.format),
+    };
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// List the renames to the naming convention, and carry them out with
+/// `apply`
+///
+/// The full list is printed before anything is renamed; a collision
+/// aborts before any rename.
+fn rename(
+    dir: &Path,
+    apply: bool,
+    format: &OutputFormat,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let renames = plan_renames(dir)?;
+    match format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let json = serde_json::json!({
+                "applied": apply,
+                "renames": renames.iter().map(|rename| serde_json::json!({
+                    "from": rename.from.display().to_string(),
+                    "to": rename.to.display().to_string(),
+                    "format": rename.format.to_string(),
+                })).collect::<Vec<_>>(),
+            });
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&json)?),
+                _ => println!("{}", json),
+            }
+        }
+        OutputFormat::Text => {
+            for rename in &renames {
+                println!("  {}", rename);
+            }
+            if renames.is_empty() {
+                println!("All signature files follow the naming convention");
+            } else if apply {
+                println!("Renaming {} file(s)", renames.len());
+            } else {
+                println!(
+                    "Dry run: {} file(s) would be renamed; pass --apply to rename them",
+                    renames.len()
+                );
+            }
+        }
+    }
+    if apply {
+        apply_renames(&renames)?;
+    }
+    Ok(true)
+}
+
+fn diff_files(a: &str, b: &str) -> Result<SignatureFileDiff, Box<dyn std::error::Error>> {
+    Ok(diff_signature_files(&load(a)?, &load(b)?))
+}
+
+fn load(path: &str) -> Result<SignatureFile, Box<dyn std::error::Error>> {
+    if !Path::new(path).exists() {
+        return Err(format!("Signature file not found: {}", path).into());
+    }
+    Ok(SignatureFile::from_json(&fs::read_to_string(path)?)?)
+}
@@ -2,43 +2,123 @@
 //!
 //! Generate governance keypairs for Bitcoin governance operations.
 
+use blvm_sdk::cli::input::{load_secret_key, read_key_passphrase, KEY_PASSPHRASE_ENV};
 use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
-use blvm_sdk::governance::GovernanceKeypair;
-use clap::Parser;
+use blvm_sdk::governance::paper::{self, PaperArtifact, PaperBackup, PaperOptions, Restored};
+use blvm_sdk::governance::{GovernanceKeypair, Keystore};
+use blvm_sdk::util::Redacted;
+use clap::{Parser, Subcommand};
 use std::fs;
-// No need for Path import
+use std::io::{self, Write};
+use zeroize::Zeroizing;
 
 /// Generate governance keypairs
 #[derive(Parser, Debug)]
 #[command(name = "blvm-keygen")]
 #[command(about = "Generate governance keypairs for Bitcoin Commons governance operations")]
 struct Args {
+    /// Paper backups and key file migration; without a command, generate
+    /// a keypair
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Output file for the keypair
     #[arg(short, long, default_value = "governance.key")]
     output: String,
 
-    /// Output format (text, json)
+    /// Output format (text, json, jsonl)
     #[arg(short, long, default_value = "text")]
     format: OutputFormat,
 
     /// Generate deterministic keypair from seed
     #[arg(long)]
-    seed: Option<String>,
+    seed: Option<Redacted<String>>,
 
     /// Show private key in output
     #[arg(long)]
     show_private: bool,
+
+    /// Encrypt the key file under a passphrase, read from
+    /// BLLVM_KEY_PASSPHRASE or prompted for
+    #[arg(long)]
+    encrypt: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write a printable, error-corrected backup of a key or configuration
+    PaperBackup {
+        /// File to back up: a key file for key-share, otherwise any file
+        input: String,
+
+        /// What the file holds (key-share, multisig-config, keyset)
+        #[arg(long, default_value = "key-share")]
+        artifact: PaperArtifact,
+
+        /// Parity chunks: how many chunks may be lost or damaged
+        #[arg(long, default_value_t = PaperOptions::default().parity)]
+        parity: usize,
+
+        /// Bytes per chunk
+        #[arg(long, default_value_t = PaperOptions::default().chunk_size)]
+        chunk_size: usize,
+
+        /// Write the document to this new file (owner-readable only)
+        /// instead of stdout
+        #[arg(long)]
+        document: Option<String>,
+
+        /// Add a QR code for every chunk (needs the qr feature)
+        #[arg(long)]
+        qr: bool,
+    },
+    /// Restore a paper backup from its chunk lines
+    ///
+    /// A key share is written to a new key file, readable by its owner
+    /// only, and never printed.
+    PaperRestore {
+        /// Files holding the chunk lines, as typed or scanned back in;
+        /// other lines are ignored
+        #[arg(required = true)]
+        documents: Vec<String>,
+
+        /// New file for the restored key or configuration
+        #[arg(short, long)]
+        output: String,
+    },
+    /// Convert a key file of the earlier format into a keystore
+    ///
+    /// The keystore is a new file, readable by its owner only; an
+    /// encrypted key stays encrypted under the same passphrase.
+    Migrate {
+        /// Key file to convert
+        input: String,
+
+        /// New keystore file
+        #[arg(short, long)]
+        output: String,
+
+        /// Label of the key (default: the input file name up to its first dot)
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Team whose multisig slot the key fills
+        #[arg(long)]
+        team: Option<String>,
+    },
 }
 
 fn main() {
     let args = Args::parse();
     let formatter = OutputFormatter::new(args.format.clone());
 
-    match generate_keypair(&args) {
-        Ok(keypair) => {
-            let output = format_keypair_output(&keypair, &args, &formatter);
-            println!("{}", output);
-        }
+    let result = match &args.command {
+        Some(command) => run_command(command, &args, &formatter),
+        None => generate_keypair(&args)
+            .map(|keypair| format_keypair_output(&keypair, &args, &formatter)),
+    };
+    match result {
+        Ok(output) => println!("{}", output),
         Err(e) => {
             eprintln!("{}", formatter.format_error(&*e));
             std::process::exit(1);
@@ -49,7 +129,7 @@ fn main() {
 fn generate_keypair(args: &Args) -> Result<GovernanceKeypair, Box<dyn std::error::Error>> {
     let keypair = if let Some(seed) = &args.seed {
         // Generate deterministic keypair from seed
-        let seed_bytes = seed.as_bytes();
+        let seed_bytes = seed.expose().as_bytes();
         if seed_bytes.len() < 32 {
             return Err("Seed must be at least 32 bytes".into());
         }
@@ -63,7 +143,20 @@ fn generate_keypair(args: &Args) -> Result<GovernanceKeypair, Box<dyn std::error
     };
 
     // Save keypair to file
-    save_keypair(&keypair, &args.output)?;
+    if args.encrypt {
+        let passphrase = read_key_passphrase("Passphrase for the new key file: ")?;
+        if passphrase.is_empty() {
+            return Err("The passphrase must not be empty".into());
+        }
+        if std::env::var_os(KEY_PASSPHRASE_ENV).is_none()
+            && *read_key_passphrase("Repeat the passphrase: ")? != *passphrase
+        {
+            return Err("Passphrases do not match".into());
+        }
+        fs::write(&args.output, keypair.to_encrypted_json(&passphrase)?)?;
+    } else {
+        save_keypair(&keypair, &args.output)?;
+    }
 
     Ok(keypair)
 }
@@ -72,16 +165,20 @@ fn save_keypair(
     keypair: &GovernanceKeypair,
     output_path: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(output_path, key_file_json(keypair)?)?;
+
+    Ok(())
+}
+
+/// Contents of a key file
+fn key_file_json(keypair: &GovernanceKeypair) -> Result<String, Box<dyn std::error::Error>> {
     let keypair_data = serde_json::json!({
         "public_key": hex::encode(keypair.public_key().to_bytes()),
         "secret_key": hex::encode(keypair.secret_key_bytes()),
         "created_at": chrono::Utc::now().to_rfc3339(),
     });
 
-    let json_str = serde_json::to_string_pretty(&keypair_data)?;
-    fs::write(output_path, json_str)?;
-
-    Ok(())
+    Ok(serde_json::to_string_pretty(&keypair_data)?)
 }
 
 fn format_keypair_output(
@@ -89,30 +186,251 @@ fn format_keypair_output(
     args: &Args,
     formatter: &OutputFormatter,
 ) -> String {
-    if args.format == OutputFormat::Json {
-        let output_data = serde_json::json!({
-            "success": true,
-            "public_key": hex::encode(keypair.public_key().to_bytes()),
-            "secret_key": if args.show_private {
-                Some(hex::encode(keypair.secret_key_bytes()))
-            } else {
-                None
-            },
-            "output_file": args.output,
-        });
-        formatter
-            .format(&output_data)
-            .unwrap_or_else(|_| "{}".to_string())
-    } else {
-        let mut output = "Generated governance keypair\n".to_string();
-        output.push_str(&format!("Public key: {}\n", keypair.public_key()));
-        if args.show_private {
-            output.push_str(&format!(
-                "Secret key: {}\n",
-                hex::encode(keypair.secret_key_bytes())
-            ));
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = serde_json::json!({
+                "success": true,
+                "public_key": hex::encode(keypair.public_key().to_bytes()),
+                "secret_key": if args.show_private {
+                    Some(hex::encode(keypair.secret_key_bytes()))
+                } else {
+                    None
+                },
+                "output_file": args.output,
+            });
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            let mut output = "Generated governance keypair\n".to_string();
+            output.push_str(&format!("Public key: {}\n", keypair.public_key()));
+            if args.show_private {
+                output.push_str(&format!(
+                    "Secret key: {}\n",
+                    hex::encode(keypair.secret_key_bytes())
+                ));
+            }
+            output.push_str(&format!("Saved to: {}\n", args.output));
+            output
+        }
+    }
+}
+
+fn run_command(
+    command: &Command,
+    args: &Args,
+    formatter: &OutputFormatter,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match command {
+        Command::PaperBackup {
+            input,
+            artifact,
+            parity,
+            chunk_size,
+            document,
+            qr,
+        } => {
+            let data = match artifact {
+                PaperArtifact::KeyShare => load_secret_key(input)?,
+                _ => Zeroizing::new(fs::read(input)?),
+            };
+            let options = PaperOptions {
+                chunk_size: *chunk_size,
+                parity: *parity,
+            };
+            let backup = paper::backup(*artifact, &data, &options)?;
+            let mut text = backup.to_document();
+            if *qr {
+                text.push_str(&qr_codes(&backup)?);
+            }
+            match document {
+                Some(path) => {
+                    write_private(path, text.as_bytes())?;
+                    Ok(format_backup_output(&backup, path, args, formatter))
+                }
+                None => Ok(text),
+            }
+        }
+        Command::PaperRestore { documents, output } => {
+            let mut lines = Vec::new();
+            for path in documents {
+                lines.extend(paper::chunk_lines(&fs::read_to_string(path)?));
+            }
+            let restored = paper::restore(&lines)?;
+            // Key material goes straight into a key file, never to stdout
+            let public_key = match restored.artifact {
+                PaperArtifact::KeyShare => {
+                    let keypair = GovernanceKeypair::from_secret_key(&restored.data)?;
+                    write_private(output, key_file_json(&keypair)?.as_bytes())?;
+                    Some(keypair.public_key().to_string())
+                }
+                _ => {
+                    write_private(output, &restored.data)?;
+                    None
+                }
+            };
+            Ok(format_restore_output(
+                &restored,
+                public_key.as_deref(),
+                output,
+                args,
+                formatter,
+            ))
+        }
+        Command::Migrate {
+            input,
+            output,
+            label,
+            team,
+        } => {
+            let (mut keystore, migrated) = Keystore::load_or_migrate(input)?;
+            if !migrated {
+                return Err(format!("{} is already a keystore", input).into());
+            }
+            if let Some(label) = label {
+                keystore.label = label.clone();
+            }
+            if let Some(team) = team {
+                keystore = keystore.with_team(team);
+            }
+            write_private(output, Zeroizing::new(keystore.to_json()).as_bytes())?;
+            Ok(format_migrate_output(&keystore, output, args, formatter))
+        }
+    }
+}
+
+#[cfg(feature = "qr")]
+fn qr_codes(backup: &PaperBackup) -> Result<String, Box<dyn std::error::Error>> {
+    let mut text = String::new();
+    for (number, code) in backup.qr_codes()?.iter().enumerate() {
+        text.push_str(&format!("\nChunk {}:\n{}\n", number + 1, code));
+    }
+    Ok(text)
+}
+
+#[cfg(not(feature = "qr"))]
+fn qr_codes(_backup: &PaperBackup) -> Result<String, Box<dyn std::error::Error>> {
+    Err("--qr needs the qr feature".into())
+}
+
+/// Write a new file only its owner can read; existing files are not replaced
+fn write_private(path: &str, contents: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+fn format_backup_output(
+    backup: &PaperBackup,
+    path: &str,
+    args: &Args,
+    formatter: &OutputFormatter,
+) -> String {
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = serde_json::json!({
+                "success": true,
+                "artifact": backup.artifact.to_string(),
+                "data_chunks": backup.data_chunks,
+                "parity_chunks": backup.parity_chunks,
+                "document": path,
+            });
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => format!(
+            "Wrote paper backup of a {} ({} data + {} parity chunks) to {}",
+            backup.artifact, backup.data_chunks, backup.parity_chunks, path
+        ),
+    }
+}
+
+fn format_migrate_output(
+    keystore: &Keystore,
+    output: &str,
+    args: &Args,
+    formatter: &OutputFormatter,
+) -> String {
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = serde_json::json!({
+                "success": true,
+                "label": keystore.label,
+                "public_key": keystore.public_key,
+                "team_id": keystore.team_id,
+                "encrypted": keystore.is_encrypted(),
+                "output_file": output,
+            });
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            let mut text = format!("Migrated key '{}' to a keystore\n", keystore.label);
+            text.push_str(&format!("Public key: {}\n", keystore.public_key));
+            if let Some(team_id) = &keystore.team_id {
+                text.push_str(&format!("Team: {}\n", team_id));
+            }
+            text.push_str(&format!("Saved to: {}\n", output));
+            text
+        }
+    }
+}
+
+fn format_restore_output(
+    restored: &Restored,
+    public_key: Option<&str>,
+    output: &str,
+    args: &Args,
+    formatter: &OutputFormatter,
+) -> String {
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = serde_json::json!({
+                "success": true,
+                "artifact": restored.artifact.to_string(),
+                "public_key": public_key,
+                "missing_chunks": restored.missing,
+                "corrected_chunks": restored.corrected,
+                "output_file": output,
+            });
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            let mut text = format!("Restored {}\n", restored.artifact);
+            if let Some(public_key) = public_key {
+                text.push_str(&format!("Public key: {}\n", public_key));
+            }
+            let numbers = |chunks: &[usize]| {
+                chunks
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            if !restored.missing.is_empty() {
+                text.push_str(&format!(
+                    "Missing or damaged chunks: {}\n",
+                    numbers(&restored.missing)
+                ));
+            }
+            if !restored.corrected.is_empty() {
+                text.push_str(&format!(
+                    "Rebuilt from parity: {}\n",
+                    numbers(&restored.corrected)
+                ));
+            }
+            text.push_str(&format!("Saved to: {}\n", output));
+            text
         }
-        output.push_str(&format!("Saved to: {}\n", args.output));
-        output
     }
 }
@@ -5,30 +5,86 @@
 //! This tool signs binaries and verification bundles with maintainer multisig,
 //! creating cryptographic proof that binaries match verified code.
 
+use blvm_sdk::cli::input::{load_secret_key, parse_comma_separated};
 use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
-use blvm_sdk::governance::{GovernanceKeypair, Signature};
-use blvm_sdk::sign_message as crypto_sign_message;
-use clap::{Parser, Subcommand};
+use blvm_sdk::governance::attributes::parse_attribute;
+use blvm_sdk::governance::journal::{JournalRecord, SigningJournal, SUPERSEDES_ATTRIBUTE};
+use blvm_sdk::governance::keystore::is_keystore_json;
+use blvm_sdk::governance::manifest::Manifest;
+use blvm_sdk::governance::oci::OciImageRef;
+use blvm_sdk::governance::sigfile::{canonical_name, SigFormat};
+use blvm_sdk::governance::{
+    CommandSigner, GovernanceKeypair, GovernanceSigner, PublicKey, Signature, SignatureRecord,
+    SignedArtifactMetadata, SignedAttributes, SignedTimestamp, SigningTarget,
+};
+use blvm_sdk::util::fs::write_atomic;
+use blvm_sdk::util::parallel::{CancelToken, JobReport, Limits, Report};
+use clap::{Args as ClapArgs, Parser, Subcommand};
 use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Sign binaries and verification bundles
 #[derive(Parser, Debug)]
 #[command(name = "blvm-sign-binary")]
 #[command(about = "Sign binaries and verification bundles for Bitcoin Commons releases")]
 struct Args {
-    /// Output file for the signature
-    #[arg(short, long, default_value = "signature.json")]
-    output: String,
+    /// Output file for the signature (default: the conventional
+    /// <artifact>.<fpr8>.sig.json in the current directory)
+    #[arg(short, long)]
+    output: Option<String>,
 
-    /// Output format (text, json)
+    /// Output format (text, json, jsonl)
     #[arg(short, long, default_value = "text")]
     format: OutputFormat,
 
     /// Private key file
-    #[arg(short, long, required = true)]
-    key: String,
+    #[arg(short, long, required_unless_present = "signer_command")]
+    key: Option<String>,
+
+    /// Sign by running this program instead of loading a key file, e.g. an
+    /// HSM client or a remote signer; it reads the hex SHA256 of the message
+    /// on stdin and writes the compact signature as hex on stdout
+    #[arg(
+        long,
+        value_name = "PROGRAM",
+        conflicts_with = "key",
+        requires = "pubkey"
+    )]
+    signer_command: Option<String>,
+
+    /// Argument passed to the signer command (repeatable)
+    #[arg(
+        long = "signer-arg",
+        value_name = "ARG",
+        requires = "signer_command",
+        allow_hyphen_values = true
+    )]
+    signer_args: Vec<String>,
+
+    /// Public key (hex) the signer command's signatures verify against
+    #[arg(long, requires = "signer_command")]
+    pubkey: Option<String>,
+
+    /// Attribute to attest with the signature, for escalation policies
+    /// (NAME=VALUE, repeatable; e.g. paths=consensus/pow.rs)
+    #[arg(long = "attribute", value_name = "NAME=VALUE")]
+    attributes: Vec<String>,
+
+    /// Signing journal of the key (default: <key>.journal, or
+    /// <short key fingerprint>.journal with --signer-command)
+    #[arg(long)]
+    journal: Option<String>,
+
+    /// Sign an artifact already signed with this key with a different
+    /// payload, attesting that the new signature supersedes the old ones
+    #[arg(long)]
+    supersede: bool,
+
+    /// Sign a file even if it looks like a secret key file
+    #[arg(long)]
+    force: bool,
 
     /// What to sign
     #[command(subcommand)]
@@ -83,6 +139,65 @@ enum SignTarget {
         #[arg(short, long)]
         version: Option<String>,
     },
+    /// Hash a set of files into a manifest and sign the manifest once
+    Manifest {
+        /// Directory to hash (every file, recursively)
+        #[arg(
+            short,
+            long,
+            conflicts_with = "files",
+            required_unless_present = "files"
+        )]
+        dir: Option<String>,
+
+        /// Files to hash (comma-separated)
+        #[arg(long)]
+        files: Option<String>,
+
+        /// Output file for the manifest (default: SHA256SUMS in the
+        /// directory, or in the current directory for a file list)
+        #[arg(short, long)]
+        manifest: Option<String>,
+
+        /// Version string
+        #[arg(short, long)]
+        version: Option<String>,
+
+        #[command(flatten)]
+        hashing: HashingArgs,
+    },
+    /// Sign an OCI image digest
+    Image {
+        /// Image digest (sha256:<hex>)
+        #[arg(short, long, required = true)]
+        digest: String,
+
+        /// Image repository
+        #[arg(short, long, required = true)]
+        repository: String,
+
+        /// Image tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Limits for hashing many files in parallel
+#[derive(ClapArgs, Debug)]
+struct HashingArgs {
+    /// Files hashed at once (default: one per CPU)
+    #[arg(long)]
+    jobs: Option<usize>,
+
+    /// Total MiB of the files hashed at once (default: 256 per job)
+    #[arg(long, value_name = "MIB")]
+    max_in_flight: Option<u64>,
+}
+
+impl HashingArgs {
+    fn limits(&self) -> Limits {
+        Limits::new(self.jobs, self.max_in_flight.map(|mib| mib * 1024 * 1024))
+    }
 }
 
 fn main() {
@@ -90,8 +205,8 @@ fn main() {
     let formatter = OutputFormatter::new(args.format.clone());
 
     match sign_target(&args) {
-        Ok(result) => {
-            let output = format_signature_output(&result, &args, &formatter);
+        Ok((result, output_file)) => {
+            let output = format_signature_output(&result, &output_file, &args, &formatter);
             println!("{}", output);
         }
         Err(e) => {
@@ -104,27 +219,85 @@ fn main() {
 #[derive(Debug)]
 struct SignResult {
     signature: Signature,
+    timestamp: SignedTimestamp,
+    attributes: Option<SignedAttributes>,
+    message: Vec<u8>,
     file_hash: String,
     file_path: String,
-    metadata: serde_json::Value,
+    metadata: SignedArtifactMetadata,
+    hashing: Option<Report<()>>,
+}
+
+/// What a target's signature file is named after
+fn artifact_name(target: &SignTarget) -> String {
+    match target {
+        SignTarget::Binary { file, .. }
+        | SignTarget::Bundle { file, .. }
+        | SignTarget::Checksums { file, .. } => file.clone(),
+        SignTarget::Manifest { manifest, .. } => {
+            manifest.clone().unwrap_or_else(|| "SHA256SUMS".to_string())
+        }
+        SignTarget::Image {
+            digest,
+            repository,
+            tag,
+        } => format!("{}:{}", repository, tag.as_ref().unwrap_or(digest)),
+    }
 }
 
-fn sign_target(args: &Args) -> Result<SignResult, Box<dyn std::error::Error>> {
-    // Load the keypair
-    let keypair = load_keypair(&args.key)?;
+/// Sign the target, returning the result and the file it was saved to
+fn sign_target(args: &Args) -> Result<(SignResult, String), Box<dyn std::error::Error>> {
+    // Ctrl-C stops hashing and leaves no signature file behind
+    let cancel = CancelToken::on_interrupt().unwrap_or_default();
+
+    // Signing below goes through the signer only
+    let signer = load_signer(args)?;
+    let signer = signer.as_ref();
+    let output_file = args.output.clone().unwrap_or_else(|| {
+        canonical_name(
+            &artifact_name(&args.target),
+            &signer.public_key().sha256_fingerprint(),
+            SigFormat::Json,
+        )
+    });
+
+    // Earlier signatures with this key, checked before anything is written
+    let journal_path =
+        args.journal
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| match &args.key {
+                Some(key) => SigningJournal::default_path(Path::new(key)),
+                None => PathBuf::from(format!("{}.journal", signer.public_key().short())),
+            });
+    let mut journal = SigningJournal::open(&journal_path)?;
+
+    let mut attributes = BTreeMap::new();
+    for attribute in &args.attributes {
+        let (name, value) = parse_attribute(attribute)?;
+        if name == SUPERSEDES_ATTRIBUTE {
+            return Err(format!("The '{}' attribute is set by --supersede", name).into());
+        }
+        if attributes.insert(name.clone(), value).is_some() {
+            return Err(format!("Attribute '{}' given more than once", name).into());
+        }
+    }
 
-    match &args.target {
+    let target = &args.target;
+    match target {
         SignTarget::Binary {
             file,
             binary_type,
             version,
             commit,
         } => sign_binary(
-            &keypair,
+            signer,
+            target,
             file,
             binary_type,
             version.as_deref(),
             commit.as_deref(),
+            args.force,
         ),
         SignTarget::Bundle {
             file,
@@ -132,29 +305,194 @@ fn sign_target(args: &Args) -> Result<SignResult, Box<dyn std::error::Error>> {
             build_config_hash,
             spec_hash,
         } => sign_bundle(
-            &keypair,
+            signer,
+            target,
             file,
             source_hash.as_deref(),
             build_config_hash.as_deref(),
             spec_hash.as_deref(),
         ),
         SignTarget::Checksums { file, version } => {
-            sign_checksums(&keypair, file, version.as_deref())
+            sign_checksums(signer, target, file, version.as_deref(), args.force)
         }
+        SignTarget::Manifest {
+            dir,
+            files,
+            manifest,
+            version,
+            hashing,
+        } => sign_manifest(
+            signer,
+            target,
+            ManifestSource::new(dir.as_deref(), files.as_deref())?,
+            manifest.as_deref(),
+            &[PathBuf::from(&output_file), journal_path.clone()],
+            version.as_deref(),
+            &hashing.limits(),
+        ),
+        SignTarget::Image {
+            digest,
+            repository,
+            tag,
+        } => sign_image(signer, digest, repository, tag.as_deref()),
+    }
+    .and_then(|mut result| {
+        // Signing an artifact again with a different payload must
+        // supersede the earlier signatures
+        let mut record = JournalRecord {
+            artifact_hash: result.file_hash.clone(),
+            message_digest: hex::encode(Sha256::digest(&result.message)),
+            attributes: attributes.clone(),
+            metadata: result.metadata.to_value(),
+            signed_at: result.timestamp.signed_at,
+            output_path: output_file.clone(),
+            supersedes: Vec::new(),
+        };
+        let conflicts = journal.conflicts(&record);
+        if !conflicts.is_empty() {
+            if !args.supersede {
+                let earlier: BTreeSet<&str> = conflicts
+                    .iter()
+                    .map(|e| e.record.output_path.as_str())
+                    .collect();
+                return Err(format!(
+                    "{} was already signed with this key with a different payload ({}); \
+                     pass --supersede to sign it again",
+                    result.file_path,
+                    earlier.into_iter().collect::<Vec<_>>().join(", ")
+                )
+                .into());
+            }
+            let superseded: BTreeSet<String> = conflicts
+                .iter()
+                .map(|e| e.record.message_digest.clone())
+                .collect();
+            record.supersedes = superseded.into_iter().collect();
+            attributes.insert(
+                SUPERSEDES_ATTRIBUTE.to_string(),
+                record.supersedes.join(","),
+            );
+        }
+
+        // Attest the attributes with the same key
+        if !attributes.is_empty() {
+            result.attributes = Some(SignedAttributes::sign_with(
+                signer,
+                &result.message,
+                attributes,
+            )?);
+        }
+
+        // Save signature to file, then record it
+        cancel.check("signing")?;
+        save_signature(&result, &signer.public_key(), &output_file)?;
+        journal.append(record)?;
+        Ok((result, output_file))
+    })
+}
+
+/// Sign a message and attest the signing time with the same key
+fn sign_with_timestamp(
+    signer: &dyn GovernanceSigner,
+    message: &[u8],
+) -> Result<(Signature, SignedTimestamp), Box<dyn std::error::Error>> {
+    let signature = signer.sign(message)?;
+    let timestamp = SignedTimestamp::sign_with(signer, message, chrono::Utc::now())?;
+    Ok((signature, timestamp))
+}
+
+/// Build the exact message signed for a target with the given file hash
+///
+/// For images, `file_hash` is the hex part of the normalized digest.
+fn signing_message_for(target: &SignTarget, file_hash: &str) -> Vec<u8> {
+    let file_hash = file_hash.to_string();
+    let target = match target {
+        SignTarget::Binary {
+            binary_type,
+            version,
+            commit,
+            ..
+        } => SigningTarget::Binary {
+            binary_type: binary_type.clone(),
+            file_hash,
+            version: version.clone(),
+            commit: commit.clone(),
+        },
+        SignTarget::Bundle {
+            source_hash,
+            build_config_hash,
+            spec_hash,
+            ..
+        } => SigningTarget::Bundle {
+            file_hash,
+            source_hash: source_hash.clone(),
+            build_config_hash: build_config_hash.clone(),
+            spec_hash: spec_hash.clone(),
+        },
+        // A manifest is signed as its canonical checksums file
+        SignTarget::Checksums { version, .. } | SignTarget::Manifest { version, .. } => {
+            SigningTarget::Checksums {
+                file_hash,
+                version: version.clone(),
+            }
+        }
+        SignTarget::Image {
+            repository, tag, ..
+        } => SigningTarget::Image {
+            repository: repository.clone(),
+            digest: format!("sha256:{}", file_hash),
+            tag: tag.clone(),
+        },
+    };
+    target.to_signing_bytes()
+}
+
+/// Largest file inspected for a JSON or hex key
+const KEY_FILE_SCAN_LIMIT: usize = 64 * 1024;
+
+/// Whether file content looks like a secret key: a keystore, a key file
+/// with a `secret_key` field, or a raw 32-byte key (binary or hex)
+fn looks_like_secret_key(data: &[u8]) -> bool {
+    if data.len() == 32 {
+        return true;
     }
-    .and_then(|result| {
-        // Save signature to file
-        save_signature(&result, &args.output)?;
-        Ok(result)
+    if data.len() > KEY_FILE_SCAN_LIMIT {
+        return false;
+    }
+    if let Ok(serde_json::Value::Object(object)) = serde_json::from_slice(data) {
+        return object.contains_key("secret_key") || object.contains_key("format_version");
+    }
+    std::str::from_utf8(data).is_ok_and(|text| {
+        let text = text.trim();
+        text.len() == 64 && text.bytes().all(|b| b.is_ascii_hexdigit())
     })
 }
 
+/// Refuse to sign a secret key, which would publish a commitment over it
+fn check_not_secret_key(
+    file_path: &str,
+    data: &[u8],
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !force && looks_like_secret_key(data) {
+        return Err(format!(
+            "{} looks like a secret key file; signing it would publish a commitment over \
+             the key. Pass --force to sign it anyway",
+            file_path
+        )
+        .into());
+    }
+    Ok(())
+}
+
 fn sign_binary(
-    keypair: &GovernanceKeypair,
+    signer: &dyn GovernanceSigner,
+    target: &SignTarget,
     file_path: &str,
     binary_type: &str,
     version: Option<&str>,
     commit: Option<&str>,
+    force: bool,
 ) -> Result<SignResult, Box<dyn std::error::Error>> {
     if !Path::new(file_path).exists() {
         return Err(format!("Binary file not found: {}", file_path).into());
@@ -162,50 +500,44 @@ fn sign_binary(
 
     // Read binary file
     let binary_data = fs::read(file_path)?;
+    check_not_secret_key(file_path, &binary_data, force)?;
 
     // Compute SHA256 hash
     let mut hasher = Sha256::new();
     hasher.update(&binary_data);
     let file_hash = hex::encode(hasher.finalize());
 
-    // Create message to sign: binary_type:file_hash:version:commit
-    let mut message_parts = vec![
-        "binary".to_string(),
-        binary_type.to_string(),
-        file_hash.clone(),
-    ];
-    if let Some(v) = version {
-        message_parts.push(v.to_string());
-    }
-    if let Some(c) = commit {
-        message_parts.push(c.to_string());
-    }
-    let message = message_parts.join(":");
-
     // Sign the message
-    let signature = crypto_sign_message(&keypair.secret_key, message.as_bytes())?;
+    let message = signing_message_for(target, &file_hash);
+    let (signature, timestamp) = sign_with_timestamp(signer, &message)?;
 
     // Create metadata
-    let metadata = serde_json::json!({
-        "type": "binary",
-        "binary_type": binary_type,
-        "file_path": file_path,
-        "file_hash": file_hash,
-        "version": version,
-        "commit": commit,
-        "signed_at": chrono::Utc::now().to_rfc3339(),
-    });
+    let metadata = SignedArtifactMetadata {
+        artifact_type: "binary".to_string(),
+        binary_type: Some(binary_type.to_string()),
+        file_path: Some(file_path.to_string()),
+        file_hash: file_hash.clone(),
+        version: version.map(str::to_string),
+        commit: commit.map(str::to_string),
+        signed_at: timestamp.signed_at,
+        extra: BTreeMap::new(),
+    };
 
     Ok(SignResult {
         signature,
+        timestamp,
+        attributes: None,
+        message,
         file_hash,
         file_path: file_path.to_string(),
         metadata,
+        hashing: None,
     })
 }
 
 fn sign_bundle(
-    keypair: &GovernanceKeypair,
+    signer: &dyn GovernanceSigner,
+    target: &SignTarget,
     file_path: &str,
     source_hash: Option<&str>,
     build_config_hash: Option<&str>,
@@ -223,45 +555,47 @@ fn sign_bundle(
     hasher.update(&bundle_data);
     let file_hash = hex::encode(hasher.finalize());
 
-    // Create message to sign: bundle:file_hash:source_hash:build_config_hash:spec_hash
-    let mut message_parts = vec!["bundle".to_string(), file_hash.clone()];
-    if let Some(sh) = source_hash {
-        message_parts.push(sh.to_string());
-    }
-    if let Some(bch) = build_config_hash {
-        message_parts.push(bch.to_string());
-    }
-    if let Some(sph) = spec_hash {
-        message_parts.push(sph.to_string());
-    }
-    let message = message_parts.join(":");
-
     // Sign the message
-    let signature = crypto_sign_message(&keypair.secret_key, message.as_bytes())?;
+    let message = signing_message_for(target, &file_hash);
+    let (signature, timestamp) = sign_with_timestamp(signer, &message)?;
 
     // Create metadata
-    let metadata = serde_json::json!({
-        "type": "bundle",
-        "file_path": file_path,
-        "file_hash": file_hash,
-        "source_hash": source_hash,
-        "build_config_hash": build_config_hash,
-        "spec_hash": spec_hash,
-        "signed_at": chrono::Utc::now().to_rfc3339(),
-    });
+    let metadata = SignedArtifactMetadata {
+        artifact_type: "bundle".to_string(),
+        binary_type: None,
+        file_path: Some(file_path.to_string()),
+        file_hash: file_hash.clone(),
+        version: None,
+        commit: None,
+        signed_at: timestamp.signed_at,
+        extra: BTreeMap::from([
+            ("source_hash".to_string(), serde_json::json!(source_hash)),
+            (
+                "build_config_hash".to_string(),
+                serde_json::json!(build_config_hash),
+            ),
+            ("spec_hash".to_string(), serde_json::json!(spec_hash)),
+        ]),
+    };
 
     Ok(SignResult {
         signature,
+        timestamp,
+        attributes: None,
+        message,
         file_hash,
         file_path: file_path.to_string(),
         metadata,
+        hashing: None,
     })
 }
 
 fn sign_checksums(
-    keypair: &GovernanceKeypair,
+    signer: &dyn GovernanceSigner,
+    target: &SignTarget,
     file_path: &str,
     version: Option<&str>,
+    force: bool,
 ) -> Result<SignResult, Box<dyn std::error::Error>> {
     if !Path::new(file_path).exists() {
         return Err(format!("Checksums file not found: {}", file_path).into());
@@ -269,108 +603,444 @@ fn sign_checksums(
 
     // Read checksums file
     let checksums_data = fs::read_to_string(file_path)?;
+    check_not_secret_key(file_path, checksums_data.as_bytes(), force)?;
 
     // Compute SHA256 hash of file contents
     let mut hasher = Sha256::new();
     hasher.update(checksums_data.as_bytes());
     let file_hash = hex::encode(hasher.finalize());
 
-    // Create message to sign: checksums:file_hash:version
-    let mut message_parts = vec!["checksums".to_string(), file_hash.clone()];
-    if let Some(v) = version {
-        message_parts.push(v.to_string());
-    }
-    let message = message_parts.join(":");
-
     // Sign the message
-    let signature = crypto_sign_message(&keypair.secret_key, message.as_bytes())?;
+    let message = signing_message_for(target, &file_hash);
+    let (signature, timestamp) = sign_with_timestamp(signer, &message)?;
 
     // Create metadata
-    let metadata = serde_json::json!({
-        "type": "checksums",
-        "file_path": file_path,
-        "file_hash": file_hash,
-        "version": version,
-        "signed_at": chrono::Utc::now().to_rfc3339(),
-    });
+    let metadata = SignedArtifactMetadata {
+        artifact_type: "checksums".to_string(),
+        binary_type: None,
+        file_path: Some(file_path.to_string()),
+        file_hash: file_hash.clone(),
+        version: version.map(str::to_string),
+        commit: None,
+        signed_at: timestamp.signed_at,
+        extra: BTreeMap::new(),
+    };
 
     Ok(SignResult {
         signature,
+        timestamp,
+        attributes: None,
+        message,
         file_hash,
         file_path: file_path.to_string(),
         metadata,
+        hashing: None,
+    })
+}
+
+/// Files a manifest is built from
+enum ManifestSource<'a> {
+    /// Every file under a directory
+    Dir(&'a str),
+    /// Comma-separated file list
+    Files(&'a str),
+}
+
+impl<'a> ManifestSource<'a> {
+    fn new(
+        dir: Option<&'a str>,
+        files: Option<&'a str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        match (dir, files) {
+            (Some(dir), _) => Ok(Self::Dir(dir)),
+            (None, Some(files)) => Ok(Self::Files(files)),
+            (None, None) => Err("Either --dir or --files is required".into()),
+        }
+    }
+}
+
+fn sign_manifest(
+    signer: &dyn GovernanceSigner,
+    target: &SignTarget,
+    source: ManifestSource,
+    manifest_path: Option<&str>,
+    exclude: &[PathBuf],
+    version: Option<&str>,
+    limits: &Limits,
+) -> Result<SignResult, Box<dyn std::error::Error>> {
+    // Build the manifest from the directory or the file list, stopping on Ctrl-C
+    let cancel = CancelToken::on_interrupt().unwrap_or_default();
+    let ((manifest, report), manifest_path) = match source {
+        ManifestSource::Dir(dir) => {
+            if !Path::new(dir).is_dir() {
+                return Err(format!("Directory not found: {}", dir).into());
+            }
+            let manifest_path = manifest_path
+                .map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(dir).join("SHA256SUMS"));
+            // The manifest, the signature and the journal are not hashed
+            let mut exclude = exclude.to_vec();
+            exclude.push(manifest_path.clone());
+            (
+                Manifest::from_dir_with(Path::new(dir), &exclude, limits, &cancel)?,
+                manifest_path,
+            )
+        }
+        ManifestSource::Files(files) => {
+            let files = parse_comma_separated(files);
+            if let Some(missing) = files.iter().find(|file| !Path::new(file).is_file()) {
+                return Err(format!("File not found: {}", missing).into());
+            }
+            let manifest_path = PathBuf::from(manifest_path.unwrap_or("SHA256SUMS"));
+            (
+                Manifest::from_files_with(Path::new("."), &files, limits, &cancel)?,
+                manifest_path,
+            )
+        }
+    };
+    if manifest.is_empty() {
+        return Err("No files to include in the manifest".into());
+    }
+
+    // Write the manifest and sign its digest
+    write_atomic(&manifest_path, manifest.to_sums().as_bytes())?;
+    let file_hash = manifest.digest();
+    let message = signing_message_for(target, &file_hash);
+    let (signature, timestamp) = sign_with_timestamp(signer, &message)?;
+
+    // Create metadata
+    let metadata = SignedArtifactMetadata {
+        artifact_type: "manifest".to_string(),
+        binary_type: None,
+        file_path: Some(manifest_path.display().to_string()),
+        file_hash: file_hash.clone(),
+        version: version.map(str::to_string),
+        commit: None,
+        signed_at: timestamp.signed_at,
+        extra: BTreeMap::from([("file_count".to_string(), serde_json::json!(manifest.len()))]),
+    };
+
+    Ok(SignResult {
+        signature,
+        timestamp,
+        attributes: None,
+        message,
+        file_hash,
+        file_path: manifest_path.display().to_string(),
+        metadata,
+        hashing: Some(report),
+    })
+}
+
+fn sign_image(
+    signer: &dyn GovernanceSigner,
+    digest: &str,
+    repository: &str,
+    tag: Option<&str>,
+) -> Result<SignResult, Box<dyn std::error::Error>> {
+    // Validate and normalize the digest
+    let image = OciImageRef::new(repository, digest, tag)?;
+
+    // Sign the message
+    let message = image.to_signing_bytes();
+    let (signature, timestamp) = sign_with_timestamp(signer, &message)?;
+
+    // Create metadata
+    let metadata = SignedArtifactMetadata {
+        artifact_type: "image".to_string(),
+        binary_type: None,
+        file_path: None,
+        file_hash: image.digest_hex().to_string(),
+        version: None,
+        commit: None,
+        signed_at: timestamp.signed_at,
+        extra: BTreeMap::from([
+            (
+                "repository".to_string(),
+                serde_json::json!(image.repository),
+            ),
+            ("digest".to_string(), serde_json::json!(image.digest)),
+            ("tag".to_string(), serde_json::json!(image.tag)),
+        ]),
+    };
+
+    Ok(SignResult {
+        signature,
+        timestamp,
+        attributes: None,
+        message,
+        file_hash: image.digest_hex().to_string(),
+        file_path: image.to_string(),
+        metadata,
+        hashing: None,
     })
 }
 
+/// The key file's keypair, or the external program of `--signer-command`
+fn load_signer(args: &Args) -> Result<Box<dyn GovernanceSigner>, Box<dyn std::error::Error>> {
+    match (&args.key, &args.signer_command, &args.pubkey) {
+        (Some(key), _, _) => Ok(Box::new(load_keypair(key)?)),
+        (None, Some(program), Some(pubkey)) => {
+            let public_key: PublicKey = pubkey
+                .parse()
+                .map_err(|e| format!("Invalid --pubkey: {}", e))?;
+            Ok(Box::new(
+                CommandSigner::new(program, public_key).with_args(&args.signer_args),
+            ))
+        }
+        _ => Err("Either --key or --signer-command with --pubkey is required".into()),
+    }
+}
+
 fn load_keypair(key_path: &str) -> Result<GovernanceKeypair, Box<dyn std::error::Error>> {
     if !Path::new(key_path).exists() {
         return Err(format!("Key file not found: {}", key_path).into());
     }
 
-    let key_data = fs::read_to_string(key_path)?;
-    let key_json: serde_json::Value = serde_json::from_str(&key_data)?;
-
-    let secret_key_hex = key_json["secret_key"]
-        .as_str()
-        .ok_or("Invalid key file format")?;
-
-    let secret_key_bytes = hex::decode(secret_key_hex)?;
+    if !is_keystore_json(&fs::read_to_string(key_path)?) {
+        eprintln!(
+            "Warning: {} uses the deprecated key file format; convert it with `blvm-keygen migrate {} -o <KEYSTORE>`",
+            key_path, key_path
+        );
+    }
+    let secret_key_bytes = load_secret_key(key_path)?;
     GovernanceKeypair::from_secret_key(&secret_key_bytes)
         .map_err(|e| format!("Invalid secret key: {}", e).into())
 }
 
 fn save_signature(
     result: &SignResult,
+    public_key: &PublicKey,
     output_path: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let signature_data = serde_json::json!({
-        "signature": hex::encode(result.signature.to_bytes()),
-        "signer": hex::encode(result.metadata.get("signer").and_then(|s| s.as_str()).unwrap_or("unknown")),
-        "file_path": result.file_path,
-        "file_hash": result.file_hash,
-        "metadata": result.metadata,
-        "created_at": chrono::Utc::now().to_rfc3339(),
-    });
-
-    let json_str = serde_json::to_string_pretty(&signature_data)?;
-    fs::write(output_path, json_str)?;
+    let record = SignatureRecord {
+        signature: result.signature.clone(),
+        public_key: Some(public_key.clone()),
+        signer: None,
+        file_path: Some(result.file_path.clone()),
+        file_hash: Some(result.file_hash.clone()),
+        metadata: Some(result.metadata.clone()),
+        created_at: result.timestamp.signed_at,
+        timestamp_signature: Some(result.timestamp.signature.clone()),
+        attributes: result.attributes.as_ref().map(|a| a.attributes.clone()),
+        attributes_signature: result.attributes.as_ref().map(|a| a.signature.clone()),
+    };
+    write_atomic(Path::new(output_path), record.to_json_pretty().as_bytes())?;
 
     Ok(())
 }
 
 fn format_signature_output(
     result: &SignResult,
+    output_file: &str,
     args: &Args,
     formatter: &OutputFormatter,
 ) -> String {
-    if args.format == OutputFormat::Json {
-        let output_data = serde_json::json!({
-            "success": true,
-            "signature": hex::encode(result.signature.to_bytes()),
-            "file_path": result.file_path,
-            "file_hash": result.file_hash,
-            "output_file": args.output,
-            "metadata": result.metadata,
-        });
-        formatter
-            .format(&output_data)
-            .unwrap_or_else(|_| "{}".to_string())
-    } else {
-        format!(
-            "Signed {} successfully\n\
-             File: {}\n\
-             Hash: {}\n\
-             Signature: {}\n\
-             Saved to: {}\n",
-            result
-                .metadata
-                .get("type")
-                .and_then(|t| t.as_str())
-                .unwrap_or("file"),
-            result.file_path,
-            result.file_hash,
-            result.signature,
-            args.output
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = serde_json::json!({
+                "success": true,
+                "signature": result.signature,
+                "file_path": result.file_path,
+                "file_hash": result.file_hash,
+                "output_file": output_file,
+                "metadata": result.metadata,
+                "attributes": result.attributes.as_ref().map(|a| &a.attributes),
+                "hashing": result.hashing.as_ref().map(|report| {
+                    report.jobs.iter().map(JobReport::to_json).collect::<Vec<_>>()
+                }),
+            });
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            let mut output = format!(
+                "Signed {} successfully\n\
+                 File: {}\n\
+                 Hash: {}\n\
+                 Signature: {}\n\
+                 Saved to: {}\n",
+                result.metadata.artifact_type,
+                result.file_path,
+                result.file_hash,
+                result.signature,
+                output_file
+            );
+            if let Some(report) = &result.hashing {
+                output.push_str(&format!("Hashing:\n{}\n", report));
+            }
+            output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945";
+
+    fn message(target: SignTarget) -> String {
+        String::from_utf8(signing_message_for(&target, HASH)).unwrap()
+    }
+
+    #[test]
+    fn test_binary_signing_message() {
+        let target = |version: Option<&str>, commit: Option<&str>| SignTarget::Binary {
+            file: "blvm-node".to_string(),
+            binary_type: "consensus".to_string(),
+            version: version.map(str::to_string),
+            commit: commit.map(str::to_string),
+        };
+
+        assert_eq!(
+            message(target(None, None)),
+            format!("binary:consensus:{}", HASH)
+        );
+        assert_eq!(
+            message(target(Some("0.1.0"), Some("abc123"))),
+            format!("binary:consensus:{}:0.1.0:abc123", HASH)
+        );
+    }
+
+    #[test]
+    fn test_bundle_signing_message() {
+        let target = SignTarget::Bundle {
+            file: "bundle.tar.gz".to_string(),
+            source_hash: Some("src".to_string()),
+            build_config_hash: None,
+            spec_hash: Some("spec".to_string()),
+        };
+        assert_eq!(message(target), format!("bundle:{}:src:spec", HASH));
+    }
+
+    #[test]
+    fn test_checksums_signing_message() {
+        let target = SignTarget::Checksums {
+            file: "SHA256SUMS".to_string(),
+            version: Some("0.1.0".to_string()),
+        };
+        assert_eq!(message(target), format!("checksums:{}:0.1.0", HASH));
+    }
+
+    #[test]
+    fn test_manifest_signing_message_matches_library() {
+        let target = SignTarget::Manifest {
+            dir: Some("dist".to_string()),
+            files: None,
+            manifest: None,
+            version: Some("0.1.0".to_string()),
+            hashing: HashingArgs {
+                jobs: None,
+                max_in_flight: None,
+            },
+        };
+        let mut manifest = Manifest::new();
+        manifest.insert("blvm-node", HASH);
+        assert_eq!(
+            signing_message_for(&target, &manifest.digest()),
+            manifest.signing_bytes(Some("0.1.0"))
+        );
+    }
+
+    #[test]
+    fn test_artifact_names() {
+        let binary = SignTarget::Checksums {
+            file: "dist/SHA256SUMS".to_string(),
+            version: None,
+        };
+        assert_eq!(artifact_name(&binary), "dist/SHA256SUMS");
+        let image = SignTarget::Image {
+            digest: format!("sha256:{}", HASH),
+            repository: "ghcr.io/btcdecoded/lightning".to_string(),
+            tag: Some("v1.0.0".to_string()),
+        };
+        assert_eq!(
+            canonical_name(&artifact_name(&image), &[0xab; 32], SigFormat::Json),
+            "lightning_v1.0.0.abababab.sig.json"
+        );
+    }
+
+    #[test]
+    fn test_image_signing_message_matches_library() {
+        let target = SignTarget::Image {
+            digest: format!("sha256:{}", HASH),
+            repository: "ghcr.io/btcdecoded/lightning".to_string(),
+            tag: Some("v1.0.0".to_string()),
+        };
+        let image = OciImageRef::new(
+            "ghcr.io/btcdecoded/lightning",
+            &format!("sha256:{}", HASH),
+            Some("v1.0.0"),
         )
+        .unwrap();
+        assert_eq!(
+            signing_message_for(&target, image.digest_hex()),
+            image.to_signing_bytes()
+        );
+    }
+
+    /// Signs through a keypair it never exposes, counting signatures
+    struct MockSigner {
+        keypair: GovernanceKeypair,
+        signed: std::cell::Cell<usize>,
+    }
+
+    impl GovernanceSigner for MockSigner {
+        fn public_key(&self) -> PublicKey {
+            self.keypair.public_key()
+        }
+
+        fn sign(&self, msg: &[u8]) -> blvm_sdk::governance::GovernanceResult<Signature> {
+            self.signed.set(self.signed.get() + 1);
+            self.keypair.sign(msg)
+        }
+    }
+
+    #[test]
+    fn test_sign_with_mock_signer() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("blvm-node");
+        fs::write(&file, b"binary").unwrap();
+        let file = file.to_str().unwrap();
+        let signer = MockSigner {
+            keypair: GovernanceKeypair::generate().unwrap(),
+            signed: std::cell::Cell::new(0),
+        };
+        let public_key = signer.public_key();
+
+        let binary = SignTarget::Binary {
+            file: file.to_string(),
+            binary_type: "consensus".to_string(),
+            version: None,
+            commit: None,
+        };
+        let bundle = SignTarget::Bundle {
+            file: file.to_string(),
+            source_hash: None,
+            build_config_hash: None,
+            spec_hash: None,
+        };
+        let checksums = SignTarget::Checksums {
+            file: file.to_string(),
+            version: None,
+        };
+        let results = [
+            sign_binary(&signer, &binary, file, "consensus", None, None, false).unwrap(),
+            sign_bundle(&signer, &bundle, file, None, None, None).unwrap(),
+            sign_checksums(&signer, &checksums, file, None, false).unwrap(),
+        ];
+        for result in &results {
+            assert!(blvm_sdk::governance::verify_signature(
+                &result.signature,
+                &result.message,
+                &public_key
+            )
+            .unwrap());
+            assert!(result.timestamp.verify(&result.message, &public_key));
+        }
+        // A signature and a timestamp for each target
+        assert_eq!(signer.signed.get(), 6);
     }
 }
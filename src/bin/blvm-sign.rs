@@ -2,11 +2,16 @@
 //!
 //! Sign governance messages for Bitcoin Commons governance operations.
 
+use blvm_sdk::cli::input::{load_secret_key, parse_sha256_hex};
 use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
-use blvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Signature};
+use blvm_sdk::governance::sigfile::{canonical_name, message_artifact, SigFormat};
+use blvm_sdk::governance::{
+    GovernanceKeypair, GovernanceMessage, PublicKey, Signature, SignatureRecord,
+};
 use blvm_sdk::sign_message as crypto_sign_message;
+use blvm_sdk::util::fs::write_atomic;
+use blvm_sdk::util::CancelToken;
 use clap::{Parser, Subcommand};
-use std::fs;
 use std::path::Path;
 
 /// Sign governance messages
@@ -14,11 +19,12 @@ use std::path::Path;
 #[command(name = "blvm-sign")]
 #[command(about = "Sign governance messages for Bitcoin Commons governance operations")]
 struct Args {
-    /// Output file for the signature
-    #[arg(short, long, default_value = "signature.txt")]
-    output: String,
+    /// Output file for the signature (default: the conventional name,
+    /// e.g. release-v1.0.0.<fpr8>.sig.json)
+    #[arg(short, long)]
+    output: Option<String>,
 
-    /// Output format (text, json)
+    /// Output format (text, json, jsonl)
     #[arg(short, long, default_value = "text")]
     format: OutputFormat,
 
@@ -52,6 +58,10 @@ enum MessageCommand {
         /// Module version
         #[arg(short, long, required = true)]
         version: String,
+
+        /// SHA256 of the module binary, binding the approval to that binary
+        #[arg(long)]
+        binary_hash: Option<String>,
     },
     /// Sign a budget decision message
     Budget {
@@ -70,8 +80,9 @@ fn main() {
     let formatter = OutputFormatter::new(args.format.clone());
 
     match sign_message(&args) {
-        Ok(signature) => {
-            let output = format_signature_output(&signature, &args, &formatter);
+        Ok((signature, public_key, output_file)) => {
+            let output =
+                format_signature_output(&signature, &public_key, &output_file, &args, &formatter);
             println!("{}", output);
         }
         Err(e) => {
@@ -81,7 +92,12 @@ fn main() {
     }
 }
 
-fn sign_message(args: &Args) -> Result<Signature, Box<dyn std::error::Error>> {
+/// Sign the message, returning the signature, the signer's public key and
+/// the file it was saved to
+fn sign_message(args: &Args) -> Result<(Signature, PublicKey, String), Box<dyn std::error::Error>> {
+    // Ctrl-C before the signature is written leaves no output file
+    let cancel = CancelToken::on_interrupt().unwrap_or_default();
+
     // Load the keypair
     let keypair = load_keypair(&args.key)?;
 
@@ -91,9 +107,22 @@ fn sign_message(args: &Args) -> Result<Signature, Box<dyn std::error::Error>> {
             version: version.clone(),
             commit_hash: commit.clone(),
         },
-        MessageCommand::Module { name, version } => GovernanceMessage::ModuleApproval {
+        MessageCommand::Module {
+            name,
+            version,
+            binary_hash: None,
+        } => GovernanceMessage::ModuleApproval {
+            module_name: name.clone(),
+            version: version.clone(),
+        },
+        MessageCommand::Module {
+            name,
+            version,
+            binary_hash: Some(binary_hash),
+        } => GovernanceMessage::ModuleBinaryApproval {
             module_name: name.clone(),
             version: version.clone(),
+            binary_hash: parse_sha256_hex(binary_hash)?,
         },
         MessageCommand::Budget { amount, purpose } => GovernanceMessage::BudgetDecision {
             amount: *amount,
@@ -102,12 +131,21 @@ fn sign_message(args: &Args) -> Result<Signature, Box<dyn std::error::Error>> {
     };
 
     // Sign the message
-    let signature = crypto_sign_message(&keypair.secret_key, &message.to_signing_bytes())?;
+    let signature = crypto_sign_message(keypair.secret_key.expose(), &message.to_signing_bytes())?;
 
     // Save signature to file
-    save_signature(&signature, &args.output)?;
+    cancel.check("signing")?;
+    let public_key = keypair.public_key();
+    let output_file = args.output.clone().unwrap_or_else(|| {
+        canonical_name(
+            &message_artifact(&message),
+            &public_key.sha256_fingerprint(),
+            SigFormat::Json,
+        )
+    });
+    save_signature(&signature, &public_key, &output_file)?;
 
-    Ok(signature)
+    Ok((signature, public_key, output_file))
 }
 
 fn load_keypair(key_path: &str) -> Result<GovernanceKeypair, Box<dyn std::error::Error>> {
@@ -115,51 +153,62 @@ fn load_keypair(key_path: &str) -> Result<GovernanceKeypair, Box<dyn std::error:
         return Err(format!("Key file not found: {}", key_path).into());
     }
 
-    let key_data = fs::read_to_string(key_path)?;
-    let key_json: serde_json::Value = serde_json::from_str(&key_data)?;
-
-    let secret_key_hex = key_json["secret_key"]
-        .as_str()
-        .ok_or("Invalid key file format")?;
-
-    let secret_key_bytes = hex::decode(secret_key_hex)?;
+    let secret_key_bytes = load_secret_key(key_path)?;
     GovernanceKeypair::from_secret_key(&secret_key_bytes)
         .map_err(|e| format!("Invalid secret key: {}", e).into())
 }
 
 fn save_signature(
     signature: &Signature,
+    public_key: &PublicKey,
     output_path: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let signature_data = serde_json::json!({
-        "signature": hex::encode(signature.to_bytes()),
-        "created_at": chrono::Utc::now().to_rfc3339(),
-    });
-
-    let json_str = serde_json::to_string_pretty(&signature_data)?;
-    fs::write(output_path, json_str)?;
+    let record = SignatureRecord {
+        signature: signature.clone(),
+        public_key: Some(public_key.clone()),
+        signer: None,
+        file_path: None,
+        file_hash: None,
+        metadata: None,
+        created_at: chrono::Utc::now(),
+        timestamp_signature: None,
+        attributes: None,
+        attributes_signature: None,
+    };
+    write_atomic(Path::new(output_path), record.to_json_pretty().as_bytes())?;
 
     Ok(())
 }
 
 fn format_signature_output(
     signature: &Signature,
+    public_key: &PublicKey,
+    output_file: &str,
     args: &Args,
     formatter: &OutputFormatter,
 ) -> String {
-    if args.format == OutputFormat::Json {
-        let output_data = serde_json::json!({
-            "success": true,
-            "signature": hex::encode(signature.to_bytes()),
-            "output_file": args.output,
-        });
-        formatter
-            .format(&output_data)
-            .unwrap_or_else(|_| "{}".to_string())
-    } else {
-        format!(
-            "Signed message successfully\nSignature: {}\nSaved to: {}\n",
-            signature, args.output
-        )
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = serde_json::json!({
+                "success": true,
+                "signature": signature,
+                "output_file": output_file,
+            });
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            format!(
+                "Signed message successfully\n\
+                 Public key: {} ({})\n\
+                 Signature: {}\n\
+                 Saved to: {}\n",
+                public_key,
+                public_key.short(),
+                signature,
+                output_file
+            )
+        }
     }
 }
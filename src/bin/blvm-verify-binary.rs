@@ -5,20 +5,33 @@
 //! This tool verifies that binaries and verification bundles are signed by
 //! authorized maintainers and match their cryptographic hashes.
 
-use blvm_sdk::cli::input::{parse_comma_separated, parse_threshold};
+use bitcoin::Network;
+use blvm_sdk::cli::input::{load_public_keys, parse_comma_separated, parse_network};
+use blvm_sdk::cli::notify::notify_report;
 use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
-use blvm_sdk::governance::{Multisig, PublicKey, Signature};
-use clap::{Parser, Subcommand};
-use sha2::{Digest, Sha256};
+use blvm_sdk::governance::aggregation::{SignatureTally, IN_MEMORY_LIMIT};
+use blvm_sdk::governance::archive::{EmbeddedSignatures, ReleaseArchive};
+use blvm_sdk::governance::history::{AsOf, GovernanceHistory, HistoricalVerification};
+use blvm_sdk::governance::manifest::Manifest;
+use blvm_sdk::governance::nested_multisig::NestedMultisigResult;
+use blvm_sdk::governance::oci::OciImageRef;
+use blvm_sdk::governance::pin::{check_pin, PinOutcome};
+use blvm_sdk::governance::pipeline::{
+    Pipeline, PipelineBuilder, PolicyConfig, StageResult, VerificationContext, VerificationReport,
+};
+use blvm_sdk::governance::{GovernanceConfig, Multisig, PublicKey, SigningTarget, Threshold};
+use blvm_sdk::util::humanize::format_age;
+use blvm_sdk::util::parallel::{hash_files, CancelToken, JobReport, Limits, Report};
+use clap::{Args as ClapArgs, Parser, Subcommand};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Verify binary and verification bundle signatures
 #[derive(Parser, Debug)]
 #[command(name = "blvm-verify-binary")]
 #[command(about = "Verify binary and verification bundle signatures for Bitcoin Commons releases")]
 struct Args {
-    /// Output format (text, json)
+    /// Output format (text, json, jsonl)
     #[arg(short, long, default_value = "text")]
     format: OutputFormat,
 
@@ -26,17 +39,72 @@ struct Args {
     #[command(subcommand)]
     target: VerifyTarget,
 
-    /// Signature files (comma-separated)
-    #[arg(short, long, required = true)]
-    signatures: String,
+    /// Signature files (comma-separated; not used for archives, which carry
+    /// their own signature file)
+    #[arg(short, long)]
+    signatures: Option<String>,
 
     /// Threshold (e.g., "6-of-7")
     #[arg(short, long)]
-    threshold: Option<String>,
+    threshold: Option<Threshold>,
 
     /// Public key files (comma-separated)
     #[arg(short, long)]
     pubkeys: Option<String>,
+
+    /// Governance config file with the committee, threshold and optional
+    /// team structure (TOML, or JSON with a .json extension), instead of
+    /// --pubkeys and --threshold
+    #[arg(long, conflicts_with_all = ["pubkeys", "threshold", "history"])]
+    governance_config: Option<String>,
+
+    /// Governance history file (rotation records) for historical verification
+    #[arg(long, requires = "as_of")]
+    history: Option<String>,
+
+    /// Verify against the config active at this trusted time (RFC3339; the
+    /// signing time recorded in signature files is unsigned and not used)
+    #[arg(long, requires = "history")]
+    as_of: Option<String>,
+
+    /// Verification policy file (TOML) selecting and configuring the checks
+    #[arg(long)]
+    policy: Option<String>,
+
+    /// Trust on first use: pin the public keys and threshold for this
+    /// artifact source (repository or URL) and require them afterwards
+    #[arg(long, value_name = "SOURCE", conflicts_with = "history")]
+    tofu: Option<String>,
+
+    /// Pin file for --tofu (default: ~/.config/blvm/pins.json)
+    #[arg(long, requires = "tofu")]
+    pin_file: Option<String>,
+
+    /// Accept a change of the pinned keys or threshold, with an audit note
+    #[arg(
+        long,
+        requires = "tofu",
+        value_name = "NOTE",
+        num_args = 0..=1,
+        default_missing_value = "accepted with --accept-key-change"
+    )]
+    accept_key_change: Option<String>,
+
+    /// POST the JSON report to this URL when done (repeatable; needs the
+    /// net feature). Failed notifications are warnings and never change
+    /// the exit code
+    #[arg(long = "notify-url", value_name = "URL")]
+    notify_urls: Vec<String>,
+
+    /// File holding the shared secret for the notification HMAC header
+    #[arg(long, requires = "notify_urls")]
+    notify_secret_file: Option<String>,
+
+    /// Print the P2WSH address of the multisig verified against, on this
+    /// network (mainnet, testnet, signet, regtest), to compare with the
+    /// expected on-chain address
+    #[arg(long, value_name = "NETWORK", value_parser = parse_network)]
+    show_address: Option<Network>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -87,6 +155,72 @@ enum VerifyTarget {
         #[arg(short, long)]
         version: Option<String>,
     },
+    /// Verify files against a signed manifest
+    Manifest {
+        /// Path to the manifest (SHA256SUMS format)
+        #[arg(short, long, required = true)]
+        file: String,
+
+        /// Directory the manifest paths are relative to (default: the
+        /// manifest's directory)
+        #[arg(short, long)]
+        dir: Option<String>,
+
+        /// Version string
+        #[arg(short, long)]
+        version: Option<String>,
+
+        #[command(flatten)]
+        hashing: HashingArgs,
+    },
+    /// Verify a release archive (.tar.gz or .zip) against its embedded
+    /// signature file and manifest
+    Archive {
+        /// Path to the archive
+        #[arg(short, long, required = true)]
+        file: String,
+
+        /// Path of the signature or aggregate file inside the archive
+        #[arg(long, required = true, value_name = "MEMBER")]
+        signature_in_archive: String,
+
+        /// Version string
+        #[arg(short, long)]
+        version: Option<String>,
+    },
+    /// Verify an OCI image digest
+    Image {
+        /// Image digest (sha256:<hex>); with the `net` feature, looked up
+        /// from the registry by tag when omitted
+        #[arg(short, long)]
+        digest: Option<String>,
+
+        /// Image repository
+        #[arg(short, long, required = true)]
+        repository: String,
+
+        /// Image tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+}
+
+/// Limits for hashing many files in parallel
+#[derive(ClapArgs, Debug)]
+struct HashingArgs {
+    /// Files hashed at once (default: one per CPU)
+    #[arg(long)]
+    jobs: Option<usize>,
+
+    /// Total MiB of the files hashed at once (default: 256 per job)
+    #[arg(long, value_name = "MIB")]
+    max_in_flight: Option<u64>,
+}
+
+impl HashingArgs {
+    fn limits(&self) -> Limits {
+        Limits::new(self.jobs, self.max_in_flight.map(|mib| mib * 1024 * 1024))
+    }
 }
 
 fn main() {
@@ -95,8 +229,23 @@ fn main() {
 
     match verify_target(&args) {
         Ok(result) => {
+            // JSON Lines streams one line per hashed file ahead of the summary
+            if let (OutputFormat::JsonLines, Some(report)) = (&args.format, &result.hashing) {
+                let mut stdout = std::io::stdout().lock();
+                if let Err(e) =
+                    formatter.format_each(report.jobs.iter().map(JobReport::to_json), &mut stdout)
+                {
+                    eprintln!("{}", formatter.format_error(&e));
+                    std::process::exit(1);
+                }
+            }
             let output = format_verification_output(&result, &args, &formatter);
             println!("{}", output);
+            notify_report(
+                &args.notify_urls,
+                args.notify_secret_file.as_deref(),
+                &verification_json(&result),
+            );
             if !result.valid {
                 std::process::exit(1);
             }
@@ -116,22 +265,53 @@ struct VerificationResult {
     valid_signatures: usize,
     invalid_signatures: usize,
     threshold_met: bool,
+    /// Team approvals, with a governance config that has teams
+    nested: Option<NestedMultisigResult>,
     errors: Vec<String>,
+    signed_at: Option<chrono::DateTime<chrono::Utc>>,
+    historical: Option<HistoricalVerification>,
+    pin: Option<PinOutcome>,
+    /// P2WSH address of the multisig, with `--show-address`
+    address: Option<String>,
+    report: VerificationReport,
+    hashing: Option<Report<()>>,
 }
 
 fn verify_target(args: &Args) -> Result<VerificationResult, Box<dyn std::error::Error>> {
-    // Load signatures
-    let signature_files = parse_comma_separated(&args.signatures);
-    let signatures = load_signatures(&signature_files)?;
-
-    // Load public keys if provided
-    let public_keys = if let Some(pubkey_files) = &args.pubkeys {
-        let pubkey_files = parse_comma_separated(pubkey_files);
-        load_public_keys(&pubkey_files)?
-    } else {
-        Vec::new()
+    // Ctrl-C stops loading and hashing
+    let cancel = CancelToken::on_interrupt().unwrap_or_default();
+
+    // Read the signatures embedded in archive targets
+    let embedded = match &args.target {
+        VerifyTarget::Archive {
+            file,
+            signature_in_archive,
+            ..
+        } => {
+            let archive = ReleaseArchive::open(file)?;
+            let embedded =
+                EmbeddedSignatures::from_json(&archive.read_member(signature_in_archive)?)?;
+            Some((archive, embedded, signature_in_archive.as_str()))
+        }
+        _ => None,
     };
 
+    // Load the committee from the config file, or the public keys if provided
+    let (public_keys, given_threshold, nested_multisig) =
+        if let Some(path) = &args.governance_config {
+            let config = GovernanceConfig::from_file(path)?;
+            (
+                config.public_keys(),
+                Some(config.threshold),
+                config.to_nested_multisig()?,
+            )
+        } else if let Some(pubkey_files) = &args.pubkeys {
+            let pubkey_files = parse_comma_separated(pubkey_files);
+            (load_public_keys(&pubkey_files)?, args.threshold, None)
+        } else {
+            (Vec::new(), args.threshold, None)
+        };
+
     // Create message to verify based on target type
     let (message_bytes, file_hash, file_path) = match &args.target {
         VerifyTarget::Binary {
@@ -140,21 +320,14 @@ fn verify_target(args: &Args) -> Result<VerificationResult, Box<dyn std::error::
             version,
             commit,
         } => {
-            let binary_data = fs::read(file)?;
-            let mut hasher = Sha256::new();
-            hasher.update(&binary_data);
-            let hash = hex::encode(hasher.finalize());
-
-            let mut message_parts =
-                vec!["binary".to_string(), binary_type.to_string(), hash.clone()];
-            if let Some(v) = version {
-                message_parts.push(v.to_string());
-            }
-            if let Some(c) = commit {
-                message_parts.push(c.to_string());
-            }
-            let message = message_parts.join(":");
-            (message.into_bytes(), hash, file.clone())
+            let hash = hash_file(file, &cancel)?;
+            let target = SigningTarget::Binary {
+                binary_type: binary_type.clone(),
+                file_hash: hash.clone(),
+                version: version.clone(),
+                commit: commit.clone(),
+            };
+            (target.to_signing_bytes(), hash, file.clone())
         }
         VerifyTarget::Bundle {
             file,
@@ -162,147 +335,326 @@ fn verify_target(args: &Args) -> Result<VerificationResult, Box<dyn std::error::
             build_config_hash,
             spec_hash,
         } => {
-            let bundle_data = fs::read(file)?;
-            let mut hasher = Sha256::new();
-            hasher.update(&bundle_data);
-            let hash = hex::encode(hasher.finalize());
-
-            let mut message_parts = vec!["bundle".to_string(), hash.clone()];
-            if let Some(sh) = source_hash {
-                message_parts.push(sh.to_string());
+            let hash = hash_file(file, &cancel)?;
+            let target = SigningTarget::Bundle {
+                file_hash: hash.clone(),
+                source_hash: source_hash.clone(),
+                build_config_hash: build_config_hash.clone(),
+                spec_hash: spec_hash.clone(),
+            };
+            (target.to_signing_bytes(), hash, file.clone())
+        }
+        VerifyTarget::Checksums { file, version } => {
+            let hash = hash_file(file, &cancel)?;
+            let target = SigningTarget::Checksums {
+                file_hash: hash.clone(),
+                version: version.clone(),
+            };
+            (target.to_signing_bytes(), hash, file.clone())
+        }
+        VerifyTarget::Manifest { file, version, .. } => {
+            let manifest = load_manifest(file)?;
+            (
+                manifest.signing_bytes(version.as_deref()),
+                manifest.digest(),
+                file.clone(),
+            )
+        }
+        VerifyTarget::Archive { file, version, .. } => {
+            let (_, embedded, _) = embedded.as_ref().ok_or("Embedded signatures not loaded")?;
+            (
+                embedded.manifest.signing_bytes(version.as_deref()),
+                embedded.manifest.digest(),
+                file.clone(),
+            )
+        }
+        VerifyTarget::Image {
+            digest,
+            repository,
+            tag,
+        } => {
+            let digest = match digest {
+                Some(digest) => digest.clone(),
+                None => lookup_digest(repository, tag.as_deref())?,
+            };
+            let image = OciImageRef::new(repository, &digest, tag.as_deref())?;
+            (
+                image.to_signing_bytes(),
+                image.digest_hex().to_string(),
+                image.to_string(),
+            )
+        }
+    };
+
+    // Signatures can only be by keys of the history, when one is given
+    let history = match &args.history {
+        Some(history) => Some(GovernanceHistory::load(Path::new(history))?),
+        None => None,
+    };
+    let candidate_keys: Vec<PublicKey> = match &history {
+        Some(history) => history
+            .generations()
+            .iter()
+            .flat_map(|generation| generation.multisig.public_keys().iter().cloned())
+            .collect(),
+        None => public_keys.clone(),
+    };
+
+    // Load signatures, from the archive itself for archive targets, verifying
+    // them as they are read
+    let tally = match &embedded {
+        Some((_, embedded, _)) => {
+            let mut tally = SignatureTally::new(&message_bytes, &candidate_keys);
+            for signature in &embedded.signatures {
+                tally.add_signature(signature.clone());
             }
-            if let Some(bch) = build_config_hash {
-                message_parts.push(bch.to_string());
+            for timestamp in &embedded.timestamps {
+                tally.add_timestamp(timestamp.clone());
             }
-            if let Some(sph) = spec_hash {
-                message_parts.push(sph.to_string());
+            for attributes in &embedded.attributes {
+                tally.add_attributes(attributes.clone());
             }
-            let message = message_parts.join(":");
-            (message.into_bytes(), hash, file.clone())
+            tally.latest_signed_at = embedded.signed_at.iter().max().copied();
+            tally
         }
-        VerifyTarget::Checksums { file, version } => {
-            let checksums_data = fs::read_to_string(file)?;
-            let mut hasher = Sha256::new();
-            hasher.update(checksums_data.as_bytes());
-            let hash = hex::encode(hasher.finalize());
-
-            let mut message_parts = vec!["checksums".to_string(), hash.clone()];
-            if let Some(v) = version {
-                message_parts.push(v.to_string());
-            }
-            let message = message_parts.join(":");
-            (message.into_bytes(), hash, file.clone())
+        None => {
+            let signature_files = parse_comma_separated(
+                args.signatures
+                    .as_deref()
+                    .ok_or("--signatures is required")?,
+            );
+            load_signatures(&signature_files, &message_bytes, &candidate_keys, &cancel)?
+        }
+    };
+    let signatures = tally.signatures();
+
+    // Select the historical config if requested
+    let historical = match (&history, &args.as_of) {
+        (Some(history), Some(as_of)) => {
+            let AsOf(time) = as_of.parse()?;
+            Some(history.verify_at(&message_bytes, &signatures, time)?)
         }
+        _ => None,
     };
 
-    // Verify signatures
-    let mut valid_signatures = 0;
-    let mut invalid_signatures = 0;
     let mut errors = Vec::new();
+    let pinned_keys = public_keys.clone();
 
-    for signature in &signatures {
-        let mut verified = false;
-        for public_key in &public_keys {
-            match blvm_sdk::governance::verify_signature(signature, &message_bytes, public_key) {
-                Ok(true) => {
-                    verified = true;
-                    break;
-                }
-                Ok(false) => continue,
-                Err(e) => {
-                    errors.push(format!("Verification error: {}", e));
-                    continue;
-                }
-            }
+    // Verify against the historical generation's keys, or the given ones
+    let (public_keys, threshold) = match &historical {
+        Some(historical) => (historical.public_keys.clone(), Some(historical.threshold)),
+        None => (public_keys, given_threshold),
+    };
+    let address = match args.show_address {
+        Some(network) => Some(multisig_address(&public_keys, threshold, network)?),
+        None => None,
+    };
+    // A team structure must approve as well
+    let nested = match &nested_multisig {
+        Some(nested) => Some(nested.verify_signatures(&message_bytes, &signatures)?),
+        None => None,
+    };
+
+    // Run the verification stages selected by the policy
+    let pipeline = load_pipeline(args)?;
+    let mut ctx = VerificationContext::new(message_bytes, &file_path, &file_hash);
+    ctx.recorded_hashes = tally.recorded_hashes.clone();
+    ctx.signatures = signatures;
+    ctx.rejected_signatures = tally.rejected;
+    ctx.public_keys = public_keys;
+    ctx.threshold = threshold;
+    ctx.signed_at = tally.latest_signed_at.into_iter().collect();
+    ctx.timestamps = tally.timestamps();
+    ctx.attributes = tally.attributes();
+    ctx.nested_approved = nested.as_ref().map(|nested| nested.inter_team_approved);
+
+    let report = pipeline.run(&mut ctx);
+    let threshold_met = report
+        .entry("threshold")
+        .is_some_and(|entry| !entry.result.is_fail())
+        && ctx.nested_approved != Some(false);
+    let mut valid = report.passed();
+    let (valid_signatures, invalid_signatures) = (ctx.valid_signers.len(), ctx.invalid_signatures);
+
+    // Check the files against the signed manifest, stopping on Ctrl-C
+    let mut hashing = None;
+    let mismatches = match (&args.target, &embedded) {
+        (_, Some((archive, embedded, signature_member))) => {
+            archive.verify_members(signature_member, &embedded.manifest)?
         }
-        if verified {
-            valid_signatures += 1;
-        } else {
-            invalid_signatures += 1;
+        (
+            VerifyTarget::Manifest {
+                file,
+                dir,
+                hashing: hashing_args,
+                ..
+            },
+            None,
+        ) => {
+            let base = match dir {
+                Some(dir) => PathBuf::from(dir),
+                None => Path::new(file)
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+            };
+            let (mismatches, report) =
+                load_manifest(file)?.verify_files_with(&base, &hashing_args.limits(), &cancel)?;
+            hashing = Some(report);
+            mismatches
         }
+        _ => Vec::new(),
+    };
+    valid &= mismatches.is_empty();
+    errors.extend(mismatches.iter().map(|m| format!("file {}", m)));
+    if let Some(nested) = nested.as_ref().filter(|nested| !nested.inter_team_approved) {
+        valid = false;
+        errors.push(format!(
+            "teams: {} of {} required teams approved",
+            nested.teams_approved, nested.teams_required
+        ));
     }
-
-    // Check multisig threshold if provided
-    let threshold_met = if let Some(threshold_str) = &args.threshold {
-        let (threshold, total) = parse_threshold(threshold_str)?;
-        if public_keys.len() != total {
-            errors.push(format!(
-                "Expected {} public keys, got {}",
-                total,
-                public_keys.len()
-            ));
-            false
-        } else {
-            let multisig = Multisig::new(threshold, total, public_keys)?;
-            match multisig.verify(&message_bytes, &signatures) {
-                Ok(result) => result,
-                Err(e) => {
-                    errors.push(format!("Multisig verification error: {}", e));
-                    false
-                }
-            }
-        }
-    } else {
-        valid_signatures > 0
+    errors.extend(
+        report
+            .entries
+            .iter()
+            .filter_map(|entry| match &entry.result {
+                StageResult::Fail(detail) => Some(format!("{}: {}", entry.stage, detail)),
+                _ => None,
+            }),
+    );
+
+    // Compare the keys with the pin only once they verified the artifact
+    let pin = match &args.tofu {
+        Some(source) if valid && invalid_signatures == 0 => Some(check_pin(
+            args.pin_file.as_deref().map(Path::new),
+            source,
+            &pinned_keys,
+            // Without a threshold any one of the keys sufficed
+            given_threshold.map_or_else(|| Threshold::new(1, pinned_keys.len()), Ok)?,
+            args.accept_key_change.as_deref(),
+        )?),
+        _ => None,
     };
 
     Ok(VerificationResult {
-        valid: threshold_met && invalid_signatures == 0,
+        valid,
         file_path,
         file_hash,
         valid_signatures,
         invalid_signatures,
         threshold_met,
+        nested,
         errors,
+        signed_at: tally.latest_signed_at,
+        historical,
+        pin,
+        address,
+        report,
+        hashing,
     })
 }
 
-fn load_signatures(
-    signature_files: &[String],
-) -> Result<Vec<Signature>, Box<dyn std::error::Error>> {
-    let mut signatures = Vec::new();
+/// P2WSH address of the multisig verified against
+fn multisig_address(
+    public_keys: &[PublicKey],
+    threshold: Option<Threshold>,
+    network: Network,
+) -> Result<String, Box<dyn std::error::Error>> {
+    // Without a threshold any one of the keys suffices
+    let threshold = threshold.map_or_else(|| Threshold::new(1, public_keys.len()), Ok)?;
+    let multisig = Multisig::with_threshold(threshold, public_keys.to_vec())?;
+    Ok(multisig.to_address(network)?.to_string())
+}
 
-    for file_path in signature_files {
-        if !Path::new(file_path).exists() {
-            return Err(format!("Signature file not found: {}", file_path).into());
-        }
+/// SHA256 of a file, streamed and stopping on cancellation
+fn hash_file(file_path: &str, cancel: &CancelToken) -> Result<String, Box<dyn std::error::Error>> {
+    let (hashes, _) = hash_files(&[PathBuf::from(file_path)], &Limits::default(), cancel)?;
+    Ok(hashes.into_iter().next().unwrap_or_default())
+}
 
-        let sig_data = fs::read_to_string(file_path)?;
-        let sig_json: serde_json::Value = serde_json::from_str(&sig_data)?;
+fn load_manifest(file_path: &str) -> Result<Manifest, Box<dyn std::error::Error>> {
+    if !Path::new(file_path).exists() {
+        return Err(format!("Manifest file not found: {}", file_path).into());
+    }
+    Ok(Manifest::parse(&fs::read_to_string(file_path)?)?)
+}
 
-        let signature_hex = sig_json["signature"]
-            .as_str()
-            .ok_or("Invalid signature file format")?;
+fn load_pipeline(args: &Args) -> Result<Pipeline, Box<dyn std::error::Error>> {
+    let builder = match &args.policy {
+        Some(policy_path) => {
+            if !Path::new(policy_path).exists() {
+                return Err(format!("Policy file not found: {}", policy_path).into());
+            }
+            PolicyConfig::from_toml(&fs::read_to_string(policy_path)?)?.builder()?
+        }
+        None => PipelineBuilder::with_builtin_stages(),
+    };
+    Ok(builder.build()?)
+}
 
-        let signature_bytes = hex::decode(signature_hex)?;
-        let signature = Signature::from_bytes(&signature_bytes)?;
-        signatures.push(signature);
-    }
+/// Digest the image tag points to, from the registry
+#[cfg(feature = "net")]
+fn lookup_digest(
+    repository: &str,
+    tag: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let tag = tag.ok_or("--tag is required to look up the digest")?;
+    Ok(blvm_sdk::governance::oci::resolve_digest(repository, tag)?)
+}
 
-    Ok(signatures)
+#[cfg(not(feature = "net"))]
+fn lookup_digest(
+    _repository: &str,
+    _tag: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    Err("--digest is required (registry lookup needs the net feature)".into())
 }
 
-fn load_public_keys(pubkey_files: &[String]) -> Result<Vec<PublicKey>, Box<dyn std::error::Error>> {
-    let mut public_keys = Vec::new();
+/// Load signature or aggregate files, checking the signatures against the
+/// candidate keys as they are read
+///
+/// Aggregates over the in-memory limit are read one entry at a time, and
+/// only one signature per key is kept.
+fn load_signatures(
+    signature_files: &[String],
+    message: &[u8],
+    candidate_keys: &[PublicKey],
+    cancel: &CancelToken,
+) -> Result<SignatureTally, Box<dyn std::error::Error>> {
+    let mut tally = SignatureTally::new(message, candidate_keys);
 
-    for file_path in pubkey_files {
+    for file_path in signature_files {
+        cancel.check("loading signatures")?;
         if !Path::new(file_path).exists() {
-            return Err(format!("Public key file not found: {}", file_path).into());
+            return Err(format!("Signature file not found: {}", file_path).into());
         }
-
-        let key_data = fs::read_to_string(file_path)?;
-        let key_json: serde_json::Value = serde_json::from_str(&key_data)?;
-
-        let pubkey_hex = key_json["public_key"]
-            .as_str()
-            .ok_or("Invalid public key file format")?;
-
-        let pubkey_bytes = hex::decode(pubkey_hex)?;
-        let public_key = PublicKey::from_bytes(&pubkey_bytes)?;
-        public_keys.push(public_key);
+        tally.add_file(file_path, IN_MEMORY_LIMIT)?;
     }
 
-    Ok(public_keys)
+    Ok(tally)
+}
+
+/// JSON output, also posted to `--notify-url`
+fn verification_json(result: &VerificationResult) -> serde_json::Value {
+    serde_json::json!({
+        "success": result.valid,
+        "file_path": result.file_path,
+        "file_hash": result.file_hash,
+        "valid_signatures": result.valid_signatures,
+        "invalid_signatures": result.invalid_signatures,
+        "threshold_met": result.threshold_met,
+        "teams_approved": result.nested.as_ref().map(|n| n.teams_approved),
+        "teams_required": result.nested.as_ref().map(|n| n.teams_required),
+        "errors": result.errors,
+        "signed_at": result.signed_at.map(|t| t.to_rfc3339()),
+        "config_generation": result.historical.as_ref().map(|h| h.generation()),
+        "as_of": result.historical.as_ref().map(|h| h.as_of.to_rfc3339()),
+        "rotation_boundary": result.historical.as_ref().map(|h| h.ambiguous),
+        "pin": result.pin.as_ref().map(|p| p.to_string()),
+        "multisig_address": result.address,
+        "checks": result.report.entries,
+    })
 }
 
 fn format_verification_output(
@@ -310,40 +662,67 @@ fn format_verification_output(
     args: &Args,
     formatter: &OutputFormatter,
 ) -> String {
-    if args.format == OutputFormat::Json {
-        let output_data = serde_json::json!({
-            "success": result.valid,
-            "file_path": result.file_path,
-            "file_hash": result.file_hash,
-            "valid_signatures": result.valid_signatures,
-            "invalid_signatures": result.invalid_signatures,
-            "threshold_met": result.threshold_met,
-            "errors": result.errors,
-        });
-        formatter
-            .format(&output_data)
-            .unwrap_or_else(|_| "{}".to_string())
-    } else {
-        let mut output = "Verification Results\n".to_string();
-        output.push_str(&format!("File: {}\n", result.file_path));
-        output.push_str(&format!("Hash: {}\n", result.file_hash));
-        output.push_str(&format!("Valid signatures: {}\n", result.valid_signatures));
-        output.push_str(&format!(
-            "Invalid signatures: {}\n",
-            result.invalid_signatures
-        ));
-        output.push_str(&format!("Threshold met: {}\n", result.threshold_met));
-        if !result.errors.is_empty() {
-            output.push_str("\nErrors:\n");
-            for error in &result.errors {
-                output.push_str(&format!("  - {}\n", error));
-            }
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = verification_json(result);
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
         }
-        if result.valid {
-            output.push_str("\n✅ Verification PASSED\n");
-        } else {
-            output.push_str("\n❌ Verification FAILED\n");
+        OutputFormat::Text => {
+            let mut output = "Verification Results\n".to_string();
+            output.push_str(&format!("File: {}\n", result.file_path));
+            output.push_str(&format!("Hash: {}\n", result.file_hash));
+            output.push_str(&format!("Valid signatures: {}\n", result.valid_signatures));
+            output.push_str(&format!(
+                "Invalid signatures: {}\n",
+                result.invalid_signatures
+            ));
+            output.push_str(&format!("Threshold met: {}\n", result.threshold_met));
+            if let Some(nested) = &result.nested {
+                output.push_str(&format!(
+                    "Teams approved: {} of {} required\n",
+                    nested.teams_approved, nested.teams_required
+                ));
+            }
+            if let Some(signed_at) = result.signed_at {
+                output.push_str(&format!(
+                    "Recorded signing time: {} ({}, unsigned)\n",
+                    signed_at.to_rfc3339(),
+                    format_age(signed_at.into())
+                ));
+            }
+            output.push_str(&format!("Checks:\n{}", result.report));
+            if let Some(report) = &result.hashing {
+                output.push_str(&format!("Hashing:\n{}\n", report));
+            }
+            if let Some(historical) = &result.historical {
+                output.push_str(&format!("Verified against: {}\n", historical.describe()));
+            }
+            if let Some(address) = &result.address {
+                output.push_str(&format!("Multisig address: {}\n", address));
+            }
+            match &result.pin {
+                Some(PinOutcome::ChangeAccepted(change)) => {
+                    output.push_str(&format!("WARNING: accepted key change: {}\n", change));
+                }
+                Some(PinOutcome::Pinned) => {
+                    output.push_str(&format!("Key pin: {}\n", PinOutcome::Pinned));
+                }
+                Some(PinOutcome::Consistent) | None => {}
+            }
+            if !result.errors.is_empty() {
+                output.push_str("\nErrors:\n");
+                for error in &result.errors {
+                    output.push_str(&format!("  - {}\n", error));
+                }
+            }
+            if result.valid {
+                output.push_str("\n✅ Verification PASSED\n");
+            } else {
+                output.push_str("\n❌ Verification FAILED\n");
+            }
+            output
         }
-        output
     }
 }
@@ -2,11 +2,22 @@
 //!
 //! Verify governance signatures and multisig thresholds.
 
-use blvm_sdk::cli::input::{parse_comma_separated, parse_threshold};
+use bitcoin::Network;
+use blvm_sdk::cli::input::{
+    load_public_keys, parse_comma_separated, parse_network, parse_sha256_hex,
+};
+use blvm_sdk::cli::notify::notify_report;
 use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
-use blvm_sdk::governance::{GovernanceMessage, Multisig, PublicKey, Signature};
+use blvm_sdk::governance::aggregation::{SignatureTally, IN_MEMORY_LIMIT};
+use blvm_sdk::governance::history::{AsOf, GovernanceHistory, HistoricalVerification};
+use blvm_sdk::governance::nested_multisig::{NestedMultisig, NestedMultisigResult};
+use blvm_sdk::governance::pin::{check_pin, PinOutcome};
+use blvm_sdk::governance::pipeline::{PipelineBuilder, VerificationContext, VerificationReport};
+use blvm_sdk::governance::{GovernanceConfig, GovernanceMessage, Multisig, PublicKey, Threshold};
+use blvm_sdk::util::humanize::{format_btc, format_sats};
+use blvm_sdk::util::CancelToken;
 use clap::{Parser, Subcommand};
-use std::fs;
+use sha2::{Digest, Sha256};
 use std::path::Path;
 
 /// Verify governance signatures
@@ -14,7 +25,7 @@ use std::path::Path;
 #[command(name = "blvm-verify")]
 #[command(about = "Verify governance signatures and multisig thresholds")]
 struct Args {
-    /// Output format (text, json)
+    /// Output format (text, json, jsonl)
     #[arg(short, long, default_value = "text")]
     format: OutputFormat,
 
@@ -23,16 +34,71 @@ struct Args {
     message: MessageCommand,
 
     /// Signature files (comma-separated)
-    #[arg(short, long, required = true)]
-    signatures: String,
+    #[arg(short, long, required_unless_present = "explain")]
+    signatures: Option<String>,
 
     /// Threshold (e.g., "3-of-5")
     #[arg(short, long)]
-    threshold: Option<String>,
+    threshold: Option<Threshold>,
 
     /// Public key files (comma-separated)
     #[arg(short, long)]
     pubkeys: Option<String>,
+
+    /// Governance config file with the committee, threshold and optional
+    /// team structure (TOML, or JSON with a .json extension), instead of
+    /// --pubkeys and --threshold
+    #[arg(long, conflicts_with_all = ["pubkeys", "threshold", "history"])]
+    governance_config: Option<String>,
+
+    /// Governance history file (rotation records) for historical verification
+    #[arg(long, requires = "as_of")]
+    history: Option<String>,
+
+    /// Verify against the config active at this trusted time (RFC3339; the
+    /// signing time recorded in signature files is unsigned and not used)
+    #[arg(long, requires = "history")]
+    as_of: Option<String>,
+
+    /// Trust on first use: pin the public keys and threshold for this
+    /// artifact source (repository or URL) and require them afterwards
+    #[arg(long, value_name = "SOURCE", conflicts_with = "history")]
+    tofu: Option<String>,
+
+    /// Pin file for --tofu (default: ~/.config/blvm/pins.json)
+    #[arg(long, requires = "tofu")]
+    pin_file: Option<String>,
+
+    /// Accept a change of the pinned keys or threshold, with an audit note
+    #[arg(
+        long,
+        requires = "tofu",
+        value_name = "NOTE",
+        num_args = 0..=1,
+        default_missing_value = "accepted with --accept-key-change"
+    )]
+    accept_key_change: Option<String>,
+
+    /// POST the JSON report to this URL when done (repeatable; needs the
+    /// net feature). Failed notifications are warnings and never change
+    /// the exit code
+    #[arg(long = "notify-url", value_name = "URL")]
+    notify_urls: Vec<String>,
+
+    /// File holding the shared secret for the notification HMAC header
+    #[arg(long, requires = "notify_urls")]
+    notify_secret_file: Option<String>,
+
+    /// Print the P2WSH address of the multisig verified against, on this
+    /// network (mainnet, testnet, signet, regtest), to compare with the
+    /// expected on-chain address
+    #[arg(long, value_name = "NETWORK", value_parser = parse_network)]
+    show_address: Option<Network>,
+
+    /// Print the committee, the reconstructed signed message and the
+    /// expected keys, then exit without reading any signature
+    #[arg(long)]
+    explain: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -56,6 +122,10 @@ enum MessageCommand {
         /// Module version
         #[arg(short, long, required = true)]
         version: String,
+
+        /// SHA256 of the module binary, binding the approval to that binary
+        #[arg(long)]
+        binary_hash: Option<String>,
     },
     /// Verify a budget decision message
     Budget {
@@ -73,10 +143,26 @@ fn main() {
     let args = Args::parse();
     let formatter = OutputFormatter::new(args.format.clone());
 
+    if args.explain {
+        match explain(&args) {
+            Ok(explanation) => println!("{}", format_explanation(&explanation, &args, &formatter)),
+            Err(e) => {
+                eprintln!("{}", formatter.format_error(&*e));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     match verify_message(&args) {
         Ok(result) => {
             let output = format_verification_output(&result, &args, &formatter);
             println!("{}", output);
+            notify_report(
+                &args.notify_urls,
+                args.notify_secret_file.as_deref(),
+                &verification_json(&result),
+            );
         }
         Err(e) => {
             eprintln!("{}", formatter.format_error(&*e));
@@ -85,68 +171,156 @@ fn main() {
     }
 }
 
-fn verify_message(args: &Args) -> Result<VerificationResult, Box<dyn std::error::Error>> {
-    // Create the message
-    let message = match &args.message {
+/// The governance message the arguments describe
+fn build_message(
+    command: &MessageCommand,
+) -> Result<GovernanceMessage, Box<dyn std::error::Error>> {
+    Ok(match command {
         MessageCommand::Release { version, commit } => GovernanceMessage::Release {
             version: version.clone(),
             commit_hash: commit.clone(),
         },
-        MessageCommand::Module { name, version } => GovernanceMessage::ModuleApproval {
+        MessageCommand::Module {
+            name,
+            version,
+            binary_hash: None,
+        } => GovernanceMessage::ModuleApproval {
+            module_name: name.clone(),
+            version: version.clone(),
+        },
+        MessageCommand::Module {
+            name,
+            version,
+            binary_hash: Some(binary_hash),
+        } => GovernanceMessage::ModuleBinaryApproval {
             module_name: name.clone(),
             version: version.clone(),
+            binary_hash: parse_sha256_hex(binary_hash)?,
         },
         MessageCommand::Budget { amount, purpose } => GovernanceMessage::BudgetDecision {
             amount: *amount,
             purpose: purpose.clone(),
         },
-    };
+    })
+}
 
-    // Load signatures
-    let signature_files = parse_comma_separated(&args.signatures);
-    let signatures = load_signatures(&signature_files)?;
+/// Committee from `--governance-config`, or `--pubkeys` and `--threshold`
+struct GivenCommittee {
+    public_keys: Vec<PublicKey>,
+    threshold: Option<Threshold>,
+    /// Team structure of a config with a nested section
+    nested: Option<NestedMultisig>,
+}
 
-    // Load public keys if provided
-    let public_keys = if let Some(pubkey_files) = &args.pubkeys {
-        let pubkey_files = parse_comma_separated(pubkey_files);
-        load_public_keys(&pubkey_files)?
-    } else {
-        Vec::new()
-    };
+fn load_committee(args: &Args) -> Result<GivenCommittee, Box<dyn std::error::Error>> {
+    if let Some(path) = &args.governance_config {
+        let config = GovernanceConfig::from_file(path)?;
+        return Ok(GivenCommittee {
+            public_keys: config.public_keys(),
+            threshold: Some(config.threshold),
+            nested: config.to_nested_multisig()?,
+        });
+    }
+    Ok(GivenCommittee {
+        public_keys: match &args.pubkeys {
+            Some(pubkey_files) => load_public_keys(&parse_comma_separated(pubkey_files))?,
+            None => Vec::new(),
+        },
+        threshold: args.threshold,
+        nested: None,
+    })
+}
+
+fn verify_message(args: &Args) -> Result<VerificationResult, Box<dyn std::error::Error>> {
+    let message = build_message(&args.message)?;
+    let committee = load_committee(args)?;
+    let public_keys = committee.public_keys.clone();
 
-    // Verify signatures
     let message_bytes = message.to_signing_bytes();
-    let mut valid_signatures = 0;
-    let mut invalid_signatures = 0;
-
-    for signature in &signatures {
-        let mut verified = false;
-        for public_key in &public_keys {
-            if blvm_sdk::governance::verify_signature(signature, &message_bytes, public_key)? {
-                verified = true;
-                break;
-            }
-        }
-        if verified {
-            valid_signatures += 1;
-        } else {
-            invalid_signatures += 1;
-        }
-    }
 
-    // Check multisig threshold if provided
-    let threshold_met = if let Some(threshold_str) = &args.threshold {
-        let (threshold, total) = parse_threshold(threshold_str)?;
-        if public_keys.len() != total {
-            return Err(
-                format!("Expected {} public keys, got {}", total, public_keys.len()).into(),
-            );
+    // Signatures can only be by keys of the history, when one is given
+    let history = match &args.history {
+        Some(history) => Some(GovernanceHistory::load(Path::new(history))?),
+        None => None,
+    };
+    let candidate_keys: Vec<PublicKey> = match &history {
+        Some(history) => history
+            .generations()
+            .iter()
+            .flat_map(|generation| generation.multisig.public_keys().iter().cloned())
+            .collect(),
+        None => public_keys.clone(),
+    };
+
+    // Load signatures, verifying them as they are read
+    // Ctrl-C stops loading large aggregates
+    let cancel = CancelToken::on_interrupt().unwrap_or_default();
+    let signature_files = parse_comma_separated(
+        args.signatures
+            .as_deref()
+            .expect("clap requires --signatures without --explain"),
+    );
+    let tally = load_signatures(&signature_files, &message_bytes, &candidate_keys, &cancel)?;
+    let signatures = tally.signatures();
+
+    // Select the historical config if requested
+    let historical = match (&history, &args.as_of) {
+        (Some(history), Some(as_of)) => {
+            let AsOf(time) = as_of.parse()?;
+            Some(history.verify_at(&message_bytes, &signatures, time)?)
         }
+        _ => None,
+    };
+
+    let pinned_keys = public_keys.clone();
+
+    // Verify against the historical generation's keys, or the given ones
+    let (public_keys, threshold) = match &historical {
+        Some(historical) => (historical.public_keys.clone(), Some(historical.threshold)),
+        None => (public_keys, committee.threshold),
+    };
+    let address = match args.show_address {
+        Some(network) => Some(multisig_address(&public_keys, threshold, network)?),
+        None => None,
+    };
+    // A team structure must approve as well
+    let nested = match &committee.nested {
+        Some(nested) => Some(nested.verify_signatures(&message_bytes, &signatures)?),
+        None => None,
+    };
 
-        let multisig = Multisig::new(threshold, total, public_keys)?;
-        multisig.verify(&message_bytes, &signatures)?
-    } else {
-        valid_signatures > 0
+    let mut ctx = VerificationContext::new(message_bytes, &message.description(), "");
+    ctx.signatures = signatures;
+    ctx.rejected_signatures = tally.rejected;
+    ctx.public_keys = public_keys;
+    ctx.threshold = threshold;
+    ctx.signed_at = tally.latest_signed_at.into_iter().collect();
+    ctx.timestamps = tally.timestamps();
+    ctx.nested_approved = nested.as_ref().map(|nested| nested.inter_team_approved);
+
+    let report = PipelineBuilder::with_builtin_stages()
+        .build()?
+        .run(&mut ctx);
+    let threshold_met = report
+        .entry("threshold")
+        .is_some_and(|entry| !entry.result.is_fail())
+        && ctx.nested_approved != Some(false);
+    let (valid_signatures, invalid_signatures) = (ctx.valid_signers.len(), ctx.invalid_signatures);
+
+    // Compare the keys with the pin only once every signature verified and
+    // the threshold is met
+    let pin = match &args.tofu {
+        Some(source) if threshold_met && invalid_signatures == 0 => Some(check_pin(
+            args.pin_file.as_deref().map(Path::new),
+            source,
+            &pinned_keys,
+            // Without a threshold any one of the keys sufficed
+            committee
+                .threshold
+                .map_or_else(|| Threshold::new(1, pinned_keys.len()), Ok)?,
+            args.accept_key_change.as_deref(),
+        )?),
+        _ => None,
     };
 
     Ok(VerificationResult {
@@ -154,63 +328,215 @@ fn verify_message(args: &Args) -> Result<VerificationResult, Box<dyn std::error:
         valid_signatures,
         invalid_signatures,
         threshold_met,
+        nested,
+        historical,
+        pin,
+        address,
+        report,
     })
 }
 
+/// A committee signatures would be checked against
+#[derive(Debug)]
+struct ExplainedCommittee {
+    /// History generation, with `--history`
+    generation: Option<usize>,
+    /// Required signatures; `None` without keys
+    threshold: Option<Threshold>,
+    public_keys: Vec<PublicKey>,
+}
+
+/// What `--explain` prints
+#[derive(Debug)]
+struct Explanation {
+    message: GovernanceMessage,
+    committees: Vec<ExplainedCommittee>,
+    pin_source: Option<String>,
+}
+
+/// Work out what would be verified, without reading any signature
+fn explain(args: &Args) -> Result<Explanation, Box<dyn std::error::Error>> {
+    let message = build_message(&args.message)?;
+    let committees = match (&args.history, &args.as_of) {
+        (Some(history), Some(as_of)) => {
+            let AsOf(time) = as_of.parse()?;
+            let history = GovernanceHistory::load(Path::new(history))?;
+            let configs = history.configs_at(time);
+            if configs.is_empty() {
+                return Err(format!("No governance config is active at {}", time).into());
+            }
+            configs
+                .into_iter()
+                .map(|config| ExplainedCommittee {
+                    generation: Some(config.generation),
+                    threshold: Some(config.multisig.m_of_n()),
+                    public_keys: config.multisig.public_keys().to_vec(),
+                })
+                .collect()
+        }
+        _ => {
+            let GivenCommittee {
+                public_keys,
+                threshold,
+                ..
+            } = load_committee(args)?;
+            // Without a threshold any one of the keys suffices
+            let threshold = match threshold {
+                Some(threshold) => Some(threshold),
+                None if public_keys.is_empty() => None,
+                None => Some(Threshold::new(1, public_keys.len())?),
+            };
+            vec![ExplainedCommittee {
+                generation: None,
+                threshold,
+                public_keys,
+            }]
+        }
+    };
+
+    Ok(Explanation {
+        message,
+        committees,
+        pin_source: args.tofu.clone(),
+    })
+}
+
+fn explanation_json(explanation: &Explanation) -> serde_json::Value {
+    let signing_bytes = explanation.message.to_signing_bytes();
+    serde_json::json!({
+        "message": explanation.message.description(),
+        "signing_bytes": String::from_utf8_lossy(&signing_bytes),
+        "signing_bytes_sha256": hex::encode(Sha256::digest(&signing_bytes)),
+        "committees": explanation.committees.iter().map(|committee| serde_json::json!({
+            "generation": committee.generation,
+            "threshold": committee.threshold.map(|t| t.to_string()),
+            "public_keys": committee.public_keys.iter().map(|key| serde_json::json!({
+                "public_key": key.to_string(),
+                "fingerprint": hex::encode(key.sha256_fingerprint()),
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+        "pin_source": explanation.pin_source,
+    })
+}
+
+fn format_explanation(
+    explanation: &Explanation,
+    args: &Args,
+    formatter: &OutputFormatter,
+) -> String {
+    if !matches!(args.format, OutputFormat::Text) {
+        return formatter
+            .format(&explanation_json(explanation))
+            .unwrap_or_else(|_| "{}".to_string());
+    }
+
+    let signing_bytes = explanation.message.to_signing_bytes();
+    let mut output = "Verification preview (no signatures read)\n".to_string();
+    output.push_str(&format!("Message: {}\n", explanation.message.description()));
+    output.push_str(&format!(
+        "Signed message: {}\n",
+        String::from_utf8_lossy(&signing_bytes)
+    ));
+    output.push_str(&format!(
+        "Signed message SHA256: {}\n",
+        hex::encode(Sha256::digest(&signing_bytes))
+    ));
+    for committee in &explanation.committees {
+        let threshold = committee.threshold.map_or_else(
+            || "none (no public keys given)".to_string(),
+            |t| t.to_string(),
+        );
+        match committee.generation {
+            Some(generation) => output.push_str(&format!(
+                "Threshold (generation {}): {}\n",
+                generation, threshold
+            )),
+            None => output.push_str(&format!("Threshold: {}\n", threshold)),
+        }
+        output.push_str("Expected keys:\n");
+        for (i, key) in committee.public_keys.iter().enumerate() {
+            output.push_str(&format!(
+                "  {}. {} (fingerprint {})\n",
+                i + 1,
+                key,
+                hex::encode(key.sha256_fingerprint())
+            ));
+        }
+    }
+    if let Some(source) = &explanation.pin_source {
+        output.push_str(&format!("Keys pinned for: {}\n", source));
+    }
+    output
+}
+
 #[derive(Debug)]
 struct VerificationResult {
     message: GovernanceMessage,
     valid_signatures: usize,
     invalid_signatures: usize,
     threshold_met: bool,
+    /// Team approvals, with a governance config that has teams
+    nested: Option<NestedMultisigResult>,
+    historical: Option<HistoricalVerification>,
+    pin: Option<PinOutcome>,
+    /// P2WSH address of the multisig, with `--show-address`
+    address: Option<String>,
+    report: VerificationReport,
 }
 
+/// P2WSH address of the multisig verified against
+fn multisig_address(
+    public_keys: &[PublicKey],
+    threshold: Option<Threshold>,
+    network: Network,
+) -> Result<String, Box<dyn std::error::Error>> {
+    // Without a threshold any one of the keys suffices
+    let threshold = threshold.map_or_else(|| Threshold::new(1, public_keys.len()), Ok)?;
+    let multisig = Multisig::with_threshold(threshold, public_keys.to_vec())?;
+    Ok(multisig.to_address(network)?.to_string())
+}
+
+/// Signatures from signature or aggregate files, checked against the
+/// candidate keys as they are read
+///
+/// Aggregates over the in-memory limit are read one entry at a time, and
+/// only one signature per key is kept.
 fn load_signatures(
     signature_files: &[String],
-) -> Result<Vec<Signature>, Box<dyn std::error::Error>> {
-    let mut signatures = Vec::new();
+    message: &[u8],
+    candidate_keys: &[PublicKey],
+    cancel: &CancelToken,
+) -> Result<SignatureTally, Box<dyn std::error::Error>> {
+    let mut tally = SignatureTally::new(message, candidate_keys);
 
     for file_path in signature_files {
+        cancel.check("loading signatures")?;
         if !Path::new(file_path).exists() {
             return Err(format!("Signature file not found: {}", file_path).into());
         }
-
-        let sig_data = fs::read_to_string(file_path)?;
-        let sig_json: serde_json::Value = serde_json::from_str(&sig_data)?;
-
-        let signature_hex = sig_json["signature"]
-            .as_str()
-            .ok_or("Invalid signature file format")?;
-
-        let signature_bytes = hex::decode(signature_hex)?;
-        let signature = Signature::from_bytes(&signature_bytes)?;
-        signatures.push(signature);
+        tally.add_file(file_path, IN_MEMORY_LIMIT)?;
     }
 
-    Ok(signatures)
+    Ok(tally)
 }
 
-fn load_public_keys(pubkey_files: &[String]) -> Result<Vec<PublicKey>, Box<dyn std::error::Error>> {
-    let mut public_keys = Vec::new();
-
-    for file_path in pubkey_files {
-        if !Path::new(file_path).exists() {
-            return Err(format!("Public key file not found: {}", file_path).into());
-        }
-
-        let key_data = fs::read_to_string(file_path)?;
-        let key_json: serde_json::Value = serde_json::from_str(&key_data)?;
-
-        let pubkey_hex = key_json["public_key"]
-            .as_str()
-            .ok_or("Invalid public key file format")?;
-
-        let pubkey_bytes = hex::decode(pubkey_hex)?;
-        let public_key = PublicKey::from_bytes(&pubkey_bytes)?;
-        public_keys.push(public_key);
-    }
-
-    Ok(public_keys)
+/// JSON output, also posted to `--notify-url`
+fn verification_json(result: &VerificationResult) -> serde_json::Value {
+    serde_json::json!({
+        "success": true,
+        "message": result.message.description(),
+        "valid_signatures": result.valid_signatures,
+        "invalid_signatures": result.invalid_signatures,
+        "threshold_met": result.threshold_met,
+        "teams_approved": result.nested.as_ref().map(|n| n.teams_approved),
+        "teams_required": result.nested.as_ref().map(|n| n.teams_required),
+        "config_generation": result.historical.as_ref().map(|h| h.generation()),
+        "as_of": result.historical.as_ref().map(|h| h.as_of.to_rfc3339()),
+        "rotation_boundary": result.historical.as_ref().map(|h| h.ambiguous),
+        "pin": result.pin.as_ref().map(|p| p.to_string()),
+        "multisig_address": result.address,
+        "checks": result.report.entries,
+    })
 }
 
 fn format_verification_output(
@@ -218,26 +544,52 @@ fn format_verification_output(
     args: &Args,
     formatter: &OutputFormatter,
 ) -> String {
-    if args.format == OutputFormat::Json {
-        let output_data = serde_json::json!({
-            "success": true,
-            "message": result.message.description(),
-            "valid_signatures": result.valid_signatures,
-            "invalid_signatures": result.invalid_signatures,
-            "threshold_met": result.threshold_met,
-        });
-        formatter
-            .format(&output_data)
-            .unwrap_or_else(|_| "{}".to_string())
-    } else {
-        let mut output = "Verification Results\n".to_string();
-        output.push_str(&format!("Message: {}\n", result.message.description()));
-        output.push_str(&format!("Valid signatures: {}\n", result.valid_signatures));
-        output.push_str(&format!(
-            "Invalid signatures: {}\n",
-            result.invalid_signatures
-        ));
-        output.push_str(&format!("Threshold met: {}\n", result.threshold_met));
-        output
+    match args.format {
+        OutputFormat::Json | OutputFormat::JsonLines => {
+            let output_data = verification_json(result);
+            formatter
+                .format(&output_data)
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputFormat::Text => {
+            let mut output = "Verification Results\n".to_string();
+            output.push_str(&format!("Message: {}\n", result.message.description()));
+            if let GovernanceMessage::BudgetDecision { amount, .. } = &result.message {
+                output.push_str(&format!(
+                    "Amount: {} ({})\n",
+                    format_btc(*amount),
+                    format_sats(*amount)
+                ));
+            }
+            output.push_str(&format!("Valid signatures: {}\n", result.valid_signatures));
+            output.push_str(&format!(
+                "Invalid signatures: {}\n",
+                result.invalid_signatures
+            ));
+            output.push_str(&format!("Threshold met: {}\n", result.threshold_met));
+            if let Some(nested) = &result.nested {
+                output.push_str(&format!(
+                    "Teams approved: {} of {} required\n",
+                    nested.teams_approved, nested.teams_required
+                ));
+            }
+            output.push_str(&format!("Checks:\n{}", result.report));
+            if let Some(historical) = &result.historical {
+                output.push_str(&format!("Verified against: {}\n", historical.describe()));
+            }
+            if let Some(address) = &result.address {
+                output.push_str(&format!("Multisig address: {}\n", address));
+            }
+            match &result.pin {
+                Some(PinOutcome::ChangeAccepted(change)) => {
+                    output.push_str(&format!("WARNING: accepted key change: {}\n", change));
+                }
+                Some(PinOutcome::Pinned) => {
+                    output.push_str(&format!("Key pin: {}\n", PinOutcome::Pinned));
+                }
+                Some(PinOutcome::Consistent) | None => {}
+            }
+            output
+        }
     }
 }
@@ -2,9 +2,16 @@
 //!
 //! Input parsing and validation utilities for CLI tools.
 
+use bitcoin::bip32::ChildNumber;
 use std::path::Path;
 use std::str::FromStr;
 
+use crate::governance::keystore::{is_keystore_json, KeystoreSecret};
+use crate::governance::{Keystore, PublicKey};
+use crate::util::Redacted;
+use serde::Deserialize;
+use zeroize::Zeroizing;
+
 /// Input validation errors
 #[derive(Debug, thiserror::Error)]
 pub enum InputError {
@@ -35,6 +42,17 @@ pub fn parse_hex(hex_str: &str) -> Result<Vec<u8>, InputError> {
         .map_err(|e| InputError::InvalidFormat(format!("Invalid hex string: {}", e)))
 }
 
+/// Parse a hex-encoded SHA256 digest, returning it in lowercase
+pub fn parse_sha256_hex(value: &str) -> Result<String, InputError> {
+    if value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(InputError::InvalidFormat(format!(
+            "Invalid SHA256 '{}': expected 64 hex characters",
+            value
+        )));
+    }
+    Ok(value.to_ascii_lowercase())
+}
+
 /// Parse a base64 string
 pub fn parse_base64(base64_str: &str) -> Result<Vec<u8>, InputError> {
     use base64::{engine::general_purpose, Engine as _};
@@ -54,6 +72,31 @@ where
         .map_err(|e| InputError::InvalidValue(format!("Invalid number: {}", e)))
 }
 
+/// Parse a Bitcoin network name (`bitcoin` or `mainnet`, `testnet`,
+/// `signet`, `regtest`)
+pub fn parse_network(value: &str) -> Result<bitcoin::Network, InputError> {
+    match value {
+        "mainnet" => Ok(bitcoin::Network::Bitcoin),
+        other => other.parse().map_err(|_| {
+            InputError::InvalidValue(format!(
+                "Unknown network '{}': expected mainnet, testnet, signet or regtest",
+                value
+            ))
+        }),
+    }
+}
+
+/// Parse a BIP32 derivation path such as `m/44'/0'/0'/0/0`
+///
+/// Hardened steps are marked with `'` or `h`.
+pub fn parse_bip32_path(path: &str) -> Result<Vec<ChildNumber>, InputError> {
+    Ok(crate::governance::bip32::parse_path(path)
+        .map_err(|e| InputError::InvalidFormat(e.to_string()))?
+        .into_iter()
+        .map(ChildNumber::from)
+        .collect())
+}
+
 /// Parse a comma-separated list
 pub fn parse_comma_separated(value: &str) -> Vec<String> {
     value
@@ -64,6 +107,9 @@ pub fn parse_comma_separated(value: &str) -> Vec<String> {
 }
 
 /// Validate a threshold string (e.g., "3-of-5")
+///
+/// Only checks the format and `N <= M`. Parse a
+/// [`Threshold`](crate::governance::Threshold) for a fully validated threshold.
 pub fn parse_threshold(threshold: &str) -> Result<(usize, usize), InputError> {
     let parts: Vec<&str> = threshold.split("-of-").collect();
 
@@ -90,6 +136,163 @@ pub fn parse_threshold(threshold: &str) -> Result<(usize, usize), InputError> {
     Ok((threshold_num, total_num))
 }
 
+/// The secret part of a key file
+#[derive(Deserialize)]
+struct KeyFileSecret {
+    secret_key: Redacted<String>,
+}
+
+/// Environment variable holding the passphrase of encrypted key files
+pub const KEY_PASSPHRASE_ENV: &str = "BLLVM_KEY_PASSPHRASE";
+
+/// Load the secret key bytes of a key file: a [`Keystore`] or a key file
+/// of the earlier format
+///
+/// Passphrase-encrypted key files are decrypted with the passphrase from
+/// [`KEY_PASSPHRASE_ENV`], or one prompted for on the terminal. Errors name
+/// the file but never quote its contents.
+pub fn load_secret_key(path: &str) -> Result<Zeroizing<Vec<u8>>, InputError> {
+    let invalid =
+        |reason: &str| InputError::InvalidFormat(format!("key file {}: {}", path, reason));
+
+    let contents = Zeroizing::new(std::fs::read_to_string(path)?);
+    if is_keystore_json(&contents) {
+        let keystore = Keystore::from_json(&contents)
+            .map_err(|e| InputError::InvalidFormat(format!("key file {}: {}", path, e)))?;
+        return match &keystore.secret {
+            KeystoreSecret::Plain(secret_key) => Ok(Zeroizing::new(
+                hex::decode(secret_key.expose()).map_err(|_| invalid("secret is not valid hex"))?,
+            )),
+            KeystoreSecret::Encrypted(envelope) => {
+                decrypt_secret_key(path, &Zeroizing::new(envelope.to_string()))
+            }
+        };
+    }
+    if crate::governance::keys::is_encrypted_key_json(&contents) {
+        return decrypt_secret_key(path, &contents);
+    }
+    let key_file: KeyFileSecret =
+        serde_json::from_str(&contents).map_err(|_| invalid("no hex secret_key field"))?;
+    let secret = hex::decode(key_file.secret_key.expose())
+        .map_err(|_| invalid("secret_key is not valid hex"))?;
+    Ok(Zeroizing::new(secret))
+}
+
+#[cfg(feature = "signing")]
+fn decrypt_secret_key(path: &str, contents: &str) -> Result<Zeroizing<Vec<u8>>, InputError> {
+    let passphrase = read_key_passphrase(&format!("Passphrase for {}: ", path))?;
+    let keypair = crate::governance::GovernanceKeypair::from_encrypted_json(contents, &passphrase)
+        .map_err(|e| InputError::InvalidValue(format!("key file {}: {}", path, e)))?;
+    Ok(Zeroizing::new(keypair.secret_key_bytes().to_vec()))
+}
+
+#[cfg(not(feature = "signing"))]
+fn decrypt_secret_key(path: &str, _contents: &str) -> Result<Zeroizing<Vec<u8>>, InputError> {
+    Err(InputError::InvalidFormat(format!(
+        "key file {}: encrypted key files need the signing feature",
+        path
+    )))
+}
+
+/// Read a key passphrase from [`KEY_PASSPHRASE_ENV`], or prompt for it on
+/// the terminal without echoing it
+pub fn read_key_passphrase(prompt: &str) -> Result<Zeroizing<String>, InputError> {
+    if let Ok(passphrase) = std::env::var(KEY_PASSPHRASE_ENV) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+
+    use std::io::{BufRead, IsTerminal, Write};
+    if !std::io::stdin().is_terminal() {
+        return Err(InputError::InvalidValue(format!(
+            "a passphrase is needed: set {} or run from a terminal",
+            KEY_PASSPHRASE_ENV
+        )));
+    }
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+
+    let _echo = EchoOff::new();
+    let mut line = Zeroizing::new(String::new());
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(Zeroizing::new(
+        line.trim_end_matches(['\r', '\n']).to_string(),
+    ))
+}
+
+/// Disables terminal echo on stdin until dropped
+struct EchoOff {
+    #[cfg(unix)]
+    saved: Option<libc::termios>,
+}
+
+impl EchoOff {
+    #[cfg(unix)]
+    fn new() -> Self {
+        // SAFETY: termios is plain data, filled in by tcgetattr before use
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Self { saved: None };
+        }
+        let saved = termios;
+        termios.c_lflag &= !libc::ECHO;
+        termios.c_lflag |= libc::ECHONL;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return Self { saved: None };
+        }
+        Self { saved: Some(saved) }
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(saved) = &self.saved {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved) };
+        }
+    }
+}
+
+/// Load public keys from key files
+///
+/// Each file is a JSON object with a hex `public_key` field, as written by
+/// `bllvm-keygen`. A key listed more than once is loaded once, so it cannot
+/// inflate the committee.
+pub fn load_public_keys(paths: &[String]) -> Result<Vec<PublicKey>, InputError> {
+    let mut public_keys: Vec<PublicKey> = Vec::new();
+
+    for path in paths {
+        if !Path::new(path).exists() {
+            return Err(InputError::FileNotFound(format!(
+                "public key file {}",
+                path
+            )));
+        }
+        let invalid = |reason: &dyn std::fmt::Display| {
+            InputError::InvalidFormat(format!("public key file {}: {}", path, reason))
+        };
+
+        let key_json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path)?).map_err(|e| invalid(&e))?;
+        let pubkey_hex = key_json["public_key"]
+            .as_str()
+            .ok_or_else(|| invalid(&"no public_key field"))?;
+        let public_key = hex::decode(pubkey_hex)
+            .map_err(|e| invalid(&e))
+            .and_then(|bytes| PublicKey::from_bytes(&bytes).map_err(|e| invalid(&e)))?;
+
+        if !public_keys.contains(&public_key) {
+            public_keys.push(public_key);
+        }
+    }
+
+    Ok(public_keys)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,6 +300,24 @@ mod tests {
     use std::io::Write;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_parse_bip32_path() {
+        assert_eq!(
+            parse_bip32_path("m/44'/0'/0'/0/0").unwrap(),
+            vec![
+                ChildNumber::Hardened { index: 44 },
+                ChildNumber::Hardened { index: 0 },
+                ChildNumber::Hardened { index: 0 },
+                ChildNumber::Normal { index: 0 },
+                ChildNumber::Normal { index: 0 },
+            ]
+        );
+        assert!(matches!(
+            parse_bip32_path("m/44'/zero"),
+            Err(InputError::InvalidFormat(_))
+        ));
+    }
+
     #[test]
     fn test_parse_hex() {
         let result = parse_hex("deadbeef");
@@ -110,6 +331,14 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_sha256_hex() {
+        let hash = "AB".repeat(32);
+        assert_eq!(parse_sha256_hex(&hash).unwrap(), "ab".repeat(32));
+        assert!(parse_sha256_hex("abcd").is_err());
+        assert!(parse_sha256_hex(&"zz".repeat(32)).is_err());
+    }
+
     #[test]
     fn test_parse_base64() {
         let result = parse_base64("dGVzdA==");
@@ -157,4 +386,37 @@ mod tests {
         let result = parse_file_path("/nonexistent/file.txt");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_load_public_keys() {
+        let dir = tempdir().unwrap();
+        let key_file = |name: &str, contents: String| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let keys: Vec<PublicKey> = (0..2)
+            .map(|_| {
+                crate::governance::GovernanceKeypair::generate()
+                    .unwrap()
+                    .public_key()
+            })
+            .collect();
+        let alice = key_file("alice.json", format!(r#"{{"public_key": "{}"}}"#, keys[0]));
+        let bob = key_file("bob.json", format!(r#"{{"public_key": "{}"}}"#, keys[1]));
+
+        // The same key twice counts once
+        let loaded = load_public_keys(&[alice.clone(), bob, alice]).unwrap();
+        assert_eq!(loaded, keys);
+
+        let invalid = key_file("invalid.json", r#"{"public_key": "02zz"}"#.to_string());
+        assert!(matches!(
+            load_public_keys(&[invalid]),
+            Err(InputError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            load_public_keys(&["missing.json".to_string()]),
+            Err(InputError::FileNotFound(_))
+        ));
+    }
 }
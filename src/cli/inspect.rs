@@ -0,0 +1,453 @@
+//! # Signature File Inspection
+//!
+//! Structural comparison of signature files written by `blvm-sign-binary`
+//! and aggregates written by `blvm-aggregate-signatures`.
+//!
+//! Only signed content is compared. Timestamps and paths recorded alongside
+//! a signature (`signed_at`, `created_at`, `aggregated_at`, `file_path`) are
+//! ignored, so two files covering the same release compare as equivalent.
+//!
+//! Values of secret-named metadata fields are compared but shown as
+//! [`REDACTED`].
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::cli::input::InputError;
+use crate::governance::SigningTarget;
+use crate::util::redact::{is_secret_name, redact_json, REDACTED};
+
+/// Metadata fields that are not covered by any signature
+const UNSIGNED_FIELDS: [&str; 4] = ["signed_at", "created_at", "aggregated_at", "file_path"];
+
+/// Bytes shown per hexdump line
+const HEXDUMP_WIDTH: usize = 16;
+
+/// Normalized view of a single signature file or an aggregate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureFile {
+    /// Aggregate format version (`None` for single signature files)
+    pub format_version: Option<String>,
+    /// Hash of the signed target
+    pub target_hash: Option<String>,
+    /// Signatures keyed by signer identity
+    pub signers: BTreeMap<String, String>,
+    /// Signed metadata
+    pub metadata: BTreeMap<String, Value>,
+    /// Payload the signatures commit to, rebuilt from the metadata
+    pub canonical_payload: Option<Vec<u8>>,
+}
+
+impl SignatureFile {
+    /// Parse a signature file or aggregate from JSON
+    pub fn from_json(json: &str) -> Result<Self, InputError> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| InputError::InvalidFormat(format!("Invalid signature file: {}", e)))?;
+        Self::from_value(&value)
+    }
+
+    /// Build the normalized view from parsed JSON
+    pub fn from_value(value: &Value) -> Result<Self, InputError> {
+        let object = value.as_object().ok_or_else(|| {
+            InputError::InvalidFormat("Signature file must be a JSON object".to_string())
+        })?;
+
+        let metadata: BTreeMap<String, Value> = object
+            .get("metadata")
+            .and_then(Value::as_object)
+            .map(|m| {
+                m.iter()
+                    .filter(|(k, v)| !UNSIGNED_FIELDS.contains(&k.as_str()) && !v.is_null())
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let entries: Vec<&Value> = match object.get("signatures").and_then(Value::as_array) {
+            Some(signatures) => signatures.iter().collect(),
+            None if object.contains_key("signature") => vec![value],
+            None => {
+                return Err(InputError::InvalidFormat(
+                    "Signature file has neither 'signature' nor 'signatures'".to_string(),
+                ))
+            }
+        };
+
+        let mut signers = BTreeMap::new();
+        for entry in entries {
+            let signature = entry
+                .get("signature")
+                .and_then(Value::as_str)
+                .ok_or_else(|| {
+                    InputError::InvalidFormat("Signature entry is missing 'signature'".to_string())
+                })?;
+            signers.insert(signer_identity(entry, signature), signature.to_string());
+        }
+
+        let target_hash = object
+            .get("file_hash")
+            .or_else(|| metadata.get("file_hash"))
+            .or_else(|| metadata.get("digest"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        Ok(Self {
+            format_version: object
+                .get("version")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            target_hash,
+            canonical_payload: object
+                .get("metadata")
+                .and_then(SigningTarget::from_metadata)
+                .map(|target| target.to_signing_bytes()),
+            signers,
+            metadata,
+        })
+    }
+}
+
+/// Identify a signer by public key, falling back to the signer field and
+/// finally the signature itself
+fn signer_identity(entry: &Value, signature: &str) -> String {
+    ["public_key", "signer"]
+        .iter()
+        .filter_map(|field| entry.get(field).and_then(Value::as_str))
+        .find(|id| !id.is_empty() && *id != hex::encode("unknown"))
+        .unwrap_or(signature)
+        .to_string()
+}
+
+/// A value that differs between the two files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueDifference<T> {
+    pub a: Option<T>,
+    pub b: Option<T>,
+}
+
+/// A signed metadata field that differs between the two files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MetadataDifference {
+    pub field: String,
+    pub a: Option<Value>,
+    pub b: Option<Value>,
+}
+
+/// First divergence between the canonical payloads
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PayloadDifference {
+    /// Byte offset of the first differing byte
+    pub offset: usize,
+    /// Hexdump line of `a` containing the divergence
+    pub a: String,
+    /// Hexdump line of `b` containing the divergence
+    pub b: String,
+}
+
+/// Structural differences between two signature files
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct SignatureFileDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_hash: Option<ValueDifference<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signers_only_in_a: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signers_only_in_b: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub metadata: Vec<MetadataDifference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<PayloadDifference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_version: Option<ValueDifference<String>>,
+}
+
+impl SignatureFileDiff {
+    /// Whether the two files carry the same signed content
+    pub fn is_equivalent(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Machine-readable diff
+    pub fn to_json(&self) -> Value {
+        let mut json = serde_json::to_value(self).unwrap_or_default();
+        json["equivalent"] = Value::Bool(self.is_equivalent());
+        json
+    }
+}
+
+impl fmt::Display for SignatureFileDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_equivalent() {
+            return write!(f, "equivalent");
+        }
+
+        let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "(none)".to_string());
+        writeln!(f, "different")?;
+        if let Some(hash) = &self.target_hash {
+            writeln!(f, "Target hash: {} vs {}", show(&hash.a), show(&hash.b))?;
+        }
+        for signer in &self.signers_only_in_a {
+            writeln!(f, "Signer only in a: {}", signer)?;
+        }
+        for signer in &self.signers_only_in_b {
+            writeln!(f, "Signer only in b: {}", signer)?;
+        }
+        for field in &self.metadata {
+            let show = |v: &Option<Value>| {
+                v.as_ref()
+                    .map(Value::to_string)
+                    .unwrap_or_else(|| "(none)".to_string())
+            };
+            writeln!(
+                f,
+                "Metadata '{}': {} vs {}",
+                field.field,
+                show(&field.a),
+                show(&field.b)
+            )?;
+        }
+        if let Some(payload) = &self.payload {
+            writeln!(f, "Payload differs at byte {}:", payload.offset)?;
+            writeln!(f, "  a: {}", payload.a)?;
+            writeln!(f, "  b: {}", payload.b)?;
+        }
+        if let Some(version) = &self.format_version {
+            writeln!(
+                f,
+                "Format version: {} vs {}",
+                show(&version.a),
+                show(&version.b)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two signature files structurally
+pub fn diff_signature_files(a: &SignatureFile, b: &SignatureFile) -> SignatureFileDiff {
+    let differ = |a: &Option<String>, b: &Option<String>| {
+        (a != b).then(|| ValueDifference {
+            a: a.clone(),
+            b: b.clone(),
+        })
+    };
+
+    let mut fields: Vec<&String> = a.metadata.keys().chain(b.metadata.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    let metadata = fields
+        .into_iter()
+        .filter(|field| a.metadata.get(*field) != b.metadata.get(*field))
+        .map(|field| {
+            let show = |value: Option<&Value>| {
+                value.map(|value| {
+                    if is_secret_name(field) {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_json(value)
+                    }
+                })
+            };
+            MetadataDifference {
+                field: field.clone(),
+                a: show(a.metadata.get(field)),
+                b: show(b.metadata.get(field)),
+            }
+        })
+        .collect();
+
+    let payload = match (&a.canonical_payload, &b.canonical_payload) {
+        (Some(pa), Some(pb)) => first_divergence(pa, pb),
+        _ => None,
+    };
+
+    SignatureFileDiff {
+        target_hash: differ(&a.target_hash, &b.target_hash),
+        signers_only_in_a: a
+            .signers
+            .keys()
+            .filter(|s| !b.signers.contains_key(*s))
+            .cloned()
+            .collect(),
+        signers_only_in_b: b
+            .signers
+            .keys()
+            .filter(|s| !a.signers.contains_key(*s))
+            .cloned()
+            .collect(),
+        metadata,
+        payload,
+        format_version: differ(&a.format_version, &b.format_version),
+    }
+}
+
+fn first_divergence(a: &[u8], b: &[u8]) -> Option<PayloadDifference> {
+    let offset = a
+        .iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))?;
+
+    let line_start = offset - offset % HEXDUMP_WIDTH;
+    Some(PayloadDifference {
+        offset,
+        a: hexdump_line(a, line_start),
+        b: hexdump_line(b, line_start),
+    })
+}
+
+/// Format one `offset  hex bytes  |ascii|` hexdump line
+fn hexdump_line(data: &[u8], start: usize) -> String {
+    let end = (start + HEXDUMP_WIDTH).min(data.len());
+    let bytes = data.get(start..end).unwrap_or_default();
+
+    let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let ascii: String = bytes
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+
+    format!(
+        "{:08x}  {:<width$}  |{}|",
+        start,
+        hex.join(" "),
+        ascii,
+        width = HEXDUMP_WIDTH * 3 - 1
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const HASH: &str = "4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945";
+
+    fn aggregate(signers: &[&str], version: &str, aggregated_at: &str) -> Value {
+        json!({
+            "version": "1.0",
+            "signature_count": signers.len(),
+            "signatures": signers.iter().map(|s| json!({
+                "signature": format!("sig-{}", s),
+                "public_key": s,
+                "signed_at": "2024-01-15T10:30:00Z",
+            })).collect::<Vec<_>>(),
+            "threshold": "2-of-3",
+            "metadata": {
+                "type": "binary",
+                "binary_type": "application",
+                "file_path": "target/release/blvm-node",
+                "file_hash": HASH,
+                "version": version,
+                "signed_at": "2024-01-15T10:30:00Z",
+            },
+            "aggregated_at": aggregated_at,
+        })
+    }
+
+    fn diff(a: &Value, b: &Value) -> SignatureFileDiff {
+        diff_signature_files(
+            &SignatureFile::from_value(a).unwrap(),
+            &SignatureFile::from_value(b).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_equivalent_despite_unsigned_metadata() {
+        let a = aggregate(&["alice", "bob"], "0.1.0", "2024-01-15T10:30:00Z");
+        let mut b = aggregate(&["bob", "alice"], "0.1.0", "2024-02-01T08:00:00Z");
+        b["metadata"]["file_path"] = json!("/tmp/blvm-node");
+
+        let diff = diff(&a, &b);
+        assert!(diff.is_equivalent());
+        assert_eq!(diff.to_string(), "equivalent");
+        assert_eq!(diff.to_json(), json!({ "equivalent": true }));
+    }
+
+    #[test]
+    fn test_target_hash_differs() {
+        let a = aggregate(&["alice"], "0.1.0", "");
+        let mut b = a.clone();
+        b["metadata"]["file_hash"] = json!("00".repeat(32));
+
+        let diff = diff(&a, &b);
+        assert_eq!(diff.target_hash.unwrap().b, Some("00".repeat(32)));
+    }
+
+    #[test]
+    fn test_signer_sets_differ() {
+        let a = aggregate(&["alice", "bob"], "0.1.0", "");
+        let b = aggregate(&["bob", "carol"], "0.1.0", "");
+
+        let diff = diff(&a, &b);
+        assert_eq!(diff.signers_only_in_a, vec!["alice"]);
+        assert_eq!(diff.signers_only_in_b, vec!["carol"]);
+        assert!(diff.metadata.is_empty());
+        assert!(diff.payload.is_none());
+    }
+
+    #[test]
+    fn test_signed_metadata_and_payload_differ() {
+        let a = aggregate(&["alice"], "0.1.0", "");
+        let b = aggregate(&["alice"], "0.1.1", "");
+
+        let diff = diff(&a, &b);
+        assert_eq!(diff.metadata.len(), 1);
+        assert_eq!(diff.metadata[0].field, "version");
+
+        // "binary:application:<64 hex>:0.1.0" diverges in the last character
+        let payload = diff.payload.unwrap();
+        assert_eq!(payload.offset, "binary:application:".len() + 64 + 5);
+        assert!(payload.a.ends_with(":0.1.0|"));
+        assert!(payload.b.ends_with(":0.1.1|"));
+        assert!(payload
+            .a
+            .starts_with(&format!("{:08x}", payload.offset - payload.offset % 16)));
+    }
+
+    #[test]
+    fn test_format_version_differs() {
+        let a = aggregate(&["alice"], "0.1.0", "");
+        let mut b = a.clone();
+        b["version"] = json!("2.0");
+
+        let diff = diff(&a, &b);
+        assert_eq!(
+            diff.format_version,
+            Some(ValueDifference {
+                a: Some("1.0".to_string()),
+                b: Some("2.0".to_string())
+            })
+        );
+        assert!(diff.to_string().contains("Format version: 1.0 vs 2.0"));
+    }
+
+    #[test]
+    fn test_single_signature_file() {
+        let single = json!({
+            "signature": "sig-alice",
+            "signer": hex::encode("unknown"),
+            "file_hash": HASH,
+            "metadata": { "type": "checksums", "file_hash": HASH, "version": "0.1.0" },
+            "created_at": "2024-01-15T10:30:00Z",
+        });
+
+        let file = SignatureFile::from_value(&single).unwrap();
+        assert_eq!(file.format_version, None);
+        assert_eq!(file.signers.keys().collect::<Vec<_>>(), vec!["sig-alice"]);
+        assert_eq!(
+            file.canonical_payload.unwrap(),
+            format!("checksums:{}:0.1.0", HASH).into_bytes()
+        );
+
+        assert!(SignatureFile::from_value(&json!({ "metadata": {} })).is_err());
+    }
+}
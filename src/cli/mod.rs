@@ -3,4 +3,8 @@
 //! Shared utilities for command-line tools.
 
 pub mod input;
+pub mod inspect;
+pub mod notify;
 pub mod output;
+
+pub use crate::util::humanize;
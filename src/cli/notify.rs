@@ -0,0 +1,47 @@
+//! # CLI Notifications
+//!
+//! `--notify-url` handling shared by the verify and aggregate tools.
+
+use serde::Serialize;
+
+/// Post `report` to every `--notify-url`, warning on stderr about each
+/// delivery that failed
+///
+/// Notification never changes a tool's exit code: failures, including an
+/// unreadable `--notify-secret-file`, are warnings only.
+#[cfg(feature = "net")]
+pub fn notify_report<T: Serialize>(urls: &[String], secret_file: Option<&str>, report: &T) {
+    use crate::governance::notify::{load_secret, send_report, NotifyTarget};
+
+    if urls.is_empty() {
+        return;
+    }
+    let secret = match secret_file.map(|path| load_secret(std::path::Path::new(path))) {
+        Some(Ok(secret)) => Some(secret),
+        Some(Err(e)) => {
+            eprintln!("Warning: notifications not sent: {}", e);
+            return;
+        }
+        None => None,
+    };
+    let targets: Vec<NotifyTarget> = urls
+        .iter()
+        .map(|url| NotifyTarget {
+            url: url.clone(),
+            secret: secret.clone(),
+        })
+        .collect();
+    for delivery in send_report(report, &targets) {
+        if !delivery.is_delivered() {
+            eprintln!("Warning: {}", delivery);
+        }
+    }
+}
+
+/// Without the `net` feature, warn that no notification was sent
+#[cfg(not(feature = "net"))]
+pub fn notify_report<T: Serialize>(urls: &[String], _secret_file: Option<&str>, _report: &T) {
+    if !urls.is_empty() {
+        eprintln!("Warning: notifications not sent (--notify-url needs the net feature)");
+    }
+}
@@ -4,6 +4,9 @@
 
 use serde::Serialize;
 use std::fmt;
+use std::io::{self, Write};
+
+use crate::governance::error::GovernanceError;
 
 /// Output format options
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,6 +15,8 @@ pub enum OutputFormat {
     Text,
     /// JSON output
     Json,
+    /// JSON Lines (NDJSON) output: one compact JSON object per line
+    JsonLines,
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -21,6 +26,7 @@ impl std::str::FromStr for OutputFormat {
         match s.to_lowercase().as_str() {
             "text" | "txt" => Ok(OutputFormat::Text),
             "json" => Ok(OutputFormat::Json),
+            "jsonl" | "ndjson" | "json-lines" => Ok(OutputFormat::JsonLines),
             _ => Err(format!("Invalid output format: {}", s)),
         }
     }
@@ -46,22 +52,70 @@ impl OutputFormatter {
             OutputFormat::Text => Ok(value.to_string()),
             OutputFormat::Json => serde_json::to_string_pretty(value)
                 .map_err(|e| format!("JSON serialization error: {}", e)),
+            OutputFormat::JsonLines => {
+                serde_json::to_string(value).map_err(|e| format!("JSON serialization error: {}", e))
+            }
         }
     }
 
-    /// Format an error for output
-    pub fn format_error(&self, error: &dyn std::error::Error) -> String {
+    /// Write a stream of values as they arrive
+    ///
+    /// Text writes each value's `Display` on its own line, JSON Lines one
+    /// compact JSON object per line, and JSON a single array whose elements
+    /// are written one at a time. Nothing is buffered beyond one value.
+    pub fn format_each<T, W>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        out: &mut W,
+    ) -> io::Result<()>
+    where
+        T: Serialize + fmt::Display,
+        W: Write,
+    {
         match self.format {
-            OutputFormat::Text => format!("Error: {}", error),
+            OutputFormat::Text => {
+                for item in items {
+                    writeln!(out, "{}", item)?;
+                }
+            }
+            OutputFormat::JsonLines => {
+                for item in items {
+                    serde_json::to_writer(&mut *out, &item)?;
+                    writeln!(out)?;
+                }
+            }
             OutputFormat::Json => {
-                let error_json = serde_json::json!({
-                    "error": true,
-                    "message": error.to_string()
-                });
-                serde_json::to_string_pretty(&error_json)
-                    .unwrap_or_else(|_| format!("{{\"error\": true, \"message\": \"{}\"}}", error))
+                write!(out, "[")?;
+                for (i, item) in items.into_iter().enumerate() {
+                    writeln!(out, "{}", if i == 0 { "" } else { "," })?;
+                    serde_json::to_writer_pretty(&mut *out, &item)?;
+                }
+                writeln!(out, "\n]")?;
             }
         }
+        out.flush()
+    }
+
+    /// Format an error for output
+    pub fn format_error(&self, error: &(dyn std::error::Error + 'static)) -> String {
+        match self.format {
+            OutputFormat::Text => format!("Error: {}", error),
+            OutputFormat::Json => serde_json::to_string_pretty(&Self::error_json(error))
+                .unwrap_or_else(|_| format!("{{\"error\": true, \"message\": \"{}\"}}", error)),
+            OutputFormat::JsonLines => Self::error_json(error).to_string(),
+        }
+    }
+
+    /// JSON error object, with the stable `code` of governance errors
+    fn error_json(error: &(dyn std::error::Error + 'static)) -> serde_json::Value {
+        let mut error_json = serde_json::json!({
+            "error": true,
+            "message": error.to_string()
+        });
+        if let Some(governance_error) = error.downcast_ref::<GovernanceError>() {
+            error_json["code"] = governance_error.code().into();
+        }
+        error_json
     }
 
     /// Format a success message
@@ -77,6 +131,11 @@ impl OutputFormatter {
                     format!("{{\"success\": true, \"message\": \"{}\"}}", message)
                 })
             }
+            OutputFormat::JsonLines => serde_json::json!({
+                "success": true,
+                "message": message
+            })
+            .to_string(),
         }
     }
 }
@@ -90,6 +149,14 @@ mod tests {
         assert_eq!("text".parse::<OutputFormat>().unwrap(), OutputFormat::Text);
         assert_eq!("txt".parse::<OutputFormat>().unwrap(), OutputFormat::Text);
         assert_eq!("json".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+        assert_eq!(
+            "jsonl".parse::<OutputFormat>().unwrap(),
+            OutputFormat::JsonLines
+        );
+        assert_eq!(
+            "ndjson".parse::<OutputFormat>().unwrap(),
+            OutputFormat::JsonLines
+        );
         assert!("invalid".parse::<OutputFormat>().is_err());
     }
 
@@ -106,4 +173,86 @@ mod tests {
         let result = formatter.format(&serde_json::json!({"message": "test"}));
         assert!(result.unwrap().contains("test"));
     }
+
+    #[test]
+    fn test_json_error_code() {
+        let formatter = OutputFormatter::new(OutputFormat::Json);
+        let error = GovernanceError::InsufficientSignatures { got: 1, need: 3 };
+        let value: serde_json::Value =
+            serde_json::from_str(&formatter.format_error(&error)).unwrap();
+        assert_eq!(value["code"], "INSUFFICIENT_SIGNATURES");
+        assert_eq!(value["message"], "Insufficient signatures: got 1, need 3");
+
+        let io_error = std::io::Error::other("disk full");
+        let value: serde_json::Value =
+            serde_json::from_str(&formatter.format_error(&io_error)).unwrap();
+        assert!(value.get("code").is_none());
+    }
+
+    /// Item with distinct text and JSON renderings
+    #[derive(Serialize)]
+    struct Item {
+        file: String,
+        valid: bool,
+    }
+
+    impl fmt::Display for Item {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "{}: {}",
+                self.file,
+                if self.valid { "ok" } else { "FAILED" }
+            )
+        }
+    }
+
+    fn items() -> impl Iterator<Item = Item> {
+        (1..=3).map(|i| Item {
+            file: format!("file{}", i),
+            valid: i != 2,
+        })
+    }
+
+    fn format_each(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        OutputFormatter::new(format)
+            .format_each(items(), &mut out)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json_lines_format_each() {
+        let output = format_each(OutputFormat::JsonLines);
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for (i, line) in lines.iter().enumerate() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["file"], format!("file{}", i + 1));
+        }
+    }
+
+    #[test]
+    fn test_format_each_json_and_text() {
+        let value: serde_json::Value =
+            serde_json::from_str(&format_each(OutputFormat::Json)).unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 3);
+        assert_eq!(value[1]["valid"], false);
+
+        let empty: serde_json::Value = {
+            let mut out = Vec::new();
+            OutputFormatter::new(OutputFormat::Json)
+                .format_each(std::iter::empty::<Item>(), &mut out)
+                .unwrap();
+            serde_json::from_slice(&out).unwrap()
+        };
+        assert_eq!(empty, serde_json::json!([]));
+
+        assert_eq!(
+            format_each(OutputFormat::Text),
+            "file1: ok\nfile2: FAILED\nfile3: ok\n"
+        );
+    }
 }
@@ -0,0 +1,114 @@
+//! Module Approvals
+//!
+//! Committee-signed approvals binding a module version to its binary hash.
+//!
+//! An approval signs a `GovernanceMessage::ModuleBinaryApproval`
+//! (`MODULE:<name>:<version>:<binary_hash>`), which `blvm-sign module
+//! --binary-hash` produces.
+
+use crate::composition::types::*;
+use crate::governance::{GovernanceMessage, Multisig, Signature};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A signed approval for one module binary
+#[derive(Debug, Clone)]
+pub struct ModuleApproval {
+    /// Module name
+    pub module_name: String,
+    /// Approved module version
+    pub version: String,
+    /// Hex-encoded SHA256 of the approved binary
+    pub binary_hash: String,
+    /// Committee signatures over the approval
+    pub signatures: Vec<Signature>,
+}
+
+impl ModuleApproval {
+    /// The governance message this approval signs
+    pub fn message(&self) -> GovernanceMessage {
+        GovernanceMessage::ModuleBinaryApproval {
+            module_name: self.module_name.clone(),
+            version: self.version.clone(),
+            binary_hash: self.binary_hash.clone(),
+        }
+    }
+
+    /// Bytes signed by committee members for a module binary
+    pub fn signing_bytes(module_name: &str, version: &str, binary_hash: &str) -> Vec<u8> {
+        GovernanceMessage::ModuleBinaryApproval {
+            module_name: module_name.to_string(),
+            version: version.to_string(),
+            binary_hash: binary_hash.to_string(),
+        }
+        .to_signing_bytes()
+    }
+}
+
+/// Set of module approvals, keyed by module name
+#[derive(Debug, Clone, Default)]
+pub struct ModuleApprovals {
+    approvals: HashMap<String, Vec<ModuleApproval>>,
+}
+
+impl ModuleApprovals {
+    /// Create an empty approval set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an approval
+    pub fn add(&mut self, approval: ModuleApproval) {
+        self.approvals
+            .entry(approval.module_name.clone())
+            .or_default()
+            .push(approval);
+    }
+
+    /// Find the approval for a module version
+    pub fn get(&self, module_name: &str, version: &str) -> Option<&ModuleApproval> {
+        self.approvals
+            .get(module_name)
+            .and_then(|approvals| approvals.iter().find(|a| a.version == version))
+    }
+
+    /// Verify a module binary has threshold approval
+    pub fn verify(&self, info: &ModuleInfo, multisig: &Multisig) -> Result<()> {
+        let rejected = |reason: String| {
+            CompositionError::GovernanceRejected(format!(
+                "Module {} version {} lacks threshold governance approval: {}",
+                info.name, info.version, reason
+            ))
+        };
+
+        let approval = self
+            .get(&info.name, &info.version)
+            .ok_or_else(|| rejected("no approval found".to_string()))?;
+
+        let binary_path = info
+            .binary_path
+            .as_ref()
+            .ok_or_else(|| rejected("module has no binary path".to_string()))?;
+        let binary_hash = hash_file(binary_path)?;
+        if !binary_hash.eq_ignore_ascii_case(&approval.binary_hash) {
+            return Err(rejected(format!(
+                "binary hash {} does not match approved hash {}",
+                binary_hash, approval.binary_hash
+            )));
+        }
+
+        let message = ModuleApproval::signing_bytes(&info.name, &info.version, &binary_hash);
+        match multisig.verify(&message, &approval.signatures) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(rejected("signature threshold not met".to_string())),
+            Err(e) => Err(rejected(e.to_string())),
+        }
+    }
+}
+
+/// Compute the hex-encoded SHA256 of a file
+pub fn hash_file(path: &Path) -> Result<String> {
+    let data = std::fs::read(path)?;
+    Ok(hex::encode(Sha256::digest(&data)))
+}
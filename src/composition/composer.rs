@@ -2,18 +2,24 @@
 //!
 //! High-level API for composing Bitcoin nodes from modules.
 
+use crate::composition::approval::ModuleApprovals;
 use crate::composition::config::NodeConfig;
+use crate::composition::features::{FeatureSelection, ResolvedComposition};
 use crate::composition::lifecycle::ModuleLifecycle;
 use crate::composition::registry::ModuleRegistry;
 use crate::composition::schema::validate_config_schema;
 use crate::composition::types::*;
 use crate::composition::validation::validate_composition;
+use crate::governance::Multisig;
+use crate::util::CancelToken;
 use std::path::Path;
 
 /// Node composer for building nodes from modules
 pub struct NodeComposer {
     /// Module lifecycle manager (owns the registry)
     lifecycle: ModuleLifecycle,
+    /// Approvals and multisig required before a module may start
+    module_verification: Option<(ModuleApprovals, Multisig)>,
 }
 
 impl NodeComposer {
@@ -22,13 +28,52 @@ impl NodeComposer {
         let registry = ModuleRegistry::new(modules_dir);
         let lifecycle = ModuleLifecycle::new(registry);
 
-        Self { lifecycle }
+        Self {
+            lifecycle,
+            module_verification: None,
+        }
+    }
+
+    /// Require threshold governance approval of each module binary before it starts
+    pub fn with_module_verification(
+        mut self,
+        approvals: ModuleApprovals,
+        multisig: Multisig,
+    ) -> Self {
+        self.module_verification = Some((approvals, multisig));
+        self
     }
 
     /// Compose node from configuration file
     pub async fn compose_from_config<P: AsRef<Path>>(
         &mut self,
         config_path: P,
+    ) -> Result<ComposedNode> {
+        self.compose_from_config_with_cancel(config_path, &CancelToken::new())
+            .await
+    }
+
+    /// Compose node from configuration file unless `cancel` is tripped
+    ///
+    /// See [`compose_node_with_cancel`](Self::compose_node_with_cancel).
+    pub async fn compose_from_config_with_cancel<P: AsRef<Path>>(
+        &mut self,
+        config_path: P,
+        cancel: &CancelToken,
+    ) -> Result<ComposedNode> {
+        self.compose_from_config_with_features(config_path, &FeatureSelection::default(), cancel)
+            .await
+    }
+
+    /// Compose node from configuration file with the selected features
+    ///
+    /// Fails before starting anything if an included module depends on a
+    /// module left out by an inactive feature.
+    pub async fn compose_from_config_with_features<P: AsRef<Path>>(
+        &mut self,
+        config_path: P,
+        features: &FeatureSelection,
+        cancel: &CancelToken,
     ) -> Result<ComposedNode> {
         // Load configuration
         let config = NodeConfig::from_file(config_path)?;
@@ -42,15 +87,49 @@ impl NodeComposer {
             )));
         }
 
-        // Convert to spec
-        let spec = config.to_spec()?;
+        // Resolve features to a spec
+        let resolved = config.resolve(features)?;
+        resolved.check_dependencies(self.registry())?;
 
-        // Compose from spec
-        self.compose_node(spec).await
+        self.compose_resolved(resolved, cancel).await
     }
 
     /// Compose node from specification
     pub async fn compose_node(&mut self, spec: NodeSpec) -> Result<ComposedNode> {
+        self.compose_node_with_cancel(spec, &CancelToken::new())
+            .await
+    }
+
+    /// Compose node from specification unless `cancel` is tripped
+    ///
+    /// Cancellation points: before each module is started, and inside each
+    /// module start (see [`ModuleLifecycle::start_module_with_cancel`]). On
+    /// cancellation the modules started by this call are stopped again, in
+    /// reverse order, and `Cancelled` is returned; nothing it started is left
+    /// running. The registry is not modified.
+    pub async fn compose_node_with_cancel(
+        &mut self,
+        spec: NodeSpec,
+        cancel: &CancelToken,
+    ) -> Result<ComposedNode> {
+        // No features are active for a bare spec
+        let resolved = ResolvedComposition {
+            spec,
+            features: Default::default(),
+            excluded: Default::default(),
+        };
+        self.compose_resolved(resolved, cancel).await
+    }
+
+    /// Compose a resolved configuration, recording its composition hash
+    async fn compose_resolved(
+        &mut self,
+        resolved: ResolvedComposition,
+        cancel: &CancelToken,
+    ) -> Result<ComposedNode> {
+        let composition_hash = resolved.composition_hash()?;
+        let spec = resolved.spec;
+
         // Validate composition
         let validation = self.validate_composition(&spec)?;
         if !validation.valid {
@@ -67,27 +146,82 @@ impl NodeComposer {
                 continue;
             }
 
-            let info = self
-                .lifecycle
-                .registry
-                .get_module(&module_spec.name, module_spec.version.as_deref())?;
-
-            // Start module via lifecycle (now async)
-            self.lifecycle_mut().start_module(&info.name).await?;
-            let status = self.lifecycle().get_module_status(&info.name).await?;
-            let health = self.lifecycle().health_check(&info.name).await?;
-
-            loaded_modules.push(LoadedModule {
-                info,
-                status,
-                health,
-            });
+            let loaded = if cancel.is_cancelled() {
+                Err(CompositionError::Cancelled(format!(
+                    "module {} was not started",
+                    module_spec.name
+                )))
+            } else {
+                self.load_module(module_spec, cancel).await
+            };
+            match loaded {
+                Ok(module) => loaded_modules.push(module),
+                Err(e @ CompositionError::Cancelled(_)) => {
+                    self.stop_loaded(&loaded_modules).await?;
+                    return Err(e);
+                }
+                Err(e) => return Err(e),
+            }
         }
 
         Ok(ComposedNode {
             spec,
             modules: loaded_modules,
             status: NodeStatus::Running,
+            composition_hash,
+        })
+    }
+
+    /// Stop modules started by an unfinished composition, newest first
+    ///
+    /// Every module is attempted; the first failure is returned.
+    async fn stop_loaded(&mut self, loaded: &[LoadedModule]) -> Result<()> {
+        let mut result = Ok(());
+        for module in loaded.iter().rev() {
+            if let Err(e) = self.lifecycle.stop_module(&module.info.name).await {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+
+    /// Verify and start a single module
+    async fn load_module(
+        &mut self,
+        module_spec: &ModuleSpec,
+        cancel: &CancelToken,
+    ) -> Result<LoadedModule> {
+        let info = self
+            .lifecycle
+            .registry
+            .get_module(&module_spec.name, module_spec.version.as_deref())?;
+
+        if let Some((approvals, multisig)) = &self.module_verification {
+            approvals.verify(&info, multisig)?;
+        }
+
+        self.lifecycle_mut()
+            .set_external_dependencies(&info.name, module_spec.external_dependencies.clone());
+        self.lifecycle_mut()
+            .set_sandbox_config(&info.name, module_spec.sandbox.clone());
+        self.lifecycle_mut()
+            .set_service_config(&info.name, module_spec.service.clone());
+        self.lifecycle_mut()
+            .set_data_config(&info.name, module_spec.data.clone());
+
+        // Start module via lifecycle (now async)
+        self.lifecycle_mut()
+            .start_module_with_cancel(&info.name, cancel)
+            .await?;
+        let status = self.lifecycle().get_module_status(&info.name).await?;
+        let health = self.lifecycle().health_check(&info.name).await?;
+        let data_dir = self.lifecycle().data_dir_status(&info.name).cloned();
+
+        Ok(LoadedModule {
+            info,
+            status,
+            health,
+            data_dir,
         })
     }
 
@@ -123,3 +257,274 @@ impl NodeComposer {
         &mut self.lifecycle
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::composition::approval::ModuleApproval;
+    use crate::composition::datadir::DataDirConfig;
+    use crate::composition::lifecycle::LifecycleEvent;
+    use crate::composition::sandbox::SandboxConfig;
+    use crate::composition::service::ServiceConfig;
+    use crate::governance::GovernanceKeypair;
+    use std::collections::HashMap;
+
+    fn composer_with_module(dir: &Path) -> (NodeComposer, ModuleInfo) {
+        let binary_path = dir.join("lightning");
+        std::fs::write(&binary_path, b"#!/bin/sh\nexit 0\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&binary_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let info = ModuleInfo {
+            name: "lightning".to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            author: None,
+            capabilities: Vec::new(),
+            dependencies: HashMap::new(),
+            entry_point: "lightning".to_string(),
+            directory: Some(dir.to_path_buf()),
+            binary_path: Some(binary_path),
+            config_schema: HashMap::new(),
+        };
+
+        let mut composer = NodeComposer::new(dir);
+        composer.registry_mut().register_module(info.clone());
+        (composer, info)
+    }
+
+    fn module_spec() -> ModuleSpec {
+        ModuleSpec {
+            name: "lightning".to_string(),
+            version: Some("0.1.0".to_string()),
+            enabled: true,
+            config: HashMap::new(),
+            external_dependencies: Vec::new(),
+            sandbox: SandboxConfig::default(),
+            service: ServiceConfig::default(),
+            data: DataDirConfig::default(),
+        }
+    }
+
+    fn approval(keypair: &GovernanceKeypair, binary_hash: &str) -> ModuleApproval {
+        let message = ModuleApproval::signing_bytes("lightning", "0.1.0", binary_hash);
+        ModuleApproval {
+            module_name: "lightning".to_string(),
+            version: "0.1.0".to_string(),
+            binary_hash: binary_hash.to_string(),
+            signatures: vec![crate::sign_message(keypair.secret_key.expose(), &message).unwrap()],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approved_module_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let (composer, info) = composer_with_module(dir.path());
+
+        let binary_hash =
+            crate::composition::approval::hash_file(info.binary_path.as_ref().unwrap()).unwrap();
+        let mut approvals = ModuleApprovals::new();
+        approvals.add(approval(&keypair, &binary_hash));
+
+        let mut composer = composer.with_module_verification(approvals, multisig);
+        let loaded = composer
+            .load_module(&module_spec(), &CancelToken::new())
+            .await
+            .unwrap();
+        assert_eq!(loaded.status, ModuleStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_unapproved_module_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let (composer, _info) = composer_with_module(dir.path());
+
+        // Approval for a different binary
+        let mut approvals = ModuleApprovals::new();
+        approvals.add(approval(&keypair, &"00".repeat(32)));
+
+        let mut composer = composer.with_module_verification(approvals, multisig);
+        let err = composer
+            .load_module(&module_spec(), &CancelToken::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CompositionError::GovernanceRejected(_)));
+        assert!(err
+            .to_string()
+            .contains("Module lightning version 0.1.0 lacks threshold governance approval"));
+        assert_eq!(
+            composer
+                .lifecycle()
+                .get_module_status("lightning")
+                .await
+                .unwrap(),
+            ModuleStatus::NotInstalled
+        );
+    }
+
+    #[tokio::test]
+    async fn test_module_without_approval_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let (composer, _info) = composer_with_module(dir.path());
+
+        let mut composer = composer.with_module_verification(ModuleApprovals::new(), multisig);
+        let err = composer
+            .load_module(&module_spec(), &CancelToken::new())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no approval found"));
+    }
+
+    #[tokio::test]
+    async fn test_composed_node_records_composition_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut composer, _info) = composer_with_module(dir.path());
+        let config_path = dir.path().join("node.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+[node]
+name = "featured"
+network = "regtest"
+
+[features.lightning]
+modules = ["lightning"]
+
+[modules.lightning]
+version = "0.1.0"
+"#,
+        )
+        .unwrap();
+        let selection = FeatureSelection {
+            default_features: true,
+            features: vec!["lightning".to_string()],
+        };
+
+        let composed = composer
+            .compose_from_config_with_features(&config_path, &selection, &CancelToken::new())
+            .await
+            .unwrap();
+        let resolved = NodeConfig::from_file(&config_path)
+            .unwrap()
+            .resolve(&selection)
+            .unwrap();
+        assert_eq!(composed.spec.modules.len(), 1);
+        assert_eq!(
+            composed.composition_hash,
+            resolved.composition_hash().unwrap()
+        );
+    }
+
+    fn module(name: &str) -> ModuleInfo {
+        ModuleInfo {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            author: None,
+            capabilities: Vec::new(),
+            dependencies: HashMap::new(),
+            entry_point: name.to_string(),
+            directory: None,
+            binary_path: None,
+            config_schema: HashMap::new(),
+        }
+    }
+
+    /// Three modules; the last waits on an external dependency that never
+    /// becomes available
+    fn staged_composition(dir: &Path) -> (NodeComposer, NodeSpec) {
+        let mut composer = NodeComposer::new(dir);
+        let mut modules = Vec::new();
+        for name in ["wallet", "indexer", "lightning"] {
+            composer.registry_mut().register_module(module(name));
+            modules.push(ModuleSpec {
+                name: name.to_string(),
+                ..module_spec()
+            });
+        }
+        modules[2].external_dependencies = vec![crate::composition::probe::ExternalDependency {
+            name: "bitcoind-rpc".to_string(),
+            probe: crate::composition::probe::Probe::Command {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "exit 1".to_string()],
+                exit_code: 0,
+            },
+            timeout: "1s".to_string(),
+            retry_interval: "1m".to_string(),
+            deadline: "10m".to_string(),
+        }];
+
+        let spec = NodeSpec {
+            name: "staged".to_string(),
+            version: None,
+            network: NetworkType::Regtest,
+            modules,
+        };
+        (composer, spec)
+    }
+
+    async fn status(composer: &NodeComposer, name: &str) -> ModuleStatus {
+        composer.lifecycle().get_module_status(name).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_mid_compose_stops_started_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut composer, spec) = staged_composition(dir.path());
+        let modules_before = composer.registry().list_modules().len();
+
+        // Trip the token once the third module starts waiting
+        let cancel = CancelToken::new();
+        let mut events = composer.lifecycle().subscribe();
+        let trip = cancel.clone();
+        tokio::spawn(async move {
+            if let Ok(LifecycleEvent::Waiting { .. }) = events.recv().await {
+                trip.cancel();
+            }
+        });
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            composer.compose_node_with_cancel(spec, &cancel),
+        )
+        .await
+        .expect("cancellation should end the composition");
+
+        assert!(matches!(result, Err(CompositionError::Cancelled(_))));
+        assert_eq!(status(&composer, "wallet").await, ModuleStatus::Stopped);
+        assert_eq!(status(&composer, "indexer").await, ModuleStatus::Stopped);
+        assert_eq!(
+            status(&composer, "lightning").await,
+            ModuleStatus::NotInstalled
+        );
+        assert_eq!(composer.registry().list_modules().len(), modules_before);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_before_compose_starts_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut composer, spec) = staged_composition(dir.path());
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        let err = composer
+            .compose_node_with_cancel(spec, &cancel)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("module wallet was not started"));
+        assert_eq!(
+            status(&composer, "wallet").await,
+            ModuleStatus::NotInstalled
+        );
+    }
+}
@@ -2,10 +2,15 @@
 //!
 //! TOML-based declarative configuration format for node composition.
 
+use crate::composition::datadir::DataDirConfig;
+use crate::composition::features::{self, FeatureConfig, FeatureSelection, ResolvedComposition};
+use crate::composition::probe::ExternalDependency;
+use crate::composition::sandbox::SandboxConfig;
+use crate::composition::service::ServiceConfig;
 use crate::composition::types::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 /// Node configuration from TOML file
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +21,9 @@ pub struct NodeConfig {
     /// Module configurations
     #[serde(default)]
     pub modules: HashMap<String, ModuleConfig>,
+    /// Named groups of modules, activated together
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub features: HashMap<String, FeatureConfig>,
 }
 
 /// Node metadata section
@@ -52,6 +60,21 @@ pub struct ModuleConfig {
     /// Module-specific configuration
     #[serde(default)]
     pub config: HashMap<String, toml::Value>,
+    /// External services that must be available before the module starts
+    #[serde(default)]
+    pub external_dependencies: Vec<ExternalDependency>,
+    /// OS-level sandbox settings
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    /// Arguments, environment, restart policy and resource limits
+    #[serde(default)]
+    pub service: ServiceConfig,
+    /// Data directory and layout version
+    #[serde(default)]
+    pub data: DataDirConfig,
+    /// Features that must all be active for the module to be included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_features: Vec<String>,
 }
 
 fn default_true() -> bool {
@@ -81,8 +104,18 @@ impl NodeConfig {
         Ok(())
     }
 
-    /// Convert to NodeSpec
+    /// Convert to NodeSpec, with the default features
     pub fn to_spec(&self) -> Result<NodeSpec> {
+        Ok(self.resolve(&FeatureSelection::default())?.spec)
+    }
+
+    /// Resolve the modules included under a feature selection
+    pub fn resolve(&self, selection: &FeatureSelection) -> Result<ResolvedComposition> {
+        features::resolve(self, selection)
+    }
+
+    /// NodeSpec without modules
+    pub(crate) fn base_spec(&self) -> Result<NodeSpec> {
         let network = match self.node.network.as_str() {
             "mainnet" => NetworkType::Mainnet,
             "testnet" => NetworkType::Testnet,
@@ -95,38 +128,43 @@ impl NodeConfig {
             }
         };
 
-        let modules: Result<Vec<ModuleSpec>> = self
-            .modules
-            .iter()
-            .filter(|(_, cfg)| cfg.enabled)
-            .map(|(name, cfg)| {
-                // Convert toml::Value to serde_json::Value
-                let config: HashMap<String, serde_json::Value> = cfg
-                    .config
-                    .iter()
-                    .map(|(k, v)| {
-                        let json_value = toml_to_json_value(v);
-                        (k.clone(), json_value)
-                    })
-                    .collect();
-
-                Ok(ModuleSpec {
-                    name: name.clone(),
-                    version: cfg.version.clone(),
-                    enabled: cfg.enabled,
-                    config,
-                })
-            })
-            .collect();
-
         Ok(NodeSpec {
             name: self.node.name.clone(),
             version: self.node.version.clone(),
             network,
-            modules: modules?,
+            modules: Vec::new(),
         })
     }
 
+    /// ModuleSpec for an included module, with the given configuration
+    pub(crate) fn module_spec(
+        &self,
+        name: &str,
+        module_config: &HashMap<String, toml::Value>,
+    ) -> ModuleSpec {
+        let cfg = &self.modules[name];
+
+        // Convert toml::Value to serde_json::Value
+        let config: HashMap<String, serde_json::Value> = module_config
+            .iter()
+            .map(|(k, v)| {
+                let json_value = toml_to_json_value(v);
+                (k.clone(), json_value)
+            })
+            .collect();
+
+        ModuleSpec {
+            name: name.to_string(),
+            version: cfg.version.clone(),
+            enabled: true,
+            config,
+            external_dependencies: cfg.external_dependencies.clone(),
+            sandbox: cfg.sandbox.clone(),
+            service: cfg.service.clone(),
+            data: cfg.data.clone(),
+        }
+    }
+
     /// Generate template configuration
     pub fn template() -> Self {
         let mut modules = HashMap::new();
@@ -138,6 +176,11 @@ impl NodeConfig {
                 enabled: false,
                 version: Some("0.1.0".to_string()),
                 config: HashMap::new(),
+                external_dependencies: Vec::new(),
+                sandbox: SandboxConfig::default(),
+                service: ServiceConfig::default(),
+                data: DataDirConfig::default(),
+                required_features: Vec::new(),
             },
         );
 
@@ -147,6 +190,11 @@ impl NodeConfig {
                 enabled: false,
                 version: Some("0.2.0".to_string()),
                 config: HashMap::new(),
+                external_dependencies: Vec::new(),
+                sandbox: SandboxConfig::default(),
+                service: ServiceConfig::default(),
+                data: DataDirConfig::default(),
+                required_features: Vec::new(),
             },
         );
 
@@ -157,6 +205,7 @@ impl NodeConfig {
                 network: "mainnet".to_string(),
             },
             modules,
+            features: HashMap::new(),
         }
     }
 }
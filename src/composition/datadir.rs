@@ -0,0 +1,411 @@
+//! Module Data Directories
+//!
+//! Prepares a module's data directory before the module starts: the
+//! directory is created (with parents) at mode 0700, its ownership is checked
+//! against the service's run-as user, and a `layout_version` marker records
+//! the data layout the contents are in.
+//!
+//! When a module version declares a newer layout than the marker, the
+//! migration hooks registered for the versions in between run in order, and
+//! the marker is advanced after each one, so a hook never runs twice. An
+//! older declared layout is a downgrade and is refused unless explicitly
+//! allowed.
+
+use crate::composition::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Marker file recording the data layout version
+pub const LAYOUT_VERSION_FILE: &str = "layout_version";
+
+/// Data directory settings of a module
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataDirConfig {
+    /// Data directory; none means the module keeps no data
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// Data layout version the module version uses
+    #[serde(default)]
+    pub layout_version: u32,
+}
+
+/// A prepared data directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataDirStatus {
+    pub path: PathBuf,
+    /// Layout version the contents are in
+    pub layout_version: u32,
+}
+
+/// Migrates a data directory to the layout version it is registered for
+pub type MigrationHook = Arc<dyn Fn(&Path) -> Result<()> + Send + Sync>;
+
+/// Prepares module data directories and runs their migrations
+#[derive(Default)]
+pub struct DataDirManager {
+    /// Migration hooks by module and target layout version
+    migrations: HashMap<String, BTreeMap<u32, MigrationHook>>,
+    /// Start modules whose data is in a newer layout than they declare
+    allow_downgrade: bool,
+}
+
+impl DataDirManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the hook migrating a module's data to `layout_version`
+    pub fn register_migration(&mut self, module: &str, layout_version: u32, hook: MigrationHook) {
+        self.migrations
+            .entry(module.to_string())
+            .or_default()
+            .insert(layout_version, hook);
+    }
+
+    /// Allow starting modules on data in a newer layout (`--allow-data-downgrade`)
+    pub fn set_allow_downgrade(&mut self, allow: bool) {
+        self.allow_downgrade = allow;
+    }
+
+    /// Create, check and migrate a module's data directory
+    ///
+    /// `run_as` is the user the module runs as when privilege separation is
+    /// configured (a user name or numeric uid); the directory must be owned
+    /// by it. Returns `None` when the module has no data directory.
+    pub fn prepare(
+        &self,
+        module: &str,
+        config: &DataDirConfig,
+        run_as: Option<&str>,
+    ) -> Result<Option<DataDirStatus>> {
+        let Some(dir) = &config.dir else {
+            return Ok(None);
+        };
+
+        let owner = run_as.map(resolve_uid).transpose()?;
+        if dir.exists() {
+            check_permissions(module, dir, owner)?;
+        } else {
+            create(dir, owner)?;
+        }
+
+        let marker = dir.join(LAYOUT_VERSION_FILE);
+        let current = match fs::read_to_string(&marker) {
+            Ok(contents) => contents.trim().parse::<u32>().map_err(|_| {
+                CompositionError::InvalidConfiguration(format!(
+                    "Module {} data layout marker {} is not a version: {:?}",
+                    module,
+                    marker.display(),
+                    contents.trim()
+                ))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                write_marker(&marker, config.layout_version)?;
+                return Ok(Some(DataDirStatus {
+                    path: dir.clone(),
+                    layout_version: config.layout_version,
+                }));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if current > config.layout_version {
+            if !self.allow_downgrade {
+                return Err(CompositionError::InvalidConfiguration(format!(
+                    "Module {} data in {} has layout version {}, newer than version {} the module \
+                     uses; refusing to downgrade (use --allow-data-downgrade)",
+                    module,
+                    dir.display(),
+                    current,
+                    config.layout_version
+                )));
+            }
+            return Ok(Some(DataDirStatus {
+                path: dir.clone(),
+                layout_version: current,
+            }));
+        }
+
+        if current < config.layout_version {
+            let hooks = self.migrations.get(module);
+            for (&version, hook) in hooks
+                .into_iter()
+                .flat_map(|hooks| hooks.range(current + 1..=config.layout_version))
+            {
+                hook(dir).map_err(|e| {
+                    CompositionError::InstallationFailed(format!(
+                        "Module {} data migration to layout version {} failed: {}",
+                        module, version, e
+                    ))
+                })?;
+                write_marker(&marker, version)?;
+            }
+            write_marker(&marker, config.layout_version)?;
+        }
+
+        Ok(Some(DataDirStatus {
+            path: dir.clone(),
+            layout_version: config.layout_version,
+        }))
+    }
+}
+
+/// Write the marker through a temporary file, so it is never half-written
+fn write_marker(marker: &Path, version: u32) -> Result<()> {
+    let temporary = marker.with_extension("tmp");
+    fs::write(&temporary, format!("{}\n", version))?;
+    fs::rename(&temporary, marker)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create(dir: &Path, owner: Option<u32>) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    // The mode is subject to the umask
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    if let Some(uid) = owner {
+        std::os::unix::fs::chown(dir, Some(uid), None).map_err(|e| {
+            CompositionError::InvalidConfiguration(format!(
+                "Cannot give data directory {} to uid {}: {}",
+                dir.display(),
+                uid,
+                e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn create(dir: &Path, _owner: Option<u32>) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn check_permissions(module: &str, dir: &Path, owner: Option<u32>) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(dir)?;
+    if !metadata.is_dir() {
+        return Err(CompositionError::InvalidConfiguration(format!(
+            "Module {} data directory {} is not a directory",
+            module,
+            dir.display()
+        )));
+    }
+    let mode = metadata.mode() & 0o777;
+    if mode & 0o077 != 0 {
+        return Err(CompositionError::InvalidConfiguration(format!(
+            "Module {} data directory {} has mode {:o}; expected 700",
+            module,
+            dir.display(),
+            mode
+        )));
+    }
+    if let Some(uid) = owner {
+        if metadata.uid() != uid {
+            return Err(CompositionError::InvalidConfiguration(format!(
+                "Module {} data directory {} is owned by uid {}, but the module runs as uid {}",
+                module,
+                dir.display(),
+                metadata.uid(),
+                uid
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(module: &str, dir: &Path, _owner: Option<u32>) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(CompositionError::InvalidConfiguration(format!(
+            "Module {} data directory {} is not a directory",
+            module,
+            dir.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Uid of a user name or numeric uid
+#[cfg(unix)]
+fn resolve_uid(user: &str) -> Result<u32> {
+    if let Ok(uid) = user.parse::<u32>() {
+        return Ok(uid);
+    }
+
+    let unknown =
+        || CompositionError::InvalidConfiguration(format!("Unknown run-as user {}", user));
+    let name = std::ffi::CString::new(user).map_err(|_| unknown())?;
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buffer = vec![0 as libc::c_char; 16384];
+    let mut result = std::ptr::null_mut();
+    let rc = unsafe {
+        libc::getpwnam_r(
+            name.as_ptr(),
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if rc != 0 || result.is_null() {
+        return Err(unknown());
+    }
+    Ok(passwd.pw_uid)
+}
+
+#[cfg(not(unix))]
+fn resolve_uid(user: &str) -> Result<u32> {
+    Err(CompositionError::InvalidConfiguration(format!(
+        "Cannot run modules as {}: privilege separation needs a Unix platform",
+        user
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    fn config(dir: &Path, layout_version: u32) -> DataDirConfig {
+        DataDirConfig {
+            dir: Some(dir.to_path_buf()),
+            layout_version,
+        }
+    }
+
+    #[test]
+    fn test_fresh_init() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("node/lightning");
+        let manager = DataDirManager::new();
+
+        let status = manager
+            .prepare("lightning", &config(&dir, 2), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.path, dir);
+        assert_eq!(status.layout_version, 2);
+        assert_eq!(
+            fs::read_to_string(dir.join(LAYOUT_VERSION_FILE)).unwrap(),
+            "2\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+
+        assert!(manager
+            .prepare("lightning", &DataDirConfig::default(), None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permission_mismatch() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("lightning");
+        fs::create_dir(&dir).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        let manager = DataDirManager::new();
+
+        let err = manager
+            .prepare("lightning", &config(&dir, 1), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("mode 755"), "{}", err);
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700)).unwrap();
+        let uid = fs::metadata(&dir).unwrap().uid();
+        let other = (uid + 1).to_string();
+        let err = manager
+            .prepare("lightning", &config(&dir, 1), Some(&other))
+            .unwrap_err();
+        assert!(err.to_string().contains("owned by uid"), "{}", err);
+
+        assert!(manager
+            .prepare("lightning", &config(&dir, 1), Some(&uid.to_string()))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_upgrade_runs_migration_once() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("lightning");
+        let mut manager = DataDirManager::new();
+        manager
+            .prepare("lightning", &config(&dir, 1), None)
+            .unwrap();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        manager.register_migration(
+            "lightning",
+            2,
+            Arc::new(move |dir: &Path| -> Result<()> {
+                counter.fetch_add(1, Ordering::SeqCst);
+                fs::write(dir.join("migrated"), b"v2")?;
+                Ok(())
+            }),
+        );
+        // Not part of this upgrade
+        manager.register_migration(
+            "lightning",
+            3,
+            Arc::new(|_: &Path| -> Result<()> { panic!("migrated past the declared layout") }),
+        );
+
+        for _ in 0..2 {
+            let status = manager
+                .prepare("lightning", &config(&dir, 2), None)
+                .unwrap()
+                .unwrap();
+            assert_eq!(status.layout_version, 2);
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(dir.join("migrated").exists());
+    }
+
+    #[test]
+    fn test_downgrade_refused() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("lightning");
+        let mut manager = DataDirManager::new();
+        manager
+            .prepare("lightning", &config(&dir, 3), None)
+            .unwrap();
+
+        let err = manager
+            .prepare("lightning", &config(&dir, 2), None)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("--allow-data-downgrade"),
+            "{}",
+            err
+        );
+
+        manager.set_allow_downgrade(true);
+        let status = manager
+            .prepare("lightning", &config(&dir, 2), None)
+            .unwrap()
+            .unwrap();
+        // The data stays in its newer layout
+        assert_eq!(status.layout_version, 3);
+    }
+}
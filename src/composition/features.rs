@@ -0,0 +1,401 @@
+//! Composition Features
+//!
+//! Named groups of modules switched on and off together, so operators can
+//! enable a whole stack (say, lightning) with one flag instead of editing
+//! every module section.
+//!
+//! ```toml
+//! [features.lightning]
+//! default = false
+//! modules = ["lightning", "lnd-bridge"]
+//!
+//! [features.lightning.overrides.wallet]
+//! anchor_outputs = true
+//!
+//! [modules.watchtower]
+//! required_features = ["lightning"]
+//! ```
+//!
+//! Modules named by a feature are included exactly when one of those
+//! features is active; other modules follow their `enabled` flag. Either way,
+//! every feature in a module's `required_features` must be active. Active
+//! features apply their config overrides to the included modules.
+
+use crate::composition::config::NodeConfig;
+use crate::composition::registry::ModuleRegistry;
+use crate::composition::types::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A named group of modules
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeatureConfig {
+    /// Active unless default features are disabled
+    #[serde(default)]
+    pub default: bool,
+    /// Modules the feature activates
+    #[serde(default)]
+    pub modules: Vec<String>,
+    /// Module config values set while the feature is active, by module
+    #[serde(default)]
+    pub overrides: HashMap<String, HashMap<String, toml::Value>>,
+}
+
+/// Which features to activate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureSelection {
+    /// Activate the features marked `default = true`
+    pub default_features: bool,
+    /// Features to activate in addition
+    pub features: Vec<String>,
+}
+
+impl Default for FeatureSelection {
+    /// Default features only
+    fn default() -> Self {
+        Self {
+            default_features: true,
+            features: Vec::new(),
+        }
+    }
+}
+
+/// A configuration resolved against a feature selection
+#[derive(Debug, Clone)]
+pub struct ResolvedComposition {
+    /// Included modules, sorted by name, with feature overrides applied
+    pub spec: NodeSpec,
+    /// Active features
+    pub features: BTreeSet<String>,
+    /// Modules left out by feature resolution, with a feature that would
+    /// include them
+    pub excluded: BTreeMap<String, String>,
+}
+
+impl ResolvedComposition {
+    /// Check that no included module depends on a module excluded by a
+    /// feature, naming the feature to enable
+    ///
+    /// Modules the registry does not know are skipped; composition
+    /// validation reports them.
+    pub fn check_dependencies(&self, registry: &ModuleRegistry) -> Result<()> {
+        for module in &self.spec.modules {
+            let Ok(info) = registry.get_module(&module.name, module.version.as_deref()) else {
+                continue;
+            };
+            let mut dependencies: Vec<&String> = info.dependencies.keys().collect();
+            dependencies.sort();
+            for dependency in dependencies {
+                if let Some(feature) = self.excluded.get(dependency) {
+                    return Err(CompositionError::DependencyResolutionFailed(format!(
+                        "Module '{}' depends on '{}', which is excluded because feature '{}' is not active (enable it with --feature {})",
+                        module.name, dependency, feature, feature
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// SHA256 identifying the composition, including the active features
+    pub fn composition_hash(&self) -> Result<String> {
+        let canonical = serde_json::to_value(serde_json::json!({
+            "spec": self.spec,
+            "features": self.features,
+        }))
+        .map_err(|e| CompositionError::SerializationError(e.to_string()))?;
+        Ok(hex::encode(Sha256::digest(canonical.to_string())))
+    }
+}
+
+/// Resolve a configuration's modules against a feature selection
+pub(crate) fn resolve(
+    config: &NodeConfig,
+    selection: &FeatureSelection,
+) -> Result<ResolvedComposition> {
+    check_feature_references(config, selection)?;
+
+    let mut active: BTreeSet<String> = selection.features.iter().cloned().collect();
+    if selection.default_features {
+        active.extend(
+            config
+                .features
+                .iter()
+                .filter(|(_, feature)| feature.default)
+                .map(|(name, _)| name.clone()),
+        );
+    }
+
+    // Features naming each module, sorted
+    let mut listed_by: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for (name, feature) in &config.features {
+        for module in &feature.modules {
+            listed_by.entry(module).or_default().insert(name);
+        }
+    }
+
+    let mut included = BTreeMap::new();
+    let mut excluded = BTreeMap::new();
+    for (name, module) in &config.modules {
+        let missing_required = module
+            .required_features
+            .iter()
+            .find(|feature| !active.contains(*feature));
+        let listing = listed_by.get(name.as_str());
+        let activated = listing.map(|features| features.iter().any(|f| active.contains(*f)));
+
+        if let Some(feature) = missing_required {
+            excluded.insert(name.clone(), feature.clone());
+        } else if activated == Some(false) {
+            let feature = listing.and_then(|features| features.iter().next()).unwrap();
+            excluded.insert(name.clone(), feature.to_string());
+        } else if activated == Some(true) || module.enabled {
+            included.insert(name.clone(), module.config.clone());
+        }
+    }
+
+    // Apply overrides, rejecting conflicting values from two features
+    let mut set_by: HashMap<(&str, &str), &str> = HashMap::new();
+    for feature_name in &active {
+        let feature = &config.features[feature_name];
+        let mut modules: Vec<_> = feature.overrides.iter().collect();
+        modules.sort_by_key(|(module, _)| *module);
+        for (module, values) in modules {
+            let Some(module_config) = included.get_mut(module) else {
+                continue;
+            };
+            for (key, value) in values {
+                if let Some(other) = set_by.insert((module, key), feature_name) {
+                    if module_config.get(key) != Some(value) {
+                        return Err(CompositionError::InvalidConfiguration(format!(
+                            "Features '{}' and '{}' set different values for {}.{}",
+                            other, feature_name, module, key
+                        )));
+                    }
+                }
+                module_config.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    let mut spec = config.base_spec()?;
+    spec.modules = included
+        .into_iter()
+        .map(|(name, module_config)| config.module_spec(&name, &module_config))
+        .collect();
+
+    Ok(ResolvedComposition {
+        spec,
+        features: active,
+        excluded,
+    })
+}
+
+/// Reject selections and references to features or modules that do not exist
+fn check_feature_references(config: &NodeConfig, selection: &FeatureSelection) -> Result<()> {
+    let unknown_feature = |feature: &String| !config.features.contains_key(feature);
+
+    if let Some(feature) = selection.features.iter().find(|f| unknown_feature(f)) {
+        return Err(CompositionError::InvalidConfiguration(format!(
+            "Unknown feature '{}'",
+            feature
+        )));
+    }
+    for (name, module) in &config.modules {
+        if let Some(feature) = module.required_features.iter().find(|f| unknown_feature(f)) {
+            return Err(CompositionError::InvalidConfiguration(format!(
+                "Module '{}' requires unknown feature '{}'",
+                name, feature
+            )));
+        }
+    }
+    for (name, feature) in &config.features {
+        let named = feature.modules.iter().chain(feature.overrides.keys());
+        if let Some(module) = named.into_iter().find(|m| !config.modules.contains_key(*m)) {
+            return Err(CompositionError::InvalidConfiguration(format!(
+                "Feature '{}' names module '{}', which has no [modules.{}] section",
+                name, module, module
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[node]
+name = "featured"
+network = "regtest"
+
+[features.indexing]
+default = true
+modules = ["indexer"]
+
+[features.lightning]
+modules = ["lightning", "watchtower"]
+
+[features.lightning.overrides.wallet]
+anchor_outputs = true
+
+[modules.wallet]
+version = "0.1.0"
+
+[modules.indexer]
+version = "0.1.0"
+
+[modules.lightning]
+version = "0.1.0"
+enabled = false
+
+[modules.watchtower]
+version = "0.1.0"
+required_features = ["indexing"]
+"#;
+
+    fn config() -> NodeConfig {
+        toml::from_str(CONFIG).unwrap()
+    }
+
+    fn names(resolved: &ResolvedComposition) -> Vec<&str> {
+        resolved
+            .spec
+            .modules
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect()
+    }
+
+    fn module(name: &str, dependencies: &[&str]) -> ModuleInfo {
+        ModuleInfo {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            author: None,
+            capabilities: Vec::new(),
+            dependencies: dependencies
+                .iter()
+                .map(|d| (d.to_string(), "0.1.0".to_string()))
+                .collect(),
+            entry_point: name.to_string(),
+            directory: None,
+            binary_path: None,
+            config_schema: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_default_features() {
+        let resolved = config().resolve(&FeatureSelection::default()).unwrap();
+        assert_eq!(names(&resolved), ["indexer", "wallet"]);
+        assert_eq!(resolved.features.iter().collect::<Vec<_>>(), ["indexing"]);
+        assert_eq!(resolved.excluded["lightning"], "lightning");
+        assert!(resolved.spec.modules[1].config.is_empty());
+
+        let none = config()
+            .resolve(&FeatureSelection {
+                default_features: false,
+                features: Vec::new(),
+            })
+            .unwrap();
+        assert_eq!(names(&none), ["wallet"]);
+        assert_ne!(
+            none.composition_hash().unwrap(),
+            resolved.composition_hash().unwrap()
+        );
+        assert_eq!(config().to_spec().unwrap().modules.len(), 2);
+    }
+
+    #[test]
+    fn test_added_feature_with_overrides() {
+        let selection = FeatureSelection {
+            default_features: true,
+            features: vec!["lightning".to_string()],
+        };
+        let resolved = config().resolve(&selection).unwrap();
+
+        // Activated despite `enabled = false`; watchtower also needs indexing
+        assert_eq!(
+            names(&resolved),
+            ["indexer", "lightning", "wallet", "watchtower"]
+        );
+        let wallet = &resolved.spec.modules[2];
+        assert_eq!(wallet.config["anchor_outputs"], serde_json::json!(true));
+        assert!(resolved.excluded.is_empty());
+
+        // The feature set alone changes the hash
+        let mut same_modules = resolved.clone();
+        same_modules.features.remove("lightning");
+        assert_ne!(
+            same_modules.composition_hash().unwrap(),
+            resolved.composition_hash().unwrap()
+        );
+        assert_eq!(
+            config()
+                .resolve(&selection)
+                .unwrap()
+                .composition_hash()
+                .unwrap(),
+            resolved.composition_hash().unwrap()
+        );
+
+        // Without default features, watchtower's requirement is unmet
+        let resolved = config()
+            .resolve(&FeatureSelection {
+                default_features: false,
+                ..selection
+            })
+            .unwrap();
+        assert_eq!(names(&resolved), ["lightning", "wallet"]);
+        assert_eq!(resolved.excluded["watchtower"], "indexing");
+    }
+
+    #[test]
+    fn test_dependency_excluded_by_feature() {
+        let mut registry = ModuleRegistry::new("./modules");
+        registry.register_module(module("wallet", &["lightning"]));
+        registry.register_module(module("indexer", &[]));
+
+        let resolved = config().resolve(&FeatureSelection::default()).unwrap();
+        let err = resolved.check_dependencies(&registry).unwrap_err();
+        assert!(matches!(
+            err,
+            CompositionError::DependencyResolutionFailed(_)
+        ));
+        assert!(err
+            .to_string()
+            .contains("Module 'wallet' depends on 'lightning'"));
+        assert!(err.to_string().contains("--feature lightning"));
+
+        let resolved = config()
+            .resolve(&FeatureSelection {
+                default_features: true,
+                features: vec!["lightning".to_string()],
+            })
+            .unwrap();
+        assert!(resolved.check_dependencies(&registry).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_features_rejected() {
+        let selection = FeatureSelection {
+            default_features: true,
+            features: vec!["tor".to_string()],
+        };
+        let err = config().resolve(&selection).unwrap_err();
+        assert!(err.to_string().contains("Unknown feature 'tor'"));
+
+        let mut config = config();
+        config
+            .features
+            .get_mut("lightning")
+            .unwrap()
+            .modules
+            .push("eclair".to_string());
+        let err = config.resolve(&FeatureSelection::default()).unwrap_err();
+        assert!(err.to_string().contains("names module 'eclair'"));
+    }
+}
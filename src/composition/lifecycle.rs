@@ -2,14 +2,72 @@
 //!
 //! Handles starting, stopping, restarting, and health checking of modules.
 
-use crate::composition::conversion::*;
+use crate::composition::datadir::{DataDirConfig, DataDirManager, DataDirStatus};
+use crate::composition::probe::ExternalDependency;
 use crate::composition::registry::ModuleRegistry;
+use crate::composition::sandbox::{generate_profile, Enforcement, SandboxConfig, SandboxProfile};
+use crate::composition::schema::validate_module_config;
+use crate::composition::service::ServiceConfig;
 use crate::composition::types::*;
+use crate::governance::{GovernanceMessage, Multisig, SignatureBundle};
+use crate::module::security::permissions_from_capabilities;
+use crate::util::CancelToken;
 use blvm_node::module::manager::ModuleManager;
 use blvm_node::module::traits::ModuleMetadata as RefModuleMetadata;
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::process::{Child, Command};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
+
+/// Capacity of the lifecycle event channel
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Interval between health polls while waiting for readiness
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Time source for readiness polling
+pub trait Clock: Send + Sync {
+    /// Current instant
+    fn now(&self) -> Instant;
+    /// Wait for `duration`
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+/// Wall clock backed by tokio's timer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Reports the health of a running module, e.g. by querying its endpoint
+pub type HealthProbe = Arc<dyn Fn() -> ModuleHealth + Send + Sync>;
+
+/// Events emitted while managing module lifecycles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// Waiting for an external dependency before starting a module
+    Waiting {
+        module: String,
+        dependency: String,
+        attempt: u32,
+    },
+    /// A module's sandbox profile was not (fully) enforced
+    SandboxWarning { module: String, message: String },
+    /// A governance restart failed and the module could not be started
+    /// again with its previous configuration either
+    RollbackFailed { module: String, error: String },
+}
 
 /// Module lifecycle manager
 pub struct ModuleLifecycle {
@@ -19,6 +77,28 @@ pub struct ModuleLifecycle {
     module_manager: Option<Arc<Mutex<ModuleManager>>>,
     /// Module status cache
     status_cache: HashMap<String, ModuleStatus>,
+    /// Applied module configurations
+    configs: HashMap<String, serde_json::Value>,
+    /// External dependencies probed before each module starts
+    external_dependencies: HashMap<String, Vec<ExternalDependency>>,
+    /// Sandbox settings applied when module processes are spawned
+    sandbox: HashMap<String, SandboxConfig>,
+    /// Service settings, including the user a module runs as
+    services: HashMap<String, ServiceConfig>,
+    /// Data directory settings
+    data: HashMap<String, DataDirConfig>,
+    /// Data directory preparation and migrations
+    data_dirs: DataDirManager,
+    /// Data directories prepared at the last start
+    data_status: HashMap<String, DataDirStatus>,
+    /// Module processes spawned without a ModuleManager
+    processes: HashMap<String, Child>,
+    /// Lifecycle event channel
+    events: broadcast::Sender<LifecycleEvent>,
+    /// Health probes consulted for running modules
+    health_probes: HashMap<String, HealthProbe>,
+    /// Time source for readiness polling
+    clock: Arc<dyn Clock>,
 }
 
 impl ModuleLifecycle {
@@ -28,6 +108,166 @@ impl ModuleLifecycle {
             registry,
             module_manager: None,
             status_cache: HashMap::new(),
+            configs: HashMap::new(),
+            external_dependencies: HashMap::new(),
+            sandbox: HashMap::new(),
+            services: HashMap::new(),
+            data: HashMap::new(),
+            data_dirs: DataDirManager::new(),
+            data_status: HashMap::new(),
+            processes: HashMap::new(),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            health_probes: HashMap::new(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Set the clock used when waiting for readiness
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the probe reporting a module's health while it runs
+    pub fn set_health_probe(&mut self, name: &str, probe: HealthProbe) {
+        self.health_probes.insert(name.to_string(), probe);
+    }
+
+    /// Subscribe to lifecycle events
+    pub fn subscribe(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.events.subscribe()
+    }
+
+    /// Set the external dependencies that must be available before a module starts
+    pub fn set_external_dependencies(&mut self, name: &str, dependencies: Vec<ExternalDependency>) {
+        self.external_dependencies
+            .insert(name.to_string(), dependencies);
+    }
+
+    /// Set the sandbox settings for a module
+    pub fn set_sandbox_config(&mut self, name: &str, config: SandboxConfig) {
+        self.sandbox.insert(name.to_string(), config);
+    }
+
+    /// Set the service settings for a module
+    pub fn set_service_config(&mut self, name: &str, config: ServiceConfig) {
+        self.services.insert(name.to_string(), config);
+    }
+
+    /// Set the data directory settings for a module
+    pub fn set_data_config(&mut self, name: &str, config: DataDirConfig) {
+        self.data.insert(name.to_string(), config);
+    }
+
+    /// Data directory manager, to register migrations and allow downgrades
+    pub fn data_dirs_mut(&mut self) -> &mut DataDirManager {
+        &mut self.data_dirs
+    }
+
+    /// Data directory prepared when the module was last started
+    pub fn data_dir_status(&self, name: &str) -> Option<&DataDirStatus> {
+        self.data_status.get(name)
+    }
+
+    /// Generate a module's sandbox profile from the permissions its
+    /// capabilities name
+    pub fn sandbox_profile(&self, name: &str) -> Result<SandboxProfile> {
+        let info = self.registry.get_module(name, None)?;
+        let mut config = self.sandbox.get(name).cloned().unwrap_or_default();
+        if let Some(dir) = self.data.get(name).and_then(|data| data.dir.clone()) {
+            if !config.data_dirs.contains(&dir) {
+                config.data_dirs.push(dir);
+            }
+        }
+        let granted = permissions_from_capabilities(&info.capabilities);
+        Ok(generate_profile(&info, &granted, &config))
+    }
+
+    /// Build the command that spawns a module's process, confined by its
+    /// sandbox profile
+    ///
+    /// Warnings (warn mode, unsupported platforms) are emitted as
+    /// `SandboxWarning` events.
+    pub fn sandboxed_command(&self, name: &str) -> Result<Command> {
+        let info = self.registry.get_module(name, None)?;
+        let binary_path = info.binary_path.as_ref().ok_or_else(|| {
+            CompositionError::ModuleNotFound(format!("Module {} has no binary path", name))
+        })?;
+
+        let mut command = Command::new(binary_path);
+        for message in self.sandbox_profile(name)?.apply(&mut command)? {
+            self.sandbox_warning(name, message);
+        }
+        Ok(command)
+    }
+
+    fn sandbox_warning(&self, name: &str, message: String) {
+        // No subscribers is not an error
+        let _ = self.events.send(LifecycleEvent::SandboxWarning {
+            module: name.to_string(),
+            message,
+        });
+    }
+
+    /// Wait for a module's external dependencies, emitting `Waiting` events
+    async fn wait_for_external_dependencies(
+        &mut self,
+        name: &str,
+        cancel: &CancelToken,
+    ) -> Result<()> {
+        let dependencies = self
+            .external_dependencies
+            .get(name)
+            .cloned()
+            .unwrap_or_default();
+
+        for dependency in &dependencies {
+            let events = self.events.clone();
+            let result = dependency
+                .wait_until_available_with_cancel(cancel, |attempt| {
+                    // No subscribers is not an error
+                    let _ = events.send(LifecycleEvent::Waiting {
+                        module: name.to_string(),
+                        dependency: dependency.name.clone(),
+                        attempt,
+                    });
+                })
+                .await;
+
+            if let Err(e) = result {
+                // A cancelled wait leaves the status as it was
+                if !matches!(e, CompositionError::Cancelled(_)) {
+                    self.status_cache
+                        .insert(name.to_string(), ModuleStatus::Error(e.to_string()));
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create, check and migrate a module's data directory
+    fn prepare_data_dir(&mut self, name: &str) -> Result<()> {
+        let Some(config) = self.data.get(name) else {
+            return Ok(());
+        };
+        let run_as = self
+            .services
+            .get(name)
+            .and_then(|service| service.run_as.as_deref());
+
+        match self.data_dirs.prepare(name, config, run_as) {
+            Ok(Some(status)) => {
+                self.data_status.insert(name.to_string(), status);
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(e) => {
+                self.status_cache
+                    .insert(name.to_string(), ModuleStatus::Error(e.to_string()));
+                Err(e)
+            }
         }
     }
 
@@ -37,11 +277,75 @@ impl ModuleLifecycle {
         self
     }
 
+    /// Set the configuration a module is started with
+    pub fn set_module_config(&mut self, name: &str, config: serde_json::Value) {
+        self.configs.insert(name.to_string(), config);
+    }
+
+    /// Get the configuration currently applied to a module
+    pub fn module_config(&self, name: &str) -> Option<&serde_json::Value> {
+        self.configs.get(name)
+    }
+
     /// Start a module
     pub async fn start_module(&mut self, name: &str) -> Result<()> {
+        self.start_module_with_cancel(name, &CancelToken::new())
+            .await
+    }
+
+    /// Start a module unless `cancel` is tripped first
+    ///
+    /// Cancellation points: before and while waiting for the module's
+    /// external dependencies, and just before the module is launched. Once
+    /// launched the start completes. A cancelled start returns `Cancelled`,
+    /// launches nothing and leaves the module's status unchanged.
+    pub async fn start_module_with_cancel(
+        &mut self,
+        name: &str,
+        cancel: &CancelToken,
+    ) -> Result<()> {
         let info = self.registry.get_module(name, None)?;
 
+        if let Some(config) = self.configs.get(name) {
+            if let Err(e) = validate_module_config(&info, config) {
+                self.status_cache
+                    .insert(name.to_string(), ModuleStatus::Error(e.to_string()));
+                return Err(e);
+            }
+        }
+
+        self.prepare_data_dir(name)?;
+        self.wait_for_external_dependencies(name, cancel).await?;
+        if cancel.is_cancelled() {
+            return Err(CompositionError::Cancelled(format!(
+                "module {} was not started",
+                name
+            )));
+        }
+
         if let Some(ref manager) = self.module_manager {
+            // The node spawns the process itself, so the profile cannot be
+            // installed on it
+            match self.sandbox_profile(name)?.enforcement {
+                Enforcement::Off => {}
+                Enforcement::Warn => self.sandbox_warning(
+                    name,
+                    format!(
+                        "sandbox for module {} not enforced: the node's module manager spawns it",
+                        name
+                    ),
+                ),
+                Enforcement::Enforce => {
+                    let e = CompositionError::InvalidConfiguration(format!(
+                        "Cannot sandbox module {}: the node's module manager spawns it",
+                        name
+                    ));
+                    self.status_cache
+                        .insert(name.to_string(), ModuleStatus::Error(e.to_string()));
+                    return Err(e);
+                }
+            }
+
             // Convert ModuleInfo to ModuleMetadata
             let metadata: RefModuleMetadata = info.clone().into();
 
@@ -58,8 +362,27 @@ impl ModuleLifecycle {
                 HashMap::new(), // TODO: Get config from ModuleSpec
             )
             .await
-            .map_err(|e| CompositionError::from(e))?;
+            .map_err(CompositionError::from)?;
 
+            self.status_cache
+                .insert(name.to_string(), ModuleStatus::Running);
+        } else if info.binary_path.is_some() {
+            // Spawn the module ourselves, confined by its sandbox profile
+            if let Some(child) = self.processes.get_mut(name) {
+                if matches!(child.try_wait(), Ok(None)) {
+                    return Ok(());
+                }
+            }
+            let child = self.sandboxed_command(name)?.spawn().map_err(|e| {
+                let e = CompositionError::InstallationFailed(format!(
+                    "Cannot spawn module {}: {}",
+                    name, e
+                ));
+                self.status_cache
+                    .insert(name.to_string(), ModuleStatus::Error(e.to_string()));
+                e
+            })?;
+            self.processes.insert(name.to_string(), child);
             self.status_cache
                 .insert(name.to_string(), ModuleStatus::Running);
         } else {
@@ -79,7 +402,12 @@ impl ModuleLifecycle {
             let mut mgr = manager.lock().await;
             mgr.unload_module(name)
                 .await
-                .map_err(|e| CompositionError::from(e))?;
+                .map_err(CompositionError::from)?;
+        }
+        if let Some(mut child) = self.processes.remove(name) {
+            // The process may already have exited
+            let _ = child.kill();
+            child.wait()?;
         }
 
         self.status_cache
@@ -94,6 +422,60 @@ impl ModuleLifecycle {
         self.start_module(name).await
     }
 
+    /// Restart a module with a new configuration approved by governance
+    ///
+    /// The approval bundle must carry a `ConfigUpdate` message for this module
+    /// and exactly this configuration, signed by the multisig threshold. If the
+    /// module fails to start with the new configuration, the previous
+    /// configuration is restored and the module is started with it again.
+    pub async fn restart_with_governance(
+        &mut self,
+        name: &str,
+        new_config: serde_json::Value,
+        approval: &SignatureBundle,
+        multisig: &Multisig,
+    ) -> Result<()> {
+        let expected = GovernanceMessage::config_update(name, &new_config);
+        if approval.message != expected {
+            return Err(CompositionError::GovernanceRejected(format!(
+                "Approval is not a config update for module {} with this configuration",
+                name
+            )));
+        }
+
+        let approved = approval
+            .verify(multisig)
+            .map_err(|e| CompositionError::GovernanceRejected(e.to_string()))?;
+        if !approved {
+            return Err(CompositionError::GovernanceRejected(format!(
+                "Config update for module {} lacks threshold approval",
+                name
+            )));
+        }
+
+        self.stop_module(name).await?;
+        let previous = self.configs.insert(name.to_string(), new_config);
+
+        if let Err(e) = self.start_module(name).await {
+            // Roll back to the previous configuration
+            match previous {
+                Some(config) => self.configs.insert(name.to_string(), config),
+                None => self.configs.remove(name),
+            };
+            // The restart's failure is the error to report; a failed
+            // rollback is reported as an event
+            if let Err(rollback) = self.start_module(name).await {
+                let _ = self.events.send(LifecycleEvent::RollbackFailed {
+                    module: name.to_string(),
+                    error: rollback.to_string(),
+                });
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
     /// Get module status
     pub async fn get_module_status(&self, name: &str) -> Result<ModuleStatus> {
         let _module = self.registry.get_module(name, None)?;
@@ -110,13 +492,60 @@ impl ModuleLifecycle {
     pub async fn health_check(&self, name: &str) -> Result<ModuleHealth> {
         let status = self.get_module_status(name).await?;
         match status {
-            ModuleStatus::Running => Ok(ModuleHealth::Healthy),
+            ModuleStatus::Running => Ok(self
+                .health_probes
+                .get(name)
+                .map_or(ModuleHealth::Healthy, |probe| probe())),
             ModuleStatus::Error(msg) => Ok(ModuleHealth::Unhealthy(msg)),
             ModuleStatus::Stopped | ModuleStatus::NotInstalled => Ok(ModuleHealth::Unknown),
             _ => Ok(ModuleHealth::Degraded),
         }
     }
 
+    /// Wait until every started module reports healthy
+    ///
+    /// Polls [`health_check`](Self::health_check) for each module that has
+    /// been started and not stopped since. On timeout, returns the modules
+    /// that are still not healthy.
+    pub async fn wait_until_ready(
+        &self,
+        timeout: Duration,
+    ) -> std::result::Result<(), Vec<String>> {
+        let deadline = self.clock.now() + timeout;
+        loop {
+            let unhealthy = self.unhealthy_modules().await;
+            if unhealthy.is_empty() {
+                return Ok(());
+            }
+            let now = self.clock.now();
+            if now >= deadline {
+                return Err(unhealthy);
+            }
+            self.clock
+                .sleep(READINESS_POLL_INTERVAL.min(deadline - now))
+                .await;
+        }
+    }
+
+    /// Started modules that are not healthy, sorted by name
+    async fn unhealthy_modules(&self) -> Vec<String> {
+        let mut names: Vec<&String> = self
+            .status_cache
+            .iter()
+            .filter(|(_, status)| **status != ModuleStatus::Stopped)
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+
+        let mut unhealthy = Vec::new();
+        for name in names {
+            if !matches!(self.health_check(name).await, Ok(ModuleHealth::Healthy)) {
+                unhealthy.push(name.clone());
+            }
+        }
+        unhealthy
+    }
+
     /// Get the module registry
     pub fn registry(&self) -> &ModuleRegistry {
         &self.registry
@@ -127,3 +556,333 @@ impl ModuleLifecycle {
         &mut self.registry
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    fn lifecycle_with_module() -> ModuleLifecycle {
+        let mut registry = ModuleRegistry::new("./modules");
+        let mut config_schema = HashMap::new();
+        config_schema.insert("port".to_string(), "integer".to_string());
+        registry.register_module(ModuleInfo {
+            name: "lightning".to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            author: None,
+            capabilities: Vec::new(),
+            dependencies: HashMap::new(),
+            entry_point: "lightning".to_string(),
+            directory: None,
+            binary_path: None,
+            config_schema,
+        });
+        ModuleLifecycle::new(registry)
+    }
+
+    fn approve(
+        keypair: &GovernanceKeypair,
+        name: &str,
+        config: &serde_json::Value,
+    ) -> SignatureBundle {
+        let message = GovernanceMessage::config_update(name, config);
+        let signature =
+            crate::sign_message(keypair.secret_key.expose(), &message.to_signing_bytes()).unwrap();
+        SignatureBundle::with_signatures(message, vec![signature])
+    }
+
+    #[tokio::test]
+    async fn test_start_waits_for_external_dependencies() {
+        use crate::composition::probe::Probe;
+
+        let mut lifecycle = lifecycle_with_module();
+        let mut events = lifecycle.subscribe();
+        lifecycle.set_external_dependencies(
+            "lightning",
+            vec![ExternalDependency {
+                name: "bitcoind-rpc".to_string(),
+                probe: Probe::Command {
+                    command: "sh".to_string(),
+                    args: vec!["-c".to_string(), "exit 1".to_string()],
+                    exit_code: 0,
+                },
+                timeout: "1s".to_string(),
+                retry_interval: "1s".to_string(),
+                deadline: "1s".to_string(),
+            }],
+        );
+
+        let err = lifecycle.start_module("lightning").await.unwrap_err();
+        assert!(err.to_string().contains("external dependency bitcoind-rpc"));
+        assert!(matches!(
+            lifecycle.get_module_status("lightning").await.unwrap(),
+            ModuleStatus::Error(_)
+        ));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            LifecycleEvent::Waiting {
+                module: "lightning".to_string(),
+                dependency: "bitcoind-rpc".to_string(),
+                attempt: 1,
+            }
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restart_with_governance() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let mut lifecycle = lifecycle_with_module();
+        lifecycle.start_module("lightning").await.unwrap();
+
+        let config = serde_json::json!({"port": 9735});
+        let approval = approve(&keypair, "lightning", &config);
+        lifecycle
+            .restart_with_governance("lightning", config.clone(), &approval, &multisig)
+            .await
+            .unwrap();
+
+        assert_eq!(lifecycle.module_config("lightning"), Some(&config));
+        assert_eq!(
+            lifecycle.get_module_status("lightning").await.unwrap(),
+            ModuleStatus::Running
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restart_with_governance_rejects_unapproved_config() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let mut lifecycle = lifecycle_with_module();
+
+        let approval = approve(&keypair, "lightning", &serde_json::json!({"port": 9735}));
+        let result = lifecycle
+            .restart_with_governance(
+                "lightning",
+                serde_json::json!({"port": 1}),
+                &approval,
+                &multisig,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CompositionError::GovernanceRejected(_))
+        ));
+        assert!(lifecycle.module_config("lightning").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_restart_with_governance_rolls_back_on_start_failure() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let mut lifecycle = lifecycle_with_module();
+
+        let previous = serde_json::json!({"port": 9735});
+        lifecycle.set_module_config("lightning", previous.clone());
+        lifecycle.start_module("lightning").await.unwrap();
+
+        // Approved, but rejected by the module's config schema at start
+        let bad_config = serde_json::json!({"port": "not-a-number"});
+        let approval = approve(&keypair, "lightning", &bad_config);
+        let result = lifecycle
+            .restart_with_governance("lightning", bad_config, &approval, &multisig)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CompositionError::InvalidConfiguration(_))
+        ));
+        assert_eq!(lifecycle.module_config("lightning"), Some(&previous));
+        assert_eq!(
+            lifecycle.get_module_status("lightning").await.unwrap(),
+            ModuleStatus::Running
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restart_with_governance_reports_restart_error_when_rollback_fails() {
+        use crate::composition::probe::Probe;
+
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let mut lifecycle = lifecycle_with_module();
+        lifecycle.start_module("lightning").await.unwrap();
+
+        // The dependency disappears, so neither configuration can start
+        lifecycle.set_external_dependencies(
+            "lightning",
+            vec![ExternalDependency {
+                name: "bitcoind-rpc".to_string(),
+                probe: Probe::Command {
+                    command: "sh".to_string(),
+                    args: vec!["-c".to_string(), "exit 1".to_string()],
+                    exit_code: 0,
+                },
+                timeout: "1s".to_string(),
+                retry_interval: "1s".to_string(),
+                deadline: "1s".to_string(),
+            }],
+        );
+        let mut events = lifecycle.subscribe();
+
+        let config = serde_json::json!({"port": 9736});
+        let approval = approve(&keypair, "lightning", &config);
+        let result = lifecycle
+            .restart_with_governance("lightning", config, &approval, &multisig)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CompositionError::ExternalDependencyUnavailable(_))
+        ));
+        assert!(lifecycle.module_config("lightning").is_none());
+        assert!(matches!(
+            lifecycle.get_module_status("lightning").await.unwrap(),
+            ModuleStatus::Error(_)
+        ));
+        let rollback = std::iter::from_fn(|| events.try_recv().ok())
+            .find_map(|event| match event {
+                LifecycleEvent::RollbackFailed { module, error } => Some((module, error)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(rollback.0, "lightning");
+        assert!(rollback.1.contains("bitcoind-rpc"));
+    }
+
+    #[test]
+    fn test_sandbox_warn_mode_emits_events() {
+        let mut lifecycle = lifecycle_with_module();
+        let mut info = lifecycle.registry().get_module("lightning", None).unwrap();
+        info.binary_path = Some("/bin/true".into());
+        info.capabilities = vec!["network_access".to_string()];
+        lifecycle.registry_mut().register_module(info);
+        lifecycle.set_sandbox_config(
+            "lightning",
+            SandboxConfig {
+                enforcement: crate::composition::sandbox::Enforcement::Warn,
+                data_dirs: Vec::new(),
+            },
+        );
+
+        assert!(lifecycle.sandbox_profile("lightning").unwrap().network);
+
+        let mut events = lifecycle.subscribe();
+        let mut command = lifecycle.sandboxed_command("lightning").unwrap();
+        assert!(command.status().unwrap().success());
+
+        match events.try_recv().unwrap() {
+            LifecycleEvent::SandboxWarning { module, message } => {
+                assert_eq!(module, "lightning");
+                assert!(message.contains("not enforced"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_spawns_sandboxed_module() {
+        let mut lifecycle = lifecycle_with_module();
+        let mut info = lifecycle.registry().get_module("lightning", None).unwrap();
+        info.binary_path = Some("/bin/true".into());
+        lifecycle.registry_mut().register_module(info);
+        lifecycle.set_sandbox_config(
+            "lightning",
+            SandboxConfig {
+                enforcement: crate::composition::sandbox::Enforcement::Warn,
+                data_dirs: Vec::new(),
+            },
+        );
+
+        let mut events = lifecycle.subscribe();
+        lifecycle.start_module("lightning").await.unwrap();
+        assert!(lifecycle.processes.contains_key("lightning"));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            LifecycleEvent::SandboxWarning { .. }
+        ));
+
+        lifecycle.stop_module("lightning").await.unwrap();
+        assert!(lifecycle.processes.is_empty());
+        assert_eq!(
+            lifecycle.get_module_status("lightning").await.unwrap(),
+            ModuleStatus::Stopped
+        );
+    }
+
+    /// Clock whose sleeps advance time instantly
+    struct TestClock {
+        now: std::sync::Mutex<Instant>,
+    }
+
+    impl TestClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                now: std::sync::Mutex::new(Instant::now()),
+            })
+        }
+    }
+
+    impl Clock for TestClock {
+        fn now(&self) -> Instant {
+            *self.now.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+            *self.now.lock().unwrap() += duration;
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_after_delay() {
+        let clock = TestClock::new();
+        let mut lifecycle = lifecycle_with_module().with_clock(clock.clone());
+        let started = clock.now();
+        let probe_clock = clock.clone();
+        lifecycle.set_health_probe(
+            "lightning",
+            Arc::new(move || {
+                if probe_clock.now() - started >= Duration::from_secs(2) {
+                    ModuleHealth::Healthy
+                } else {
+                    ModuleHealth::Unhealthy("syncing".to_string())
+                }
+            }),
+        );
+        lifecycle.start_module("lightning").await.unwrap();
+
+        lifecycle
+            .wait_until_ready(Duration::from_secs(10))
+            .await
+            .unwrap();
+        let waited = clock.now() - started;
+        assert!(waited >= Duration::from_secs(2) && waited < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_times_out() {
+        let clock = TestClock::new();
+        let mut lifecycle = lifecycle_with_module().with_clock(clock.clone());
+        lifecycle.set_health_probe(
+            "lightning",
+            Arc::new(|| ModuleHealth::Unhealthy("no peers".to_string())),
+        );
+        lifecycle.start_module("lightning").await.unwrap();
+
+        let started = clock.now();
+        let unhealthy = lifecycle
+            .wait_until_ready(Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert_eq!(unhealthy, vec!["lightning".to_string()]);
+        assert_eq!(clock.now() - started, Duration::from_secs(5));
+
+        // A stopped module no longer holds up readiness
+        lifecycle.stop_module("lightning").await.unwrap();
+        assert!(lifecycle.wait_until_ready(Duration::ZERO).await.is_ok());
+    }
+}
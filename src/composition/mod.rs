@@ -7,20 +7,37 @@
 //! - Module discovery and registry management
 //! - Declarative node composition from TOML configuration
 //! - Module lifecycle management (start/stop/restart)
+//! - Module data directory setup and layout migrations
 //! - Dependency resolution and validation
+//! - Feature flags switching groups of modules together
+//! - Export to systemd units for non-supervised deployments
 
+pub mod approval;
 pub mod composer;
 pub mod config;
 pub mod conversion;
+pub mod datadir;
+pub mod features;
 pub mod lifecycle;
+pub mod probe;
 pub mod registry;
+pub mod sandbox;
 pub mod schema;
+pub mod service;
+pub mod systemd;
 pub mod types;
 pub mod validation;
 
 // Re-export main types for convenience
+pub use approval::{ModuleApproval, ModuleApprovals};
 pub use composer::NodeComposer;
 pub use config::NodeConfig;
-pub use lifecycle::ModuleLifecycle;
+pub use datadir::{DataDirConfig, DataDirManager, DataDirStatus, MigrationHook};
+pub use features::{FeatureConfig, FeatureSelection, ResolvedComposition};
+pub use lifecycle::{Clock, HealthProbe, LifecycleEvent, ModuleLifecycle, SystemClock};
+pub use probe::{ExternalDependency, Probe};
 pub use registry::ModuleRegistry;
+pub use sandbox::{Enforcement, SandboxConfig, SandboxProfile};
+pub use service::{ResourceLimits, RestartPolicy, ServiceConfig};
+pub use systemd::{export_systemd, SystemdUnit};
 pub use types::*;
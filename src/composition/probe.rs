@@ -0,0 +1,477 @@
+//! External Dependency Probes
+//!
+//! Wait-and-retry probes for services a module needs but the composer does
+//! not manage (a database, an upstream node's RPC endpoint, ...).
+//!
+//! Durations are written in the CLI duration syntax (`5s`, `2m`, `1h30m`).
+
+use crate::composition::types::*;
+use crate::util::humanize::parse_duration;
+use crate::util::CancelToken;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// How to check that an external dependency is available
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Probe {
+    /// Succeeds when a TCP connection can be opened
+    Tcp { address: String },
+    /// Succeeds when an HTTP GET returns the expected status
+    Http {
+        url: String,
+        #[serde(default = "default_http_status")]
+        status: u16,
+    },
+    /// Succeeds when the command exits with the expected code
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        exit_code: i32,
+    },
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+fn default_timeout() -> String {
+    "5s".to_string()
+}
+
+fn default_retry_interval() -> String {
+    "1s".to_string()
+}
+
+fn default_deadline() -> String {
+    "60s".to_string()
+}
+
+/// An external service a module depends on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalDependency {
+    /// Dependency name used in events and errors
+    pub name: String,
+    /// Availability probe
+    pub probe: Probe,
+    /// Timeout for a single probe attempt
+    #[serde(default = "default_timeout")]
+    pub timeout: String,
+    /// Delay between probe attempts
+    #[serde(default = "default_retry_interval")]
+    pub retry_interval: String,
+    /// Total time to wait before giving up
+    #[serde(default = "default_deadline")]
+    pub deadline: String,
+}
+
+/// Parsed probe timing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProbeTiming {
+    pub timeout: Duration,
+    pub retry_interval: Duration,
+    pub deadline: Duration,
+}
+
+impl ExternalDependency {
+    /// Parse the timeout, retry interval and deadline
+    pub fn timing(&self) -> Result<ProbeTiming> {
+        let parse = |field: &str, value: &str| {
+            parse_duration(value).map_err(|e| {
+                CompositionError::InvalidConfiguration(format!(
+                    "External dependency '{}' {}: {}",
+                    self.name, field, e
+                ))
+            })
+        };
+
+        Ok(ProbeTiming {
+            timeout: parse("timeout", &self.timeout)?,
+            retry_interval: parse("retry_interval", &self.retry_interval)?,
+            deadline: parse("deadline", &self.deadline)?,
+        })
+    }
+
+    /// Check the dependency definition without probing it
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(CompositionError::InvalidConfiguration(
+                "External dependency name cannot be empty".to_string(),
+            ));
+        }
+        self.timing()?;
+
+        if let Probe::Http { url, .. } = &self.probe {
+            parse_http_url(url).map_err(|e| {
+                CompositionError::InvalidConfiguration(format!(
+                    "External dependency '{}': {}",
+                    self.name, e
+                ))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Human-readable description of the probe target
+    pub fn describe(&self) -> String {
+        match &self.probe {
+            Probe::Tcp { address } => format!("{} (tcp {})", self.name, address),
+            Probe::Http { url, status } => format!("{} (http {} -> {})", self.name, url, status),
+            Probe::Command { command, .. } => format!("{} (command {})", self.name, command),
+        }
+    }
+
+    /// Run the probe once
+    pub fn probe_once(&self, timeout: Duration) -> bool {
+        match &self.probe {
+            Probe::Tcp { address } => probe_tcp(address, timeout),
+            Probe::Http { url, status } => probe_http(url, *status, timeout),
+            Probe::Command {
+                command,
+                args,
+                exit_code,
+            } => probe_command(command, args, *exit_code, timeout),
+        }
+    }
+
+    /// Probe until the dependency is available or the deadline passes
+    ///
+    /// `on_retry` is called with the attempt number after each failed attempt
+    /// that will be retried.
+    pub async fn wait_until_available<F>(&self, on_retry: F) -> Result<()>
+    where
+        F: FnMut(u32),
+    {
+        self.wait_until_available_with_cancel(&CancelToken::new(), on_retry)
+            .await
+    }
+
+    /// Probe until the dependency is available, the deadline passes, or
+    /// `cancel` is tripped
+    ///
+    /// Each attempt runs [`probe_once`](Self::probe_once) on the blocking
+    /// thread pool, so the probe's connect, DNS lookup and command wait never
+    /// stall the async runtime. Cancellation is checked before, during and
+    /// between attempts; a cancelled attempt is abandoned and finishes in the
+    /// background within its timeout. Returns `Cancelled` when cancelled.
+    pub async fn wait_until_available_with_cancel<F>(
+        &self,
+        cancel: &CancelToken,
+        mut on_retry: F,
+    ) -> Result<()>
+    where
+        F: FnMut(u32),
+    {
+        let timing = self.timing()?;
+        let started = Instant::now();
+        let mut attempt = 1;
+
+        loop {
+            if cancel.is_cancelled() {
+                return Err(self.cancelled());
+            }
+            let dependency = self.clone();
+            let timeout = timing.timeout;
+            let attempt_result =
+                tokio::task::spawn_blocking(move || dependency.probe_once(timeout));
+            tokio::select! {
+                available = attempt_result => {
+                    if available.unwrap_or(false) {
+                        return Ok(());
+                    }
+                }
+                _ = cancel.cancelled() => return Err(self.cancelled()),
+            }
+
+            let elapsed = started.elapsed();
+            if elapsed >= timing.deadline {
+                return Err(CompositionError::ExternalDependencyUnavailable(format!(
+                    "external dependency {} never became available within {}",
+                    self.describe(),
+                    self.deadline
+                )));
+            }
+
+            on_retry(attempt);
+            attempt += 1;
+            tokio::select! {
+                _ = tokio::time::sleep(timing.retry_interval.min(timing.deadline - elapsed)) => {}
+                _ = cancel.cancelled() => return Err(self.cancelled()),
+            }
+        }
+    }
+
+    fn cancelled(&self) -> CompositionError {
+        CompositionError::Cancelled(format!(
+            "waiting for external dependency {}",
+            self.describe()
+        ))
+    }
+}
+
+fn connect(address: &str, timeout: Duration) -> Option<TcpStream> {
+    let addrs = address.to_socket_addrs().ok()?;
+    for addr in addrs {
+        if let Ok(stream) = TcpStream::connect_timeout(&addr, timeout) {
+            return Some(stream);
+        }
+    }
+    None
+}
+
+fn probe_tcp(address: &str, timeout: Duration) -> bool {
+    connect(address, timeout).is_some()
+}
+
+/// Split an `http://host[:port][/path]` URL into `(host:port, host, path)`
+fn parse_http_url(url: &str) -> std::result::Result<(String, String, String), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("only http:// URLs can be probed: {}", url))?;
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(format!("missing host in URL: {}", url));
+    }
+
+    let host = authority.split(':').next().unwrap_or(authority).to_string();
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    Ok((address, host, path.to_string()))
+}
+
+fn probe_http(url: &str, expected_status: u16, timeout: Duration) -> bool {
+    let Ok((address, host, path)) = parse_http_url(url) else {
+        return false;
+    };
+    let Some(mut stream) = connect(&address, timeout) else {
+        return false;
+    };
+
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+
+    let mut response = [0u8; 32];
+    let mut read = 0;
+    while read < response.len() {
+        match stream.read(&mut response[read..]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => read += n,
+        }
+    }
+
+    // Status line: HTTP/1.x <code> <reason>
+    String::from_utf8_lossy(&response[..read])
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        == Some(expected_status)
+}
+
+fn probe_command(command: &str, args: &[String], exit_code: i32, timeout: Duration) -> bool {
+    let Ok(mut child) = Command::new(command).args(args).spawn() else {
+        return false;
+    };
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.code() == Some(exit_code),
+            Ok(None) if started.elapsed() < timeout => {
+                std::thread::sleep(Duration::from_millis(10))
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn tcp_dependency(address: String, deadline: &str) -> ExternalDependency {
+        ExternalDependency {
+            name: "upstream".to_string(),
+            probe: Probe::Tcp { address },
+            timeout: "1s".to_string(),
+            retry_interval: "0s".to_string(),
+            deadline: deadline.to_string(),
+        }
+    }
+
+    fn unused_address() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_delayed_dependency() {
+        let address = unused_address();
+        let bind_address = address.clone();
+        let server = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            let listener = TcpListener::bind(bind_address).unwrap();
+            let _ = listener.accept();
+        });
+
+        let dependency = tcp_dependency(address, "10s");
+        let mut retries = 0;
+        dependency
+            .wait_until_available(|_| retries += 1)
+            .await
+            .unwrap();
+
+        assert!(retries > 0);
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_deadline_failure() {
+        let dependency = ExternalDependency {
+            retry_interval: "1s".to_string(),
+            ..tcp_dependency(unused_address(), "1s")
+        };
+
+        let mut retries = 0;
+        let err = dependency
+            .wait_until_available(|_| retries += 1)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("never became available"));
+        assert!(retries >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_while_waiting() {
+        let dependency = ExternalDependency {
+            retry_interval: "1m".to_string(),
+            ..tcp_dependency(unused_address(), "10m")
+        };
+        let cancel = CancelToken::new();
+
+        let mut retries = 0;
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            dependency.wait_until_available_with_cancel(&cancel, |_| {
+                retries += 1;
+                cancel.cancel();
+            }),
+        )
+        .await
+        .expect("cancellation should end the wait");
+
+        assert!(matches!(result, Err(CompositionError::Cancelled(_))));
+        assert_eq!(retries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_during_attempt() {
+        let dependency = ExternalDependency {
+            name: "slow".to_string(),
+            probe: Probe::Command {
+                command: "sleep".to_string(),
+                args: vec!["2".to_string()],
+                exit_code: 0,
+            },
+            timeout: "10s".to_string(),
+            retry_interval: "0s".to_string(),
+            deadline: "1m".to_string(),
+        };
+        let cancel = CancelToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            trigger.cancel();
+        });
+
+        let started = Instant::now();
+        let result = dependency
+            .wait_until_available_with_cancel(&cancel, |_| {})
+            .await;
+
+        assert!(matches!(result, Err(CompositionError::Cancelled(_))));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_command_probe() {
+        let dependency = ExternalDependency {
+            name: "exit-code".to_string(),
+            probe: Probe::Command {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "exit 3".to_string()],
+                exit_code: 3,
+            },
+            timeout: default_timeout(),
+            retry_interval: default_retry_interval(),
+            deadline: default_deadline(),
+        };
+        assert!(dependency.probe_once(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut dependency = tcp_dependency("127.0.0.1:1".to_string(), "30s");
+        assert!(dependency.validate().is_ok());
+
+        dependency.deadline = "soon".to_string();
+        assert!(dependency.validate().is_err());
+
+        dependency.deadline = "30s".to_string();
+        dependency.probe = Probe::Http {
+            url: "https://example.com/health".to_string(),
+            status: 200,
+        };
+        assert!(dependency.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_from_toml() {
+        let dependency: ExternalDependency = toml::from_str(
+            r#"
+            name = "postgres"
+            deadline = "2m"
+            probe = { type = "tcp", address = "127.0.0.1:5432" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(dependency.timeout, "5s");
+        assert_eq!(
+            dependency.probe,
+            Probe::Tcp {
+                address: "127.0.0.1:5432".to_string()
+            }
+        );
+        assert_eq!(
+            dependency.timing().unwrap().deadline,
+            Duration::from_secs(120)
+        );
+    }
+}
@@ -3,22 +3,35 @@
 //! High-level module registry API for discovering, installing, updating,
 //! and removing modules. Wraps bllvm-node module registry functionality.
 
-use crate::composition::conversion::*;
 use crate::composition::types::*;
+use crate::governance::oci::{verify_image, OciImageRef};
+use crate::governance::{Multisig, Signature};
+use crate::util::CancelToken;
 use blvm_node::module::registry::{
-    DiscoveredModule as RefDiscoveredModule, ModuleDependencies as RefModuleDependencies,
-    ModuleDiscovery as RefModuleDiscovery, ModuleManifest as RefModuleManifest,
+    ModuleDependencies as RefModuleDependencies, ModuleDiscovery as RefModuleDiscovery,
 };
 use blvm_node::module::traits::ModuleError as RefModuleError;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+fn check_cancelled(cancel: &CancelToken, operation: &str) -> Result<()> {
+    if cancel.is_cancelled() {
+        return Err(CompositionError::Cancelled(format!(
+            "{} cancelled before it started",
+            operation
+        )));
+    }
+    Ok(())
+}
+
 /// Module registry for managing module lifecycle
 pub struct ModuleRegistry {
     /// Base directory for modules
     modules_dir: PathBuf,
     /// Discovered modules cache
     discovered: Vec<ModuleInfo>,
+    /// Governance multisig that must approve image-sourced modules
+    image_multisig: Option<Multisig>,
 }
 
 impl ModuleRegistry {
@@ -27,9 +40,16 @@ impl ModuleRegistry {
         Self {
             modules_dir: modules_dir.as_ref().to_path_buf(),
             discovered: Vec::new(),
+            image_multisig: None,
         }
     }
 
+    /// Require governance approval from the given multisig for OCI image modules
+    pub fn with_image_multisig(mut self, multisig: Multisig) -> Self {
+        self.image_multisig = Some(multisig);
+        self
+    }
+
     /// Discover available modules in the modules directory
     pub fn discover_modules(&mut self) -> Result<Vec<ModuleInfo>> {
         let discovery = RefModuleDiscovery::new(&self.modules_dir);
@@ -37,7 +57,7 @@ impl ModuleRegistry {
             .discover_modules()
             .map_err(|e: RefModuleError| CompositionError::from(e))?;
 
-        self.discovered = discovered.iter().map(|d| ModuleInfo::from(d)).collect();
+        self.discovered = discovered.iter().map(ModuleInfo::from).collect();
 
         Ok(self.discovered.clone())
     }
@@ -47,7 +67,7 @@ impl ModuleRegistry {
         let module = self
             .discovered
             .iter()
-            .find(|m| m.name == name && version.map_or(true, |v| m.version == v))
+            .find(|m| m.name == name && version.is_none_or(|v| m.version == v))
             .ok_or_else(|| {
                 let msg = if let Some(v) = version {
                     format!("Module {} version {} not found", name, v)
@@ -62,6 +82,19 @@ impl ModuleRegistry {
 
     /// Install module from source
     pub fn install_module(&mut self, source: ModuleSource) -> Result<ModuleInfo> {
+        self.install_module_with_cancel(source, &CancelToken::new())
+    }
+
+    /// Install module from source unless `cancel` is tripped
+    ///
+    /// Cancellation point: before the registry is changed. A cancelled
+    /// install returns `Cancelled` and leaves the registry as it was.
+    pub fn install_module_with_cancel(
+        &mut self,
+        source: ModuleSource,
+        cancel: &CancelToken,
+    ) -> Result<ModuleInfo> {
+        check_cancelled(cancel, "module install")?;
         match source {
             ModuleSource::Path(path) => {
                 // Validate path exists
@@ -77,7 +110,7 @@ impl ModuleRegistry {
                 let discovery = RefModuleDiscovery::new(&path);
                 let discovered = discovery
                     .discover_modules()
-                    .map_err(|e| CompositionError::from(e))?;
+                    .map_err(CompositionError::from)?;
 
                 if discovered.is_empty() {
                     return Err(CompositionError::InstallationFailed(
@@ -102,13 +135,113 @@ impl ModuleRegistry {
                     "Git installation not yet implemented".to_string(),
                 ))
             }
+            ModuleSource::Oci {
+                repository,
+                digest,
+                tag,
+                signatures,
+            } => self.register_image_module(&repository, &digest, tag.as_deref(), &signatures),
         }
     }
 
+    /// Register an OCI image module after checking its governance signatures
+    fn register_image_module(
+        &mut self,
+        repository: &str,
+        digest: &str,
+        tag: Option<&str>,
+        signatures: &[String],
+    ) -> Result<ModuleInfo> {
+        let image = OciImageRef::new(repository, digest, tag)
+            .map_err(|e| CompositionError::InvalidConfiguration(e.to_string()))?;
+
+        let multisig = self.image_multisig.as_ref().ok_or_else(|| {
+            CompositionError::InstallationFailed(format!(
+                "No governance multisig configured to verify image {}",
+                image
+            ))
+        })?;
+
+        if signatures.is_empty() {
+            return Err(CompositionError::InstallationFailed(format!(
+                "Image {} is unsigned",
+                image
+            )));
+        }
+
+        let signatures = signatures
+            .iter()
+            .map(|s| {
+                let bytes = hex::decode(s).map_err(|e| {
+                    CompositionError::InvalidConfiguration(format!("Invalid signature hex: {}", e))
+                })?;
+                Signature::from_bytes(&bytes)
+                    .map_err(|e| CompositionError::InvalidConfiguration(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let approved = verify_image(&image, &signatures, multisig).map_err(|e| {
+            CompositionError::InstallationFailed(format!(
+                "Image {} failed governance verification: {}",
+                image, e
+            ))
+        })?;
+        if !approved {
+            return Err(CompositionError::InstallationFailed(format!(
+                "Image {} lacks threshold governance approval",
+                image
+            )));
+        }
+
+        let name = image
+            .repository
+            .rsplit('/')
+            .next()
+            .unwrap_or(&image.repository)
+            .to_string();
+        let info = ModuleInfo {
+            name,
+            version: image.tag.clone().unwrap_or_else(|| image.digest.clone()),
+            description: None,
+            author: None,
+            capabilities: Vec::new(),
+            dependencies: HashMap::new(),
+            entry_point: image.to_string(),
+            directory: None,
+            binary_path: None,
+            config_schema: HashMap::new(),
+        };
+
+        self.register_module(info.clone());
+
+        Ok(info)
+    }
+
+    /// Add a module to the registry, replacing any entry with the same name
+    pub(crate) fn register_module(&mut self, info: ModuleInfo) {
+        self.discovered.retain(|m| m.name != info.name);
+        self.discovered.push(info);
+    }
+
     /// Update module to new version
     pub fn update_module(&mut self, name: &str, new_version: &str) -> Result<ModuleInfo> {
+        self.update_module_with_cancel(name, new_version, &CancelToken::new())
+    }
+
+    /// Update module to new version unless `cancel` is tripped
+    ///
+    /// Cancellation point: before the installed version is touched. A
+    /// cancelled update returns `Cancelled` and leaves the old version in
+    /// place.
+    pub fn update_module_with_cancel(
+        &mut self,
+        name: &str,
+        _new_version: &str,
+        cancel: &CancelToken,
+    ) -> Result<ModuleInfo> {
         // Check if module exists
         let _current = self.get_module(name, None)?;
+        check_cancelled(cancel, "module update")?;
 
         // For now, this is a placeholder
         // In a full implementation, this would:
@@ -150,7 +283,7 @@ impl ModuleRegistry {
         let discovery = RefModuleDiscovery::new(&self.modules_dir);
         let all_discovered = discovery
             .discover_modules()
-            .map_err(|e| CompositionError::from(e))?;
+            .map_err(CompositionError::from)?;
 
         // Filter to only requested modules and convert to owned values
         let requested: Vec<_> = all_discovered
@@ -160,7 +293,7 @@ impl ModuleRegistry {
             .collect();
 
         let resolution =
-            RefModuleDependencies::resolve(&requested).map_err(|e| CompositionError::from(e))?;
+            RefModuleDependencies::resolve(&requested).map_err(CompositionError::from)?;
 
         // Build result with resolved modules
         let mut resolved = Vec::new();
@@ -0,0 +1,791 @@
+//! Module Sandboxing
+//!
+//! Translates the permissions granted to a module into an OS-level sandbox
+//! profile, and applies that profile to the module's process at spawn.
+//!
+//! Profile generation is pure; [`SandboxProfile::apply`] installs the profile
+//! as a pre-exec hook on a [`Command`]. On Linux the profile is enforced with
+//! landlock (file access), seccomp (syscall allowlist and socket families)
+//! and `no_new_privs`. Other platforms run modules unconfined with a warning.
+
+use crate::composition::types::*;
+use crate::module::security::{Permission, PermissionSet};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// System paths every module may read and execute (binaries, libraries,
+/// loader configuration)
+const SYSTEM_READ_PATHS: [&str; 6] = ["/usr", "/lib", "/lib64", "/bin", "/etc", "/dev/urandom"];
+
+/// Paths every module may read and write
+const SYSTEM_WRITE_PATHS: [&str; 1] = ["/dev/null"];
+
+/// Syscalls available to every sandboxed module
+///
+/// Socket syscalls are included so modules can reach the node over IPC;
+/// without [`Permission::NetworkAccess`], `socket` is limited to Unix sockets.
+const BASE_SYSCALLS: &[&str] = &[
+    // File I/O
+    "read",
+    "write",
+    "readv",
+    "writev",
+    "pread64",
+    "pwrite64",
+    "openat",
+    "open",
+    "creat",
+    "close",
+    "close_range",
+    "lseek",
+    "fstat",
+    "stat",
+    "lstat",
+    "newfstatat",
+    "statx",
+    "statfs",
+    "fstatfs",
+    "access",
+    "faccessat",
+    "faccessat2",
+    "fcntl",
+    "flock",
+    "fsync",
+    "fdatasync",
+    "truncate",
+    "ftruncate",
+    "getdents",
+    "getdents64",
+    "getcwd",
+    "chdir",
+    "fchdir",
+    "rename",
+    "renameat",
+    "renameat2",
+    "mkdir",
+    "mkdirat",
+    "rmdir",
+    "unlink",
+    "unlinkat",
+    "link",
+    "linkat",
+    "symlink",
+    "symlinkat",
+    "readlink",
+    "readlinkat",
+    "chmod",
+    "fchmod",
+    "fchmodat",
+    "chown",
+    "fchown",
+    "fchownat",
+    "lchown",
+    "umask",
+    "utimensat",
+    "sendfile",
+    "copy_file_range",
+    "fadvise64",
+    "ioctl",
+    "dup",
+    "dup2",
+    "dup3",
+    "pipe",
+    "pipe2",
+    "memfd_create",
+    // Memory
+    "mmap",
+    "mprotect",
+    "munmap",
+    "mremap",
+    "msync",
+    "mincore",
+    "madvise",
+    "brk",
+    "mlock",
+    "munlock",
+    "membarrier",
+    // Processes, threads and signals
+    "clone",
+    "clone3",
+    "fork",
+    "vfork",
+    "execve",
+    "execveat",
+    "exit",
+    "exit_group",
+    "wait4",
+    "kill",
+    "tgkill",
+    "getpid",
+    "gettid",
+    "getppid",
+    "getpgid",
+    "setpgid",
+    "getpgrp",
+    "getsid",
+    "setsid",
+    "getuid",
+    "geteuid",
+    "getgid",
+    "getegid",
+    "capget",
+    "prctl",
+    "arch_prctl",
+    "set_tid_address",
+    "set_robust_list",
+    "get_robust_list",
+    "futex",
+    "rseq",
+    "sched_yield",
+    "sched_getaffinity",
+    "sched_setaffinity",
+    "sched_getparam",
+    "sched_getscheduler",
+    "getrlimit",
+    "prlimit64",
+    "getrusage",
+    "rt_sigaction",
+    "rt_sigprocmask",
+    "rt_sigreturn",
+    "sigaltstack",
+    "restart_syscall",
+    "uname",
+    "sysinfo",
+    "getrandom",
+    // Time and polling
+    "nanosleep",
+    "clock_nanosleep",
+    "clock_gettime",
+    "clock_getres",
+    "gettimeofday",
+    "time",
+    "poll",
+    "ppoll",
+    "select",
+    "pselect6",
+    "epoll_create",
+    "epoll_create1",
+    "epoll_ctl",
+    "epoll_wait",
+    "epoll_pwait",
+    "eventfd",
+    "eventfd2",
+    "timerfd_create",
+    "timerfd_settime",
+    "timerfd_gettime",
+    "signalfd",
+    "signalfd4",
+    // Sockets
+    "socket",
+    "socketpair",
+    "connect",
+    "accept",
+    "accept4",
+    "bind",
+    "listen",
+    "getsockname",
+    "getpeername",
+    "sendto",
+    "recvfrom",
+    "sendmsg",
+    "recvmsg",
+    "shutdown",
+    "setsockopt",
+    "getsockopt",
+];
+
+/// How a module's sandbox profile is applied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Enforcement {
+    /// No sandboxing
+    #[default]
+    Off,
+    /// Generate the profile and report what would be restricted
+    Warn,
+    /// Confine the module process
+    Enforce,
+}
+
+/// Per-module sandbox settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// Enforcement mode
+    #[serde(default)]
+    pub enforcement: Enforcement,
+    /// Directories the module stores data in
+    #[serde(default)]
+    pub data_dirs: Vec<PathBuf>,
+}
+
+/// File access granted beneath a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAccess {
+    /// Read files, list directories and execute
+    Read,
+    /// Read plus create, modify and remove
+    ReadWrite,
+}
+
+/// File access rule applied beneath a path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRule {
+    pub path: PathBuf,
+    pub access: FileAccess,
+}
+
+/// OS-level confinement for one module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SandboxProfile {
+    /// Module name
+    pub module: String,
+    /// Enforcement mode
+    pub enforcement: Enforcement,
+    /// Whether non-Unix sockets may be opened
+    pub network: bool,
+    /// Seccomp syscall allowlist
+    pub syscalls: Vec<&'static str>,
+    /// File access rules; everything else is denied
+    pub file_rules: Vec<FileRule>,
+}
+
+/// Generate the sandbox profile for a module from its granted permissions
+///
+/// [`Permission::NetworkAccess`] allows network sockets, and
+/// [`Permission::WriteFilesystem`] or [`Permission::ManageFilesystem`] makes
+/// the data directories writable.
+pub fn generate_profile(
+    info: &ModuleInfo,
+    granted: &PermissionSet,
+    config: &SandboxConfig,
+) -> SandboxProfile {
+    let mut file_rules: Vec<FileRule> = SYSTEM_READ_PATHS
+        .iter()
+        .map(|path| FileRule {
+            path: PathBuf::from(path),
+            access: FileAccess::Read,
+        })
+        .chain(SYSTEM_WRITE_PATHS.iter().map(|path| FileRule {
+            path: PathBuf::from(path),
+            access: FileAccess::ReadWrite,
+        }))
+        .collect();
+
+    if let Some(directory) = &info.directory {
+        file_rules.push(FileRule {
+            path: directory.clone(),
+            access: FileAccess::Read,
+        });
+    }
+
+    let data_access = if granted.has(&Permission::WriteFilesystem)
+        || granted.has(&Permission::ManageFilesystem)
+    {
+        FileAccess::ReadWrite
+    } else {
+        FileAccess::Read
+    };
+    file_rules.extend(config.data_dirs.iter().map(|dir| FileRule {
+        path: dir.clone(),
+        access: data_access,
+    }));
+
+    SandboxProfile {
+        module: info.name.clone(),
+        enforcement: config.enforcement,
+        network: granted.has(&Permission::NetworkAccess),
+        syscalls: BASE_SYSCALLS.to_vec(),
+        file_rules,
+    }
+}
+
+impl SandboxProfile {
+    /// Describe the restrictions this profile imposes
+    pub fn describe(&self) -> Vec<String> {
+        let mut restrictions = vec![format!(
+            "file access limited to {}",
+            self.file_rules
+                .iter()
+                .map(|rule| match rule.access {
+                    FileAccess::Read => format!("{} (read)", rule.path.display()),
+                    FileAccess::ReadWrite => format!("{} (read-write)", rule.path.display()),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        )];
+        if !self.network {
+            restrictions.push("network sockets denied (Unix sockets allowed)".to_string());
+        }
+        restrictions.push(format!(
+            "syscalls limited to an allowlist of {}",
+            self.syscalls.len()
+        ));
+        restrictions
+    }
+
+    /// Apply the profile to a module's command before it is spawned
+    ///
+    /// Returns warnings to surface to the operator. In `enforce` mode on
+    /// Linux the restrictions are installed as a pre-exec hook, so they only
+    /// affect the spawned process.
+    pub fn apply(&self, command: &mut Command) -> Result<Vec<String>> {
+        match self.enforcement {
+            Enforcement::Off => Ok(Vec::new()),
+            Enforcement::Warn => Ok(self
+                .describe()
+                .into_iter()
+                .map(|restriction| {
+                    format!(
+                        "sandbox for module {} not enforced: would apply {}",
+                        self.module, restriction
+                    )
+                })
+                .collect()),
+            Enforcement::Enforce => self.enforce(command),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn enforce(&self, command: &mut Command) -> Result<Vec<String>> {
+        linux::install(self, command)?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn enforce(&self, _command: &mut Command) -> Result<Vec<String>> {
+        Ok(vec![format!(
+            "sandboxing is not supported on this platform; module {} runs unconfined",
+            self.module
+        )])
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    //! Landlock and seccomp enforcement
+
+    use super::{FileAccess, SandboxProfile};
+    use crate::composition::types::*;
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+    const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+    const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+    const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+    /// All access rights of landlock ABI 1
+    const ACCESS_FS_ALL: u64 = (1 << 13) - 1;
+    /// Rights that apply to files (as opposed to directories)
+    const ACCESS_FS_FILE: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_WRITE_FILE | ACCESS_FS_READ_FILE;
+    const ACCESS_FS_READ: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xc000_003e);
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xc000_00b7);
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    const AUDIT_ARCH: Option<u32> = None;
+
+    /// Offsets into `struct seccomp_data`
+    const SECCOMP_DATA_NR: u32 = 0;
+    const SECCOMP_DATA_ARCH: u32 = 4;
+    const SECCOMP_DATA_ARG0: u32 = 16;
+
+    fn sandbox_error(module: &str, what: &str, e: impl std::fmt::Display) -> CompositionError {
+        CompositionError::InvalidConfiguration(format!(
+            "Cannot sandbox module {}: {}: {}",
+            module, what, e
+        ))
+    }
+
+    pub(super) fn install(profile: &SandboxProfile, command: &mut Command) -> Result<()> {
+        let ruleset = landlock_ruleset(profile)?;
+        let filter = seccomp_filter(&profile.syscalls, profile.network)
+            .ok_or_else(|| sandbox_error(&profile.module, "seccomp", "unsupported architecture"))?;
+
+        // Only async-signal-safe calls from here on: everything was prepared above.
+        let hook = move || {
+            // SAFETY: plain syscalls on values owned by the closure.
+            unsafe {
+                if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                if libc::syscall(libc::SYS_landlock_restrict_self, ruleset.as_raw_fd(), 0) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let program = libc::sock_fprog {
+                    len: filter.len() as libc::c_ushort,
+                    filter: filter.as_ptr() as *mut libc::sock_filter,
+                };
+                if libc::prctl(
+                    libc::PR_SET_SECCOMP,
+                    libc::SECCOMP_MODE_FILTER as libc::c_ulong,
+                    &program as *const libc::sock_fprog,
+                ) != 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        };
+
+        // SAFETY: the hook only performs async-signal-safe syscalls.
+        unsafe {
+            command.pre_exec(hook);
+        }
+        Ok(())
+    }
+
+    /// Build the landlock ruleset in the parent; the child only restricts itself
+    fn landlock_ruleset(profile: &SandboxProfile) -> Result<OwnedFd> {
+        let attr = RulesetAttr {
+            handled_access_fs: ACCESS_FS_ALL,
+        };
+        // SAFETY: attr outlives the call and its size is passed alongside.
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                std::mem::size_of::<RulesetAttr>(),
+                0,
+            )
+        };
+        if fd < 0 {
+            return Err(sandbox_error(
+                &profile.module,
+                "landlock unavailable",
+                io::Error::last_os_error(),
+            ));
+        }
+        // SAFETY: the kernel returned a new file descriptor we now own.
+        let ruleset = unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) };
+
+        for rule in &profile.file_rules {
+            // Rules for paths that do not exist on this system are skipped
+            let Ok(file) = OpenOptions::new()
+                .read(true)
+                .custom_flags(libc::O_PATH | libc::O_CLOEXEC)
+                .open(&rule.path)
+            else {
+                continue;
+            };
+
+            let mut allowed_access = match rule.access {
+                FileAccess::Read => ACCESS_FS_READ,
+                FileAccess::ReadWrite => ACCESS_FS_ALL,
+            };
+            if !file.metadata().map(|m| m.is_dir()).unwrap_or(false) {
+                allowed_access &= ACCESS_FS_FILE;
+            }
+
+            let beneath = PathBeneathAttr {
+                allowed_access,
+                parent_fd: file.as_raw_fd(),
+            };
+            // SAFETY: both descriptors are open and beneath outlives the call.
+            let result = unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    ruleset.as_raw_fd(),
+                    LANDLOCK_RULE_PATH_BENEATH,
+                    &beneath as *const PathBeneathAttr,
+                    0,
+                )
+            };
+            if result != 0 {
+                return Err(sandbox_error(
+                    &profile.module,
+                    &format!("landlock rule for {}", rule.path.display()),
+                    io::Error::last_os_error(),
+                ));
+            }
+        }
+
+        Ok(ruleset)
+    }
+
+    fn statement(code: u32, k: u32) -> libc::sock_filter {
+        libc::sock_filter {
+            code: code as u16,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    fn jump_eq(k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter {
+            code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+            jt,
+            jf,
+            k,
+        }
+    }
+
+    /// Build a seccomp allowlist filter
+    ///
+    /// Denied syscalls fail with `EPERM`. Without network access, `socket`
+    /// fails with `EACCES` for every family but `AF_UNIX`.
+    pub(super) fn seccomp_filter(
+        syscalls: &[&str],
+        network: bool,
+    ) -> Option<Vec<libc::sock_filter>> {
+        let arch = AUDIT_ARCH?;
+        let load = |offset| statement(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset);
+        let allow = statement(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ALLOW);
+        let deny = |errno: libc::c_int| {
+            statement(
+                libc::BPF_RET | libc::BPF_K,
+                libc::SECCOMP_RET_ERRNO | errno as u32,
+            )
+        };
+
+        let mut filter = vec![
+            load(SECCOMP_DATA_ARCH),
+            jump_eq(arch, 1, 0),
+            deny(libc::EPERM),
+            load(SECCOMP_DATA_NR),
+        ];
+
+        if !network {
+            filter.extend([
+                jump_eq(libc::SYS_socket as u32, 0, 4),
+                load(SECCOMP_DATA_ARG0),
+                jump_eq(libc::AF_UNIX as u32, 0, 1),
+                allow,
+                deny(libc::EACCES),
+            ]);
+        }
+
+        for number in syscalls.iter().filter_map(|name| syscall_number(name)) {
+            filter.extend([jump_eq(number as u32, 0, 1), allow]);
+        }
+        filter.push(deny(libc::EPERM));
+
+        Some(filter)
+    }
+
+    macro_rules! syscall_table {
+        ($fn_name:ident: $($sys:ident),* $(,)?) => {
+            fn $fn_name(name: &str) -> Option<libc::c_long> {
+                $(
+                    if name == &stringify!($sys)[4..] {
+                        return Some(libc::$sys);
+                    }
+                )*
+                None
+            }
+        };
+    }
+
+    syscall_table!(generic_syscall_number:
+        SYS_read, SYS_write, SYS_readv, SYS_writev, SYS_pread64, SYS_pwrite64, SYS_openat,
+        SYS_close, SYS_close_range, SYS_lseek, SYS_fstat, SYS_newfstatat, SYS_statx,
+        SYS_statfs, SYS_fstatfs, SYS_faccessat, SYS_faccessat2, SYS_fcntl, SYS_flock,
+        SYS_fsync, SYS_fdatasync, SYS_truncate, SYS_ftruncate, SYS_getdents64, SYS_getcwd,
+        SYS_chdir, SYS_fchdir, SYS_renameat, SYS_renameat2, SYS_mkdirat, SYS_unlinkat,
+        SYS_linkat, SYS_symlinkat, SYS_readlinkat, SYS_fchmod, SYS_fchmodat, SYS_fchown,
+        SYS_fchownat, SYS_umask, SYS_utimensat, SYS_sendfile, SYS_copy_file_range,
+        SYS_fadvise64, SYS_ioctl, SYS_dup, SYS_dup3, SYS_pipe2, SYS_memfd_create, SYS_mmap,
+        SYS_mprotect, SYS_munmap, SYS_mremap, SYS_msync, SYS_mincore, SYS_madvise, SYS_brk,
+        SYS_mlock, SYS_munlock, SYS_membarrier, SYS_clone, SYS_clone3, SYS_execve,
+        SYS_execveat, SYS_exit, SYS_exit_group, SYS_wait4, SYS_kill, SYS_tgkill, SYS_getpid,
+        SYS_gettid, SYS_getppid, SYS_getpgid, SYS_setpgid, SYS_getsid, SYS_setsid,
+        SYS_getuid, SYS_geteuid, SYS_getgid, SYS_getegid, SYS_capget, SYS_prctl,
+        SYS_set_tid_address, SYS_set_robust_list, SYS_get_robust_list, SYS_futex, SYS_rseq,
+        SYS_sched_yield, SYS_sched_getaffinity, SYS_sched_setaffinity, SYS_sched_getparam,
+        SYS_sched_getscheduler, SYS_getrlimit, SYS_prlimit64, SYS_getrusage,
+        SYS_rt_sigaction, SYS_rt_sigprocmask, SYS_rt_sigreturn, SYS_sigaltstack,
+        SYS_restart_syscall, SYS_uname, SYS_sysinfo, SYS_getrandom, SYS_nanosleep,
+        SYS_clock_nanosleep, SYS_clock_gettime, SYS_clock_getres, SYS_gettimeofday,
+        SYS_ppoll, SYS_pselect6, SYS_epoll_create1, SYS_epoll_ctl, SYS_epoll_pwait,
+        SYS_eventfd2, SYS_timerfd_create, SYS_timerfd_settime, SYS_timerfd_gettime,
+        SYS_signalfd4, SYS_socket, SYS_socketpair, SYS_connect, SYS_accept, SYS_accept4,
+        SYS_bind, SYS_listen, SYS_getsockname, SYS_getpeername, SYS_sendto, SYS_recvfrom,
+        SYS_sendmsg, SYS_recvmsg, SYS_shutdown, SYS_setsockopt, SYS_getsockopt,
+    );
+
+    #[cfg(target_arch = "x86_64")]
+    syscall_table!(legacy_syscall_number:
+        SYS_open, SYS_creat, SYS_stat, SYS_lstat, SYS_access, SYS_getdents, SYS_rename,
+        SYS_mkdir, SYS_rmdir, SYS_unlink, SYS_link, SYS_symlink, SYS_readlink, SYS_chmod,
+        SYS_chown, SYS_lchown, SYS_dup2, SYS_pipe, SYS_fork, SYS_vfork, SYS_getpgrp,
+        SYS_arch_prctl, SYS_time, SYS_poll, SYS_select, SYS_epoll_create, SYS_epoll_wait,
+        SYS_eventfd, SYS_signalfd,
+    );
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn legacy_syscall_number(_name: &str) -> Option<libc::c_long> {
+        None
+    }
+
+    /// Resolve a syscall name for this architecture
+    fn syscall_number(name: &str) -> Option<libc::c_long> {
+        generic_syscall_number(name).or_else(|| legacy_syscall_number(name))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_syscall_names_resolve() {
+            assert_eq!(syscall_number("read"), Some(libc::SYS_read));
+            assert_eq!(syscall_number("socket"), Some(libc::SYS_socket));
+            assert_eq!(syscall_number("not_a_syscall"), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn module_info(directory: Option<&Path>) -> ModuleInfo {
+        ModuleInfo {
+            name: "lightning".to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            author: None,
+            capabilities: Vec::new(),
+            dependencies: HashMap::new(),
+            entry_point: "lightning".to_string(),
+            directory: directory.map(Path::to_path_buf),
+            binary_path: None,
+            config_schema: HashMap::new(),
+        }
+    }
+
+    fn config(enforcement: Enforcement, data_dirs: &[&Path]) -> SandboxConfig {
+        SandboxConfig {
+            enforcement,
+            data_dirs: data_dirs.iter().map(|d| d.to_path_buf()).collect(),
+        }
+    }
+
+    fn rule(profile: &SandboxProfile, path: &str) -> Option<FileAccess> {
+        profile
+            .file_rules
+            .iter()
+            .find(|r| r.path == Path::new(path))
+            .map(|r| r.access)
+    }
+
+    #[test]
+    fn test_permission_mapping() {
+        let info = module_info(Some(Path::new("/opt/modules/lightning")));
+        let data = config(Enforcement::Enforce, &[Path::new("/var/lib/lightning")]);
+
+        // No permissions: no network, read-only data
+        let profile = generate_profile(&info, &PermissionSet::new(), &data);
+        assert!(!profile.network);
+        assert_eq!(rule(&profile, "/var/lib/lightning"), Some(FileAccess::Read));
+        assert_eq!(
+            rule(&profile, "/opt/modules/lightning"),
+            Some(FileAccess::Read)
+        );
+        assert_eq!(rule(&profile, "/usr"), Some(FileAccess::Read));
+        assert_eq!(rule(&profile, "/home"), None);
+        assert!(profile.syscalls.contains(&"execve"));
+
+        // Network and data writes
+        let granted = PermissionSet::from_vec(vec![
+            Permission::NetworkAccess,
+            Permission::WriteFilesystem,
+            Permission::ReadBlockchain,
+        ]);
+        let profile = generate_profile(&info, &granted, &data);
+        assert!(profile.network);
+        assert_eq!(
+            rule(&profile, "/var/lib/lightning"),
+            Some(FileAccess::ReadWrite)
+        );
+        assert_eq!(profile.enforcement, Enforcement::Enforce);
+    }
+
+    #[test]
+    fn test_enforcement_from_toml() {
+        let parsed: SandboxConfig =
+            toml::from_str("enforcement = \"warn\"\ndata_dirs = [\"/var/lib/lightning\"]").unwrap();
+        assert_eq!(parsed.enforcement, Enforcement::Warn);
+        assert_eq!(SandboxConfig::default().enforcement, Enforcement::Off);
+        assert!(toml::from_str::<SandboxConfig>("enforcement = \"strict\"").is_err());
+    }
+
+    #[test]
+    fn test_warn_mode_only_reports() {
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("secret");
+        std::fs::write(&secret, "secret").unwrap();
+
+        let profile = generate_profile(
+            &module_info(None),
+            &PermissionSet::new(),
+            &config(Enforcement::Warn, &[]),
+        );
+        let mut command = Command::new("cat");
+        command.arg(&secret);
+        let warnings = profile.apply(&mut command).unwrap();
+
+        assert!(warnings
+            .iter()
+            .all(|w| w.starts_with("sandbox for module lightning not enforced")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("network sockets denied")));
+        assert_eq!(command.output().unwrap().stdout, b"secret");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_enforce_denies_disallowed_file() {
+        let allowed = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let data = allowed.path().join("data");
+        let secret = outside.path().join("secret");
+        std::fs::write(&data, "data").unwrap();
+        std::fs::write(&secret, "secret").unwrap();
+
+        let profile = generate_profile(
+            &module_info(None),
+            &PermissionSet::new(),
+            &config(Enforcement::Enforce, &[allowed.path()]),
+        );
+        let run = |path: &Path| {
+            let mut command = Command::new("cat");
+            command.arg(path);
+            profile
+                .apply(&mut command)
+                .map(|_| command.output().unwrap())
+        };
+
+        let output = run(&data).expect("sandbox must be available to test enforcement");
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"data");
+
+        let output = run(&secret).unwrap();
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+}
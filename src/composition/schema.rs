@@ -4,6 +4,7 @@
 
 use crate::composition::config::NodeConfig;
 use crate::composition::types::*;
+use serde_json::Value;
 
 /// Validate node configuration schema
 pub fn validate_config_schema(config: &NodeConfig) -> Result<ValidationResult> {
@@ -29,6 +30,12 @@ pub fn validate_config_schema(config: &NodeConfig) -> Result<ValidationResult> {
                 errors.push("Module name cannot be empty".to_string());
             }
 
+            for dependency in &module_cfg.external_dependencies {
+                if let Err(e) = dependency.validate() {
+                    errors.push(format!("Module '{}': {}", name, e));
+                }
+            }
+
             // Warn if version not specified
             if module_cfg.version.is_none() {
                 warnings.push(format!(
@@ -47,3 +54,47 @@ pub fn validate_config_schema(config: &NodeConfig) -> Result<ValidationResult> {
         dependencies: Vec::new(), // Will be populated during dependency resolution
     })
 }
+
+/// Validate a module configuration against the module's declared config schema
+///
+/// The schema maps option names to type names (`string`, `integer`, `number`,
+/// `boolean`, `array`, `object`). Modules without a schema accept any config.
+pub fn validate_module_config(info: &ModuleInfo, config: &Value) -> Result<()> {
+    if info.config_schema.is_empty() || config.is_null() {
+        return Ok(());
+    }
+
+    let options = config.as_object().ok_or_else(|| {
+        CompositionError::InvalidConfiguration(format!(
+            "Module '{}' configuration must be a table",
+            info.name
+        ))
+    })?;
+
+    for (key, value) in options {
+        let expected = info.config_schema.get(key).ok_or_else(|| {
+            CompositionError::InvalidConfiguration(format!(
+                "Module '{}' has no config option '{}'",
+                info.name, key
+            ))
+        })?;
+
+        let matches = match expected.as_str() {
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => true,
+        };
+        if !matches {
+            return Err(CompositionError::InvalidConfiguration(format!(
+                "Module '{}' config option '{}' must be of type {}",
+                info.name, key, expected
+            )));
+        }
+    }
+
+    Ok(())
+}
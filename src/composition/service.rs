@@ -0,0 +1,123 @@
+//! Module Service Settings
+//!
+//! How a module process is run: command-line arguments, environment,
+//! restart policy and resource limits.
+//!
+//! Arguments and environment values are templates. The placeholders
+//! `{node}`, `{network}`, `{module}`, `{version}` and `{module_dir}` are
+//! replaced with values from the composition.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// When a module process is restarted after it exits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Never restart
+    Never,
+    /// Restart after a non-zero exit or a crash
+    #[default]
+    OnFailure,
+    /// Always restart
+    Always,
+}
+
+/// Resource limits for a module process
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Memory limit (`512M`, `2G`, ...)
+    #[serde(default)]
+    pub memory: Option<String>,
+    /// CPU quota in percent of one CPU
+    #[serde(default)]
+    pub cpu_percent: Option<u32>,
+    /// Maximum open file descriptors
+    #[serde(default)]
+    pub open_files: Option<u64>,
+    /// Maximum number of tasks (processes and threads)
+    #[serde(default)]
+    pub tasks: Option<u64>,
+}
+
+/// Per-module service settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceConfig {
+    /// Command-line argument templates
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variable templates
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Restart policy
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Delay before a restart (CLI duration syntax, e.g. `5s`)
+    #[serde(default)]
+    pub restart_delay: Option<String>,
+    /// Resource limits
+    #[serde(default)]
+    pub limits: ResourceLimits,
+    /// User the module runs as (name or uid), for privilege separation
+    #[serde(default)]
+    pub run_as: Option<String>,
+}
+
+/// Values substituted into argument and environment templates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateContext {
+    pub node: String,
+    pub network: String,
+    pub module: String,
+    pub version: String,
+    pub module_dir: String,
+}
+
+impl TemplateContext {
+    /// Replace the placeholders in a template
+    pub fn render(&self, template: &str) -> String {
+        template
+            .replace("{node}", &self.node)
+            .replace("{network}", &self.network)
+            .replace("{module}", &self.module)
+            .replace("{version}", &self.version)
+            .replace("{module_dir}", &self.module_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_from_toml() {
+        let service: ServiceConfig = toml::from_str(
+            r#"
+            args = ["--network", "{network}"]
+            restart = "always"
+            limits = { memory = "512M", open_files = 4096 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(service.restart, RestartPolicy::Always);
+        assert_eq!(service.limits.memory.as_deref(), Some("512M"));
+        assert_eq!(service.limits.open_files, Some(4096));
+        assert_eq!(ServiceConfig::default().restart, RestartPolicy::OnFailure);
+    }
+
+    #[test]
+    fn test_render() {
+        let context = TemplateContext {
+            node: "my-node".to_string(),
+            network: "regtest".to_string(),
+            module: "lightning".to_string(),
+            version: "0.1.0".to_string(),
+            module_dir: "/opt/modules/lightning".to_string(),
+        };
+        assert_eq!(
+            context.render("{module_dir}/{module}-{version}.toml"),
+            "/opt/modules/lightning/lightning-0.1.0.toml"
+        );
+    }
+}
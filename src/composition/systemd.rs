@@ -0,0 +1,562 @@
+//! systemd Export
+//!
+//! Generates systemd units from a composition so systemd, rather than a
+//! long-running composer, supervises the modules.
+//!
+//! Each enabled module becomes `<node>-<module>.service`; a
+//! `<node>.target` groups them. Names are escaped like `systemd-escape`
+//! (`-` becomes `\x2d`), so the separator is unambiguous. Module dependencies become `After=` and
+//! `Requires=` edges, the restart policy and resource limits map to their
+//! systemd directives, and an enforced sandbox maps to systemd's own
+//! hardening options.
+
+use crate::composition::registry::ModuleRegistry;
+use crate::composition::sandbox::{generate_profile, Enforcement, FileAccess};
+use crate::composition::service::{RestartPolicy, TemplateContext};
+use crate::composition::types::*;
+use crate::module::security::permissions_from_capabilities;
+use crate::util::humanize::parse_duration;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Header written at the top of every generated unit
+const GENERATED_HEADER: &str = "# Generated by blvm-compose export-systemd. Do not edit.";
+
+/// A generated unit file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemdUnit {
+    /// Unit file name (`<node>-<module>.service`, `<node>.target`)
+    pub name: String,
+    /// Unit file contents
+    pub contents: String,
+}
+
+/// Escape a name for use in a unit name
+///
+/// Characters outside `[A-Za-z0-9:_.]` are written as `\xNN`. `-` is
+/// escaped too: it separates the node and module names in service names, so
+/// node `a-b` with module `c` and node `a` with module `b-c` stay distinct.
+pub fn escape_unit_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b':' | b'_' | b'.') {
+            escaped.push(byte as char);
+        } else {
+            let _ = write!(escaped, "\\x{:02x}", byte);
+        }
+    }
+    escaped
+}
+
+/// Quote a word for a command line or list directive
+///
+/// `%` is doubled so it is not read as a specifier; with `expand_variables`
+/// (as in `ExecStart=`) `$` is doubled too. Words that are empty or contain
+/// whitespace, quotes, backslashes or `;` are double-quoted with C-style
+/// escapes.
+fn quote(word: &str, expand_variables: bool) -> String {
+    let mut escaped = word.replace('%', "%%");
+    if expand_variables {
+        escaped = escaped.replace('$', "$$");
+    }
+
+    let needs_quotes = escaped.is_empty()
+        || escaped
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | ';'));
+    if !needs_quotes {
+        return escaped;
+    }
+
+    let mut quoted = String::with_capacity(escaped.len() + 2);
+    quoted.push('"');
+    for c in escaped.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escape `%` in free text so it is not read as a specifier
+fn escape_specifiers(text: &str) -> String {
+    text.replace('%', "%%")
+}
+
+fn network_name(network: NetworkType) -> &'static str {
+    match network {
+        NetworkType::Mainnet => "mainnet",
+        NetworkType::Testnet => "testnet",
+        NetworkType::Regtest => "regtest",
+    }
+}
+
+/// Unit name of a module's service
+pub fn service_name(node: &str, module: &str) -> String {
+    format!(
+        "{}-{}.service",
+        escape_unit_name(node),
+        escape_unit_name(module)
+    )
+}
+
+/// Unit name of the target grouping a node's modules
+pub fn target_name(node: &str) -> String {
+    format!("{}.target", escape_unit_name(node))
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    Ok(std::path::absolute(path)?)
+}
+
+fn validate_memory(module: &str, memory: &str) -> Result<()> {
+    let digits = memory
+        .strip_suffix(['K', 'M', 'G', 'T', '%'])
+        .unwrap_or(memory);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(CompositionError::InvalidConfiguration(format!(
+            "Module '{}' memory limit '{}': expected bytes with an optional K, M, G, T or % suffix",
+            module, memory
+        )));
+    }
+    Ok(())
+}
+
+/// Generate the service unit for one module
+fn service_unit(
+    spec: &NodeSpec,
+    module: &ModuleSpec,
+    info: &ModuleInfo,
+    planned: &BTreeSet<&str>,
+) -> Result<SystemdUnit> {
+    let binary_path = info.binary_path.as_ref().ok_or_else(|| {
+        CompositionError::ModuleNotFound(format!("Module {} has no binary path", info.name))
+    })?;
+    let binary_path = absolute(binary_path)?;
+    let module_dir = match &info.directory {
+        Some(directory) => Some(absolute(directory)?),
+        None => None,
+    };
+
+    let context = TemplateContext {
+        node: spec.name.clone(),
+        network: network_name(spec.network).to_string(),
+        module: info.name.clone(),
+        version: info.version.clone(),
+        module_dir: module_dir
+            .as_ref()
+            .map(|d| d.display().to_string())
+            .unwrap_or_default(),
+    };
+
+    let mut dependencies: Vec<&String> = info.dependencies.keys().collect();
+    dependencies.sort();
+    for dependency in &dependencies {
+        if !planned.contains(dependency.as_str()) {
+            return Err(CompositionError::DependencyResolutionFailed(format!(
+                "Module {} depends on {}, which is not enabled in the composition",
+                info.name, dependency
+            )));
+        }
+    }
+
+    let service = &module.service;
+    let mut unit = String::new();
+    let _ = writeln!(unit, "{}", GENERATED_HEADER);
+    let _ = writeln!(unit, "[Unit]");
+    let _ = writeln!(
+        unit,
+        "Description={}",
+        escape_specifiers(&format!(
+            "{} module {} {}",
+            spec.name, info.name, info.version
+        ))
+    );
+    let _ = writeln!(unit, "PartOf={}", target_name(&spec.name));
+    for dependency in &dependencies {
+        let dependency_unit = service_name(&spec.name, dependency);
+        let _ = writeln!(unit, "After={}", dependency_unit);
+        let _ = writeln!(unit, "Requires={}", dependency_unit);
+    }
+
+    let _ = writeln!(unit);
+    let _ = writeln!(unit, "[Service]");
+    let _ = writeln!(unit, "Type=simple");
+    if let Some(user) = &service.run_as {
+        let _ = writeln!(unit, "User={}", user);
+    }
+    let exec_start: Vec<String> = std::iter::once(binary_path.display().to_string())
+        .chain(service.args.iter().map(|arg| context.render(arg)))
+        .map(|word| quote(&word, true))
+        .collect();
+    let _ = writeln!(unit, "ExecStart={}", exec_start.join(" "));
+    for (key, value) in &service.env {
+        let assignment = format!("{}={}", key, context.render(value));
+        let _ = writeln!(unit, "Environment={}", quote(&assignment, false));
+    }
+    if let Some(module_dir) = &module_dir {
+        let _ = writeln!(
+            unit,
+            "WorkingDirectory={}",
+            escape_specifiers(&module_dir.display().to_string())
+        );
+    }
+
+    let restart = match service.restart {
+        RestartPolicy::Never => "no",
+        RestartPolicy::OnFailure => "on-failure",
+        RestartPolicy::Always => "always",
+    };
+    let _ = writeln!(unit, "Restart={}", restart);
+    if let Some(delay) = &service.restart_delay {
+        let delay = parse_duration(delay).map_err(|e| {
+            CompositionError::InvalidConfiguration(format!(
+                "Module '{}' restart_delay: {}",
+                info.name, e
+            ))
+        })?;
+        if delay.subsec_millis() == 0 {
+            let _ = writeln!(unit, "RestartSec={}s", delay.as_secs());
+        } else {
+            let _ = writeln!(unit, "RestartSec={}ms", delay.as_millis());
+        }
+    }
+
+    let limits = &service.limits;
+    if let Some(memory) = &limits.memory {
+        validate_memory(&info.name, memory)?;
+        let _ = writeln!(unit, "MemoryMax={}", memory);
+    }
+    if let Some(cpu_percent) = limits.cpu_percent {
+        let _ = writeln!(unit, "CPUQuota={}%", cpu_percent);
+    }
+    if let Some(open_files) = limits.open_files {
+        let _ = writeln!(unit, "LimitNOFILE={}", open_files);
+    }
+    if let Some(tasks) = limits.tasks {
+        let _ = writeln!(unit, "TasksMax={}", tasks);
+    }
+
+    if module.sandbox.enforcement == Enforcement::Enforce {
+        let profile = generate_profile(
+            info,
+            &permissions_from_capabilities(&info.capabilities),
+            &module.sandbox,
+        );
+        let _ = writeln!(unit, "NoNewPrivileges=yes");
+        let _ = writeln!(unit, "ProtectSystem=strict");
+        if !profile.network {
+            let _ = writeln!(unit, "RestrictAddressFamilies=AF_UNIX");
+        }
+        let writable: Vec<String> = profile
+            .file_rules
+            .iter()
+            .filter(|rule| rule.access == FileAccess::ReadWrite && !rule.path.starts_with("/dev"))
+            .map(|rule| quote(&rule.path.display().to_string(), false))
+            .collect();
+        if !writable.is_empty() {
+            let _ = writeln!(unit, "ReadWritePaths={}", writable.join(" "));
+        }
+    }
+
+    let _ = writeln!(unit);
+    let _ = writeln!(unit, "[Install]");
+    let _ = writeln!(unit, "WantedBy={}", target_name(&spec.name));
+
+    Ok(SystemdUnit {
+        name: service_name(&spec.name, &info.name),
+        contents: unit,
+    })
+}
+
+/// Generate units for every enabled module plus the grouping target
+///
+/// Units are returned sorted by name, with the target last.
+pub fn export_systemd(spec: &NodeSpec, registry: &ModuleRegistry) -> Result<Vec<SystemdUnit>> {
+    let modules: Vec<&ModuleSpec> = spec.modules.iter().filter(|m| m.enabled).collect();
+    let planned: BTreeSet<&str> = modules.iter().map(|m| m.name.as_str()).collect();
+
+    let mut units = Vec::with_capacity(modules.len() + 1);
+    for module in &modules {
+        let info = registry.get_module(&module.name, module.version.as_deref())?;
+        units.push(service_unit(spec, module, &info, &planned)?);
+    }
+    units.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut target = String::new();
+    let _ = writeln!(target, "{}", GENERATED_HEADER);
+    let _ = writeln!(target, "[Unit]");
+    let _ = writeln!(
+        target,
+        "Description={}",
+        escape_specifiers(&format!(
+            "{} composed node ({})",
+            spec.name,
+            network_name(spec.network)
+        ))
+    );
+    if !units.is_empty() {
+        let names: Vec<&str> = units.iter().map(|u| u.name.as_str()).collect();
+        let _ = writeln!(target, "Wants={}", names.join(" "));
+    }
+    let _ = writeln!(target);
+    let _ = writeln!(target, "[Install]");
+    let _ = writeln!(target, "WantedBy=multi-user.target");
+
+    units.push(SystemdUnit {
+        name: target_name(&spec.name),
+        contents: target,
+    });
+    Ok(units)
+}
+
+/// Write units into a directory, returning the written paths
+pub fn write_units(units: &[SystemdUnit], dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    units
+        .iter()
+        .map(|unit| {
+            let path = dir.join(&unit.name);
+            std::fs::write(&path, &unit.contents)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Check written units with `systemd-analyze verify`
+///
+/// Returns `Ok(false)` if `systemd-analyze` is not available.
+pub fn verify_units(paths: &[PathBuf]) -> Result<bool> {
+    let output = match Command::new("systemd-analyze")
+        .arg("verify")
+        .arg("--man=no")
+        .args(paths)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    if !output.status.success() {
+        return Err(CompositionError::ValidationFailed(format!(
+            "systemd-analyze verify failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::composition::sandbox::SandboxConfig;
+    use crate::composition::service::{ResourceLimits, ServiceConfig};
+    use std::collections::{BTreeMap, HashMap};
+
+    fn module_info(name: &str, dependencies: &[&str]) -> ModuleInfo {
+        ModuleInfo {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            author: None,
+            capabilities: Vec::new(),
+            dependencies: dependencies
+                .iter()
+                .map(|d| (d.to_string(), ">=0.1.0".to_string()))
+                .collect(),
+            entry_point: name.to_string(),
+            directory: Some(PathBuf::from(format!("/opt/modules/{}", name))),
+            binary_path: Some(PathBuf::from(format!("/opt/modules/{}/{}", name, name))),
+            config_schema: HashMap::new(),
+        }
+    }
+
+    fn module_spec(name: &str, service: ServiceConfig) -> ModuleSpec {
+        ModuleSpec {
+            name: name.to_string(),
+            version: None,
+            enabled: true,
+            config: HashMap::new(),
+            external_dependencies: Vec::new(),
+            sandbox: SandboxConfig::default(),
+            service,
+            data: Default::default(),
+        }
+    }
+
+    /// storage <- lightning <- dashboard
+    fn fixture() -> (NodeSpec, ModuleRegistry) {
+        let mut registry = ModuleRegistry::new("/opt/modules");
+        registry.register_module(module_info("storage", &[]));
+        registry.register_module(module_info("lightning", &["storage"]));
+        registry.register_module(module_info("dashboard", &["lightning", "storage"]));
+
+        let lightning = ServiceConfig {
+            args: vec![
+                "--network".to_string(),
+                "{network}".to_string(),
+                "--alias".to_string(),
+                "my node; 100% \"fast\" $HOME".to_string(),
+            ],
+            env: BTreeMap::from([(
+                "LIGHTNING_DIR".to_string(),
+                "{module_dir}/data dir".to_string(),
+            )]),
+            restart: RestartPolicy::Always,
+            restart_delay: Some("5s".to_string()),
+            limits: ResourceLimits {
+                memory: Some("512M".to_string()),
+                cpu_percent: Some(50),
+                open_files: Some(4096),
+                tasks: Some(64),
+            },
+            run_as: None,
+        };
+
+        let spec = NodeSpec {
+            name: "my-node".to_string(),
+            version: None,
+            network: NetworkType::Regtest,
+            modules: vec![
+                module_spec("lightning", lightning),
+                module_spec("storage", ServiceConfig::default()),
+                module_spec("dashboard", ServiceConfig::default()),
+            ],
+        };
+        (spec, registry)
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let (spec, registry) = fixture();
+        let units = export_systemd(&spec, &registry).unwrap();
+
+        let names: Vec<&str> = units.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "my\\x2dnode-dashboard.service",
+                "my\\x2dnode-lightning.service",
+                "my\\x2dnode-storage.service",
+                "my\\x2dnode.target",
+            ]
+        );
+
+        assert_eq!(
+            units[1].contents,
+            r#"# Generated by blvm-compose export-systemd. Do not edit.
+[Unit]
+Description=my-node module lightning 0.1.0
+PartOf=my\x2dnode.target
+After=my\x2dnode-storage.service
+Requires=my\x2dnode-storage.service
+
+[Service]
+Type=simple
+ExecStart=/opt/modules/lightning/lightning --network regtest --alias "my node; 100%% \"fast\" $$HOME"
+Environment="LIGHTNING_DIR=/opt/modules/lightning/data dir"
+WorkingDirectory=/opt/modules/lightning
+Restart=always
+RestartSec=5s
+MemoryMax=512M
+CPUQuota=50%
+LimitNOFILE=4096
+TasksMax=64
+
+[Install]
+WantedBy=my\x2dnode.target
+"#
+        );
+
+        assert_eq!(
+            units[3].contents,
+            r#"# Generated by blvm-compose export-systemd. Do not edit.
+[Unit]
+Description=my-node composed node (regtest)
+Wants=my\x2dnode-dashboard.service my\x2dnode-lightning.service my\x2dnode-storage.service
+
+[Install]
+WantedBy=multi-user.target
+"#
+        );
+    }
+
+    #[test]
+    fn test_dependency_ordering_matches_plan() {
+        let (spec, registry) = fixture();
+        let units = export_systemd(&spec, &registry).unwrap();
+
+        for module in &spec.modules {
+            let info = registry.get_module(&module.name, None).unwrap();
+            let unit = units
+                .iter()
+                .find(|u| u.name == service_name(&spec.name, &module.name))
+                .unwrap();
+
+            let after: BTreeSet<String> = unit
+                .contents
+                .lines()
+                .filter_map(|line| line.strip_prefix("After="))
+                .map(str::to_string)
+                .collect();
+            let requires: BTreeSet<String> = unit
+                .contents
+                .lines()
+                .filter_map(|line| line.strip_prefix("Requires="))
+                .map(str::to_string)
+                .collect();
+            let expected: BTreeSet<String> = info
+                .dependencies
+                .keys()
+                .map(|d| service_name(&spec.name, d))
+                .collect();
+
+            assert_eq!(after, expected, "{}", module.name);
+            assert_eq!(requires, expected, "{}", module.name);
+        }
+    }
+
+    #[test]
+    fn test_missing_dependency() {
+        let (mut spec, registry) = fixture();
+        spec.modules.retain(|m| m.name != "storage");
+
+        let err = export_systemd(&spec, &registry).unwrap_err();
+        assert!(err.to_string().contains("depends on storage"));
+    }
+
+    #[test]
+    fn test_enforced_sandbox() {
+        let (mut spec, registry) = fixture();
+        spec.modules[1].sandbox = SandboxConfig {
+            enforcement: Enforcement::Enforce,
+            data_dirs: Vec::new(),
+        };
+
+        let units = export_systemd(&spec, &registry).unwrap();
+        let storage = &units[2].contents;
+        assert!(storage.contains("NoNewPrivileges=yes\n"));
+        assert!(storage.contains("RestrictAddressFamilies=AF_UNIX\n"));
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("plain", true), "plain");
+        assert_eq!(quote("", true), "\"\"");
+        assert_eq!(quote("a b", false), "\"a b\"");
+        assert_eq!(quote("$HOME", true), "$$HOME");
+        assert_eq!(quote("$HOME", false), "$HOME");
+        assert_eq!(quote("back\\slash", false), "\"back\\\\slash\"");
+        assert_eq!(escape_unit_name("my node/1"), "my\\x20node\\x2f1");
+        assert_eq!(escape_unit_name("a-b"), "a\\x2db");
+        assert_ne!(service_name("a-b", "c"), service_name("a", "b-c"));
+    }
+}
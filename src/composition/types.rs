@@ -2,6 +2,10 @@
 //!
 //! Core types for module registry and node composition.
 
+use crate::composition::datadir::{DataDirConfig, DataDirStatus};
+use crate::composition::probe::ExternalDependency;
+use crate::composition::sandbox::SandboxConfig;
+use crate::composition::service::ServiceConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -41,6 +45,18 @@ pub enum ModuleSource {
     Registry(String),
     /// Install from git repository
     Git { url: String, tag: Option<String> },
+    /// Install from OCI image pinned by digest
+    Oci {
+        /// Image repository
+        repository: String,
+        /// Content digest (`sha256:<hex>`)
+        digest: String,
+        /// Optional image tag
+        tag: Option<String>,
+        /// Hex-encoded governance signatures over the image reference
+        #[serde(default)]
+        signatures: Vec<String>,
+    },
 }
 
 /// Module lifecycle status
@@ -109,6 +125,18 @@ pub struct ModuleSpec {
     /// Module-specific configuration
     #[serde(default)]
     pub config: HashMap<String, serde_json::Value>,
+    /// External services that must be available before the module starts
+    #[serde(default)]
+    pub external_dependencies: Vec<ExternalDependency>,
+    /// OS-level sandbox settings
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    /// Arguments, environment, restart policy and resource limits
+    #[serde(default)]
+    pub service: ServiceConfig,
+    /// Data directory and layout version
+    #[serde(default)]
+    pub data: DataDirConfig,
 }
 
 /// Loaded module information
@@ -120,6 +148,8 @@ pub struct LoadedModule {
     pub status: ModuleStatus,
     /// Module health
     pub health: ModuleHealth,
+    /// Prepared data directory, if the module keeps data
+    pub data_dir: Option<DataDirStatus>,
 }
 
 /// Composed node result
@@ -131,6 +161,8 @@ pub struct ComposedNode {
     pub modules: Vec<LoadedModule>,
     /// Overall node status
     pub status: NodeStatus,
+    /// Hash of the composition, including the active features
+    pub composition_hash: String,
 }
 
 /// Node status
@@ -187,6 +219,15 @@ pub enum CompositionError {
 
     #[error("Serialization error: {0}")]
     SerializationError(String),
+
+    #[error("Governance approval failed: {0}")]
+    GovernanceRejected(String),
+
+    #[error("External dependency unavailable: {0}")]
+    ExternalDependencyUnavailable(String),
+
+    #[error("Operation cancelled: {0}")]
+    Cancelled(String),
 }
 
 pub type Result<T> = std::result::Result<T, CompositionError>;
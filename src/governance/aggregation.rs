@@ -0,0 +1,1013 @@
+//! # Signature Aggregation
+//!
+//! The aggregate signature file written by `blvm-aggregate-signatures`: the
+//! signatures of several maintainers over the same artifact, collected into
+//! one JSON document.
+//!
+//! ```json
+//! {
+//!   "version": "1.0",
+//!   "signature_count": 2,
+//!   "signatures": [{"signature": "...", "signer": "...", "signed_at": "...", "public_key": "..."}],
+//!   "threshold": "2-of-3",
+//!   "metadata": {...},
+//!   "aggregated_at": "2024-01-01T00:00:00+00:00"
+//! }
+//! ```
+//!
+//! Aggregates from org-wide attestations can carry tens of thousands of
+//! signatures. [`AggregateReader`] and [`AggregateWriter`] process them one
+//! entry at a time; [`SignatureFile::read`] streams files larger than its
+//! in-memory limit, and [`SignatureTally`] keeps only what verification
+//! needs, however many entries are read.
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+use crate::governance::artifact::SignedArtifactMetadata;
+use crate::governance::history;
+use crate::governance::{
+    GovernanceError, GovernanceResult, PublicKey, Signature, SignedAttributes, SignedTimestamp,
+    Threshold,
+};
+use crate::util::fs::read_with_retry;
+
+/// Version of the aggregate signature format
+pub const AGGREGATION_FORMAT_VERSION: &str = "1.0";
+
+/// Largest signature file parsed in memory by default; larger files are
+/// streamed
+pub const IN_MEMORY_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// Default cap on the signatures one aggregation collects, so a huge
+/// input list cannot exhaust memory
+pub const DEFAULT_MAX_SIGNATURES: usize = 100_000;
+
+/// Largest single JSON value (one entry, or one header field) the streaming
+/// reader accepts
+const MAX_VALUE_SIZE: usize = 1024 * 1024;
+
+/// Aggregate entry for one signature file
+///
+/// Takes the `signature`, `timestamp_signature`, `attributes`,
+/// `attributes_signature` and `public_key` fields, and the signer and
+/// signing time from the top level or from `metadata`.
+pub fn signature_entry(signature_file: &Value) -> Value {
+    let field = |name: &str, metadata_name: &str| {
+        signature_file.get(name).or_else(|| {
+            signature_file
+                .get("metadata")
+                .and_then(|m| m.get(metadata_name))
+        })
+    };
+
+    let mut entry = serde_json::json!({
+        "signature": signature_file.get("signature"),
+        "signer": field("signer", "signer"),
+        "signed_at": field("created_at", "signed_at"),
+        "public_key": signature_file.get("public_key"),
+    });
+    for name in ["timestamp_signature", "attributes", "attributes_signature"] {
+        if let Some(value) = signature_file.get(name) {
+            entry[name] = value.clone();
+        }
+    }
+    entry
+}
+
+/// Read a signature file to aggregate
+///
+/// Transient read errors, as network filesystems produce, are retried a
+/// few times (see [`read_with_retry`]); permanent ones fail at once.
+pub fn read_signature_json(path: impl AsRef<Path>) -> GovernanceResult<Value> {
+    let path = path.as_ref();
+    read_signature_json_with(path, || File::open(path))
+}
+
+/// [`read_signature_json`] from the readers `open` returns
+///
+/// `path` names the file in errors.
+pub fn read_signature_json_with<R: Read>(
+    path: &Path,
+    open: impl FnMut() -> io::Result<R>,
+) -> GovernanceResult<Value> {
+    let contents = read_with_retry(open).map_err(|e| read_error(path, e))?;
+    serde_json::from_slice(&contents).map_err(|e| {
+        GovernanceError::Serialization(format!("Invalid signature file {}: {}", path.display(), e))
+    })
+}
+
+/// Build the aggregate signature document
+///
+/// `signatures` are entries as produced by [`signature_entry`]; `metadata`
+/// is usually the metadata of the first signature file.
+pub fn build_aggregation_json(
+    signatures: &[Value],
+    threshold: Option<Threshold>,
+    metadata: Option<&Value>,
+) -> Value {
+    serde_json::json!({
+        "version": AGGREGATION_FORMAT_VERSION,
+        "signature_count": signatures.len(),
+        "signatures": signatures,
+        "threshold": threshold,
+        "metadata": metadata,
+        "aggregated_at": chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Pull parser over an aggregate signature file
+///
+/// Top-level fields before `signatures` are parsed when the reader is
+/// created; entries are then returned one at a time by
+/// [`next_entry`](Self::next_entry). Fields after the array (in files written
+/// by [`build_aggregation_json`], `threshold` and `version`) are only
+/// available from [`finish`](Self::finish).
+pub struct AggregateReader<R> {
+    reader: R,
+    header: Map<String, Value>,
+    in_entries: bool,
+    seen_entries: bool,
+    entries_read: usize,
+}
+
+impl AggregateReader<BufReader<File>> {
+    /// Open an aggregate file
+    pub fn open(path: impl AsRef<Path>) -> GovernanceResult<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| read_error(path, e))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: BufRead> AggregateReader<R> {
+    /// Parse the header up to the start of the `signatures` array
+    pub fn new(reader: R) -> GovernanceResult<Self> {
+        let mut aggregate = Self {
+            reader,
+            header: Map::new(),
+            in_entries: false,
+            seen_entries: false,
+            entries_read: 0,
+        };
+        aggregate.skip_whitespace()?;
+        aggregate.expect(b'{')?;
+        aggregate.read_fields(true)?;
+        if !aggregate.seen_entries {
+            return Err(invalid("no signatures array"));
+        }
+        Ok(aggregate)
+    }
+
+    /// Top-level fields read so far
+    pub fn header(&self) -> &Map<String, Value> {
+        &self.header
+    }
+
+    /// Number of entries returned so far
+    pub fn entries_read(&self) -> usize {
+        self.entries_read
+    }
+
+    /// Next signature entry, or `None` after the last one
+    pub fn next_entry(&mut self) -> GovernanceResult<Option<Value>> {
+        if !self.in_entries {
+            return Ok(None);
+        }
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b']') {
+            self.bump();
+            self.in_entries = false;
+            self.read_fields(false)?;
+            self.skip_whitespace()?;
+            if let Some(byte) = self.peek()? {
+                return Err(invalid(&format!(
+                    "trailing data after the aggregate ({})",
+                    byte as char
+                )));
+            }
+            return Ok(None);
+        }
+        if self.entries_read > 0 {
+            self.expect(b',')?;
+            self.skip_whitespace()?;
+        }
+        let entry = self.read_value()?;
+        self.entries_read += 1;
+        Ok(Some(entry))
+    }
+
+    /// Skip any remaining entries and return the complete header
+    ///
+    /// Fails when the file declares a `signature_count` other than the
+    /// number of entries.
+    pub fn finish(mut self) -> GovernanceResult<Map<String, Value>> {
+        while self.next_entry()?.is_some() {}
+        check_signature_count(&self.header, self.entries_read)?;
+        Ok(self.header)
+    }
+
+    /// Read `"key": value` members until the `signatures` array or the end
+    /// of the object
+    fn read_fields(&mut self, mut first: bool) -> GovernanceResult<()> {
+        loop {
+            self.skip_whitespace()?;
+            if self.peek()? == Some(b'}') {
+                self.bump();
+                return Ok(());
+            }
+            if !first {
+                self.expect(b',')?;
+                self.skip_whitespace()?;
+            }
+            first = false;
+
+            let key: String = self.read_value()?;
+            self.skip_whitespace()?;
+            self.expect(b':')?;
+            self.skip_whitespace()?;
+            if key == "signatures" {
+                if self.seen_entries {
+                    return Err(invalid("duplicate signatures array"));
+                }
+                self.expect(b'[')?;
+                self.in_entries = true;
+                self.seen_entries = true;
+                return Ok(());
+            }
+            let value = self.read_value()?;
+            self.header.insert(key, value);
+        }
+    }
+
+    /// Copy the bytes of one JSON value and parse them
+    fn read_value<T: DeserializeOwned>(&mut self) -> GovernanceResult<T> {
+        let mut bytes = Vec::new();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        while let Some(byte) = self.peek()? {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                }
+            } else {
+                match byte {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' if depth == 0 => break,
+                    b'}' | b']' => depth -= 1,
+                    b',' if depth == 0 => break,
+                    byte if byte.is_ascii_whitespace() && depth == 0 => break,
+                    _ => {}
+                }
+            }
+            bytes.push(byte);
+            self.bump();
+            if bytes.len() > MAX_VALUE_SIZE {
+                return Err(invalid(&format!(
+                    "value larger than {} bytes",
+                    MAX_VALUE_SIZE
+                )));
+            }
+            // A string or container at the top level ends with its closing byte
+            if depth == 0 && !in_string && matches!(byte, b'"' | b'}' | b']') {
+                break;
+            }
+        }
+
+        serde_json::from_slice(&bytes).map_err(|e| invalid(&e.to_string()))
+    }
+
+    fn peek(&mut self) -> GovernanceResult<Option<u8>> {
+        let buffer = self.reader.fill_buf().map_err(|e| {
+            GovernanceError::InvalidInput(format!("Failed to read aggregate: {}", e))
+        })?;
+        Ok(buffer.first().copied())
+    }
+
+    fn bump(&mut self) {
+        self.reader.consume(1);
+    }
+
+    fn skip_whitespace(&mut self) -> GovernanceResult<()> {
+        while matches!(self.peek()?, Some(byte) if byte.is_ascii_whitespace()) {
+            self.bump();
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, expected: u8) -> GovernanceResult<()> {
+        match self.peek()? {
+            Some(byte) if byte == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(byte) => Err(invalid(&format!(
+                "expected '{}', found '{}'",
+                expected as char, byte as char
+            ))),
+            None => Err(invalid(&format!(
+                "expected '{}', found end of file",
+                expected as char
+            ))),
+        }
+    }
+}
+
+/// Streaming counterpart of [`build_aggregation_json`]
+///
+/// Writes the header first, then each appended entry, and the
+/// `signature_count` last once it is known.
+pub struct AggregateWriter<W: Write> {
+    writer: W,
+    signature_count: usize,
+}
+
+impl<W: Write> AggregateWriter<W> {
+    /// Write the header and open the `signatures` array
+    pub fn new(
+        mut writer: W,
+        threshold: Option<Threshold>,
+        metadata: Option<&Value>,
+    ) -> GovernanceResult<Self> {
+        let header = |name: &str, value: Value| format!("{}:{}", Value::from(name), value);
+        let fields = [
+            header("version", AGGREGATION_FORMAT_VERSION.into()),
+            header("threshold", serde_json::json!(threshold)),
+            header("metadata", serde_json::json!(metadata)),
+            header("aggregated_at", Utc::now().to_rfc3339().into()),
+        ];
+        write!(writer, "{{{},\"signatures\":[", fields.join(",")).map_err(write_error)?;
+        Ok(Self {
+            writer,
+            signature_count: 0,
+        })
+    }
+
+    /// Append one entry, as produced by [`signature_entry`]
+    pub fn append(&mut self, entry: &Value) -> GovernanceResult<()> {
+        let separator = if self.signature_count == 0 { "" } else { "," };
+        write!(self.writer, "{}\n{}", separator, entry).map_err(write_error)?;
+        self.signature_count += 1;
+        Ok(())
+    }
+
+    /// Number of entries appended
+    pub fn signature_count(&self) -> usize {
+        self.signature_count
+    }
+
+    /// Close the document and flush the writer
+    pub fn finish(mut self) -> GovernanceResult<W> {
+        writeln!(
+            self.writer,
+            "\n],\"signature_count\":{}}}",
+            self.signature_count
+        )
+        .and_then(|_| self.writer.flush())
+        .map_err(write_error)?;
+        Ok(self.writer)
+    }
+}
+
+/// Signatures read from a signature file (`signature`) or an aggregate
+/// (`signatures`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureFile {
+    /// Signatures, in file order
+    pub signatures: Vec<Signature>,
+    /// Signing times recorded for the signatures
+    pub signed_at: Vec<DateTime<Utc>>,
+    /// Signing times attested by the signers
+    pub timestamps: Vec<SignedTimestamp>,
+    /// Attributes attested by the signers
+    pub attributes: Vec<SignedAttributes>,
+    /// Target hash recorded at the top level
+    pub file_hash: Option<String>,
+    /// Artifact metadata recorded at the top level, if any
+    pub metadata: Option<SignedArtifactMetadata>,
+}
+
+impl SignatureFile {
+    /// Read a signature file, streaming aggregates larger than
+    /// `in_memory_limit` bytes
+    pub fn read(path: impl AsRef<Path>, in_memory_limit: u64) -> GovernanceResult<Self> {
+        let path = path.as_ref();
+        if file_size(path)? > in_memory_limit {
+            Self::read_streaming(path)
+        } else {
+            Self::read_in_memory(path, in_memory_limit)
+        }
+    }
+
+    /// Parse a whole file in memory, refusing files larger than `limit` bytes
+    pub fn read_in_memory(path: impl AsRef<Path>, limit: u64) -> GovernanceResult<Self> {
+        let path = path.as_ref();
+        let size = file_size(path)?;
+        if size > limit {
+            return Err(GovernanceError::InvalidInput(format!(
+                "{} is {} bytes, over the in-memory limit of {} bytes",
+                path.display(),
+                size,
+                limit
+            )));
+        }
+        Self::from_json(&std::fs::read(path).map_err(|e| read_error(path, e))?)
+    }
+
+    /// Parse an aggregate one entry at a time
+    pub fn read_streaming(path: impl AsRef<Path>) -> GovernanceResult<Self> {
+        let mut reader = AggregateReader::open(path)?;
+        let mut contents = Self::empty();
+        while let Some(entry) = reader.next_entry()? {
+            contents.push_entry(&entry)?;
+        }
+        let header = reader.finish()?;
+        contents.metadata = SignedArtifactMetadata::from_field(header.get("metadata"))?;
+        contents.file_hash = file_hash(&header);
+        Ok(contents)
+    }
+
+    /// Parse a signature file or aggregate held in memory
+    pub fn from_json(json: &[u8]) -> GovernanceResult<Self> {
+        let value: Value = serde_json::from_slice(json).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid signature file: {}", e))
+        })?;
+
+        let mut contents = Self::empty();
+        match value.get("signatures") {
+            Some(entries) => {
+                let entries = entries
+                    .as_array()
+                    .ok_or_else(|| invalid("signatures is not an array"))?;
+                let header = value.as_object().expect("has a signatures field");
+                check_signature_count(header, entries.len())?;
+                for entry in entries {
+                    contents.push_entry(entry)?;
+                }
+            }
+            None => {
+                contents.signatures.push(entry_signature(&value)?);
+                contents.signed_at.extend(history::signed_at(&value));
+                contents
+                    .timestamps
+                    .extend(SignedTimestamp::from_entry(&value)?);
+                contents
+                    .attributes
+                    .extend(SignedAttributes::from_entry(&value)?);
+            }
+        }
+        contents.metadata = SignedArtifactMetadata::from_field(value.get("metadata"))?;
+        contents.file_hash = value.as_object().and_then(file_hash);
+        Ok(contents)
+    }
+
+    fn empty() -> Self {
+        Self {
+            signatures: Vec::new(),
+            signed_at: Vec::new(),
+            timestamps: Vec::new(),
+            attributes: Vec::new(),
+            file_hash: None,
+            metadata: None,
+        }
+    }
+
+    fn push_entry(&mut self, entry: &Value) -> GovernanceResult<()> {
+        self.signatures.push(entry_signature(entry)?);
+        self.signed_at.extend(entry_signed_at(entry));
+        self.timestamps.extend(SignedTimestamp::from_entry(entry)?);
+        self.attributes.extend(SignedAttributes::from_entry(entry)?);
+        Ok(())
+    }
+}
+
+/// Signatures reduced to what verification against known keys needs
+///
+/// Signatures are checked against the candidate keys as they are added. One
+/// valid under none of the keys is only counted in
+/// [`rejected`](Self::rejected); of the signatures by one key only the first
+/// is kept, of its signed timestamps only the latest, and of its attested
+/// attributes each distinct attestation. Verifying the kept
+/// signatures finds the same signers and signing times as verifying every
+/// entry, while memory grows with the number of keys instead of entries.
+#[derive(Debug, Clone)]
+pub struct SignatureTally {
+    message: Vec<u8>,
+    keys: Vec<PublicKey>,
+    signatures: BTreeMap<usize, Signature>,
+    timestamps: BTreeMap<usize, SignedTimestamp>,
+    attributes: BTreeMap<usize, Vec<SignedAttributes>>,
+    /// Signatures added
+    pub entries: usize,
+    /// Signatures valid under none of the candidate keys
+    pub rejected: usize,
+    /// Latest signing time recorded with the signatures (unsigned)
+    pub latest_signed_at: Option<DateTime<Utc>>,
+    /// Target hashes recorded at the top level of the files
+    pub recorded_hashes: Vec<String>,
+}
+
+impl SignatureTally {
+    /// Tally signatures over `message` against the candidate `keys`
+    pub fn new(message: &[u8], keys: &[PublicKey]) -> Self {
+        Self {
+            message: message.to_vec(),
+            keys: keys.to_vec(),
+            signatures: BTreeMap::new(),
+            timestamps: BTreeMap::new(),
+            attributes: BTreeMap::new(),
+            entries: 0,
+            rejected: 0,
+            latest_signed_at: None,
+            recorded_hashes: Vec::new(),
+        }
+    }
+
+    /// Add a signature file or aggregate, streaming files larger than
+    /// `in_memory_limit` bytes one entry at a time
+    pub fn add_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        in_memory_limit: u64,
+    ) -> GovernanceResult<()> {
+        let path = path.as_ref();
+        if file_size(path)? > in_memory_limit {
+            let mut reader = AggregateReader::open(path)?;
+            while let Some(entry) = reader.next_entry()? {
+                self.add_signature(entry_signature(&entry)?);
+                self.latest_signed_at = self.latest_signed_at.max(entry_signed_at(&entry));
+                if let Some(timestamp) = SignedTimestamp::from_entry(&entry)? {
+                    self.add_timestamp(timestamp);
+                }
+                if let Some(attributes) = SignedAttributes::from_entry(&entry)? {
+                    self.add_attributes(attributes);
+                }
+            }
+            let header = reader.finish()?;
+            SignedArtifactMetadata::from_field(header.get("metadata"))?;
+            self.recorded_hashes.extend(file_hash(&header));
+        } else {
+            let file = SignatureFile::read_in_memory(path, in_memory_limit)?;
+            file.signatures
+                .into_iter()
+                .for_each(|signature| self.add_signature(signature));
+            file.timestamps
+                .into_iter()
+                .for_each(|timestamp| self.add_timestamp(timestamp));
+            file.attributes
+                .into_iter()
+                .for_each(|attributes| self.add_attributes(attributes));
+            self.latest_signed_at = self.latest_signed_at.max(file.signed_at.into_iter().max());
+            self.recorded_hashes.extend(file.file_hash);
+        }
+        Ok(())
+    }
+
+    /// Add one signature
+    pub fn add_signature(&mut self, signature: Signature) {
+        self.entries += 1;
+        match self.signer(|key| {
+            crate::governance::verify_signature(&signature, &self.message, key).unwrap_or(false)
+        }) {
+            Some(index) => {
+                self.signatures.entry(index).or_insert(signature);
+            }
+            None => self.rejected += 1,
+        }
+    }
+
+    /// Add one signed timestamp
+    pub fn add_timestamp(&mut self, timestamp: SignedTimestamp) {
+        if let Some(index) = self.signer(|key| timestamp.verify(&self.message, key)) {
+            let latest = self.timestamps.entry(index).or_insert(timestamp.clone());
+            if timestamp.signed_at > latest.signed_at {
+                *latest = timestamp;
+            }
+        }
+    }
+
+    /// Add one attribute attestation
+    pub fn add_attributes(&mut self, attributes: SignedAttributes) {
+        if let Some(index) = self.signer(|key| attributes.verify(&self.message, key)) {
+            let kept = self.attributes.entry(index).or_default();
+            if !kept.contains(&attributes) {
+                kept.push(attributes);
+            }
+        }
+    }
+
+    /// Kept signatures, at most one per candidate key
+    pub fn signatures(&self) -> Vec<Signature> {
+        self.signatures.values().cloned().collect()
+    }
+
+    /// Latest signed timestamp of each candidate key that has one
+    pub fn timestamps(&self) -> Vec<SignedTimestamp> {
+        self.timestamps.values().cloned().collect()
+    }
+
+    /// Attribute attestations of the candidate keys
+    pub fn attributes(&self) -> Vec<SignedAttributes> {
+        self.attributes.values().flatten().cloned().collect()
+    }
+
+    fn signer(&self, signed_by: impl Fn(&PublicKey) -> bool) -> Option<usize> {
+        self.keys.iter().position(signed_by)
+    }
+}
+
+/// Identity of an entry's signer, for deduplication
+///
+/// The compressed bytes of the entry's `public_key`, so that one key is
+/// recognized however it is encoded. Entries without a public key are
+/// identified by their signature bytes instead.
+pub fn entry_signer(entry: &Value) -> GovernanceResult<Vec<u8>> {
+    match entry.get("public_key") {
+        None | Some(Value::Null) => Ok(entry_signature(entry)?.to_bytes().to_vec()),
+        Some(public_key) => {
+            let bytes = public_key
+                .as_str()
+                .ok_or_else(|| GovernanceError::InvalidKey("Invalid public key".to_string()))
+                .and_then(|hex_key| {
+                    hex::decode(hex_key).map_err(|e| {
+                        GovernanceError::InvalidKey(format!("Invalid public key hex: {}", e))
+                    })
+                })?;
+            Ok(PublicKey::from_bytes(&bytes)?.to_bytes().to_vec())
+        }
+    }
+}
+
+/// Decode the hex `signature` field of a signature file or aggregate entry
+pub fn entry_signature(entry: &Value) -> GovernanceResult<Signature> {
+    let signature_hex = entry["signature"].as_str().ok_or_else(|| {
+        GovernanceError::InvalidSignatureFormat("Invalid signature file format".to_string())
+    })?;
+    let bytes = hex::decode(signature_hex)
+        .map_err(|e| GovernanceError::InvalidSignatureFormat(e.to_string()))?;
+    Signature::from_bytes(&bytes)
+}
+
+fn entry_signed_at(entry: &Value) -> Option<DateTime<Utc>> {
+    entry["signed_at"]
+        .as_str()
+        .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn check_signature_count(header: &Map<String, Value>, entries: usize) -> GovernanceResult<()> {
+    match header.get("signature_count").and_then(Value::as_u64) {
+        Some(declared) if declared != entries as u64 => Err(invalid(&format!(
+            "declares {} signatures but contains {}",
+            declared, entries
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn file_hash(header: &Map<String, Value>) -> Option<String> {
+    header
+        .get("file_hash")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn file_size(path: &Path) -> GovernanceResult<u64> {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .map_err(|e| read_error(path, e))
+}
+
+fn invalid(reason: &str) -> GovernanceError {
+    GovernanceError::Serialization(format!("Invalid aggregate file: {}", reason))
+}
+
+fn read_error(path: &Path, error: io::Error) -> GovernanceError {
+    GovernanceError::InvalidInput(format!("Failed to read {}: {}", path.display(), error))
+}
+
+fn write_error(error: io::Error) -> GovernanceError {
+    GovernanceError::InvalidInput(format!("Failed to write aggregate: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::signatures::sign_message;
+    use crate::governance::GovernanceKeypair;
+    use std::cell::Cell;
+
+    /// Aggregate entries over a few keys, each entry with its own signer
+    fn fixture_entries(count: usize) -> Vec<Value> {
+        let keypairs: Vec<GovernanceKeypair> = (0..4)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let signed: Vec<(String, String)> = keypairs
+            .iter()
+            .map(|keypair| {
+                let signature = sign_message(keypair.secret_key.expose(), b"release").unwrap();
+                (
+                    hex::encode(signature.to_bytes()),
+                    hex::encode(keypair.public_key_bytes()),
+                )
+            })
+            .collect();
+
+        (0..count)
+            .map(|i| {
+                let (signature, public_key) = &signed[i % signed.len()];
+                signature_entry(&serde_json::json!({
+                    "signature": signature,
+                    "public_key": public_key,
+                    "signer": format!("maintainer-{}", i),
+                    "created_at": format!("2024-01-01T00:00:{:02}Z", i % 60),
+                }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_aggregation_json() {
+        let files = [
+            serde_json::json!({
+                "signature": "aa",
+                "public_key": "02aa",
+                "signer": "alice",
+                "created_at": "2024-01-01T00:00:00Z",
+                "metadata": {"file": "node.tar.gz"},
+            }),
+            serde_json::json!({
+                "signature": "bb",
+                "public_key": "02bb",
+                "metadata": {"signer": "bob", "signed_at": "2024-01-02T00:00:00Z"},
+                "timestamp_signature": "cc",
+            }),
+        ];
+        let signatures: Vec<Value> = files.iter().map(signature_entry).collect();
+        let threshold = Threshold::new(2, 3).unwrap();
+
+        let aggregated =
+            build_aggregation_json(&signatures, Some(threshold), files[0].get("metadata"));
+
+        assert_eq!(aggregated["version"], "1.0");
+        assert_eq!(aggregated["signature_count"], 2);
+        assert_eq!(aggregated["threshold"], "2-of-3");
+        assert_eq!(aggregated["metadata"]["file"], "node.tar.gz");
+        assert_eq!(
+            aggregated["signatures"],
+            serde_json::json!([
+                {"signature": "aa", "signer": "alice", "signed_at": "2024-01-01T00:00:00Z", "public_key": "02aa"},
+                {"signature": "bb", "signer": "bob", "signed_at": "2024-01-02T00:00:00Z", "public_key": "02bb", "timestamp_signature": "cc"},
+            ])
+        );
+        assert!(aggregated["aggregated_at"].is_string());
+
+        let unconstrained = build_aggregation_json(&[], None, None);
+        assert_eq!(unconstrained["signature_count"], 0);
+        assert!(unconstrained["threshold"].is_null());
+    }
+
+    #[test]
+    fn test_streaming_matches_in_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aggregate.json");
+        let entries = fixture_entries(50_000);
+        let metadata = serde_json::json!({
+            "type": "checksums",
+            "file_hash": "00".repeat(32),
+            "signed_at": "2024-01-15T10:30:00Z",
+        });
+        let aggregate =
+            build_aggregation_json(&entries, Threshold::new(3, 5).ok(), Some(&metadata));
+        std::fs::write(&path, serde_json::to_string_pretty(&aggregate).unwrap()).unwrap();
+
+        let mut reader = AggregateReader::open(&path).unwrap();
+        assert_eq!(reader.header()["metadata"], metadata);
+        let mut expected = aggregate["signatures"].as_array().unwrap().iter();
+        while let Some(entry) = reader.next_entry().unwrap() {
+            assert_eq!(Some(&entry), expected.next());
+        }
+        assert!(expected.next().is_none());
+        assert_eq!(reader.entries_read(), 50_000);
+        let mut header = aggregate.as_object().unwrap().clone();
+        header.remove("signatures");
+        assert_eq!(reader.finish().unwrap(), header);
+
+        // Under a cap the in-memory path refuses, the file is streamed
+        let cap = std::fs::metadata(&path).unwrap().len() / 4;
+        let err = SignatureFile::read_in_memory(&path, cap).unwrap_err();
+        assert!(err.to_string().contains("over the in-memory limit"));
+        let streamed = SignatureFile::read(&path, cap).unwrap();
+        assert_eq!(streamed.signatures.len(), 50_000);
+        assert_eq!(
+            streamed.metadata,
+            Some(SignedArtifactMetadata::from_value(&metadata).unwrap())
+        );
+        assert_eq!(
+            streamed,
+            SignatureFile::read_in_memory(&path, u64::MAX).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_streaming_writer() {
+        let entries = fixture_entries(3);
+        let metadata = serde_json::json!({"file": "node.tar.gz"});
+        let threshold = Threshold::new(2, 3).ok();
+
+        let mut writer = AggregateWriter::new(Vec::new(), threshold, Some(&metadata)).unwrap();
+        for entry in &entries {
+            writer.append(entry).unwrap();
+        }
+        assert_eq!(writer.signature_count(), 3);
+        let written = writer.finish().unwrap();
+
+        let mut parsed: Value = serde_json::from_slice(&written).unwrap();
+        let mut expected = build_aggregation_json(&entries, threshold, Some(&metadata));
+        parsed["aggregated_at"] = Value::Null;
+        expected["aggregated_at"] = Value::Null;
+        assert_eq!(parsed, expected);
+
+        // Everything but the count precedes the entries
+        let mut reader = AggregateReader::new(&written[..]).unwrap();
+        assert_eq!(reader.header()["threshold"], "2-of-3");
+        assert!(!reader.header().contains_key("signature_count"));
+        assert_eq!(reader.next_entry().unwrap().as_ref(), Some(&entries[0]));
+        assert_eq!(reader.finish().unwrap()["signature_count"], 3);
+
+        let empty = AggregateWriter::new(Vec::new(), None, None)
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert!(SignatureFile::from_json(&empty)
+            .unwrap()
+            .signatures
+            .is_empty());
+    }
+
+    #[test]
+    fn test_aggregates_through_transient_read_errors() {
+        /// Fails the first two reads with `WouldBlock`
+        struct FlakyReader<'a> {
+            contents: &'a [u8],
+            failures: &'a Cell<u32>,
+        }
+
+        impl Read for FlakyReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.failures.get() > 0 {
+                    self.failures.set(self.failures.get() - 1);
+                    return Err(io::ErrorKind::WouldBlock.into());
+                }
+                self.contents.read(buf)
+            }
+        }
+
+        let entries = fixture_entries(2);
+        let files: Vec<Vec<u8>> = entries
+            .iter()
+            .map(|entry| serde_json::to_vec(entry).unwrap())
+            .collect();
+        let path = Path::new("nfs/alice.sig.json");
+
+        let mut writer = AggregateWriter::new(Vec::new(), None, None).unwrap();
+        for file in &files {
+            let failures = Cell::new(2);
+            let json = read_signature_json_with(path, || {
+                Ok(FlakyReader {
+                    contents: file,
+                    failures: &failures,
+                })
+            })
+            .unwrap();
+            writer.append(&signature_entry(&json)).unwrap();
+        }
+        let aggregate = SignatureFile::from_json(&writer.finish().unwrap()).unwrap();
+        assert_eq!(aggregate.signatures.len(), 2);
+
+        // A missing file fails without retrying
+        let err = read_signature_json(Path::new("missing.sig.json")).unwrap_err();
+        assert!(err.to_string().contains("missing.sig.json"));
+    }
+
+    #[test]
+    fn test_reader_rejects_malformed_aggregates() {
+        let entry = fixture_entries(1).remove(0);
+        let aggregate =
+            |body: String| AggregateReader::new(body.as_bytes()).and_then(AggregateReader::finish);
+
+        assert!(aggregate(format!(r#"{{"signatures": [{}]}}"#, entry)).is_ok());
+        assert!(aggregate(r#"{"version": "1.0"}"#.to_string()).is_err());
+        assert!(aggregate(format!(r#"{{"signatures": [{}, "#, entry)).is_err());
+        assert!(aggregate(format!(r#"{{"signatures": [{} {}]}}"#, entry, entry)).is_err());
+        assert!(aggregate(format!(r#"{{"signatures": [{}]}} {{}}"#, entry)).is_err());
+        assert!(aggregate(r#"{"signatures": [], "signatures": []}"#.to_string()).is_err());
+
+        // Both paths check the declared count
+        let miscounted = format!(r#"{{"signature_count": 2, "signatures": [{}]}}"#, entry);
+        let err = aggregate(miscounted.clone()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("declares 2 signatures but contains 1"));
+        assert!(SignatureFile::from_json(miscounted.as_bytes()).is_err());
+
+        // A single signature file is not an aggregate, but is a signature file
+        assert!(aggregate(entry.to_string()).is_err());
+        assert_eq!(
+            SignatureFile::from_json(entry.to_string().as_bytes())
+                .unwrap()
+                .signatures
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_tally_matches_full_verification() {
+        use crate::governance::pipeline::{SignatureStage, VerificationContext, VerificationStage};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aggregate.json");
+        let mut entries = fixture_entries(2_000);
+        let keys: Vec<PublicKey> = entries[..4]
+            .iter()
+            .map(|entry| {
+                PublicKey::from_bytes(&hex::decode(entry["public_key"].as_str().unwrap()).unwrap())
+                    .unwrap()
+            })
+            .collect();
+        // Two entries by a key outside the candidates
+        let outsider = GovernanceKeypair::generate().unwrap();
+        let forged = hex::encode(
+            sign_message(outsider.secret_key.expose(), b"release")
+                .unwrap()
+                .to_bytes(),
+        );
+        for entry in &mut entries[10..12] {
+            entry["signature"] = forged.clone().into();
+        }
+        let aggregate = build_aggregation_json(&entries, None, None);
+        std::fs::write(&path, aggregate.to_string()).unwrap();
+
+        let verify = |signatures: Vec<Signature>, rejected: usize| {
+            let mut ctx = VerificationContext::new(b"release".to_vec(), "release", "");
+            ctx.signatures = signatures;
+            ctx.rejected_signatures = rejected;
+            ctx.public_keys = keys[..3].to_vec();
+            let result = SignatureStage.check(&mut ctx);
+            (result, ctx.valid_signers, ctx.invalid_signatures)
+        };
+        let full = SignatureFile::read_in_memory(&path, u64::MAX).unwrap();
+
+        // Streamed under a small cap, keeping one signature per key
+        let mut tally = SignatureTally::new(b"release", &keys[..3]);
+        tally.add_file(&path, 1024).unwrap();
+        assert_eq!(tally.entries, 2_000);
+        assert_eq!(tally.signatures().len(), 3);
+        assert_eq!(tally.latest_signed_at, full.signed_at.iter().max().copied());
+        assert_eq!(
+            verify(tally.signatures(), tally.rejected),
+            verify(full.signatures, 0)
+        );
+    }
+
+    #[test]
+    fn test_entry_signer() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let compressed = hex::encode(keypair.public_key_bytes());
+        let uncompressed = hex::encode(keypair.public_key().to_uncompressed_bytes());
+        let signature = hex::encode(
+            sign_message(keypair.secret_key.expose(), b"m")
+                .unwrap()
+                .to_bytes(),
+        );
+
+        let entry = |public_key: Value| serde_json::json!({"signature": signature, "public_key": public_key});
+        let signer = entry_signer(&entry(compressed.clone().into())).unwrap();
+        assert_eq!(signer, keypair.public_key_bytes());
+        assert_eq!(
+            entry_signer(&entry(compressed.to_uppercase().into())).unwrap(),
+            signer
+        );
+        assert_eq!(entry_signer(&entry(uncompressed.into())).unwrap(), signer);
+        assert!(entry_signer(&entry("02zz".into())).is_err());
+
+        // Without a public key, the signature identifies the entry
+        assert_eq!(
+            entry_signer(&entry(Value::Null)).unwrap(),
+            hex::decode(&signature).unwrap()
+        );
+    }
+}
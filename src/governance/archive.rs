@@ -0,0 +1,376 @@
+//! # Release Archives
+//!
+//! Verification of release archives (`.tar.gz`, `.zip`) that carry their own
+//! signature file.
+//!
+//! The embedded signature file is a signature or aggregate file with an added
+//! `manifest` object mapping member paths to SHA256 hashes. The maintainers
+//! sign it as a [`Manifest`]: the signed message is
+//! `checksums:<digest>[:version]`.
+//!
+//! Members are read by streaming: the signature member is the only one
+//! buffered, and every other member is hashed as it is decompressed, so
+//! nothing is written to disk.
+//!
+//! The signature file cannot contain its own hash, so by convention it is
+//! excluded from the manifest check. A manifest entry for it is ignored.
+//!
+//! Only regular files and directories are accepted. A symlink, hardlink or
+//! device member, or two members with the same normalized path, make the
+//! archive invalid rather than unlisted.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::history;
+use crate::governance::manifest::{hash_reader, normalize_path, Manifest, MemberMismatch};
+use crate::governance::{Signature, SignedAttributes, SignedTimestamp};
+
+/// Largest signature member read from an archive (16 MiB)
+pub const MAX_SIGNATURE_MEMBER_SIZE: u64 = 16 * 1024 * 1024;
+
+/// File type bits of a zip member's Unix mode
+const S_IFMT: u32 = 0o170000;
+const S_IFREG: u32 = 0o100000;
+const S_IFLNK: u32 = 0o120000;
+
+/// Archive container format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Gzip-compressed tar (`.tar.gz`, `.tgz`)
+    TarGz,
+    /// Zip (`.zip`)
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detect the format from the file extension
+    pub fn from_path(path: &Path) -> GovernanceResult<Self> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(ArchiveFormat::TarGz)
+        } else if name.ends_with(".zip") {
+            Ok(ArchiveFormat::Zip)
+        } else {
+            Err(GovernanceError::InvalidInput(format!(
+                "Unsupported archive format: {} (expected .tar.gz, .tgz or .zip)",
+                path.display()
+            )))
+        }
+    }
+}
+
+/// A release archive on disk
+#[derive(Debug, Clone)]
+pub struct ReleaseArchive {
+    path: PathBuf,
+    format: ArchiveFormat,
+}
+
+impl ReleaseArchive {
+    /// Open an archive, detecting its format from the extension
+    pub fn open<P: AsRef<Path>>(path: P) -> GovernanceResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let format = ArchiveFormat::from_path(&path)?;
+        if !path.is_file() {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Archive not found: {}",
+                path.display()
+            )));
+        }
+        Ok(Self { path, format })
+    }
+
+    /// Archive path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Archive format
+    pub fn format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    /// Read one member into memory
+    ///
+    /// Tar archives are scanned until the member is found; zip archives seek
+    /// to it through the central directory.
+    pub fn read_member(&self, member: &str) -> GovernanceResult<Vec<u8>> {
+        let wanted = normalize_path(member);
+        let mut found = None;
+
+        match self.format {
+            ArchiveFormat::TarGz => {
+                let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(self.file()?));
+                for entry in archive.entries().map_err(|e| self.read_error(e))? {
+                    let entry = entry.map_err(|e| self.read_error(e))?;
+                    let path = entry.path().map_err(|e| self.read_error(e))?;
+                    if normalize_path(&path.to_string_lossy()) == wanted {
+                        found = Some(read_limited(entry).map_err(|e| self.read_error(e))?);
+                        break;
+                    }
+                }
+            }
+            ArchiveFormat::Zip => {
+                let mut archive = zip::ZipArchive::new(self.file()?)
+                    .map_err(|e| self.read_error(io::Error::other(e)))?;
+                let name = archive
+                    .file_names()
+                    .find(|name| normalize_path(name) == wanted)
+                    .map(str::to_string);
+                if let Some(name) = name {
+                    let entry = archive
+                        .by_name(&name)
+                        .map_err(|e| self.read_error(io::Error::other(e)))?;
+                    found = Some(read_limited(entry).map_err(|e| self.read_error(e))?);
+                }
+            }
+        }
+
+        found.ok_or_else(|| {
+            GovernanceError::InvalidInput(format!(
+                "Member {} not found in {}",
+                member,
+                self.path.display()
+            ))
+        })
+    }
+
+    /// SHA256 of every regular file member, hashed while streaming
+    ///
+    /// `exclude` (the embedded signature file) is left out. Directories are
+    /// skipped; symlinks, hardlinks and other special members are rejected,
+    /// as are paths that occur twice once normalized (`./x` and `x`).
+    pub fn hash_members(&self, exclude: &str) -> GovernanceResult<BTreeMap<String, String>> {
+        let exclude = normalize_path(exclude);
+        let mut hashes = BTreeMap::new();
+        let mut seen = BTreeSet::new();
+
+        match self.format {
+            ArchiveFormat::TarGz => {
+                let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(self.file()?));
+                for entry in archive.entries().map_err(|e| self.read_error(e))? {
+                    let mut entry = entry.map_err(|e| self.read_error(e))?;
+                    let entry_type = entry.header().entry_type();
+                    if entry_type.is_dir() || entry_type.is_pax_global_extensions() {
+                        continue;
+                    }
+                    let path = entry.path().map_err(|e| self.read_error(e))?;
+                    let path = self.member_path(&path.to_string_lossy(), &mut seen)?;
+                    if !entry_type.is_file() {
+                        return Err(self.special_member(&path, &format!("{:?}", entry_type)));
+                    }
+                    if path == exclude {
+                        continue;
+                    }
+                    let hash = hash_reader(&mut entry).map_err(|e| self.read_error(e))?;
+                    hashes.insert(path, hash);
+                }
+            }
+            ArchiveFormat::Zip => {
+                let mut archive = zip::ZipArchive::new(self.file()?)
+                    .map_err(|e| self.read_error(io::Error::other(e)))?;
+                for index in 0..archive.len() {
+                    let mut entry = archive
+                        .by_index(index)
+                        .map_err(|e| self.read_error(io::Error::other(e)))?;
+                    if entry.is_dir() {
+                        continue;
+                    }
+                    let path = self.member_path(entry.name(), &mut seen)?;
+                    let file_type = entry.unix_mode().map_or(0, |mode| mode & S_IFMT);
+                    if file_type == S_IFLNK {
+                        return Err(self.special_member(&path, "Symlink"));
+                    }
+                    if file_type != 0 && file_type != S_IFREG {
+                        return Err(self.special_member(&path, &format!("mode {:o}", file_type)));
+                    }
+                    if path == exclude {
+                        continue;
+                    }
+                    let hash = hash_reader(&mut entry).map_err(|e| self.read_error(e))?;
+                    hashes.insert(path, hash);
+                }
+            }
+        }
+
+        Ok(hashes)
+    }
+
+    /// Compare the members against a manifest, excluding the signature member
+    pub fn verify_members(
+        &self,
+        signature_member: &str,
+        manifest: &Manifest,
+    ) -> GovernanceResult<Vec<MemberMismatch>> {
+        let actual = self.hash_members(signature_member)?;
+        Ok(manifest.compare(&actual, Some(signature_member)))
+    }
+
+    /// Normalize a member path, rejecting one already seen
+    fn member_path(&self, name: &str, seen: &mut BTreeSet<String>) -> GovernanceResult<String> {
+        let path = normalize_path(name);
+        if !seen.insert(path.clone()) {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Archive {} contains member {} more than once",
+                self.path.display(),
+                path
+            )));
+        }
+        Ok(path)
+    }
+
+    fn special_member(&self, path: &str, kind: &str) -> GovernanceError {
+        GovernanceError::InvalidInput(format!(
+            "Archive {} member {} is not a regular file ({})",
+            self.path.display(),
+            path,
+            kind
+        ))
+    }
+
+    fn file(&self) -> GovernanceResult<File> {
+        File::open(&self.path).map_err(|e| self.read_error(e))
+    }
+
+    fn read_error(&self, error: io::Error) -> GovernanceError {
+        GovernanceError::InvalidInput(format!(
+            "Failed to read archive {}: {}",
+            self.path.display(),
+            error
+        ))
+    }
+}
+
+/// Signatures and manifest read from an embedded signature file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedSignatures {
+    /// Maintainer signatures over the manifest
+    pub signatures: Vec<Signature>,
+    /// Signed manifest of the other members
+    pub manifest: Manifest,
+    /// Signing times recorded for the signatures
+    pub signed_at: Vec<DateTime<Utc>>,
+    /// Signing times attested by the signers
+    pub timestamps: Vec<SignedTimestamp>,
+    /// Attributes attested by the signers
+    pub attributes: Vec<SignedAttributes>,
+}
+
+impl EmbeddedSignatures {
+    /// Parse a signature file (`signature`) or aggregate (`signatures`)
+    /// carrying a `manifest` object
+    pub fn from_json(json: &[u8]) -> GovernanceResult<Self> {
+        let value: Value = serde_json::from_slice(json).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid embedded signature file: {}", e))
+        })?;
+
+        let entries: Vec<&Value> = match value.get("signatures").and_then(Value::as_array) {
+            Some(entries) => entries.iter().collect(),
+            None => vec![&value],
+        };
+        let mut signatures = Vec::new();
+        let mut signed_at: Vec<DateTime<Utc>> = history::signed_at(&value).into_iter().collect();
+        let mut timestamps = Vec::new();
+        let mut attributes = Vec::new();
+        for entry in entries {
+            timestamps.extend(SignedTimestamp::from_entry(entry)?);
+            attributes.extend(SignedAttributes::from_entry(entry)?);
+            let signature_hex = entry["signature"].as_str().ok_or_else(|| {
+                GovernanceError::InvalidSignatureFormat(
+                    "embedded signature entry has no signature".to_string(),
+                )
+            })?;
+            let bytes = hex::decode(signature_hex)
+                .map_err(|e| GovernanceError::InvalidSignatureFormat(e.to_string()))?;
+            signatures.push(Signature::from_bytes(&bytes)?);
+            if let Some(time) = entry["signed_at"].as_str() {
+                if let Ok(time) = DateTime::parse_from_rfc3339(time) {
+                    signed_at.push(time.with_timezone(&Utc));
+                }
+            }
+        }
+
+        let entries: BTreeMap<String, String> = value
+            .get("manifest")
+            .cloned()
+            .ok_or_else(|| {
+                GovernanceError::MessageFormat(
+                    "embedded signature file has no manifest".to_string(),
+                )
+            })
+            .and_then(|manifest| {
+                serde_json::from_value(manifest)
+                    .map_err(|e| GovernanceError::Serialization(format!("Invalid manifest: {}", e)))
+            })?;
+        let mut manifest = Manifest::new();
+        for (path, hash) in &entries {
+            manifest.insert(path, hash);
+        }
+
+        Ok(Self {
+            signatures,
+            manifest,
+            signed_at,
+            timestamps,
+            attributes,
+        })
+    }
+}
+
+fn read_limited(reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader
+        .take(MAX_SIGNATURE_MEMBER_SIZE + 1)
+        .read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_SIGNATURE_MEMBER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "signature member exceeds 16 MiB",
+        ));
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("release.tar.gz")).unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("release.TGZ")).unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("release.zip")).unwrap(),
+            ArchiveFormat::Zip
+        );
+        assert!(ArchiveFormat::from_path(Path::new("release.tar.xz")).is_err());
+    }
+
+    #[test]
+    fn test_embedded_manifest() {
+        let embedded = EmbeddedSignatures::from_json(
+            br#"{"signatures": [], "manifest": {"./b": "22", "a": "11"}}"#,
+        )
+        .unwrap();
+        assert!(embedded.signatures.is_empty());
+        assert_eq!(embedded.manifest.to_sums(), "11  a\n22  b\n");
+
+        assert!(EmbeddedSignatures::from_json(br#"{"signatures": []}"#).is_err());
+    }
+}
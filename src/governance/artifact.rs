@@ -0,0 +1,410 @@
+//! # Signed Artifact Metadata
+//!
+//! The `metadata` object `blvm-sign-binary` writes into signature files, and
+//! that the aggregation and verification tools read back, so a file missing
+//! a required field is refused rather than passed along.
+//!
+//! Every artifact records its `type`, `file_hash` and `signed_at`, and a
+//! binary its `binary_type` too. Fields particular to other artifact types
+//! (a bundle's `source_hash`, a manifest's `file_count`, an image's
+//! `repository`) are kept in [`SignedArtifactMetadata::extra`].
+//!
+//! [`SignatureRecord`] is the whole single signature file `blvm-sign` and
+//! `blvm-sign-binary` write, with keys and signatures as hex.
+//! [`verify_artifact`] checks one against the artifact as it is now on
+//! disk.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::{verify_signature, PublicKey, Signature, SigningTarget};
+
+/// Metadata recorded with a signed artifact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedArtifactMetadata {
+    /// Artifact type: `binary`, `bundle`, `checksums`, `manifest` or `image`
+    #[serde(rename = "type")]
+    pub artifact_type: String,
+    /// Kind of binary, required for `binary` artifacts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_type: Option<String>,
+    /// Path the artifact was signed from (not signed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// SHA256 of the artifact, hex; for images, the manifest digest
+    pub file_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// When the artifact was signed (recorded, not signed)
+    pub signed_at: DateTime<Utc>,
+    /// Fields particular to the artifact type
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl SignedArtifactMetadata {
+    /// Parse the `metadata` object of a signature file or aggregate
+    ///
+    /// Fails if a required field is missing or has the wrong type.
+    pub fn from_value(value: &Value) -> GovernanceResult<Self> {
+        let metadata: Self = serde_json::from_value(value.clone()).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid signature metadata: {}", e))
+        })?;
+        metadata.check()?;
+        Ok(metadata)
+    }
+
+    /// Check the fields serde cannot: a binary needs its `binary_type`
+    fn check(&self) -> GovernanceResult<()> {
+        if self.artifact_type == "binary" && self.binary_type.is_none() {
+            return Err(GovernanceError::Serialization(
+                "Invalid signature metadata: missing field `binary_type`".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parse the `metadata` field of a signature file or aggregate header,
+    /// which may be missing or null
+    pub fn from_field(metadata: Option<&Value>) -> GovernanceResult<Option<Self>> {
+        match metadata {
+            None | Some(Value::Null) => Ok(None),
+            Some(metadata) => Self::from_value(metadata).map(Some),
+        }
+    }
+
+    /// The metadata as JSON
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).expect("metadata serializes to JSON")
+    }
+}
+
+/// A single signature file
+///
+/// `blvm-sign` writes only the signature, key and signing time; the other
+/// fields are `blvm-sign-binary`'s. Files written before `signer` was
+/// dropped from `blvm-sign-binary` output hold the hex of `unknown` there,
+/// and still parse.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureRecord {
+    pub signature: Signature,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<PublicKey>,
+    /// Name of the signer, if the signing tool knew one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<SignedArtifactMetadata>,
+    /// When the file was written (not signed; see `timestamp_signature`)
+    pub created_at: DateTime<Utc>,
+    /// Signature over the signing time (see [`SignedTimestamp`](crate::governance::SignedTimestamp))
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_signature: Option<Signature>,
+    /// Attested attributes (see [`SignedAttributes`](crate::governance::SignedAttributes))
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes_signature: Option<Signature>,
+}
+
+impl SignatureRecord {
+    /// Parse a signature file
+    pub fn from_json(json: &[u8]) -> GovernanceResult<Self> {
+        let record: Self = serde_json::from_slice(json).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid signature file: {}", e))
+        })?;
+        if let Some(metadata) = &record.metadata {
+            metadata.check()?;
+        }
+        Ok(record)
+    }
+
+    /// The file as pretty-printed JSON
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("signature file serializes to JSON")
+    }
+}
+
+/// Outcome of [`verify_artifact`], with the two checks reported apart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifyArtifactResult {
+    /// `file_hash` recorded in the signed metadata
+    pub recorded_hash: String,
+    /// SHA256 of the artifact as it is now
+    pub actual_hash: String,
+    /// Whether the artifact still hashes to the recorded hash
+    pub hash_matches: bool,
+    /// Whether the signature over the recorded metadata is valid under the
+    /// key
+    pub signature_valid: bool,
+}
+
+impl VerifyArtifactResult {
+    /// Whether both checks passed
+    pub fn is_valid(&self) -> bool {
+        self.hash_matches && self.signature_valid
+    }
+}
+
+impl fmt::Display for VerifyArtifactResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(
+                f,
+                "Artifact matches its signed hash and the signature is valid"
+            );
+        }
+        let mut failures = Vec::new();
+        if !self.hash_matches {
+            failures.push(format!(
+                "hash mismatch: signed {}, artifact is {}",
+                self.recorded_hash, self.actual_hash
+            ));
+        }
+        if !self.signature_valid {
+            failures.push("signature mismatch: not valid under the given key".to_string());
+        }
+        write!(f, "Verification failed: {}", failures.join("; "))
+    }
+}
+
+/// Check a signature file against the artifact it signs
+///
+/// Re-hashes the file at `artifact_path` and compares it to the signed
+/// `file_hash`, and checks the signature over the recorded metadata under
+/// `public_key`. Both checks always run, so a tampered artifact and a
+/// signature by another key are told apart. Only artifacts whose
+/// `file_hash` is the SHA256 of one file (binaries, bundles and checksum
+/// files) can be checked; manifests and images are refused.
+pub fn verify_artifact(
+    signature_file: &SignatureRecord,
+    artifact_path: impl AsRef<Path>,
+    public_key: &PublicKey,
+) -> GovernanceResult<VerifyArtifactResult> {
+    let metadata = signature_file.metadata.as_ref().ok_or_else(|| {
+        GovernanceError::InvalidInput("Signature file has no metadata to check against".to_string())
+    })?;
+    if !matches!(
+        metadata.artifact_type.as_str(),
+        "binary" | "bundle" | "checksums"
+    ) {
+        return Err(GovernanceError::InvalidInput(format!(
+            "A {} signature does not record the hash of one file",
+            metadata.artifact_type
+        )));
+    }
+    let target = SigningTarget::from_metadata(&metadata.to_value()).ok_or_else(|| {
+        GovernanceError::InvalidInput(
+            "Signature metadata does not name a signed target".to_string(),
+        )
+    })?;
+
+    let path = artifact_path.as_ref();
+    let actual_hash = sha256_file(path).map_err(|e| {
+        GovernanceError::InvalidInput(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+
+    Ok(VerifyArtifactResult {
+        hash_matches: actual_hash.eq_ignore_ascii_case(&metadata.file_hash),
+        signature_valid: verify_signature(
+            &signature_file.signature,
+            &target.to_signing_bytes(),
+            public_key,
+        )?,
+        recorded_hash: metadata.file_hash.clone(),
+        actual_hash,
+    })
+}
+
+/// SHA256 of a file, hex, read in chunks
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const HASH: &str = "4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945";
+
+    fn binary() -> SignedArtifactMetadata {
+        SignedArtifactMetadata {
+            artifact_type: "binary".to_string(),
+            binary_type: Some("consensus".to_string()),
+            file_path: Some("blvm-node".to_string()),
+            file_hash: HASH.to_string(),
+            version: Some("v1.0.0".to_string()),
+            commit: Some("abc123".to_string()),
+            signed_at: "2024-01-15T10:30:00Z".parse().unwrap(),
+            extra: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let metadata = binary();
+        let value = metadata.to_value();
+        assert_eq!(value["type"], "binary");
+        assert_eq!(value["binary_type"], "consensus");
+        assert_eq!(value["file_hash"], HASH);
+        assert_eq!(value["signed_at"], "2024-01-15T10:30:00Z");
+        assert_eq!(
+            SignedArtifactMetadata::from_value(&value).unwrap(),
+            metadata
+        );
+
+        // Type-specific fields survive the round trip
+        let value = json!({
+            "type": "manifest",
+            "file_hash": HASH,
+            "file_count": 3,
+            "version": null,
+            "signed_at": "2024-01-15T10:30:00+00:00",
+        });
+        let manifest = SignedArtifactMetadata::from_value(&value).unwrap();
+        assert_eq!(manifest.extra["file_count"], 3);
+        assert_eq!(manifest.version, None);
+        assert_eq!(manifest.to_value()["file_count"], 3);
+    }
+
+    #[test]
+    fn test_metadata_missing_fields_rejected() {
+        let mut value = binary().to_value();
+        value.as_object_mut().unwrap().remove("file_hash");
+        let err = SignedArtifactMetadata::from_value(&value).unwrap_err();
+        assert!(err.to_string().contains("file_hash"), "{}", err);
+
+        let mut value = binary().to_value();
+        value.as_object_mut().unwrap().remove("binary_type");
+        let err = SignedArtifactMetadata::from_value(&value).unwrap_err();
+        assert!(err.to_string().contains("binary_type"), "{}", err);
+
+        let mut value = binary().to_value();
+        value["signed_at"] = json!("yesterday");
+        assert!(SignedArtifactMetadata::from_value(&value).is_err());
+
+        // Files without metadata have none to check
+        assert_eq!(SignedArtifactMetadata::from_field(None).unwrap(), None);
+        assert_eq!(
+            SignedArtifactMetadata::from_field(Some(&Value::Null)).unwrap(),
+            None
+        );
+        let partial = json!({ "type": "binary" });
+        assert!(SignedArtifactMetadata::from_field(Some(&partial)).is_err());
+    }
+
+    #[test]
+    fn test_signature_record_round_trip() {
+        let keypair = crate::governance::GovernanceKeypair::generate().unwrap();
+        let signature = crate::sign_message(keypair.secret_key.expose(), b"test message").unwrap();
+        let record = SignatureRecord {
+            signature: signature.clone(),
+            public_key: Some(keypair.public_key()),
+            signer: None,
+            file_path: Some("blvm-node".to_string()),
+            file_hash: Some(HASH.to_string()),
+            metadata: Some(binary()),
+            created_at: "2024-01-15T10:30:00Z".parse().unwrap(),
+            timestamp_signature: Some(signature.clone()),
+            attributes: Some(BTreeMap::from([("role".to_string(), "ci".to_string())])),
+            attributes_signature: Some(signature),
+        };
+
+        let json = record.to_json_pretty();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["signature"], record.signature.to_string());
+        assert_eq!(value["public_key"], keypair.public_key().to_string());
+        assert!(value.get("signer").is_none());
+        assert_eq!(SignatureRecord::from_json(json.as_bytes()).unwrap(), record);
+
+        let toml = toml::to_string(&record).unwrap();
+        assert_eq!(toml::from_str::<SignatureRecord>(&toml).unwrap(), record);
+
+        // Metadata is checked as when read on its own
+        let mut value = value;
+        value["metadata"]
+            .as_object_mut()
+            .unwrap()
+            .remove("binary_type");
+        let err = SignatureRecord::from_json(value.to_string().as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("binary_type"), "{}", err);
+
+        value["signature"] = json!("abcd");
+        let err = SignatureRecord::from_json(value.to_string().as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("64 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_verify_artifact() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let artifact = dir.path().join("blvm-node");
+        std::fs::write(&artifact, b"release build").unwrap();
+        let file_hash = hex::encode(Sha256::digest(b"release build"));
+
+        let keypair = crate::governance::GovernanceKeypair::generate().unwrap();
+        let metadata = SignedArtifactMetadata {
+            file_hash: file_hash.clone(),
+            ..binary()
+        };
+        let target = SigningTarget::from_metadata(&metadata.to_value()).unwrap();
+        let record = SignatureRecord {
+            signature: crate::sign_message(keypair.secret_key.expose(), &target.to_signing_bytes())
+                .unwrap(),
+            public_key: Some(keypair.public_key()),
+            signer: None,
+            file_path: Some("blvm-node".to_string()),
+            file_hash: Some(file_hash.clone()),
+            metadata: Some(metadata),
+            created_at: "2024-01-15T10:30:00Z".parse().unwrap(),
+            timestamp_signature: None,
+            attributes: None,
+            attributes_signature: None,
+        };
+
+        // Matching artifact
+        let result = verify_artifact(&record, &artifact, &keypair.public_key()).unwrap();
+        assert!(result.is_valid(), "{}", result);
+        assert_eq!(result.actual_hash, file_hash);
+
+        // Wrong key: the file matches, the signature does not
+        let other = crate::governance::GovernanceKeypair::generate().unwrap();
+        let result = verify_artifact(&record, &artifact, &other.public_key()).unwrap();
+        assert!(result.hash_matches);
+        assert!(!result.signature_valid);
+        assert!(result.to_string().contains("signature mismatch"));
+
+        // Tampered artifact: the signature is still valid, the file is not
+        std::fs::write(&artifact, b"release build with a backdoor").unwrap();
+        let result = verify_artifact(&record, &artifact, &keypair.public_key()).unwrap();
+        assert!(!result.hash_matches);
+        assert!(result.signature_valid);
+        assert_eq!(result.recorded_hash, file_hash);
+        assert!(result.to_string().contains("hash mismatch"), "{}", result);
+        assert!(!result.is_valid());
+
+        // Missing artifacts and targets without a file hash are errors
+        assert!(
+            verify_artifact(&record, dir.path().join("missing"), &keypair.public_key()).is_err()
+        );
+        let mut image = record.clone();
+        image.metadata.as_mut().unwrap().artifact_type = "image".to_string();
+        assert!(verify_artifact(&image, &artifact, &keypair.public_key()).is_err());
+    }
+}
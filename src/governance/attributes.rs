@@ -0,0 +1,235 @@
+//! # Signed Attributes
+//!
+//! Facts a signer attests about the artifact they signed.
+//!
+//! Escalation rules (see [`escalation`](crate::governance::escalation)) key
+//! off attributes such as the lines changed, the paths touched or a
+//! `hard-fork` flag. Values written next to a signature can be edited by
+//! anyone relaying the file, so attributes are signed like timestamps: the
+//! signer signs `attributes:<sha256(message)>:<name>=<value>:...` with the
+//! same key, names in sorted order.
+//!
+//! Names are lowercase ASCII letters, digits, `-` and `_`. `type` is
+//! reserved: it is always the kind of the signed message itself.
+
+#[cfg(any(feature = "signing", test))]
+use secp256k1::SecretKey;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::messages::join_fields;
+use crate::governance::signer::GovernanceSigner;
+use crate::governance::{PublicKey, Signature};
+
+/// Attribute name reserved for the kind of the signed message
+pub const TYPE_ATTRIBUTE: &str = "type";
+
+/// Attributes attested by one signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedAttributes {
+    /// Attribute values by name
+    pub attributes: BTreeMap<String, String>,
+    /// Signature over [`signing_bytes`](Self::signing_bytes)
+    pub signature: Signature,
+}
+
+impl SignedAttributes {
+    /// Bytes covered by the attribute signature
+    pub fn signing_bytes(message: &[u8], attributes: &BTreeMap<String, String>) -> Vec<u8> {
+        let mut fields = vec![
+            "attributes".to_string(),
+            hex::encode(Sha256::digest(message)),
+        ];
+        fields.extend(
+            attributes
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value)),
+        );
+        join_fields(&fields)
+    }
+
+    /// Attest `attributes` for `message`
+    #[cfg(any(feature = "signing", test))]
+    pub fn sign(
+        secret_key: &SecretKey,
+        message: &[u8],
+        attributes: BTreeMap<String, String>,
+    ) -> GovernanceResult<Self> {
+        for name in attributes.keys() {
+            check_name(name)?;
+        }
+        let signature =
+            crate::sign_message(secret_key, &Self::signing_bytes(message, &attributes))?;
+        Ok(Self {
+            attributes,
+            signature,
+        })
+    }
+
+    /// Like [`sign`](Self::sign), with the key behind `signer`
+    pub fn sign_with(
+        signer: &dyn GovernanceSigner,
+        message: &[u8],
+        attributes: BTreeMap<String, String>,
+    ) -> GovernanceResult<Self> {
+        for name in attributes.keys() {
+            check_name(name)?;
+        }
+        let signature = signer.sign(&Self::signing_bytes(message, &attributes))?;
+        Ok(Self {
+            attributes,
+            signature,
+        })
+    }
+
+    /// Whether the attributes were signed by `public_key` for `message`
+    pub fn verify(&self, message: &[u8], public_key: &PublicKey) -> bool {
+        crate::governance::verify_signature(
+            &self.signature,
+            &Self::signing_bytes(message, &self.attributes),
+            public_key,
+        )
+        .unwrap_or(false)
+    }
+
+    /// Read the attributes of a signature file or aggregate entry
+    ///
+    /// Takes the `attributes` object (string values) and the hex
+    /// `attributes_signature` field. Returns `None` when the entry has no
+    /// attribute signature; attributes without one are ignored.
+    pub fn from_entry(entry: &Value) -> GovernanceResult<Option<Self>> {
+        let Some(signature_hex) = entry.get("attributes_signature").and_then(Value::as_str) else {
+            return Ok(None);
+        };
+        let bytes = hex::decode(signature_hex).map_err(|e| {
+            GovernanceError::InvalidSignatureFormat(format!("Invalid attributes signature: {}", e))
+        })?;
+        let invalid = GovernanceError::MessageFormat;
+        let object = entry
+            .get("attributes")
+            .and_then(Value::as_object)
+            .ok_or_else(|| invalid("attributes signature without attributes".to_string()))?;
+
+        let mut attributes = BTreeMap::new();
+        for (name, value) in object {
+            let value = value
+                .as_str()
+                .ok_or_else(|| invalid(format!("attribute '{}' is not a string", name)))?;
+            attributes.insert(name.clone(), value.to_string());
+        }
+
+        Ok(Some(Self {
+            attributes,
+            signature: Signature::from_bytes(&bytes)?,
+        }))
+    }
+}
+
+/// Parse a `name=value` attribute, as given on the command line
+pub fn parse_attribute(attribute: &str) -> GovernanceResult<(String, String)> {
+    let (name, value) = attribute.split_once('=').ok_or_else(|| {
+        GovernanceError::InvalidInput(format!(
+            "Invalid attribute '{}': expected name=value",
+            attribute
+        ))
+    })?;
+    check_name(name)?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+fn check_name(name: &str) -> GovernanceResult<()> {
+    if name == TYPE_ATTRIBUTE {
+        return Err(GovernanceError::InvalidInput(
+            "The 'type' attribute is reserved for the message kind".to_string(),
+        ));
+    }
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Invalid attribute name '{}': use lowercase letters, digits, '-' and '_'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    fn attributes(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let message = b"MODULE:lightning:1.0.0";
+        let signed = SignedAttributes::sign(
+            keypair.secret_key.expose(),
+            message,
+            attributes(&[("lines-changed", "120"), ("paths", "consensus/pow.rs")]),
+        )
+        .unwrap();
+
+        assert!(signed.verify(message, &keypair.public_key()));
+        assert!(!signed.verify(message, &other.public_key()));
+        assert!(!signed.verify(b"MODULE:lightning:1.0.1", &keypair.public_key()));
+
+        let mut edited = signed.clone();
+        edited
+            .attributes
+            .insert("paths".to_string(), "docs/".to_string());
+        assert!(!edited.verify(message, &keypair.public_key()));
+    }
+
+    #[test]
+    fn test_from_entry() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let signed = SignedAttributes::sign(
+            keypair.secret_key.expose(),
+            b"m",
+            attributes(&[("hard-fork", "true")]),
+        )
+        .unwrap();
+        let signature_hex = hex::encode(signed.signature.to_bytes());
+
+        let entry = serde_json::json!({
+            "attributes": { "hard-fork": "true" },
+            "attributes_signature": signature_hex,
+        });
+        assert_eq!(SignedAttributes::from_entry(&entry).unwrap(), Some(signed));
+
+        let unsigned = serde_json::json!({ "attributes": { "hard-fork": "false" } });
+        assert_eq!(SignedAttributes::from_entry(&unsigned).unwrap(), None);
+        let number = serde_json::json!({
+            "attributes": { "lines-changed": 120 },
+            "attributes_signature": signature_hex,
+        });
+        assert!(SignedAttributes::from_entry(&number).is_err());
+    }
+
+    #[test]
+    fn test_parse_attribute() {
+        assert_eq!(
+            parse_attribute("paths=consensus/pow.rs,net/peer.rs").unwrap(),
+            (
+                "paths".to_string(),
+                "consensus/pow.rs,net/peer.rs".to_string()
+            )
+        );
+        assert!(parse_attribute("hard-fork").is_err());
+        assert!(parse_attribute("Hard:Fork=true").is_err());
+        assert!(parse_attribute("type=release").is_err());
+    }
+}
@@ -6,15 +6,28 @@
 //!
 //! Key derivation path format: m/purpose'/coin_type'/account'/change/address_index
 //! Example: m/44'/0'/0'/0/0 (BIP44 standard path for Bitcoin mainnet first address)
+//!
+//! Private derivation needs the `signing` feature. Extended public keys and
+//! non-hardened public derivation are always available, so verification
+//! infrastructure can derive a committee's keys from an xpub alone.
 
+#[cfg(any(feature = "signing", test))]
+use crate::governance::context::signing_context;
+use crate::governance::context::verification_context;
 use crate::governance::error::{GovernanceError, GovernanceResult};
 use hmac::{Hmac, Mac};
-use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+#[cfg(any(feature = "signing", test))]
+use secp256k1::SecretKey;
+use secp256k1::{PublicKey, Scalar};
 use sha2::Sha512;
 
 type HmacSha512 = Hmac<Sha512>;
 
+/// Offset of hardened child numbers
+pub const HARDENED: u32 = 0x80000000;
+
 /// Extended private key (xprv)
+#[cfg(any(feature = "signing", test))]
 #[derive(Debug, Clone)]
 pub struct ExtendedPrivateKey {
     /// Depth in derivation tree (0 = master)
@@ -49,6 +62,7 @@ pub struct ExtendedPublicKey {
 /// BIP32: I = HMAC-SHA512(Key = "Bitcoin seed", Data = seed)
 ///        IL = first 32 bytes (master private key)
 ///        IR = last 32 bytes (master chain code)
+#[cfg(any(feature = "signing", test))]
 pub fn derive_master_key(seed: &[u8]) -> GovernanceResult<(ExtendedPrivateKey, ExtendedPublicKey)> {
     if seed.len() < 16 || seed.len() > 64 {
         return Err(GovernanceError::InvalidInput(
@@ -70,11 +84,11 @@ pub fn derive_master_key(seed: &[u8]) -> GovernanceResult<(ExtendedPrivateKey, E
     chain_code.copy_from_slice(&bytes[32..]);
 
     // Create secret key
-    let secp = Secp256k1::new();
+    let secp = signing_context();
     let private_key = SecretKey::from_slice(&private_key_bytes)
         .map_err(|e| GovernanceError::InvalidKey(format!("Invalid master private key: {}", e)))?;
 
-    let public_key = private_key.public_key(&secp);
+    let public_key = private_key.public_key(secp);
 
     let xprv = ExtendedPrivateKey {
         depth: 0,
@@ -99,11 +113,12 @@ pub fn derive_master_key(seed: &[u8]) -> GovernanceResult<(ExtendedPrivateKey, E
 ///
 /// If child_number >= 2^31, use hardened derivation (uses private key)
 /// Otherwise, use normal derivation (can use public key)
+#[cfg(any(feature = "signing", test))]
 pub fn derive_child_private(
     parent: &ExtendedPrivateKey,
     child_number: u32,
 ) -> GovernanceResult<(ExtendedPrivateKey, ExtendedPublicKey)> {
-    let secp = Secp256k1::new();
+    let secp = signing_context();
     let is_hardened = child_number >= 0x80000000;
 
     // Prepare data for HMAC
@@ -115,14 +130,14 @@ pub fn derive_child_private(
         data.extend_from_slice(&parent.private_key.secret_bytes());
     } else {
         // Normal: parent_public_key || child_number (4 bytes, big-endian)
-        let parent_pubkey = parent.private_key.public_key(&secp);
+        let parent_pubkey = parent.private_key.public_key(secp);
         data.extend_from_slice(&parent_pubkey.serialize());
     }
 
     data.extend_from_slice(&child_number.to_be_bytes());
 
     // Calculate parent fingerprint (first 4 bytes of RIPEMD160(SHA256(parent_pubkey)))
-    let parent_pubkey = parent.private_key.public_key(&secp);
+    let parent_pubkey = parent.private_key.public_key(secp);
     let parent_fingerprint = calculate_fingerprint(&parent_pubkey.serialize());
 
     // HMAC-SHA512(chain_code, data)
@@ -152,7 +167,7 @@ pub fn derive_child_private(
         GovernanceError::InvalidKey("Key addition resulted in zero or invalid key".to_string())
     })?;
 
-    let child_public = child_private.public_key(&secp);
+    let child_public = child_private.public_key(secp);
 
     let child_xprv = ExtendedPrivateKey {
         depth: parent.depth + 1,
@@ -191,9 +206,6 @@ pub fn derive_child_public(
     data.extend_from_slice(&parent.public_key.serialize());
     data.extend_from_slice(&child_number.to_be_bytes());
 
-    // Calculate parent fingerprint
-    let parent_fingerprint = calculate_fingerprint(&parent.public_key.serialize());
-
     // HMAC-SHA512(chain_code, data)
     let mut hmac = HmacSha512::new_from_slice(&parent.chain_code)
         .map_err(|e| GovernanceError::InvalidInput(format!("HMAC error: {}", e)))?;
@@ -216,10 +228,10 @@ pub fn derive_child_public(
 
     // Add il_scalar * G to parent public key using add_exp_tweak
     // This computes: parent_pubkey + (il_scalar * G)
-    let secp = Secp256k1::new();
+    let secp = verification_context();
     let child_public = parent
         .public_key
-        .add_exp_tweak(&secp, &il_scalar)
+        .add_exp_tweak(secp, &il_scalar)
         .map_err(|_| GovernanceError::InvalidKey("Point addition failed".to_string()))?;
 
     let parent_fingerprint = calculate_fingerprint(&parent.public_key.serialize());
@@ -236,7 +248,7 @@ pub fn derive_child_public(
 /// Calculate key fingerprint (first 4 bytes of RIPEMD160(SHA256(pubkey)))
 fn calculate_fingerprint(pubkey: &[u8]) -> [u8; 4] {
     use ripemd::{Digest as RipemdDigest, Ripemd160};
-    use sha2::{Digest, Sha256};
+    use sha2::Sha256;
 
     // SHA256(pubkey)
     let mut sha256 = Sha256::new();
@@ -245,7 +257,7 @@ fn calculate_fingerprint(pubkey: &[u8]) -> [u8; 4] {
 
     // RIPEMD160(SHA256(pubkey))
     let mut ripemd = Ripemd160::new();
-    ripemd.update(&sha256_hash);
+    ripemd.update(sha256_hash);
     let ripemd_hash = ripemd.finalize();
 
     // First 4 bytes
@@ -254,16 +266,50 @@ fn calculate_fingerprint(pubkey: &[u8]) -> [u8; 4] {
     fingerprint
 }
 
+/// Parse a BIP32 derivation path such as `m/44'/0'/7'/0/1`
+///
+/// Hardened steps are marked with `'` or `h`. `m` alone is the empty path.
+/// Malformed paths are `InvalidKey` errors.
+pub fn parse_path(path: &str) -> GovernanceResult<Vec<u32>> {
+    let invalid = |reason: String| {
+        GovernanceError::InvalidKey(format!("Invalid derivation path '{}': {}", path, reason))
+    };
+
+    let mut steps = path.split('/');
+    if steps.next() != Some("m") {
+        return Err(invalid("must start with 'm'".to_string()));
+    }
+    steps
+        .map(|step| {
+            let (index, hardened) = match step.strip_suffix(['\'', 'h']) {
+                Some(index) => (index, true),
+                None => (step, false),
+            };
+            // u32::from_str accepts a leading '+'
+            if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid(format!("bad step '{}'", step)));
+            }
+            match index.parse::<u32>() {
+                Ok(index) if index < HARDENED => {
+                    Ok(if hardened { index | HARDENED } else { index })
+                }
+                _ => Err(invalid(format!("step '{}' is out of range", step))),
+            }
+        })
+        .collect()
+}
+
+#[cfg(any(feature = "signing", test))]
 impl ExtendedPrivateKey {
     /// Get the corresponding extended public key
     pub fn to_extended_public(&self) -> ExtendedPublicKey {
-        let secp = Secp256k1::new();
+        let secp = signing_context();
         ExtendedPublicKey {
             depth: self.depth,
             parent_fingerprint: self.parent_fingerprint,
             child_number: self.child_number,
             chain_code: self.chain_code,
-            public_key: self.private_key.public_key(&secp),
+            public_key: self.private_key.public_key(secp),
         }
     }
 
@@ -275,6 +321,15 @@ impl ExtendedPrivateKey {
         derive_child_private(self, child_number)
     }
 
+    /// Derive the key at a path below this one, see [`parse_path`]
+    pub fn derive_path(&self, path: &str) -> GovernanceResult<ExtendedPrivateKey> {
+        parse_path(path)?
+            .into_iter()
+            .try_fold(self.clone(), |key, index| {
+                Ok(derive_child_private(&key, index)?.0)
+            })
+    }
+
     /// Get private key bytes
     pub fn private_key_bytes(&self) -> [u8; 32] {
         self.private_key.secret_bytes()
@@ -287,6 +342,30 @@ impl ExtendedPublicKey {
         derive_child_public(self, child_number)
     }
 
+    /// Derive the key at a path below this one, see [`parse_path`]
+    ///
+    /// Hardened steps need the private key and are `InvalidKey` errors.
+    pub fn derive_path(&self, path: &str) -> GovernanceResult<ExtendedPublicKey> {
+        parse_path(path)?
+            .into_iter()
+            .try_fold(self.clone(), |key, index| {
+                if index >= HARDENED {
+                    return Err(GovernanceError::InvalidKey(format!(
+                        "Derivation path '{}' has a hardened step, which needs the private key",
+                        path
+                    )));
+                }
+                derive_child_public(&key, index)
+            })
+    }
+
+    /// The governance public key of this extended key
+    pub fn to_public_key(&self) -> crate::governance::PublicKey {
+        crate::governance::PublicKey {
+            inner: self.public_key,
+        }
+    }
+
     /// Get public key bytes (compressed)
     pub fn public_key_bytes(&self) -> [u8; 33] {
         self.public_key.serialize()
@@ -312,7 +391,7 @@ mod tests {
     #[test]
     fn test_child_derivation() {
         let seed = b"test seed for BIP32";
-        let (master_xprv, master_xpub) = derive_master_key(seed).unwrap();
+        let (master_xprv, _master_xpub) = derive_master_key(seed).unwrap();
 
         // Derive first child
         let (child_xprv, child_xpub) = master_xprv.derive_child(0).unwrap();
@@ -341,4 +420,94 @@ mod tests {
         assert_eq!(hardened_xprv.child_number, hardened_index);
         assert!(hardened_xprv.child_number >= 0x80000000);
     }
+
+    /// BIP32 test vector 1, chain m/0H/1/2H/2/1000000000
+    const VECTOR1_SEED: &str = "000102030405060708090a0b0c0d0e0f";
+    const VECTOR1_PRIVATE: &str =
+        "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8";
+    const VECTOR1_PUBLIC: &str =
+        "022a471424da5e657499d1ff51cb43c47481a03b1e77f951fe64cec9f5a48f7011";
+    const VECTOR1_CHAIN_CODE: &str =
+        "c783e67b921d2beb8f6b389cc646d7263b4145701dadd2161548a8b078e65e9e";
+
+    #[test]
+    fn test_bip32_vector1() {
+        let (master, _) = derive_master_key(&hex::decode(VECTOR1_SEED).unwrap()).unwrap();
+
+        let key = master.derive_path("m/0'/1/2h/2/1000000000").unwrap();
+        assert_eq!(hex::encode(key.private_key_bytes()), VECTOR1_PRIVATE);
+        assert_eq!(hex::encode(key.chain_code), VECTOR1_CHAIN_CODE);
+        assert_eq!(key.depth, 5);
+
+        // The public half of the chain from the last hardened key's xpub
+        let xpub = master
+            .derive_path("m/0'/1/2'")
+            .unwrap()
+            .to_extended_public();
+        let public = xpub.derive_path("m/2/1000000000").unwrap();
+        assert_eq!(hex::encode(public.public_key_bytes()), VECTOR1_PUBLIC);
+        assert_eq!(hex::encode(public.chain_code), VECTOR1_CHAIN_CODE);
+    }
+
+    #[test]
+    fn test_bip32_vector3_leading_zeros() {
+        let seed = hex::decode(
+            "4b381541583be4423346c643850da4b320e46a87ae3d2a4e6da11eba819cd4acba45d239319ac14f863b8d5ab5a0d0c64d2e8a1e7d1457df2e5a3c51c73235be",
+        )
+        .unwrap();
+        let (master, _) = derive_master_key(&seed).unwrap();
+
+        let key = master.derive_path("m/0'").unwrap();
+        assert_eq!(
+            hex::encode(key.private_key_bytes()),
+            "491f7a2eebc7b57028e0d3faa0acda02e75c33b03c48fb288c41e2ea44e1daef"
+        );
+        assert_eq!(
+            hex::encode(key.chain_code),
+            "e5fea12a97b927fc9dc3d2cb0d1ea1cf50aa5a1fdc1f933e8906bb38df3377bd"
+        );
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(parse_path("m").unwrap(), Vec::<u32>::new());
+        assert_eq!(
+            parse_path("m/44'/0'/7'/0/1").unwrap(),
+            vec![HARDENED | 44, HARDENED, HARDENED | 7, 0, 1]
+        );
+        assert_eq!(parse_path("m/2147483647h").unwrap(), vec![u32::MAX]);
+
+        for path in [
+            "",
+            "44'/0'",
+            "m/",
+            "m//1",
+            "m/x",
+            "m/+1",
+            "m/-1",
+            "m/1''",
+            "m/2147483648",
+            "m/4294967296",
+            "n/1",
+        ] {
+            assert!(
+                matches!(parse_path(path), Err(GovernanceError::InvalidKey(_))),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_public_derivation_rejects_hardened() {
+        let (_, xpub) = derive_master_key(&hex::decode(VECTOR1_SEED).unwrap()).unwrap();
+        assert!(matches!(
+            xpub.derive_path("m/0/1'"),
+            Err(GovernanceError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            xpub.derive_path("m/0/bad"),
+            Err(GovernanceError::InvalidKey(_))
+        ));
+    }
 }
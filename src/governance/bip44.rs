@@ -7,9 +7,7 @@
 //!
 //! Example: m/44'/0'/0'/0/0 (Bitcoin mainnet first address)
 
-use crate::governance::bip32::{
-    derive_child_private, derive_master_key, ExtendedPrivateKey, ExtendedPublicKey,
-};
+use crate::governance::bip32::{derive_master_key, ExtendedPrivateKey, ExtendedPublicKey};
 use crate::governance::error::{GovernanceError, GovernanceResult};
 
 /// BIP44 purpose (always 44 for multi-account hierarchy)
@@ -170,18 +168,6 @@ impl Bip44Path {
         })
     }
 
-    /// Convert to string representation (e.g., "m/44'/0'/0'/0/0")
-    pub fn to_string(&self) -> String {
-        format!(
-            "m/{}/{}'/{}'/{}/{}",
-            self.purpose,
-            self.coin_type.value(),
-            self.account,
-            self.change.value(),
-            self.address_index
-        )
-    }
-
     /// Derive key from master key using this path
     pub fn derive(
         &self,
@@ -221,6 +207,21 @@ impl Bip44Path {
     }
 }
 
+impl std::fmt::Display for Bip44Path {
+    /// String representation (e.g., "m/44'/0'/0'/0/0")
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "m/{}/{}'/{}'/{}/{}",
+            self.purpose,
+            self.coin_type.value(),
+            self.account,
+            self.change.value(),
+            self.address_index
+        )
+    }
+}
+
 /// BIP44 wallet for managing multiple accounts and addresses
 pub struct Bip44Wallet {
     /// Master extended private key
@@ -0,0 +1,185 @@
+//! # Signature Bundles
+//!
+//! A governance message together with the signatures collected for it.
+//! Signatures may be tagged with the epoch they were made in, so a bundle
+//! aggregated across epochs can be cut down to the current one.
+
+use crate::governance::error::GovernanceResult;
+use crate::governance::{GovernanceMessage, Multisig, Signature};
+
+/// A governance message and its collected signatures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureBundle {
+    pub message: GovernanceMessage,
+    pub signatures: Vec<Signature>,
+    /// Epoch of each signature, by index; `None`, or no entry for a
+    /// signature added directly to `signatures`, means unknown
+    epochs: Vec<Option<u64>>,
+}
+
+/// A signature removed by [`SignatureBundle::filter_epoch`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedSignature {
+    /// Index of the signature in the bundle before filtering
+    pub index: usize,
+    pub signature: Signature,
+    /// Epoch the signature was made in, if known
+    pub epoch: Option<u64>,
+}
+
+impl SignatureBundle {
+    /// Create a bundle for a message with no signatures yet
+    pub fn new(message: GovernanceMessage) -> Self {
+        Self {
+            message,
+            signatures: Vec::new(),
+            epochs: Vec::new(),
+        }
+    }
+
+    /// Create a bundle from a message and existing signatures
+    pub fn with_signatures(message: GovernanceMessage, signatures: Vec<Signature>) -> Self {
+        Self {
+            message,
+            signatures,
+            epochs: Vec::new(),
+        }
+    }
+
+    /// Add a signature to the bundle
+    pub fn add_signature(&mut self, signature: Signature) {
+        self.push(signature, None);
+    }
+
+    /// Add a signature made in `epoch` to the bundle
+    pub fn add_signature_in_epoch(&mut self, signature: Signature, epoch: u64) {
+        self.push(signature, Some(epoch));
+    }
+
+    /// Epoch the signature at `index` was made in, if known
+    pub fn epoch(&self, index: usize) -> Option<u64> {
+        self.epochs.get(index).copied().flatten()
+    }
+
+    /// Keep only the signatures made in `epoch`
+    ///
+    /// Signatures from other epochs, and those of unknown epoch, are
+    /// removed and returned in bundle order.
+    pub fn filter_epoch(&mut self, epoch: u64) -> Vec<DroppedSignature> {
+        let mut kept = Vec::new();
+        let mut kept_epochs = Vec::new();
+        let mut dropped = Vec::new();
+        for (index, signature) in std::mem::take(&mut self.signatures).into_iter().enumerate() {
+            match self.epoch(index) {
+                Some(e) if e == epoch => {
+                    kept.push(signature);
+                    kept_epochs.push(Some(e));
+                }
+                other => dropped.push(DroppedSignature {
+                    index,
+                    signature,
+                    epoch: other,
+                }),
+            }
+        }
+        self.signatures = kept;
+        self.epochs = kept_epochs;
+        dropped
+    }
+
+    fn push(&mut self, signature: Signature, epoch: Option<u64>) {
+        // Signatures pushed directly onto `signatures` have no epoch
+        self.epochs.resize(self.signatures.len(), None);
+        self.signatures.push(signature);
+        self.epochs.push(epoch);
+    }
+
+    /// Verify the bundle's signatures meet the multisig threshold
+    pub fn verify(&self, multisig: &Multisig) -> GovernanceResult<bool> {
+        multisig.verify(&self.message.to_signing_bytes(), &self.signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    #[test]
+    fn test_bundle_verification() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(2, 3, public_keys).unwrap();
+
+        let message = GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: "abc123".to_string(),
+        };
+        let mut bundle = SignatureBundle::new(message.clone());
+        for kp in &keypairs[0..2] {
+            bundle.add_signature(
+                crate::sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap(),
+            );
+        }
+
+        assert!(bundle.verify(&multisig).unwrap());
+
+        bundle.signatures.truncate(1);
+        assert!(bundle.verify(&multisig).is_err());
+    }
+
+    #[test]
+    fn test_filter_epoch() {
+        let keypairs: Vec<_> = (0..4)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(2, 4, public_keys).unwrap();
+
+        let message = GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: "abc123".to_string(),
+        };
+        let sign = |i: usize| {
+            crate::sign_message(keypairs[i].secret_key.expose(), &message.to_signing_bytes())
+                .unwrap()
+        };
+        let mut bundle = SignatureBundle::new(message.clone());
+        bundle.add_signature_in_epoch(sign(0), 1);
+        bundle.add_signature_in_epoch(sign(1), 2);
+        bundle.add_signature_in_epoch(sign(2), 1);
+        bundle.add_signature_in_epoch(sign(3), 2);
+        assert_eq!(bundle.epoch(2), Some(1));
+
+        let dropped = bundle.filter_epoch(2);
+        assert_eq!(
+            dropped,
+            vec![
+                DroppedSignature {
+                    index: 0,
+                    signature: sign(0),
+                    epoch: Some(1),
+                },
+                DroppedSignature {
+                    index: 2,
+                    signature: sign(2),
+                    epoch: Some(1),
+                },
+            ]
+        );
+        assert_eq!(bundle.signatures, vec![sign(1), sign(3)]);
+        assert_eq!(bundle.epoch(0), Some(2));
+        assert_eq!(bundle.epoch(1), Some(2));
+        assert!(bundle.verify(&multisig).unwrap());
+
+        // Signatures of unknown epoch are dropped too
+        bundle.signatures.push(sign(0));
+        let dropped = bundle.filter_epoch(2);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].index, 2);
+        assert_eq!(dropped[0].epoch, None);
+        assert_eq!(bundle.signatures.len(), 2);
+    }
+}
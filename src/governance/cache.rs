@@ -0,0 +1,259 @@
+//! # Verification Cache
+//!
+//! Caches multisig verification results.
+//!
+//! Cache keys bind the committee fingerprint (threshold and sorted keys), the
+//! message hash and the signature set, so a result cached for one committee is
+//! never returned after the committee changes.
+//!
+//! The cache holds at most a fixed number of results; once full, the oldest
+//! result is evicted for each new one.
+
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+
+use crate::governance::error::GovernanceResult;
+use crate::governance::{Multisig, Signature};
+
+/// Key identifying a cached verification result
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifyCacheKey {
+    committee: [u8; 32],
+    message_hash: [u8; 32],
+    signatures_hash: [u8; 32],
+}
+
+impl VerifyCacheKey {
+    /// Build a cache key for a multisig verification
+    pub fn new(multisig: &Multisig, message: &[u8], signatures: &[Signature]) -> Self {
+        let mut hasher = Sha256::new();
+        for signature in signatures {
+            hasher.update(signature.to_bytes());
+        }
+
+        Self {
+            committee: multisig.fingerprint(),
+            message_hash: Sha256::digest(message).into(),
+            signatures_hash: hasher.finalize().into(),
+        }
+    }
+
+    /// Get the committee fingerprint this key is bound to
+    pub fn committee(&self) -> &[u8; 32] {
+        &self.committee
+    }
+}
+
+/// Number of results a [`VerifyCache::new`] cache holds
+pub const DEFAULT_VERIFY_CACHE_CAPACITY: usize = 4096;
+
+/// Cache of multisig verification results, bounded in size
+#[derive(Debug, Clone)]
+pub struct VerifyCache {
+    entries: HashMap<VerifyCacheKey, bool>,
+    /// Keys in insertion order, oldest first
+    order: VecDeque<VerifyCacheKey>,
+    capacity: usize,
+}
+
+impl Default for VerifyCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_VERIFY_CACHE_CAPACITY)
+    }
+}
+
+impl VerifyCache {
+    /// Create an empty cache holding up to
+    /// [`DEFAULT_VERIFY_CACHE_CAPACITY`] results
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty cache holding up to `capacity` results
+    ///
+    /// A cache with capacity zero stores nothing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Maximum number of cached results
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Look up a cached result
+    pub fn get(&self, key: &VerifyCacheKey) -> Option<bool> {
+        self.entries.get(key).copied()
+    }
+
+    /// Store a verification result, evicting the oldest one if full
+    pub fn insert(&mut self, key: VerifyCacheKey, valid: bool) {
+        if let Some(cached) = self.entries.get_mut(&key) {
+            *cached = valid;
+            return;
+        }
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.push_back(key.clone());
+        self.entries.insert(key, valid);
+    }
+
+    /// Verify signatures against a multisig, using the cache when possible
+    ///
+    /// Errors are not cached.
+    pub fn verify(
+        &mut self,
+        multisig: &Multisig,
+        message: &[u8],
+        signatures: &[Signature],
+    ) -> GovernanceResult<bool> {
+        let key = VerifyCacheKey::new(multisig, message, signatures);
+        if let Some(valid) = self.get(&key) {
+            return Ok(valid);
+        }
+
+        let valid = multisig.verify(message, signatures)?;
+        self.insert(key, valid);
+        Ok(valid)
+    }
+
+    /// Drop every entry cached for the given committee fingerprint
+    pub fn invalidate_committee(&mut self, committee: &[u8; 32]) {
+        self.entries.retain(|key, _| key.committee != *committee);
+        self.order.retain(|key| key.committee != *committee);
+    }
+
+    /// Remove all cached entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    /// Number of cached entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    #[test]
+    fn test_cache_hit() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(2, 3, public_keys).unwrap();
+        let message = b"test message";
+
+        let signatures: Vec<_> = keypairs[0..2]
+            .iter()
+            .map(|kp| crate::sign_message(kp.secret_key.expose(), message).unwrap())
+            .collect();
+
+        let mut cache = VerifyCache::new();
+        assert!(cache.verify(&multisig, message, &signatures).unwrap());
+        assert_eq!(cache.len(), 1);
+
+        let key = VerifyCacheKey::new(&multisig, message, &signatures);
+        assert_eq!(cache.get(&key), Some(true));
+        assert!(cache.verify(&multisig, message, &signatures).unwrap());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_committee_change_misses_cache() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(1, 3, public_keys.clone()).unwrap();
+        let message = b"test message";
+
+        let signatures =
+            vec![crate::sign_message(keypairs[0].secret_key.expose(), message).unwrap()];
+
+        let mut cache = VerifyCache::new();
+        assert!(cache.verify(&multisig, message, &signatures).unwrap());
+
+        // Replace the signer's key: same message and signature, new committee
+        let mut rotated_keys = public_keys;
+        rotated_keys[0] = GovernanceKeypair::generate().unwrap().public_key();
+        let rotated = Multisig::new(1, 3, rotated_keys).unwrap();
+
+        let key = VerifyCacheKey::new(&rotated, message, &signatures);
+        assert_eq!(cache.get(&key), None);
+        assert!(!cache.verify(&rotated, message, &signatures).unwrap());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_invalidate_committee() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let message = b"test message";
+        let signatures = vec![crate::sign_message(keypair.secret_key.expose(), message).unwrap()];
+
+        let mut cache = VerifyCache::new();
+        cache.verify(&multisig, message, &signatures).unwrap();
+        assert!(!cache.is_empty());
+
+        cache.invalidate_committee(&multisig.fingerprint());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let messages: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i]).collect();
+        let keys: Vec<_> = messages
+            .iter()
+            .map(|message| {
+                let signature = crate::sign_message(keypair.secret_key.expose(), message).unwrap();
+                (message, vec![signature])
+            })
+            .collect();
+
+        let mut cache = VerifyCache::with_capacity(3);
+        for (message, signatures) in &keys {
+            assert!(cache.verify(&multisig, message, signatures).unwrap());
+            assert!(cache.len() <= 3);
+        }
+        assert_eq!(cache.len(), 3);
+
+        let key = |i: usize| VerifyCacheKey::new(&multisig, keys[i].0, &keys[i].1);
+        assert_eq!(cache.get(&key(0)), None);
+        for i in 1..4 {
+            assert_eq!(cache.get(&key(i)), Some(true));
+        }
+
+        // Updating a cached result does not evict anything
+        cache.insert(key(1), false);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get(&key(1)), Some(false));
+
+        let mut disabled = VerifyCache::with_capacity(0);
+        disabled.insert(key(0), true);
+        assert!(disabled.is_empty());
+        assert_eq!(VerifyCache::new().capacity(), DEFAULT_VERIFY_CACHE_CAPACITY);
+    }
+}
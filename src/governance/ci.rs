@@ -0,0 +1,461 @@
+//! # CI Release Metadata
+//!
+//! Build governance messages from CI environment variables, so release
+//! automation can produce exactly the message maintainers will sign.
+//!
+//! Supports the GitHub Actions and GitLab CI variable conventions. Values in
+//! [`CiOverrides`] always take precedence over the environment.
+
+use std::collections::BTreeMap;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::GovernanceMessage;
+
+/// A CI system whose environment variables can be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiProvider {
+    /// GitHub Actions (`GITHUB_*` variables)
+    GitHubActions,
+    /// GitLab CI (`CI_*` variables)
+    GitLabCi,
+}
+
+/// Values that replace the corresponding environment variables
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CiOverrides {
+    pub tag: Option<String>,
+    pub sha: Option<String>,
+    pub repository: Option<String>,
+    pub run_url: Option<String>,
+    pub workflow: Option<String>,
+    pub actor: Option<String>,
+}
+
+/// A value and where it came from, for error messages
+struct Sourced {
+    value: String,
+    source: String,
+}
+
+impl CiProvider {
+    /// Variable holding the commit SHA
+    fn sha_var(self) -> &'static str {
+        match self {
+            CiProvider::GitHubActions => "GITHUB_SHA",
+            CiProvider::GitLabCi => "CI_COMMIT_SHA",
+        }
+    }
+
+    /// Variable holding the repository path
+    fn repository_var(self) -> &'static str {
+        match self {
+            CiProvider::GitHubActions => "GITHUB_REPOSITORY",
+            CiProvider::GitLabCi => "CI_PROJECT_PATH",
+        }
+    }
+
+    fn tag<F>(self, var: &F) -> GovernanceResult<Sourced>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        match self {
+            CiProvider::GitHubActions => {
+                // GITHUB_REF is refs/tags/<tag> for tag pushes
+                let reference = require(var, "GITHUB_REF")?;
+                match reference.value.strip_prefix("refs/tags/") {
+                    Some(tag) => Ok(Sourced {
+                        value: tag.to_string(),
+                        source: reference.source,
+                    }),
+                    None => Err(GovernanceError::InvalidInput(format!(
+                        "CI variable GITHUB_REF is not a tag ref: {}",
+                        reference.value
+                    ))),
+                }
+            }
+            CiProvider::GitLabCi => require(var, "CI_COMMIT_TAG"),
+        }
+    }
+
+    fn run_url<F>(self, var: &F) -> Option<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        match self {
+            CiProvider::GitHubActions => Some(format!(
+                "{}/{}/actions/runs/{}",
+                var("GITHUB_SERVER_URL")?,
+                var("GITHUB_REPOSITORY")?,
+                var("GITHUB_RUN_ID")?
+            )),
+            CiProvider::GitLabCi => var("CI_PIPELINE_URL"),
+        }
+    }
+
+    fn workflow<F>(self, var: &F) -> Option<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        match self {
+            CiProvider::GitHubActions => var("GITHUB_WORKFLOW"),
+            CiProvider::GitLabCi => var("CI_PIPELINE_NAME").or_else(|| var("CI_JOB_NAME")),
+        }
+    }
+
+    fn actor<F>(self, var: &F) -> Option<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        match self {
+            CiProvider::GitHubActions => var("GITHUB_ACTOR"),
+            CiProvider::GitLabCi => var("GITLAB_USER_LOGIN"),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CiProvider::GitHubActions => "github-actions",
+            CiProvider::GitLabCi => "gitlab-ci",
+        }
+    }
+}
+
+/// Read a variable, treating empty values as missing
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn require<F>(var: &F, name: &str) -> GovernanceResult<Sourced>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    var(name)
+        .map(|value| Sourced {
+            value,
+            source: format!("CI variable {}", name),
+        })
+        .ok_or_else(|| GovernanceError::InvalidInput(format!("Missing CI variable {}", name)))
+}
+
+fn overridden(value: &Option<String>, field: &str) -> Option<Sourced> {
+    value.as_ref().map(|value| Sourced {
+        value: value.clone(),
+        source: format!("override {}", field),
+    })
+}
+
+/// Whether a tag looks like a version (`v1.2.3`, `1.2.3-rc.1`)
+fn is_version_tag(tag: &str) -> bool {
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<&str> = core.split('.').collect();
+
+    (2..=3).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Build the release message for the current CI run
+///
+/// The tag must look like a version and the commit SHA must be 40 hex
+/// characters. Errors name the variable (or override) that was missing or
+/// invalid.
+pub fn message_from_env(
+    provider: CiProvider,
+    overrides: &CiOverrides,
+) -> GovernanceResult<GovernanceMessage> {
+    message_from_vars(provider, overrides, &env_var)
+}
+
+fn message_from_vars<F>(
+    provider: CiProvider,
+    overrides: &CiOverrides,
+    var: &F,
+) -> GovernanceResult<GovernanceMessage>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let tag = match overridden(&overrides.tag, "tag") {
+        Some(tag) => tag,
+        None => provider.tag(var)?,
+    };
+    if !is_version_tag(&tag.value) {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Invalid {}: tag '{}' does not look like a version",
+            tag.source, tag.value
+        )));
+    }
+
+    let sha = match overridden(&overrides.sha, "sha") {
+        Some(sha) => sha,
+        None => require(var, provider.sha_var())?,
+    };
+    if sha.value.len() != 40 || !sha.value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Invalid {}: expected 40 hex characters, got '{}'",
+            sha.source, sha.value
+        )));
+    }
+
+    Ok(GovernanceMessage::Release {
+        version: tag.value,
+        commit_hash: sha.value.to_lowercase(),
+    })
+}
+
+/// Assemble the signed metadata describing the current CI run
+///
+/// Contains the provider and, when available, `repository`, `run_url`,
+/// `workflow` and `actor`.
+pub fn signing_context_from_env(
+    provider: CiProvider,
+    overrides: &CiOverrides,
+) -> BTreeMap<String, String> {
+    signing_context_from_vars(provider, overrides, &env_var)
+}
+
+fn signing_context_from_vars<F>(
+    provider: CiProvider,
+    overrides: &CiOverrides,
+    var: &F,
+) -> BTreeMap<String, String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let fields = [
+        (
+            "repository",
+            overrides
+                .repository
+                .clone()
+                .or_else(|| var(provider.repository_var())),
+        ),
+        (
+            "run_url",
+            overrides.run_url.clone().or_else(|| provider.run_url(var)),
+        ),
+        (
+            "workflow",
+            overrides
+                .workflow
+                .clone()
+                .or_else(|| provider.workflow(var)),
+        ),
+        (
+            "actor",
+            overrides.actor.clone().or_else(|| provider.actor(var)),
+        ),
+    ];
+
+    let mut context = BTreeMap::new();
+    context.insert("ci_provider".to_string(), provider.name().to_string());
+    for (key, value) in fields {
+        if let Some(value) = value {
+            context.insert(key.to_string(), value);
+        }
+    }
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Serializes tests that modify the process environment
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    const GITHUB_VARS: [(&str, &str); 7] = [
+        ("GITHUB_REF", "refs/tags/v1.2.0"),
+        ("GITHUB_SHA", SHA),
+        ("GITHUB_REPOSITORY", "BTCDecoded/blvm-node"),
+        ("GITHUB_SERVER_URL", "https://github.com"),
+        ("GITHUB_RUN_ID", "42"),
+        ("GITHUB_WORKFLOW", "release"),
+        ("GITHUB_ACTOR", "maintainer"),
+    ];
+
+    const GITLAB_VARS: [(&str, &str); 6] = [
+        ("CI_COMMIT_TAG", "1.2.0-rc.1"),
+        ("CI_COMMIT_SHA", SHA),
+        ("CI_PROJECT_PATH", "btcdecoded/blvm-node"),
+        (
+            "CI_PIPELINE_URL",
+            "https://gitlab.com/btcdecoded/blvm-node/-/pipelines/7",
+        ),
+        ("CI_JOB_NAME", "release"),
+        ("GITLAB_USER_LOGIN", "maintainer"),
+    ];
+
+    /// Run `f` with exactly `vars` set from the given variable set
+    fn with_env<T>(all: &[(&str, &str)], vars: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        for (name, _) in all {
+            std::env::remove_var(name);
+        }
+        for (name, value) in vars {
+            std::env::set_var(name, value);
+        }
+        let result = f();
+        for (name, _) in all {
+            std::env::remove_var(name);
+        }
+        result
+    }
+
+    fn without(
+        vars: &[(&'static str, &'static str)],
+        name: &str,
+    ) -> Vec<(&'static str, &'static str)> {
+        vars.iter().copied().filter(|(n, _)| *n != name).collect()
+    }
+
+    #[test]
+    fn test_github_actions() {
+        with_env(&GITHUB_VARS, &GITHUB_VARS, || {
+            let overrides = CiOverrides::default();
+            assert_eq!(
+                message_from_env(CiProvider::GitHubActions, &overrides).unwrap(),
+                GovernanceMessage::Release {
+                    version: "v1.2.0".to_string(),
+                    commit_hash: SHA.to_string(),
+                }
+            );
+
+            let context = signing_context_from_env(CiProvider::GitHubActions, &overrides);
+            assert_eq!(context["ci_provider"], "github-actions");
+            assert_eq!(context["repository"], "BTCDecoded/blvm-node");
+            assert_eq!(
+                context["run_url"],
+                "https://github.com/BTCDecoded/blvm-node/actions/runs/42"
+            );
+            assert_eq!(context["workflow"], "release");
+            assert_eq!(context["actor"], "maintainer");
+        });
+    }
+
+    #[test]
+    fn test_gitlab_ci() {
+        with_env(&GITLAB_VARS, &GITLAB_VARS, || {
+            let overrides = CiOverrides::default();
+            assert_eq!(
+                message_from_env(CiProvider::GitLabCi, &overrides).unwrap(),
+                GovernanceMessage::Release {
+                    version: "1.2.0-rc.1".to_string(),
+                    commit_hash: SHA.to_string(),
+                }
+            );
+
+            let context = signing_context_from_env(CiProvider::GitLabCi, &overrides);
+            assert_eq!(context["repository"], "btcdecoded/blvm-node");
+            assert_eq!(
+                context["run_url"],
+                "https://gitlab.com/btcdecoded/blvm-node/-/pipelines/7"
+            );
+            assert_eq!(context["workflow"], "release");
+        });
+    }
+
+    #[test]
+    fn test_overrides_win() {
+        with_env(&GITHUB_VARS, &GITHUB_VARS, || {
+            let overrides = CiOverrides {
+                tag: Some("v2.0.0".to_string()),
+                actor: Some("release-bot".to_string()),
+                ..Default::default()
+            };
+            let message = message_from_env(CiProvider::GitHubActions, &overrides).unwrap();
+            assert_eq!(
+                message.to_signing_bytes(),
+                format!("RELEASE:v2.0.0:{}", SHA).into_bytes()
+            );
+
+            let context = signing_context_from_env(CiProvider::GitHubActions, &overrides);
+            assert_eq!(context["actor"], "release-bot");
+        });
+    }
+
+    #[test]
+    fn test_missing_variable() {
+        let vars = without(&GITHUB_VARS, "GITHUB_SHA");
+        with_env(&GITHUB_VARS, &vars, || {
+            let err =
+                message_from_env(CiProvider::GitHubActions, &CiOverrides::default()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Invalid input: Missing CI variable GITHUB_SHA"
+            );
+        });
+
+        let vars = without(&GITLAB_VARS, "CI_COMMIT_TAG");
+        with_env(&GITLAB_VARS, &vars, || {
+            let err = message_from_env(CiProvider::GitLabCi, &CiOverrides::default()).unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("Missing CI variable CI_COMMIT_TAG"));
+
+            let context = signing_context_from_env(CiProvider::GitLabCi, &CiOverrides::default());
+            assert!(context.contains_key("repository"));
+        });
+
+        // Missing context values are omitted, not errors
+        with_env(&GITLAB_VARS, &[], || {
+            let context = signing_context_from_env(CiProvider::GitLabCi, &CiOverrides::default());
+            assert_eq!(context.keys().collect::<Vec<_>>(), vec!["ci_provider"]);
+        });
+    }
+
+    #[test]
+    fn test_malformed_values() {
+        let mut vars = without(&GITLAB_VARS, "CI_COMMIT_SHA");
+        vars.push(("CI_COMMIT_SHA", "abc123"));
+        with_env(&GITLAB_VARS, &vars, || {
+            let err = message_from_env(CiProvider::GitLabCi, &CiOverrides::default())
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("CI variable CI_COMMIT_SHA"), "{}", err);
+            assert!(err.contains("expected 40 hex characters"), "{}", err);
+        });
+
+        with_env(&GITHUB_VARS, &GITHUB_VARS, || {
+            let overrides = CiOverrides {
+                sha: Some(SHA.replace('a', "z")),
+                ..Default::default()
+            };
+            let err = message_from_env(CiProvider::GitHubActions, &overrides)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("override sha"), "{}", err);
+
+            let overrides = CiOverrides {
+                tag: Some("nightly".to_string()),
+                ..Default::default()
+            };
+            let err = message_from_env(CiProvider::GitHubActions, &overrides)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("does not look like a version"), "{}", err);
+        });
+
+        let mut vars = without(&GITHUB_VARS, "GITHUB_REF");
+        vars.push(("GITHUB_REF", "refs/heads/main"));
+        with_env(&GITHUB_VARS, &vars, || {
+            let err =
+                message_from_env(CiProvider::GitHubActions, &CiOverrides::default()).unwrap_err();
+            assert!(err.to_string().contains("GITHUB_REF is not a tag ref"));
+        });
+    }
+
+    #[test]
+    fn test_version_tags() {
+        for tag in ["v1.2.3", "1.2", "v0.1.0-rc.1", "1.0.0+build.5"] {
+            assert!(is_version_tag(tag), "{}", tag);
+        }
+        for tag in ["", "v", "latest", "v1", "1.2.3.4", "v1..2", "v1.x"] {
+            assert!(!is_version_tag(tag), "{}", tag);
+        }
+    }
+}
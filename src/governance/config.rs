@@ -0,0 +1,383 @@
+//! # Governance Config
+//!
+//! One file describing the committee: its members and their keys, the flat
+//! signature threshold over all of them, and optionally the team structure
+//! for nested verification. It replaces passing `--pubkeys` and
+//! `--threshold` separately.
+//!
+//! ```toml
+//! threshold = "2-of-3"
+//!
+//! [[members]]
+//! github = "alice"
+//! public_key = "02..."
+//!
+//! # ... one [[members]] entry per key
+//!
+//! [nested]
+//! teams_required = 2
+//! maintainers_per_team_required = 1
+//!
+//! [[nested.teams]]
+//! id = "core"
+//! members = ["alice", "bob"]
+//! ```
+//!
+//! Teams name their members by `github`; each key is listed once, under
+//! `members`. A key may be given as BIP340 x-only hex (64 characters); it is
+//! read as the key with even y and written back compressed.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::nested_multisig::{NestedMultisig, Team, TeamMaintainer};
+use crate::governance::{Multisig, PublicKey, Threshold};
+
+/// Committee and thresholds of a governance config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GovernanceConfig {
+    /// Flat signature threshold over all members (`N-of-M`, where M is the
+    /// number of members)
+    pub threshold: Threshold,
+    /// Committee members, in key order
+    pub members: Vec<MemberConfig>,
+    /// Team structure for nested verification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nested: Option<NestedConfig>,
+}
+
+/// A committee member
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberConfig {
+    /// GitHub username, unique in the committee
+    pub github: String,
+    /// Hex-encoded public key; x-only hex is read as the key with even y
+    #[serde(deserialize_with = "deserialize_member_key")]
+    pub public_key: PublicKey,
+}
+
+/// Read a member key, also accepting BIP340 x-only hex, which
+/// [`PublicKey`]'s own parsing rejects
+fn deserialize_member_key<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<PublicKey, D::Error> {
+    let key = String::deserialize(deserializer)?;
+    let parsed = match hex::decode(&key).map(<[u8; 32]>::try_from) {
+        Ok(Ok(xonly)) => PublicKey::from_xonly_bytes(&xonly),
+        _ => key.parse(),
+    };
+    parsed.map_err(serde::de::Error::custom)
+}
+
+/// Team structure of a committee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NestedConfig {
+    /// Teams that must approve
+    pub teams_required: usize,
+    /// Signatures each approving team needs
+    pub maintainers_per_team_required: usize,
+    pub teams: Vec<TeamConfig>,
+}
+
+/// A team of committee members
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamConfig {
+    pub id: String,
+    /// Display name (default: the id)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// GitHub usernames of the team's members
+    pub members: Vec<String>,
+}
+
+impl GovernanceConfig {
+    /// Parse and check a TOML config
+    pub fn from_toml(toml: &str) -> GovernanceResult<Self> {
+        let config: Self = toml::from_str(toml).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid governance config: {}", e))
+        })?;
+        config.check()?;
+        Ok(config)
+    }
+
+    /// Parse and check a JSON config
+    pub fn from_json(json: &str) -> GovernanceResult<Self> {
+        let config: Self = serde_json::from_str(json).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid governance config: {}", e))
+        })?;
+        config.check()?;
+        Ok(config)
+    }
+
+    /// Read a config file: JSON if its extension is `.json`, TOML otherwise
+    pub fn from_file<P: AsRef<Path>>(path: P) -> GovernanceResult<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            GovernanceError::InvalidInput(format!(
+                "Cannot read governance config file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&contents)
+        } else {
+            Self::from_toml(&contents)
+        }
+    }
+
+    /// Public keys of the members, in order
+    pub fn public_keys(&self) -> Vec<PublicKey> {
+        self.members
+            .iter()
+            .map(|member| member.public_key.clone())
+            .collect()
+    }
+
+    /// The flat multisig over all members
+    pub fn to_multisig(&self) -> GovernanceResult<Multisig> {
+        Multisig::with_threshold(self.threshold, self.public_keys())
+    }
+
+    /// The nested multisig of the team structure, if the config has one
+    pub fn to_nested_multisig(&self) -> GovernanceResult<Option<NestedMultisig>> {
+        let Some(nested) = &self.nested else {
+            return Ok(None);
+        };
+        let by_github: HashMap<&str, &MemberConfig> = self
+            .members
+            .iter()
+            .map(|member| (member.github.as_str(), member))
+            .collect();
+
+        let teams = nested
+            .teams
+            .iter()
+            .map(|team| {
+                let maintainers = team
+                    .members
+                    .iter()
+                    .map(|github| {
+                        let member = by_github.get(github.as_str()).ok_or_else(|| {
+                            GovernanceError::InvalidMultisig(format!(
+                                "Team {} lists {}, who is not a committee member",
+                                team.id, github
+                            ))
+                        })?;
+                        Ok(TeamMaintainer {
+                            github: member.github.clone(),
+                            public_key: member.public_key.clone(),
+                        })
+                    })
+                    .collect::<GovernanceResult<Vec<_>>>()?;
+                Ok(Team {
+                    id: team.id.clone(),
+                    name: team.name.clone().unwrap_or_else(|| team.id.clone()),
+                    maintainers,
+                })
+            })
+            .collect::<GovernanceResult<Vec<_>>>()?;
+
+        NestedMultisig::new(
+            teams,
+            nested.teams_required,
+            nested.maintainers_per_team_required,
+        )
+        .map(Some)
+    }
+
+    /// Check that members are unique and both verifiers can be built
+    fn check(&self) -> GovernanceResult<()> {
+        let mut seen = HashSet::new();
+        if let Some(member) = self.members.iter().find(|m| !seen.insert(&m.github)) {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "Committee member {} is listed twice",
+                member.github
+            )));
+        }
+        self.to_multisig()?;
+        self.to_nested_multisig()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::signatures::sign_message;
+    use crate::governance::GovernanceKeypair;
+    use tempfile::TempDir;
+
+    fn keypairs() -> Vec<GovernanceKeypair> {
+        (1..=4u8)
+            .map(|i| GovernanceKeypair::from_secret_key(&[i; 32]).unwrap())
+            .collect()
+    }
+
+    /// Two teams of two; a 3-of-4 flat threshold, or one signature from
+    /// each of the two teams
+    fn config_toml(keypairs: &[GovernanceKeypair]) -> String {
+        let mut toml = "threshold = \"3-of-4\"\n".to_string();
+        for (github, keypair) in ["alice", "bob", "carol", "dave"].iter().zip(keypairs) {
+            toml.push_str(&format!(
+                "\n[[members]]\ngithub = \"{}\"\npublic_key = \"{}\"\n",
+                github,
+                keypair.public_key()
+            ));
+        }
+        toml.push_str(
+            r#"
+[nested]
+teams_required = 2
+maintainers_per_team_required = 1
+
+[[nested.teams]]
+id = "core"
+name = "Core"
+members = ["alice", "bob"]
+
+[[nested.teams]]
+id = "infra"
+members = ["carol", "dave"]
+"#,
+        );
+        toml
+    }
+
+    #[test]
+    fn test_flat_and_nested_verifiers() {
+        let keypairs = keypairs();
+        let config = GovernanceConfig::from_toml(&config_toml(&keypairs)).unwrap();
+
+        let multisig = config.to_multisig().unwrap();
+        assert_eq!(multisig.m_of_n().to_string(), "3-of-4");
+        let keys: Vec<_> = keypairs.iter().map(|k| k.public_key()).collect();
+        assert_eq!(multisig.public_keys(), keys.as_slice());
+
+        let nested = config.to_nested_multisig().unwrap().unwrap();
+        let message = b"release v1.0.0";
+        let sign = |i: usize| sign_message(keypairs[i].secret_key.expose(), message).unwrap();
+
+        // alice and carol: one per team, but below the flat threshold
+        let signatures = [sign(0), sign(2)];
+        assert!(matches!(
+            multisig.verify(message, &signatures),
+            Err(GovernanceError::InsufficientSignatures { got: 2, need: 3 })
+        ));
+        let result = nested.verify_signatures(message, &signatures).unwrap();
+        assert!(result.inter_team_approved);
+        assert_eq!(result.team_details[0].team_name, "Core");
+        assert_eq!(result.team_details[1].team_name, "infra");
+
+        // alice and bob: one team only
+        let result = nested
+            .verify_signatures(message, &[sign(0), sign(1)])
+            .unwrap();
+        assert!(!result.inter_team_approved);
+        assert_eq!(result.teams_approved, 1);
+
+        // Everyone meets both
+        let signatures: Vec<_> = (0..4).map(sign).collect();
+        assert!(multisig.verify(message, &signatures).unwrap());
+        assert!(
+            nested
+                .verify_signatures(message, &signatures)
+                .unwrap()
+                .inter_team_approved
+        );
+    }
+
+    #[test]
+    fn test_xonly_member_keys() {
+        use secp256k1::Parity;
+
+        let keypairs = keypairs();
+        let config = GovernanceConfig::from_toml(&config_toml(&keypairs)).unwrap();
+        // Only keys with even y survive the x-only form unchanged
+        let even: Vec<_> = keypairs
+            .iter()
+            .map(|k| k.public_key())
+            .filter(|k| k.parity() == Parity::Even)
+            .collect();
+        assert!(!even.is_empty());
+
+        let mut toml = config_toml(&keypairs);
+        for key in &even {
+            toml = toml.replace(&key.to_string(), &hex::encode(key.to_xonly_bytes()));
+        }
+        let xonly = GovernanceConfig::from_toml(&toml).unwrap();
+        assert_eq!(xonly, config);
+        assert_eq!(
+            xonly.to_multisig().unwrap().public_keys(),
+            config.to_multisig().unwrap().public_keys()
+        );
+    }
+
+    #[test]
+    fn test_flat_only_config() {
+        let keypairs = keypairs();
+        let toml = config_toml(&keypairs);
+        let flat = &toml[..toml.find("[nested]").unwrap()];
+        let config = GovernanceConfig::from_toml(flat).unwrap();
+        assert!(config.nested.is_none());
+        assert!(config.to_nested_multisig().unwrap().is_none());
+        assert_eq!(config.to_multisig().unwrap().public_keys().len(), 4);
+    }
+
+    #[test]
+    fn test_from_file_toml_and_json() {
+        let keypairs = keypairs();
+        let config = GovernanceConfig::from_toml(&config_toml(&keypairs)).unwrap();
+        let dir = TempDir::new().unwrap();
+
+        let toml_path = dir.path().join("governance.toml");
+        std::fs::write(&toml_path, config_toml(&keypairs)).unwrap();
+        assert_eq!(GovernanceConfig::from_file(&toml_path).unwrap(), config);
+
+        let json_path = dir.path().join("governance.json");
+        std::fs::write(&json_path, serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(GovernanceConfig::from_file(&json_path).unwrap(), config);
+
+        assert!(matches!(
+            GovernanceConfig::from_file(dir.path().join("missing.toml")),
+            Err(GovernanceError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        let toml = config_toml(&keypairs());
+        let invalid = |toml: &str| GovernanceConfig::from_toml(toml).unwrap_err();
+
+        // Threshold over a different number of members
+        assert!(matches!(
+            invalid(&toml.replace("3-of-4", "3-of-5")),
+            GovernanceError::InvalidMultisig(_)
+        ));
+        assert!(matches!(
+            invalid(&toml.replace("3-of-4", "5-of-4")),
+            GovernanceError::Serialization(_)
+        ));
+        // Unknown team member, duplicate member
+        assert!(matches!(
+            invalid(&toml.replace("\"carol\", \"dave\"", "\"carol\", \"erin\"")),
+            GovernanceError::InvalidMultisig(_)
+        ));
+        assert!(matches!(
+            invalid(&toml.replace("github = \"bob\"", "github = \"alice\"")),
+            GovernanceError::InvalidMultisig(_)
+        ));
+        // More teams required than there are
+        assert!(matches!(
+            invalid(&toml.replace("teams_required = 2", "teams_required = 3")),
+            GovernanceError::InvalidThreshold { .. }
+        ));
+        // Not a key
+        assert!(matches!(
+            invalid(&toml.replacen("public_key = \"02", "public_key = \"05", 1)),
+            GovernanceError::Serialization(_)
+        ));
+    }
+}
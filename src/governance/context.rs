@@ -0,0 +1,156 @@
+//! # Secp256k1 Contexts
+//!
+//! The libsecp256k1 contexts the crate uses, pinned in one place.
+//!
+//! Verification paths run on a verification-only context, which holds no
+//! signing tables and cannot sign; paths that handle secret keys (signing,
+//! public key derivation, BIP32) use the full context. [`context_info`]
+//! reports the exact flags of each so deployments can check them.
+//!
+//! Both contexts are created once, on first use, and shared by every
+//! operation: building a context allocates and precomputes tables, which
+//! would otherwise dominate verifying many signatures.
+
+use secp256k1::{Context, Secp256k1};
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// Context type used for verification
+///
+/// It cannot sign or derive public keys:
+///
+/// ```compile_fail
+/// let secp = secp256k1::Secp256k1::verification_only();
+/// let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+/// let _ = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+/// ```
+pub type VerificationContext = secp256k1::VerifyOnly;
+
+/// Context type used for signing and key derivation
+pub type SigningContext = secp256k1::All;
+
+/// The contexts used by the crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextInfo {
+    /// libsecp256k1 flags of the verification context
+    pub verification_flags: u32,
+    /// Description of the verification context
+    pub verification: &'static str,
+    /// libsecp256k1 flags of the signing context
+    pub signing_flags: u32,
+    /// Description of the signing context
+    pub signing: &'static str,
+}
+
+/// Report the context configuration used by the crate
+pub fn context_info() -> ContextInfo {
+    ContextInfo {
+        verification_flags: VerificationContext::FLAGS,
+        verification: VerificationContext::DESCRIPTION,
+        signing_flags: SigningContext::FLAGS,
+        signing: SigningContext::DESCRIPTION,
+    }
+}
+
+/// Number of contexts created, to check they are shared
+#[cfg(test)]
+static CONTEXTS_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Shared context for verifying signatures
+pub(crate) fn verification_context() -> &'static Secp256k1<VerificationContext> {
+    static CONTEXT: OnceLock<Secp256k1<VerificationContext>> = OnceLock::new();
+    CONTEXT.get_or_init(|| {
+        #[cfg(test)]
+        CONTEXTS_CREATED.fetch_add(1, Ordering::SeqCst);
+        Secp256k1::verification_only()
+    })
+}
+
+/// Shared context for signing and deriving keys
+#[cfg(any(feature = "signing", test))]
+pub(crate) fn signing_context() -> &'static Secp256k1<SigningContext> {
+    static CONTEXT: OnceLock<Secp256k1<SigningContext>> = OnceLock::new();
+    CONTEXT.get_or_init(|| {
+        #[cfg(test)]
+        CONTEXTS_CREATED.fetch_add(1, Ordering::SeqCst);
+        Secp256k1::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::signatures::{
+        sign_message, sign_message_schnorr, verify_schnorr, verify_signature,
+    };
+    use crate::governance::GovernanceKeypair;
+    use secp256k1::{ffi, Signing, Verification};
+    use sha2::Digest;
+
+    fn assert_verification<C: Verification>() {}
+    fn assert_signing<C: Signing + Verification>() {}
+
+    #[test]
+    fn test_context_info() {
+        let info = context_info();
+        assert_eq!(info.verification_flags, ffi::SECP256K1_START_VERIFY);
+        assert_eq!(
+            info.signing_flags,
+            ffi::SECP256K1_START_SIGN | ffi::SECP256K1_START_VERIFY
+        );
+        assert_eq!(info.verification, "verification only");
+        assert_eq!(info.signing, "all capabilities");
+
+        // Only the signing context has the signing capability
+        let sign_bit = ffi::SECP256K1_START_SIGN & !ffi::SECP256K1_START_VERIFY;
+        assert_eq!(info.verification_flags & sign_bit, 0);
+        assert_ne!(info.signing_flags & sign_bit, 0);
+
+        assert_verification::<VerificationContext>();
+        assert_signing::<SigningContext>();
+    }
+
+    #[test]
+    fn test_verification_uses_verify_only_context() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let message = b"test message";
+
+        let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
+        assert!(verify_signature(&signature, message, &keypair.public_key()).unwrap());
+
+        let schnorr = sign_message_schnorr(keypair.secret_key.expose(), message).unwrap();
+        assert!(verify_schnorr(&schnorr, message, &keypair.public_key()).unwrap());
+
+        // A verify-only context accepts the same signatures
+        let secp = verification_context();
+        let digest = sha2::Sha256::digest(message);
+        let digest = secp256k1::Message::from_digest_slice(&digest).unwrap();
+        assert!(secp
+            .verify_ecdsa(&digest, &signature.inner, &keypair.public_key)
+            .is_ok());
+        let (x_only, _) = keypair.public_key.x_only_public_key();
+        assert!(secp
+            .verify_schnorr(&schnorr.inner, &digest, &x_only)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_contexts_are_shared() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let message = b"test message";
+        let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
+        assert!(verify_signature(&signature, message, &keypair.public_key()).unwrap());
+        let created = CONTEXTS_CREATED.load(Ordering::SeqCst);
+
+        for _ in 0..1000 {
+            assert!(verify_signature(&signature, message, &keypair.public_key()).unwrap());
+        }
+        sign_message(keypair.secret_key.expose(), message).unwrap();
+
+        assert_eq!(CONTEXTS_CREATED.load(Ordering::SeqCst), created);
+        assert!(created <= 2);
+        assert!(std::ptr::eq(verification_context(), verification_context()));
+        assert!(std::ptr::eq(signing_context(), signing_context()));
+    }
+}
@@ -0,0 +1,393 @@
+//! # Ed25519 Keys
+//!
+//! Ed25519 keys and signatures, alongside the secp256k1 governance keys,
+//! for participants who prefer Ed25519.
+//!
+//! [`KeyAlgorithm`] tags a key or signature with its algorithm, and
+//! [`AnyPublicKey`] / [`AnySignature`] hold either kind, so a committee
+//! mixing both is representable: [`MixedMultisig`] counts a signature only
+//! for a key of the same algorithm.
+
+use ed25519_dalek::VerifyingKey;
+#[cfg(any(feature = "signing", test))]
+use ed25519_dalek::{Signer, SigningKey};
+#[cfg(any(feature = "signing", test))]
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt;
+#[cfg(any(feature = "signing", test))]
+use zeroize::Zeroizing;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::{PublicKey, Signature, Threshold};
+
+/// An Ed25519 keypair
+#[cfg(any(feature = "signing", test))]
+#[derive(Debug, Clone)]
+pub struct Ed25519Keypair {
+    signing_key: SigningKey,
+}
+
+/// An Ed25519 public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ed25519PublicKey {
+    inner: VerifyingKey,
+}
+
+/// An Ed25519 signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ed25519Signature {
+    inner: ed25519_dalek::Signature,
+}
+
+#[cfg(any(feature = "signing", test))]
+impl Ed25519Keypair {
+    /// Generate a new random keypair
+    pub fn generate() -> GovernanceResult<Self> {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        OsRng.try_fill_bytes(&mut *seed).map_err(|e| {
+            GovernanceError::Cryptographic(format!("Failed to draw key material: {}", e))
+        })?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&seed),
+        })
+    }
+
+    /// Create a keypair from a 32-byte secret key
+    pub fn from_secret_key(secret_bytes: &[u8]) -> GovernanceResult<Self> {
+        let secret: &[u8; 32] = secret_bytes.try_into().map_err(|_| {
+            GovernanceError::InvalidKey(format!(
+                "Ed25519 secret key must be 32 bytes, got {}",
+                secret_bytes.len()
+            ))
+        })?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(secret),
+        })
+    }
+
+    /// Get the public key
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        Ed25519PublicKey {
+            inner: self.signing_key.verifying_key(),
+        }
+    }
+
+    /// Get the secret key bytes
+    pub fn secret_key_bytes(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.signing_key.to_bytes())
+    }
+
+    /// Sign a message
+    pub fn sign(&self, message: &[u8]) -> Ed25519Signature {
+        Ed25519Signature {
+            inner: self.signing_key.sign(message),
+        }
+    }
+}
+
+impl Ed25519PublicKey {
+    /// Create a public key from its 32 bytes
+    pub fn from_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
+        let bytes: &[u8; 32] = bytes.try_into().map_err(|_| {
+            GovernanceError::InvalidKey(format!(
+                "Ed25519 public key must be 32 bytes, got {}",
+                bytes.len()
+            ))
+        })?;
+        let inner = VerifyingKey::from_bytes(bytes)
+            .map_err(|e| GovernanceError::InvalidKey(format!("Invalid public key: {}", e)))?;
+        Ok(Self { inner })
+    }
+
+    /// Get the public key bytes
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.inner.to_bytes()
+    }
+
+    /// SHA256 fingerprint of the key bytes
+    pub fn sha256_fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(self.to_bytes()).into()
+    }
+
+    /// Verify a signature over a message
+    ///
+    /// Uses strict verification, rejecting weak keys and non-canonical
+    /// signatures.
+    pub fn verify(&self, message: &[u8], signature: &Ed25519Signature) -> bool {
+        self.inner.verify_strict(message, &signature.inner).is_ok()
+    }
+}
+
+impl Ed25519Signature {
+    /// Create a signature from its 64 bytes
+    pub fn from_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
+        let inner = ed25519_dalek::Signature::from_slice(bytes).map_err(|e| {
+            GovernanceError::InvalidSignatureFormat(format!("Invalid signature: {}", e))
+        })?;
+        Ok(Self { inner })
+    }
+
+    /// Get the signature bytes
+    pub fn to_bytes(&self) -> [u8; 64] {
+        self.inner.to_bytes()
+    }
+}
+
+impl fmt::Display for Ed25519PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Display for Ed25519Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+/// Signature algorithm of a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAlgorithm {
+    /// ECDSA over secp256k1 (the governance default)
+    Secp256k1,
+    /// Ed25519
+    Ed25519,
+}
+
+impl fmt::Display for KeyAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyAlgorithm::Secp256k1 => write!(f, "secp256k1"),
+            KeyAlgorithm::Ed25519 => write!(f, "ed25519"),
+        }
+    }
+}
+
+/// A public key of either algorithm
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnyPublicKey {
+    Secp256k1(PublicKey),
+    Ed25519(Ed25519PublicKey),
+}
+
+/// A signature of either algorithm
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnySignature {
+    Secp256k1(Signature),
+    Ed25519(Ed25519Signature),
+}
+
+impl AnyPublicKey {
+    /// The key's algorithm
+    pub fn algorithm(&self) -> KeyAlgorithm {
+        match self {
+            AnyPublicKey::Secp256k1(_) => KeyAlgorithm::Secp256k1,
+            AnyPublicKey::Ed25519(_) => KeyAlgorithm::Ed25519,
+        }
+    }
+
+    /// Verify a signature over a message
+    ///
+    /// A signature of the other algorithm never verifies.
+    pub fn verify(&self, message: &[u8], signature: &AnySignature) -> GovernanceResult<bool> {
+        match (self, signature) {
+            (AnyPublicKey::Secp256k1(key), AnySignature::Secp256k1(signature)) => {
+                crate::governance::verify_signature(signature, message, key)
+            }
+            (AnyPublicKey::Ed25519(key), AnySignature::Ed25519(signature)) => {
+                Ok(key.verify(message, signature))
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+impl AnySignature {
+    /// The signature's algorithm
+    pub fn algorithm(&self) -> KeyAlgorithm {
+        match self {
+            AnySignature::Secp256k1(_) => KeyAlgorithm::Secp256k1,
+            AnySignature::Ed25519(_) => KeyAlgorithm::Ed25519,
+        }
+    }
+}
+
+impl From<PublicKey> for AnyPublicKey {
+    fn from(key: PublicKey) -> Self {
+        AnyPublicKey::Secp256k1(key)
+    }
+}
+
+impl From<Ed25519PublicKey> for AnyPublicKey {
+    fn from(key: Ed25519PublicKey) -> Self {
+        AnyPublicKey::Ed25519(key)
+    }
+}
+
+impl From<Signature> for AnySignature {
+    fn from(signature: Signature) -> Self {
+        AnySignature::Secp256k1(signature)
+    }
+}
+
+impl From<Ed25519Signature> for AnySignature {
+    fn from(signature: Ed25519Signature) -> Self {
+        AnySignature::Ed25519(signature)
+    }
+}
+
+/// A multisig whose keys may use different algorithms
+///
+/// Each signature counts only for a key of its own algorithm, and each key
+/// at most once.
+#[derive(Debug, Clone)]
+pub struct MixedMultisig {
+    threshold: Threshold,
+    public_keys: Vec<AnyPublicKey>,
+}
+
+impl MixedMultisig {
+    /// Create a mixed multisig configuration from a [`Threshold`]
+    pub fn new(threshold: Threshold, public_keys: Vec<AnyPublicKey>) -> GovernanceResult<Self> {
+        if public_keys.len() != threshold.total() {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "Expected {} public keys, got {}",
+                threshold.total(),
+                public_keys.len()
+            )));
+        }
+        let unique_keys: HashSet<_> = public_keys.iter().collect();
+        if unique_keys.len() != public_keys.len() {
+            return Err(GovernanceError::InvalidMultisig(
+                "Duplicate public keys not allowed".to_string(),
+            ));
+        }
+        Ok(Self {
+            threshold,
+            public_keys,
+        })
+    }
+
+    /// Indices of the keys with a valid signature over `message`
+    pub fn collect_valid_signatures(
+        &self,
+        message: &[u8],
+        signatures: &[AnySignature],
+    ) -> GovernanceResult<Vec<usize>> {
+        let mut signed = Vec::new();
+        for signature in signatures {
+            for (i, key) in self.public_keys.iter().enumerate() {
+                if !signed.contains(&i) && key.verify(message, signature)? {
+                    signed.push(i);
+                    break;
+                }
+            }
+        }
+        Ok(signed)
+    }
+
+    /// Whether distinct keys signed `message` up to the threshold
+    pub fn verify(&self, message: &[u8], signatures: &[AnySignature]) -> GovernanceResult<bool> {
+        let signed = self.collect_valid_signatures(message, signatures)?;
+        Ok(self.threshold.is_met(signed.len()))
+    }
+
+    /// The threshold
+    pub fn m_of_n(&self) -> Threshold {
+        self.threshold
+    }
+
+    /// The public keys
+    pub fn public_keys(&self) -> &[AnyPublicKey] {
+        &self.public_keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = Ed25519Keypair::generate().unwrap();
+        let signature = keypair.sign(b"release v1.0.0");
+        assert!(keypair.public_key().verify(b"release v1.0.0", &signature));
+        assert!(!keypair.public_key().verify(b"release v1.0.1", &signature));
+
+        let other = Ed25519Keypair::generate().unwrap();
+        assert!(!other.public_key().verify(b"release v1.0.0", &signature));
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let keypair = Ed25519Keypair::generate().unwrap();
+        let restored = Ed25519Keypair::from_secret_key(&*keypair.secret_key_bytes()).unwrap();
+        assert_eq!(restored.public_key(), keypair.public_key());
+
+        let public_key = Ed25519PublicKey::from_bytes(&keypair.public_key().to_bytes()).unwrap();
+        assert_eq!(public_key, keypair.public_key());
+
+        let signature = keypair.sign(b"message");
+        let parsed = Ed25519Signature::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(public_key.verify(b"message", &parsed));
+
+        assert!(Ed25519PublicKey::from_bytes(&[2; 33]).is_err());
+        assert!(Ed25519Signature::from_bytes(&[0; 63]).is_err());
+        assert!(Ed25519Keypair::from_secret_key(&[1; 31]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_secp256k1_signature_for_ed25519_key() {
+        let message = b"release v1.0.0";
+        let secp = GovernanceKeypair::generate().unwrap();
+        let secp_signature: AnySignature = crate::sign_message(secp.secret_key.expose(), message)
+            .unwrap()
+            .into();
+        let ed25519 = Ed25519Keypair::generate().unwrap();
+        let ed25519_key = AnyPublicKey::from(ed25519.public_key());
+
+        assert_eq!(secp_signature.algorithm(), KeyAlgorithm::Secp256k1);
+        assert_eq!(ed25519_key.algorithm(), KeyAlgorithm::Ed25519);
+        assert!(!ed25519_key.verify(message, &secp_signature).unwrap());
+        assert!(AnyPublicKey::from(secp.public_key())
+            .verify(message, &secp_signature)
+            .unwrap());
+        // Nor the other way round
+        let ed25519_signature = AnySignature::from(ed25519.sign(message));
+        assert!(!AnyPublicKey::from(secp.public_key())
+            .verify(message, &ed25519_signature)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_mixed_multisig() {
+        let message = b"release v1.0.0";
+        let secp = GovernanceKeypair::generate().unwrap();
+        let ed25519 = Ed25519Keypair::generate().unwrap();
+        let multisig = MixedMultisig::new(
+            Threshold::new(2, 2).unwrap(),
+            vec![secp.public_key().into(), ed25519.public_key().into()],
+        )
+        .unwrap();
+
+        let secp_signature =
+            AnySignature::from(crate::sign_message(secp.secret_key.expose(), message).unwrap());
+        let ed25519_signature = AnySignature::from(ed25519.sign(message));
+        assert!(multisig
+            .verify(message, &[secp_signature.clone(), ed25519_signature])
+            .unwrap());
+        // The same signature twice counts once
+        assert!(!multisig
+            .verify(message, &[secp_signature.clone(), secp_signature])
+            .unwrap());
+
+        assert!(MixedMultisig::new(
+            Threshold::new(1, 2).unwrap(),
+            vec![ed25519.public_key().into(), ed25519.public_key().into()],
+        )
+        .is_err());
+    }
+}
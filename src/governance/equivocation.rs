@@ -0,0 +1,221 @@
+//! # Equivocation Detection
+//!
+//! Detects maintainers signing two conflicting messages in the same epoch.
+//!
+//! Some governance schemes require each signer to endorse at most one message
+//! per epoch. The detector records the message hash each signer signed per
+//! epoch and reports the conflicting pair when a second, different message
+//! shows up.
+//!
+//! For forensics over stored bundles, [`detect_equivocation`] reports every
+//! committee member who validly signed more than one distinct message.
+
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::governance::nested_multisig::Committee;
+use crate::governance::signatures::verify_signature;
+use crate::governance::{Fingerprint, MessageId, PublicKey, SignatureBundle};
+
+/// A signer who signed two different messages in the same epoch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equivocation {
+    pub signer: PublicKey,
+    pub epoch: u64,
+    /// Hash of the message recorded first
+    pub first_message_hash: [u8; 32],
+    /// Hash of the conflicting message
+    pub second_message_hash: [u8; 32],
+}
+
+/// Records `(signer, epoch) -> message hash` and flags conflicts
+#[derive(Debug, Clone, Default)]
+pub struct EquivocationDetector {
+    seen: HashMap<(PublicKey, u64), [u8; 32]>,
+}
+
+impl EquivocationDetector {
+    /// Create an empty detector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `signer` signed `message` in `epoch`
+    ///
+    /// Returns the conflicting pair if the signer already signed a different
+    /// message in the same epoch. Signing the same message again is not an
+    /// equivocation. The first recorded message is kept.
+    pub fn record(
+        &mut self,
+        signer: &PublicKey,
+        epoch: u64,
+        message: &[u8],
+    ) -> Option<Equivocation> {
+        let message_hash: [u8; 32] = Sha256::digest(message).into();
+
+        match self.seen.get(&(signer.clone(), epoch)) {
+            Some(first) if *first != message_hash => Some(Equivocation {
+                signer: signer.clone(),
+                epoch,
+                first_message_hash: *first,
+                second_message_hash: message_hash,
+            }),
+            Some(_) => None,
+            None => {
+                self.seen.insert((signer.clone(), epoch), message_hash);
+                None
+            }
+        }
+    }
+
+    /// Get the message hash a signer signed in an epoch, if any
+    pub fn message_hash(&self, signer: &PublicKey, epoch: u64) -> Option<&[u8; 32]> {
+        self.seen.get(&(signer.clone(), epoch))
+    }
+
+    /// Forget all records for epochs before `epoch`
+    pub fn prune_before(&mut self, epoch: u64) {
+        self.seen.retain(|(_, e), _| *e >= epoch);
+    }
+}
+
+/// Committee members who validly signed more than one distinct message
+/// across `bundles`
+///
+/// Each member is reported by key fingerprint with the ids of the messages
+/// they signed, in the order the bundles first show them. Unlike
+/// [`EquivocationDetector`], epochs are not considered: any two distinct
+/// messages count. Members are sorted by fingerprint.
+pub fn detect_equivocation(
+    bundles: &[SignatureBundle],
+    committee: &Committee,
+) -> Vec<(Fingerprint, Vec<MessageId>)> {
+    let members: Vec<&PublicKey> = committee
+        .teams()
+        .iter()
+        .flat_map(|team| team.maintainers.iter().map(|m| &m.public_key))
+        .collect();
+
+    let mut signed: BTreeMap<Fingerprint, Vec<MessageId>> = BTreeMap::new();
+    for bundle in bundles {
+        let message = bundle.message.to_signing_bytes();
+        let id = bundle.message.id();
+        for signature in &bundle.signatures {
+            let signer = members
+                .iter()
+                .find(|key| verify_signature(signature, &message, key).unwrap_or(false));
+            if let Some(signer) = signer {
+                let ids = signed.entry(signer.fingerprint()).or_default();
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+
+    signed
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    #[test]
+    fn test_conflicting_messages_flagged() {
+        let signer = GovernanceKeypair::generate().unwrap().public_key();
+        let mut detector = EquivocationDetector::new();
+
+        assert!(detector.record(&signer, 7, b"RELEASE:v1.0.0:abc").is_none());
+        let equivocation = detector.record(&signer, 7, b"RELEASE:v1.0.0:def").unwrap();
+
+        assert_eq!(equivocation.signer, signer);
+        assert_eq!(equivocation.epoch, 7);
+        assert_eq!(
+            equivocation.first_message_hash,
+            <[u8; 32]>::from(Sha256::digest(b"RELEASE:v1.0.0:abc"))
+        );
+        assert_eq!(
+            equivocation.second_message_hash,
+            <[u8; 32]>::from(Sha256::digest(b"RELEASE:v1.0.0:def"))
+        );
+    }
+
+    #[test]
+    fn test_same_message_not_flagged() {
+        let signer = GovernanceKeypair::generate().unwrap().public_key();
+        let mut detector = EquivocationDetector::new();
+
+        assert!(detector.record(&signer, 7, b"RELEASE:v1.0.0:abc").is_none());
+        assert!(detector.record(&signer, 7, b"RELEASE:v1.0.0:abc").is_none());
+    }
+
+    #[test]
+    fn test_different_epochs_and_signers_not_flagged() {
+        let signer = GovernanceKeypair::generate().unwrap().public_key();
+        let other = GovernanceKeypair::generate().unwrap().public_key();
+        let mut detector = EquivocationDetector::new();
+
+        assert!(detector.record(&signer, 7, b"message a").is_none());
+        assert!(detector.record(&signer, 8, b"message b").is_none());
+        assert!(detector.record(&other, 7, b"message b").is_none());
+
+        detector.prune_before(8);
+        assert!(detector.message_hash(&signer, 7).is_none());
+        assert!(detector.message_hash(&signer, 8).is_some());
+    }
+
+    #[test]
+    fn test_detect_equivocation_across_bundles() {
+        use crate::governance::nested_multisig::{Team, TeamMaintainer};
+        use crate::governance::GovernanceMessage;
+
+        let keypairs: Vec<GovernanceKeypair> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let committee = Committee::new(vec![Team {
+            id: "core".to_string(),
+            name: "Core".to_string(),
+            maintainers: keypairs
+                .iter()
+                .enumerate()
+                .map(|(i, kp)| TeamMaintainer {
+                    github: format!("maintainer{}", i),
+                    public_key: kp.public_key(),
+                })
+                .collect(),
+        }])
+        .unwrap();
+        let release = |commit: &str| GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: commit.to_string(),
+        };
+        let bundle = |message: GovernanceMessage, signers: &[&GovernanceKeypair]| {
+            let bytes = message.to_signing_bytes();
+            let signatures = signers
+                .iter()
+                .map(|kp| crate::sign_message(kp.secret_key.expose(), &bytes).unwrap())
+                .collect();
+            SignatureBundle::with_signatures(message, signatures)
+        };
+
+        let bundles = [
+            bundle(release("abc"), &[&keypairs[0], &keypairs[1]]),
+            bundle(release("def"), &[&keypairs[1], &keypairs[2]]),
+            // Signing the same message again is not equivocation
+            bundle(release("abc"), &[&keypairs[0]]),
+        ];
+
+        assert_eq!(
+            detect_equivocation(&bundles, &committee),
+            vec![(
+                keypairs[1].public_key().fingerprint(),
+                vec![release("abc").id(), release("def").id()]
+            )]
+        );
+        assert!(detect_equivocation(&bundles[..1], &committee).is_empty());
+    }
+}
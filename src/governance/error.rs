@@ -50,7 +50,98 @@ pub enum GovernanceError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// Checksum of an encoded key does not match
+    #[error("Invalid checksum: {0}")]
+    InvalidChecksum(String),
+
+    /// Version byte of an encoded key names no supported network
+    #[error("Unknown network prefix: 0x{prefix:02x}")]
+    InvalidNetworkPrefix { prefix: u8 },
+
+    /// Too many chunks of a paper backup are lost or damaged
+    #[error("Unrecoverable backup: {missing} chunks missing or damaged, at most {tolerated} can be recovered")]
+    UnrecoverableBackup { missing: usize, tolerated: usize },
+
     /// Feature not yet implemented
     #[error("Not implemented: {0}")]
     NotImplemented(String),
 }
+
+impl GovernanceError {
+    /// Stable machine-readable error code
+    ///
+    /// Codes never change between releases, unlike the human-readable
+    /// message, so FFI and JSON consumers can match on them.
+    pub fn code(&self) -> &'static str {
+        match self {
+            GovernanceError::InvalidKey(_) => "INVALID_KEY",
+            GovernanceError::SignatureVerification(_) => "SIGNATURE_VERIFICATION",
+            GovernanceError::InvalidMultisig(_) => "INVALID_MULTISIG",
+            GovernanceError::MessageFormat(_) => "MESSAGE_FORMAT",
+            GovernanceError::Cryptographic(_) => "CRYPTOGRAPHIC",
+            GovernanceError::Serialization(_) => "SERIALIZATION",
+            GovernanceError::InvalidThreshold { .. } => "INVALID_THRESHOLD",
+            GovernanceError::InsufficientSignatures { .. } => "INSUFFICIENT_SIGNATURES",
+            GovernanceError::InvalidSignatureFormat(_) => "INVALID_SIGNATURE_FORMAT",
+            GovernanceError::InvalidInput(_) => "INVALID_INPUT",
+            GovernanceError::InvalidChecksum(_) => "INVALID_CHECKSUM",
+            GovernanceError::InvalidNetworkPrefix { .. } => "INVALID_NETWORK_PREFIX",
+            GovernanceError::UnrecoverableBackup { .. } => "UNRECOVERABLE_BACKUP",
+            GovernanceError::NotImplemented(_) => "NOT_IMPLEMENTED",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        let s = || "detail".to_string();
+        let cases = [
+            (GovernanceError::InvalidKey(s()), "INVALID_KEY"),
+            (
+                GovernanceError::SignatureVerification(s()),
+                "SIGNATURE_VERIFICATION",
+            ),
+            (GovernanceError::InvalidMultisig(s()), "INVALID_MULTISIG"),
+            (GovernanceError::MessageFormat(s()), "MESSAGE_FORMAT"),
+            (GovernanceError::Cryptographic(s()), "CRYPTOGRAPHIC"),
+            (GovernanceError::Serialization(s()), "SERIALIZATION"),
+            (
+                GovernanceError::InvalidThreshold {
+                    threshold: 4,
+                    total: 3,
+                },
+                "INVALID_THRESHOLD",
+            ),
+            (
+                GovernanceError::InsufficientSignatures { got: 1, need: 2 },
+                "INSUFFICIENT_SIGNATURES",
+            ),
+            (
+                GovernanceError::InvalidSignatureFormat(s()),
+                "INVALID_SIGNATURE_FORMAT",
+            ),
+            (GovernanceError::InvalidInput(s()), "INVALID_INPUT"),
+            (GovernanceError::InvalidChecksum(s()), "INVALID_CHECKSUM"),
+            (
+                GovernanceError::InvalidNetworkPrefix { prefix: 0x00 },
+                "INVALID_NETWORK_PREFIX",
+            ),
+            (
+                GovernanceError::UnrecoverableBackup {
+                    missing: 5,
+                    tolerated: 4,
+                },
+                "UNRECOVERABLE_BACKUP",
+            ),
+            (GovernanceError::NotImplemented(s()), "NOT_IMPLEMENTED"),
+        ];
+
+        for (error, code) in cases {
+            assert_eq!(error.code(), code);
+        }
+    }
+}
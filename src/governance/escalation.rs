@@ -0,0 +1,717 @@
+//! # Threshold Escalation
+//!
+//! Policy rules that demand more approval for riskier changes.
+//!
+//! A rule names the attribute values it applies to and what it requires: a
+//! higher threshold, a nested (team) approval, or a delay between reaching
+//! the threshold and accepting the artifact. Attributes come only from the
+//! [`SignedAttributes`] of valid signers, plus the implicit `type` attribute:
+//! the kind of the signed message (`binary`, `bundle`, `checksums`, `image`,
+//! `release`, `module`, `budget`, `config`), read from the message itself.
+//!
+//! Conditions are exact values, prefixes (`consensus/*`) or numeric bounds
+//! (`>=500`); an attribute holding a comma-separated list matches if any item
+//! does. An attribute a rule tests that no valid signer attested, or that
+//! signers attested differently, might match: such rules apply as well, and
+//! of all applicable rules the strictest wins. Leaving out `hard-fork=false`
+//! therefore never lowers the bar.
+//!
+//! ```toml
+//! [[escalation]]
+//! name = "consensus-paths"
+//! threshold = "6-of-7"
+//! when = { type = "module", paths = "consensus/*" }
+//!
+//! [[escalation]]
+//! name = "hard-fork"
+//! threshold = "6-of-7"
+//! nested = true
+//! delay = "30d"
+//! when = { type = "release", hard-fork = "true" }
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::Duration;
+
+use crate::governance::attributes::{SignedAttributes, TYPE_ATTRIBUTE};
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::pipeline::{StageResult, VerificationContext, VerificationStage};
+use crate::governance::{PublicKey, Threshold};
+use crate::util::humanize::{format_duration, parse_duration};
+
+/// Condition on one attribute, written as a string in policies
+///
+/// `>=N`, `>N`, `<=N` and `<N` compare numbers, a trailing `*` matches a
+/// prefix, and anything else must match exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Condition {
+    /// The value equals this string
+    Equals(String),
+    /// The value starts with this string
+    Prefix(String),
+    /// The value is a number of at least this
+    AtLeast(u64),
+    /// The value is a number of at most this
+    AtMost(u64),
+}
+
+impl Condition {
+    /// Whether `value` satisfies the condition, or `None` when a numeric
+    /// condition meets a value that is not a number
+    pub fn test(&self, value: &str) -> Option<bool> {
+        let mut items = value.split(',').map(str::trim);
+        match self {
+            Condition::Equals(expected) => Some(items.any(|item| item == expected)),
+            Condition::Prefix(prefix) => Some(items.any(|item| item.starts_with(prefix.as_str()))),
+            Condition::AtLeast(bound) => value.trim().parse::<u64>().ok().map(|n| n >= *bound),
+            Condition::AtMost(bound) => value.trim().parse::<u64>().ok().map(|n| n <= *bound),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Equals(value) => write!(f, "{}", value),
+            Condition::Prefix(prefix) => write!(f, "{}*", prefix),
+            Condition::AtLeast(bound) => write!(f, ">={}", bound),
+            Condition::AtMost(bound) => write!(f, "<={}", bound),
+        }
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = GovernanceError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let number = |bound: &str| {
+            bound.trim().parse::<u64>().map_err(|_| {
+                GovernanceError::InvalidInput(format!("Invalid condition '{}': not a number", s))
+            })
+        };
+        let out_of_range =
+            || GovernanceError::InvalidInput(format!("Invalid condition '{}': out of range", s));
+
+        if let Some(bound) = s.strip_prefix(">=") {
+            Ok(Condition::AtLeast(number(bound)?))
+        } else if let Some(bound) = s.strip_prefix("<=") {
+            Ok(Condition::AtMost(number(bound)?))
+        } else if let Some(bound) = s.strip_prefix('>') {
+            Ok(Condition::AtLeast(
+                number(bound)?.checked_add(1).ok_or_else(out_of_range)?,
+            ))
+        } else if let Some(bound) = s.strip_prefix('<') {
+            Ok(Condition::AtMost(
+                number(bound)?.checked_sub(1).ok_or_else(out_of_range)?,
+            ))
+        } else if let Some(prefix) = s.strip_suffix('*') {
+            Ok(Condition::Prefix(prefix.to_string()))
+        } else {
+            Ok(Condition::Equals(s))
+        }
+    }
+}
+
+/// An escalation rule as written in a policy
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EscalationRule {
+    /// Rule name, reported when the rule applies
+    pub name: String,
+    /// Conditions on attributes, all of which must hold
+    #[serde(default)]
+    pub when: BTreeMap<String, Condition>,
+    /// Threshold required instead of the configured one
+    #[serde(default)]
+    pub threshold: Option<Threshold>,
+    /// Whether a nested (team) approval is required as well
+    #[serde(default)]
+    pub nested: bool,
+    /// Time that must pass after the threshold is reached (`30d`, `72h`, ...)
+    #[serde(default)]
+    pub delay: Option<String>,
+}
+
+/// Attribute values attested by the valid signers of one message
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttestedAttributes {
+    values: BTreeMap<String, String>,
+    conflicting: BTreeSet<String>,
+}
+
+impl AttestedAttributes {
+    /// Collect the attributes that `signers` attested for `message`
+    ///
+    /// Attestations by anyone else are ignored. An attribute attested with
+    /// different values is conflicting and has no value.
+    pub fn collect(
+        message: &[u8],
+        attestations: &[SignedAttributes],
+        signers: &[&PublicKey],
+    ) -> Self {
+        let mut attributes = Self::default();
+        attributes
+            .values
+            .insert(TYPE_ATTRIBUTE.to_string(), message_type(message));
+
+        for attestation in attestations {
+            if !signers.iter().any(|key| attestation.verify(message, key)) {
+                continue;
+            }
+            for (name, value) in &attestation.attributes {
+                if name == TYPE_ATTRIBUTE || attributes.conflicting.contains(name) {
+                    continue;
+                }
+                match attributes.values.get(name) {
+                    None => {
+                        attributes.values.insert(name.clone(), value.clone());
+                    }
+                    Some(existing) if existing != value => {
+                        attributes.values.remove(name);
+                        attributes.conflicting.insert(name.clone());
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        attributes
+    }
+
+    /// Value of an attribute, if attested without conflict
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Whether signers attested different values for an attribute
+    pub fn is_conflicting(&self, name: &str) -> bool {
+        self.conflicting.contains(name)
+    }
+}
+
+/// Kind of a signed message: its first field, lowercased
+fn message_type(message: &[u8]) -> String {
+    let message = String::from_utf8_lossy(message);
+    message
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// The rule that applies to a message, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSelection<'a> {
+    pub rule: &'a EscalationRule,
+    pub reason: String,
+}
+
+/// Applies the strictest matching escalation rule
+///
+/// Runs after the signature stage. Passes without rules or when no rule
+/// applies; otherwise the detail names the rule, why it applies, and each of
+/// its requirements.
+#[derive(Debug, Clone, Default)]
+pub struct EscalationStage {
+    rules: Vec<(EscalationRule, Option<Duration>)>,
+}
+
+impl EscalationStage {
+    /// Create the stage, checking rule names and delays
+    pub fn new(rules: Vec<EscalationRule>) -> GovernanceResult<Self> {
+        let mut names = BTreeSet::new();
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                if !names.insert(rule.name.clone()) {
+                    return Err(GovernanceError::InvalidInput(format!(
+                        "Escalation rule '{}' appears more than once",
+                        rule.name
+                    )));
+                }
+                let delay = rule
+                    .delay
+                    .as_deref()
+                    .map(parse_duration)
+                    .transpose()
+                    .map_err(|e| {
+                        GovernanceError::InvalidInput(format!(
+                            "Escalation rule '{}' delay: {}",
+                            rule.name, e
+                        ))
+                    })?;
+                Ok((rule, delay))
+            })
+            .collect::<GovernanceResult<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    /// The strictest rule that applies to the attributes, if any
+    ///
+    /// Rules are ordered by required signatures, then nested approval, then
+    /// delay; of equally strict rules the first listed wins.
+    pub fn select(&self, attributes: &AttestedAttributes) -> Option<RuleSelection<'_>> {
+        let mut applicable = Vec::new();
+        'rules: for (rule, delay) in &self.rules {
+            let mut reasons = Vec::new();
+            let mut unknown = false;
+            for (name, condition) in &rule.when {
+                match attributes.get(name) {
+                    Some(value) => match condition.test(value) {
+                        Some(true) => {
+                            reasons.push(format!("{}={} matches {}", name, value, condition))
+                        }
+                        Some(false) => continue 'rules,
+                        None => {
+                            unknown = true;
+                            reasons.push(format!("{}={} is not a number", name, value));
+                        }
+                    },
+                    None if attributes.is_conflicting(name) => {
+                        unknown = true;
+                        reasons.push(format!("{} was attested with different values", name));
+                    }
+                    None => {
+                        unknown = true;
+                        reasons.push(format!("{} was not attested", name));
+                    }
+                }
+            }
+            if reasons.is_empty() {
+                reasons.push("applies to every message".to_string());
+            }
+            let strictness = (
+                rule.threshold.map_or(0, |threshold| threshold.required()),
+                rule.nested,
+                delay.unwrap_or_default(),
+            );
+            applicable.push((rule, reasons.join(", "), unknown, strictness));
+        }
+
+        let count = applicable.len();
+        let mut strictest: Option<(&EscalationRule, String, bool, _)> = None;
+        for candidate in applicable {
+            let stricter = match &strictest {
+                Some(best) => candidate.3 > best.3,
+                None => true,
+            };
+            if stricter {
+                strictest = Some(candidate);
+            }
+        }
+        strictest.map(|(rule, mut reason, unknown, _)| {
+            if unknown {
+                reason.push_str("; unknown attributes are assumed to match");
+            }
+            if count > 1 {
+                reason.push_str(&format!("; strictest of {} applicable rules", count));
+            }
+            RuleSelection { rule, reason }
+        })
+    }
+
+    fn delay(&self, rule: &EscalationRule) -> Option<Duration> {
+        self.rules
+            .iter()
+            .find(|(candidate, _)| candidate.name == rule.name)
+            .and_then(|(_, delay)| *delay)
+    }
+}
+
+impl VerificationStage for EscalationStage {
+    fn name(&self) -> &str {
+        "escalation"
+    }
+
+    fn after(&self) -> &[&str] {
+        &["signature"]
+    }
+
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult {
+        if self.rules.is_empty() {
+            return StageResult::Skipped("no escalation rules".to_string());
+        }
+        let signers: Vec<&PublicKey> = ctx
+            .valid_signers
+            .iter()
+            .map(|&i| &ctx.public_keys[i])
+            .collect();
+        let attributes = AttestedAttributes::collect(&ctx.message, &ctx.attributes, &signers);
+        let Some(RuleSelection { rule, reason }) = self.select(&attributes) else {
+            return StageResult::Pass("no escalation rule applies".to_string());
+        };
+
+        let mut met = Vec::new();
+        let mut unmet = Vec::new();
+        if let Some(threshold) = rule.threshold {
+            let (required, total) = (threshold.required(), threshold.total());
+            let signed = format!("{} of {} signed (need {})", signers.len(), total, required);
+            if ctx.public_keys.len() != total {
+                unmet.push(format!(
+                    "threshold {} expects {} public keys, got {}",
+                    threshold,
+                    total,
+                    ctx.public_keys.len()
+                ));
+            } else if threshold.is_met(signers.len()) {
+                met.push(signed);
+            } else {
+                unmet.push(signed);
+            }
+        }
+        if rule.nested {
+            match ctx.nested_approved {
+                Some(true) => met.push("nested approval verified".to_string()),
+                Some(false) => unmet.push("nested approval not met".to_string()),
+                None => unmet.push("nested approval required but not checked".to_string()),
+            }
+        }
+        if let Some(delay) = self.delay(rule) {
+            let required = rule
+                .threshold
+                .or(ctx.threshold)
+                .map_or(1, |threshold| threshold.required());
+            match threshold_reached_at(ctx, &signers, required) {
+                Some(reached) => {
+                    let elapsed = (ctx.now - reached).to_std().unwrap_or_default();
+                    let detail = format!(
+                        "threshold reached {} ago (delay {})",
+                        format_duration(elapsed),
+                        format_duration(delay)
+                    );
+                    if elapsed >= delay {
+                        met.push(detail);
+                    } else {
+                        unmet.push(detail);
+                    }
+                }
+                None => unmet.push(format!(
+                    "delay {} needs {} signed timestamp(s)",
+                    format_duration(delay),
+                    required
+                )),
+            }
+        }
+
+        let applies = format!("rule '{}' applies ({})", rule.name, reason);
+        if !unmet.is_empty() {
+            StageResult::Fail(format!("{}: {}", applies, unmet.join(", ")))
+        } else if met.is_empty() {
+            StageResult::Pass(format!("{}: no additional requirements", applies))
+        } else {
+            StageResult::Pass(format!("{}: {}", applies, met.join(", ")))
+        }
+    }
+}
+
+/// When the `required`-th signer signed, by the signers' signed timestamps
+fn threshold_reached_at(
+    ctx: &VerificationContext,
+    signers: &[&PublicKey],
+    required: usize,
+) -> Option<DateTime<Utc>> {
+    let mut signed_at: Vec<DateTime<Utc>> = signers
+        .iter()
+        .filter_map(|key| {
+            ctx.timestamps
+                .iter()
+                .filter(|timestamp| timestamp.verify(&ctx.message, key))
+                .map(|timestamp| timestamp.signed_at)
+                .max()
+        })
+        .collect();
+    signed_at.sort_unstable();
+    signed_at.get(required.checked_sub(1)?).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::pipeline::SignatureStage;
+    use crate::governance::{GovernanceKeypair, SignedTimestamp};
+
+    const RULES: &str = r#"
+        [[escalation]]
+        name = "consensus-paths"
+        threshold = "6-of-7"
+        when = { type = "module", paths = "consensus/*" }
+
+        [[escalation]]
+        name = "large-change"
+        threshold = "5-of-7"
+        when = { type = "module", lines-changed = ">=500" }
+
+        [[escalation]]
+        name = "hard-fork"
+        threshold = "6-of-7"
+        nested = true
+        delay = "30d"
+        when = { type = "release", hard-fork = "true" }
+    "#;
+
+    #[derive(Deserialize)]
+    struct Rules {
+        escalation: Vec<EscalationRule>,
+    }
+
+    fn stage() -> EscalationStage {
+        EscalationStage::new(toml::from_str::<Rules>(RULES).unwrap().escalation).unwrap()
+    }
+
+    fn keypairs() -> Vec<GovernanceKeypair> {
+        (0..7)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect()
+    }
+
+    /// Context where the first `signers` keys signed `message`, each
+    /// attesting `attributes`
+    fn context(
+        keypairs: &[GovernanceKeypair],
+        signers: usize,
+        message: &[u8],
+        attributes: &[(&str, &str)],
+    ) -> VerificationContext {
+        let mut ctx = VerificationContext::new(message.to_vec(), "artifact", "");
+        ctx.public_keys = keypairs.iter().map(|kp| kp.public_key()).collect();
+        ctx.threshold = Some(Threshold::new(4, 7).unwrap());
+        let attributes: BTreeMap<String, String> = attributes
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        for keypair in &keypairs[..signers] {
+            ctx.signatures
+                .push(crate::sign_message(keypair.secret_key.expose(), message).unwrap());
+            if !attributes.is_empty() {
+                ctx.attributes.push(
+                    SignedAttributes::sign(
+                        keypair.secret_key.expose(),
+                        message,
+                        attributes.clone(),
+                    )
+                    .unwrap(),
+                );
+            }
+        }
+        SignatureStage.check(&mut ctx);
+        ctx
+    }
+
+    fn selected(ctx: &VerificationContext) -> Option<String> {
+        let stage = stage();
+        let signers: Vec<&PublicKey> = ctx
+            .valid_signers
+            .iter()
+            .map(|&i| &ctx.public_keys[i])
+            .collect();
+        let attributes = AttestedAttributes::collect(&ctx.message, &ctx.attributes, &signers);
+        stage
+            .select(&attributes)
+            .map(|selection| selection.rule.name.clone())
+    }
+
+    #[test]
+    fn test_conditions() {
+        let condition = |s: &str| Condition::try_from(s.to_string()).unwrap();
+        assert_eq!(
+            condition("consensus/*"),
+            Condition::Prefix("consensus/".into())
+        );
+        assert_eq!(condition(">500"), Condition::AtLeast(501));
+        assert_eq!(condition("<=10"), Condition::AtMost(10));
+        assert!(Condition::try_from("<0".to_string()).is_err());
+        assert!(Condition::try_from(">=many".to_string()).is_err());
+
+        assert_eq!(
+            condition("consensus/*").test("net/peer.rs, consensus/pow.rs"),
+            Some(true)
+        );
+        assert_eq!(condition("true").test("false"), Some(false));
+        assert_eq!(condition(">=500").test("499"), Some(false));
+        assert_eq!(condition(">=500").test("lots"), None);
+    }
+
+    #[test]
+    fn test_rule_selection() {
+        let keypairs = keypairs();
+        let module = b"MODULE:lightning:1.0.0";
+        let release = b"RELEASE:v2.0.0:abc123";
+
+        let consensus = context(
+            &keypairs,
+            4,
+            module,
+            &[("paths", "consensus/pow.rs"), ("lines-changed", "20")],
+        );
+        assert_eq!(selected(&consensus).as_deref(), Some("consensus-paths"));
+
+        let large = context(
+            &keypairs,
+            4,
+            module,
+            &[("paths", "net/peer.rs"), ("lines-changed", "800")],
+        );
+        assert_eq!(selected(&large).as_deref(), Some("large-change"));
+
+        // Both match: the 6-of-7 rule is stricter
+        let both = context(
+            &keypairs,
+            4,
+            module,
+            &[("paths", "consensus/pow.rs"), ("lines-changed", "800")],
+        );
+        assert_eq!(selected(&both).as_deref(), Some("consensus-paths"));
+
+        let small = context(
+            &keypairs,
+            4,
+            module,
+            &[("paths", "docs/README.md"), ("lines-changed", "3")],
+        );
+        assert_eq!(selected(&small), None);
+
+        let soft_fork = context(&keypairs, 4, release, &[("hard-fork", "false")]);
+        assert_eq!(selected(&soft_fork), None);
+        let hard_fork = context(&keypairs, 4, release, &[("hard-fork", "true")]);
+        assert_eq!(selected(&hard_fork).as_deref(), Some("hard-fork"));
+
+        // The report names the rule and why it applies
+        let mut ctx = consensus;
+        let result = stage().check(&mut ctx);
+        assert_eq!(
+            result,
+            StageResult::Fail(
+                "rule 'consensus-paths' applies (paths=consensus/pow.rs matches consensus/*, \
+                 type=module matches module): 4 of 7 signed (need 6)"
+                    .to_string()
+            )
+        );
+        let mut ctx = context(
+            &keypairs,
+            6,
+            module,
+            &[("paths", "consensus/pow.rs"), ("lines-changed", "20")],
+        );
+        assert!(!stage().check(&mut ctx).is_fail());
+    }
+
+    #[test]
+    fn test_missing_attributes_select_strictest() {
+        let keypairs = keypairs();
+
+        // Without any attributes both module rules may apply
+        let ctx = context(&keypairs, 4, b"MODULE:lightning:1.0.0", &[]);
+        assert_eq!(selected(&ctx).as_deref(), Some("consensus-paths"));
+
+        // A non-numeric line count cannot rule out a large change
+        let ctx = context(
+            &keypairs,
+            4,
+            b"MODULE:lightning:1.0.0",
+            &[("paths", "docs/"), ("lines-changed", "many")],
+        );
+        assert_eq!(selected(&ctx).as_deref(), Some("large-change"));
+
+        // A release that does not say whether it is a hard fork is treated as one
+        let mut ctx = context(&keypairs, 6, b"RELEASE:v2.0.0:abc123", &[]);
+        assert_eq!(selected(&ctx).as_deref(), Some("hard-fork"));
+        let StageResult::Fail(detail) = stage().check(&mut ctx) else {
+            panic!("hard-fork rule passed without nested approval");
+        };
+        assert!(detail.contains("hard-fork was not attested"), "{}", detail);
+        assert!(detail.contains("nested approval required"), "{}", detail);
+
+        // Signers disagreeing about an attribute count as not attesting it
+        let mut ctx = context(
+            &keypairs,
+            3,
+            b"RELEASE:v2.0.0:abc123",
+            &[("hard-fork", "false")],
+        );
+        let other = SignedAttributes::sign(
+            keypairs[3].secret_key.expose(),
+            &ctx.message,
+            [("hard-fork".to_string(), "true".to_string())].into(),
+        )
+        .unwrap();
+        ctx.signatures
+            .push(crate::sign_message(keypairs[3].secret_key.expose(), &ctx.message).unwrap());
+        ctx.attributes.push(other);
+        SignatureStage.check(&mut ctx);
+        assert_eq!(selected(&ctx).as_deref(), Some("hard-fork"));
+    }
+
+    #[test]
+    fn test_unsigned_attributes_have_no_effect() {
+        let keypairs = keypairs();
+        let message = b"MODULE:lightning:1.0.0";
+        let ctx = context(
+            &keypairs,
+            4,
+            message,
+            &[("paths", "consensus/pow.rs"), ("lines-changed", "20")],
+        );
+        assert_eq!(selected(&ctx).as_deref(), Some("consensus-paths"));
+
+        // Rewriting the attested values breaks their signatures
+        let mut tampered = ctx.clone();
+        for attestation in &mut tampered.attributes {
+            attestation
+                .attributes
+                .insert("paths".to_string(), "docs/README.md".to_string());
+        }
+        assert_eq!(selected(&tampered).as_deref(), Some("consensus-paths"));
+
+        // Attributes attested by a key that did not sign do not count
+        let mut outsider = ctx.clone();
+        let stranger = GovernanceKeypair::generate().unwrap();
+        outsider.attributes = vec![SignedAttributes::sign(
+            stranger.secret_key.expose(),
+            message,
+            [
+                ("paths".to_string(), "docs/README.md".to_string()),
+                ("lines-changed".to_string(), "1".to_string()),
+            ]
+            .into(),
+        )
+        .unwrap()];
+        assert_eq!(selected(&outsider).as_deref(), Some("consensus-paths"));
+
+        // Unsigned fields in a signature file are never read
+        let entry = serde_json::json!({ "attributes": { "paths": "docs/README.md" } });
+        assert_eq!(SignedAttributes::from_entry(&entry).unwrap(), None);
+    }
+
+    #[test]
+    fn test_delay() {
+        let keypairs = keypairs();
+        let message = b"RELEASE:v2.0.0:abc123";
+        let mut ctx = context(&keypairs, 6, message, &[("hard-fork", "true")]);
+        ctx.nested_approved = Some(true);
+        let timestamps = |ctx: &VerificationContext, age: chrono::Duration| {
+            keypairs[..6]
+                .iter()
+                .map(|kp| {
+                    SignedTimestamp::sign(kp.secret_key.expose(), message, ctx.now - age).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        ctx.timestamps = timestamps(&ctx, chrono::Duration::try_days(1).unwrap());
+        assert!(stage().check(&mut ctx).is_fail());
+
+        ctx.timestamps = timestamps(&ctx, chrono::Duration::try_days(31).unwrap());
+        assert!(!stage().check(&mut ctx).is_fail());
+
+        assert!(EscalationStage::new(vec![EscalationRule {
+            name: "bad".to_string(),
+            when: BTreeMap::new(),
+            threshold: None,
+            nested: false,
+            delay: Some("soon".to_string()),
+        }])
+        .is_err());
+    }
+}
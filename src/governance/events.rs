@@ -0,0 +1,207 @@
+//! # Governance Events
+//!
+//! In-process notifications of governance-relevant events (failed
+//! verifications, revoked keys, policy failures, key changes and the audit
+//! records written for them, trust store reloads), so embedders can alert
+//! without scraping logs.
+//!
+//! Events go through a process-wide bounded broadcast channel. Emitting never
+//! waits for a subscriber: when one falls behind, the oldest queued event is
+//! discarded and counted in [`dropped`], and the subscriber sees
+//! `RecvError::Lagged`. Events are built only when someone is subscribed.
+//!
+//! Available with the `events` feature (enabled by default).
+
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use tokio::sync::broadcast;
+
+use crate::governance::trust::TrustChange;
+use crate::governance::{PublicKey, Threshold};
+
+/// Capacity of the governance event channel
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Governance-relevant events emitted by the SDK
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GovernanceEvent {
+    /// A multisig verification did not reach its threshold
+    MultisigVerificationFailed {
+        threshold: Threshold,
+        signatures: usize,
+        valid_signatures: usize,
+    },
+    /// A nested (team) multisig verification was not approved
+    NestedMultisigVerificationFailed {
+        teams_approved: usize,
+        teams_required: usize,
+        maintainers_approved: usize,
+        maintainers_required: usize,
+    },
+    /// A signature collector reached its multisig threshold; `collector`
+    /// is its id, `message_digest` the hex SHA256 of the message it
+    /// collects for, and `by_key` the index of the key whose signature
+    /// crossed it
+    ThresholdReached {
+        collector: u64,
+        message_digest: String,
+        by_key: usize,
+    },
+    /// A signature was made with a revoked key
+    RevokedKeyEncountered { public_key: PublicKey },
+    /// A required verification policy stage failed
+    PolicyCheckFailed { stage: String, detail: String },
+    /// A change of pinned keys was rejected
+    KeyChangeRejected { source: String, change: String },
+    /// A change of pinned keys was accepted
+    KeyChangeAccepted {
+        source: String,
+        change: String,
+        note: String,
+    },
+    /// An accepted key change was written to the pin file's audit log
+    AuditRecordAppended {
+        source: String,
+        accepted_at: DateTime<Utc>,
+        change: String,
+        note: String,
+    },
+    /// A trust store reload was swapped in
+    TrustStoreReloaded {
+        generation: u64,
+        changes: Vec<TrustChange>,
+    },
+    /// A trust store reload failed validation; the active store is kept
+    TrustStoreReloadRejected { generation: u64, reason: String },
+}
+
+/// A bounded, non-blocking governance event channel
+#[derive(Debug)]
+pub struct EventHub {
+    sender: broadcast::Sender<GovernanceEvent>,
+    capacity: usize,
+    dropped: AtomicU64,
+    /// Makes the queue length check and the send one step, so every
+    /// overwritten event is counted exactly once
+    send_lock: Mutex<()>,
+}
+
+impl EventHub {
+    /// Create a hub holding up to `capacity` undelivered events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+            capacity,
+            dropped: AtomicU64::new(0),
+            send_lock: Mutex::new(()),
+        }
+    }
+
+    /// Subscribe to events emitted from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<GovernanceEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Emit an event, building it only if there are subscribers
+    ///
+    /// The send lock is held only for the queue check and the send, which
+    /// take the channel's own lock anyway; the event is built before it.
+    pub fn emit(&self, event: impl FnOnce() -> GovernanceEvent) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let event = event();
+
+        let _guard = self
+            .send_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if self.sender.len() >= self.capacity {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        // Subscribers may have gone away since the check; that is not an error
+        let _ = self.sender.send(event);
+    }
+
+    /// Number of events discarded because a subscriber fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+fn hub() -> &'static EventHub {
+    static HUB: OnceLock<EventHub> = OnceLock::new();
+    HUB.get_or_init(|| EventHub::new(EVENT_CHANNEL_CAPACITY))
+}
+
+/// Subscribe to governance events
+pub fn subscribe() -> broadcast::Receiver<GovernanceEvent> {
+    hub().subscribe()
+}
+
+/// Number of governance events discarded because a subscriber fell behind
+pub fn dropped() -> u64 {
+    hub().dropped()
+}
+
+/// Emit a governance event to the process-wide hub
+pub(crate) fn emit(event: impl FnOnce() -> GovernanceEvent) {
+    hub().emit(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy_failure(stage: &str) -> GovernanceEvent {
+        GovernanceEvent::PolicyCheckFailed {
+            stage: stage.to_string(),
+            detail: "failed".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_no_subscribers_builds_nothing() {
+        let hub = EventHub::new(4);
+        hub.emit(|| unreachable!("event built without subscribers"));
+        assert_eq!(hub.dropped(), 0);
+    }
+
+    #[test]
+    fn test_full_channel_drops_with_counter() {
+        let hub = EventHub::new(2);
+        let mut events = hub.subscribe();
+
+        for stage in ["a", "b", "c"] {
+            hub.emit(|| policy_failure(stage));
+        }
+
+        assert_eq!(hub.dropped(), 1);
+        assert!(matches!(
+            events.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+        assert_eq!(events.try_recv().unwrap(), policy_failure("b"));
+        assert_eq!(events.try_recv().unwrap(), policy_failure("c"));
+    }
+
+    #[test]
+    fn test_concurrent_drops_counted_exactly() {
+        let hub = EventHub::new(8);
+        let _events = hub.subscribe();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..250 {
+                        hub.emit(|| policy_failure("a"));
+                    }
+                });
+            }
+        });
+
+        assert_eq!(hub.dropped(), 1000 - 8);
+    }
+}
@@ -0,0 +1,464 @@
+//! # Governance History
+//!
+//! Historical verification against the governance configuration that was
+//! active when a signature was made.
+//!
+//! Key sets rotate, so an old release must be verified against the multisig
+//! of its time rather than today's. A [`GovernanceHistory`] is assembled from
+//! rotation records, each naming the multisig that took effect at a point in
+//! time. Generations are numbered from 1 in order of their effective time.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::{Multisig, PublicKey, Signature, Threshold};
+use crate::util::humanize::{format_age, parse_timestamp};
+
+/// A rotation record: the multisig that took effect at a point in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationRecord {
+    /// When this configuration took effect
+    pub effective_from: DateTime<Utc>,
+    /// Signature threshold (`N-of-M`, where M is the number of keys)
+    pub threshold: Threshold,
+    /// Hex-encoded compressed public keys
+    pub public_keys: Vec<String>,
+}
+
+/// One generation of the governance configuration
+#[derive(Debug, Clone)]
+pub struct ConfigGeneration {
+    /// Generation number, starting at 1
+    pub generation: usize,
+    /// When this generation took effect
+    pub effective_from: DateTime<Utc>,
+    /// The multisig of this generation
+    pub multisig: Multisig,
+}
+
+/// Point in time to verify against, parsed from `--as-of`
+///
+/// This must be a time the verifier trusts, such as when the release was
+/// published. The signing time recorded in a signature file is not covered by
+/// any signature, so a rotated-out signer could backdate it; `auto` (use the
+/// recorded time) is refused for that reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsOf(pub DateTime<Utc>);
+
+impl FromStr for AsOf {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "auto" {
+            return Err(GovernanceError::InvalidInput(
+                "--as-of auto is not supported: the recorded signing time is not signed, \
+                 so a rotated-out signer could backdate it; pass a trusted RFC3339 time"
+                    .to_string(),
+            ));
+        }
+        parse_timestamp(s)
+            .map(|t| AsOf(t.with_timezone(&Utc)))
+            .map_err(|e| {
+                GovernanceError::InvalidInput(format!(
+                    "Invalid --as-of: expected an RFC3339 time ({})",
+                    e
+                ))
+            })
+    }
+}
+
+/// Signing time recorded in a signature file
+///
+/// Reads `created_at`, falling back to `metadata.signed_at`. Neither is
+/// covered by the signature, so the result is informational only.
+pub fn signed_at(signature_file: &serde_json::Value) -> Option<DateTime<Utc>> {
+    signature_file
+        .get("created_at")
+        .or_else(|| signature_file.get("metadata")?.get("signed_at"))
+        .and_then(|t| t.as_str())
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Verify signatures against the configuration a history file records as
+/// active at `as_of`
+pub fn verify_historical(
+    history_path: &Path,
+    as_of: &str,
+    message: &[u8],
+    signatures: &[Signature],
+) -> GovernanceResult<HistoricalVerification> {
+    let AsOf(time) = as_of.parse()?;
+    GovernanceHistory::load(history_path)?.verify_at(message, signatures, time)
+}
+
+/// Outcome of verifying against the historical configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoricalVerification {
+    /// Point in time verified against
+    pub as_of: DateTime<Utc>,
+    /// Generations the signatures were checked against
+    pub generations_tried: Vec<usize>,
+    /// Generation whose threshold was met, if any
+    pub passed_generation: Option<usize>,
+    /// The time is exactly at a rotation boundary
+    pub ambiguous: bool,
+    /// Valid signatures under the reported generation
+    pub valid_signatures: usize,
+    /// Public keys of the reported generation
+    pub public_keys: Vec<PublicKey>,
+    /// Threshold of the reported generation
+    pub threshold: Threshold,
+}
+
+impl HistoricalVerification {
+    /// Whether the threshold of an applicable generation was met
+    pub fn verified(&self) -> bool {
+        self.passed_generation.is_some()
+    }
+
+    /// Generation to report: the one that passed, else the latest tried
+    pub fn generation(&self) -> usize {
+        self.passed_generation
+            .or_else(|| self.generations_tried.last().copied())
+            .unwrap_or_default()
+    }
+
+    /// Human-readable description of the generation(s) used
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "config generation {} as of {} ({})",
+            self.generation(),
+            self.as_of.to_rfc3339(),
+            format_age(self.as_of.into())
+        );
+        if self.ambiguous {
+            description.push_str(&format!(
+                " (rotation boundary: checked generations {}; either may pass)",
+                self.generations_tried
+                    .iter()
+                    .map(|g| g.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ));
+        }
+        description
+    }
+}
+
+/// Governance configurations over time
+#[derive(Debug, Clone, Default)]
+pub struct GovernanceHistory {
+    generations: Vec<ConfigGeneration>,
+}
+
+impl GovernanceHistory {
+    /// Assemble the history from rotation records
+    pub fn from_records(records: &[RotationRecord]) -> GovernanceResult<Self> {
+        let mut records: Vec<&RotationRecord> = records.iter().collect();
+        records.sort_by_key(|r| r.effective_from);
+
+        if let Some(pair) = records
+            .windows(2)
+            .find(|pair| pair[0].effective_from == pair[1].effective_from)
+        {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Two rotation records take effect at {}",
+                pair[0].effective_from.to_rfc3339()
+            )));
+        }
+
+        let mut generations = Vec::with_capacity(records.len());
+        for (index, record) in records.into_iter().enumerate() {
+            let public_keys = record
+                .public_keys
+                .iter()
+                .map(|key| {
+                    let bytes = hex::decode(key).map_err(|e| {
+                        GovernanceError::InvalidKey(format!("Invalid public key hex: {}", e))
+                    })?;
+                    PublicKey::from_bytes(&bytes)
+                })
+                .collect::<GovernanceResult<Vec<_>>>()?;
+
+            generations.push(ConfigGeneration {
+                generation: index + 1,
+                effective_from: record.effective_from,
+                multisig: Multisig::with_threshold(record.threshold, public_keys)?,
+            });
+        }
+
+        Ok(Self { generations })
+    }
+
+    /// Parse rotation records from JSON (`{"rotations": [...]}`)
+    pub fn from_json(json: &str) -> GovernanceResult<Self> {
+        #[derive(Deserialize)]
+        struct HistoryFile {
+            rotations: Vec<RotationRecord>,
+        }
+
+        let file: HistoryFile = serde_json::from_str(json).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid governance history: {}", e))
+        })?;
+        Self::from_records(&file.rotations)
+    }
+
+    /// Read rotation records from a JSON file
+    pub fn load(path: &Path) -> GovernanceResult<Self> {
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            GovernanceError::InvalidInput(format!(
+                "Cannot read governance history file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// All generations, oldest first
+    pub fn generations(&self) -> &[ConfigGeneration] {
+        &self.generations
+    }
+
+    /// The configuration active at a point in time
+    pub fn config_at(&self, time: DateTime<Utc>) -> Option<&ConfigGeneration> {
+        self.generations
+            .iter()
+            .rev()
+            .find(|g| g.effective_from <= time)
+    }
+
+    /// The configurations applicable at a point in time
+    ///
+    /// At a rotation boundary both the outgoing and incoming generations
+    /// apply; otherwise only the active one.
+    pub fn configs_at(&self, time: DateTime<Utc>) -> Vec<&ConfigGeneration> {
+        let Some(active) = self.config_at(time) else {
+            return Vec::new();
+        };
+
+        match self.generations.get(active.generation.wrapping_sub(2)) {
+            Some(previous) if active.effective_from == time => vec![previous, active],
+            _ => vec![active],
+        }
+    }
+
+    /// Verify signatures against the configuration active at a point in time
+    pub fn verify_at(
+        &self,
+        message: &[u8],
+        signatures: &[Signature],
+        time: DateTime<Utc>,
+    ) -> GovernanceResult<HistoricalVerification> {
+        let configs = self.configs_at(time);
+        if configs.is_empty() {
+            return Err(GovernanceError::InvalidInput(format!(
+                "No governance configuration was active at {}",
+                time.to_rfc3339()
+            )));
+        }
+
+        let mut result = HistoricalVerification {
+            as_of: time,
+            generations_tried: configs.iter().map(|g| g.generation).collect(),
+            passed_generation: None,
+            ambiguous: configs.len() > 1,
+            valid_signatures: 0,
+            public_keys: Vec::new(),
+            threshold: configs[0].multisig.m_of_n(),
+        };
+
+        for config in configs {
+            let valid = config
+                .multisig
+                .collect_valid_signatures(message, signatures)?
+                .len();
+            result.valid_signatures = valid;
+            result.public_keys = config.multisig.public_keys().to_vec();
+            result.threshold = config.multisig.m_of_n();
+            if valid >= config.multisig.threshold() {
+                result.passed_generation = Some(config.generation);
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn record(effective_from: &str, keypairs: &[&GovernanceKeypair]) -> RotationRecord {
+        RotationRecord {
+            effective_from: time(effective_from),
+            threshold: Threshold::new(keypairs.len(), keypairs.len()).unwrap(),
+            public_keys: keypairs
+                .iter()
+                .map(|kp| hex::encode(kp.public_key_bytes()))
+                .collect(),
+        }
+    }
+
+    fn setup() -> (GovernanceKeypair, GovernanceKeypair, GovernanceHistory) {
+        let old = GovernanceKeypair::generate().unwrap();
+        let new = GovernanceKeypair::generate().unwrap();
+        let history = GovernanceHistory::from_records(&[
+            record("2025-01-01T00:00:00Z", &[&new]),
+            record("2023-01-01T00:00:00Z", &[&old]),
+        ])
+        .unwrap();
+        (old, new, history)
+    }
+
+    #[test]
+    fn test_old_signature_uses_historical_config() {
+        let (old, _new, history) = setup();
+        let message = b"RELEASE:v1.0.0:abc";
+        let signature = crate::sign_message(old.secret_key.expose(), message).unwrap();
+
+        let result = history
+            .verify_at(
+                message,
+                std::slice::from_ref(&signature),
+                time("2023-06-01T00:00:00Z"),
+            )
+            .unwrap();
+        assert!(result.verified());
+        assert_eq!(result.generation(), 1);
+        assert_eq!(result.public_keys, vec![old.public_key()]);
+        assert!(!result.ambiguous);
+
+        // Today's configuration rejects it
+        let result = history
+            .verify_at(message, &[signature], time("2025-06-01T00:00:00Z"))
+            .unwrap();
+        assert!(!result.verified());
+        assert_eq!(result.generations_tried, vec![2]);
+    }
+
+    #[test]
+    fn test_rotation_boundary_accepts_either_generation() {
+        let (old, new, history) = setup();
+        let message = b"RELEASE:v2.0.0:def";
+        let boundary = time("2025-01-01T00:00:00Z");
+
+        for (keypair, generation) in [(&old, 1), (&new, 2)] {
+            let signature = crate::sign_message(keypair.secret_key.expose(), message).unwrap();
+            let result = history.verify_at(message, &[signature], boundary).unwrap();
+            assert!(result.ambiguous);
+            assert_eq!(result.generations_tried, vec![1, 2]);
+            assert_eq!(result.passed_generation, Some(generation));
+            assert!(result.describe().contains("rotation boundary"));
+        }
+
+        let stranger = GovernanceKeypair::generate().unwrap();
+        let signature = crate::sign_message(stranger.secret_key.expose(), message).unwrap();
+        assert!(!history
+            .verify_at(message, &[signature], boundary)
+            .unwrap()
+            .verified());
+    }
+
+    #[test]
+    fn test_config_at() {
+        let (_old, _new, history) = setup();
+        assert!(history.config_at(time("2022-01-01T00:00:00Z")).is_none());
+        assert_eq!(
+            history
+                .config_at(time("2024-12-31T23:59:59Z"))
+                .unwrap()
+                .generation,
+            1
+        );
+        assert_eq!(
+            history
+                .config_at(time("2025-01-01T00:00:00Z"))
+                .unwrap()
+                .generation,
+            2
+        );
+    }
+
+    #[test]
+    fn test_as_of() {
+        assert_eq!(
+            "2024-01-15T10:30:00Z".parse::<AsOf>().unwrap(),
+            AsOf(time("2024-01-15T10:30:00Z"))
+        );
+        assert!("yesterday".parse::<AsOf>().is_err());
+        let err = "2024-01-15T10:30:00".parse::<AsOf>().unwrap_err();
+        assert!(err.to_string().contains("position 19"));
+
+        // The recorded signing time is unsigned, so it cannot select the config
+        let err = "auto".parse::<AsOf>().unwrap_err();
+        assert!(err.to_string().contains("not signed"));
+    }
+
+    #[test]
+    fn test_verify_historical_from_file() {
+        let old = GovernanceKeypair::generate().unwrap();
+        let new = GovernanceKeypair::generate().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let rotations = [
+            record("2023-01-01T00:00:00Z", &[&old]),
+            record("2025-01-01T00:00:00Z", &[&new]),
+        ];
+        std::fs::write(
+            &path,
+            serde_json::json!({ "rotations": rotations }).to_string(),
+        )
+        .unwrap();
+
+        let message = b"RELEASE:v1.0.0:abc";
+        let signature = crate::sign_message(old.secret_key.expose(), message).unwrap();
+        let result =
+            verify_historical(&path, "2023-06-01T00:00:00Z", message, &[signature]).unwrap();
+        assert_eq!(result.passed_generation, Some(1));
+
+        let missing = dir.path().join("missing.json");
+        assert!(verify_historical(&missing, "2023-06-01T00:00:00Z", message, &[]).is_err());
+    }
+
+    #[test]
+    fn test_signed_at() {
+        let file = serde_json::json!({ "created_at": "2024-01-15T10:30:00+00:00" });
+        assert_eq!(signed_at(&file), Some(time("2024-01-15T10:30:00Z")));
+
+        let file = serde_json::json!({ "metadata": { "signed_at": "2024-01-15T10:30:00Z" } });
+        assert_eq!(signed_at(&file), Some(time("2024-01-15T10:30:00Z")));
+
+        assert_eq!(signed_at(&serde_json::json!({ "signature": "00" })), None);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let json = serde_json::json!({
+            "rotations": [record("2023-01-01T00:00:00Z", &[&keypair])]
+        })
+        .to_string();
+        let history = GovernanceHistory::from_json(&json).unwrap();
+        assert_eq!(history.generations().len(), 1);
+
+        let duplicate = serde_json::json!({
+            "rotations": [
+                record("2023-01-01T00:00:00Z", &[&keypair]),
+                record("2023-01-01T00:00:00Z", &[&keypair]),
+            ]
+        })
+        .to_string();
+        assert!(GovernanceHistory::from_json(&duplicate).is_err());
+    }
+}
@@ -0,0 +1,560 @@
+//! # Identity Attestations
+//!
+//! Proofs that a maintainer controls a governance key, published through
+//! channels independent of the key itself (DNS, a GitHub gist, a web page).
+//!
+//! For each claimed identity the maintainer signs the statement
+//! `identity:<kind>:<subject>:<fingerprint>` with the governance key and
+//! publishes the proof text. Anyone can then fetch the published content and
+//! check it against the attestation. Fetching is done by the caller, or with
+//! [`fetch_claim_content`] when the `net` feature is enabled.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+#[cfg(any(feature = "signing", test))]
+use crate::governance::GovernanceKeypair;
+use crate::governance::{PublicKey, Signature};
+
+/// Prefix of the DNS TXT record naming a key's fingerprint
+const DNS_RECORD_PREFIX: &str = "bllvm-key=";
+
+/// An identity a maintainer claims for their governance key
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum IdentityClaim {
+    /// Control of a domain, proven by a DNS TXT record
+    Dns { domain: String },
+    /// A GitHub account, proven by a public gist
+    Github { username: String },
+    /// Control of a web page, proven by content served at the URL
+    Url { url: String },
+}
+
+impl IdentityClaim {
+    /// Claim kind used in the signed statement
+    pub fn kind(&self) -> &'static str {
+        match self {
+            IdentityClaim::Dns { .. } => "dns",
+            IdentityClaim::Github { .. } => "github",
+            IdentityClaim::Url { .. } => "url",
+        }
+    }
+
+    /// The claimed domain, username or URL
+    pub fn subject(&self) -> &str {
+        match self {
+            IdentityClaim::Dns { domain } => domain,
+            IdentityClaim::Github { username } => username,
+            IdentityClaim::Url { url } => url,
+        }
+    }
+
+    /// The signed statement binding this claim to a key
+    pub fn statement(&self, public_key: &PublicKey) -> String {
+        [
+            "identity",
+            self.kind(),
+            self.subject(),
+            &hex::encode(public_key.sha256_fingerprint()),
+        ]
+        .join(":")
+    }
+}
+
+impl fmt::Display for IdentityClaim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.kind(), self.subject())
+    }
+}
+
+/// A signed claim and the exact text to publish for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimProof {
+    /// The claimed identity
+    pub claim: IdentityClaim,
+    /// Text to publish (a TXT record, gist content or page content)
+    pub publish: String,
+    /// Hex-encoded signature over the claim's statement
+    pub signature: String,
+}
+
+/// Identity claims for one governance key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityAttestation {
+    /// Hex-encoded compressed public key
+    pub public_key: String,
+    /// Hex-encoded key fingerprint
+    pub fingerprint: String,
+    /// One proof per claim
+    pub proofs: Vec<ClaimProof>,
+    /// When the attestation was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// Claims of an attestation whose published proofs have been checked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedIdentity {
+    /// Hex-encoded compressed public key
+    pub public_key: String,
+    /// Verified claims
+    pub claims: Vec<IdentityClaim>,
+    /// Claims that could not be verified, with the reason
+    #[serde(default)]
+    pub failed: Vec<ClaimFailure>,
+    /// When the claims were verified
+    pub verified_at: DateTime<Utc>,
+}
+
+/// A claim of an attestation that failed verification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimFailure {
+    /// The claimed identity
+    pub claim: IdentityClaim,
+    /// Why the claim was not verified
+    pub reason: String,
+}
+
+/// Sign identity claims with a governance key
+#[cfg(any(feature = "signing", test))]
+pub fn attest_identity(
+    keypair: &GovernanceKeypair,
+    claims: &[IdentityClaim],
+) -> GovernanceResult<IdentityAttestation> {
+    let public_key = keypair.public_key();
+    let proofs = claims
+        .iter()
+        .map(|claim| {
+            let signature = crate::sign_message(
+                keypair.secret_key.expose(),
+                claim.statement(&public_key).as_bytes(),
+            )?;
+            Ok(ClaimProof {
+                claim: claim.clone(),
+                publish: publish_text(claim, &public_key, &signature),
+                signature: hex::encode(signature.to_bytes()),
+            })
+        })
+        .collect::<GovernanceResult<Vec<_>>>()?;
+
+    Ok(IdentityAttestation {
+        public_key: public_key.to_string(),
+        fingerprint: hex::encode(public_key.sha256_fingerprint()),
+        proofs,
+        created_at: Utc::now(),
+    })
+}
+
+/// Check every claim of an attestation against its published content
+///
+/// `fetched_content` returns what is currently published for a claim: the
+/// TXT records of the domain (one per line), the content of the user's
+/// gists, or the page at the URL. Each claim is checked on its own: claims
+/// whose signature is invalid, whose content cannot be fetched or whose
+/// proof is not published are listed in [`VerifiedIdentity::failed`]. Fails
+/// only if the attestation's key or fingerprint is invalid.
+pub fn verify_identity_attestation<F>(
+    attestation: &IdentityAttestation,
+    mut fetched_content: F,
+) -> GovernanceResult<VerifiedIdentity>
+where
+    F: FnMut(&IdentityClaim) -> GovernanceResult<String>,
+{
+    let public_key =
+        PublicKey::from_bytes(&hex::decode(&attestation.public_key).map_err(|e| {
+            GovernanceError::InvalidKey(format!("Invalid attestation public key hex: {}", e))
+        })?)?;
+    if attestation.fingerprint != hex::encode(public_key.sha256_fingerprint()) {
+        return Err(GovernanceError::SignatureVerification(
+            "Attestation fingerprint does not match its public key".to_string(),
+        ));
+    }
+
+    let mut claims = Vec::new();
+    let mut failed = Vec::new();
+    for proof in &attestation.proofs {
+        match verify_claim(proof, &public_key, &mut fetched_content) {
+            Ok(()) => claims.push(proof.claim.clone()),
+            Err(e) => failed.push(ClaimFailure {
+                claim: proof.claim.clone(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(VerifiedIdentity {
+        public_key: attestation.public_key.clone(),
+        claims,
+        failed,
+        verified_at: Utc::now(),
+    })
+}
+
+/// Check one proof's signature and published content
+fn verify_claim<F>(
+    proof: &ClaimProof,
+    public_key: &PublicKey,
+    fetched_content: &mut F,
+) -> GovernanceResult<()>
+where
+    F: FnMut(&IdentityClaim) -> GovernanceResult<String>,
+{
+    let claim = &proof.claim;
+    let signature = Signature::from_bytes(&hex::decode(&proof.signature).map_err(|e| {
+        GovernanceError::InvalidSignatureFormat(format!(
+            "Invalid signature hex for {}: {}",
+            claim, e
+        ))
+    })?)?;
+    let signed = crate::verify_signature(
+        &signature,
+        claim.statement(public_key).as_bytes(),
+        public_key,
+    )?;
+    if !signed || proof.publish != publish_text(claim, public_key, &signature) {
+        return Err(GovernanceError::SignatureVerification(format!(
+            "Proof for {} is not signed by the attested key",
+            claim
+        )));
+    }
+
+    let content = fetched_content(claim)?;
+    if !is_published(claim, &proof.publish, &content) {
+        return Err(GovernanceError::SignatureVerification(format!(
+            "Published content for {} does not contain the proof",
+            claim
+        )));
+    }
+    Ok(())
+}
+
+/// Text to publish for a claim
+fn publish_text(claim: &IdentityClaim, public_key: &PublicKey, signature: &Signature) -> String {
+    let fingerprint = hex::encode(public_key.sha256_fingerprint());
+    let signature = hex::encode(signature.to_bytes());
+    match claim {
+        IdentityClaim::Dns { .. } => format!("{}{}", DNS_RECORD_PREFIX, fingerprint),
+        IdentityClaim::Github { username } => format!(
+            "### bllvm governance key proof\n\
+             \n\
+             I am {} on GitHub, and I control the governance key with fingerprint {}.\n\
+             \n\
+             statement: {}\n\
+             key: {}\n\
+             signature: {}\n",
+            username,
+            fingerprint,
+            claim.statement(public_key),
+            public_key,
+            signature
+        ),
+        IdentityClaim::Url { .. } => format!(
+            "bllvm-key={}\nbllvm-statement={}\nbllvm-signature={}\n",
+            fingerprint,
+            claim.statement(public_key),
+            signature
+        ),
+    }
+}
+
+/// Whether fetched content contains a proof
+///
+/// DNS proofs must match a whole TXT record; other proofs must appear as
+/// consecutive lines (trailing whitespace and line endings are ignored).
+fn is_published(claim: &IdentityClaim, proof: &str, content: &str) -> bool {
+    match claim {
+        IdentityClaim::Dns { .. } => content
+            .lines()
+            .any(|record| record.trim().trim_matches('"') == proof),
+        IdentityClaim::Github { .. } | IdentityClaim::Url { .. } => {
+            let normalize = |text: &str| -> String {
+                text.lines()
+                    .map(str::trim_end)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+            normalize(content).contains(normalize(proof).trim())
+        }
+    }
+}
+
+/// Verified identities of signers, by public key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerRegistry {
+    /// Verified identities by hex-encoded compressed public key
+    signers: BTreeMap<String, VerifiedIdentity>,
+}
+
+impl SignerRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a verified attestation, replacing any earlier one for the key
+    ///
+    /// Only the verified claims are kept.
+    pub fn record(&mut self, mut identity: VerifiedIdentity) {
+        identity.failed.clear();
+        self.signers.insert(identity.public_key.clone(), identity);
+    }
+
+    /// Verified identity of a signer, if any
+    pub fn get(&self, public_key: &PublicKey) -> Option<&VerifiedIdentity> {
+        self.signers.get(&public_key.to_string())
+    }
+
+    /// Verified claims of a signer (empty if none)
+    pub fn claims(&self, public_key: &PublicKey) -> &[IdentityClaim] {
+        self.get(public_key)
+            .map_or(&[][..], |identity| &identity.claims)
+    }
+
+    /// All verified identities
+    pub fn iter(&self) -> impl Iterator<Item = &VerifiedIdentity> {
+        self.signers.values()
+    }
+}
+
+/// Timeout for fetching a published proof
+#[cfg(feature = "net")]
+const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Fetch what is currently published for a claim
+///
+/// Resolves DNS TXT records with the system resolver configuration and
+/// fetches gists and URLs over HTTP(S). For GitHub claims the content of all
+/// of the user's public gists is returned.
+#[cfg(feature = "net")]
+pub fn fetch_claim_content(claim: &IdentityClaim) -> GovernanceResult<String> {
+    match claim {
+        IdentityClaim::Dns { domain } => {
+            let valid = !domain.is_empty()
+                && domain
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+                && !domain.starts_with('-');
+            if !valid {
+                return Err(GovernanceError::InvalidInput(format!(
+                    "Invalid domain: {}",
+                    domain
+                )));
+            }
+            let failed = |e: &dyn fmt::Display| {
+                GovernanceError::InvalidInput(format!("TXT lookup for {} failed: {}", domain, e))
+            };
+            let resolver =
+                hickory_resolver::Resolver::from_system_conf().map_err(|e| failed(&e))?;
+            let records = resolver
+                .txt_lookup(domain.as_str())
+                .map_err(|e| failed(&e))?;
+            // Long records are split into chunks; join them per record
+            Ok(records
+                .iter()
+                .map(|record| {
+                    record
+                        .txt_data()
+                        .iter()
+                        .map(|chunk| String::from_utf8_lossy(chunk))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        IdentityClaim::Github { username } => {
+            let valid = !username.is_empty()
+                && username
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && !username.starts_with('-');
+            if !valid {
+                return Err(GovernanceError::InvalidInput(format!(
+                    "Invalid GitHub username: {}",
+                    username
+                )));
+            }
+            let listing = fetch_url(&format!("https://api.github.com/users/{}/gists", username))?;
+            let gists: Vec<serde_json::Value> = serde_json::from_str(&listing).map_err(|e| {
+                GovernanceError::Serialization(format!("Invalid gist listing: {}", e))
+            })?;
+            let mut content = String::new();
+            for file in gists
+                .iter()
+                .filter_map(|gist| gist["files"].as_object())
+                .flat_map(|files| files.values())
+            {
+                if let Some(raw_url) = file["raw_url"].as_str() {
+                    content.push_str(&fetch_url(raw_url)?);
+                    content.push('\n');
+                }
+            }
+            Ok(content)
+        }
+        IdentityClaim::Url { url } => fetch_url(url),
+    }
+}
+
+#[cfg(feature = "net")]
+fn fetch_url(url: &str) -> GovernanceResult<String> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Only http(s) URLs can be fetched: {}",
+            url
+        )));
+    }
+    let failed = |e: &dyn fmt::Display| {
+        GovernanceError::InvalidInput(format!("Fetching {} failed: {}", url, e))
+    };
+    ureq::get(url)
+        .timeout(FETCH_TIMEOUT)
+        .call()
+        .map_err(|e| failed(&e))?
+        .into_string()
+        .map_err(|e| failed(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims() -> Vec<IdentityClaim> {
+        vec![
+            IdentityClaim::Dns {
+                domain: "alice.example".to_string(),
+            },
+            IdentityClaim::Github {
+                username: "alice".to_string(),
+            },
+            IdentityClaim::Url {
+                url: "https://alice.example/bllvm.txt".to_string(),
+            },
+        ]
+    }
+
+    /// Content a well-behaved publisher would serve for each claim
+    fn published(attestation: &IdentityAttestation, claim: &IdentityClaim) -> String {
+        let proof = &attestation
+            .proofs
+            .iter()
+            .find(|p| &p.claim == claim)
+            .unwrap()
+            .publish;
+        match claim {
+            IdentityClaim::Dns { .. } => format!("\"v=spf1 -all\"\n\"{}\"\n", proof),
+            IdentityClaim::Github { .. } => proof.replace('\n', "\r\n"),
+            IdentityClaim::Url { .. } => format!("<pre>\n{}</pre>\n", proof),
+        }
+    }
+
+    #[test]
+    fn test_each_claim_verifies() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let attestation = attest_identity(&keypair, &claims()).unwrap();
+        let fingerprint = hex::encode(keypair.public_key().sha256_fingerprint());
+        assert_eq!(
+            attestation.proofs[0].publish,
+            format!("bllvm-key={}", fingerprint)
+        );
+
+        let verified =
+            verify_identity_attestation(&attestation, |claim| Ok(published(&attestation, claim)))
+                .unwrap();
+        assert_eq!(verified.claims, claims());
+
+        let mut registry = SignerRegistry::new();
+        registry.record(verified);
+        assert_eq!(registry.claims(&keypair.public_key()), claims().as_slice());
+        let other = GovernanceKeypair::generate().unwrap().public_key();
+        assert!(registry.claims(&other).is_empty());
+    }
+
+    #[test]
+    fn test_mismatched_content_rejected() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let attestation = attest_identity(&keypair, &claims()).unwrap();
+        let imposter = attest_identity(&other, &claims()).unwrap();
+
+        for claim in claims() {
+            // Someone else's proof is published for this claim
+            let verified = verify_identity_attestation(&attestation, |c| {
+                Ok(if *c == claim {
+                    published(&imposter, c)
+                } else {
+                    published(&attestation, c)
+                })
+            })
+            .unwrap();
+            assert_eq!(verified.failed.len(), 1);
+            assert_eq!(verified.failed[0].claim, claim);
+            assert!(verified.failed[0]
+                .reason
+                .contains("does not contain the proof"));
+            let others: Vec<_> = claims().into_iter().filter(|c| *c != claim).collect();
+            assert_eq!(verified.claims, others);
+        }
+
+        // A DNS record must match exactly, not merely contain the proof
+        let dns = &claims()[..1];
+        let attestation = attest_identity(&keypair, dns).unwrap();
+        let padded = format!("{}0", attestation.proofs[0].publish);
+        let verified = verify_identity_attestation(&attestation, |_| Ok(padded.clone())).unwrap();
+        assert!(verified.claims.is_empty());
+        assert_eq!(verified.failed[0].claim, dns[0]);
+    }
+
+    #[test]
+    fn test_fetch_failure_reported_per_claim() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let attestation = attest_identity(&keypair, &claims()).unwrap();
+
+        let verified = verify_identity_attestation(&attestation, |claim| match claim {
+            IdentityClaim::Github { .. } => Err(GovernanceError::InvalidInput(
+                "Fetching gists failed".to_string(),
+            )),
+            _ => Ok(published(&attestation, claim)),
+        })
+        .unwrap();
+        assert_eq!(
+            verified.claims,
+            vec![claims()[0].clone(), claims()[2].clone()]
+        );
+        assert_eq!(verified.failed[0].claim, claims()[1]);
+        assert!(verified.failed[0].reason.contains("Fetching gists failed"));
+
+        // The registry only keeps what was verified
+        let mut registry = SignerRegistry::new();
+        registry.record(verified);
+        let recorded = registry.get(&keypair.public_key()).unwrap();
+        assert_eq!(recorded.claims.len(), 2);
+        assert!(recorded.failed.is_empty());
+    }
+
+    #[test]
+    fn test_tampered_attestation_rejected() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let mut attestation = attest_identity(&keypair, &claims()[..2]).unwrap();
+        attestation.proofs[1].claim = IdentityClaim::Github {
+            username: "mallory".to_string(),
+        };
+
+        let verified =
+            verify_identity_attestation(&attestation, |claim| Ok(published(&attestation, claim)))
+                .unwrap();
+        assert_eq!(verified.claims, claims()[..1]);
+        assert_eq!(verified.failed[0].claim, attestation.proofs[1].claim);
+        assert!(verified.failed[0]
+            .reason
+            .contains("not signed by the attested key"));
+
+        // A fingerprint that does not match the key fails the whole attestation
+        attestation.fingerprint = hex::encode([0u8; 32]);
+        assert!(
+            verify_identity_attestation(&attestation, |claim| Ok(published(&attestation, claim)))
+                .is_err()
+        );
+    }
+}
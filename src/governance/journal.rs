@@ -0,0 +1,278 @@
+//! # Signing Journal
+//!
+//! An append-only local record of every signature produced with a key.
+//!
+//! Before signing an artifact, a signer checks the journal for an earlier
+//! signature over the same artifact with a different signed payload, such as
+//! a corrected version string. Signing it again must explicitly supersede the
+//! earlier signatures, which is recorded in the journal and attested in the
+//! new signature's [`SUPERSEDES_ATTRIBUTE`], so verifiers can prefer the
+//! newer one.
+//!
+//! Each entry is one JSON line carrying the hash of the previous entry, so an
+//! edited, reordered or removed entry breaks the chain and the journal no
+//! longer opens.
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::messages::join_fields;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Signed attribute listing the message digests a signature supersedes
+pub const SUPERSEDES_ATTRIBUTE: &str = "supersedes";
+
+/// `prev_hash` of the first entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One signature produced with the key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    /// Hex SHA256 of the signed artifact
+    pub artifact_hash: String,
+    /// Hex SHA256 of the signed message
+    pub message_digest: String,
+    /// Attested attributes, other than [`SUPERSEDES_ATTRIBUTE`]
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+    /// Metadata saved with the signature
+    pub metadata: Value,
+    pub signed_at: DateTime<Utc>,
+    /// Signature file written
+    pub output_path: String,
+    /// Message digests of the earlier signatures this one supersedes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supersedes: Vec<String>,
+}
+
+impl JournalRecord {
+    /// Whether both records sign the same payload
+    pub fn same_payload(&self, other: &JournalRecord) -> bool {
+        self.message_digest == other.message_digest && self.attributes == other.attributes
+    }
+}
+
+/// A journal entry, chained to the previous one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    #[serde(flatten)]
+    pub record: JournalRecord,
+    /// Hash of the previous entry
+    pub prev_hash: String,
+    /// SHA256 over `prev_hash` and the record
+    pub hash: String,
+}
+
+impl JournalEntry {
+    fn compute_hash(prev_hash: &str, record: &JournalRecord) -> GovernanceResult<String> {
+        let record = serde_json::to_string(record)
+            .map_err(|e| GovernanceError::Serialization(e.to_string()))?;
+        Ok(hex::encode(Sha256::digest(join_fields(&[
+            "blvm-journal",
+            prev_hash,
+            &record,
+        ]))))
+    }
+}
+
+/// The signing journal of one key
+#[derive(Debug)]
+pub struct SigningJournal {
+    path: PathBuf,
+    entries: Vec<JournalEntry>,
+}
+
+impl SigningJournal {
+    /// Journal kept next to a key file (`<key>.journal`)
+    pub fn default_path(key_path: &Path) -> PathBuf {
+        let mut path = key_path.as_os_str().to_owned();
+        path.push(".journal");
+        PathBuf::from(path)
+    }
+
+    /// Open a journal and check its hash chain; a missing file is empty
+    pub fn open(path: &Path) -> GovernanceResult<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(GovernanceError::InvalidInput(format!(
+                    "Cannot read signing journal {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        let corrupt = |line: usize, reason: &str| {
+            GovernanceError::InvalidInput(format!(
+                "Signing journal {} is corrupt at line {}: {}",
+                path.display(),
+                line,
+                reason
+            ))
+        };
+        let mut entries: Vec<JournalEntry> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: JournalEntry =
+                serde_json::from_str(line).map_err(|e| corrupt(index + 1, &e.to_string()))?;
+            let prev_hash = entries.last().map_or(GENESIS_HASH, |e| e.hash.as_str());
+            if entry.prev_hash != prev_hash {
+                return Err(corrupt(index + 1, "entry does not follow the previous one"));
+            }
+            if entry.hash != JournalEntry::compute_hash(prev_hash, &entry.record)? {
+                return Err(corrupt(index + 1, "entry hash mismatch"));
+            }
+            entries.push(entry);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// Entries, oldest first
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Earlier signatures over the same artifact with a different payload
+    ///
+    /// Signatures already superseded by a later entry are not conflicts.
+    pub fn conflicts(&self, record: &JournalRecord) -> Vec<&JournalEntry> {
+        let superseded: BTreeSet<&str> = self
+            .entries
+            .iter()
+            .filter(|e| e.record.artifact_hash == record.artifact_hash)
+            .flat_map(|e| e.record.supersedes.iter().map(String::as_str))
+            .collect();
+        self.entries
+            .iter()
+            .filter(|e| e.record.artifact_hash == record.artifact_hash)
+            .filter(|e| !e.record.same_payload(record))
+            .filter(|e| !superseded.contains(e.record.message_digest.as_str()))
+            .collect()
+    }
+
+    /// Append a record and flush it to disk
+    pub fn append(&mut self, record: JournalRecord) -> GovernanceResult<&JournalEntry> {
+        let prev_hash = self
+            .entries
+            .last()
+            .map_or_else(|| GENESIS_HASH.to_string(), |e| e.hash.clone());
+        let hash = JournalEntry::compute_hash(&prev_hash, &record)?;
+        let entry = JournalEntry {
+            record,
+            prev_hash,
+            hash,
+        };
+
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| GovernanceError::Serialization(e.to_string()))?;
+        let write_error = |e: std::io::Error| {
+            GovernanceError::InvalidInput(format!(
+                "Cannot write signing journal {}: {}",
+                self.path.display(),
+                e
+            ))
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(write_error)?;
+        writeln!(file, "{}", line).map_err(write_error)?;
+        file.sync_all().map_err(write_error)?;
+
+        self.entries.push(entry);
+        Ok(self.entries.last().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(version: &str) -> JournalRecord {
+        let message = format!("binary:application:{}:{}", "ab".repeat(32), version);
+        JournalRecord {
+            artifact_hash: "ab".repeat(32),
+            message_digest: hex::encode(Sha256::digest(message.as_bytes())),
+            attributes: BTreeMap::new(),
+            metadata: serde_json::json!({ "version": version }),
+            signed_at: Utc::now(),
+            output_path: "node.bin.sig.json".to_string(),
+            supersedes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_conflicts_and_supersede() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("key.json.journal");
+        let mut journal = SigningJournal::open(&path).unwrap();
+        journal.append(record("1.0")).unwrap();
+
+        // Signing the same payload again is no conflict
+        assert!(journal.conflicts(&record("1.0")).is_empty());
+        journal.append(record("1.0")).unwrap();
+
+        let corrected = record("1.0.1");
+        let conflicts = journal.conflicts(&corrected);
+        assert_eq!(conflicts.len(), 2);
+        let mut superseding = corrected.clone();
+        superseding.supersedes = vec![conflicts[0].record.message_digest.clone()];
+        journal.append(superseding).unwrap();
+
+        // The chain survives reopening and the superseded entries no longer conflict
+        let journal = SigningJournal::open(&path).unwrap();
+        assert_eq!(journal.entries().len(), 3);
+        assert!(journal.conflicts(&corrected).is_empty());
+        assert_eq!(journal.conflicts(&record("1.0")).len(), 1);
+
+        // Another artifact never conflicts
+        let mut other = record("2.0");
+        other.artifact_hash = "cd".repeat(32);
+        assert!(journal.conflicts(&other).is_empty());
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("key.json.journal");
+        let mut journal = SigningJournal::open(&path).unwrap();
+        journal.append(record("1.0")).unwrap();
+        journal.append(record("1.0.1")).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+
+        // Rewriting the version in place
+        fs::write(&path, content.replacen("\"1.0\"", "\"1.1\"", 1)).unwrap();
+        let err = SigningJournal::open(&path).unwrap_err().to_string();
+        assert!(err.contains("line 1"), "{}", err);
+
+        // Dropping the first entry
+        fs::write(&path, format!("{}\n", lines[1])).unwrap();
+        let err = SigningJournal::open(&path).unwrap_err().to_string();
+        assert!(err.contains("does not follow"), "{}", err);
+    }
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(
+            SigningJournal::default_path(Path::new("keys/alice.key.json")),
+            PathBuf::from("keys/alice.key.json.journal")
+        );
+    }
+}
@@ -1,56 +1,437 @@
 //! # Governance Key Management
 //!
 //! Key generation and management for governance operations.
+//! Keypairs, which hold secret keys, need the `signing` feature; public keys
+//! are always available.
+//!
+//! Keypairs can be stored encrypted under a passphrase
+//! ([`GovernanceKeypair::to_encrypted_json`]): the secret key is sealed with
+//! AES-256-GCM under a key derived with scrypt. The envelope carries a
+//! version and names its algorithms, so the scheme can change without
+//! breaking existing files.
+//!
+//! Secret keys also travel in Wallet Import Format
+//! ([`GovernanceKeypair::to_wif`]), so keys kept in Bitcoin wallet tooling
+//! can be imported as they are.
 
+#[cfg(any(feature = "signing", test))]
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+#[cfg(any(feature = "signing", test))]
+use aes_gcm::{Aes256Gcm, Nonce};
+#[cfg(any(feature = "signing", test))]
+use bitcoin::{base58, Network};
+#[cfg(any(feature = "signing", test))]
 use rand::rngs::OsRng;
-use secp256k1::{PublicKey as Secp256k1PublicKey, Secp256k1, SecretKey};
+#[cfg(any(feature = "signing", test))]
+use rand::{CryptoRng, RngCore};
+use secp256k1::PublicKey as Secp256k1PublicKey;
+#[cfg(any(feature = "signing", test))]
+use secp256k1::{Keypair, SecretKey, XOnlyPublicKey};
+use secp256k1::{Parity, XOnlyPublicKey as Secp256k1XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt;
+#[cfg(any(feature = "signing", test))]
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(any(feature = "signing", test))]
+use std::time::Duration;
+#[cfg(any(feature = "signing", test))]
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
+#[cfg(any(feature = "signing", test))]
+use crate::governance::bip32::{
+    derive_child_private, derive_master_key, ExtendedPrivateKey, HARDENED,
+};
+#[cfg(any(feature = "signing", test))]
+use crate::governance::bip39::{
+    mnemonic_from_entropy, mnemonic_to_entropy, mnemonic_to_seed, EntropyStrength,
+};
+#[cfg(any(feature = "signing", test))]
+use crate::governance::context::signing_context;
 use crate::governance::error::{GovernanceError, GovernanceResult};
+#[cfg(any(feature = "signing", test))]
+#[cfg(any(feature = "signing", test))]
+use crate::util::Redacted;
+
+/// Attempts to draw key material before giving up on the entropy source
+#[cfg(any(feature = "signing", test))]
+const KEYGEN_ATTEMPTS: u32 = 4;
+
+/// Backoff before the first retry; doubled after each failed attempt
+#[cfg(any(feature = "signing", test))]
+const KEYGEN_INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// BIP32 path of the keypair behind a mnemonic: the first BIP44 key, so
+/// wallets can reproduce it
+pub const MNEMONIC_KEY_PATH: &str = "m/44'/0'/0'/0/0";
+
+/// WIF version byte of mainnet secret keys
+pub const WIF_PREFIX_MAINNET: u8 = 0x80;
+
+/// WIF version byte of testnet, signet and regtest secret keys
+pub const WIF_PREFIX_TESTNET: u8 = 0xef;
+
+/// Envelope version written by [`GovernanceKeypair::to_encrypted_json`]
+pub const ENCRYPTED_KEY_VERSION: u32 = 1;
+
+/// scrypt cost (log2 of N) for new encrypted key files: 32 MiB of memory
+#[cfg(any(feature = "signing", test))]
+const SCRYPT_LOG_N: u8 = 15;
+
+/// Highest scrypt cost accepted when reading, so a crafted file cannot
+/// demand unbounded memory
+#[cfg(any(feature = "signing", test))]
+const SCRYPT_MAX_LOG_N: u8 = 20;
+
+/// Passphrase-encrypted key file, see [`GovernanceKeypair::to_encrypted_json`]
+#[cfg(any(feature = "signing", test))]
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedKeyFile {
+    version: u32,
+    /// Compressed public key, hex; also the associated data of the cipher
+    public_key: String,
+    kdf: KdfParams,
+    cipher: CipherParams,
+    /// Sealed secret key with its authentication tag, hex
+    ciphertext: String,
+}
+
+#[cfg(any(feature = "signing", test))]
+#[derive(Debug, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+#[cfg(any(feature = "signing", test))]
+#[derive(Debug, Serialize, Deserialize)]
+struct CipherParams {
+    algorithm: String,
+    nonce: String,
+}
+
+/// Whether a key file is passphrase-encrypted rather than plaintext
+pub fn is_encrypted_key_json(json: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(json)
+        .is_ok_and(|value| value.get("ciphertext").is_some() && value.get("version").is_some())
+}
+
+/// Placeholder shown instead of a secret key
+#[cfg(any(feature = "signing", test))]
+const SECRET_KEY_REDACTED: &str = "[REDACTED]";
+
+/// Number of secret keys erased, to check drops erase them
+#[cfg(test)]
+static SECRETS_ERASED: AtomicUsize = AtomicUsize::new(0);
+
+/// A secp256k1 secret key that is erased on drop and never formatted
+///
+/// [`SecretKey`] is `Copy`, so the key is only lent out through
+/// [`expose`](Self::expose); copies taken from it are not erased.
+#[cfg(any(feature = "signing", test))]
+#[derive(Clone)]
+pub struct SecretKeyMaterial(SecretKey);
+
+#[cfg(any(feature = "signing", test))]
+impl SecretKeyMaterial {
+    /// Borrow the key, e.g. for
+    /// [`sign_message`](crate::governance::signatures::sign_message)
+    pub fn expose(&self) -> &SecretKey {
+        &self.0
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
+impl Zeroize for SecretKeyMaterial {
+    /// Overwrite the key; zero is not a valid key, so libsecp256k1 writes a
+    /// fixed non-secret value instead
+    fn zeroize(&mut self) {
+        self.0.non_secure_erase();
+        #[cfg(test)]
+        SECRETS_ERASED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
+impl Drop for SecretKeyMaterial {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
+impl ZeroizeOnDrop for SecretKeyMaterial {}
+
+#[cfg(any(feature = "signing", test))]
+impl fmt::Debug for SecretKeyMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(SECRET_KEY_REDACTED)
+    }
+}
 
 /// A governance keypair for signing governance messages
-#[derive(Debug, Clone)]
+///
+/// `Debug` shows the public key only.
+#[cfg(any(feature = "signing", test))]
+#[derive(Clone)]
 pub struct GovernanceKeypair {
-    pub secret_key: SecretKey,
+    pub secret_key: SecretKeyMaterial,
     pub public_key: Secp256k1PublicKey,
+    /// BIP32 chain code, for keypairs from a master seed or derived from one
+    chain_code: Option<Redacted<[u8; 32]>>,
+    /// BIP39 entropy, for master keypairs recovered from a mnemonic
+    mnemonic_entropy: Option<Redacted<Zeroizing<Vec<u8>>>>,
 }
 
 /// A public key for governance operations
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Serialized as the lowercase hex of its compressed form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct PublicKey {
     pub inner: Secp256k1PublicKey,
 }
 
+#[cfg(any(feature = "signing", test))]
 impl GovernanceKeypair {
     /// Generate a new random keypair
     pub fn generate() -> GovernanceResult<Self> {
-        let secp = Secp256k1::new();
-        let mut rng = OsRng;
+        Self::generate_with_rng(&mut OsRng)
+    }
 
-        let secret_key = SecretKey::new(&mut rng);
-        let public_key = secret_key.public_key(&secp);
+    /// Generate a new keypair from the given cryptographically secure
+    /// entropy source
+    ///
+    /// Failures of the entropy source are retried with exponential backoff;
+    /// `Cryptographic("entropy unavailable")` is returned once the attempts
+    /// are exhausted. The drawn key material is wiped after each attempt.
+    pub fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        rng: &mut R,
+    ) -> GovernanceResult<Self> {
+        let mut backoff = KEYGEN_INITIAL_BACKOFF;
 
-        Ok(Self {
-            secret_key,
-            public_key,
-        })
+        for attempt in 1..=KEYGEN_ATTEMPTS {
+            let mut secret_bytes = Zeroizing::new([0u8; 32]);
+            // Out-of-range scalars are as unlikely as a hash collision; retry them too
+            if rng.try_fill_bytes(secret_bytes.as_mut()).is_ok() {
+                if let Ok(keypair) = Self::from_secret_key(secret_bytes.as_ref()) {
+                    return Ok(keypair);
+                }
+            }
+
+            if attempt < KEYGEN_ATTEMPTS {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+        }
+
+        Err(GovernanceError::Cryptographic(
+            "entropy unavailable".to_string(),
+        ))
     }
 
     /// Create a keypair from a secret key
     pub fn from_secret_key(secret_bytes: &[u8]) -> GovernanceResult<Self> {
-        let secp = Secp256k1::new();
+        let secp = signing_context();
 
         let secret_key = SecretKey::from_slice(secret_bytes)
             .map_err(|e| GovernanceError::InvalidKey(format!("Invalid secret key: {}", e)))?;
 
-        let public_key = secret_key.public_key(&secp);
+        let public_key = secret_key.public_key(secp);
 
         Ok(Self {
-            secret_key,
+            secret_key: SecretKeyMaterial(secret_key),
             public_key,
+            chain_code: None,
+            mnemonic_entropy: None,
         })
     }
 
+    /// Create the BIP32 master keypair of a seed (16 to 64 bytes)
+    ///
+    /// Unlike other keypairs it carries a chain code, so child keys can be
+    /// derived with [`derive_child`](Self::derive_child).
+    pub fn from_master_seed(seed: &[u8]) -> GovernanceResult<Self> {
+        let (master, _) = derive_master_key(seed)?;
+        Ok(Self::from_extended(&master))
+    }
+
+    /// Derive a BIP32 child keypair
+    ///
+    /// `index` is below 2^31; `hardened` adds 2^31. Needs a keypair with a
+    /// chain code: one from [`from_master_seed`](Self::from_master_seed) or
+    /// derived from it.
+    pub fn derive_child(&self, index: u32, hardened: bool) -> GovernanceResult<Self> {
+        if index >= HARDENED {
+            return Err(GovernanceError::InvalidKey(format!(
+                "Child index {} is out of range; hardened indices are index + 2^31",
+                index
+            )));
+        }
+        let chain_code = self.chain_code.as_ref().ok_or_else(|| {
+            GovernanceError::InvalidKey(
+                "Keypair has no chain code; derive it from a master seed".to_string(),
+            )
+        })?;
+
+        let mut parent = ExtendedPrivateKey {
+            depth: 0,
+            parent_fingerprint: [0u8; 4],
+            child_number: 0,
+            chain_code: *chain_code.expose(),
+            private_key: *self.secret_key.expose(),
+        };
+        let child_number = if hardened { index | HARDENED } else { index };
+        let derived = derive_child_private(&parent, child_number);
+        // Extended keys hold plain copies of the secrets; erase them here
+        parent.private_key.non_secure_erase();
+        let (mut child, _) = derived?;
+        let keypair = Self::from_extended(&child);
+        child.private_key.non_secure_erase();
+        Ok(keypair)
+    }
+
+    fn from_extended(key: &ExtendedPrivateKey) -> Self {
+        Self {
+            secret_key: SecretKeyMaterial(key.private_key),
+            public_key: key.private_key.public_key(signing_context()),
+            chain_code: Some(Redacted::new(key.chain_code)),
+            mnemonic_entropy: None,
+        }
+    }
+
+    /// Generate a keypair backed up by a new BIP39 mnemonic
+    ///
+    /// The keypair is the one [`from_mnemonic`](Self::from_mnemonic) gives
+    /// with an empty passphrase; [`to_mnemonic`](Self::to_mnemonic) returns
+    /// the phrase.
+    pub fn generate_with_mnemonic(strength: EntropyStrength) -> GovernanceResult<Self> {
+        let mut entropy = Zeroizing::new(vec![0u8; strength.entropy_bytes()]);
+        OsRng
+            .try_fill_bytes(&mut entropy)
+            .map_err(|_| GovernanceError::Cryptographic("entropy unavailable".to_string()))?;
+        let words = Zeroizing::new(mnemonic_from_entropy(&entropy)?);
+        Self::from_mnemonic_words(&words, entropy, "")
+    }
+
+    /// Recover a keypair from a BIP39 mnemonic phrase
+    ///
+    /// Accepts 12, 15, 18, 21 or 24 English words separated by whitespace.
+    /// The seed is PBKDF2-HMAC-SHA512 of the phrase (2048 iterations) salted
+    /// with the passphrase (empty for none), and the keypair is derived from
+    /// it at [`MNEMONIC_KEY_PATH`]. Unknown words and a wrong checksum are
+    /// `InvalidKey` errors.
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> GovernanceResult<Self> {
+        let words: Zeroizing<Vec<String>> =
+            Zeroizing::new(phrase.split_whitespace().map(str::to_string).collect());
+        let entropy = mnemonic_to_entropy(&words).map_err(|e| match e {
+            GovernanceError::InvalidInput(message) => GovernanceError::InvalidKey(message),
+            other => other,
+        })?;
+        Self::from_mnemonic_words(&words, Zeroizing::new(entropy), passphrase)
+    }
+
+    fn from_mnemonic_words(
+        words: &[String],
+        entropy: Zeroizing<Vec<u8>>,
+        passphrase: &str,
+    ) -> GovernanceResult<Self> {
+        let seed = Zeroizing::new(mnemonic_to_seed(words, passphrase));
+        let (master, _) = derive_master_key(seed.as_ref())?;
+        let mut keypair = Self::derive(&master, MNEMONIC_KEY_PATH)?;
+        keypair.mnemonic_entropy = Some(Redacted::new(entropy));
+        Ok(keypair)
+    }
+
+    /// The BIP39 mnemonic phrase of the keypair
+    ///
+    /// Only keypairs from [`from_mnemonic`](Self::from_mnemonic) or
+    /// [`generate_with_mnemonic`](Self::generate_with_mnemonic) have one;
+    /// others are `InvalidKey` errors. A BIP39 passphrase is not part of the
+    /// phrase and must be kept alongside it.
+    pub fn to_mnemonic(&self) -> GovernanceResult<String> {
+        let entropy = self.mnemonic_entropy.as_ref().ok_or_else(|| {
+            GovernanceError::InvalidKey("Keypair was not created from a mnemonic".to_string())
+        })?;
+        let words = Zeroizing::new(mnemonic_from_entropy(entropy.expose())?);
+        Ok(words.join(" "))
+    }
+
+    /// Encode the secret key in Wallet Import Format, as Bitcoin Core's
+    /// `dumpprivkey` does
+    ///
+    /// The key is flagged compressed, like every governance public key.
+    /// Signet and regtest share the testnet prefix.
+    pub fn to_wif(&self, network: Network) -> String {
+        let prefix = match network {
+            Network::Bitcoin => WIF_PREFIX_MAINNET,
+            _ => WIF_PREFIX_TESTNET,
+        };
+        let mut payload = Zeroizing::new(Vec::with_capacity(34));
+        payload.push(prefix);
+        payload.extend_from_slice(self.secret_key_bytes().as_ref());
+        payload.push(0x01);
+        base58::encode_check(&payload)
+    }
+
+    /// Import a secret key in Wallet Import Format
+    ///
+    /// Accepts mainnet and testnet prefixes, with or without the compressed
+    /// flag; the keypair is the same either way. A wrong Base58Check checksum
+    /// is `InvalidChecksum` and any other version byte is
+    /// `InvalidNetworkPrefix`; other malformed input is `InvalidKey`.
+    pub fn from_wif(wif: &str) -> GovernanceResult<Self> {
+        let payload = Zeroizing::new(base58::decode_check(wif.trim()).map_err(|e| match e {
+            base58::Error::BadChecksum(..) => {
+                GovernanceError::InvalidChecksum("WIF checksum does not match".to_string())
+            }
+            _ => GovernanceError::InvalidKey("Invalid WIF encoding".to_string()),
+        })?);
+
+        let (&prefix, key) = payload
+            .split_first()
+            .ok_or_else(|| GovernanceError::InvalidKey("Empty WIF payload".to_string()))?;
+        if prefix != WIF_PREFIX_MAINNET && prefix != WIF_PREFIX_TESTNET {
+            return Err(GovernanceError::InvalidNetworkPrefix { prefix });
+        }
+        let secret = match key.len() {
+            32 => key,
+            33 if key[32] == 0x01 => &key[..32],
+            33 => {
+                return Err(GovernanceError::InvalidKey(
+                    "Invalid WIF compression flag".to_string(),
+                ))
+            }
+            n => {
+                return Err(GovernanceError::InvalidKey(format!(
+                    "WIF key must be 32 or 33 bytes, got {}",
+                    n
+                )))
+            }
+        };
+        Self::from_secret_key(secret)
+    }
+
+    /// Derive the keypair at a BIP32 path (e.g. `m/44'/0'/7'/0/1`) below an
+    /// extended private key
+    ///
+    /// Keys at non-hardened steps below an xpub can be derived without the
+    /// secret, with [`ExtendedPublicKey::derive_path`](crate::governance::bip32::ExtendedPublicKey::derive_path).
+    /// Malformed paths are `InvalidKey` errors.
+    pub fn derive(root: &ExtendedPrivateKey, path: &str) -> GovernanceResult<Self> {
+        Ok(Self::from_extended(&root.derive_path(path)?))
+    }
+
+    /// Derive the keypair at a BIP32 path below the master key of a seed
+    pub fn derive_from_seed(seed: &[u8], path: &str) -> GovernanceResult<Self> {
+        let (master, _) = derive_master_key(seed)?;
+        Self::derive(&master, path)
+    }
+
     /// Get the public key
     pub fn public_key(&self) -> PublicKey {
         PublicKey {
@@ -58,17 +439,155 @@ impl GovernanceKeypair {
         }
     }
 
-    /// Get the secret key bytes
-    pub fn secret_key_bytes(&self) -> [u8; 32] {
-        self.secret_key.secret_bytes()
+    /// Get the secret key bytes, wiped when dropped
+    pub fn secret_key_bytes(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.secret_key.0.secret_bytes())
     }
 
     /// Get the public key bytes
     pub fn public_key_bytes(&self) -> [u8; 33] {
         self.public_key.serialize()
     }
+
+    /// Get the secp256k1 keypair and x-only public key used for BIP340
+    /// Schnorr signing
+    pub fn to_keypair_for_schnorr(&self) -> (Keypair, XOnlyPublicKey) {
+        let secp = signing_context();
+        let keypair = Keypair::from_secret_key(secp, self.secret_key.expose());
+        let (x_only, _) = keypair.x_only_public_key();
+        (keypair, x_only)
+    }
+
+    /// Encrypt the keypair under a passphrase, as a JSON key file
+    ///
+    /// The public key stays readable, so the file still works as a public
+    /// key file.
+    pub fn to_encrypted_json(&self, passphrase: &str) -> GovernanceResult<String> {
+        self.encrypt_with_cost(passphrase, SCRYPT_LOG_N)
+    }
+
+    fn encrypt_with_cost(&self, passphrase: &str, log_n: u8) -> GovernanceResult<String> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        OsRng
+            .try_fill_bytes(&mut salt)
+            .and_then(|_| OsRng.try_fill_bytes(&mut nonce))
+            .map_err(|e| GovernanceError::Cryptographic(format!("entropy unavailable: {}", e)))?;
+
+        let kdf = KdfParams {
+            algorithm: "scrypt".to_string(),
+            log_n,
+            r: 8,
+            p: 1,
+            salt: hex::encode(salt),
+        };
+        let cipher = encrypted_key_cipher(passphrase, &kdf)?;
+        let public_key = self.public_key_bytes();
+        let secret_key = self.secret_key_bytes();
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &secret_key[..],
+                    aad: &public_key,
+                },
+            )
+            .map_err(|_| GovernanceError::Cryptographic("key encryption failed".to_string()))?;
+
+        let file = EncryptedKeyFile {
+            version: ENCRYPTED_KEY_VERSION,
+            public_key: hex::encode(public_key),
+            kdf,
+            cipher: CipherParams {
+                algorithm: "aes-256-gcm".to_string(),
+                nonce: hex::encode(nonce),
+            },
+            ciphertext: hex::encode(ciphertext),
+        };
+        serde_json::to_string_pretty(&file)
+            .map_err(|e| GovernanceError::Serialization(e.to_string()))
+    }
+
+    /// Decrypt a key file written by [`to_encrypted_json`](Self::to_encrypted_json)
+    ///
+    /// A wrong passphrase or a modified file is an `InvalidKey` error.
+    pub fn from_encrypted_json(json: &str, passphrase: &str) -> GovernanceResult<Self> {
+        let invalid = |reason: &str| {
+            GovernanceError::InvalidKey(format!("Invalid encrypted key file: {}", reason))
+        };
+
+        let file: EncryptedKeyFile =
+            serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))?;
+        if file.version != ENCRYPTED_KEY_VERSION {
+            return Err(invalid(&format!("unsupported version {}", file.version)));
+        }
+        if file.cipher.algorithm != "aes-256-gcm" {
+            return Err(invalid(&format!(
+                "unsupported cipher '{}'",
+                file.cipher.algorithm
+            )));
+        }
+        let public_key = hex::decode(&file.public_key).map_err(|_| invalid("bad public_key"))?;
+        let nonce = hex::decode(&file.cipher.nonce)
+            .ok()
+            .filter(|nonce| nonce.len() == 12)
+            .ok_or_else(|| invalid("bad nonce"))?;
+        let ciphertext = hex::decode(&file.ciphertext).map_err(|_| invalid("bad ciphertext"))?;
+
+        let cipher = encrypted_key_cipher(passphrase, &file.kdf)?;
+        let secret_key = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &public_key,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| {
+                GovernanceError::InvalidKey(
+                    "Wrong passphrase or corrupted encrypted key file".to_string(),
+                )
+            })?;
+
+        let keypair = Self::from_secret_key(&secret_key)?;
+        if keypair.public_key_bytes()[..] != public_key[..] {
+            return Err(invalid("secret key does not match public_key"));
+        }
+        Ok(keypair)
+    }
+}
+
+/// AES-256-GCM keyed by the scrypt derivation of the passphrase
+#[cfg(any(feature = "signing", test))]
+fn encrypted_key_cipher(passphrase: &str, kdf: &KdfParams) -> GovernanceResult<Aes256Gcm> {
+    let invalid = |reason: String| {
+        GovernanceError::InvalidKey(format!("Invalid encrypted key file: {}", reason))
+    };
+
+    if kdf.algorithm != "scrypt" {
+        return Err(invalid(format!("unsupported KDF '{}'", kdf.algorithm)));
+    }
+    if kdf.log_n > SCRYPT_MAX_LOG_N {
+        return Err(invalid(format!(
+            "scrypt cost 2^{} exceeds the limit 2^{}",
+            kdf.log_n, SCRYPT_MAX_LOG_N
+        )));
+    }
+    let salt = hex::decode(&kdf.salt).map_err(|_| invalid("bad salt".to_string()))?;
+    let params = scrypt::Params::new(kdf.log_n, kdf.r, kdf.p, 32)
+        .map_err(|e| invalid(format!("bad scrypt parameters: {}", e)))?;
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(passphrase.as_bytes(), &salt, &params, &mut key[..])
+        .map_err(|e| GovernanceError::Cryptographic(format!("scrypt failed: {}", e)))?;
+    Aes256Gcm::new_from_slice(&key[..])
+        .map_err(|e| GovernanceError::Cryptographic(format!("bad cipher key: {}", e)))
 }
 
+/// Compact public key fingerprint, see [`PublicKey::fingerprint`]
+pub type Fingerprint = [u8; 8];
+
 impl PublicKey {
     /// Create a public key from bytes
     pub fn from_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
@@ -78,6 +597,46 @@ impl PublicKey {
         Ok(Self { inner: public_key })
     }
 
+    /// Create a public key from uncompressed SEC1 bytes (`0x04 || x || y`)
+    ///
+    /// The key is normalized: it always serializes in compressed form.
+    pub fn from_uncompressed_bytes(bytes: &[u8; 65]) -> GovernanceResult<Self> {
+        if bytes[0] != 0x04 {
+            return Err(GovernanceError::InvalidKey(format!(
+                "Uncompressed public key must start with 0x04, got 0x{:02x}",
+                bytes[0]
+            )));
+        }
+
+        Self::from_bytes(bytes)
+    }
+
+    /// Create a public key from BIP340 x-only bytes
+    ///
+    /// The key with even y is taken. A key with odd y comes back as its
+    /// negation, which ECDSA signatures by the original key do not verify
+    /// against; check [`parity`](Self::parity) before exporting a key as
+    /// x-only.
+    pub fn from_xonly_bytes(bytes: &[u8; 32]) -> GovernanceResult<Self> {
+        let xonly = Secp256k1XOnlyPublicKey::from_slice(bytes).map_err(|e| {
+            GovernanceError::InvalidKey(format!("Invalid x-only public key: {}", e))
+        })?;
+        Ok(Self {
+            inner: xonly.public_key(Parity::Even),
+        })
+    }
+
+    /// Get the BIP340 x-only bytes: the x coordinate alone
+    pub fn to_xonly_bytes(&self) -> [u8; 32] {
+        self.inner.x_only_public_key().0.serialize()
+    }
+
+    /// Parity of the y coordinate, which [`to_xonly_bytes`](Self::to_xonly_bytes)
+    /// leaves out
+    pub fn parity(&self) -> Parity {
+        self.inner.x_only_public_key().1
+    }
+
     /// Get the public key bytes
     pub fn to_bytes(&self) -> [u8; 33] {
         self.inner.serialize()
@@ -92,6 +651,34 @@ impl PublicKey {
     pub fn to_uncompressed_bytes(&self) -> [u8; 65] {
         self.inner.serialize_uncompressed()
     }
+
+    /// Compute a SHA256 fingerprint identifying this key
+    ///
+    /// The SHA256 of the compressed public key. This is not the 4-byte
+    /// BIP32 key fingerprint (the first bytes of its HASH160).
+    pub fn sha256_fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(self.to_bytes()).into()
+    }
+
+    /// Compact fingerprint for indexing: the first 8 bytes of
+    /// [`sha256_fingerprint`](Self::sha256_fingerprint)
+    ///
+    /// Distinct keys may share a fingerprint; compare the full keys to
+    /// confirm a match.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let mut fingerprint = [0u8; 8];
+        fingerprint.copy_from_slice(&self.sha256_fingerprint()[..8]);
+        fingerprint
+    }
+
+    /// [`fingerprint`](Self::fingerprint) as 16 lowercase hex characters,
+    /// to name the key in reports and logs
+    ///
+    /// Derived from the compressed key alone, so it does not change between
+    /// versions.
+    pub fn short(&self) -> String {
+        hex::encode(self.fingerprint())
+    }
 }
 
 impl fmt::Display for PublicKey {
@@ -100,6 +687,60 @@ impl fmt::Display for PublicKey {
     }
 }
 
+/// Parses compressed or uncompressed hex
+///
+/// x-only hex is rejected: it leaves out the parity of y, and reading it as
+/// even y would silently negate keys with odd y. Governance config files
+/// accept it; elsewhere use [`PublicKey::from_xonly_bytes`] to take even y
+/// explicitly.
+impl std::str::FromStr for PublicKey {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s)
+            .map_err(|e| GovernanceError::InvalidKey(format!("Invalid public key hex: {}", e)))?;
+        match bytes.len() {
+            33 | 65 => Self::from_bytes(&bytes).map_err(|_| {
+                GovernanceError::InvalidKey("Public key is not a point on secp256k1".to_string())
+            }),
+            32 => Err(GovernanceError::InvalidKey(
+                "Public key is 32-byte x-only, which does not say whether y is even; \
+                 use the compressed form, or from_xonly_bytes to assume even y"
+                    .to_string(),
+            )),
+            len => Err(GovernanceError::InvalidKey(format!(
+                "Public key must be 33 bytes (compressed) or 65 bytes (uncompressed), got {}",
+                len
+            ))),
+        }
+    }
+}
+
+impl TryFrom<String> for PublicKey {
+    type Error = GovernanceError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<PublicKey> for String {
+    fn from(public_key: PublicKey) -> Self {
+        public_key.to_string()
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
+impl fmt::Debug for GovernanceKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GovernanceKeypair")
+            .field("public_key", &format_args!("{}", self.public_key()))
+            .field("secret_key", &self.secret_key)
+            .finish()
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
 impl fmt::Display for GovernanceKeypair {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "GovernanceKeypair(pubkey: {})", self.public_key())
@@ -110,6 +751,64 @@ impl fmt::Display for GovernanceKeypair {
 mod tests {
     use super::*;
 
+    /// Entropy source that fails a number of times before delegating to OsRng
+    struct FlakyRng {
+        failures: u32,
+        calls: u32,
+    }
+
+    impl RngCore for FlakyRng {
+        fn next_u32(&mut self) -> u32 {
+            OsRng.next_u32()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            OsRng.next_u64()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.try_fill_bytes(dest).unwrap()
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.calls += 1;
+            if self.calls <= self.failures {
+                return Err(rand::Error::new("entropy source not ready"));
+            }
+            OsRng.try_fill_bytes(dest)
+        }
+    }
+
+    // Delegates to OsRng once it stops failing
+    impl CryptoRng for FlakyRng {}
+
+    #[test]
+    fn test_generate_retries_entropy_failures() {
+        let mut rng = FlakyRng {
+            failures: 2,
+            calls: 0,
+        };
+        let keypair = GovernanceKeypair::generate_with_rng(&mut rng).unwrap();
+        assert_eq!(rng.calls, 3);
+        assert_eq!(
+            keypair.public_key,
+            keypair.secret_key.expose().public_key(signing_context())
+        );
+    }
+
+    #[test]
+    fn test_generate_gives_up_after_retries() {
+        let mut rng = FlakyRng {
+            failures: u32::MAX,
+            calls: 0,
+        };
+        let err = GovernanceKeypair::generate_with_rng(&mut rng).unwrap_err();
+        assert!(
+            matches!(err, GovernanceError::Cryptographic(ref msg) if msg == "entropy unavailable")
+        );
+        assert_eq!(rng.calls, KEYGEN_ATTEMPTS);
+    }
+
     #[test]
     fn test_keypair_generation() {
         let keypair = GovernanceKeypair::generate().unwrap();
@@ -126,12 +825,38 @@ mod tests {
         let keypair1 = GovernanceKeypair::generate().unwrap();
         let secret_bytes = keypair1.secret_key_bytes();
 
-        let keypair2 = GovernanceKeypair::from_secret_key(&secret_bytes).unwrap();
+        let keypair2 = GovernanceKeypair::from_secret_key(&*secret_bytes).unwrap();
 
         // Both keypairs should have the same public key
         assert_eq!(keypair1.public_key(), keypair2.public_key());
     }
 
+    #[test]
+    fn test_keypair_debug_redacts_secret() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let debug = format!("{:?} {:#?}", keypair, keypair);
+
+        assert!(!debug.contains(&hex::encode(keypair.secret_key_bytes())));
+        assert!(debug.contains(&keypair.public_key().to_string()));
+        assert!(debug.contains(SECRET_KEY_REDACTED));
+    }
+
+    #[test]
+    fn test_secret_key_erased_on_drop() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let secret = *keypair.secret_key_bytes();
+
+        let mut material = keypair.secret_key.clone();
+        material.zeroize();
+        assert_ne!(material.expose().secret_bytes(), secret);
+
+        // Each drop erases its copy; other tests only add to the count
+        let erased = SECRETS_ERASED.load(Ordering::SeqCst);
+        drop(keypair);
+        drop(material);
+        assert!(SECRETS_ERASED.load(Ordering::SeqCst) >= erased + 2);
+    }
+
     #[test]
     fn test_invalid_secret_key() {
         let invalid_bytes = [0u8; 31]; // Too short
@@ -139,10 +864,437 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_public_key_from_uncompressed_bytes() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let public_key = keypair.public_key();
+
+        let uncompressed = public_key.to_uncompressed_bytes();
+        let from_uncompressed = PublicKey::from_uncompressed_bytes(&uncompressed).unwrap();
+        let from_compressed = PublicKey::from_bytes(&public_key.to_compressed_bytes()).unwrap();
+
+        assert_eq!(from_uncompressed, from_compressed);
+        assert_eq!(
+            from_uncompressed.to_bytes(),
+            public_key.to_compressed_bytes()
+        );
+
+        let mut bad_prefix = uncompressed;
+        bad_prefix[0] = 0x06;
+        assert!(PublicKey::from_uncompressed_bytes(&bad_prefix).is_err());
+    }
+
+    #[test]
+    fn test_public_key_serde() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Holder {
+            public_key: PublicKey,
+        }
+
+        let public_key = GovernanceKeypair::generate().unwrap().public_key();
+        let json = serde_json::to_string(&public_key).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(public_key.to_bytes())));
+        assert_eq!(
+            serde_json::from_str::<PublicKey>(&json).unwrap(),
+            public_key
+        );
+
+        let holder = Holder { public_key };
+        let toml = toml::to_string(&holder).unwrap();
+        assert_eq!(toml::from_str::<Holder>(&toml).unwrap(), holder);
+
+        // Uncompressed keys are read, and written back compressed
+        let uncompressed = hex::encode(holder.public_key.to_uncompressed_bytes());
+        let parsed: PublicKey = serde_json::from_value(uncompressed.into()).unwrap();
+        assert_eq!(parsed, holder.public_key);
+
+        let rejects = |hex_key: String, reason: &str| {
+            let err = serde_json::from_value::<PublicKey>(hex_key.into()).unwrap_err();
+            assert!(err.to_string().contains(reason), "{}", err);
+        };
+        rejects("02zz".to_string(), "Invalid public key hex");
+        rejects("02".repeat(20), "got 20");
+        rejects(format!("02{}", "ff".repeat(32)), "not a point on secp256k1");
+        rejects("ff".repeat(32), "whether y is even");
+    }
+
+    /// A key with even y, which survives an x-only round trip
+    fn even_y_keypair() -> GovernanceKeypair {
+        loop {
+            let keypair = GovernanceKeypair::generate().unwrap();
+            if keypair.public_key().parity() == Parity::Even {
+                return keypair;
+            }
+        }
+    }
+
+    #[test]
+    fn test_xonly_round_trip() {
+        for _ in 0..8 {
+            let public_key = GovernanceKeypair::generate().unwrap().public_key();
+            let xonly = public_key.to_xonly_bytes();
+            assert_eq!(xonly[..], public_key.to_bytes()[1..]);
+            let imported = PublicKey::from_xonly_bytes(&xonly).unwrap();
+            assert_eq!(imported.to_xonly_bytes(), xonly);
+            assert_eq!(imported.parity(), Parity::Even);
+            // Only an odd key changes: it comes back negated
+            assert_eq!(imported == public_key, public_key.parity() == Parity::Even);
+            let err = hex::encode(xonly).parse::<PublicKey>().unwrap_err();
+            assert!(err.to_string().contains("whether y is even"));
+        }
+        assert!(PublicKey::from_xonly_bytes(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn test_xonly_key_verifies_ecdsa() {
+        let keypair = even_y_keypair();
+        let signature = crate::sign_message(keypair.secret_key.expose(), b"release").unwrap();
+        let imported = PublicKey::from_xonly_bytes(&keypair.public_key().to_xonly_bytes()).unwrap();
+        assert_eq!(imported, keypair.public_key());
+        assert!(crate::governance::verify_signature(&signature, b"release", &imported).unwrap());
+    }
+
+    #[test]
+    fn test_fingerprint_vectors() {
+        // SHA256 of the compressed key, first 8 bytes
+        let vectors = [
+            // The generator point
+            (
+                "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                "0f715baf5d4c2ed3",
+            ),
+            // First BIP44 key of the all-zero mnemonic (test_from_mnemonic_vector)
+            (
+                "03aaeb52dd7494c361049de67cc680e83ebcbbbdbeb13637d92cd845f70308af5e",
+                "a41ac9373b9868ec",
+            ),
+        ];
+        for (key, expected) in vectors {
+            let public_key = PublicKey::from_bytes(&hex::decode(key).unwrap()).unwrap();
+            assert_eq!(public_key.short(), expected);
+            assert_eq!(hex::encode(public_key.fingerprint()), expected);
+            assert!(hex::encode(public_key.sha256_fingerprint()).starts_with(expected));
+        }
+    }
+
     #[test]
     fn test_invalid_public_key() {
         let invalid_bytes = [0u8; 32]; // Wrong length for public key
         let result = PublicKey::from_bytes(&invalid_bytes);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_keypair_for_schnorr() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let (schnorr_keypair, x_only) = keypair.to_keypair_for_schnorr();
+
+        assert_eq!(schnorr_keypair.secret_bytes(), *keypair.secret_key_bytes());
+        assert_eq!(x_only.serialize(), keypair.public_key_bytes()[1..]);
+    }
+
+    #[test]
+    fn test_encrypted_key_roundtrip() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let json = keypair.encrypt_with_cost("hunter2", 10).unwrap();
+
+        assert!(is_encrypted_key_json(&json));
+        assert!(!json.contains(&hex::encode(keypair.secret_key_bytes())));
+        let restored = GovernanceKeypair::from_encrypted_json(&json, "hunter2").unwrap();
+        assert_eq!(restored.secret_key_bytes(), keypair.secret_key_bytes());
+        assert_eq!(restored.public_key(), keypair.public_key());
+
+        // Fresh salt and nonce each time
+        assert_ne!(json, keypair.encrypt_with_cost("hunter2", 10).unwrap());
+    }
+
+    #[test]
+    fn test_encrypted_key_wrong_passphrase() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let json = keypair.encrypt_with_cost("hunter2", 10).unwrap();
+
+        let err = GovernanceKeypair::from_encrypted_json(&json, "hunter3").unwrap_err();
+        assert!(matches!(err, GovernanceError::InvalidKey(_)), "{}", err);
+        assert!(err.to_string().contains("Wrong passphrase"), "{}", err);
+
+        // The public key is authenticated too
+        let other = GovernanceKeypair::generate().unwrap();
+        let mut file: serde_json::Value = serde_json::from_str(&json).unwrap();
+        file["public_key"] = hex::encode(other.public_key_bytes()).into();
+        let err = GovernanceKeypair::from_encrypted_json(&file.to_string(), "hunter2").unwrap_err();
+        assert!(matches!(err, GovernanceError::InvalidKey(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_encrypted_key_envelope_checks() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let json = keypair.encrypt_with_cost("hunter2", 10).unwrap();
+        let file: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let rejects = |edit: &dyn Fn(&mut serde_json::Value), reason: &str| {
+            let mut file = file.clone();
+            edit(&mut file);
+            let err =
+                GovernanceKeypair::from_encrypted_json(&file.to_string(), "hunter2").unwrap_err();
+            assert!(err.to_string().contains(reason), "{}", err);
+        };
+        rejects(&|f| f["version"] = 2.into(), "unsupported version 2");
+        rejects(
+            &|f| f["kdf"]["algorithm"] = "argon2id".into(),
+            "unsupported KDF",
+        );
+        rejects(
+            &|f| f["cipher"]["algorithm"] = "rot13".into(),
+            "unsupported cipher",
+        );
+        rejects(&|f| f["kdf"]["log_n"] = 40.into(), "exceeds the limit");
+
+        assert!(!is_encrypted_key_json(
+            r#"{"public_key": "00", "secret_key": "00"}"#
+        ));
+    }
+
+    #[test]
+    fn test_derive_keypair() {
+        let seed = [7u8; 32];
+        let release = GovernanceKeypair::derive_from_seed(&seed, "m/44'/0'/7'/0/1").unwrap();
+        let budget = GovernanceKeypair::derive_from_seed(&seed, "m/44'/0'/7'/0/2").unwrap();
+        assert_ne!(release.public_key(), budget.public_key());
+
+        // Verification infrastructure derives the same key from the xpub
+        let (master, _) = derive_master_key(&seed).unwrap();
+        let account = master
+            .derive_path("m/44'/0'/7'")
+            .unwrap()
+            .to_extended_public();
+        let public = account.derive_path("m/0/1").unwrap().to_public_key();
+        assert_eq!(public, release.public_key());
+        assert_eq!(
+            GovernanceKeypair::derive(&master, "m/44'/0'/7'/0/1")
+                .unwrap()
+                .secret_key_bytes(),
+            release.secret_key_bytes()
+        );
+
+        let err = GovernanceKeypair::derive_from_seed(&seed, "m/44'/x").unwrap_err();
+        assert!(matches!(err, GovernanceError::InvalidKey(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_derive_child_roundtrip() {
+        let seed = [9u8; 32];
+        let master = GovernanceKeypair::from_master_seed(&seed).unwrap();
+        let child = master
+            .derive_child(44, true)
+            .and_then(|key| key.derive_child(0, true))
+            .and_then(|key| key.derive_child(0, false))
+            .unwrap();
+
+        let again = GovernanceKeypair::from_master_seed(&seed)
+            .unwrap()
+            .derive_child(44, true)
+            .and_then(|key| key.derive_child(0, true))
+            .and_then(|key| key.derive_child(0, false))
+            .unwrap();
+        assert_eq!(child.public_key_bytes(), again.public_key_bytes());
+
+        // The same key as along the path
+        let along_path = GovernanceKeypair::derive_from_seed(&seed, "m/44'/0'/0").unwrap();
+        assert_eq!(child.public_key_bytes(), along_path.public_key_bytes());
+
+        // Hardened and normal children differ
+        assert_ne!(
+            master.derive_child(1, true).unwrap().public_key_bytes(),
+            master.derive_child(1, false).unwrap().public_key_bytes()
+        );
+    }
+
+    #[test]
+    fn test_derive_child_errors() {
+        let master = GovernanceKeypair::from_master_seed(&[9u8; 32]).unwrap();
+        assert!(matches!(
+            master.derive_child(HARDENED, false),
+            Err(GovernanceError::InvalidKey(_))
+        ));
+
+        let random = GovernanceKeypair::generate().unwrap();
+        assert!(matches!(
+            random.derive_child(0, false),
+            Err(GovernanceError::InvalidKey(_))
+        ));
+
+        // The chain code stays out of Debug output
+        let chain_code = hex::encode(master.chain_code.as_ref().unwrap().expose());
+        assert!(!format!("{:?}", master).contains(&chain_code));
+    }
+
+    /// All-zero entropy, from the BIP39 test vectors
+    const ZERO_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon \
+                                 abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_from_mnemonic_vector() {
+        // BIP39 vector: the seed of the phrase with passphrase "TREZOR"
+        let words: Vec<String> = ZERO_MNEMONIC.split(' ').map(str::to_string).collect();
+        assert_eq!(
+            hex::encode(mnemonic_to_seed(&words, "TREZOR")),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        let keypair = GovernanceKeypair::from_mnemonic(ZERO_MNEMONIC, "TREZOR").unwrap();
+        let (master, _) = derive_master_key(&mnemonic_to_seed(&words, "TREZOR")).unwrap();
+        let expected = GovernanceKeypair::derive(&master, MNEMONIC_KEY_PATH).unwrap();
+        assert_eq!(keypair.secret_key_bytes(), expected.secret_key_bytes());
+
+        // The well-known first BIP44 key of the phrase, as wallets derive it
+        let keypair = GovernanceKeypair::from_mnemonic(ZERO_MNEMONIC, "").unwrap();
+        assert_eq!(
+            hex::encode(keypair.secret_key_bytes()),
+            "e284129cc0922579a535bbf4d1a3b25773090d28c909bc0fed73b5e0222cc372"
+        );
+        assert_eq!(
+            hex::encode(keypair.public_key_bytes()),
+            "03aaeb52dd7494c361049de67cc680e83ebcbbbdbeb13637d92cd845f70308af5e"
+        );
+        assert_eq!(keypair.to_mnemonic().unwrap(), ZERO_MNEMONIC);
+        assert!(keypair.derive_child(0, true).is_ok());
+    }
+
+    #[test]
+    fn test_mnemonic_round_trip() {
+        for strength in [
+            EntropyStrength::Bits128,
+            EntropyStrength::Bits160,
+            EntropyStrength::Bits192,
+            EntropyStrength::Bits224,
+            EntropyStrength::Bits256,
+        ] {
+            let keypair = GovernanceKeypair::generate_with_mnemonic(strength).unwrap();
+            let phrase = keypair.to_mnemonic().unwrap();
+            assert_eq!(phrase.split(' ').count(), strength.word_count());
+
+            let recovered = GovernanceKeypair::from_mnemonic(&phrase, "").unwrap();
+            assert_eq!(recovered.secret_key_bytes(), keypair.secret_key_bytes());
+            assert_eq!(recovered.to_mnemonic().unwrap(), phrase);
+
+            // The passphrase selects a different key
+            let other = GovernanceKeypair::from_mnemonic(&phrase, "extra").unwrap();
+            assert_ne!(other.secret_key_bytes(), keypair.secret_key_bytes());
+        }
+    }
+
+    #[test]
+    fn test_wif_vectors() {
+        // (secret key, mainnet, testnet, mainnet uncompressed) as Bitcoin
+        // Core's dumpprivkey prints them
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn",
+                "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN87JcbXMTcA",
+                "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf",
+            ),
+            (
+                "0c28fca386c7a227600b2fe50b7cae11ec86d3bf1fbe471be89827e19d72aa1d",
+                "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617",
+                "cMzLdeGd5vEqxB8B6VFQoRopQ3sLAAvEzDAoQgvX54xwofSWj1fx",
+                "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ",
+            ),
+        ];
+
+        for (secret, mainnet, testnet, uncompressed) in vectors {
+            let keypair =
+                GovernanceKeypair::from_secret_key(&hex::decode(secret).unwrap()).unwrap();
+            assert_eq!(keypair.to_wif(Network::Bitcoin), mainnet);
+            assert_eq!(keypair.to_wif(Network::Testnet), testnet);
+            assert_eq!(keypair.to_wif(Network::Regtest), testnet);
+
+            for wif in [mainnet, testnet, uncompressed] {
+                let imported = GovernanceKeypair::from_wif(wif).unwrap();
+                assert_eq!(hex::encode(imported.secret_key_bytes()), secret);
+                assert_eq!(imported.public_key(), keypair.public_key());
+            }
+        }
+    }
+
+    #[test]
+    fn test_wif_round_trip_signing() {
+        use crate::governance::signatures::{sign_message, verify_signature};
+
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let wif = keypair.to_wif(Network::Bitcoin);
+        assert!(wif.starts_with('K') || wif.starts_with('L'));
+
+        let imported = GovernanceKeypair::from_wif(&wif).unwrap();
+        assert_eq!(imported.to_wif(Network::Bitcoin), wif);
+        let message = b"governance message";
+        let signature = sign_message(imported.secret_key.expose(), message).unwrap();
+        assert!(verify_signature(&signature, message, &keypair.public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_from_wif_errors() {
+        let wif = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+
+        // A changed character breaks the checksum
+        let tampered = wif.replace("nhXJ", "nhXK");
+        assert!(matches!(
+            GovernanceKeypair::from_wif(&tampered),
+            Err(GovernanceError::InvalidChecksum(_))
+        ));
+
+        // A valid checksum over an address version byte
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(&[0; 31]);
+        payload.extend_from_slice(&[1, 1]);
+        let wrong_network = base58::encode_check(&payload);
+        assert!(matches!(
+            GovernanceKeypair::from_wif(&wrong_network),
+            Err(GovernanceError::InvalidNetworkPrefix { prefix: 0x00 })
+        ));
+
+        // Bad compression flag, wrong length, not base58, zero key
+        payload[0] = WIF_PREFIX_MAINNET;
+        payload[33] = 0x02;
+        let invalid_key = |wif: &str| {
+            matches!(
+                GovernanceKeypair::from_wif(wif),
+                Err(GovernanceError::InvalidKey(_))
+            )
+        };
+        assert!(invalid_key(&base58::encode_check(&payload)));
+        assert!(invalid_key(&base58::encode_check(&payload[..20])));
+        assert!(invalid_key("0OIl"));
+        let mut zero = vec![WIF_PREFIX_MAINNET];
+        zero.extend_from_slice(&[0; 32]);
+        assert!(invalid_key(&base58::encode_check(&zero)));
+
+        // Errors do not echo the key
+        let err = GovernanceKeypair::from_wif(&tampered).unwrap_err();
+        assert!(!format!("{} {:?}", err, err).contains("nhXK"));
+    }
+
+    #[test]
+    fn test_from_mnemonic_errors() {
+        let invalid_key = |phrase: &str| {
+            matches!(
+                GovernanceKeypair::from_mnemonic(phrase, ""),
+                Err(GovernanceError::InvalidKey(_))
+            )
+        };
+        // Bad checksum word
+        assert!(invalid_key(&ZERO_MNEMONIC.replace("about", "abandon")));
+        // Unknown word and wrong length
+        assert!(invalid_key(&ZERO_MNEMONIC.replace("about", "bitcoinx")));
+        assert!(invalid_key("abandon abandon about"));
+
+        let random = GovernanceKeypair::generate().unwrap();
+        assert!(matches!(
+            random.to_mnemonic(),
+            Err(GovernanceError::InvalidKey(_))
+        ));
+
+        // The phrase stays out of Debug output
+        let keypair = GovernanceKeypair::from_mnemonic(ZERO_MNEMONIC, "").unwrap();
+        assert!(!format!("{:?}", keypair).contains("abandon"));
+    }
 }
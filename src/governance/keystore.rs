@@ -0,0 +1,566 @@
+//! # Keystores
+//!
+//! A key file with metadata: a label, the public key, when it was created,
+//! optionally the team the key signs for, and the secret key, in hex or
+//! encrypted under a passphrase
+//! ([`GovernanceKeypair::to_encrypted_json`](crate::governance::GovernanceKeypair::to_encrypted_json)):
+//!
+//! ```json
+//! {
+//!   "format_version": 1,
+//!   "label": "alice",
+//!   "public_key": "02...",
+//!   "created_at": "2024-01-01T00:00:00Z",
+//!   "team_id": "core",
+//!   "secret": { "plain": "<hex>" }
+//! }
+//! ```
+//!
+//! `secret` is `{"encrypted": {...}}` for an encrypted key. Key files of
+//! the earlier format (a bare `secret_key`, or a bare encrypted envelope)
+//! are read by [`Keystore::from_legacy_json`] and
+//! [`Keystore::load_or_migrate`].
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::keys::is_encrypted_key_json;
+#[cfg(any(feature = "signing", test))]
+use crate::governance::GovernanceKeypair;
+use crate::governance::PublicKey;
+use crate::util::fs::write_atomic_with;
+use crate::util::Redacted;
+
+/// Keystore format written by this version
+pub const KEYSTORE_FORMAT_VERSION: u32 = 1;
+
+/// Fields every keystore has
+const REQUIRED_FIELDS: [&str; 5] = [
+    "format_version",
+    "label",
+    "public_key",
+    "created_at",
+    "secret",
+];
+
+/// A key file with its metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keystore {
+    pub format_version: u32,
+    /// Name the key holder gave the key
+    pub label: String,
+    pub public_key: PublicKey,
+    pub created_at: DateTime<Utc>,
+    /// Team whose multisig slot the key fills
+    pub team_id: Option<String>,
+    pub secret: KeystoreSecret,
+}
+
+/// The secret key of a keystore
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeystoreSecret {
+    /// Hex of the 32-byte secret key
+    Plain(Redacted<String>),
+    /// A passphrase-encrypted key envelope
+    Encrypted(Value),
+}
+
+impl Keystore {
+    /// A keystore holding the keypair's secret key in hex
+    #[cfg(any(feature = "signing", test))]
+    pub fn new(keypair: &GovernanceKeypair, label: &str) -> Self {
+        Self {
+            format_version: KEYSTORE_FORMAT_VERSION,
+            label: label.to_string(),
+            public_key: keypair.public_key(),
+            created_at: Utc::now(),
+            team_id: None,
+            secret: KeystoreSecret::Plain(Redacted::new(hex::encode(keypair.secret_key_bytes()))),
+        }
+    }
+
+    /// A keystore holding the keypair encrypted under `passphrase`
+    #[cfg(any(feature = "signing", test))]
+    pub fn encrypted(
+        keypair: &GovernanceKeypair,
+        label: &str,
+        passphrase: &str,
+    ) -> GovernanceResult<Self> {
+        let envelope = serde_json::from_str(&keypair.to_encrypted_json(passphrase)?)
+            .map_err(|e| GovernanceError::Serialization(e.to_string()))?;
+        Ok(Self {
+            secret: KeystoreSecret::Encrypted(envelope),
+            ..Self::new(keypair, label)
+        })
+    }
+
+    /// Assign the key to a team
+    pub fn with_team(mut self, team_id: &str) -> Self {
+        self.team_id = Some(team_id.to_string());
+        self
+    }
+
+    /// Whether the secret key is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self.secret, KeystoreSecret::Encrypted(_))
+    }
+
+    /// The keypair, decrypting it with `passphrase` if it is encrypted
+    #[cfg(any(feature = "signing", test))]
+    pub fn keypair(&self, passphrase: Option<&str>) -> GovernanceResult<GovernanceKeypair> {
+        let keypair = match &self.secret {
+            KeystoreSecret::Plain(secret_key) => {
+                let bytes = zeroize::Zeroizing::new(
+                    hex::decode(secret_key.expose()).map_err(|_| invalid("bad `secret`"))?,
+                );
+                GovernanceKeypair::from_secret_key(&bytes)?
+            }
+            KeystoreSecret::Encrypted(envelope) => {
+                let passphrase = passphrase.ok_or_else(|| {
+                    GovernanceError::InvalidKey(
+                        "Keystore is encrypted: a passphrase is needed".to_string(),
+                    )
+                })?;
+                GovernanceKeypair::from_encrypted_json(&envelope.to_string(), passphrase)?
+            }
+        };
+        if keypair.public_key() != self.public_key {
+            return Err(invalid("secret key does not match `public_key`"));
+        }
+        Ok(keypair)
+    }
+
+    /// Parse a keystore, naming the first missing or invalid field
+    pub fn from_json(json: &str) -> GovernanceResult<Self> {
+        let value: Value = serde_json::from_str(json).map_err(|_| invalid("not a JSON object"))?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| invalid("not a JSON object"))?;
+        if let Some(missing) = REQUIRED_FIELDS
+            .iter()
+            .find(|field| !object.contains_key(**field))
+        {
+            return Err(invalid(&format!("missing field `{}`", missing)));
+        }
+
+        let format_version: u32 = field(object, "format_version")?;
+        if format_version != KEYSTORE_FORMAT_VERSION {
+            return Err(invalid(&format!(
+                "unsupported format version {}",
+                format_version
+            )));
+        }
+        let public_key: PublicKey = field(object, "public_key")?;
+        let created_at: String = field(object, "created_at")?;
+        let created_at = DateTime::parse_from_rfc3339(&created_at)
+            .map_err(|_| invalid("bad `created_at`: expected an RFC 3339 time"))?
+            .with_timezone(&Utc);
+        let team_id = match object.get("team_id") {
+            None | Some(Value::Null) => None,
+            Some(_) => Some(field(object, "team_id")?),
+        };
+
+        let secret = match object.get("secret").and_then(Value::as_object) {
+            Some(secret) if secret.len() == 1 && secret.contains_key("plain") => {
+                let secret_key: Redacted<String> = field(secret, "plain")
+                    .map_err(|_| invalid("bad `secret.plain`: expected hex"))?;
+                let derived = public_key_of(secret_key.expose())
+                    .ok_or_else(|| invalid("bad `secret.plain`: expected a hex secret key"))?;
+                if derived != public_key {
+                    return Err(invalid("secret key does not match `public_key`"));
+                }
+                KeystoreSecret::Plain(secret_key)
+            }
+            Some(secret) if secret.len() == 1 && secret.contains_key("encrypted") => {
+                let envelope = &secret["encrypted"];
+                if !is_encrypted_key_json(&envelope.to_string()) {
+                    return Err(invalid("bad `secret.encrypted`: not an encrypted key"));
+                }
+                if envelope.get("public_key").and_then(Value::as_str)
+                    != Some(public_key.to_string().as_str())
+                {
+                    return Err(invalid("`secret.encrypted` is for another public key"));
+                }
+                KeystoreSecret::Encrypted(envelope.clone())
+            }
+            _ => {
+                return Err(invalid(
+                    "bad `secret`: expected {\"plain\": ...} or {\"encrypted\": ...}",
+                ))
+            }
+        };
+
+        Ok(Self {
+            format_version,
+            label: field(object, "label")?,
+            public_key,
+            created_at,
+            team_id,
+            secret,
+        })
+    }
+
+    /// Read a key file of the earlier format: a plaintext `secret_key`
+    /// (with optional `public_key` and `created_at`) or an encrypted
+    /// envelope
+    ///
+    /// The key gets `label`; a file without `created_at` is dated now.
+    pub fn from_legacy_json(json: &str, label: &str) -> GovernanceResult<Self> {
+        let invalid = |reason: &str| {
+            GovernanceError::InvalidKey(format!("Invalid legacy key file: {}", reason))
+        };
+        let value: Value = serde_json::from_str(json).map_err(|_| invalid("not a JSON object"))?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| invalid("not a JSON object"))?;
+        let created_at = object
+            .get("created_at")
+            .and_then(Value::as_str)
+            .and_then(|created_at| DateTime::parse_from_rfc3339(created_at).ok())
+            .map_or_else(Utc::now, |created_at| created_at.with_timezone(&Utc));
+
+        let (public_key, secret) = if is_encrypted_key_json(json) {
+            let public_key = object
+                .get("public_key")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("missing field `public_key`"))?
+                .parse()
+                .map_err(|_| invalid("bad `public_key`"))?;
+            (public_key, KeystoreSecret::Encrypted(value.clone()))
+        } else {
+            let secret_key: Redacted<String> = object
+                .get("secret_key")
+                .ok_or_else(|| invalid("missing field `secret_key`"))
+                .and_then(|secret_key| {
+                    serde_json::from_value(secret_key.clone())
+                        .map_err(|_| invalid("bad `secret_key`"))
+                })?;
+            let public_key = public_key_of(secret_key.expose())
+                .ok_or_else(|| invalid("bad `secret_key`: expected 32 bytes of hex"))?;
+            if let Some(given) = object.get("public_key").and_then(Value::as_str) {
+                if given.parse::<PublicKey>().ok().as_ref() != Some(&public_key) {
+                    return Err(invalid("secret key does not match `public_key`"));
+                }
+            }
+            (public_key, KeystoreSecret::Plain(secret_key))
+        };
+
+        Ok(Self {
+            format_version: KEYSTORE_FORMAT_VERSION,
+            label: label.to_string(),
+            public_key,
+            created_at,
+            team_id: None,
+            secret,
+        })
+    }
+
+    /// The keystore as pretty-printed JSON
+    ///
+    /// Holds the secret key: write it only to files its owner alone can
+    /// read, as [`save`](Self::save) does.
+    pub fn to_json(&self) -> String {
+        let mut object = Map::new();
+        object.insert("format_version".into(), self.format_version.into());
+        object.insert("label".into(), self.label.clone().into());
+        object.insert("public_key".into(), self.public_key.to_string().into());
+        object.insert(
+            "created_at".into(),
+            self.created_at
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+                .into(),
+        );
+        if let Some(team_id) = &self.team_id {
+            object.insert("team_id".into(), team_id.clone().into());
+        }
+        let secret = match &self.secret {
+            KeystoreSecret::Plain(secret_key) => ("plain", secret_key.expose().clone().into()),
+            KeystoreSecret::Encrypted(envelope) => ("encrypted", envelope.clone()),
+        };
+        object.insert(
+            "secret".into(),
+            Value::Object(Map::from_iter([(secret.0.to_string(), secret.1)])),
+        );
+        serde_json::to_string_pretty(&Value::Object(object))
+            .expect("a JSON value always serializes")
+    }
+
+    /// Read a keystore file
+    pub fn load(path: impl AsRef<Path>) -> GovernanceResult<Self> {
+        Self::from_json(&read(path.as_ref())?)
+    }
+
+    /// Read a keystore file, or a key file of the earlier format labelled
+    /// with its file name
+    ///
+    /// Returns whether the file was of the earlier format, so callers can
+    /// ask for it to be migrated.
+    pub fn load_or_migrate(path: impl AsRef<Path>) -> GovernanceResult<(Self, bool)> {
+        let path = path.as_ref();
+        let json = read(path)?;
+        if is_keystore_json(&json) {
+            return Ok((Self::from_json(&json)?, false));
+        }
+        let label = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .and_then(|name| name.split('.').next().map(str::to_string))
+            .unwrap_or_default();
+        Ok((Self::from_legacy_json(&json, &label)?, true))
+    }
+
+    /// Write the keystore, replacing `path` atomically; on Unix the file
+    /// is readable by its owner only
+    pub fn save(&self, path: impl AsRef<Path>) -> GovernanceResult<()> {
+        let json = zeroize::Zeroizing::new(self.to_json());
+        write_atomic_with(path.as_ref(), |file| {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+            }
+            std::io::Write::write_all(file, json.as_bytes())
+        })
+        .map_err(|e| {
+            GovernanceError::InvalidInput(format!(
+                "Cannot write keystore {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Whether a key file is a keystore rather than of the earlier format
+pub fn is_keystore_json(json: &str) -> bool {
+    serde_json::from_str::<Value>(json).is_ok_and(|value| value.get("format_version").is_some())
+}
+
+fn invalid(reason: &str) -> GovernanceError {
+    GovernanceError::InvalidKey(format!("Invalid keystore: {}", reason))
+}
+
+/// A field of a keystore object; errors name the field, never its value
+fn field<T: DeserializeOwned>(object: &Map<String, Value>, name: &str) -> GovernanceResult<T> {
+    serde_json::from_value(object[name].clone()).map_err(|_| invalid(&format!("bad `{}`", name)))
+}
+
+fn read(path: &Path) -> GovernanceResult<String> {
+    std::fs::read_to_string(path).map_err(|e| {
+        GovernanceError::InvalidInput(format!("Cannot read keystore {}: {}", path.display(), e))
+    })
+}
+
+/// Public key of a hex secret key
+fn public_key_of(secret_hex: &str) -> Option<PublicKey> {
+    let bytes = zeroize::Zeroizing::new(hex::decode(secret_hex).ok()?);
+    let secret_key = secp256k1::SecretKey::from_slice(&bytes).ok()?;
+    Some(PublicKey {
+        inner: secret_key.public_key(secp256k1::SECP256K1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn keypair() -> GovernanceKeypair {
+        GovernanceKeypair::from_secret_key(&[7; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let keypair = keypair();
+        let keystore = Keystore::new(&keypair, "alice").with_team("core");
+        let json = keystore.to_json();
+        assert!(is_keystore_json(&json));
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format_version"], 1);
+        assert_eq!(value["team_id"], "core");
+        assert_eq!(
+            value["secret"]["plain"],
+            hex::encode(keypair.secret_key_bytes())
+        );
+
+        let parsed = Keystore::from_json(&json).unwrap();
+        assert_eq!(parsed.label, "alice");
+        assert_eq!(parsed.team_id.as_deref(), Some("core"));
+        assert_eq!(
+            parsed.created_at.timestamp(),
+            keystore.created_at.timestamp()
+        );
+        assert!(!parsed.is_encrypted());
+        assert_eq!(
+            parsed.keypair(None).unwrap().public_key(),
+            keypair.public_key()
+        );
+
+        // Debug never shows the secret
+        assert!(!format!("{:?}", parsed).contains(&hex::encode(keypair.secret_key_bytes())));
+    }
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let keypair = keypair();
+        let keystore = Keystore::encrypted(&keypair, "alice", "hunter2").unwrap();
+        let json = keystore.to_json();
+        assert!(!json.contains(&hex::encode(keypair.secret_key_bytes())));
+
+        let parsed = Keystore::from_json(&json).unwrap();
+        assert!(parsed.is_encrypted());
+        assert!(parsed.team_id.is_none());
+        assert_eq!(
+            parsed.keypair(Some("hunter2")).unwrap().public_key(),
+            keypair.public_key()
+        );
+        assert!(parsed.keypair(Some("hunter3")).is_err());
+        assert!(parsed.keypair(None).is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("alice.keystore.json");
+        let keystore = Keystore::new(&keypair(), "alice");
+        keystore.save(&path).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let loaded = Keystore::load(&path).unwrap();
+        assert_eq!(loaded.public_key, keystore.public_key);
+        assert_eq!(loaded.secret, keystore.secret);
+        assert!(!Keystore::load_or_migrate(&path).unwrap().1);
+
+        assert!(matches!(
+            Keystore::load(dir.path().join("missing.json")),
+            Err(GovernanceError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_migrate_legacy_files() {
+        let keypair = keypair();
+        let secret_hex = hex::encode(keypair.secret_key_bytes());
+        let dir = TempDir::new().unwrap();
+
+        // What blvm-keygen writes
+        let legacy = serde_json::json!({
+            "public_key": keypair.public_key().to_string(),
+            "secret_key": secret_hex,
+            "created_at": "2024-01-02T03:04:05Z",
+        });
+        let path = dir.path().join("alice.key.json");
+        std::fs::write(&path, legacy.to_string()).unwrap();
+        let (keystore, migrated) = Keystore::load_or_migrate(&path).unwrap();
+        assert!(migrated);
+        assert_eq!(keystore.label, "alice");
+        assert_eq!(keystore.public_key, keypair.public_key());
+        assert_eq!(
+            keystore.created_at.to_rfc3339(),
+            "2024-01-02T03:04:05+00:00"
+        );
+
+        // Migrating writes a keystore with the same key
+        let migrated_path = dir.path().join("alice.keystore.json");
+        keystore.save(&migrated_path).unwrap();
+        let (reloaded, migrated) = Keystore::load_or_migrate(&migrated_path).unwrap();
+        assert!(!migrated);
+        assert_eq!(
+            reloaded.keypair(None).unwrap().public_key(),
+            keypair.public_key()
+        );
+
+        // A bare secret key is enough
+        let bare = serde_json::json!({ "secret_key": secret_hex }).to_string();
+        let keystore = Keystore::from_legacy_json(&bare, "bare").unwrap();
+        assert_eq!(keystore.public_key, keypair.public_key());
+
+        // Encrypted key files keep their envelope
+        let encrypted = keypair.to_encrypted_json("hunter2").unwrap();
+        let keystore = Keystore::from_legacy_json(&encrypted, "alice").unwrap();
+        assert!(keystore.is_encrypted());
+        let reparsed = Keystore::from_json(&keystore.to_json()).unwrap();
+        assert_eq!(
+            reparsed.keypair(Some("hunter2")).unwrap().public_key(),
+            keypair.public_key()
+        );
+
+        let other = GovernanceKeypair::from_secret_key(&[8; 32]).unwrap();
+        let mismatched = serde_json::json!({
+            "public_key": other.public_key().to_string(),
+            "secret_key": secret_hex,
+        });
+        let err = Keystore::from_legacy_json(&mismatched.to_string(), "alice").unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{}", err);
+        let err = Keystore::from_legacy_json("{}", "alice").unwrap_err();
+        assert!(
+            err.to_string().contains("missing field `secret_key`"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_schema_errors_name_the_field() {
+        let keypair = keypair();
+        let secret_hex = hex::encode(keypair.secret_key_bytes());
+        let valid: Value =
+            serde_json::from_str(&Keystore::new(&keypair, "alice").to_json()).unwrap();
+
+        for field in REQUIRED_FIELDS {
+            let mut value = valid.clone();
+            value.as_object_mut().unwrap().remove(field);
+            let err = Keystore::from_json(&value.to_string()).unwrap_err();
+            assert!(matches!(err, GovernanceError::InvalidKey(_)));
+            assert!(
+                err.to_string()
+                    .contains(&format!("missing field `{}`", field)),
+                "{}",
+                err
+            );
+        }
+
+        let other = GovernanceKeypair::from_secret_key(&[8; 32]).unwrap();
+        let cases = [
+            (
+                "format_version",
+                Value::from(2),
+                "unsupported format version 2",
+            ),
+            ("label", Value::from(5), "bad `label`"),
+            ("public_key", Value::from("02zz"), "bad `public_key`"),
+            ("created_at", Value::from("yesterday"), "bad `created_at`"),
+            ("team_id", Value::from(false), "bad `team_id`"),
+            (
+                "public_key",
+                Value::from(other.public_key().to_string()),
+                "does not match",
+            ),
+            ("secret", Value::from(secret_hex.clone()), "bad `secret`"),
+            (
+                "secret",
+                serde_json::json!({ "plain": "abcd" }),
+                "bad `secret.plain`",
+            ),
+        ];
+        for (field, replacement, reason) in cases {
+            let mut value = valid.clone();
+            value[field] = replacement;
+            let err = Keystore::from_json(&value.to_string()).unwrap_err();
+            assert!(err.to_string().contains(reason), "{}: {}", field, err);
+            assert!(!err.to_string().contains(&secret_hex));
+        }
+
+        let legacy = serde_json::json!({ "secret_key": secret_hex }).to_string();
+        assert!(!is_keystore_json(&legacy));
+        let err = Keystore::from_json(&legacy).unwrap_err();
+        assert!(err.to_string().contains("missing field `format_version`"));
+    }
+}
@@ -0,0 +1,455 @@
+//! # Release Manifests
+//!
+//! A manifest lists the SHA256 hash of every file in a release so that one
+//! signature covers all of them.
+//!
+//! The canonical form is `SHA256SUMS` format: one `<hash>  <path>` line per
+//! file, sorted by path, with `/` separators. Maintainers sign the manifest
+//! as a checksums file (`checksums:<digest>[:version]`, where `<digest>` is the
+//! SHA256 of the canonical form), so a manifest signature also verifies with
+//! the checksums tools.
+
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::messages::SigningTarget;
+use crate::util::parallel::{hash_files, CancelToken, Limits, Report};
+
+/// File paths and their SHA256 hashes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    entries: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Create an empty manifest
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace an entry
+    ///
+    /// Paths are normalized (no leading `./` or `/`) and hashes lowercased.
+    pub fn insert(&mut self, path: &str, hash: &str) {
+        self.entries
+            .insert(normalize_path(path), hash.to_ascii_lowercase());
+    }
+
+    /// Entries by path
+    pub fn entries(&self) -> &BTreeMap<String, String> {
+        &self.entries
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the manifest has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Hash the given files, recording their paths relative to `base`
+    pub fn from_files<P: AsRef<Path>>(base: &Path, files: &[P]) -> GovernanceResult<Self> {
+        Self::from_files_with(base, files, &Limits::default(), &CancelToken::new())
+            .map(|(manifest, _)| manifest)
+    }
+
+    /// Hash the given files in parallel under `limits`
+    ///
+    /// Returns the manifest and the per-file timing report. Cancellation
+    /// stops hashing and returns an error.
+    pub fn from_files_with<P: AsRef<Path>>(
+        base: &Path,
+        files: &[P],
+        limits: &Limits,
+        cancel: &CancelToken,
+    ) -> GovernanceResult<(Self, Report<()>)> {
+        let paths: Vec<PathBuf> = files
+            .iter()
+            .map(|file| file.as_ref().to_path_buf())
+            .collect();
+        let (hashes, report) = hash_files(&paths, limits, cancel).map_err(hash_error)?;
+
+        let mut manifest = Self::new();
+        for (file, hash) in paths.iter().zip(hashes) {
+            let relative = file.strip_prefix(base).unwrap_or(file);
+            manifest.insert(&manifest_path(relative)?, &hash);
+        }
+        Ok((manifest, report))
+    }
+
+    /// Hash every file under a directory, recursively
+    ///
+    /// Paths in `exclude` (for example the manifest and signature being
+    /// written into the directory) are skipped.
+    pub fn from_dir<P: AsRef<Path>>(dir: &Path, exclude: &[P]) -> GovernanceResult<Self> {
+        Self::from_dir_with(dir, exclude, &Limits::default(), &CancelToken::new())
+            .map(|(manifest, _)| manifest)
+    }
+
+    /// Hash every file under a directory in parallel under `limits`
+    pub fn from_dir_with<P: AsRef<Path>>(
+        dir: &Path,
+        exclude: &[P],
+        limits: &Limits,
+        cancel: &CancelToken,
+    ) -> GovernanceResult<(Self, Report<()>)> {
+        let exclude: Vec<PathBuf> = exclude
+            .iter()
+            .filter_map(|path| path.as_ref().canonicalize().ok())
+            .collect();
+        let mut files = Vec::new();
+        collect_files(dir, &mut files)?;
+        files.retain(|file| {
+            file.canonicalize()
+                .map_or(true, |file| !exclude.contains(&file))
+        });
+        Self::from_files_with(dir, &files, limits, cancel)
+    }
+
+    /// Parse `SHA256SUMS` format (`<hash>  <path>` or `<hash> *<path>`)
+    ///
+    /// Paths with a `..` component are rejected.
+    pub fn parse(text: &str) -> GovernanceResult<Self> {
+        let mut manifest = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed = line.split_once(' ').and_then(|(hash, path)| {
+                let path = path.strip_prefix([' ', '*'])?;
+                let valid_hash = hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+                (valid_hash && !path.is_empty()).then_some((hash, path))
+            });
+            let invalid_line = || {
+                GovernanceError::MessageFormat(format!(
+                    "Invalid manifest line {}: {}",
+                    number + 1,
+                    line
+                ))
+            };
+            let (hash, path) = parsed.ok_or_else(invalid_line)?;
+            // Listed paths are joined onto the base directory when verifying
+            let path =
+                manifest_path(Path::new(&normalize_path(path))).map_err(|_| invalid_line())?;
+            manifest.insert(&path, hash);
+        }
+        Ok(manifest)
+    }
+
+    /// Canonical `SHA256SUMS` text
+    pub fn to_sums(&self) -> String {
+        self.entries
+            .iter()
+            .map(|(path, hash)| format!("{}  {}\n", hash, path))
+            .collect()
+    }
+
+    /// SHA256 of the canonical text
+    pub fn digest(&self) -> String {
+        hex::encode(Sha256::digest(self.to_sums().as_bytes()))
+    }
+
+    /// The signed message: `checksums:<digest>[:version]`
+    pub fn signing_bytes(&self, version: Option<&str>) -> Vec<u8> {
+        SigningTarget::Checksums {
+            file_hash: self.digest(),
+            version: version.map(str::to_string),
+        }
+        .to_signing_bytes()
+    }
+
+    /// Compare actual file hashes against the manifest
+    ///
+    /// `exclude` is ignored on both sides (an embedded signature file cannot
+    /// list its own hash).
+    pub fn compare(
+        &self,
+        actual: &BTreeMap<String, String>,
+        exclude: Option<&str>,
+    ) -> Vec<MemberMismatch> {
+        let exclude = exclude.map(normalize_path);
+        let excluded = |path: &String| exclude.as_ref() == Some(path);
+        let mut mismatches = Vec::new();
+
+        for (path, expected) in self.entries.iter().filter(|(path, _)| !excluded(path)) {
+            match actual.get(path) {
+                Some(hash) if hash.eq_ignore_ascii_case(expected) => {}
+                Some(hash) => mismatches.push(MemberMismatch::Modified {
+                    path: path.clone(),
+                    expected: expected.clone(),
+                    actual: hash.clone(),
+                }),
+                None => mismatches.push(MemberMismatch::Missing(path.clone())),
+            }
+        }
+        for path in actual.keys() {
+            if !excluded(path) && !self.entries.contains_key(path) {
+                mismatches.push(MemberMismatch::Unlisted(path.clone()));
+            }
+        }
+
+        mismatches
+    }
+
+    /// Check the listed files under `base`
+    ///
+    /// Only listed files are hashed; other files in the directory (such as
+    /// the manifest and its signature) are not reported.
+    pub fn verify_files(&self, base: &Path) -> GovernanceResult<Vec<MemberMismatch>> {
+        self.verify_files_with(base, &Limits::default(), &CancelToken::new())
+            .map(|(mismatches, _)| mismatches)
+    }
+
+    /// Check the listed files under `base`, hashing in parallel under
+    /// `limits`
+    ///
+    /// Returns the mismatches and the per-file timing report.
+    pub fn verify_files_with(
+        &self,
+        base: &Path,
+        limits: &Limits,
+        cancel: &CancelToken,
+    ) -> GovernanceResult<(Vec<MemberMismatch>, Report<()>)> {
+        let present: Vec<&String> = self
+            .entries
+            .keys()
+            .filter(|path| base.join(path).is_file())
+            .collect();
+        let files: Vec<PathBuf> = present.iter().map(|path| base.join(path)).collect();
+        let (hashes, report) = hash_files(&files, limits, cancel).map_err(hash_error)?;
+
+        let actual = present.into_iter().cloned().zip(hashes).collect();
+        Ok((self.compare(&actual, None), report))
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_sums())
+    }
+}
+
+/// A difference between a manifest and the files it describes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemberMismatch {
+    /// The file's hash differs from the manifest
+    Modified {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    /// Listed in the manifest but absent
+    Missing(String),
+    /// Present but not listed in the manifest
+    Unlisted(String),
+}
+
+impl fmt::Display for MemberMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemberMismatch::Modified {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{}: manifest records {}, file hashes to {}",
+                path, expected, actual
+            ),
+            MemberMismatch::Missing(path) => write!(f, "{}: listed in manifest but missing", path),
+            MemberMismatch::Unlisted(path) => write!(f, "{}: not listed in manifest", path),
+        }
+    }
+}
+
+/// SHA256 of everything a reader yields, streamed
+pub(crate) fn hash_reader(reader: &mut impl Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(reader, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Path without a leading `./` or `/`
+pub(crate) fn normalize_path(path: &str) -> String {
+    let mut path = path;
+    loop {
+        if let Some(rest) = path.strip_prefix("./") {
+            path = rest;
+        } else if let Some(rest) = path.strip_prefix('/') {
+            path = rest;
+        } else {
+            return path.to_string();
+        }
+    }
+}
+
+fn hash_error(error: io::Error) -> GovernanceError {
+    GovernanceError::InvalidInput(format!("Failed to hash files: {}", error))
+}
+
+/// Relative path with `/` separators
+///
+/// A line break in a name would inject a line into the `SHA256SUMS` form,
+/// so such names are rejected.
+fn manifest_path(path: &Path) -> GovernanceResult<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => {
+                let part = part.to_string_lossy();
+                if part.contains(['\n', '\r']) {
+                    return Err(GovernanceError::InvalidInput(format!(
+                        "Manifest paths cannot contain line breaks: {:?}",
+                        path
+                    )));
+                }
+                parts.push(part.into_owned());
+            }
+            Component::CurDir => {}
+            _ => {
+                return Err(GovernanceError::InvalidInput(format!(
+                    "Manifest paths must be relative and inside the base directory: {}",
+                    path.display()
+                )))
+            }
+        }
+    }
+    Ok(parts.join("/"))
+}
+
+/// Regular files under `dir`, recursively
+///
+/// Symlinks are not followed: a symlinked directory could leave the tree or
+/// loop, and a symlinked file is listed under a name it does not have, so
+/// either is an error.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> GovernanceResult<()> {
+    let read_error = |e: io::Error| {
+        GovernanceError::InvalidInput(format!("Failed to read {}: {}", dir.display(), e))
+    };
+    for entry in std::fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        let file_type = std::fs::symlink_metadata(&path)
+            .map_err(read_error)?
+            .file_type();
+        if file_type.is_symlink() {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Manifest directories cannot contain symlinks: {}",
+                path.display()
+            )));
+        } else if file_type.is_dir() {
+            collect_files(&path, files)?;
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const HASH_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+    #[test]
+    fn test_parse_round_trip() {
+        let manifest = Manifest::parse(&format!(
+            "{}  ./bin/node\n{} *README.md\n\n",
+            HASH_B,
+            HASH_A.to_uppercase()
+        ))
+        .unwrap();
+
+        assert_eq!(
+            manifest.to_sums(),
+            format!("{}  README.md\n{}  bin/node\n", HASH_A, HASH_B)
+        );
+        assert_eq!(Manifest::parse(&manifest.to_sums()).unwrap(), manifest);
+        assert!(Manifest::parse("not-a-hash  file\n").is_err());
+        assert!(Manifest::parse(&format!("{}\n", HASH_A)).is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut manifest = Manifest::new();
+        manifest.insert("bin/node", "aa");
+        manifest.insert("README", "bb");
+        manifest.insert("SIGS.json", "cc");
+
+        let actual: BTreeMap<String, String> =
+            [("bin/node", "AA"), ("README", "ff"), ("extra", "dd")]
+                .iter()
+                .map(|(path, hash)| (path.to_string(), hash.to_string()))
+                .collect();
+
+        assert_eq!(
+            manifest.compare(&actual, Some("./SIGS.json")),
+            vec![
+                MemberMismatch::Modified {
+                    path: "README".to_string(),
+                    expected: "bb".to_string(),
+                    actual: "ff".to_string(),
+                },
+                MemberMismatch::Unlisted("extra".to_string()),
+            ]
+        );
+        assert!(manifest
+            .compare(&actual, None)
+            .contains(&MemberMismatch::Missing("SIGS.json".to_string())));
+    }
+
+    #[test]
+    fn test_signing_bytes() {
+        let mut manifest = Manifest::new();
+        manifest.insert("a", HASH_A);
+        assert_eq!(
+            manifest.signing_bytes(Some("v1.0.0")),
+            format!("checksums:{}:v1.0.0", manifest.digest()).into_bytes()
+        );
+        assert_eq!(
+            manifest.digest(),
+            hex::encode(Sha256::digest(format!("{}  a\n", HASH_A)))
+        );
+    }
+
+    #[test]
+    fn test_paths_outside_base_rejected() {
+        assert!(manifest_path(Path::new("../secret")).is_err());
+        assert_eq!(manifest_path(Path::new("./bin/node")).unwrap(), "bin/node");
+        assert!(manifest_path(Path::new("evil\naaaa  bin/node")).is_err());
+        assert!(Manifest::parse(&format!("{}  bin/../../etc/passwd\n", HASH_A)).is_err());
+        assert_eq!(
+            Manifest::parse(&format!("{}  /bin/node\n", HASH_A))
+                .unwrap()
+                .entries()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["bin/node"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_not_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret"), b"secret").unwrap();
+        std::fs::write(dir.path().join("node"), b"node").unwrap();
+        assert_eq!(
+            Manifest::from_dir::<&Path>(dir.path(), &[]).unwrap().len(),
+            1
+        );
+
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("linked")).unwrap();
+        let err = Manifest::from_dir::<&Path>(dir.path(), &[]).unwrap_err();
+        assert!(err.to_string().contains("symlinks"), "{}", err);
+    }
+}
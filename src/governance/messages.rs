@@ -2,10 +2,19 @@
 //!
 //! Message formats for governance operations.
 
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use rand::rngs::OsRng;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Borrow;
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-// No error types needed for this module
+/// Stable identifier of a message, see [`GovernanceMessage::id`]
+pub type MessageId = [u8; 32];
 
 /// A governance message that can be signed
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -20,29 +29,418 @@ pub enum GovernanceMessage {
         module_name: String,
         version: String,
     },
+    /// A module approval bound to the SHA256 of the module binary
+    ModuleBinaryApproval {
+        module_name: String,
+        version: String,
+        binary_hash: String,
+    },
     /// A budget decision message
     BudgetDecision { amount: u64, purpose: String },
+    /// A module configuration update message
+    ConfigUpdate {
+        module_name: String,
+        config_hash: String,
+    },
+    /// Replace a maintainer's key, e.g. after it was compromised
+    KeyRotation {
+        old_public_key: String,
+        new_public_key: String,
+        reason: String,
+    },
+    /// An emergency decision
+    Emergency {
+        severity: EmergencySeverity,
+        description: String,
+        required_action: String,
+    },
+    /// Change a protocol parameter
+    ///
+    /// The `justification` is informational: it is not part of the signing
+    /// bytes, and [`from_signing_bytes`](Self::from_signing_bytes) leaves it
+    /// empty.
+    ConfigChange {
+        parameter: String,
+        old_value: String,
+        new_value: String,
+        justification: String,
+    },
+    /// Add, remove or suspend a team member
+    MemberUpdate {
+        action: MemberAction,
+        github: String,
+        public_key: String,
+        team_id: String,
+    },
+}
+
+/// How urgent an emergency is
+///
+/// Written in lowercase (`critical`, `high`, `medium`) in signed messages
+/// and when serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmergencySeverity {
+    Critical,
+    High,
+    Medium,
+}
+
+impl EmergencySeverity {
+    fn as_str(self) -> &'static str {
+        match self {
+            EmergencySeverity::Critical => "critical",
+            EmergencySeverity::High => "high",
+            EmergencySeverity::Medium => "medium",
+        }
+    }
+}
+
+impl fmt::Display for EmergencySeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EmergencySeverity {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            EmergencySeverity::Critical,
+            EmergencySeverity::High,
+            EmergencySeverity::Medium,
+        ]
+        .into_iter()
+        .find(|severity| severity.as_str() == s)
+        .ok_or_else(|| {
+            GovernanceError::InvalidInput(format!(
+                "Unknown emergency severity '{}': expected critical, high or medium",
+                s
+            ))
+        })
+    }
+}
+
+/// What a [`GovernanceMessage::MemberUpdate`] does to the member
+///
+/// Written in lowercase (`add`, `remove`, `suspend`) in signed messages and
+/// when serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberAction {
+    Add,
+    Remove,
+    Suspend,
+}
+
+impl MemberAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            MemberAction::Add => "add",
+            MemberAction::Remove => "remove",
+            MemberAction::Suspend => "suspend",
+        }
+    }
+}
+
+impl fmt::Display for MemberAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MemberAction {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            MemberAction::Add,
+            MemberAction::Remove,
+            MemberAction::Suspend,
+        ]
+        .into_iter()
+        .find(|action| action.as_str() == s)
+        .ok_or_else(|| {
+            GovernanceError::InvalidInput(format!(
+                "Unknown member action '{}': expected add, remove or suspend",
+                s
+            ))
+        })
+    }
 }
 
 impl GovernanceMessage {
+    /// Create a config update message for a module's new configuration
+    ///
+    /// The configuration is bound by the SHA256 of its JSON serialization.
+    pub fn config_update(module_name: &str, config: &serde_json::Value) -> Self {
+        let config_hash = hex::encode(Sha256::digest(config.to_string().as_bytes()));
+        GovernanceMessage::ConfigUpdate {
+            module_name: module_name.to_string(),
+            config_hash,
+        }
+    }
+
     /// Convert the message to bytes for signing
+    ///
+    /// A type tag and the fields, joined by [`join_fields`], so a `:` in a
+    /// field cannot shift it into the next one.
     pub fn to_signing_bytes(&self) -> Vec<u8> {
-        // Use a standardized format for signing
         match self {
             GovernanceMessage::Release {
                 version,
                 commit_hash,
-            } => format!("RELEASE:{}:{}", version, commit_hash).into_bytes(),
+            } => join_fields(&["RELEASE", version, commit_hash]),
             GovernanceMessage::ModuleApproval {
                 module_name,
                 version,
-            } => format!("MODULE:{}:{}", module_name, version).into_bytes(),
+            } => join_fields(&["MODULE", module_name, version]),
+            GovernanceMessage::ModuleBinaryApproval {
+                module_name,
+                version,
+                binary_hash,
+            } => join_fields(&["MODULE", module_name, version, binary_hash]),
             GovernanceMessage::BudgetDecision { amount, purpose } => {
-                format!("BUDGET:{}:{}", amount, purpose).into_bytes()
+                join_fields(&["BUDGET", &amount.to_string(), purpose])
             }
+            GovernanceMessage::ConfigUpdate {
+                module_name,
+                config_hash,
+            } => join_fields(&["CONFIG", module_name, config_hash]),
+            GovernanceMessage::KeyRotation {
+                old_public_key,
+                new_public_key,
+                reason,
+            } => join_fields(&["KEYROTATION", old_public_key, new_public_key, reason]),
+            GovernanceMessage::Emergency {
+                severity,
+                description,
+                required_action,
+            } => join_fields(&["EMERGENCY", severity.as_str(), description, required_action]),
+            GovernanceMessage::ConfigChange {
+                parameter,
+                old_value,
+                new_value,
+                ..
+            } => join_fields(&["CONFIG", parameter, old_value, new_value]),
+            GovernanceMessage::MemberUpdate {
+                action,
+                github,
+                public_key,
+                team_id,
+            } => join_fields(&["MEMBER", action.as_str(), github, public_key, team_id]),
         }
     }
 
+    /// Parse a message from its signing bytes
+    ///
+    /// The inverse of [`to_signing_bytes`](Self::to_signing_bytes). Only the
+    /// exact bytes of some message are accepted: anything else (invalid
+    /// UTF-8, an unknown tag, a wrong field count, a bad escape or a
+    /// non-canonical amount) is a `MessageFormat` error.
+    pub fn from_signing_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| GovernanceError::MessageFormat("Message is not UTF-8".to_string()))?;
+        let mut fields = split_fields(text)?;
+        let tag = fields.remove(0);
+
+        let mut fields = fields.into_iter();
+        let message = match (tag.as_str(), fields.len()) {
+            ("RELEASE", 2) => GovernanceMessage::Release {
+                version: fields.next().unwrap_or_default(),
+                commit_hash: fields.next().unwrap_or_default(),
+            },
+            ("MODULE", 2) => GovernanceMessage::ModuleApproval {
+                module_name: fields.next().unwrap_or_default(),
+                version: fields.next().unwrap_or_default(),
+            },
+            ("MODULE", 3) => GovernanceMessage::ModuleBinaryApproval {
+                module_name: fields.next().unwrap_or_default(),
+                version: fields.next().unwrap_or_default(),
+                binary_hash: fields.next().unwrap_or_default(),
+            },
+            ("BUDGET", 2) => {
+                let amount = fields.next().unwrap_or_default();
+                GovernanceMessage::BudgetDecision {
+                    amount: amount
+                        .parse()
+                        .ok()
+                        .filter(|parsed: &u64| parsed.to_string() == amount)
+                        .ok_or_else(|| {
+                            GovernanceError::MessageFormat(format!(
+                                "Invalid budget amount '{}'",
+                                amount
+                            ))
+                        })?,
+                    purpose: fields.next().unwrap_or_default(),
+                }
+            }
+            ("CONFIG", 2) => GovernanceMessage::ConfigUpdate {
+                module_name: fields.next().unwrap_or_default(),
+                config_hash: fields.next().unwrap_or_default(),
+            },
+            ("KEYROTATION", 3) => GovernanceMessage::KeyRotation {
+                old_public_key: fields.next().unwrap_or_default(),
+                new_public_key: fields.next().unwrap_or_default(),
+                reason: fields.next().unwrap_or_default(),
+            },
+            ("EMERGENCY", 3) => {
+                let severity = fields.next().unwrap_or_default();
+                GovernanceMessage::Emergency {
+                    severity: severity.parse().map_err(|_| {
+                        GovernanceError::MessageFormat(format!(
+                            "Invalid emergency severity '{}'",
+                            severity
+                        ))
+                    })?,
+                    description: fields.next().unwrap_or_default(),
+                    required_action: fields.next().unwrap_or_default(),
+                }
+            }
+            ("CONFIG", 3) => GovernanceMessage::ConfigChange {
+                parameter: fields.next().unwrap_or_default(),
+                old_value: fields.next().unwrap_or_default(),
+                new_value: fields.next().unwrap_or_default(),
+                justification: String::new(),
+            },
+            ("MEMBER", 4) => {
+                let action = fields.next().unwrap_or_default();
+                GovernanceMessage::MemberUpdate {
+                    action: action.parse().map_err(|_| {
+                        GovernanceError::MessageFormat(format!(
+                            "Invalid member action '{}'",
+                            action
+                        ))
+                    })?,
+                    github: fields.next().unwrap_or_default(),
+                    public_key: fields.next().unwrap_or_default(),
+                    team_id: fields.next().unwrap_or_default(),
+                }
+            }
+            (tag, count) => {
+                return Err(GovernanceError::MessageFormat(format!(
+                    "Unknown message type '{}' with {} fields",
+                    tag, count
+                )))
+            }
+        };
+        Ok(message)
+    }
+
+    /// Iterate over the message fields as `(name, value)` pairs
+    ///
+    /// The first pair is always `("type", <variant name>)`, followed by the
+    /// variant's fields in declaration order.
+    pub fn iter_fields(&self) -> impl Iterator<Item = (&'static str, String)> {
+        let fields = match self {
+            GovernanceMessage::Release {
+                version,
+                commit_hash,
+            } => vec![
+                ("type", "Release".to_string()),
+                ("version", version.clone()),
+                ("commit_hash", commit_hash.clone()),
+            ],
+            GovernanceMessage::ModuleApproval {
+                module_name,
+                version,
+            } => vec![
+                ("type", "ModuleApproval".to_string()),
+                ("module_name", module_name.clone()),
+                ("version", version.clone()),
+            ],
+            GovernanceMessage::ModuleBinaryApproval {
+                module_name,
+                version,
+                binary_hash,
+            } => vec![
+                ("type", "ModuleBinaryApproval".to_string()),
+                ("module_name", module_name.clone()),
+                ("version", version.clone()),
+                ("binary_hash", binary_hash.clone()),
+            ],
+            GovernanceMessage::BudgetDecision { amount, purpose } => vec![
+                ("type", "BudgetDecision".to_string()),
+                ("amount", amount.to_string()),
+                ("purpose", purpose.clone()),
+            ],
+            GovernanceMessage::ConfigUpdate {
+                module_name,
+                config_hash,
+            } => vec![
+                ("type", "ConfigUpdate".to_string()),
+                ("module_name", module_name.clone()),
+                ("config_hash", config_hash.clone()),
+            ],
+            GovernanceMessage::KeyRotation {
+                old_public_key,
+                new_public_key,
+                reason,
+            } => vec![
+                ("type", "KeyRotation".to_string()),
+                ("old_public_key", old_public_key.clone()),
+                ("new_public_key", new_public_key.clone()),
+                ("reason", reason.clone()),
+            ],
+            GovernanceMessage::Emergency {
+                severity,
+                description,
+                required_action,
+            } => vec![
+                ("type", "Emergency".to_string()),
+                ("severity", severity.to_string()),
+                ("description", description.clone()),
+                ("required_action", required_action.clone()),
+            ],
+            GovernanceMessage::ConfigChange {
+                parameter,
+                old_value,
+                new_value,
+                justification,
+            } => vec![
+                ("type", "ConfigChange".to_string()),
+                ("parameter", parameter.clone()),
+                ("old_value", old_value.clone()),
+                ("new_value", new_value.clone()),
+                ("justification", justification.clone()),
+            ],
+            GovernanceMessage::MemberUpdate {
+                action,
+                github,
+                public_key,
+                team_id,
+            } => vec![
+                ("type", "MemberUpdate".to_string()),
+                ("action", action.to_string()),
+                ("github", github.clone()),
+                ("public_key", public_key.clone()),
+                ("team_id", team_id.clone()),
+            ],
+        };
+
+        fields.into_iter()
+    }
+
+    /// Stable identifier of the message, usable as a database key
+    ///
+    /// SHA256 of the canonical encoding: `message-id` followed by the
+    /// `name=value` fields of [`iter_fields`](Self::iter_fields) sorted by
+    /// name, joined by [`join_fields`]. The encoding is injective, so
+    /// distinct messages have distinct ids.
+    pub fn id(&self) -> MessageId {
+        let mut fields: Vec<(&'static str, String)> = self.iter_fields().collect();
+        fields.sort_by_key(|(name, _)| *name);
+        let encoded: Vec<String> = std::iter::once("message-id".to_string())
+            .chain(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| format!("{}={}", name, value)),
+            )
+            .collect();
+        Sha256::digest(join_fields(&encoded)).into()
+    }
+
     /// Get a human-readable description of the message
     pub fn description(&self) -> String {
         match self {
@@ -58,13 +456,411 @@ impl GovernanceMessage {
             } => {
                 format!("Approve module {} version {}", module_name, version)
             }
+            GovernanceMessage::ModuleBinaryApproval {
+                module_name,
+                version,
+                binary_hash,
+            } => {
+                format!(
+                    "Approve module {} version {} (binary: {})",
+                    module_name, version, binary_hash
+                )
+            }
             GovernanceMessage::BudgetDecision { amount, purpose } => {
                 format!("Budget decision: {} satoshis for {}", amount, purpose)
             }
+            GovernanceMessage::ConfigUpdate {
+                module_name,
+                config_hash,
+            } => {
+                format!(
+                    "Update module {} configuration (hash: {})",
+                    module_name, config_hash
+                )
+            }
+            GovernanceMessage::KeyRotation {
+                old_public_key,
+                new_public_key,
+                reason,
+            } => {
+                format!(
+                    "Rotate key {} to {} ({})",
+                    old_public_key, new_public_key, reason
+                )
+            }
+            GovernanceMessage::Emergency {
+                severity,
+                description,
+                required_action,
+            } => {
+                format!(
+                    "Emergency ({}): {}; required action: {}",
+                    severity, description, required_action
+                )
+            }
+            GovernanceMessage::ConfigChange {
+                parameter,
+                old_value,
+                new_value,
+                ..
+            } => {
+                format!("Change {} from {} to {}", parameter, old_value, new_value)
+            }
+            GovernanceMessage::MemberUpdate {
+                action,
+                github,
+                team_id,
+                ..
+            } => {
+                let verb = match action {
+                    MemberAction::Add => "Add",
+                    MemberAction::Remove => "Remove",
+                    MemberAction::Suspend => "Suspend",
+                };
+                format!("{} member {} (team {})", verb, github, team_id)
+            }
         }
     }
 }
 
+/// A governance message bound to a random nonce and its creation time
+///
+/// A signature over a bare [`GovernanceMessage`] can be replayed: the same
+/// `Release` signs to the same bytes every time. Wrapped, each issue of the
+/// message signs to different bytes, and verifiers reject issues older than
+/// their freshness window. Within the window, a [`NonceRegistry`] rejects
+/// exact replays.
+///
+/// `T` is the message or a reference to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceMessage<T = GovernanceMessage> {
+    pub message: T,
+    pub nonce: [u8; 32],
+    /// Creation time, Unix seconds
+    pub timestamp: u64,
+}
+
+impl<T: Borrow<GovernanceMessage>> NonceMessage<T> {
+    /// Wrap `message` with a fresh nonce and the current time
+    pub fn new(message: T) -> Self {
+        Self {
+            message,
+            nonce: Self::generate_nonce(),
+            timestamp: unix_now(),
+        }
+    }
+
+    /// 32 random bytes from the operating system
+    pub fn generate_nonce() -> [u8; 32] {
+        let mut nonce = [0u8; 32];
+        OsRng.fill_bytes(&mut nonce);
+        nonce
+    }
+
+    /// Bytes to sign: the SHA256 of the message's signing bytes, the nonce
+    /// and the timestamp (8 bytes, big-endian)
+    ///
+    /// The nonce and timestamp have fixed widths, so the combination is
+    /// unambiguous.
+    pub fn to_signing_bytes(&self) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.message.borrow().to_signing_bytes());
+        hasher.update(self.nonce);
+        hasher.update(self.timestamp.to_be_bytes());
+        hasher.finalize().to_vec()
+    }
+
+    /// Whether the message was created at most `max_age_secs` ago
+    ///
+    /// A timestamp in the future is not fresh.
+    pub fn verify_freshness(&self, max_age_secs: u64) -> bool {
+        self.is_fresh_at(unix_now(), max_age_secs)
+    }
+
+    /// [`verify_freshness`](Self::verify_freshness) at `now` (Unix seconds)
+    pub fn is_fresh_at(&self, now: u64, max_age_secs: u64) -> bool {
+        now.checked_sub(self.timestamp)
+            .is_some_and(|age| age <= max_age_secs)
+    }
+}
+
+/// Nonces of accepted [`NonceMessage`]s, to reject a second use within the
+/// freshness window
+///
+/// Nonces older than the window are forgotten, since the messages carrying
+/// them are no longer fresh. At most `capacity` nonces are kept; when that
+/// many fresh messages were accepted, further ones are refused until the
+/// oldest age out, rather than forgetting a nonce that could still replay.
+/// Record a message only after its signature verified, so that forged
+/// messages cannot fill the registry.
+#[derive(Debug, Clone)]
+pub struct NonceRegistry {
+    max_age_secs: u64,
+    capacity: usize,
+    seen: HashSet<[u8; 32]>,
+    /// Nonces in the order they were recorded, with their timestamps
+    order: VecDeque<(u64, [u8; 32])>,
+}
+
+impl NonceRegistry {
+    /// Registry for messages at most `max_age_secs` old, holding at most
+    /// `capacity` nonces
+    pub fn new(max_age_secs: u64, capacity: usize) -> Self {
+        Self {
+            max_age_secs,
+            capacity,
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Accept `message` if it is fresh and its nonce was not seen, recording
+    /// the nonce
+    pub fn check<T: Borrow<GovernanceMessage>>(
+        &mut self,
+        message: &NonceMessage<T>,
+    ) -> GovernanceResult<()> {
+        self.check_at(message, unix_now())
+    }
+
+    /// [`check`](Self::check) at `now` (Unix seconds)
+    pub fn check_at<T: Borrow<GovernanceMessage>>(
+        &mut self,
+        message: &NonceMessage<T>,
+        now: u64,
+    ) -> GovernanceResult<()> {
+        if !message.is_fresh_at(now, self.max_age_secs) {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Message from {} is not within {}s of {}",
+                message.timestamp, self.max_age_secs, now
+            )));
+        }
+        self.expire(now);
+        if self.seen.contains(&message.nonce) {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Nonce {} was already used",
+                hex::encode(message.nonce)
+            )));
+        }
+        if self.seen.len() >= self.capacity {
+            return Err(GovernanceError::InvalidInput(format!(
+                "{} fresh nonces recorded, the registry's capacity",
+                self.capacity
+            )));
+        }
+        self.seen.insert(message.nonce);
+        self.order.push_back((message.timestamp, message.nonce));
+        Ok(())
+    }
+
+    /// Nonces recorded and still within the window
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Whether no nonce is recorded
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Forget nonces of messages no longer fresh at `now`
+    fn expire(&mut self, now: u64) {
+        let cutoff = now.saturating_sub(self.max_age_secs);
+        // Timestamps are not recorded in order, so expire by scanning
+        self.order.retain(|(timestamp, nonce)| {
+            let fresh = *timestamp >= cutoff;
+            if !fresh {
+                self.seen.remove(nonce);
+            }
+            fresh
+        });
+    }
+}
+
+/// Current time in Unix seconds; 0 if the clock is before 1970
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// A release artifact and the fields bound into its signature
+///
+/// This is the single builder for the messages signed by `blvm-sign-binary`
+/// and checked by `blvm-verify-binary` and `blvm-inspect`. Each message is
+/// `<type>:<required fields>[:<optional fields>]`, with absent optional
+/// fields left out and fields joined by [`join_fields`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningTarget {
+    /// `binary:<binary_type>:<file_hash>[:<version>][:<commit>]`
+    Binary {
+        binary_type: String,
+        file_hash: String,
+        version: Option<String>,
+        commit: Option<String>,
+    },
+    /// `bundle:<file_hash>[:<source_hash>][:<build_config_hash>][:<spec_hash>]`
+    Bundle {
+        file_hash: String,
+        source_hash: Option<String>,
+        build_config_hash: Option<String>,
+        spec_hash: Option<String>,
+    },
+    /// `checksums:<file_hash>[:<version>]`, also used for manifests
+    Checksums {
+        file_hash: String,
+        version: Option<String>,
+    },
+    /// `image:<repository>:<digest>[:<tag>]`
+    Image {
+        repository: String,
+        digest: String,
+        tag: Option<String>,
+    },
+}
+
+impl SigningTarget {
+    /// Rebuild the target from the `metadata` object of a signature file
+    ///
+    /// Returns `None` for an unknown `type` or a missing required field.
+    pub fn from_metadata(metadata: &serde_json::Value) -> Option<Self> {
+        let field = |name: &str| metadata.get(name).and_then(|v| v.as_str());
+        let optional = |name: &str| field(name).map(str::to_string);
+        let required = |name: &str| optional(name);
+
+        Some(match field("type")? {
+            "binary" => SigningTarget::Binary {
+                binary_type: required("binary_type")?,
+                file_hash: required("file_hash")?,
+                version: optional("version"),
+                commit: optional("commit"),
+            },
+            "bundle" => SigningTarget::Bundle {
+                file_hash: required("file_hash")?,
+                source_hash: optional("source_hash"),
+                build_config_hash: optional("build_config_hash"),
+                spec_hash: optional("spec_hash"),
+            },
+            "checksums" | "manifest" => SigningTarget::Checksums {
+                file_hash: required("file_hash")?,
+                version: optional("version"),
+            },
+            "image" => SigningTarget::Image {
+                repository: required("repository")?,
+                digest: required("digest")?,
+                tag: optional("tag"),
+            },
+            _ => return None,
+        })
+    }
+
+    /// Convert the target to bytes for signing
+    pub fn to_signing_bytes(&self) -> Vec<u8> {
+        match self {
+            SigningTarget::Binary {
+                binary_type,
+                file_hash,
+                version,
+                commit,
+            } => join_present(
+                &["binary", binary_type, file_hash],
+                &[version.as_deref(), commit.as_deref()],
+            ),
+            SigningTarget::Bundle {
+                file_hash,
+                source_hash,
+                build_config_hash,
+                spec_hash,
+            } => join_present(
+                &["bundle", file_hash],
+                &[
+                    source_hash.as_deref(),
+                    build_config_hash.as_deref(),
+                    spec_hash.as_deref(),
+                ],
+            ),
+            SigningTarget::Checksums { file_hash, version } => {
+                join_present(&["checksums", file_hash], &[version.as_deref()])
+            }
+            SigningTarget::Image {
+                repository,
+                digest,
+                tag,
+            } => {
+                // A digest is always `sha256:<hex>`, so it is kept verbatim
+                let mut bytes = join_fields(&["image", repository]);
+                bytes.push(b':');
+                bytes.extend_from_slice(digest.as_bytes());
+                if let Some(tag) = tag {
+                    bytes.push(b':');
+                    bytes.extend_from_slice(escape_field(tag).as_bytes());
+                }
+                bytes
+            }
+        }
+    }
+}
+
+/// Join the required fields and whichever optional fields are present
+fn join_present(required: &[&str], optional: &[Option<&str>]) -> Vec<u8> {
+    let fields: Vec<&str> = required
+        .iter()
+        .copied()
+        .chain(optional.iter().flatten().copied())
+        .collect();
+    join_fields(&fields)
+}
+
+/// Join signed message fields with `:`, escaping `%` and `:` in each field
+///
+/// A field holding a `:` (a registry port, say) would otherwise make two
+/// different messages join to the same bytes. Fields without either
+/// character are unchanged, so existing signatures over plain fields stay
+/// valid.
+pub fn join_fields<S: AsRef<str>>(fields: &[S]) -> Vec<u8> {
+    fields
+        .iter()
+        .map(|field| escape_field(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(":")
+        .into_bytes()
+}
+
+/// Escape `%` and `:` in one signed message field
+pub fn escape_field(field: &str) -> String {
+    field.replace('%', "%25").replace(':', "%3A")
+}
+
+/// Split signed message bytes into fields, undoing [`join_fields`]
+///
+/// Only the escapes [`escape_field`] writes are accepted, so every result
+/// joins back to the same text.
+fn split_fields(text: &str) -> GovernanceResult<Vec<String>> {
+    text.split(':').map(unescape_field).collect()
+}
+
+/// Undo [`escape_field`]
+fn unescape_field(field: &str) -> GovernanceResult<String> {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(percent) = rest.find('%') {
+        unescaped.push_str(&rest[..percent]);
+        let escape = rest.get(percent..percent + 3);
+        match escape {
+            Some("%25") => unescaped.push('%'),
+            Some("%3A") => unescaped.push(':'),
+            _ => {
+                return Err(GovernanceError::MessageFormat(
+                    "Invalid escape in message field".to_string(),
+                ))
+            }
+        }
+        rest = &rest[percent + 3..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
 impl fmt::Display for GovernanceMessage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.description())
@@ -74,6 +870,7 @@ impl fmt::Display for GovernanceMessage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_release_message() {
@@ -102,6 +899,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_module_binary_approval_message() {
+        let message = GovernanceMessage::ModuleBinaryApproval {
+            module_name: "lightning".to_string(),
+            version: "v2.0.0".to_string(),
+            binary_hash: "abc123".to_string(),
+        };
+
+        let bytes = message.to_signing_bytes();
+        assert_eq!(bytes, b"MODULE:lightning:v2.0.0:abc123");
+        assert_eq!(
+            message.description(),
+            "Approve module lightning version v2.0.0 (binary: abc123)"
+        );
+    }
+
+    #[test]
+    fn test_signing_target_messages() {
+        let binary = SigningTarget::Binary {
+            binary_type: "consensus".to_string(),
+            file_hash: "abc".to_string(),
+            version: Some("0.1.0".to_string()),
+            commit: None,
+        };
+        assert_eq!(binary.to_signing_bytes(), b"binary:consensus:abc:0.1.0");
+
+        let bundle = SigningTarget::Bundle {
+            file_hash: "abc".to_string(),
+            source_hash: None,
+            build_config_hash: Some("cfg".to_string()),
+            spec_hash: None,
+        };
+        assert_eq!(bundle.to_signing_bytes(), b"bundle:abc:cfg");
+
+        let image = SigningTarget::Image {
+            repository: "host:5000/repo".to_string(),
+            digest: "sha256:abc".to_string(),
+            tag: Some("v1".to_string()),
+        };
+        assert_eq!(
+            image.to_signing_bytes(),
+            b"image:host%3A5000/repo:sha256:abc:v1"
+        );
+    }
+
+    #[test]
+    fn test_signing_target_from_metadata() {
+        let metadata = serde_json::json!({
+            "type": "manifest",
+            "file_hash": "abc",
+            "version": "0.1.0",
+            "file_count": 3,
+        });
+        assert_eq!(
+            SigningTarget::from_metadata(&metadata),
+            Some(SigningTarget::Checksums {
+                file_hash: "abc".to_string(),
+                version: Some("0.1.0".to_string()),
+            })
+        );
+
+        let missing_hash = serde_json::json!({"type": "binary", "binary_type": "consensus"});
+        assert_eq!(SigningTarget::from_metadata(&missing_hash), None);
+        let unknown = serde_json::json!({"type": "other", "file_hash": "abc"});
+        assert_eq!(SigningTarget::from_metadata(&unknown), None);
+    }
+
     #[test]
     fn test_budget_decision_message() {
         let message = GovernanceMessage::BudgetDecision {
@@ -117,6 +981,523 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_config_update_message() {
+        let config = serde_json::json!({"port": 9735});
+        let message = GovernanceMessage::config_update("lightning", &config);
+
+        let hash = hex::encode(Sha256::digest(b"{\"port\":9735}"));
+        assert_eq!(
+            message.to_signing_bytes(),
+            format!("CONFIG:lightning:{}", hash).into_bytes()
+        );
+        assert_ne!(
+            message,
+            GovernanceMessage::config_update("lightning", &serde_json::json!({"port": 9736}))
+        );
+    }
+
+    #[test]
+    fn test_key_rotation_message() {
+        let message = GovernanceMessage::KeyRotation {
+            old_public_key: "02aa".to_string(),
+            new_public_key: "03bb".to_string(),
+            reason: "compromised".to_string(),
+        };
+
+        assert_eq!(
+            message.to_signing_bytes(),
+            b"KEYROTATION:02aa:03bb:compromised"
+        );
+        assert_eq!(
+            message.description(),
+            "Rotate key 02aa to 03bb (compromised)"
+        );
+        assert_eq!(message.to_string(), message.description());
+    }
+
+    #[test]
+    fn test_emergency_message() {
+        let message = GovernanceMessage::Emergency {
+            severity: EmergencySeverity::Critical,
+            description: "consensus bug".to_string(),
+            required_action: "halt: upgrade".to_string(),
+        };
+
+        assert_eq!(
+            message.to_signing_bytes(),
+            b"EMERGENCY:critical:consensus bug:halt%3A upgrade"
+        );
+        assert_eq!(
+            message.description(),
+            "Emergency (critical): consensus bug; required action: halt: upgrade"
+        );
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["Emergency"]["severity"], "critical");
+        assert_eq!(
+            serde_json::from_value::<GovernanceMessage>(json).unwrap(),
+            message
+        );
+
+        for severity in [
+            EmergencySeverity::Critical,
+            EmergencySeverity::High,
+            EmergencySeverity::Medium,
+        ] {
+            assert_eq!(
+                severity.to_string().parse::<EmergencySeverity>().unwrap(),
+                severity
+            );
+        }
+        assert!("Critical".parse::<EmergencySeverity>().is_err());
+    }
+
+    #[test]
+    fn test_nonce_message() {
+        let release = GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: "abc123".to_string(),
+        };
+        let first = NonceMessage::new(release.clone());
+        let second = NonceMessage::new(&release);
+        assert_ne!(first.nonce, second.nonce);
+        assert_eq!(first.to_signing_bytes().len(), 32);
+        assert_ne!(first.to_signing_bytes(), second.to_signing_bytes());
+        assert_ne!(first.to_signing_bytes(), release.to_signing_bytes());
+
+        // Same nonce and time: same bytes, whether owned or borrowed
+        let copy = NonceMessage {
+            message: &release,
+            nonce: first.nonce,
+            timestamp: first.timestamp,
+        };
+        assert_eq!(copy.to_signing_bytes(), first.to_signing_bytes());
+        let later = NonceMessage {
+            timestamp: first.timestamp + 1,
+            ..copy.clone()
+        };
+        assert_ne!(later.to_signing_bytes(), first.to_signing_bytes());
+
+        // A signature over one issue does not verify another
+        let keypair = crate::governance::GovernanceKeypair::generate().unwrap();
+        let signature =
+            crate::sign_message(keypair.secret_key.expose(), &first.to_signing_bytes()).unwrap();
+        let verify = |bytes: Vec<u8>| {
+            crate::governance::verify_signature(&signature, &bytes, &keypair.public_key()).unwrap()
+        };
+        assert!(verify(copy.to_signing_bytes()));
+        assert!(!verify(second.to_signing_bytes()));
+        assert!(!verify(release.to_signing_bytes()));
+
+        assert!(first.verify_freshness(60));
+        let at = |timestamp| NonceMessage {
+            timestamp,
+            ..copy.clone()
+        };
+        assert!(at(1_000).is_fresh_at(1_000, 0));
+        assert!(at(1_000).is_fresh_at(1_300, 300));
+        assert!(!at(1_000).is_fresh_at(1_301, 300));
+        assert!(!at(1_001).is_fresh_at(1_000, 300));
+    }
+
+    #[test]
+    fn test_nonce_registry() {
+        let release = GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: "abc123".to_string(),
+        };
+        let at = |timestamp| NonceMessage {
+            message: &release,
+            nonce: NonceMessage::<GovernanceMessage>::generate_nonce(),
+            timestamp,
+        };
+        let mut registry = NonceRegistry::new(300, 2);
+
+        // A second use of the same nonce is refused, even reissued later
+        let first = at(1_000);
+        registry.check_at(&first, 1_000).unwrap();
+        assert!(registry.check_at(&first, 1_010).is_err());
+        let reissued = NonceMessage {
+            timestamp: 1_005,
+            ..first.clone()
+        };
+        assert!(registry.check_at(&reissued, 1_010).is_err());
+
+        // Stale messages are refused without being recorded
+        assert!(registry.check_at(&at(600), 1_010).is_err());
+        assert_eq!(registry.len(), 1);
+
+        // At capacity, fresh nonces are kept rather than evicted
+        registry.check_at(&at(1_010), 1_010).unwrap();
+        assert!(matches!(
+            registry.check_at(&at(1_020), 1_020),
+            Err(GovernanceError::InvalidInput(_))
+        ));
+        assert!(registry.check_at(&first, 1_020).is_err());
+
+        // Once the first message ages out, its slot frees up
+        registry.check_at(&at(1_301), 1_301).unwrap();
+        assert_eq!(registry.len(), 2);
+    }
+
+    /// Sign `message` and check the signature covers exactly its signing bytes
+    fn assert_signs(message: &GovernanceMessage, tampered: &GovernanceMessage) {
+        let keypair = crate::governance::GovernanceKeypair::generate().unwrap();
+        let signature =
+            crate::sign_message(keypair.secret_key.expose(), &message.to_signing_bytes()).unwrap();
+        let verify = |message: &GovernanceMessage| {
+            crate::governance::verify_signature(
+                &signature,
+                &message.to_signing_bytes(),
+                &keypair.public_key(),
+            )
+            .unwrap()
+        };
+        assert!(verify(message));
+        assert!(!verify(tampered));
+    }
+
+    #[test]
+    fn test_config_change_message() {
+        let message = GovernanceMessage::ConfigChange {
+            parameter: "max_block_weight".to_string(),
+            old_value: "4000000".to_string(),
+            new_value: "8000000".to_string(),
+            justification: "capacity".to_string(),
+        };
+
+        assert_eq!(
+            message.to_signing_bytes(),
+            b"CONFIG:max_block_weight:4000000:8000000"
+        );
+        assert_eq!(
+            message.description(),
+            "Change max_block_weight from 4000000 to 8000000"
+        );
+
+        // The same message from JSON with its fields in another order
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            serde_json::from_str::<GovernanceMessage>(&json).unwrap(),
+            message
+        );
+        let reordered: GovernanceMessage = serde_json::from_str(
+            r#"{"ConfigChange": {"justification": "capacity", "new_value": "8000000",
+                "old_value": "4000000", "parameter": "max_block_weight"}}"#,
+        )
+        .unwrap();
+        assert_eq!(reordered.to_signing_bytes(), message.to_signing_bytes());
+
+        // The justification is not signed; the values are
+        let mut rejustified = message.clone();
+        if let GovernanceMessage::ConfigChange { justification, .. } = &mut rejustified {
+            *justification = "other".to_string();
+        }
+        assert_eq!(rejustified.to_signing_bytes(), message.to_signing_bytes());
+        let mut tampered = message.clone();
+        if let GovernanceMessage::ConfigChange { new_value, .. } = &mut tampered {
+            *new_value = "9000000".to_string();
+        }
+        assert_signs(&message, &tampered);
+
+        // Module config updates keep their two-field encoding
+        assert!(matches!(
+            GovernanceMessage::from_signing_bytes(&message.to_signing_bytes()).unwrap(),
+            GovernanceMessage::ConfigChange { justification, .. } if justification.is_empty()
+        ));
+        assert!(matches!(
+            GovernanceMessage::from_signing_bytes(b"CONFIG:lightning:abc123").unwrap(),
+            GovernanceMessage::ConfigUpdate { .. }
+        ));
+    }
+
+    #[test]
+    fn test_member_update_message() {
+        let message = GovernanceMessage::MemberUpdate {
+            action: MemberAction::Suspend,
+            github: "alice".to_string(),
+            public_key: "02aa".to_string(),
+            team_id: "core".to_string(),
+        };
+
+        assert_eq!(
+            message.to_signing_bytes(),
+            b"MEMBER:suspend:alice:02aa:core"
+        );
+        assert_eq!(message.description(), "Suspend member alice (team core)");
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["MemberUpdate"]["action"], "suspend");
+        assert_eq!(
+            serde_json::from_value::<GovernanceMessage>(json).unwrap(),
+            message
+        );
+        let reordered: GovernanceMessage = serde_json::from_str(
+            r#"{"MemberUpdate": {"team_id": "core", "public_key": "02aa",
+                "github": "alice", "action": "suspend"}}"#,
+        )
+        .unwrap();
+        assert_eq!(reordered.to_signing_bytes(), message.to_signing_bytes());
+
+        let mut tampered = message.clone();
+        if let GovernanceMessage::MemberUpdate { action, .. } = &mut tampered {
+            *action = MemberAction::Remove;
+        }
+        assert_signs(&message, &tampered);
+
+        for action in [
+            MemberAction::Add,
+            MemberAction::Remove,
+            MemberAction::Suspend,
+        ] {
+            assert_eq!(action.to_string().parse::<MemberAction>().unwrap(), action);
+        }
+        assert!("Add".parse::<MemberAction>().is_err());
+    }
+
+    #[test]
+    fn test_iter_fields() {
+        let message = GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: "abc123".to_string(),
+        };
+
+        let fields: Vec<_> = message.iter_fields().collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("type", "Release".to_string()),
+                ("version", "v1.0.0".to_string()),
+                ("commit_hash", "abc123".to_string()),
+            ]
+        );
+
+        let message = GovernanceMessage::BudgetDecision {
+            amount: 1000000,
+            purpose: "development".to_string(),
+        };
+
+        let fields: Vec<_> = message.iter_fields().collect();
+        assert_eq!(fields[0], ("type", "BudgetDecision".to_string()));
+        assert_eq!(fields[1], ("amount", "1000000".to_string()));
+        assert_eq!(fields[2], ("purpose", "development".to_string()));
+    }
+
+    #[test]
+    fn test_message_id() {
+        let release = |commit: &str| GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: commit.to_string(),
+        };
+        assert_eq!(release("abc123").id(), release("abc123").id());
+        assert_ne!(release("abc123").id(), release("abc124").id());
+
+        // A `:` cannot move between fields
+        let colon = GovernanceMessage::Release {
+            version: "v1.0.0:abc".to_string(),
+            commit_hash: "123".to_string(),
+        };
+        assert_eq!(
+            colon.to_signing_bytes(),
+            b"RELEASE:v1.0.0%3Aabc:123".to_vec()
+        );
+        assert_eq!(
+            release("abc:123").to_signing_bytes(),
+            b"RELEASE:v1.0.0:abc%3A123".to_vec()
+        );
+        assert_ne!(colon.id(), release("abc:123").id());
+
+        // Different variants with the same values differ
+        let approval = GovernanceMessage::ModuleApproval {
+            module_name: "lightning".to_string(),
+            version: "1.0.0".to_string(),
+        };
+        let config = GovernanceMessage::ConfigUpdate {
+            module_name: "lightning".to_string(),
+            config_hash: "1.0.0".to_string(),
+        };
+        assert_ne!(approval.id(), config.id());
+    }
+
+    #[test]
+    fn test_from_signing_bytes() {
+        let parse = GovernanceMessage::from_signing_bytes;
+        assert_eq!(
+            parse(b"RELEASE:v1.0.0:abc123").unwrap(),
+            GovernanceMessage::Release {
+                version: "v1.0.0".to_string(),
+                commit_hash: "abc123".to_string(),
+            }
+        );
+        assert!(matches!(
+            parse(b"MODULE:lightning:v2.0.0").unwrap(),
+            GovernanceMessage::ModuleApproval { .. }
+        ));
+        assert!(matches!(
+            parse(b"MODULE:lightning:v2.0.0:abc123").unwrap(),
+            GovernanceMessage::ModuleBinaryApproval { .. }
+        ));
+        assert_eq!(
+            parse(b"BUDGET:1000000:dev%3Aops 100%25").unwrap(),
+            GovernanceMessage::BudgetDecision {
+                amount: 1000000,
+                purpose: "dev:ops 100%".to_string(),
+            }
+        );
+
+        for malformed in [
+            &b""[..],
+            b"RELEASE",
+            b"RELEASE:v1.0.0",
+            b"RELEASE:v1.0.0:abc:123",
+            b"MODULE:a:b:c:d",
+            b"BUDGET:-1:x",
+            b"BUDGET:+1:x",
+            b"BUDGET:01:x",
+            b"BUDGET:18446744073709551616:x",
+            b"CONFIG:a:100%",
+            b"CONFIG:a:%3a",
+            b"CONFIG:a:%2",
+            b"KEYROTATION:02aa:03bb",
+            b"EMERGENCY:low:a:b",
+            b"EMERGENCY:Critical:a:b",
+            b"CONFIG:a:b:c:d",
+            b"MEMBER:add:alice:02aa",
+            b"MEMBER:Add:alice:02aa:core",
+            b"MEMBER:ban:alice:02aa:core",
+            b"release:v1.0.0:abc",
+            b"RELEASE:\xff:abc",
+        ] {
+            assert!(
+                matches!(parse(malformed), Err(GovernanceError::MessageFormat(_))),
+                "{:?}",
+                String::from_utf8_lossy(malformed)
+            );
+        }
+    }
+
+    fn message_strategy() -> impl Strategy<Value = GovernanceMessage> {
+        let text = || any::<String>();
+        prop_oneof![
+            (text(), text()).prop_map(|(version, commit_hash)| GovernanceMessage::Release {
+                version,
+                commit_hash
+            }),
+            (text(), text()).prop_map(|(module_name, version)| {
+                GovernanceMessage::ModuleApproval {
+                    module_name,
+                    version,
+                }
+            }),
+            (text(), text(), text()).prop_map(|(module_name, version, binary_hash)| {
+                GovernanceMessage::ModuleBinaryApproval {
+                    module_name,
+                    version,
+                    binary_hash,
+                }
+            }),
+            (any::<u64>(), text()).prop_map(|(amount, purpose)| {
+                GovernanceMessage::BudgetDecision { amount, purpose }
+            }),
+            (text(), text()).prop_map(|(module_name, config_hash)| {
+                GovernanceMessage::ConfigUpdate {
+                    module_name,
+                    config_hash,
+                }
+            }),
+            (text(), text(), text()).prop_map(|(old_public_key, new_public_key, reason)| {
+                GovernanceMessage::KeyRotation {
+                    old_public_key,
+                    new_public_key,
+                    reason,
+                }
+            }),
+            (
+                prop_oneof![
+                    Just(EmergencySeverity::Critical),
+                    Just(EmergencySeverity::High),
+                    Just(EmergencySeverity::Medium),
+                ],
+                text(),
+                text()
+            )
+                .prop_map(|(severity, description, required_action)| {
+                    GovernanceMessage::Emergency {
+                        severity,
+                        description,
+                        required_action,
+                    }
+                }),
+            // The justification is not signed, so it does not round-trip
+            (text(), text(), text()).prop_map(|(parameter, old_value, new_value)| {
+                GovernanceMessage::ConfigChange {
+                    parameter,
+                    old_value,
+                    new_value,
+                    justification: String::new(),
+                }
+            }),
+            (
+                prop_oneof![
+                    Just(MemberAction::Add),
+                    Just(MemberAction::Remove),
+                    Just(MemberAction::Suspend),
+                ],
+                text(),
+                text(),
+                text()
+            )
+                .prop_map(|(action, github, public_key, team_id)| {
+                    GovernanceMessage::MemberUpdate {
+                        action,
+                        github,
+                        public_key,
+                        team_id,
+                    }
+                }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_signing_bytes_round_trip(message in message_strategy()) {
+            let bytes = message.to_signing_bytes();
+            prop_assert_eq!(GovernanceMessage::from_signing_bytes(&bytes).unwrap(), message);
+        }
+
+        #[test]
+        fn prop_from_signing_bytes_never_panics(
+            tag in prop_oneof![
+                Just(&b""[..]),
+                Just(&b"RELEASE:"[..]),
+                Just(&b"MODULE:"[..]),
+                Just(&b"BUDGET:"[..]),
+                Just(&b"CONFIG:"[..]),
+                Just(&b"KEYROTATION:"[..]),
+                Just(&b"EMERGENCY:critical:"[..]),
+                Just(&b"MEMBER:add:"[..]),
+            ],
+            rest in proptest::collection::vec(any::<u8>(), 0..64),
+        ) {
+            let bytes = [tag, rest.as_slice()].concat();
+            // Whatever parses is the exact encoding of the parsed message
+            if let Ok(message) = GovernanceMessage::from_signing_bytes(&bytes) {
+                prop_assert_eq!(message.to_signing_bytes(), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_join_fields() {
+        assert_eq!(join_fields(&["image", "repo", "v1"]), b"image:repo:v1");
+        assert_eq!(
+            join_fields(&["image", "host:5000/repo", "100%"]),
+            b"image:host%3A5000/repo:100%25"
+        );
+        assert_ne!(join_fields(&["a:b", "c"]), join_fields(&["a", "b:c"]));
+    }
+
     #[test]
     fn test_message_serialization() {
         let message = GovernanceMessage::Release {
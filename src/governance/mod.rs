@@ -8,22 +8,111 @@
 //! - Multisig threshold logic
 //! - Message formats for governance decisions
 
+/// Emit a `GovernanceEvent` variant; compiles to
+/// nothing without the `events` feature
+///
+/// Without the feature the field values still count as used (in a closure
+/// that is never called), so callers need no `cfg` of their own.
+macro_rules! emit_event {
+    ($variant:ident { $($name:ident: $value:expr),* $(,)? }) => {
+        #[cfg(feature = "events")]
+        $crate::governance::events::emit(|| {
+            $crate::governance::events::GovernanceEvent::$variant { $($name: $value),* }
+        });
+        #[cfg(not(feature = "events"))]
+        let _ = || ($($value,)*);
+    };
+}
+
+pub mod aggregation;
+pub mod archive;
+pub mod artifact;
+pub mod attributes;
 pub mod bip32;
+#[cfg(any(feature = "signing", test))]
 pub mod bip39;
+#[cfg(any(feature = "signing", test))]
 pub mod bip44;
+pub mod bundle;
+pub mod cache;
+pub mod ci;
+pub mod config;
+pub mod context;
+pub mod ed25519;
+pub mod equivocation;
 pub mod error;
+pub mod escalation;
+#[cfg(feature = "events")]
+pub mod events;
+pub mod history;
+pub mod identity;
+#[cfg(any(feature = "signing", test))]
+pub mod journal;
 pub mod keys;
+pub mod keystore;
+pub mod manifest;
 pub mod messages;
 pub mod multisig;
+pub mod musig2;
 pub mod nested_multisig;
+#[cfg(feature = "net")]
+pub mod notify;
+pub mod oci;
+pub mod paper;
+pub mod payload;
+pub mod pin;
+pub mod pipeline;
 pub mod psbt;
+pub mod rotation;
+pub mod secret_sharing;
+pub mod sigfile;
 pub mod signatures;
+pub mod signer;
+pub mod threshold;
+pub mod timestamp;
+pub mod trust;
 pub mod verification;
 
 // Re-export main types
+pub use artifact::{
+    verify_artifact, SignatureRecord, SignedArtifactMetadata, VerifyArtifactResult,
+};
+pub use attributes::SignedAttributes;
+pub use bundle::SignatureBundle;
+pub use cache::{VerifyCache, VerifyCacheKey, DEFAULT_VERIFY_CACHE_CAPACITY};
+pub use config::GovernanceConfig;
+pub use context::{context_info, ContextInfo};
 pub use error::{GovernanceError, GovernanceResult};
-pub use keys::{GovernanceKeypair, PublicKey};
-pub use messages::GovernanceMessage;
-pub use multisig::Multisig;
-pub use signatures::Signature;
-pub use verification::verify_signature;
+pub use history::GovernanceHistory;
+#[cfg(any(feature = "signing", test))]
+pub use identity::attest_identity;
+pub use identity::{
+    verify_identity_attestation, IdentityAttestation, IdentityClaim, SignerRegistry,
+};
+pub use keys::{Fingerprint, PublicKey};
+#[cfg(any(feature = "signing", test))]
+pub use keys::{GovernanceKeypair, SecretKeyMaterial};
+pub use keystore::Keystore;
+pub use messages::{
+    EmergencySeverity, GovernanceMessage, MemberAction, MessageId, NonceMessage, NonceRegistry,
+    SigningTarget,
+};
+pub use multisig::{
+    Collected, Multisig, Remaining, ScriptKind, SignatureCollector, WeightedMultisig,
+};
+pub use payload::{SigScheme, SignedPayload, VerifyingKey};
+pub use rotation::{verify_rotation, KeyRotationStatement};
+pub use secret_sharing::{combine_shares, split_secret, SecretShare};
+pub use signatures::{
+    compute_rfc6979_nonce, verify_schnorr, verify_signature_lax, SchnorrSignature, SigEncoding,
+    Signature,
+};
+#[cfg(any(feature = "signing", test))]
+pub use signatures::{sign_message_rfc6979, sign_message_schnorr, sign_message_with_ctx};
+pub use signer::{CommandSigner, GovernanceSigner};
+pub use threshold::Threshold;
+pub use timestamp::SignedTimestamp;
+pub use trust::{TrustHandle, TrustStore};
+pub use verification::{
+    batch_verify, batch_verify_fail_fast, verify_signature, verify_signatures_batch,
+};
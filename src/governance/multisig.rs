@@ -2,17 +2,103 @@
 //!
 //! Multisig threshold logic and signature collection.
 
-use std::collections::HashSet;
+use bitcoin::opcodes::all::OP_CHECKMULTISIG;
+use bitcoin::script::Builder;
+use bitcoin::{Address, Network, ScriptBuf};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::governance::error::{GovernanceError, GovernanceResult};
-use crate::governance::{PublicKey, Signature};
+use crate::governance::nested_multisig::{
+    Committee, GroupedReport, GroupedSigner, TeamSignerCount,
+};
+use crate::governance::rotation::{verify_rotation, KeyRotationStatement};
+use crate::governance::signer::GovernanceSigner;
+use crate::governance::{GovernanceMessage, PublicKey, Signature, Threshold};
+
+/// Signatures still needed to meet a multisig threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remaining {
+    /// Additional distinct valid signatures needed
+    pub count: usize,
+    /// Indices of keys that have not signed yet
+    pub eligible: Vec<usize>,
+}
+
+/// Most keys an `OP_CHECKMULTISIG` witness script may hold
+pub const MAX_SCRIPT_KEYS: usize = 20;
+
+/// Most keys a P2SH `multi()` may hold, its script being limited to 520 bytes
+pub const MAX_P2SH_KEYS: usize = 15;
+
+/// Script type of a multisig output descriptor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKind {
+    /// `sh(multi(...))`
+    P2sh,
+    /// `wsh(multi(...))`
+    P2wsh,
+    /// `sh(wsh(multi(...)))`
+    P2shP2wsh,
+}
+
+/// Characters a descriptor may use, in BIP380 checksum order
+const DESCRIPTOR_INPUT_CHARSET: &str =
+    "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+/// Characters of a descriptor checksum
+const DESCRIPTOR_CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
 
 /// A multisig configuration
-#[derive(Debug, Clone)]
+///
+/// Serialized as `{threshold, total, public_keys}` with hex keys, plus
+/// `rotations` once a key was rotated; deserializing checks the
+/// configuration as [`Multisig::new`] does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "MultisigFields", into = "MultisigFields")]
 pub struct Multisig {
+    threshold: Threshold,
+    public_keys: Vec<PublicKey>,
+    rotations: u64,
+}
+
+/// Serialized form of a [`Multisig`]
+#[derive(Serialize, Deserialize)]
+struct MultisigFields {
     threshold: usize,
     total: usize,
     public_keys: Vec<PublicKey>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    rotations: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl TryFrom<MultisigFields> for Multisig {
+    type Error = GovernanceError;
+
+    fn try_from(fields: MultisigFields) -> Result<Self, Self::Error> {
+        let mut multisig = Self::new(fields.threshold, fields.total, fields.public_keys)?;
+        multisig.rotations = fields.rotations;
+        Ok(multisig)
+    }
+}
+
+impl From<Multisig> for MultisigFields {
+    fn from(multisig: Multisig) -> Self {
+        Self {
+            threshold: multisig.threshold.required(),
+            total: multisig.threshold.total(),
+            public_keys: multisig.public_keys,
+            rotations: multisig.rotations,
+        }
+    }
 }
 
 impl Multisig {
@@ -22,14 +108,15 @@ impl Multisig {
         total: usize,
         public_keys: Vec<PublicKey>,
     ) -> GovernanceResult<Self> {
-        if threshold == 0 {
-            return Err(GovernanceError::InvalidThreshold { threshold, total });
-        }
-
-        if threshold > total {
-            return Err(GovernanceError::InvalidThreshold { threshold, total });
-        }
+        Self::with_threshold(Threshold::new(threshold, total)?, public_keys)
+    }
 
+    /// Create a multisig configuration from a [`Threshold`]
+    pub fn with_threshold(
+        threshold: Threshold,
+        public_keys: Vec<PublicKey>,
+    ) -> GovernanceResult<Self> {
+        let total = threshold.total();
         if public_keys.len() != total {
             return Err(GovernanceError::InvalidMultisig(format!(
                 "Expected {} public keys, got {}",
@@ -48,36 +135,165 @@ impl Multisig {
 
         Ok(Self {
             threshold,
-            total,
             public_keys,
+            rotations: 0,
         })
     }
 
     /// Verify a set of signatures against a message
     pub fn verify(&self, message: &[u8], signatures: &[Signature]) -> GovernanceResult<bool> {
-        if signatures.len() < self.threshold {
+        if signatures.len() < self.threshold.required() {
+            emit_event!(MultisigVerificationFailed {
+                threshold: self.threshold,
+                signatures: signatures.len(),
+                valid_signatures: 0,
+            });
             return Err(GovernanceError::InsufficientSignatures {
                 got: signatures.len(),
-                need: self.threshold,
+                need: self.threshold.required(),
             });
         }
 
         let valid_signatures = self.collect_valid_signatures(message, signatures)?;
-        Ok(valid_signatures.len() >= self.threshold)
+        let verified = self.threshold.is_met(valid_signatures.len());
+        if !verified {
+            emit_event!(MultisigVerificationFailed {
+                threshold: self.threshold,
+                signatures: signatures.len(),
+                valid_signatures: valid_signatures.len(),
+            });
+        }
+        Ok(verified)
+    }
+
+    /// Verify a set of signatures against a governance message
+    ///
+    /// The message is encoded with [`GovernanceMessage::to_signing_bytes`],
+    /// so callers cannot verify against a drifted encoding.
+    pub fn verify_message(
+        &self,
+        message: &GovernanceMessage,
+        signatures: &[Signature],
+    ) -> GovernanceResult<bool> {
+        self.verify(&message.to_signing_bytes(), signatures)
+    }
+
+    /// Verify the signatures of many messages against this multisig, in
+    /// parallel
+    ///
+    /// Each result is what [`verify`](Self::verify) gives for that message,
+    /// except that a message with fewer signatures than the threshold is
+    /// `false` rather than an error, so one short message does not fail
+    /// the whole batch.
+    pub fn verify_batch(&self, messages: &[(&[u8], &[Signature])]) -> GovernanceResult<Vec<bool>> {
+        messages
+            .par_iter()
+            .map(|(message, signatures)| {
+                if signatures.len() < self.threshold.required() {
+                    return Ok(false);
+                }
+                self.verify(message, signatures)
+            })
+            .collect()
+    }
+
+    /// Verify signatures, grouping the valid signers by team
+    ///
+    /// The threshold still counts distinct signers across the whole
+    /// multisig, whatever their team; the committee only labels them. Keys
+    /// the committee does not know are counted as unassigned.
+    pub fn verify_with_committee(
+        &self,
+        message: &[u8],
+        signatures: &[Signature],
+        committee: &Committee,
+    ) -> GovernanceResult<GroupedReport> {
+        let signed: BTreeSet<usize> = self
+            .collect_valid_signatures(message, signatures)?
+            .into_iter()
+            .collect();
+
+        let mut teams: Vec<TeamSignerCount> = committee
+            .teams()
+            .iter()
+            .map(|team| TeamSignerCount {
+                team_id: team.id.clone(),
+                team_name: team.name.clone(),
+                signed: 0,
+                members: team.maintainers.len(),
+            })
+            .collect();
+        let mut unassigned = 0;
+        let signers = signed
+            .iter()
+            .map(
+                |&key_index| match committee.member(&self.public_keys[key_index]) {
+                    Some((team, maintainer)) => {
+                        if let Some(count) = teams.iter_mut().find(|t| t.team_id == team.id) {
+                            count.signed += 1;
+                        }
+                        GroupedSigner {
+                            key_index,
+                            team_id: Some(team.id.clone()),
+                            github: Some(maintainer.github.clone()),
+                            fingerprint: self.public_keys[key_index].short(),
+                        }
+                    }
+                    None => {
+                        unassigned += 1;
+                        GroupedSigner {
+                            key_index,
+                            team_id: None,
+                            github: None,
+                            fingerprint: self.public_keys[key_index].short(),
+                        }
+                    }
+                },
+            )
+            .collect();
+
+        Ok(GroupedReport {
+            threshold_met: self.threshold.is_met(signed.len()),
+            signers,
+            teams,
+            unassigned,
+        })
+    }
+
+    /// Verify signatures, taking ownership of the multisig and signatures
+    ///
+    /// Same result as [`Multisig::verify`]. Both are dropped when this
+    /// returns, making it explicit in long-running services that neither is
+    /// used again.
+    pub fn verify_and_consume(
+        self,
+        message: &[u8],
+        signatures: Vec<Signature>,
+    ) -> GovernanceResult<bool> {
+        self.verify(message, &signatures)
     }
 
-    /// Collect valid signatures and return their indices
+    /// Collect valid signatures and return the indices of their keys
+    ///
+    /// Each key counts once: a signature matching only keys that already
+    /// signed is skipped, so repeated signatures cannot stand in for
+    /// distinct signers.
     pub fn collect_valid_signatures(
         &self,
         message: &[u8],
         signatures: &[Signature],
     ) -> GovernanceResult<Vec<usize>> {
         let mut valid_indices = Vec::new();
+        let mut matched = HashSet::new();
 
         for signature in signatures.iter() {
-            // Try to verify against each public key
+            // Try to verify against each key that has not signed yet
             for (j, public_key) in self.public_keys.iter().enumerate() {
+                if matched.contains(&j) {
+                    continue;
+                }
                 if crate::governance::verify_signature(signature, message, public_key)? {
+                    matched.insert(j);
                     valid_indices.push(j);
                     break;
                 }
@@ -87,14 +303,50 @@ impl Multisig {
         Ok(valid_indices)
     }
 
+    /// Start collecting signatures over `message` one at a time
+    pub fn collector(&self, message: &[u8]) -> SignatureCollector<'_> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        SignatureCollector {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            multisig: self,
+            message: message.to_vec(),
+            signatures: vec![None; self.public_keys.len()],
+            collected: 0,
+        }
+    }
+
+    /// How many more distinct valid signatures are needed, and from which keys
+    pub fn remaining_requirement(
+        &self,
+        message: &[u8],
+        collected: &[Signature],
+    ) -> GovernanceResult<Remaining> {
+        let signed: HashSet<usize> = self
+            .collect_valid_signatures(message, collected)?
+            .into_iter()
+            .collect();
+
+        Ok(Remaining {
+            count: self.threshold.required().saturating_sub(signed.len()),
+            eligible: (0..self.public_keys.len())
+                .filter(|i| !signed.contains(i))
+                .collect(),
+        })
+    }
+
     /// Get the threshold
     pub fn threshold(&self) -> usize {
-        self.threshold
+        self.threshold.required()
     }
 
     /// Get the total number of keys
     pub fn total(&self) -> usize {
-        self.total
+        self.threshold.total()
+    }
+
+    /// Get the threshold as "m of n"
+    pub fn m_of_n(&self) -> Threshold {
+        self.threshold
     }
 
     /// Get the public keys
@@ -102,6 +354,170 @@ impl Multisig {
         &self.public_keys
     }
 
+    /// Key rotations applied to this multisig, the sequence number the next
+    /// [`KeyRotationStatement`] must carry
+    pub fn rotations(&self) -> u64 {
+        self.rotations
+    }
+
+    /// Compute a fingerprint identifying this committee
+    ///
+    /// The fingerprint is the SHA256 of the threshold followed by the sorted
+    /// compressed public keys, so it is independent of key order but changes
+    /// whenever the threshold or any member key changes.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut keys: Vec<[u8; 33]> = self.public_keys.iter().map(|k| k.to_bytes()).collect();
+        keys.sort();
+
+        let mut hasher = Sha256::new();
+        hasher.update((self.threshold.required() as u64).to_be_bytes());
+        for key in &keys {
+            hasher.update(key);
+        }
+        hasher.finalize().into()
+    }
+
+    /// Witness script of this multisig: `OP_m <keys> OP_n OP_CHECKMULTISIG`
+    ///
+    /// Keys are sorted by their compressed encoding (BIP67, `sortedmulti`),
+    /// so the script does not depend on the order keys were loaded in.
+    pub fn witness_script(&self) -> GovernanceResult<ScriptBuf> {
+        self.checkmultisig_script(true)
+    }
+
+    /// `OP_m <keys> OP_n OP_CHECKMULTISIG`, with the keys sorted or in
+    /// stored order
+    fn checkmultisig_script(&self, sorted: bool) -> GovernanceResult<ScriptBuf> {
+        if self.public_keys.len() > MAX_SCRIPT_KEYS {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "A multisig script holds at most {} keys, got {}",
+                MAX_SCRIPT_KEYS,
+                self.public_keys.len()
+            )));
+        }
+
+        let mut keys: Vec<[u8; 33]> = self.public_keys.iter().map(|k| k.to_bytes()).collect();
+        if sorted {
+            keys.sort();
+        }
+
+        let mut builder = Builder::new().push_int(self.threshold.required() as i64);
+        for key in keys {
+            builder = builder.push_slice(key);
+        }
+        Ok(builder
+            .push_int(self.threshold.total() as i64)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script())
+    }
+
+    /// P2WSH address of [`witness_script`](Self::witness_script) on a network
+    pub fn to_address(&self, network: Network) -> GovernanceResult<Address> {
+        Ok(Address::p2wsh(&self.witness_script()?, network))
+    }
+
+    /// P2WSH address of the `OP_m <keys> OP_n OP_CHECKMULTISIG` script with
+    /// the keys in stored order
+    ///
+    /// The address of [`to_descriptor`](Self::to_descriptor) with
+    /// [`ScriptKind::P2wsh`]. It differs from [`to_address`](Self::to_address),
+    /// which sorts the keys, unless they are stored sorted. At most
+    /// [`MAX_SCRIPT_KEYS`] keys, the consensus limit.
+    pub fn p2wsh_address(&self, network: Network) -> GovernanceResult<Address> {
+        Ok(Address::p2wsh(&self.checkmultisig_script(false)?, network))
+    }
+
+    /// Output descriptor of this multisig, with its BIP380 checksum
+    ///
+    /// For example `wsh(multi(2,<key1>,<key2>,<key3>))#<checksum>`, as
+    /// Bitcoin Core's `importdescriptors` accepts it. Keys are hex in the
+    /// order the multisig holds them (`multi`, not `sortedmulti`), so the
+    /// script differs from [`witness_script`](Self::witness_script) unless
+    /// they are already sorted.
+    pub fn to_descriptor(&self, kind: ScriptKind) -> GovernanceResult<String> {
+        let max_keys = match kind {
+            ScriptKind::P2sh => MAX_P2SH_KEYS,
+            ScriptKind::P2wsh | ScriptKind::P2shP2wsh => MAX_SCRIPT_KEYS,
+        };
+        if self.public_keys.len() > max_keys {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "A {:?} multisig holds at most {} keys, got {}",
+                kind,
+                max_keys,
+                self.public_keys.len()
+            )));
+        }
+
+        let mut multi = format!("multi({}", self.threshold.required());
+        for key in &self.public_keys {
+            multi.push(',');
+            multi.push_str(&key.to_string());
+        }
+        multi.push(')');
+        let descriptor = match kind {
+            ScriptKind::P2sh => format!("sh({})", multi),
+            ScriptKind::P2wsh => format!("wsh({})", multi),
+            ScriptKind::P2shP2wsh => format!("sh(wsh({}))", multi),
+        };
+        let checksum = descriptor_checksum(&descriptor);
+        Ok(format!("{}#{}", descriptor, checksum))
+    }
+
+    /// The multisig with the key a verified rotation statement replaces
+    /// swapped for its successor, in the same position
+    ///
+    /// Fails if the statement is not signed by the old key, if it was made
+    /// for another committee or another number of prior rotations, if it
+    /// takes effect after `now`, if the old key is not in the set, or if the
+    /// new key already is. The result counts one more rotation, so a
+    /// statement applies once: after A hands over to B and B back to A, the
+    /// A to B statement no longer matches.
+    pub fn apply_rotation(
+        &self,
+        statement: &KeyRotationStatement,
+        now: DateTime<Utc>,
+    ) -> GovernanceResult<Self> {
+        let new_public_key = verify_rotation(statement)?;
+        if statement.committee_fingerprint != hex::encode(self.fingerprint()) {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "Rotation of {} was made for another committee",
+                statement.old_public_key.short()
+            )));
+        }
+        if statement.sequence != self.rotations {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "Rotation of {} is number {}, the multisig expects number {}",
+                statement.old_public_key.short(),
+                statement.sequence,
+                self.rotations
+            )));
+        }
+        if statement.effective_at > now {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Rotation of {} takes effect at {}, not before",
+                statement.old_public_key.short(),
+                statement
+                    .effective_at
+                    .to_rfc3339_opts(SecondsFormat::Secs, true)
+            )));
+        }
+        let index = self
+            .public_keys
+            .iter()
+            .position(|key| *key == statement.old_public_key)
+            .ok_or_else(|| {
+                GovernanceError::InvalidMultisig(format!(
+                    "Rotated key {} is not in the multisig",
+                    statement.old_public_key.short()
+                ))
+            })?;
+        let mut public_keys = self.public_keys.clone();
+        public_keys[index] = new_public_key;
+        let mut rotated = Self::with_threshold(self.threshold, public_keys)?;
+        rotated.rotations = self.rotations + 1;
+        Ok(rotated)
+    }
+
     /// Check if a signature is valid for this multisig
     pub fn is_valid_signature(
         &self,
@@ -117,6 +533,233 @@ impl Multisig {
     }
 }
 
+/// Signatures over one message, collected as they arrive
+///
+/// Each key counts once. The signature that brings the distinct valid
+/// signatures up to the threshold is reported by [`add`](Self::add) with
+/// `threshold_reached`, exactly once, and also emits
+/// `GovernanceEvent::ThresholdReached` with the collector's [`id`](Self::id).
+#[derive(Debug, Clone)]
+pub struct SignatureCollector<'a> {
+    /// Distinguishes collectors in events, unique within the process
+    id: u64,
+    multisig: &'a Multisig,
+    message: Vec<u8>,
+    /// Valid signature of each key, by key index
+    signatures: Vec<Option<Signature>>,
+    collected: usize,
+}
+
+/// A signature a [`SignatureCollector`] counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collected {
+    /// Index of the key the signature counts for
+    pub index: usize,
+    /// Whether this signature brought the collector up to the threshold
+    pub threshold_reached: bool,
+}
+
+impl SignatureCollector<'_> {
+    /// Add a signature, returning the key it counts for and whether it
+    /// reached the threshold
+    ///
+    /// `None` when it matches no key that has not signed yet: invalid,
+    /// by an unknown key, or from a key already counted.
+    pub fn add(&mut self, signature: Signature) -> GovernanceResult<Option<Collected>> {
+        for (index, public_key) in self.multisig.public_keys.iter().enumerate() {
+            if self.signatures[index].is_some() {
+                continue;
+            }
+            if crate::governance::verify_signature(&signature, &self.message, public_key)? {
+                self.signatures[index] = Some(signature);
+                self.collected += 1;
+                let threshold_reached = self.collected == self.multisig.threshold.required();
+                if threshold_reached {
+                    emit_event!(ThresholdReached {
+                        collector: self.id,
+                        message_digest: hex::encode(Sha256::digest(&self.message)),
+                        by_key: index,
+                    });
+                }
+                return Ok(Some(Collected {
+                    index,
+                    threshold_reached,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Sign the message with `signer` and add the signature
+    pub fn sign_with(
+        &mut self,
+        signer: &dyn GovernanceSigner,
+    ) -> GovernanceResult<Option<Collected>> {
+        let signature = signer.sign(&self.message)?;
+        self.add(signature)
+    }
+
+    /// Identifier of this collector in `ThresholdReached` events
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Number of distinct keys with a valid signature
+    pub fn collected(&self) -> usize {
+        self.collected
+    }
+
+    /// Whether the collected signatures meet the threshold
+    pub fn is_met(&self) -> bool {
+        self.multisig.threshold.is_met(self.collected)
+    }
+
+    /// The valid signatures, in key order
+    pub fn signatures(&self) -> Vec<Signature> {
+        self.signatures.iter().flatten().cloned().collect()
+    }
+}
+
+/// BIP380 checksum of a descriptor built from [`DESCRIPTOR_INPUT_CHARSET`]
+fn descriptor_checksum(descriptor: &str) -> String {
+    fn polymod(c: u64, value: u64) -> u64 {
+        const GENERATOR: [u64; 5] = [
+            0xf5dee51989,
+            0xa9fdca3312,
+            0x1bab10e32d,
+            0x3706b1677a,
+            0x644d626ffd,
+        ];
+        let top = c >> 35;
+        let mut c = ((c & 0x7ffffffff) << 5) ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                c ^= generator;
+            }
+        }
+        c
+    }
+
+    let mut c = 1;
+    let mut class = 0;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let position = DESCRIPTOR_INPUT_CHARSET
+            .find(ch)
+            .expect("descriptor uses the input charset") as u64;
+        c = polymod(c, position & 31);
+        class = class * 3 + (position >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = polymod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = polymod(c, class);
+    }
+    for _ in 0..8 {
+        c = polymod(c, 0);
+    }
+    c ^= 1;
+
+    (0..8)
+        .map(|i| DESCRIPTOR_CHECKSUM_CHARSET[((c >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect()
+}
+
+/// A multisig in which each key carries a weight
+///
+/// Approval needs the weights of the distinct keys with a valid signature to
+/// add up to the threshold weight.
+#[derive(Debug, Clone)]
+pub struct WeightedMultisig {
+    threshold_weight: u64,
+    keys: Vec<(PublicKey, u64)>,
+}
+
+impl WeightedMultisig {
+    /// Create a weighted multisig from `(key, weight)` pairs
+    ///
+    /// The threshold weight must be at least 1 and at most the total weight.
+    pub fn new(threshold_weight: u64, keys: Vec<(PublicKey, u64)>) -> GovernanceResult<Self> {
+        let unique_keys: HashSet<_> = keys.iter().map(|(key, _)| key).collect();
+        if unique_keys.len() != keys.len() {
+            return Err(GovernanceError::InvalidMultisig(
+                "Duplicate public keys not allowed".to_string(),
+            ));
+        }
+        let total_weight = keys
+            .iter()
+            .try_fold(0u64, |total, (_, weight)| total.checked_add(*weight))
+            .ok_or_else(|| {
+                GovernanceError::InvalidMultisig("Total weight overflows".to_string())
+            })?;
+        if threshold_weight == 0 || threshold_weight > total_weight {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "Threshold weight must be between 1 and the total weight {}, got {}",
+                total_weight, threshold_weight
+            )));
+        }
+
+        Ok(Self {
+            threshold_weight,
+            keys,
+        })
+    }
+
+    /// Total weight of the distinct keys with a valid signature over `message`
+    pub fn signed_weight(&self, message: &[u8], signatures: &[Signature]) -> GovernanceResult<u64> {
+        let mut signed = HashSet::new();
+        for signature in signatures {
+            for (i, (public_key, _)) in self.keys.iter().enumerate() {
+                if crate::governance::verify_signature(signature, message, public_key)? {
+                    signed.insert(i);
+                    break;
+                }
+            }
+        }
+        Ok(signed.iter().map(|&i| self.keys[i].1).sum())
+    }
+
+    /// Whether the signed weight meets the threshold weight
+    pub fn verify(&self, message: &[u8], signatures: &[Signature]) -> GovernanceResult<bool> {
+        Ok(self.signed_weight(message, signatures)? >= self.threshold_weight)
+    }
+
+    /// Fraction of the total weight that validly signed, from 0.0 to 1.0
+    ///
+    /// Reported next to [`verify`](Self::verify)'s result, e.g. for
+    /// dashboards; a zero total weight reports 0.0 rather than NaN.
+    pub fn approval_ratio(
+        &self,
+        message: &[u8],
+        signatures: &[Signature],
+    ) -> GovernanceResult<f64> {
+        let total_weight = self.total_weight();
+        if total_weight == 0 {
+            return Ok(0.0);
+        }
+        Ok(self.signed_weight(message, signatures)? as f64 / total_weight as f64)
+    }
+
+    /// Weight needed for approval
+    pub fn threshold_weight(&self) -> u64 {
+        self.threshold_weight
+    }
+
+    /// Sum of the key weights
+    pub fn total_weight(&self) -> u64 {
+        self.keys.iter().map(|(_, weight)| weight).sum()
+    }
+
+    /// The keys and their weights
+    pub fn keys(&self) -> &[(PublicKey, u64)] {
+        &self.keys
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,6 +793,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_with_threshold() {
+        let public_keys: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap().public_key())
+            .collect();
+
+        let threshold: Threshold = "2-of-3".parse().unwrap();
+        let multisig = Multisig::with_threshold(threshold, public_keys.clone()).unwrap();
+        assert_eq!(multisig.m_of_n(), threshold);
+        assert_eq!(
+            multisig.fingerprint(),
+            Multisig::new(2, 3, public_keys.clone())
+                .unwrap()
+                .fingerprint()
+        );
+
+        let threshold: Threshold = "2-of-4".parse().unwrap();
+        assert!(Multisig::with_threshold(threshold, public_keys).is_err());
+    }
+
     #[test]
     fn test_multisig_verification() {
         let keypairs: Vec<_> = (0..5)
@@ -163,13 +826,94 @@ mod tests {
         // Sign with 3 keys (meets threshold)
         let signatures: Vec<_> = keypairs[0..3]
             .iter()
-            .map(|kp| crate::sign_message(&kp.secret_key, message).unwrap())
+            .map(|kp| crate::sign_message(kp.secret_key.expose(), message).unwrap())
             .collect();
 
         let result = multisig.verify(message, &signatures).unwrap();
         assert!(result);
     }
 
+    #[test]
+    fn test_repeated_signature_counts_once() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(2, 3, public_keys).unwrap();
+        let message = b"test message";
+
+        let signature = crate::sign_message(keypairs[0].secret_key.expose(), message).unwrap();
+        let signatures = vec![signature.clone(), signature.clone(), signature];
+        assert_eq!(
+            multisig
+                .collect_valid_signatures(message, &signatures)
+                .unwrap(),
+            vec![0]
+        );
+        assert!(!multisig.verify(message, &signatures).unwrap());
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(2, 3, public_keys).unwrap();
+        let sign = |signers: &[usize], message: &[u8]| -> Vec<Signature> {
+            signers
+                .iter()
+                .map(|&i| crate::sign_message(keypairs[i].secret_key.expose(), message).unwrap())
+                .collect()
+        };
+
+        let (v0, v2): (&[u8], &[u8]) = (b"release:v1.0.0", b"release:v1.0.2");
+        let approved = sign(&[0, 2], v0);
+        let wrong_message = sign(&[0, 1], b"release:v1.0.1");
+        let short = sign(&[1], v2);
+        let results = multisig
+            .verify_batch(&[
+                (v0, approved.as_slice()),
+                (v0, wrong_message.as_slice()),
+                (v2, short.as_slice()),
+            ])
+            .unwrap();
+        assert_eq!(results, vec![true, false, false]);
+        assert!(multisig.verify_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_message_matches_raw_bytes() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(2, 3, public_keys).unwrap();
+        let message = GovernanceMessage::Release {
+            version: "v1.0.0".to_string(),
+            commit_hash: "abc123".to_string(),
+        };
+        let sign = |keypairs: &[GovernanceKeypair], bytes: &[u8]| -> Vec<Signature> {
+            keypairs
+                .iter()
+                .map(|kp| crate::sign_message(kp.secret_key.expose(), bytes).unwrap())
+                .collect()
+        };
+
+        let signatures = sign(&keypairs[..2], &message.to_signing_bytes());
+        assert!(multisig.verify_message(&message, &signatures).unwrap());
+        assert!(multisig
+            .verify(&message.to_signing_bytes(), &signatures)
+            .unwrap());
+
+        // Signatures over a drifted encoding fail both ways
+        let drifted = sign(&keypairs[..2], b"RELEASE v1.0.0 abc123");
+        assert!(!multisig.verify_message(&message, &drifted).unwrap());
+        assert!(!multisig
+            .verify(&message.to_signing_bytes(), &drifted)
+            .unwrap());
+    }
+
     #[test]
     fn test_insufficient_signatures() {
         let keypairs: Vec<_> = (0..5)
@@ -183,13 +927,45 @@ mod tests {
         // Sign with only 2 keys (below threshold)
         let signatures: Vec<_> = keypairs[0..2]
             .iter()
-            .map(|kp| crate::sign_message(&kp.secret_key, message).unwrap())
+            .map(|kp| crate::sign_message(kp.secret_key.expose(), message).unwrap())
             .collect();
 
         let result = multisig.verify(message, &signatures);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_verify_and_consume_matches_verify() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let multisig = Multisig::new(2, 3, public_keys).unwrap();
+        let message = b"test message";
+
+        let sign = |kp: &GovernanceKeypair, msg: &[u8]| {
+            crate::sign_message(kp.secret_key.expose(), msg).unwrap()
+        };
+        let cases = vec![
+            // Threshold met
+            vec![sign(&keypairs[0], message), sign(&keypairs[1], message)],
+            // Enough signatures, but one is over another message
+            vec![sign(&keypairs[0], message), sign(&keypairs[1], b"other")],
+            // Below threshold
+            vec![sign(&keypairs[0], message)],
+        ];
+
+        for signatures in cases {
+            let borrowed = multisig.verify(message, &signatures);
+            let consumed = multisig.clone().verify_and_consume(message, signatures);
+            match (borrowed, consumed) {
+                (Ok(a), Ok(b)) => assert_eq!(a, b),
+                (Err(a), Err(b)) => assert_eq!(a.to_string(), b.to_string()),
+                (a, b) => panic!("results differ: {:?} vs {:?}", a, b),
+            }
+        }
+    }
+
     #[test]
     fn test_duplicate_public_keys() {
         let keypair = GovernanceKeypair::generate().unwrap();
@@ -201,4 +977,384 @@ mod tests {
         let result = Multisig::new(2, 2, public_keys);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_multisig_serde() {
+        let keys: Vec<_> = (1..=3u8)
+            .map(|i| {
+                GovernanceKeypair::from_secret_key(&[i; 32])
+                    .unwrap()
+                    .public_key()
+            })
+            .collect();
+        let multisig = Multisig::new(2, 3, keys.clone()).unwrap();
+
+        let value = serde_json::to_value(&multisig).unwrap();
+        assert_eq!(value["threshold"], 2);
+        assert_eq!(value["total"], 3);
+        assert_eq!(value["public_keys"][0], keys[0].to_string());
+        let parsed: Multisig = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(parsed.m_of_n(), multisig.m_of_n());
+        assert_eq!(parsed.public_keys(), multisig.public_keys());
+
+        let mut duplicate = value.clone();
+        duplicate["public_keys"][2] = duplicate["public_keys"][0].clone();
+        let err = serde_json::from_value::<Multisig>(duplicate).unwrap_err();
+        assert!(err.to_string().contains("Duplicate public keys"));
+
+        let mut wrong_total = value.clone();
+        wrong_total["total"] = 4.into();
+        assert!(serde_json::from_value::<Multisig>(wrong_total).is_err());
+
+        let mut bad_key = value;
+        bad_key["public_keys"][1] = "02abcd".into();
+        assert!(serde_json::from_value::<Multisig>(bad_key).is_err());
+    }
+
+    #[test]
+    fn test_signature_collector() {
+        let keypairs: Vec<_> = (0..5)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let multisig =
+            Multisig::new(3, 5, keypairs.iter().map(|k| k.public_key()).collect()).unwrap();
+        let message = b"release v1.0.0";
+        let sign =
+            |i: usize| crate::sign_message(keypairs[i].secret_key.expose(), message).unwrap();
+        let counted = |index, threshold_reached| {
+            Some(Collected {
+                index,
+                threshold_reached,
+            })
+        };
+        #[cfg(feature = "events")]
+        let mut events = crate::governance::events::subscribe();
+
+        let mut collector = multisig.collector(message);
+        // A second session over the same message is told apart by its id
+        let mut second = multisig.collector(message);
+        assert_ne!(collector.id(), second.id());
+        assert_eq!(collector.add(sign(4)).unwrap(), counted(4, false));
+        // A repeat and a signature over another message do not count
+        assert_eq!(collector.add(sign(4)).unwrap(), None);
+        let other = crate::sign_message(keypairs[0].secret_key.expose(), b"other").unwrap();
+        assert_eq!(collector.add(other).unwrap(), None);
+        assert_eq!(collector.add(sign(1)).unwrap(), counted(1, false));
+        assert!(!collector.is_met());
+        assert_eq!(second.add(sign(3)).unwrap(), counted(3, false));
+        assert_eq!(collector.add(sign(2)).unwrap(), counted(2, true));
+        assert!(collector.is_met());
+        assert_eq!(
+            collector.sign_with(&keypairs[0]).unwrap(),
+            counted(0, false)
+        );
+        assert_eq!(collector.collected(), 4);
+        assert_eq!(
+            collector.signatures(),
+            vec![sign(0), sign(1), sign(2), sign(4)]
+        );
+
+        // Fired once, by the third distinct signer; other tests emit
+        // events concurrently, so look for this collector's only
+        #[cfg(feature = "events")]
+        {
+            use crate::governance::events::GovernanceEvent;
+            let mut reached = Vec::new();
+            while let Ok(event) = events.try_recv() {
+                if let GovernanceEvent::ThresholdReached {
+                    collector: id,
+                    message_digest,
+                    by_key,
+                } = event
+                {
+                    if id == collector.id() {
+                        assert_eq!(message_digest, hex::encode(Sha256::digest(message)));
+                        reached.push(by_key);
+                    }
+                }
+            }
+            assert_eq!(reached, vec![2]);
+        }
+    }
+
+    #[test]
+    fn test_remaining_requirement() {
+        let keypairs: Vec<_> = (0..5)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+
+        let multisig = Multisig::new(3, 5, public_keys).unwrap();
+        let message = b"test message";
+
+        // Keys 1 and 3 have signed; key 1 signed twice
+        let signatures: Vec<_> = [1, 3, 1]
+            .iter()
+            .map(|&i| crate::sign_message(keypairs[i].secret_key.expose(), message).unwrap())
+            .collect();
+
+        let remaining = multisig
+            .remaining_requirement(message, &signatures)
+            .unwrap();
+        assert_eq!(remaining.count, 1);
+        assert_eq!(remaining.eligible, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_verify_with_committee() {
+        use crate::governance::nested_multisig::{Team, TeamMaintainer};
+
+        let keypairs: Vec<_> = (0..5)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let team = |id: &str, members: &[(&str, usize)]| Team {
+            id: id.to_string(),
+            name: format!("Team {}", id),
+            maintainers: members
+                .iter()
+                .map(|&(github, i)| TeamMaintainer {
+                    github: github.to_string(),
+                    public_key: public_keys[i].clone(),
+                })
+                .collect(),
+        };
+        // Key 4 belongs to no team
+        let committee = Committee::new(vec![
+            team("core", &[("alice", 0), ("bob", 1)]),
+            team("infra", &[("carol", 2), ("dave", 3)]),
+        ])
+        .unwrap();
+        let multisig = Multisig::new(3, 5, public_keys.clone()).unwrap();
+        let message = b"test message";
+
+        // Alice, Carol and Bob sign; Alice twice
+        let signatures: Vec<_> = [0, 2, 1, 0]
+            .iter()
+            .map(|&i| crate::sign_message(keypairs[i].secret_key.expose(), message).unwrap())
+            .collect();
+        let report = multisig
+            .verify_with_committee(message, &signatures, &committee)
+            .unwrap();
+
+        assert!(report.threshold_met);
+        let signers: Vec<_> = report
+            .signers
+            .iter()
+            .map(|s| (s.key_index, s.team_id.as_deref(), s.github.as_deref()))
+            .collect();
+        assert_eq!(
+            signers,
+            vec![
+                (0, Some("core"), Some("alice")),
+                (1, Some("core"), Some("bob")),
+                (2, Some("infra"), Some("carol")),
+            ]
+        );
+        let counts: Vec<_> = report
+            .teams
+            .iter()
+            .map(|t| (t.team_id.as_str(), t.signed, t.members))
+            .collect();
+        assert_eq!(counts, vec![("core", 2, 2), ("infra", 1, 2)]);
+        assert_eq!(report.unassigned, 0);
+        let fpr = |i: usize| public_keys[i].short();
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "Team core (core): 2 of 2 signed (alice [{}], bob [{}])\n\
+                 Team infra (infra): 1 of 2 signed (carol [{}])\n",
+                fpr(0),
+                fpr(1),
+                fpr(2)
+            )
+        );
+
+        // A signer outside the committee is unassigned
+        let signature = crate::sign_message(keypairs[4].secret_key.expose(), message).unwrap();
+        let report = multisig
+            .verify_with_committee(message, &[signature], &committee)
+            .unwrap();
+        assert!(!report.threshold_met);
+        assert_eq!(report.unassigned, 1);
+        assert!(report.teams.iter().all(|t| t.signed == 0));
+        assert_eq!(report.signers[0].fingerprint, fpr(4));
+        assert!(report
+            .to_string()
+            .ends_with(&format!("No team: 1 signed ({})\n", fpr(4))));
+    }
+
+    #[test]
+    fn test_to_descriptor() {
+        // The public keys of secret keys 1, 2 and 3, deliberately unsorted
+        let keys = [
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+        ];
+        let public_keys: Vec<_> = keys
+            .iter()
+            .map(|key| PublicKey::from_bytes(&hex::decode(key).unwrap()).unwrap())
+            .collect();
+        let multisig = Multisig::new(2, 3, public_keys).unwrap();
+
+        let multi = format!("multi(2,{},{},{})", keys[0], keys[1], keys[2]);
+        assert_eq!(
+            multisig.to_descriptor(ScriptKind::P2wsh).unwrap(),
+            format!("wsh({})#h2qhwaws", multi)
+        );
+        assert_eq!(
+            multisig.to_descriptor(ScriptKind::P2sh).unwrap(),
+            format!("sh({})#fa9xsf5f", multi)
+        );
+        assert_eq!(
+            multisig.to_descriptor(ScriptKind::P2shP2wsh).unwrap(),
+            format!("sh(wsh({}))#6r0zrmsm", multi)
+        );
+
+        // BIP380 checksum vector
+        assert_eq!(descriptor_checksum("raw(deadbeef)"), "89f8spxm");
+
+        // P2SH scripts hold fewer keys than witness scripts
+        let public_keys: Vec<_> = (0..16)
+            .map(|_| GovernanceKeypair::generate().unwrap().public_key())
+            .collect();
+        let multisig = Multisig::new(2, 16, public_keys).unwrap();
+        assert!(multisig.to_descriptor(ScriptKind::P2sh).is_err());
+        assert!(multisig.to_descriptor(ScriptKind::P2wsh).is_ok());
+    }
+
+    #[test]
+    fn test_witness_script_and_address() {
+        // BIP67 test vector 1: 2-of-2 with keys in reverse order
+        let keys = [
+            "02fe6f0a5a297eb38c391581c4413e084773ea23954d93f7753db7dc0adc188b2f",
+            "02ff12471208c14bd580709cb2358d98975247d8765f92bc25eab3b2763ed605f8",
+        ];
+        let public_keys: Vec<_> = keys
+            .iter()
+            .rev()
+            .map(|key| PublicKey::from_bytes(&hex::decode(key).unwrap()).unwrap())
+            .collect();
+        let multisig = Multisig::new(2, 2, public_keys).unwrap();
+
+        assert_eq!(
+            hex::encode(multisig.witness_script().unwrap().as_bytes()),
+            format!("5221{}21{}52ae", keys[0], keys[1])
+        );
+        assert_eq!(
+            multisig.to_address(Network::Bitcoin).unwrap().to_string(),
+            "bc1qknwt9mhqpd7hrjrvpqz57zjqk28xlp2h90te6v22en0m3uctnams3pq5ce"
+        );
+        assert_eq!(
+            multisig.to_address(Network::Testnet).unwrap().to_string(),
+            "tb1qknwt9mhqpd7hrjrvpqz57zjqk28xlp2h90te6v22en0m3uctnamsxfkmzk"
+        );
+    }
+
+    #[test]
+    fn test_p2wsh_address() {
+        // The keys of test_to_descriptor, unsorted
+        let keys = [
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+        ];
+        let public_keys: Vec<_> = keys
+            .iter()
+            .map(|key| PublicKey::from_bytes(&hex::decode(key).unwrap()).unwrap())
+            .collect();
+        let multisig = Multisig::new(2, 3, public_keys.clone()).unwrap();
+
+        let mainnet = multisig
+            .p2wsh_address(Network::Bitcoin)
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            mainnet,
+            "bc1qxqvgnukh04w787z9vtrgfy43p4ldmpd9th5cpe7v2cqncntpa56qgpu0t7"
+        );
+        // Version 0 witness program of a 32-byte script hash
+        assert!(mainnet.starts_with("bc1q"));
+        assert_eq!(mainnet.len(), 62);
+        assert_eq!(
+            multisig
+                .p2wsh_address(Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "tb1qxqvgnukh04w787z9vtrgfy43p4ldmpd9th5cpe7v2cqncntpa56qlf2q33"
+        );
+        assert_eq!(
+            multisig
+                .p2wsh_address(Network::Regtest)
+                .unwrap()
+                .to_string(),
+            "bcrt1qxqvgnukh04w787z9vtrgfy43p4ldmpd9th5cpe7v2cqncntpa56qjsqxyt"
+        );
+
+        // Stored order, unlike to_address; the same once the keys are sorted
+        assert_ne!(
+            multisig.p2wsh_address(Network::Bitcoin).unwrap(),
+            multisig.to_address(Network::Bitcoin).unwrap()
+        );
+        let mut sorted = public_keys;
+        sorted.sort_by_key(PublicKey::to_bytes);
+        let sorted = Multisig::new(2, 3, sorted).unwrap();
+        assert_eq!(
+            sorted.p2wsh_address(Network::Bitcoin).unwrap(),
+            multisig.to_address(Network::Bitcoin).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_witness_script_key_limit() {
+        let public_keys: Vec<_> = (0..=MAX_SCRIPT_KEYS)
+            .map(|_| GovernanceKeypair::generate().unwrap().public_key())
+            .collect();
+        let multisig = Multisig::new(2, MAX_SCRIPT_KEYS + 1, public_keys).unwrap();
+        assert!(matches!(
+            multisig.witness_script(),
+            Err(GovernanceError::InvalidMultisig(_))
+        ));
+        assert!(matches!(
+            multisig.p2wsh_address(Network::Bitcoin),
+            Err(GovernanceError::InvalidMultisig(_))
+        ));
+    }
+
+    #[test]
+    fn test_weighted_approval_ratio() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let keys: Vec<_> = keypairs
+            .iter()
+            .map(|kp| kp.public_key())
+            .zip([3, 1, 1])
+            .collect();
+        let multisig = WeightedMultisig::new(4, keys.clone()).unwrap();
+        assert_eq!(multisig.total_weight(), 5);
+        let message = b"test message";
+        let sign =
+            |i: usize| crate::sign_message(keypairs[i].secret_key.expose(), message).unwrap();
+
+        // Only the weight-3 key signs, twice
+        let signatures = [sign(0), sign(0)];
+        assert_eq!(multisig.signed_weight(message, &signatures).unwrap(), 3);
+        assert_eq!(multisig.approval_ratio(message, &signatures).unwrap(), 0.6);
+        assert!(!multisig.verify(message, &signatures).unwrap());
+
+        let signatures = [sign(0), sign(2)];
+        assert_eq!(multisig.approval_ratio(message, &signatures).unwrap(), 0.8);
+        assert!(multisig.verify(message, &signatures).unwrap());
+        assert_eq!(multisig.approval_ratio(message, &[]).unwrap(), 0.0);
+
+        // All-zero weights cannot meet any threshold
+        let zero: Vec<_> = keys.iter().map(|(key, _)| (key.clone(), 0)).collect();
+        assert!(WeightedMultisig::new(1, zero).is_err());
+        assert!(WeightedMultisig::new(0, keys.clone()).is_err());
+        assert!(WeightedMultisig::new(6, keys.clone()).is_err());
+        let duplicate = vec![keys[0].clone(), keys[0].clone()];
+        assert!(WeightedMultisig::new(1, duplicate).is_err());
+    }
 }
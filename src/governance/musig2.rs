@@ -0,0 +1,743 @@
+//! # MuSig2
+//!
+//! BIP327 MuSig2: several maintainers jointly produce one BIP340 Schnorr
+//! signature under an aggregate key, indistinguishable on chain from a
+//! single signer's.
+//!
+//! Signing takes two rounds. Each participant draws a secret nonce with
+//! [`PartialSigner::generate_nonce`] and shares its [`PublicNonce`]; once
+//! every nonce is in, each signs with [`PartialSigner::sign`] and shares its
+//! [`PartialSignature`], which the others check as they add it. Any
+//! participant holding every partial signature can then
+//! [`aggregate`](PartialSigner::aggregate) them.
+//!
+//! Keys are aggregated in the order given (BIP327 `KeySort` is left to the
+//! caller), without tweaks. As everywhere in the SDK the message is hashed
+//! with SHA256 before signing, so the result verifies with
+//! [`verify_schnorr`](crate::governance::verify_schnorr) under
+//! [`KeyAggContext::aggregated_public_key`].
+//!
+//! A secret nonce must never sign twice: [`SecretNonce`] can be neither
+//! cloned nor serialized, and signing consumes it.
+
+#[cfg(any(feature = "signing", test))]
+use rand::{rngs::OsRng, RngCore};
+use secp256k1::constants::GENERATOR_X;
+#[cfg(any(feature = "signing", test))]
+use secp256k1::SecretKey;
+use secp256k1::{Parity, PublicKey as Secp256k1PublicKey, Scalar};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+#[cfg(any(feature = "signing", test))]
+use crate::governance::context::signing_context;
+use crate::governance::context::verification_context;
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::signatures::reduce_scalar;
+use crate::governance::{PublicKey, SchnorrSignature};
+#[cfg(any(feature = "signing", test))]
+use crate::util::redact::REDACTED;
+
+/// Key aggregation of the participants' public keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAggContext {
+    public_keys: Vec<PublicKey>,
+    coefficients: Vec<Scalar>,
+    aggregate: Secp256k1PublicKey,
+}
+
+impl KeyAggContext {
+    /// Aggregate `public_keys`, in order, with the BIP327 `KeyAgg` algorithm
+    ///
+    /// Each key must appear once, so that every participant is told apart
+    /// by its key.
+    pub fn new(public_keys: &[PublicKey]) -> GovernanceResult<Self> {
+        if public_keys.is_empty() {
+            return Err(GovernanceError::InvalidMultisig(
+                "MuSig2 needs at least one public key".to_string(),
+            ));
+        }
+        let unique: std::collections::HashSet<_> = public_keys.iter().collect();
+        if unique.len() != public_keys.len() {
+            return Err(GovernanceError::InvalidMultisig(
+                "Duplicate public keys not allowed".to_string(),
+            ));
+        }
+
+        let encoded: Vec<[u8; 33]> = public_keys.iter().map(PublicKey::to_bytes).collect();
+        let parts: Vec<&[u8]> = encoded.iter().map(|key| &key[..]).collect();
+        let list_hash = tagged_hash("KeyAgg list", &parts);
+
+        // The second key's coefficient is one, which saves a multiplication
+        // when signing and verifying
+        let coefficients: Vec<Scalar> = encoded
+            .iter()
+            .enumerate()
+            .map(|(i, key)| match i {
+                1 => Scalar::ONE,
+                _ => hash_scalar("KeyAgg coefficient", &[&list_hash, key]),
+            })
+            .collect();
+
+        let terms = public_keys
+            .iter()
+            .zip(&coefficients)
+            .map(|(key, coefficient)| mul(key.inner, coefficient))
+            .collect::<GovernanceResult<Vec<_>>>()?;
+
+        Ok(Self {
+            public_keys: public_keys.to_vec(),
+            coefficients,
+            aggregate: combine(&terms)?,
+        })
+    }
+
+    /// The aggregate public key; signatures verify under its x coordinate
+    pub fn aggregated_public_key(&self) -> PublicKey {
+        PublicKey {
+            inner: self.aggregate,
+        }
+    }
+
+    /// The participants' public keys, in aggregation order
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    fn index_of(&self, public_key: &PublicKey) -> GovernanceResult<usize> {
+        self.public_keys
+            .iter()
+            .position(|key| key == public_key)
+            .ok_or_else(|| {
+                GovernanceError::InvalidInput(format!(
+                    "{} is not a participant",
+                    public_key.short()
+                ))
+            })
+    }
+}
+
+/// A participant's public nonce, shared in the first round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicNonce {
+    r1: Secp256k1PublicKey,
+    r2: Secp256k1PublicKey,
+}
+
+impl PublicNonce {
+    /// The two compressed nonce points, 66 bytes
+    pub fn to_bytes(&self) -> [u8; 66] {
+        let mut bytes = [0u8; 66];
+        bytes[..33].copy_from_slice(&self.r1.serialize());
+        bytes[33..].copy_from_slice(&self.r2.serialize());
+        bytes
+    }
+
+    /// Read a nonce written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
+        if bytes.len() != 66 {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Public nonce must be 66 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        let point = |bytes: &[u8]| {
+            Secp256k1PublicKey::from_slice(bytes)
+                .map_err(|e| GovernanceError::InvalidInput(format!("Invalid public nonce: {}", e)))
+        };
+        Ok(Self {
+            r1: point(&bytes[..33])?,
+            r2: point(&bytes[33..])?,
+        })
+    }
+}
+
+impl fmt::Display for PublicNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+/// A participant's secret nonce, for one signature only
+#[cfg(any(feature = "signing", test))]
+pub struct SecretNonce {
+    k1: SecretKey,
+    k2: SecretKey,
+    public_key: PublicKey,
+    public_nonce: PublicNonce,
+}
+
+#[cfg(any(feature = "signing", test))]
+impl SecretNonce {
+    /// The matching nonce to share with the other participants
+    pub fn public_nonce(&self) -> &PublicNonce {
+        &self.public_nonce
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
+impl fmt::Debug for SecretNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretNonce")
+            .field("public_key", &self.public_key)
+            .field("public_nonce", &self.public_nonce)
+            .field("secret", &REDACTED)
+            .finish()
+    }
+}
+
+/// A participant's share of the signature, shared in the second round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature([u8; 32]);
+
+impl PartialSignature {
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Read a partial signature, a scalar below the curve order
+    pub fn from_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+            GovernanceError::InvalidSignatureFormat(format!(
+                "Partial signature must be 32 bytes, got {}",
+                bytes.len()
+            ))
+        })?;
+        Scalar::from_be_bytes(bytes).map_err(|_| {
+            GovernanceError::InvalidSignatureFormat(
+                "Partial signature is not below the curve order".to_string(),
+            )
+        })?;
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for PartialSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+/// One participant's view of a MuSig2 signing session over one message
+///
+/// Tracks the public nonce and partial signature of every participant, by
+/// their position in the [`KeyAggContext`].
+#[derive(Debug, Clone)]
+pub struct PartialSigner {
+    key_agg: KeyAggContext,
+    message_hash: [u8; 32],
+    nonces: Vec<Option<PublicNonce>>,
+    partial_signatures: Vec<Option<PartialSignature>>,
+}
+
+/// Values every participant derives once all nonces are known
+struct Session {
+    /// Coefficient of the second nonces
+    b: Scalar,
+    /// Final nonce point
+    r: Secp256k1PublicKey,
+    /// BIP340 challenge
+    e: Scalar,
+}
+
+impl PartialSigner {
+    /// Start a session signing `message` under `key_agg`
+    pub fn new(key_agg: KeyAggContext, message: &[u8]) -> Self {
+        let participants = key_agg.public_keys.len();
+        Self {
+            key_agg,
+            message_hash: Sha256::digest(message).into(),
+            nonces: vec![None; participants],
+            partial_signatures: vec![None; participants],
+        }
+    }
+
+    pub fn key_agg(&self) -> &KeyAggContext {
+        &self.key_agg
+    }
+
+    /// Draw this participant's nonce (first round) and record its public
+    /// half
+    ///
+    /// Follows BIP327 `NonceGen`, with fresh randomness mixed with the
+    /// secret key, the aggregate key and the message.
+    #[cfg(any(feature = "signing", test))]
+    pub fn generate_nonce(&mut self, secret_key: &SecretKey) -> GovernanceResult<SecretNonce> {
+        let public_key = public_key_of(secret_key);
+        self.key_agg.index_of(&public_key)?;
+
+        let mut rand = [0u8; 32];
+        OsRng
+            .try_fill_bytes(&mut rand)
+            .map_err(|e| GovernanceError::Cryptographic(format!("entropy unavailable: {}", e)))?;
+        let aux = tagged_hash("MuSig/aux", &[&rand]);
+        for (byte, (secret, aux)) in rand
+            .iter_mut()
+            .zip(secret_key.secret_bytes().iter().zip(aux))
+        {
+            *byte = secret ^ aux;
+        }
+
+        let public_key_bytes = public_key.to_bytes();
+        let aggregate_x = x_bytes(&self.key_agg.aggregate);
+        let message_length = (self.message_hash.len() as u64).to_be_bytes();
+        let nonce = |i: u8| {
+            let k = reduce_scalar(tagged_hash(
+                "MuSig/nonce",
+                &[
+                    &rand,
+                    &[33],
+                    &public_key_bytes,
+                    &[32],
+                    &aggregate_x,
+                    &[1],
+                    &message_length,
+                    &self.message_hash,
+                    &[0, 0, 0, 0],
+                    &[i],
+                ],
+            ));
+            SecretKey::from_slice(&k).map_err(|_| degenerate())
+        };
+        let (k1, k2) = (nonce(0)?, nonce(1)?);
+
+        let public_nonce = PublicNonce {
+            r1: public_key_of(&k1).inner,
+            r2: public_key_of(&k2).inner,
+        };
+        self.add_nonce(&public_key, public_nonce.clone())?;
+        Ok(SecretNonce {
+            k1,
+            k2,
+            public_key,
+            public_nonce,
+        })
+    }
+
+    /// Record another participant's public nonce
+    ///
+    /// A participant's nonce cannot change once recorded.
+    pub fn add_nonce(
+        &mut self,
+        public_key: &PublicKey,
+        nonce: PublicNonce,
+    ) -> GovernanceResult<()> {
+        let index = self.key_agg.index_of(public_key)?;
+        match &self.nonces[index] {
+            Some(recorded) if *recorded != nonce => Err(GovernanceError::InvalidInput(format!(
+                "{} already has a different nonce",
+                public_key.short()
+            ))),
+            _ => {
+                self.nonces[index] = Some(nonce);
+                Ok(())
+            }
+        }
+    }
+
+    /// Sign as this participant (second round), once every nonce is in
+    ///
+    /// The partial signature is checked before it is returned, and recorded.
+    #[cfg(any(feature = "signing", test))]
+    pub fn sign(
+        &mut self,
+        secret_nonce: SecretNonce,
+        secret_key: &SecretKey,
+    ) -> GovernanceResult<PartialSignature> {
+        let public_key = public_key_of(secret_key);
+        if public_key != secret_nonce.public_key {
+            return Err(GovernanceError::InvalidInput(
+                "Secret nonce was drawn for another key".to_string(),
+            ));
+        }
+        let index = self.key_agg.index_of(&public_key)?;
+        if self.nonces[index].as_ref() != Some(&secret_nonce.public_nonce) {
+            return Err(GovernanceError::InvalidInput(
+                "Secret nonce was drawn for another session".to_string(),
+            ));
+        }
+        let session = self.session()?;
+
+        let (mut k1, mut k2) = (secret_nonce.k1, secret_nonce.k2);
+        if !has_even_y(&session.r) {
+            k1 = k1.negate();
+            k2 = k2.negate();
+        }
+        let mut d = *secret_key;
+        if !has_even_y(&self.key_agg.aggregate) {
+            d = d.negate();
+        }
+
+        let bk2 = k2.mul_tweak(&session.b).map_err(|_| degenerate())?;
+        let ead = d
+            .mul_tweak(&self.key_agg.coefficients[index])
+            .and_then(|ad| ad.mul_tweak(&session.e))
+            .map_err(|_| degenerate())?;
+        let s = add_scalars(
+            add_scalars(k1.secret_bytes(), bk2.secret_bytes()),
+            ead.secret_bytes(),
+        );
+
+        let partial_signature = PartialSignature(s);
+        self.add_partial_signature(&public_key, partial_signature)?;
+        Ok(partial_signature)
+    }
+
+    /// Check and record another participant's partial signature
+    pub fn add_partial_signature(
+        &mut self,
+        public_key: &PublicKey,
+        partial_signature: PartialSignature,
+    ) -> GovernanceResult<()> {
+        let index = self.key_agg.index_of(public_key)?;
+        if !self.verify_partial(index, &partial_signature)? {
+            return Err(GovernanceError::SignatureVerification(format!(
+                "Invalid partial signature from {}",
+                public_key.short()
+            )));
+        }
+        self.partial_signatures[index] = Some(partial_signature);
+        Ok(())
+    }
+
+    /// Combine every participant's partial signature into the final
+    /// Schnorr signature
+    pub fn aggregate(&self) -> GovernanceResult<SchnorrSignature> {
+        let session = self.session()?;
+        let mut s = [0u8; 32];
+        for (key, partial_signature) in self
+            .key_agg
+            .public_keys
+            .iter()
+            .zip(&self.partial_signatures)
+        {
+            let partial_signature = partial_signature.ok_or_else(|| {
+                GovernanceError::InvalidInput(format!(
+                    "Missing partial signature from {}",
+                    key.short()
+                ))
+            })?;
+            s = add_scalars(s, partial_signature.0);
+        }
+
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&x_bytes(&session.r));
+        signature[32..].copy_from_slice(&s);
+        SchnorrSignature::from_bytes(&signature)
+    }
+
+    /// Whether a partial signature is valid for the participant at `index`
+    fn verify_partial(
+        &self,
+        index: usize,
+        partial_signature: &PartialSignature,
+    ) -> GovernanceResult<bool> {
+        let session = self.session()?;
+        let nonce = self.nonces[index]
+            .as_ref()
+            .expect("session has every nonce");
+        let secp = verification_context();
+
+        let mut r = combine(&[nonce.r1, mul(nonce.r2, &session.b)?])?;
+        if !has_even_y(&session.r) {
+            r = r.negate(secp);
+        }
+        let mut key = self.key_agg.public_keys[index].inner;
+        if !has_even_y(&self.key_agg.aggregate) {
+            key = key.negate(secp);
+        }
+        let challenge = mul(mul(key, &self.key_agg.coefficients[index])?, &session.e)?;
+        let expected = combine(&[r, challenge])?;
+
+        let s = Scalar::from_be_bytes(partial_signature.0).map_err(|_| degenerate())?;
+        Ok(matches!(mul(generator(), &s), Ok(point) if point == expected))
+    }
+
+    fn session(&self) -> GovernanceResult<Session> {
+        let mut r1 = Vec::with_capacity(self.nonces.len());
+        let mut r2 = Vec::with_capacity(self.nonces.len());
+        for (key, nonce) in self.key_agg.public_keys.iter().zip(&self.nonces) {
+            let nonce = nonce.as_ref().ok_or_else(|| {
+                GovernanceError::InvalidInput(format!("Missing nonce from {}", key.short()))
+            })?;
+            r1.push(nonce.r1);
+            r2.push(nonce.r2);
+        }
+        let aggregate_nonce = PublicNonce {
+            r1: combine(&r1)?,
+            r2: combine(&r2)?,
+        };
+
+        let aggregate_x = x_bytes(&self.key_agg.aggregate);
+        let b = hash_scalar(
+            "MuSig/noncecoef",
+            &[
+                &aggregate_nonce.to_bytes(),
+                &aggregate_x,
+                &self.message_hash,
+            ],
+        );
+        let r = combine(&[aggregate_nonce.r1, mul(aggregate_nonce.r2, &b)?])?;
+        let e = hash_scalar(
+            "BIP0340/challenge",
+            &[&x_bytes(&r), &aggregate_x, &self.message_hash],
+        );
+        Ok(Session { b, r, e })
+    }
+}
+
+/// BIP340 tagged hash of the concatenated `parts`
+fn tagged_hash(tag: &str, parts: &[&[u8]]) -> [u8; 32] {
+    let tag = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag);
+    hasher.update(tag);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// A tagged hash as a scalar, modulo the curve order
+fn hash_scalar(tag: &str, parts: &[&[u8]]) -> Scalar {
+    Scalar::from_be_bytes(reduce_scalar(tagged_hash(tag, parts)))
+        .expect("reduced below the curve order")
+}
+
+/// Sum of two scalars modulo the curve order
+fn add_scalars(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let mut sum = [0u8; 32];
+    let mut carry = 0u16;
+    for i in (0..32).rev() {
+        let byte = a[i] as u16 + b[i] as u16 + carry;
+        sum[i] = byte as u8;
+        carry = byte >> 8;
+    }
+    if carry == 0 {
+        return reduce_scalar(sum);
+    }
+
+    // The sum overflowed 256 bits: subtracting the order wraps it back
+    let order = secp256k1::constants::CURVE_ORDER;
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let byte = sum[i] as i16 - order[i] as i16 - borrow;
+        borrow = (byte < 0) as i16;
+        sum[i] = byte.rem_euclid(256) as u8;
+    }
+    sum
+}
+
+fn x_bytes(point: &Secp256k1PublicKey) -> [u8; 32] {
+    point.x_only_public_key().0.serialize()
+}
+
+fn has_even_y(point: &Secp256k1PublicKey) -> bool {
+    point.x_only_public_key().1 == Parity::Even
+}
+
+fn generator() -> Secp256k1PublicKey {
+    let mut bytes = [0x02; 33];
+    bytes[1..].copy_from_slice(&GENERATOR_X);
+    Secp256k1PublicKey::from_slice(&bytes).expect("the generator is a valid point")
+}
+
+fn mul(point: Secp256k1PublicKey, scalar: &Scalar) -> GovernanceResult<Secp256k1PublicKey> {
+    point
+        .mul_tweak(verification_context(), scalar)
+        .map_err(|_| degenerate())
+}
+
+fn combine(points: &[Secp256k1PublicKey]) -> GovernanceResult<Secp256k1PublicKey> {
+    let points: Vec<&Secp256k1PublicKey> = points.iter().collect();
+    Secp256k1PublicKey::combine_keys(&points).map_err(|_| degenerate())
+}
+
+#[cfg(any(feature = "signing", test))]
+fn public_key_of(secret_key: &SecretKey) -> PublicKey {
+    PublicKey {
+        inner: Secp256k1PublicKey::from_secret_key(signing_context(), secret_key),
+    }
+}
+
+/// The negligible cases BIP327 handles with the point at infinity or a
+/// zero scalar, refused here
+fn degenerate() -> GovernanceError {
+    GovernanceError::Cryptographic(
+        "MuSig2 computation reached the point at infinity or a zero scalar".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::{verify_schnorr, GovernanceKeypair};
+
+    fn key(hex_key: &str) -> PublicKey {
+        PublicKey::from_bytes(&hex::decode(hex_key).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_key_aggregation_vectors() {
+        // BIP327 key_agg_vectors.json
+        let x1 = key("02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+        let x2 = key("03dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659");
+        let x3 = key("023590a94e768f8e1815c2f24b4d80a8e3149316c3518ce7b7ad338368d038ca66");
+
+        let aggregate_x = |keys: &[PublicKey]| {
+            let context = KeyAggContext::new(keys).unwrap();
+            hex::encode(x_bytes(&context.aggregated_public_key().inner))
+        };
+        assert_eq!(
+            aggregate_x(&[x1.clone(), x2.clone(), x3.clone()]),
+            "90539eede565f5d054f32cc0c220126889ed1e5d193baf15aef344fe59d4610c"
+        );
+        assert_eq!(
+            aggregate_x(&[x3.clone(), x2.clone(), x1.clone()]),
+            "6204de8b083426dc6eaf9502d27024d53fc826bf7d2012148a0575435df54b2b"
+        );
+
+        assert!(KeyAggContext::new(&[]).is_err());
+        assert!(KeyAggContext::new(&[x1.clone(), x2, x1]).is_err());
+    }
+
+    #[test]
+    fn test_add_scalars() {
+        let order = secp256k1::constants::CURVE_ORDER;
+        let mut below = order;
+        below[31] -= 1;
+        let mut two = [0u8; 32];
+        two[31] = 2;
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        // (n - 1) + 2 = 1, and (n - 1) + (n - 1) = n - 2
+        assert_eq!(add_scalars(below, two), one);
+        let mut expected = order;
+        expected[31] -= 2;
+        assert_eq!(add_scalars(below, below), expected);
+    }
+
+    #[test]
+    fn test_three_party_signing() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let context = KeyAggContext::new(&public_keys).unwrap();
+        let message = b"Release v1.0.0";
+
+        let mut signers: Vec<_> = keypairs
+            .iter()
+            .map(|_| PartialSigner::new(context.clone(), message))
+            .collect();
+
+        // Round 1: every participant draws a nonce and shares it
+        let mut secret_nonces = Vec::new();
+        for (signer, keypair) in signers.iter_mut().zip(&keypairs) {
+            secret_nonces.push(signer.generate_nonce(keypair.secret_key.expose()).unwrap());
+        }
+        for signer in signers.iter_mut() {
+            for (key, nonce) in public_keys.iter().zip(&secret_nonces) {
+                signer.add_nonce(key, nonce.public_nonce().clone()).unwrap();
+            }
+        }
+
+        // Round 2: every participant signs and shares its partial signature
+        let mut partial_signatures = Vec::new();
+        for ((signer, keypair), nonce) in signers.iter_mut().zip(&keypairs).zip(secret_nonces) {
+            partial_signatures.push(signer.sign(nonce, keypair.secret_key.expose()).unwrap());
+        }
+        for signer in signers.iter_mut() {
+            for (key, partial_signature) in public_keys.iter().zip(&partial_signatures) {
+                signer
+                    .add_partial_signature(key, *partial_signature)
+                    .unwrap();
+            }
+        }
+
+        // Every participant aggregates the same BIP340 signature
+        let signature = signers[0].aggregate().unwrap();
+        for signer in &signers[1..] {
+            assert_eq!(signer.aggregate().unwrap(), signature);
+        }
+        let aggregate_key = context.aggregated_public_key();
+        assert!(verify_schnorr(&signature, message, &aggregate_key).unwrap());
+        assert!(!verify_schnorr(&signature, b"Release v1.0.1", &aggregate_key).unwrap());
+        assert!(!verify_schnorr(&signature, message, &public_keys[0]).unwrap());
+
+        // A partial signature only verifies for its own participant
+        let mut checker = PartialSigner::new(context.clone(), message);
+        for (key, nonce) in public_keys.iter().zip(&signers[0].nonces) {
+            checker.add_nonce(key, nonce.clone().unwrap()).unwrap();
+        }
+        assert!(checker
+            .add_partial_signature(&public_keys[1], partial_signatures[0])
+            .is_err());
+        // and nonces cannot be swapped afterwards
+        assert!(checker
+            .add_nonce(&public_keys[0], signers[0].nonces[1].clone().unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_signing_out_of_order() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let context = KeyAggContext::new(&public_keys).unwrap();
+        let mut signer = PartialSigner::new(context, b"message");
+
+        // Signing needs every nonce
+        let nonce = signer
+            .generate_nonce(keypairs[0].secret_key.expose())
+            .unwrap();
+        let err = signer
+            .sign(nonce, keypairs[0].secret_key.expose())
+            .unwrap_err();
+        assert!(err.to_string().contains("Missing nonce"), "{}", err);
+
+        // Aggregating needs every partial signature
+        assert!(signer.aggregate().is_err());
+
+        // Only participants take part
+        let outsider = GovernanceKeypair::generate().unwrap();
+        assert!(signer.generate_nonce(outsider.secret_key.expose()).is_err());
+
+        // A secret nonce signs for its own key only
+        let nonce = signer
+            .generate_nonce(keypairs[1].secret_key.expose())
+            .unwrap();
+        assert!(signer.sign(nonce, keypairs[2].secret_key.expose()).is_err());
+    }
+
+    #[test]
+    fn test_nonce_and_partial_signature_encoding() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let context = KeyAggContext::new(&[keypair.public_key()]).unwrap();
+        let mut signer = PartialSigner::new(context, b"message");
+        let nonce = signer.generate_nonce(keypair.secret_key.expose()).unwrap();
+
+        let public_nonce = nonce.public_nonce().clone();
+        assert_eq!(
+            PublicNonce::from_bytes(&public_nonce.to_bytes()).unwrap(),
+            public_nonce
+        );
+        assert!(PublicNonce::from_bytes(&[0x02; 65]).is_err());
+        assert!(!format!("{:?}", nonce).contains(&hex::encode(nonce.k1.secret_bytes())));
+
+        let partial_signature = signer.sign(nonce, keypair.secret_key.expose()).unwrap();
+        assert_eq!(
+            PartialSignature::from_bytes(&partial_signature.to_bytes()).unwrap(),
+            partial_signature
+        );
+        assert!(PartialSignature::from_bytes(&[0xff; 32]).is_err());
+        assert!(PartialSignature::from_bytes(&[0x01; 31]).is_err());
+
+        // Even alone, a participant's key is weighted by its coefficient
+        let signature = signer.aggregate().unwrap();
+        let aggregate_key = signer.key_agg().aggregated_public_key();
+        assert_ne!(aggregate_key, keypair.public_key());
+        assert!(verify_schnorr(&signature, b"message", &aggregate_key).unwrap());
+    }
+}
@@ -2,9 +2,18 @@
 //!
 //! Provides cryptographic primitives for nested multisig operations.
 //! Supports team-based signature aggregation for nested 7×7 multisig structure.
+//! A team can also hand its approval upstream as a [`TeamApprovalPacket`]
+//! countersigned by its lead, see [`NestedMultisig::verify_packets`].
 
 use crate::governance::error::{GovernanceError, GovernanceResult};
-use crate::governance::{PublicKey, Signature};
+use crate::governance::messages::join_fields;
+#[cfg(any(feature = "signing", test))]
+use crate::governance::GovernanceKeypair;
+use crate::governance::{Fingerprint, PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 
 /// Team structure for nested multisig
 #[derive(Debug, Clone)]
@@ -61,6 +70,8 @@ impl NestedMultisig {
             }
         }
 
+        check_distinct_keys(&teams)?;
+
         Ok(Self {
             teams,
             teams_required,
@@ -87,21 +98,15 @@ impl NestedMultisig {
             if let Some(team_id) = self.find_maintainer_team(github) {
                 team_signatures
                     .entry(team_id)
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push((github.clone(), signature.clone()));
             }
         }
 
-        // Count team approvals
-        let mut teams_approved = 0;
-        let mut total_maintainers_approved = 0;
-        let mut team_details = Vec::new();
-
+        let mut signed = Vec::new();
         for team in &self.teams {
-            let team_sigs = team_signatures.get(&team.id).map(|v| v.len()).unwrap_or(0);
-
             // Verify signatures for this team
-            let mut valid_sigs = 0;
+            let mut valid_sigs = Vec::new();
             if let Some(sigs) = team_signatures.get(&team.id) {
                 for (github, sig) in sigs {
                     // Find maintainer's public key
@@ -112,12 +117,161 @@ impl NestedMultisig {
                             message,
                             &maintainer.public_key,
                         )? {
-                            valid_sigs += 1;
+                            valid_sigs.push(&maintainer.public_key);
                         }
                     }
                 }
             }
+            signed.push(valid_sigs);
+        }
+
+        Ok(self.tally(&signed, Vec::new()))
+    }
+
+    /// Verify signatures that do not name their signer
+    ///
+    /// Each signature counts for the maintainers whose key it is valid
+    /// under, as for signature files collected without usernames.
+    pub fn verify_signatures(
+        &self,
+        message: &[u8],
+        signatures: &[Signature],
+    ) -> GovernanceResult<NestedMultisigResult> {
+        let mut signed = Vec::new();
+        for team in &self.teams {
+            let mut valid_sigs = Vec::new();
+            for maintainer in &team.maintainers {
+                for signature in signatures {
+                    if crate::governance::verify_signature(
+                        signature,
+                        message,
+                        &maintainer.public_key,
+                    )? {
+                        valid_sigs.push(&maintainer.public_key);
+                        break;
+                    }
+                }
+            }
+            signed.push(valid_sigs);
+        }
+
+        Ok(self.tally(&signed, Vec::new()))
+    }
+
+    /// Verify approvals handed over by teams as packets
+    ///
+    /// Each packet must approve `message` and carry a valid signature by
+    /// its lead, a member of the packet's team; otherwise the whole call
+    /// fails. A team's distinct valid member signatures, across all its
+    /// packets, count towards its threshold, and approved teams towards
+    /// the inter-team threshold. The result lists every packet in
+    /// [`NestedMultisigResult::packets`].
+    pub fn verify_packets(
+        &self,
+        message: &[u8],
+        packets: &[TeamApprovalPacket],
+    ) -> GovernanceResult<NestedMultisigResult> {
+        let digest = TeamApprovalPacket::digest(message);
+        let mut team_signers: HashMap<&str, HashSet<&str>> = HashMap::new();
+        let mut provenance = Vec::new();
+
+        for packet in packets {
+            let (team, signers) = self.check_packet(message, &digest, packet)?;
+            provenance.push(PacketProvenance {
+                team_id: team.id.clone(),
+                lead: packet.lead.clone(),
+                maintainers_signed: signers.len(),
+                meets_team_threshold: signers.len() >= self.maintainers_per_team_required,
+            });
+            team_signers.entry(&team.id).or_default().extend(signers);
+        }
+
+        let signed: Vec<Vec<&PublicKey>> = self
+            .teams
+            .iter()
+            .map(|team| {
+                let signers = team_signers.get(team.id.as_str());
+                team.maintainers
+                    .iter()
+                    .filter(|m| signers.is_some_and(|s| s.contains(m.github.as_str())))
+                    .map(|m| &m.public_key)
+                    .collect()
+            })
+            .collect();
+        Ok(self.tally(&signed, provenance))
+    }
 
+    /// Check a packet's digest and lead signature, and collect the members
+    /// with a valid signature over `message`
+    fn check_packet<'a>(
+        &'a self,
+        message: &[u8],
+        digest: &str,
+        packet: &TeamApprovalPacket,
+    ) -> GovernanceResult<(&'a Team, HashSet<&'a str>)> {
+        if packet.message_digest != digest {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Packet from team {} approves message {}, expected {}",
+                packet.team_id, packet.message_digest, digest
+            )));
+        }
+        let team = self
+            .teams
+            .iter()
+            .find(|t| t.id == packet.team_id)
+            .ok_or_else(|| {
+                GovernanceError::InvalidMultisig(format!(
+                    "Packet from unknown team {}",
+                    packet.team_id
+                ))
+            })?;
+        let lead = team
+            .maintainers
+            .iter()
+            .find(|m| m.github == packet.lead)
+            .ok_or_else(|| {
+                GovernanceError::SignatureVerification(format!(
+                    "Lead {} of the packet is not a member of team {}",
+                    packet.lead, team.id
+                ))
+            })?;
+        let lead_signature = parse_signature(&packet.lead_signature)?;
+        if !crate::governance::verify_signature(
+            &lead_signature,
+            &packet.signing_bytes(),
+            &lead.public_key,
+        )? {
+            return Err(GovernanceError::SignatureVerification(format!(
+                "Invalid lead signature by {} on the packet from team {}",
+                packet.lead, team.id
+            )));
+        }
+
+        let mut signers = HashSet::new();
+        for member in &packet.signatures {
+            if let Some(maintainer) = team.maintainers.iter().find(|m| m.github == member.github) {
+                let signature = parse_signature(&member.signature)?;
+                if crate::governance::verify_signature(&signature, message, &maintainer.public_key)?
+                {
+                    signers.insert(maintainer.github.as_str());
+                }
+            }
+        }
+        Ok((team, signers))
+    }
+
+    /// Count team and inter-team approvals from each team's valid signatures
+    fn tally(
+        &self,
+        signed: &[Vec<&PublicKey>],
+        packets: Vec<PacketProvenance>,
+    ) -> NestedMultisigResult {
+        let mut teams_approved = 0;
+        let mut total_maintainers_approved = 0;
+        let mut team_details = Vec::new();
+
+        for (team, signers) in self.teams.iter().zip(signed) {
+            let valid_sigs = signers.len();
             let team_approved = valid_sigs >= self.maintainers_per_team_required;
 
             if team_approved {
@@ -131,20 +285,30 @@ impl NestedMultisig {
                 maintainers_signed: valid_sigs,
                 maintainers_required: self.maintainers_per_team_required,
                 approved: team_approved,
+                signers: signers.iter().map(|key| key.short()).collect(),
             });
         }
 
         let inter_team_approved = teams_approved >= self.teams_required;
         let total_maintainers_required = self.teams_required * self.maintainers_per_team_required;
+        if !inter_team_approved {
+            emit_event!(NestedMultisigVerificationFailed {
+                teams_approved: teams_approved,
+                teams_required: self.teams_required,
+                maintainers_approved: total_maintainers_approved,
+                maintainers_required: total_maintainers_required,
+            });
+        }
 
-        Ok(NestedMultisigResult {
+        NestedMultisigResult {
             teams_approved,
             teams_required: self.teams_required,
             maintainers_approved: total_maintainers_approved,
             maintainers_required: total_maintainers_required,
             inter_team_approved,
             team_details,
-        })
+            packets,
+        }
     }
 
     /// Find which team a maintainer belongs to
@@ -158,6 +322,258 @@ impl NestedMultisig {
     }
 }
 
+/// Team membership of a committee's keys
+///
+/// Lets a flat [`Multisig`](crate::governance::Multisig) report its signers
+/// by team (see [`Multisig::verify_with_committee`](crate::governance::Multisig::verify_with_committee))
+/// without requiring approvals per team.
+#[derive(Debug, Clone)]
+pub struct Committee {
+    teams: Vec<Team>,
+    /// (team, maintainer) positions of every member
+    members: Vec<(usize, usize)>,
+    /// Index into `members` by key fingerprint
+    by_fingerprint: HashMap<Fingerprint, usize>,
+    /// Fingerprints shared by more than one member
+    collisions: HashSet<Fingerprint>,
+}
+
+impl Committee {
+    /// Create a committee; each public key must belong to one maintainer
+    pub fn new(teams: Vec<Team>) -> GovernanceResult<Self> {
+        check_distinct_keys(&teams)?;
+
+        let members: Vec<(usize, usize)> = teams
+            .iter()
+            .enumerate()
+            .flat_map(|(t, team)| (0..team.maintainers.len()).map(move |m| (t, m)))
+            .collect();
+        let mut by_fingerprint = HashMap::new();
+        let mut collisions = HashSet::new();
+        for (index, &(t, m)) in members.iter().enumerate() {
+            let fingerprint = teams[t].maintainers[m].public_key.fingerprint();
+            if by_fingerprint.insert(fingerprint, index).is_some() {
+                collisions.insert(fingerprint);
+            }
+        }
+
+        Ok(Self {
+            teams,
+            members,
+            by_fingerprint,
+            collisions,
+        })
+    }
+
+    /// The committee's teams
+    pub fn teams(&self) -> &[Team] {
+        &self.teams
+    }
+
+    /// The team and maintainer holding a public key
+    pub fn member(&self, public_key: &PublicKey) -> Option<(&Team, &TeamMaintainer)> {
+        let fingerprint = public_key.fingerprint();
+        if self.collisions.contains(&fingerprint) {
+            // Several members share the fingerprint; compare full keys
+            return self.teams.iter().find_map(|team| {
+                team.maintainers
+                    .iter()
+                    .find(|m| m.public_key == *public_key)
+                    .map(|maintainer| (team, maintainer))
+            });
+        }
+        self.member_by_fingerprint(&fingerprint)
+            .filter(|(_, maintainer)| maintainer.public_key == *public_key)
+    }
+
+    /// The team and maintainer whose key has this fingerprint
+    ///
+    /// `None` for non-members, and when several members share the
+    /// fingerprint; look those up by full key with [`member`](Self::member).
+    pub fn member_by_fingerprint(
+        &self,
+        fingerprint: &Fingerprint,
+    ) -> Option<(&Team, &TeamMaintainer)> {
+        if self.collisions.contains(fingerprint) {
+            return None;
+        }
+        let (t, m) = self.members[*self.by_fingerprint.get(fingerprint)?];
+        let team = &self.teams[t];
+        Some((team, &team.maintainers[m]))
+    }
+
+    /// What changed from this committee to `other`
+    ///
+    /// Maintainers are matched by GitHub handle: a handle only in `other`
+    /// was added, one only in `self` removed, and a handle in both may have
+    /// a new key, a new team, or both.
+    pub fn diff(&self, other: &Committee) -> CommitteeDiff {
+        let old = self.by_handle();
+        let new = other.by_handle();
+        let mut diff = CommitteeDiff::default();
+
+        for (github, (team, maintainer)) in &old {
+            if !new.contains_key(github) {
+                diff.removed.push(CommitteeMember::new(team, maintainer));
+            }
+        }
+        for (github, (new_team, new_maintainer)) in &new {
+            let Some((old_team, old_maintainer)) = old.get(github) else {
+                diff.added
+                    .push(CommitteeMember::new(new_team, new_maintainer));
+                continue;
+            };
+            if old_maintainer.public_key != new_maintainer.public_key {
+                diff.key_changes.push(KeyChange {
+                    github: github.to_string(),
+                    old_public_key: old_maintainer.public_key.to_string(),
+                    new_public_key: new_maintainer.public_key.to_string(),
+                });
+            }
+            if old_team.id != new_team.id {
+                diff.team_moves.push(TeamMove {
+                    github: github.to_string(),
+                    from_team: old_team.id.clone(),
+                    to_team: new_team.id.clone(),
+                });
+            }
+        }
+        diff
+    }
+
+    /// Members by GitHub handle, in handle order
+    fn by_handle(&self) -> BTreeMap<&str, (&Team, &TeamMaintainer)> {
+        let mut members = BTreeMap::new();
+        for team in &self.teams {
+            for maintainer in &team.maintainers {
+                members
+                    .entry(maintainer.github.as_str())
+                    .or_insert((team, maintainer));
+            }
+        }
+        members
+    }
+}
+
+/// Changes between two committees, from [`Committee::diff`]
+///
+/// Each list is in GitHub handle order. Public keys are hex.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CommitteeDiff {
+    pub added: Vec<CommitteeMember>,
+    pub removed: Vec<CommitteeMember>,
+    /// Maintainers whose handle stayed and whose key changed
+    pub key_changes: Vec<KeyChange>,
+    /// Maintainers who moved to another team
+    pub team_moves: Vec<TeamMove>,
+}
+
+/// A maintainer added to or removed from a committee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitteeMember {
+    pub github: String,
+    pub team_id: String,
+    pub public_key: String,
+}
+
+impl CommitteeMember {
+    fn new(team: &Team, maintainer: &TeamMaintainer) -> Self {
+        Self {
+            github: maintainer.github.clone(),
+            team_id: team.id.clone(),
+            public_key: maintainer.public_key.to_string(),
+        }
+    }
+}
+
+/// A key rotation of a maintainer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyChange {
+    pub github: String,
+    pub old_public_key: String,
+    pub new_public_key: String,
+}
+
+/// A maintainer's move between teams
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TeamMove {
+    pub github: String,
+    pub from_team: String,
+    pub to_team: String,
+}
+
+impl CommitteeDiff {
+    /// Whether the committees have the same members, keys and teams
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.key_changes.is_empty()
+            && self.team_moves.is_empty()
+    }
+}
+
+impl fmt::Display for CommitteeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        for member in &self.added {
+            writeln!(
+                f,
+                "+ {} (team {}): {}",
+                member.github, member.team_id, member.public_key
+            )?;
+        }
+        for member in &self.removed {
+            writeln!(
+                f,
+                "- {} (team {}): {}",
+                member.github, member.team_id, member.public_key
+            )?;
+        }
+        for change in &self.key_changes {
+            writeln!(
+                f,
+                "~ {}: key {} -> {}",
+                change.github, change.old_public_key, change.new_public_key
+            )?;
+        }
+        for moved in &self.team_moves {
+            writeln!(
+                f,
+                "~ {}: team {} -> {}",
+                moved.github, moved.from_team, moved.to_team
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Check each public key belongs to exactly one maintainer
+fn check_distinct_keys(teams: &[Team]) -> GovernanceResult<()> {
+    let mut seen: HashMap<&PublicKey, (&str, &str)> = HashMap::new();
+    for team in teams {
+        for maintainer in &team.maintainers {
+            if let Some((other_team, other_github)) =
+                seen.insert(&maintainer.public_key, (&team.id, &maintainer.github))
+            {
+                return Err(GovernanceError::InvalidMultisig(if other_team == team.id {
+                    format!(
+                        "Duplicate public key in team {}: {} and {}",
+                        team.id, other_github, maintainer.github
+                    )
+                } else {
+                    format!(
+                        "Duplicate public key across teams: {} in team {} and {} in team {}",
+                        other_github, other_team, maintainer.github, team.id
+                    )
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Result of nested multisig verification
 #[derive(Debug, Clone)]
 pub struct NestedMultisigResult {
@@ -167,6 +583,67 @@ pub struct NestedMultisigResult {
     pub maintainers_required: usize,
     pub inter_team_approved: bool,
     pub team_details: Vec<TeamApprovalStatus>,
+    /// Packets the approvals came from, empty unless verified with
+    /// [`NestedMultisig::verify_packets`]
+    pub packets: Vec<PacketProvenance>,
+}
+
+impl NestedMultisigResult {
+    /// Combine the results of verifying signatures submitted in separate rounds
+    ///
+    /// Counts are merged as reported, so the same signature may be counted
+    /// in several rounds; the signer fingerprints of each team are combined
+    /// without repeats. A team's merged
+    /// count is therefore the largest count any round reports for it (capped
+    /// at the team's requirement), and a team counts once towards
+    /// `teams_approved` however many rounds approved it. To combine partial
+    /// signatures from one team, verify the combined signatures instead.
+    pub fn merge(results: &[NestedMultisigResult]) -> NestedMultisigResult {
+        let mut team_details: Vec<TeamApprovalStatus> = Vec::new();
+        for detail in results.iter().flat_map(|r| &r.team_details) {
+            match team_details
+                .iter_mut()
+                .find(|t| t.team_id == detail.team_id)
+            {
+                Some(merged) => {
+                    merged.maintainers_signed =
+                        merged.maintainers_signed.max(detail.maintainers_signed);
+                    merged.maintainers_required =
+                        merged.maintainers_required.max(detail.maintainers_required);
+                    for signer in &detail.signers {
+                        if !merged.signers.contains(signer) {
+                            merged.signers.push(signer.clone());
+                        }
+                    }
+                }
+                None => team_details.push(detail.clone()),
+            }
+        }
+        for team in &mut team_details {
+            team.maintainers_signed = team.maintainers_signed.min(team.maintainers_required);
+            team.approved = team.maintainers_signed >= team.maintainers_required;
+        }
+
+        let approved = team_details.iter().filter(|t| t.approved);
+        let teams_approved = approved.clone().count();
+        let maintainers_approved = approved.map(|t| t.maintainers_signed).sum();
+        let teams_required = results.iter().map(|r| r.teams_required).max().unwrap_or(0);
+        let maintainers_required = results
+            .iter()
+            .map(|r| r.maintainers_required)
+            .max()
+            .unwrap_or(0);
+
+        NestedMultisigResult {
+            teams_approved,
+            teams_required,
+            maintainers_approved,
+            maintainers_required,
+            inter_team_approved: teams_approved > 0 && teams_approved >= teams_required,
+            team_details,
+            packets: results.iter().flat_map(|r| r.packets.clone()).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -176,4 +653,611 @@ pub struct TeamApprovalStatus {
     pub maintainers_signed: usize,
     pub maintainers_required: usize,
     pub approved: bool,
+    /// Key fingerprints ([`PublicKey::short`]) of the members with a valid
+    /// signature
+    pub signers: Vec<String>,
+}
+
+/// A team's approval of a message, handed upstream by its lead
+///
+/// Holds the member signatures the team collected so far and the lead's
+/// signature over [`signing_bytes`](Self::signing_bytes), so the packet
+/// cannot be altered on its way to the aggregator. Signatures are hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamApprovalPacket {
+    pub team_id: String,
+    /// SHA256 of the approved message, hex
+    pub message_digest: String,
+    /// Member signatures over the message
+    pub signatures: Vec<MemberSignature>,
+    /// GitHub handle of the lead who assembled the packet
+    pub lead: String,
+    pub lead_signature: String,
+}
+
+/// A team member's signature in a [`TeamApprovalPacket`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberSignature {
+    pub github: String,
+    pub signature: String,
+}
+
+impl TeamApprovalPacket {
+    /// Assemble a packet from member signatures and sign it as `lead`
+    #[cfg(any(feature = "signing", test))]
+    pub fn new(
+        team_id: &str,
+        message: &[u8],
+        signatures: &[(String, Signature)], // (github_username, signature)
+        lead: &str,
+        lead_keypair: &GovernanceKeypair,
+    ) -> GovernanceResult<Self> {
+        let mut packet = Self {
+            team_id: team_id.to_string(),
+            message_digest: Self::digest(message),
+            signatures: signatures
+                .iter()
+                .map(|(github, signature)| MemberSignature {
+                    github: github.clone(),
+                    signature: signature.to_string(),
+                })
+                .collect(),
+            lead: lead.to_string(),
+            lead_signature: String::new(),
+        };
+        packet.lead_signature =
+            crate::sign_message(lead_keypair.secret_key.expose(), &packet.signing_bytes())?
+                .to_string();
+        Ok(packet)
+    }
+
+    /// Hex SHA256 of a message, as packets record it
+    pub fn digest(message: &[u8]) -> String {
+        hex::encode(Sha256::digest(message))
+    }
+
+    /// Bytes the lead signs
+    ///
+    /// `team-approval:<team>:<digest>:<lead>` followed by `<github>:<signature>`
+    /// for each member signature in handle order, so reordering the
+    /// signatures does not change them.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut members: Vec<&MemberSignature> = self.signatures.iter().collect();
+        members.sort_by(|a, b| (&a.github, &a.signature).cmp(&(&b.github, &b.signature)));
+        let mut fields = vec![
+            "team-approval",
+            self.team_id.as_str(),
+            self.message_digest.as_str(),
+            self.lead.as_str(),
+        ];
+        for member in members {
+            fields.push(&member.github);
+            fields.push(&member.signature);
+        }
+        join_fields(&fields)
+    }
+}
+
+/// Where a team's approval came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketProvenance {
+    pub team_id: String,
+    /// Lead who signed the packet
+    pub lead: String,
+    /// Distinct members with a valid signature in the packet
+    pub maintainers_signed: usize,
+    /// Whether the packet alone meets the team's threshold
+    pub meets_team_threshold: bool,
+}
+
+/// Collects team packets for one message as they arrive
+///
+/// Each packet is checked on [`ingest`](Self::ingest), so a packet for
+/// another message or with a bad lead signature is refused at once rather
+/// than failing the final verification.
+#[derive(Debug)]
+pub struct PacketAggregator<'a> {
+    multisig: &'a NestedMultisig,
+    message: &'a [u8],
+    digest: String,
+    packets: Vec<TeamApprovalPacket>,
+}
+
+impl<'a> PacketAggregator<'a> {
+    pub fn new(multisig: &'a NestedMultisig, message: &'a [u8]) -> Self {
+        Self {
+            multisig,
+            message,
+            digest: TeamApprovalPacket::digest(message),
+            packets: Vec::new(),
+        }
+    }
+
+    /// Check a packet and keep it
+    pub fn ingest(&mut self, packet: TeamApprovalPacket) -> GovernanceResult<()> {
+        self.multisig
+            .check_packet(self.message, &self.digest, &packet)?;
+        self.packets.push(packet);
+        Ok(())
+    }
+
+    /// Parse a JSON packet, check it and keep it
+    pub fn ingest_json(&mut self, json: &str) -> GovernanceResult<()> {
+        let packet = serde_json::from_str(json)
+            .map_err(|e| GovernanceError::Serialization(format!("Invalid packet: {}", e)))?;
+        self.ingest(packet)
+    }
+
+    /// Packets kept so far
+    pub fn packets(&self) -> &[TeamApprovalPacket] {
+        &self.packets
+    }
+
+    /// Verify the packets kept so far
+    pub fn result(&self) -> GovernanceResult<NestedMultisigResult> {
+        self.multisig.verify_packets(self.message, &self.packets)
+    }
+}
+
+fn parse_signature(hex_signature: &str) -> GovernanceResult<Signature> {
+    let bytes = hex::decode(hex_signature).map_err(|e| {
+        GovernanceError::InvalidSignatureFormat(format!("Invalid signature hex: {}", e))
+    })?;
+    Signature::from_bytes(&bytes)
+}
+
+/// Signers of a flat multisig, grouped by team
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupedReport {
+    /// Whether the distinct valid signers meet the multisig threshold
+    pub threshold_met: bool,
+    /// Distinct valid signers, in key order
+    pub signers: Vec<GroupedSigner>,
+    /// Signer counts for every team of the committee, in committee order
+    pub teams: Vec<TeamSignerCount>,
+    /// Signers whose key no team of the committee holds
+    pub unassigned: usize,
+}
+
+/// A valid signer of a flat multisig
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupedSigner {
+    /// Index of the signer's key in the multisig
+    pub key_index: usize,
+    /// Team holding the key, if any
+    pub team_id: Option<String>,
+    /// GitHub handle of the maintainer holding the key, if any
+    pub github: Option<String>,
+    /// Fingerprint of the key ([`PublicKey::short`])
+    pub fingerprint: String,
+}
+
+/// How many members of a team signed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamSignerCount {
+    pub team_id: String,
+    pub team_name: String,
+    /// Members with a valid signature
+    pub signed: usize,
+    /// Members of the team
+    pub members: usize,
+}
+
+impl fmt::Display for GroupedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for team in &self.teams {
+            let handles: Vec<String> = self
+                .signers
+                .iter()
+                .filter(|s| s.team_id.as_deref() == Some(team.team_id.as_str()))
+                .filter_map(|s| {
+                    let github = s.github.as_deref()?;
+                    Some(format!("{} [{}]", github, s.fingerprint))
+                })
+                .collect();
+            write!(
+                f,
+                "{} ({}): {} of {} signed",
+                team.team_name, team.team_id, team.signed, team.members
+            )?;
+            if !handles.is_empty() {
+                write!(f, " ({})", handles.join(", "))?;
+            }
+            writeln!(f)?;
+        }
+        if self.unassigned > 0 {
+            let fingerprints: Vec<&str> = self
+                .signers
+                .iter()
+                .filter(|s| s.team_id.is_none())
+                .map(|s| s.fingerprint.as_str())
+                .collect();
+            writeln!(
+                f,
+                "No team: {} signed ({})",
+                self.unassigned,
+                fingerprints.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    fn team(id: &str, keys: &[PublicKey]) -> Team {
+        Team {
+            id: id.to_string(),
+            name: format!("Team {}", id),
+            maintainers: keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| TeamMaintainer {
+                    github: format!("{}-maintainer{}", id, i),
+                    public_key: key.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn keys(n: usize) -> Vec<PublicKey> {
+        (0..n)
+            .map(|_| GovernanceKeypair::generate().unwrap().public_key())
+            .collect()
+    }
+
+    #[test]
+    fn test_distinct_committee() {
+        let keys = keys(6);
+        let teams = vec![team("a", &keys[..3]), team("b", &keys[3..])];
+        assert!(NestedMultisig::new(teams, 2, 2).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_within_team() {
+        let keys = keys(2);
+        let teams = vec![team(
+            "a",
+            &[keys[0].clone(), keys[1].clone(), keys[0].clone()],
+        )];
+
+        let err = NestedMultisig::new(teams, 1, 2).unwrap_err().to_string();
+        assert!(err.contains("team a"));
+        assert!(err.contains("a-maintainer0"));
+        assert!(err.contains("a-maintainer2"));
+    }
+
+    #[test]
+    fn test_duplicate_across_teams() {
+        let keys = keys(3);
+        let teams = vec![
+            team("a", &keys[..2]),
+            team("b", &[keys[2].clone(), keys[1].clone()]),
+        ];
+
+        let err = NestedMultisig::new(teams, 2, 2).unwrap_err().to_string();
+        assert!(err.contains("across teams"));
+        assert!(err.contains("a-maintainer1"));
+        assert!(err.contains("b-maintainer1"));
+    }
+
+    #[test]
+    fn test_committee_fingerprint_lookup() {
+        let keys = keys(5);
+        let committee =
+            Committee::new(vec![team("a", &keys[..2]), team("b", &keys[2..4])]).unwrap();
+
+        let (team, maintainer) = committee
+            .member_by_fingerprint(&keys[3].fingerprint())
+            .unwrap();
+        assert_eq!(team.id, "b");
+        assert_eq!(maintainer.github, "b-maintainer1");
+        assert_eq!(
+            committee.member(&keys[0]).unwrap().1.github,
+            "a-maintainer0"
+        );
+
+        assert!(committee
+            .member_by_fingerprint(&keys[4].fingerprint())
+            .is_none());
+        assert!(committee.member(&keys[4]).is_none());
+    }
+
+    #[test]
+    fn test_committee_fingerprint_collision() {
+        let keys = keys(3);
+        let mut committee = Committee::new(vec![team("a", &keys[..2])]).unwrap();
+
+        // Pretend both members share keys[0]'s fingerprint
+        let shared = keys[0].fingerprint();
+        committee.by_fingerprint.insert(shared, 1);
+        committee.collisions.insert(shared);
+
+        assert!(committee.member_by_fingerprint(&shared).is_none());
+        assert_eq!(
+            committee.member(&keys[0]).unwrap().1.github,
+            "a-maintainer0"
+        );
+        // A non-member whose fingerprint points at a member is rejected
+        committee.by_fingerprint.insert(keys[2].fingerprint(), 0);
+        assert!(committee.member(&keys[2]).is_none());
+    }
+
+    #[test]
+    fn test_committee_diff() {
+        let keys = keys(6);
+        let old = Committee::new(vec![team("a", &keys[..2]), team("b", &keys[2..4])]).unwrap();
+        assert!(old.diff(&old).is_empty());
+
+        // a-maintainer1 rotates, b-maintainer1 leaves and a-maintainer2 joins
+        let new = Committee::new(vec![
+            team("a", &[keys[0].clone(), keys[4].clone(), keys[5].clone()]),
+            team("b", &keys[2..3]),
+        ])
+        .unwrap();
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff.added,
+            vec![CommitteeMember {
+                github: "a-maintainer2".to_string(),
+                team_id: "a".to_string(),
+                public_key: keys[5].to_string(),
+            }]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![CommitteeMember {
+                github: "b-maintainer1".to_string(),
+                team_id: "b".to_string(),
+                public_key: keys[3].to_string(),
+            }]
+        );
+        assert_eq!(
+            diff.key_changes,
+            vec![KeyChange {
+                github: "a-maintainer1".to_string(),
+                old_public_key: keys[1].to_string(),
+                new_public_key: keys[4].to_string(),
+            }]
+        );
+        assert!(diff.team_moves.is_empty());
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["added"][0]["github"], "a-maintainer2");
+        assert_eq!(
+            json["key_changes"][0]["new_public_key"],
+            keys[4].to_string()
+        );
+        assert!(diff.to_string().contains("~ a-maintainer1: key"));
+    }
+
+    #[test]
+    fn test_committee_diff_team_move() {
+        let keys = keys(3);
+        let old = Committee::new(vec![team("a", &keys[..2]), team("b", &keys[2..])]).unwrap();
+        let mut teams = old.teams().to_vec();
+        let moved = teams[0].maintainers.remove(1);
+        teams[1].maintainers.push(moved);
+        let new = Committee::new(teams).unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.team_moves,
+            vec![TeamMove {
+                github: "a-maintainer1".to_string(),
+                from_team: "a".to_string(),
+                to_team: "b".to_string(),
+            }]
+        );
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.key_changes.is_empty());
+    }
+
+    #[test]
+    fn test_merge_rounds() {
+        let keypairs: Vec<_> = (0..6)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let teams = vec![team("a", &public_keys[..3]), team("b", &public_keys[3..])];
+        let multisig = NestedMultisig::new(teams, 2, 2).unwrap();
+
+        let message = b"release:v1.0.0";
+        let sign = |team: &str, i: usize| {
+            let index = if team == "a" { i } else { 3 + i };
+            (
+                format!("{}-maintainer{}", team, i),
+                crate::sign_message(keypairs[index].secret_key.expose(), message).unwrap(),
+            )
+        };
+
+        // Round 1 approves team A, round 2 team B (and repeats one A signer)
+        let round1 = multisig
+            .verify(message, &[sign("a", 0), sign("a", 1)])
+            .unwrap();
+        let round2 = multisig
+            .verify(message, &[sign("b", 0), sign("b", 2), sign("a", 0)])
+            .unwrap();
+        assert!(!round1.inter_team_approved);
+        assert!(!round2.inter_team_approved);
+
+        let merged = NestedMultisigResult::merge(&[round1.clone(), round2]);
+        assert!(merged.inter_team_approved);
+        assert_eq!(merged.teams_approved, 2);
+        assert_eq!(merged.maintainers_approved, 4);
+        assert_eq!(merged.team_details.len(), 2);
+        assert!(merged
+            .team_details
+            .iter()
+            .all(|t| t.maintainers_signed == 2));
+        // Signers are named by key fingerprint, the repeated one once
+        let short = |i: usize| public_keys[i].short();
+        assert_eq!(merged.team_details[0].signers, vec![short(0), short(1)]);
+        assert_eq!(merged.team_details[1].signers, vec![short(3), short(5)]);
+
+        // The same round twice approves nothing new
+        let repeated = NestedMultisigResult::merge(&[round1.clone(), round1]);
+        assert_eq!(repeated.teams_approved, 1);
+        assert!(!repeated.inter_team_approved);
+        assert!(!NestedMultisigResult::merge(&[]).inter_team_approved);
+    }
+
+    fn packet_committee() -> (Vec<GovernanceKeypair>, NestedMultisig) {
+        let keypairs: Vec<_> = (0..9)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let teams = vec![
+            team("a", &public_keys[..3]),
+            team("b", &public_keys[3..6]),
+            team("c", &public_keys[6..]),
+        ];
+        (keypairs, NestedMultisig::new(teams, 2, 2).unwrap())
+    }
+
+    /// Packet for `team` signed by members `signers` and lead `lead`
+    fn packet(
+        keypairs: &[GovernanceKeypair],
+        team: usize,
+        signers: &[usize],
+        lead: (usize, usize),
+        message: &[u8],
+    ) -> TeamApprovalPacket {
+        let id = |t: usize| ["a", "b", "c"][t];
+        let signatures: Vec<_> = signers
+            .iter()
+            .map(|&i| {
+                (
+                    format!("{}-maintainer{}", id(team), i),
+                    crate::sign_message(keypairs[team * 3 + i].secret_key.expose(), message)
+                        .unwrap(),
+                )
+            })
+            .collect();
+        TeamApprovalPacket::new(
+            id(team),
+            message,
+            &signatures,
+            &format!("{}-maintainer{}", id(lead.0), lead.1),
+            &keypairs[lead.0 * 3 + lead.1],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_packets_approve() {
+        let (keypairs, multisig) = packet_committee();
+        let message = b"release:v1.0.0";
+        let packets = [
+            packet(&keypairs, 0, &[0, 1], (0, 0), message),
+            packet(&keypairs, 1, &[1, 2], (1, 2), message),
+        ];
+
+        // Packets survive a JSON round trip
+        let json = serde_json::to_string(&packets[0]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TeamApprovalPacket>(&json).unwrap(),
+            packets[0]
+        );
+
+        let result = multisig.verify_packets(message, &packets).unwrap();
+        assert!(result.inter_team_approved);
+        assert_eq!(result.teams_approved, 2);
+        assert_eq!(result.maintainers_approved, 4);
+        let short = |i: usize| keypairs[i].public_key().short();
+        assert_eq!(result.team_details[0].signers, vec![short(0), short(1)]);
+        assert_eq!(result.team_details[1].signers, vec![short(4), short(5)]);
+        assert!(result.team_details[2].signers.is_empty());
+        assert_eq!(
+            result.packets,
+            vec![
+                PacketProvenance {
+                    team_id: "a".to_string(),
+                    lead: "a-maintainer0".to_string(),
+                    maintainers_signed: 2,
+                    meets_team_threshold: true,
+                },
+                PacketProvenance {
+                    team_id: "b".to_string(),
+                    lead: "b-maintainer2".to_string(),
+                    maintainers_signed: 2,
+                    meets_team_threshold: true,
+                },
+            ]
+        );
+
+        // The aggregator ingests the same packets as JSON
+        let mut aggregator = PacketAggregator::new(&multisig, message);
+        for packet in &packets {
+            aggregator
+                .ingest_json(&serde_json::to_string(packet).unwrap())
+                .unwrap();
+        }
+        assert!(aggregator.result().unwrap().inter_team_approved);
+
+        // A tampered packet no longer matches its lead signature
+        let mut tampered = packets[0].clone();
+        tampered.signatures.pop();
+        assert!(multisig.verify_packets(message, &[tampered]).is_err());
+    }
+
+    #[test]
+    fn test_packet_below_team_threshold() {
+        let (keypairs, multisig) = packet_committee();
+        let message = b"release:v1.0.0";
+        let packets = [
+            packet(&keypairs, 0, &[0, 1], (0, 0), message),
+            // Team B hands over a single signature, repeated
+            packet(&keypairs, 1, &[0, 0], (1, 0), message),
+        ];
+
+        let result = multisig.verify_packets(message, &packets).unwrap();
+        assert!(!result.inter_team_approved);
+        assert_eq!(result.teams_approved, 1);
+        assert_eq!(result.packets[1].maintainers_signed, 1);
+        assert!(!result.packets[1].meets_team_threshold);
+        assert!(!result.team_details[1].approved);
+
+        // A second packet from team B completes its approval
+        let more = packet(&keypairs, 1, &[2], (1, 0), message);
+        let result = multisig
+            .verify_packets(message, &[packets[0].clone(), packets[1].clone(), more])
+            .unwrap();
+        assert!(result.inter_team_approved);
+        assert_eq!(result.team_details[1].maintainers_signed, 2);
+    }
+
+    #[test]
+    fn test_packet_rejected() {
+        let (keypairs, multisig) = packet_committee();
+        let message = b"release:v1.0.0";
+        let good = packet(&keypairs, 0, &[0, 1], (0, 0), message);
+
+        // Team A's packet signed by a member of team B
+        let foreign_lead = packet(&keypairs, 0, &[0, 1], (1, 0), message);
+        let err = multisig
+            .verify_packets(message, &[good.clone(), foreign_lead.clone()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("b-maintainer0"));
+        assert!(err.contains("not a member of team a"));
+
+        // A packet for another message is rejected whole, even though its
+        // lead signature is valid
+        let other = packet(&keypairs, 1, &[0, 1, 2], (1, 0), b"release:v2.0.0");
+        let err = multisig
+            .verify_packets(message, &[good.clone(), other.clone()])
+            .unwrap_err();
+        assert!(matches!(err, GovernanceError::InvalidInput(_)));
+
+        let mut aggregator = PacketAggregator::new(&multisig, message);
+        aggregator.ingest(good).unwrap();
+        assert!(aggregator.ingest(foreign_lead).is_err());
+        assert!(aggregator.ingest(other).is_err());
+        assert_eq!(aggregator.packets().len(), 1);
+        assert!(!aggregator.result().unwrap().inter_team_approved);
+    }
 }
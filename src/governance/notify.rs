@@ -0,0 +1,370 @@
+//! # Verification Notifications
+//!
+//! POST verification reports to webhooks, for CI/CD systems that act on
+//! the outcome of a verification run.
+//!
+//! The body is the report serialized as JSON. With a shared secret, the
+//! `X-Blvm-Signature` header carries `sha256=<hex>`, the HMAC-SHA256 of the
+//! body under that secret, so a receiver can check the report came from a
+//! holder of the secret. Deliveries are retried a bounded number of times
+//! on 5xx responses and connection failures; each attempt has a hard
+//! timeout.
+//!
+//! Notification is best effort: a failed delivery is reported in its
+//! [`Delivery`], never as an error of the run that produced the report.
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::util::Redacted;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying the HMAC signature of the body
+pub const SIGNATURE_HEADER: &str = "X-Blvm-Signature";
+
+/// A webhook to notify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotifyTarget {
+    /// `http(s)` URL the report is posted to
+    pub url: String,
+    /// Shared secret for the signature header; no header without one
+    pub secret: Option<Redacted<Vec<u8>>>,
+}
+
+/// Retry and timeout policy for deliveries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotifyOptions {
+    /// Attempts per target, including the first
+    pub attempts: u32,
+    /// Hard timeout of one attempt (connect, send and response)
+    pub timeout: Duration,
+    /// Delay before the first retry, doubled for each further retry
+    pub backoff: Duration,
+}
+
+impl Default for NotifyOptions {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            timeout: Duration::from_secs(10),
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Outcome of notifying one target
+#[derive(Debug)]
+pub struct Delivery {
+    /// The target's URL
+    pub url: String,
+    /// Attempts made
+    pub attempts: u32,
+    /// HTTP status of the accepted delivery, or why it failed
+    pub result: GovernanceResult<u16>,
+}
+
+impl Delivery {
+    /// Whether the target accepted the report
+    pub fn is_delivered(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+impl fmt::Display for Delivery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.result {
+            Ok(status) => write!(f, "{}: delivered (HTTP {})", self.url, status),
+            Err(e) => write!(
+                f,
+                "{}: not delivered after {} attempt(s): {}",
+                self.url, self.attempts, e
+            ),
+        }
+    }
+}
+
+/// Read a shared secret from a file, without trailing whitespace
+pub fn load_secret(path: &Path) -> GovernanceResult<Redacted<Vec<u8>>> {
+    let mut secret = std::fs::read(path).map_err(|e| {
+        GovernanceError::InvalidInput(format!(
+            "Failed to read notification secret {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    while secret.last().is_some_and(u8::is_ascii_whitespace) {
+        secret.pop();
+    }
+    if secret.is_empty() {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Notification secret {} is empty",
+            path.display()
+        )));
+    }
+    Ok(Redacted::new(secret))
+}
+
+/// Value of the [`SIGNATURE_HEADER`] for `body`
+pub fn signature_header(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Post `report` as JSON to every target, with the default options
+pub fn send_report<T: Serialize + ?Sized>(report: &T, targets: &[NotifyTarget]) -> Vec<Delivery> {
+    send_report_with(report, targets, &NotifyOptions::default())
+}
+
+/// Post `report` as JSON to every target
+///
+/// Returns one [`Delivery`] per target, in order.
+pub fn send_report_with<T: Serialize + ?Sized>(
+    report: &T,
+    targets: &[NotifyTarget],
+    options: &NotifyOptions,
+) -> Vec<Delivery> {
+    let body = match serde_json::to_vec(report) {
+        Ok(body) => body,
+        Err(e) => {
+            return targets
+                .iter()
+                .map(|target| Delivery {
+                    url: target.url.clone(),
+                    attempts: 0,
+                    result: Err(GovernanceError::Serialization(e.to_string())),
+                })
+                .collect()
+        }
+    };
+    targets
+        .iter()
+        .map(|target| deliver(target, &body, options))
+        .collect()
+}
+
+fn deliver(target: &NotifyTarget, body: &[u8], options: &NotifyOptions) -> Delivery {
+    let url = &target.url;
+    let failed = |e: &dyn fmt::Display| {
+        GovernanceError::InvalidInput(format!("Notifying {} failed: {}", url, e))
+    };
+    let delivery = |attempts, result| Delivery {
+        url: url.clone(),
+        attempts,
+        result,
+    };
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return delivery(0, Err(failed(&"only http(s) URLs can be notified")));
+    }
+    let signature = target
+        .secret
+        .as_ref()
+        .map(|secret| signature_header(secret.expose(), body));
+
+    let mut backoff = options.backoff;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let mut request = ureq::post(url)
+            .timeout(options.timeout)
+            .set("Content-Type", "application/json");
+        if let Some(signature) = &signature {
+            request = request.set(SIGNATURE_HEADER, signature);
+        }
+        let retryable = match request.send_bytes(body) {
+            Ok(response) => return delivery(attempts, Ok(response.status())),
+            Err(e @ ureq::Error::Status(500..=599, _)) | Err(e @ ureq::Error::Transport(_)) => e,
+            Err(e) => return delivery(attempts, Err(failed(&e))),
+        };
+        if attempts >= options.attempts.max(1) {
+            return delivery(attempts, Err(failed(&retryable)));
+        }
+        std::thread::sleep(backoff);
+        backoff *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// A request as received by the test server
+    struct Received {
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    impl Received {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Serve one request per status, answering with the statuses in order
+    fn serve(statuses: &[u16]) -> (String, JoinHandle<Vec<Received>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let statuses = statuses.to_vec();
+        let server = std::thread::spawn(move || {
+            statuses
+                .into_iter()
+                .map(|status| {
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream);
+                    let mut headers = Vec::new();
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    loop {
+                        line.clear();
+                        reader.read_line(&mut line).unwrap();
+                        let Some((name, value)) = line.trim_end().split_once(':') else {
+                            break;
+                        };
+                        headers.push((name.to_string(), value.trim().to_string()));
+                    }
+                    let received = Received {
+                        headers,
+                        body: Vec::new(),
+                    };
+                    let length: usize = received
+                        .header("content-length")
+                        .map_or(0, |length| length.parse().unwrap());
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    write!(
+                        reader.get_mut(),
+                        "HTTP/1.1 {} Test\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        status
+                    )
+                    .unwrap();
+                    Received { body, ..received }
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    fn options() -> NotifyOptions {
+        NotifyOptions {
+            attempts: 3,
+            timeout: Duration::from_secs(5),
+            backoff: Duration::from_millis(10),
+        }
+    }
+
+    fn report() -> serde_json::Value {
+        serde_json::json!({ "entries": [{ "stage": "threshold", "result": "pass" }] })
+    }
+
+    #[test]
+    fn test_signature_header() {
+        let (url, server) = serve(&[200]);
+        let target = NotifyTarget {
+            url,
+            secret: Some(Redacted::new(b"shared secret".to_vec())),
+        };
+
+        let deliveries = send_report_with(&report(), &[target], &options());
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].result.as_ref().unwrap(), &200);
+
+        let received = server.join().unwrap();
+        let body = &received[0].body;
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(body).unwrap(),
+            report()
+        );
+        assert_eq!(
+            received[0].header(SIGNATURE_HEADER),
+            Some(signature_header(b"shared secret", body).as_str())
+        );
+        assert_ne!(
+            signature_header(b"shared secret", body),
+            signature_header(b"other secret", body)
+        );
+    }
+
+    #[test]
+    fn test_retries_server_errors() {
+        let (url, server) = serve(&[500, 503, 200]);
+        let target = NotifyTarget { url, secret: None };
+
+        let deliveries = send_report_with(&report(), &[target], &options());
+        assert!(deliveries[0].is_delivered());
+        assert_eq!(deliveries[0].attempts, 3);
+
+        let received = server.join().unwrap();
+        assert_eq!(received.len(), 3);
+        assert!(received[0].header(SIGNATURE_HEADER).is_none());
+    }
+
+    #[test]
+    fn test_bounded_retries() {
+        let (url, server) = serve(&[500, 500]);
+        let target = NotifyTarget { url, secret: None };
+
+        let options = NotifyOptions {
+            attempts: 2,
+            ..options()
+        };
+        let deliveries = send_report_with(&report(), &[target], &options);
+        assert!(!deliveries[0].is_delivered());
+        assert_eq!(deliveries[0].attempts, 2);
+        server.join().unwrap();
+
+        // Client errors are not retried
+        let (url, server) = serve(&[404]);
+        let target = NotifyTarget { url, secret: None };
+        let deliveries = send_report_with(&report(), &[target], &options);
+        assert_eq!(deliveries[0].attempts, 1);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_dead_endpoint() {
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let targets = [
+            NotifyTarget {
+                url: format!("http://{}/hook", address),
+                secret: None,
+            },
+            NotifyTarget {
+                url: "ftp://example.com/hook".to_string(),
+                secret: None,
+            },
+        ];
+
+        let deliveries = send_report_with(&report(), &targets, &options());
+        assert_eq!(deliveries[0].attempts, 3);
+        assert!(!deliveries[0].is_delivered());
+        assert_eq!(deliveries[1].attempts, 0);
+        assert!(!deliveries[1].is_delivered());
+    }
+
+    #[test]
+    fn test_load_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret");
+        std::fs::write(&path, "s3cret\n").unwrap();
+        assert_eq!(load_secret(&path).unwrap().expose(), b"s3cret");
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(load_secret(&path).is_err());
+        assert!(load_secret(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! # OCI Image Signing
+//!
+//! Governance signatures over OCI container image digests.
+//!
+//! Modules distributed through OCI registries are identified by their content
+//! digest (`sha256:<hex>`). The signed payload binds the digest together with
+//! the repository and tag, so a signature cannot be replayed for another image.
+//!
+//! With the `net` feature, [`resolve_digest`] looks up the digest a tag
+//! currently points to with a registry `HEAD` request. Verification always
+//! checks signatures against a digest, never against the tag.
+
+#[cfg(any(feature = "signing", test))]
+use secp256k1::SecretKey;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::messages::SigningTarget;
+use crate::governance::{Multisig, Signature};
+
+/// Prefix of a SHA256 OCI content digest
+const DIGEST_PREFIX: &str = "sha256:";
+
+/// A reference to an OCI image pinned by digest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OciImageRef {
+    /// Image repository (e.g. `ghcr.io/btcdecoded/lightning`)
+    pub repository: String,
+    /// Content digest (`sha256:<64 hex chars>`)
+    pub digest: String,
+    /// Optional tag the image was published under
+    pub tag: Option<String>,
+}
+
+impl OciImageRef {
+    /// Create an image reference, validating the digest format
+    pub fn new(repository: &str, digest: &str, tag: Option<&str>) -> GovernanceResult<Self> {
+        if repository.is_empty() {
+            return Err(GovernanceError::InvalidInput(
+                "Image repository cannot be empty".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            repository: repository.to_string(),
+            digest: parse_digest(digest)?,
+            tag: tag.map(|t| t.to_string()),
+        })
+    }
+
+    /// Hex part of the digest (without the `sha256:` prefix)
+    pub fn digest_hex(&self) -> &str {
+        self.digest.trim_start_matches(DIGEST_PREFIX)
+    }
+
+    /// Convert the image reference to bytes for signing
+    ///
+    /// `image:<repository>:<digest>[:<tag>]`, with `:` escaped inside the
+    /// repository (a registry port, as in `host:5000/repo`) and tag.
+    pub fn to_signing_bytes(&self) -> Vec<u8> {
+        SigningTarget::from(self).to_signing_bytes()
+    }
+}
+
+impl From<&OciImageRef> for SigningTarget {
+    fn from(image: &OciImageRef) -> Self {
+        SigningTarget::Image {
+            repository: image.repository.clone(),
+            digest: image.digest.clone(),
+            tag: image.tag.clone(),
+        }
+    }
+}
+
+impl fmt::Display for OciImageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.repository, self.digest)
+    }
+}
+
+/// Parse and normalize an OCI digest (`sha256:<hex>`)
+pub fn parse_digest(digest: &str) -> GovernanceResult<String> {
+    let hex_part = digest.strip_prefix(DIGEST_PREFIX).ok_or_else(|| {
+        GovernanceError::InvalidInput(format!(
+            "Image digest must start with '{}': {}",
+            DIGEST_PREFIX, digest
+        ))
+    })?;
+
+    if hex_part.len() != 64 || !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Image digest must be 64 hex characters: {}",
+            digest
+        )));
+    }
+
+    Ok(format!("{}{}", DIGEST_PREFIX, hex_part.to_lowercase()))
+}
+
+/// Registry used for repositories without a registry host
+const DEFAULT_REGISTRY: &str = "registry-1.docker.io";
+
+/// Split a repository into its registry host and repository name
+///
+/// The first path component is a host when it contains `.` or `:` or is
+/// `localhost`; otherwise the repository is on Docker Hub, where single-name
+/// repositories live under `library/`.
+pub fn split_repository(repository: &str) -> (String, String) {
+    match repository.split_once('/') {
+        Some((host, name)) if host.contains('.') || host.contains(':') || host == "localhost" => {
+            (host.to_string(), name.to_string())
+        }
+        Some(_) => (DEFAULT_REGISTRY.to_string(), repository.to_string()),
+        None => (
+            DEFAULT_REGISTRY.to_string(),
+            format!("library/{}", repository),
+        ),
+    }
+}
+
+/// Manifest media types accepted when resolving a tag
+#[cfg(feature = "net")]
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// Look up the digest a tag currently points to
+///
+/// Sends a `HEAD` request for the tag's manifest and reads the
+/// `Docker-Content-Digest` header, fetching an anonymous pull token when the
+/// registry asks for one. The result is only as trustworthy as the registry;
+/// governance signatures must still be checked against it.
+#[cfg(feature = "net")]
+pub fn resolve_digest(repository: &str, tag: &str) -> GovernanceResult<String> {
+    let (registry, name) = split_repository(repository);
+    let url = format!("https://{}/v2/{}/manifests/{}", registry, name, tag);
+    let head = |token: Option<&str>| {
+        let request = ureq::head(&url).set("Accept", MANIFEST_TYPES);
+        match token {
+            Some(token) => request
+                .set("Authorization", &format!("Bearer {}", token))
+                .call(),
+            None => request.call(),
+        }
+        .map_err(Box::new)
+    };
+
+    let response = match head(None) {
+        Err(error) => match *error {
+            ureq::Error::Status(401, response) => {
+                let challenge = response.header("WWW-Authenticate").unwrap_or_default();
+                head(Some(&anonymous_token(challenge)?))
+            }
+            error => Err(Box::new(error)),
+        },
+        response => response,
+    }
+    .map_err(|e| registry_error(repository, tag, &e))?;
+
+    let digest = response.header("Docker-Content-Digest").ok_or_else(|| {
+        GovernanceError::InvalidInput(format!(
+            "Registry returned no digest for {}:{}",
+            repository, tag
+        ))
+    })?;
+    parse_digest(digest)
+}
+
+/// Fetch an anonymous token for a `Bearer` authentication challenge
+#[cfg(feature = "net")]
+fn anonymous_token(challenge: &str) -> GovernanceResult<String> {
+    let params = parse_challenge(challenge);
+    let realm = params.get("realm").ok_or_else(|| {
+        GovernanceError::InvalidInput(format!(
+            "Unsupported registry authentication: {}",
+            challenge
+        ))
+    })?;
+    let mut request = ureq::get(realm);
+    for key in ["service", "scope"] {
+        if let Some(value) = params.get(key) {
+            request = request.query(key, value);
+        }
+    }
+
+    let failed = |e: &dyn std::fmt::Display| {
+        GovernanceError::InvalidInput(format!("Registry token request failed: {}", e))
+    };
+    let body = request
+        .call()
+        .map_err(|e| failed(&e))?
+        .into_string()
+        .map_err(|e| failed(&e))?;
+    let body: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| GovernanceError::Serialization(format!("Invalid registry token: {}", e)))?;
+    body.get("token")
+        .or_else(|| body.get("access_token"))
+        .and_then(|token| token.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| GovernanceError::InvalidInput("Registry issued no token".to_string()))
+}
+
+/// Parameters of a `Bearer key="value",...` challenge
+#[cfg(feature = "net")]
+fn parse_challenge(challenge: &str) -> std::collections::HashMap<String, String> {
+    let mut params = std::collections::HashMap::new();
+    let mut rest = challenge.trim().strip_prefix("Bearer").unwrap_or("").trim();
+    while let Some((key, value)) = rest.split_once("=\"") {
+        let Some((value, tail)) = value.split_once('"') else {
+            break;
+        };
+        params.insert(key.trim().to_string(), value.to_string());
+        rest = tail.trim_start_matches([',', ' ']);
+    }
+    params
+}
+
+#[cfg(feature = "net")]
+fn registry_error(repository: &str, tag: &str, error: &ureq::Error) -> GovernanceError {
+    GovernanceError::InvalidInput(format!(
+        "Failed to resolve {}:{} from the registry: {}",
+        repository, tag, error
+    ))
+}
+
+/// Sign an OCI image reference
+#[cfg(any(feature = "signing", test))]
+pub fn sign_image(secret_key: &SecretKey, image: &OciImageRef) -> GovernanceResult<Signature> {
+    crate::governance::signatures::sign_message(secret_key, &image.to_signing_bytes())
+}
+
+/// Verify that an OCI image reference carries threshold governance approval
+pub fn verify_image(
+    image: &OciImageRef,
+    signatures: &[Signature],
+    multisig: &Multisig,
+) -> GovernanceResult<bool> {
+    multisig.verify(&image.to_signing_bytes(), signatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    const DIGEST: &str = "sha256:4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945";
+
+    #[test]
+    fn test_sign_and_verify_image() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let image =
+            OciImageRef::new("ghcr.io/btcdecoded/lightning", DIGEST, Some("v1.0.0")).unwrap();
+
+        let signature = sign_image(keypair.secret_key.expose(), &image).unwrap();
+        assert!(verify_image(&image, &[signature], &multisig).unwrap());
+    }
+
+    #[test]
+    fn test_reject_different_digest() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+        let image = OciImageRef::new("ghcr.io/btcdecoded/lightning", DIGEST, None).unwrap();
+        let signature = sign_image(keypair.secret_key.expose(), &image).unwrap();
+
+        let other = OciImageRef::new(
+            "ghcr.io/btcdecoded/lightning",
+            "sha256:0000000000000000000000000000000000000000000000000000000000000000",
+            None,
+        )
+        .unwrap();
+        assert!(!verify_image(&other, &[signature], &multisig).unwrap());
+    }
+
+    #[test]
+    fn test_signing_bytes() {
+        let image = OciImageRef::new("repo/module", DIGEST, Some("v1")).unwrap();
+        assert_eq!(
+            image.to_signing_bytes(),
+            format!("image:repo/module:{}:v1", DIGEST).into_bytes()
+        );
+    }
+
+    #[test]
+    fn test_signing_bytes_registry_port() {
+        let image = OciImageRef::new("localhost:5000/module", DIGEST, Some("v1")).unwrap();
+        assert_eq!(
+            image.to_signing_bytes(),
+            format!("image:localhost%3A5000/module:{}:v1", DIGEST).into_bytes()
+        );
+
+        // Unescaped, both would sign `image:h:<DIGEST>:<other>`
+        let other = format!("sha256:{}", "0".repeat(64));
+        let in_repository = OciImageRef::new(&format!("h:{}", DIGEST), &other, None).unwrap();
+        let in_tag = OciImageRef::new("h", DIGEST, Some(&other)).unwrap();
+        assert_ne!(in_repository.to_signing_bytes(), in_tag.to_signing_bytes());
+    }
+
+    #[test]
+    fn test_split_repository() {
+        assert_eq!(
+            split_repository("ghcr.io/btcdecoded/lightning"),
+            ("ghcr.io".to_string(), "btcdecoded/lightning".to_string())
+        );
+        assert_eq!(
+            split_repository("localhost:5000/module"),
+            ("localhost:5000".to_string(), "module".to_string())
+        );
+        assert_eq!(
+            split_repository("btcdecoded/lightning"),
+            (
+                DEFAULT_REGISTRY.to_string(),
+                "btcdecoded/lightning".to_string()
+            )
+        );
+        assert_eq!(
+            split_repository("alpine"),
+            (DEFAULT_REGISTRY.to_string(), "library/alpine".to_string())
+        );
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_parse_challenge() {
+        let params = parse_challenge(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:a/b:pull,push""#,
+        );
+        assert_eq!(params["realm"], "https://ghcr.io/token");
+        assert_eq!(params["service"], "ghcr.io");
+        assert_eq!(params["scope"], "repository:a/b:pull,push");
+    }
+
+    #[test]
+    fn test_invalid_digest() {
+        assert!(parse_digest("md5:abcd").is_err());
+        assert!(parse_digest("sha256:abcd").is_err());
+        assert!(parse_digest(&DIGEST.replace('4', "z")).is_err());
+        assert_eq!(
+            parse_digest(&DIGEST.to_uppercase().replace("SHA256", "sha256")).unwrap(),
+            DIGEST
+        );
+    }
+}
@@ -0,0 +1,677 @@
+//! # Paper Backups
+//!
+//! Printable, error-corrected backups of key shares and committee
+//! configuration, for cold storage.
+//!
+//! A backup is split into data chunks of `chunk_size` bytes (the last one
+//! zero-padded) followed by `parity` Reed–Solomon parity chunks. Any
+//! `data` of the `data + parity` chunks restore it, so up to `parity`
+//! chunks may be lost or damaged. Each chunk is one line:
+//!
+//! ```text
+//! BLVM1:KEYSHARE:03:02+04:000032:MFRGGZDFMZTWQ2LKNNWG23TPOBYXE43U:1C291CA3
+//! ```
+//!
+//! with the artifact kind, the chunk number (from 1), the data and parity
+//! chunk counts, the backup length in bytes, the chunk in base32 (RFC 4648,
+//! unpadded) and the CRC-32 of everything before it. A chunk whose CRC does
+//! not match counts as missing, so damaged lines need not be found by
+//! hand, and chunks can be entered in any order.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+
+/// Format tag starting every chunk line
+const PREFIX: &str = "BLVM1";
+
+/// Largest chunk, in bytes
+pub const MAX_CHUNK_SIZE: usize = 64;
+
+/// Most chunks (data and parity) in one backup
+pub const MAX_CHUNKS: usize = 255;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// What a paper backup holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperArtifact {
+    /// A maintainer's 32-byte secret key
+    KeyShare,
+    /// A multisig configuration (threshold and committee keys)
+    MultisigConfig,
+    /// A published keyset
+    Keyset,
+}
+
+impl PaperArtifact {
+    fn code(self) -> &'static str {
+        match self {
+            PaperArtifact::KeyShare => "KEYSHARE",
+            PaperArtifact::MultisigConfig => "MULTISIG",
+            PaperArtifact::Keyset => "KEYSET",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        [
+            PaperArtifact::KeyShare,
+            PaperArtifact::MultisigConfig,
+            PaperArtifact::Keyset,
+        ]
+        .into_iter()
+        .find(|artifact| artifact.code() == code)
+    }
+}
+
+impl fmt::Display for PaperArtifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PaperArtifact::KeyShare => "key share",
+            PaperArtifact::MultisigConfig => "multisig configuration",
+            PaperArtifact::Keyset => "published keyset",
+        })
+    }
+}
+
+impl FromStr for PaperArtifact {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "key-share" => Ok(PaperArtifact::KeyShare),
+            "multisig-config" => Ok(PaperArtifact::MultisigConfig),
+            "keyset" => Ok(PaperArtifact::Keyset),
+            _ => Err(GovernanceError::InvalidInput(format!(
+                "Unknown backup artifact '{}': expected key-share, multisig-config or keyset",
+                s
+            ))),
+        }
+    }
+}
+
+/// Chunking and redundancy of a backup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperOptions {
+    /// Bytes per chunk (1 to [`MAX_CHUNK_SIZE`])
+    pub chunk_size: usize,
+    /// Parity chunks: how many chunks may be lost or damaged
+    pub parity: usize,
+}
+
+impl Default for PaperOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 16,
+            parity: 4,
+        }
+    }
+}
+
+/// A backup as printable chunk lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperBackup {
+    pub artifact: PaperArtifact,
+    /// Length of the backed-up data in bytes
+    pub length: usize,
+    /// Data chunks; any this many chunks restore the backup
+    pub data_chunks: usize,
+    /// Parity chunks
+    pub parity_chunks: usize,
+    /// Chunk lines, data chunks first
+    pub chunks: Vec<String>,
+}
+
+impl PaperBackup {
+    /// The printable document: instructions followed by the chunk lines
+    pub fn to_document(&self) -> String {
+        let mut document = format!("BLVM PAPER BACKUP: {}\n", self.artifact);
+        if self.artifact == PaperArtifact::KeyShare {
+            document.push_str("SECRET: anyone holding these lines can sign as this key.\n");
+        }
+        document.push_str(&format!(
+            "{} bytes in {} data and {} parity chunks. Any {} of the {} chunk lines\n\
+             restore it; lines may be entered in any order.\n\
+             Restore with: blvm-keygen paper-restore\n\n",
+            self.length,
+            self.data_chunks,
+            self.parity_chunks,
+            self.data_chunks,
+            self.chunks.len()
+        ));
+        for chunk in &self.chunks {
+            document.push_str(chunk);
+            document.push('\n');
+        }
+        document
+    }
+
+    /// Each chunk line as a QR code drawn with text characters
+    #[cfg(feature = "qr")]
+    pub fn qr_codes(&self) -> GovernanceResult<Vec<String>> {
+        self.chunks
+            .iter()
+            .map(|chunk| {
+                let code = qrcode::QrCode::new(chunk.as_bytes()).map_err(|e| {
+                    GovernanceError::Serialization(format!("QR encoding failed: {}", e))
+                })?;
+                Ok(code
+                    .render::<char>()
+                    .quiet_zone(false)
+                    .module_dimensions(2, 1)
+                    .build())
+            })
+            .collect()
+    }
+}
+
+/// Split `data` into a paper backup
+pub fn backup(
+    artifact: PaperArtifact,
+    data: &[u8],
+    options: &PaperOptions,
+) -> GovernanceResult<PaperBackup> {
+    let chunk_size = options.chunk_size;
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Chunk size must be 1 to {} bytes, got {}",
+            MAX_CHUNK_SIZE, chunk_size
+        )));
+    }
+    if data.is_empty() {
+        return Err(GovernanceError::InvalidInput(
+            "Nothing to back up".to_string(),
+        ));
+    }
+    let data_chunks = data.len().div_ceil(chunk_size);
+    if data_chunks + options.parity > MAX_CHUNKS {
+        return Err(GovernanceError::InvalidInput(format!(
+            "{} data and {} parity chunks exceed the {} chunk limit; use larger chunks",
+            data_chunks, options.parity, MAX_CHUNKS
+        )));
+    }
+
+    let mut shards: Vec<Zeroizing<Vec<u8>>> = data
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let mut shard = Zeroizing::new(chunk.to_vec());
+            shard.resize(chunk_size, 0);
+            shard
+        })
+        .collect();
+    for row in 0..options.parity {
+        let coefficients = parity_row(row, data_chunks);
+        let mut parity = Zeroizing::new(vec![0u8; chunk_size]);
+        for (coefficient, shard) in coefficients.iter().zip(&shards) {
+            for (p, &b) in parity.iter_mut().zip(shard.iter()) {
+                *p ^= GF.mul(*coefficient, b);
+            }
+        }
+        shards.push(parity);
+    }
+
+    let chunks = shards
+        .iter()
+        .enumerate()
+        .map(|(index, shard)| {
+            let body = format!(
+                "{}:{}:{:02}:{:02}+{:02}:{:06}:{}",
+                PREFIX,
+                artifact.code(),
+                index + 1,
+                data_chunks,
+                options.parity,
+                data.len(),
+                base32_encode(shard)
+            );
+            let crc = crc32(body.as_bytes());
+            format!("{}:{:08X}", body, crc)
+        })
+        .collect();
+
+    Ok(PaperBackup {
+        artifact,
+        length: data.len(),
+        data_chunks,
+        parity_chunks: options.parity,
+        chunks,
+    })
+}
+
+/// A restored backup
+#[derive(Debug)]
+pub struct Restored {
+    pub artifact: PaperArtifact,
+    /// The backed-up data
+    pub data: Zeroizing<Vec<u8>>,
+    /// Numbers of the chunks with no intact line (lost or damaged)
+    pub missing: Vec<usize>,
+    /// Numbers of the missing data chunks rebuilt from parity
+    pub corrected: Vec<usize>,
+}
+
+/// The chunk lines of a document, ignoring everything else
+///
+/// Whitespace inside lines is dropped and letters are uppercased, to
+/// accept chunks as typed back in.
+pub fn chunk_lines(document: &str) -> Vec<String> {
+    document
+        .lines()
+        .map(|line| {
+            line.chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+                .to_ascii_uppercase()
+        })
+        .filter(|line| line.starts_with(PREFIX))
+        .collect()
+}
+
+/// Restore a backup from its chunk lines
+///
+/// Lines that do not parse or fail their CRC are ignored. Fails with
+/// [`GovernanceError::UnrecoverableBackup`] when more chunks are missing
+/// than the backup has parity chunks.
+pub fn restore<S: AsRef<str>>(chunks: &[S]) -> GovernanceResult<Restored> {
+    let parsed: Vec<Chunk> = chunks
+        .iter()
+        .filter_map(|line| parse_chunk(line.as_ref()))
+        .collect();
+    let first = parsed
+        .first()
+        .ok_or_else(|| GovernanceError::InvalidInput("No intact backup chunk found".to_string()))?;
+    if parsed.iter().any(|chunk| !first.same_backup(chunk)) {
+        return Err(GovernanceError::InvalidInput(
+            "Chunks from different backups".to_string(),
+        ));
+    }
+    let (data_chunks, parity_chunks) = (first.data_chunks, first.parity_chunks);
+    let chunk_size = first.bytes.len();
+
+    let mut intact: BTreeMap<usize, &[u8]> = BTreeMap::new();
+    for chunk in &parsed {
+        intact.entry(chunk.index).or_insert(&chunk.bytes[..]);
+    }
+    let missing: Vec<usize> = (0..data_chunks + parity_chunks)
+        .filter(|index| !intact.contains_key(index))
+        .collect();
+    if missing.len() > parity_chunks {
+        return Err(GovernanceError::UnrecoverableBackup {
+            missing: missing.len(),
+            tolerated: parity_chunks,
+        });
+    }
+
+    // Decode from the first `data_chunks` intact chunks, data chunks first
+    let used: Vec<(usize, &[u8])> = intact
+        .iter()
+        .take(data_chunks)
+        .map(|(&index, &bytes)| (index, bytes))
+        .collect();
+    let matrix: Vec<Vec<u8>> = used
+        .iter()
+        .map(|&(index, _)| {
+            if index < data_chunks {
+                (0..data_chunks).map(|j| u8::from(j == index)).collect()
+            } else {
+                parity_row(index - data_chunks, data_chunks)
+            }
+        })
+        .collect();
+    let decoder = invert(matrix).ok_or_else(|| {
+        GovernanceError::Cryptographic("Backup chunks could not be decoded".to_string())
+    })?;
+
+    let mut data = Zeroizing::new(Vec::with_capacity(data_chunks * chunk_size));
+    for row in &decoder {
+        for byte in 0..chunk_size {
+            data.push(
+                row.iter()
+                    .zip(&used)
+                    .fold(0, |acc, (&c, (_, bytes))| acc ^ GF.mul(c, bytes[byte])),
+            );
+        }
+    }
+    data.truncate(first.length);
+
+    Ok(Restored {
+        artifact: first.artifact,
+        data,
+        corrected: missing
+            .iter()
+            .filter(|&&index| index < data_chunks)
+            .map(|index| index + 1)
+            .collect(),
+        missing: missing.iter().map(|index| index + 1).collect(),
+    })
+}
+
+/// An intact chunk line
+struct Chunk {
+    artifact: PaperArtifact,
+    /// Chunk index, from 0
+    index: usize,
+    data_chunks: usize,
+    parity_chunks: usize,
+    length: usize,
+    bytes: Zeroizing<Vec<u8>>,
+}
+
+impl Chunk {
+    fn same_backup(&self, other: &Chunk) -> bool {
+        self.artifact == other.artifact
+            && self.data_chunks == other.data_chunks
+            && self.parity_chunks == other.parity_chunks
+            && self.length == other.length
+            && self.bytes.len() == other.bytes.len()
+    }
+}
+
+fn parse_chunk(line: &str) -> Option<Chunk> {
+    let (body, crc) = line.rsplit_once(':')?;
+    if u32::from_str_radix(crc, 16).ok()? != crc32(body.as_bytes()) {
+        return None;
+    }
+    let fields: Vec<&str> = body.split(':').collect();
+    let [PREFIX, code, number, counts, length, payload] = fields[..] else {
+        return None;
+    };
+    let (data_chunks, parity_chunks) = counts.split_once('+')?;
+    let (data_chunks, parity_chunks): (usize, usize) =
+        (data_chunks.parse().ok()?, parity_chunks.parse().ok()?);
+    let number: usize = number.parse().ok()?;
+    let length: usize = length.parse().ok()?;
+    let bytes = Zeroizing::new(base32_decode(payload)?);
+    let chunks = data_chunks.checked_add(parity_chunks)?;
+    if data_chunks == 0
+        || chunks > MAX_CHUNKS
+        || !(1..=chunks).contains(&number)
+        || bytes.is_empty()
+        || length > data_chunks * bytes.len()
+    {
+        return None;
+    }
+    Some(Chunk {
+        artifact: PaperArtifact::from_code(code)?,
+        index: number - 1,
+        data_chunks,
+        parity_chunks,
+        length,
+        bytes,
+    })
+}
+
+/// Row `row` of the Cauchy matrix generating the parity chunks
+///
+/// Entries are `1 / (x + y)` for distinct `x = data_chunks + row` and
+/// `y = column`, so every square submatrix of the identity stacked on
+/// these rows is invertible: any `data_chunks` chunks decode.
+fn parity_row(row: usize, data_chunks: usize) -> Vec<u8> {
+    let x = (data_chunks + row) as u8;
+    (0..data_chunks).map(|y| GF.inv(x ^ y as u8)).collect()
+}
+
+/// Invert a square matrix over GF(256) by Gauss-Jordan elimination
+fn invert(mut matrix: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<u8>> = (0..n)
+        .map(|i| (0..n).map(|j| u8::from(i == j)).collect())
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| matrix[row][col] != 0)?;
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+        let scale = GF.inv(matrix[col][col]);
+        for value in matrix[col].iter_mut().chain(inverse[col].iter_mut()) {
+            *value = GF.mul(*value, scale);
+        }
+        let (pivot_row, pivot_inverse) = (matrix[col].clone(), inverse[col].clone());
+        for row in (0..n).filter(|&row| row != col) {
+            let factor = matrix[row][col];
+            if factor == 0 {
+                continue;
+            }
+            for (value, &p) in matrix[row].iter_mut().zip(&pivot_row) {
+                *value ^= GF.mul(factor, p);
+            }
+            for (value, &p) in inverse[row].iter_mut().zip(&pivot_inverse) {
+                *value ^= GF.mul(factor, p);
+            }
+        }
+    }
+    Some(inverse)
+}
+
+/// Arithmetic in GF(256) with the polynomial x^8 + x^4 + x^3 + x^2 + 1
+pub(crate) struct Gf {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+pub(crate) static GF: Gf = Gf::new();
+
+impl Gf {
+    const fn new() -> Self {
+        let mut exp = [0u8; 512];
+        let mut log = [0u8; 256];
+        let mut x: u16 = 1;
+        let mut i = 0;
+        while i < 255 {
+            exp[i] = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= 0x11d;
+            }
+            i += 1;
+        }
+        while i < 512 {
+            exp[i] = exp[i - 255];
+            i += 1;
+        }
+        Self { exp, log }
+    }
+
+    pub(crate) fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+    }
+
+    /// Multiplicative inverse; `a` must not be 0
+    pub(crate) fn inv(&self, a: u8) -> u8 {
+        self.exp[255 - self.log[a as usize] as usize]
+    }
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    encoded
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in encoded.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&a| a == c)?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+/// CRC-32 (IEEE 802.3)
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "threshold": "3-of-5",
+            "public_keys": ["02aa", "03bb", "02cc", "03dd", "02ee"],
+        }))
+        .unwrap()
+    }
+
+    /// Change one payload character, as a misread would
+    fn damage(line: &mut String) {
+        let at = line.len() - 12;
+        let replacement = if &line[at..=at] == "A" { "B" } else { "A" };
+        line.replace_range(at..=at, replacement);
+    }
+
+    #[test]
+    fn test_encodings() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        for length in 0..12u8 {
+            let bytes: Vec<u8> = (0..length).map(|i| i.wrapping_mul(37)).collect();
+            assert_eq!(base32_decode(&base32_encode(&bytes)).unwrap(), bytes);
+        }
+        assert!(base32_decode("MZXW6YT1").is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = config();
+        let backup = backup(
+            PaperArtifact::MultisigConfig,
+            &data,
+            &PaperOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(backup.chunks.len(), backup.data_chunks + 4);
+
+        // Chunks are read back from the document in any order
+        let mut lines = chunk_lines(&backup.to_document().to_lowercase());
+        lines.reverse();
+        let restored = restore(&lines).unwrap();
+        assert_eq!(restored.artifact, PaperArtifact::MultisigConfig);
+        assert_eq!(*restored.data, data);
+        assert!(restored.missing.is_empty());
+        assert!(restored.corrected.is_empty());
+    }
+
+    #[test]
+    fn test_recovers_up_to_parity() {
+        let data = config();
+        let options = PaperOptions {
+            chunk_size: 8,
+            parity: 3,
+        };
+        let backup = backup(PaperArtifact::Keyset, &data, &options).unwrap();
+
+        // Lose data chunk 1, damage data chunk 4 and parity chunk 2
+        let parity_2 = backup.data_chunks + 1;
+        let mut lines = backup.chunks.clone();
+        damage(&mut lines[3]);
+        damage(&mut lines[parity_2]);
+        lines.remove(0);
+
+        let restored = restore(&lines).unwrap();
+        assert_eq!(*restored.data, data);
+        assert_eq!(restored.missing, vec![1, 4, parity_2 + 1]);
+        assert_eq!(restored.corrected, vec![1, 4]);
+
+        // One chunk more than the parity cannot be recovered
+        lines.remove(0);
+        match restore(&lines) {
+            Err(GovernanceError::UnrecoverableBackup { missing, tolerated }) => {
+                assert_eq!(missing, 4);
+                assert_eq!(tolerated, 3);
+            }
+            other => panic!("expected an unrecoverable backup, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_recovers_from_parity_alone() {
+        let data = b"0123456789abcdefghijklmnopqrstuv".to_vec();
+        let options = PaperOptions {
+            chunk_size: 16,
+            parity: 2,
+        };
+        let backup = backup(PaperArtifact::KeyShare, &data, &options).unwrap();
+        assert!(backup.to_document().contains("SECRET"));
+
+        let restored = restore(&backup.chunks[2..]).unwrap();
+        assert_eq!(*restored.data, data);
+        assert_eq!(restored.corrected, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_rejects_mixed_backups() {
+        let a = backup(
+            PaperArtifact::Keyset,
+            b"first keyset",
+            &PaperOptions::default(),
+        )
+        .unwrap();
+        let b = backup(
+            PaperArtifact::Keyset,
+            b"other keyset!",
+            &PaperOptions::default(),
+        )
+        .unwrap();
+        let mixed = vec![a.chunks[0].clone(), b.chunks[1].clone()];
+        assert!(restore(&mixed).is_err());
+        assert!(restore::<&str>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_options() {
+        let too_large = PaperOptions {
+            chunk_size: MAX_CHUNK_SIZE + 1,
+            parity: 1,
+        };
+        assert!(backup(PaperArtifact::Keyset, b"x", &too_large).is_err());
+        let too_many = PaperOptions {
+            chunk_size: 1,
+            parity: 250,
+        };
+        assert!(backup(PaperArtifact::Keyset, b"too many chunks", &too_many).is_err());
+        assert!(backup(PaperArtifact::Keyset, b"", &PaperOptions::default()).is_err());
+        assert_eq!(
+            "key-share".parse::<PaperArtifact>().unwrap(),
+            PaperArtifact::KeyShare
+        );
+        assert!("seed".parse::<PaperArtifact>().is_err());
+    }
+}
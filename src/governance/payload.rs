@@ -0,0 +1,277 @@
+//! # Signed Payloads
+//!
+//! Arbitrary bytes signed under an explicit signature scheme.
+//!
+//! The scheme tag records the signature algorithm and digest so verification
+//! can pick the right algorithm, and new schemes can be introduced without
+//! reinterpreting old signatures. The tag is part of the signed bytes
+//! (`<scheme>:<payload>`), so changing it invalidates the signature.
+
+#[cfg(any(feature = "signing", test))]
+use rand::{rngs::OsRng, RngCore};
+#[cfg(any(feature = "signing", test))]
+use secp256k1::Keypair;
+use secp256k1::{ecdsa, schnorr, Message};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(any(feature = "signing", test))]
+use crate::governance::context::signing_context;
+use crate::governance::context::verification_context;
+use crate::governance::error::{GovernanceError, GovernanceResult};
+#[cfg(any(feature = "signing", test))]
+use crate::governance::GovernanceKeypair;
+use crate::governance::PublicKey;
+
+/// Signature algorithm and digest used for a payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SigScheme {
+    /// ECDSA over the SHA256 digest
+    EcdsaSha256,
+    /// BIP340 Schnorr over the SHA256 digest
+    SchnorrSha256,
+}
+
+impl SigScheme {
+    /// Scheme tag as written in signed bytes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SigScheme::EcdsaSha256 => "ecdsa-sha256",
+            SigScheme::SchnorrSha256 => "schnorr-sha256",
+        }
+    }
+}
+
+impl fmt::Display for SigScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SigScheme {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ecdsa-sha256" => Ok(SigScheme::EcdsaSha256),
+            "schnorr-sha256" => Ok(SigScheme::SchnorrSha256),
+            _ => Err(GovernanceError::InvalidInput(format!(
+                "Unknown signature scheme: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// A public key together with the scheme it verifies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey {
+    pub public_key: PublicKey,
+    pub scheme: SigScheme,
+}
+
+impl VerifyingKey {
+    /// Create a verifying key for a scheme
+    pub fn new(public_key: PublicKey, scheme: SigScheme) -> Self {
+        Self { public_key, scheme }
+    }
+}
+
+/// Bytes signed under a recorded signature scheme
+///
+/// Serializes with the payload and signature hex-encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedPayload {
+    /// Scheme the signature was made with
+    pub scheme: SigScheme,
+    /// Signed bytes
+    #[serde(with = "hex_bytes")]
+    pub payload: Vec<u8>,
+    /// Raw signature (64-byte compact ECDSA or BIP340 Schnorr)
+    #[serde(with = "hex_bytes")]
+    pub signature: Vec<u8>,
+}
+
+/// Serde adapter writing bytes as a hex string
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex_str = String::deserialize(deserializer)?;
+        hex::decode(hex_str).map_err(serde::de::Error::custom)
+    }
+}
+
+impl SignedPayload {
+    /// Sign a payload under a scheme
+    #[cfg(any(feature = "signing", test))]
+    pub fn sign(
+        keypair: &GovernanceKeypair,
+        scheme: SigScheme,
+        payload: Vec<u8>,
+    ) -> GovernanceResult<Self> {
+        let secp = signing_context();
+        let message = Self::digest(scheme, &payload)?;
+
+        let signature = match scheme {
+            SigScheme::EcdsaSha256 => secp
+                .sign_ecdsa(&message, keypair.secret_key.expose())
+                .serialize_compact()
+                .to_vec(),
+            SigScheme::SchnorrSha256 => {
+                let mut aux_rand = [0u8; 32];
+                OsRng.try_fill_bytes(&mut aux_rand).map_err(|e| {
+                    GovernanceError::Cryptographic(format!("entropy unavailable: {}", e))
+                })?;
+                let signing_keypair = Keypair::from_secret_key(secp, keypair.secret_key.expose());
+                secp.sign_schnorr_with_aux_rand(&message, &signing_keypair, &aux_rand)
+                    .as_ref()
+                    .to_vec()
+            }
+        };
+
+        Ok(Self {
+            scheme,
+            payload,
+            signature,
+        })
+    }
+
+    /// Bytes covered by the signature: `<scheme>:<payload>`
+    pub fn signing_bytes(scheme: SigScheme, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = scheme.as_str().as_bytes().to_vec();
+        bytes.push(b':');
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn digest(scheme: SigScheme, payload: &[u8]) -> GovernanceResult<Message> {
+        let digest = Sha256::digest(Self::signing_bytes(scheme, payload));
+        Message::from_digest_slice(&digest)
+            .map_err(|e| GovernanceError::Cryptographic(format!("Invalid message hash: {}", e)))
+    }
+
+    /// Verify the signature with a key for the payload's scheme
+    ///
+    /// Errors if the key expects a different scheme; returns `Ok(false)` if
+    /// the signature does not verify.
+    pub fn verify(&self, key: &VerifyingKey) -> GovernanceResult<bool> {
+        if self.scheme != key.scheme {
+            return Err(GovernanceError::SignatureVerification(format!(
+                "payload is signed with {} but the key expects {}",
+                self.scheme, key.scheme
+            )));
+        }
+
+        let secp = verification_context();
+        let message = Self::digest(self.scheme, &self.payload)?;
+
+        match self.scheme {
+            SigScheme::EcdsaSha256 => {
+                let Ok(signature) = ecdsa::Signature::from_compact(&self.signature) else {
+                    return Ok(false);
+                };
+                Ok(secp
+                    .verify_ecdsa(&message, &signature, &key.public_key.inner)
+                    .is_ok())
+            }
+            SigScheme::SchnorrSha256 => {
+                let Ok(signature) = schnorr::Signature::from_slice(&self.signature) else {
+                    return Ok(false);
+                };
+                let (x_only, _) = key.public_key.inner.x_only_public_key();
+                Ok(secp.verify_schnorr(&signature, &message, &x_only).is_ok())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(scheme: SigScheme) -> (GovernanceKeypair, SignedPayload) {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let payload = SignedPayload::sign(&keypair, scheme, b"release v1.0.0".to_vec()).unwrap();
+        (keypair, payload)
+    }
+
+    #[test]
+    fn test_ecdsa_payload() {
+        let (keypair, payload) = signed(SigScheme::EcdsaSha256);
+        let key = VerifyingKey::new(keypair.public_key(), SigScheme::EcdsaSha256);
+
+        assert!(payload.verify(&key).unwrap());
+        assert!(crate::governance::signatures::Signature::from_bytes(&payload.signature).is_ok());
+    }
+
+    #[test]
+    fn test_schnorr_payload() {
+        let (keypair, payload) = signed(SigScheme::SchnorrSha256);
+        let key = VerifyingKey::new(keypair.public_key(), SigScheme::SchnorrSha256);
+
+        assert!(payload.verify(&key).unwrap());
+        assert!(schnorr::Signature::from_slice(&payload.signature).is_ok());
+    }
+
+    #[test]
+    fn test_scheme_mismatch_rejected() {
+        let (keypair, payload) = signed(SigScheme::EcdsaSha256);
+        let key = VerifyingKey::new(keypair.public_key(), SigScheme::SchnorrSha256);
+
+        let err = payload.verify(&key).unwrap_err();
+        assert!(err.to_string().contains("ecdsa-sha256"));
+    }
+
+    #[test]
+    fn test_tampered_scheme_tag() {
+        let (keypair, mut payload) = signed(SigScheme::EcdsaSha256);
+        payload.scheme = SigScheme::SchnorrSha256;
+
+        let key = VerifyingKey::new(keypair.public_key(), SigScheme::SchnorrSha256);
+        assert!(!payload.verify(&key).unwrap());
+
+        let (keypair, mut payload) = signed(SigScheme::SchnorrSha256);
+        payload.scheme = SigScheme::EcdsaSha256;
+
+        let key = VerifyingKey::new(keypair.public_key(), SigScheme::EcdsaSha256);
+        assert!(!payload.verify(&key).unwrap());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let (keypair, payload) = signed(SigScheme::SchnorrSha256);
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["scheme"], "schnorr-sha256");
+        assert_eq!(json["payload"], hex::encode(b"release v1.0.0"));
+
+        let parsed: SignedPayload = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, payload);
+        let key = VerifyingKey::new(keypair.public_key(), SigScheme::SchnorrSha256);
+        assert!(parsed.verify(&key).unwrap());
+
+        let bad = serde_json::json!({"scheme": "ecdsa-sha256", "payload": "zz", "signature": ""});
+        assert!(serde_json::from_value::<SignedPayload>(bad).is_err());
+    }
+
+    #[test]
+    fn test_scheme_parsing() {
+        assert_eq!(
+            "schnorr-sha256".parse::<SigScheme>().unwrap(),
+            SigScheme::SchnorrSha256
+        );
+        assert!("rsa".parse::<SigScheme>().is_err());
+        assert_eq!(
+            serde_json::to_string(&SigScheme::EcdsaSha256).unwrap(),
+            "\"ecdsa-sha256\""
+        );
+    }
+}
@@ -0,0 +1,459 @@
+//! # Trust-on-First-Use Key Pinning
+//!
+//! Protection against key substitution for deployments without a governance
+//! configuration.
+//!
+//! The first successful verification for an artifact source (a repository
+//! or URL identity) pins the maintainer keys and threshold it was verified
+//! against. Later verifications must use the same keys and threshold; a new
+//! key, a removed key or a changed threshold is rejected until explicitly
+//! accepted, which updates the pin and records an audit note.
+//!
+//! The pin file is versioned JSON carrying a SHA256 integrity hash over its
+//! contents, so accidental or careless edits are detected.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::{PublicKey, Threshold};
+use crate::util::fs::write_atomic;
+use crate::util::humanize::format_age_at;
+
+/// Current pin file format version
+pub const PIN_FILE_VERSION: u32 = 1;
+
+/// Default pin file location (`~/.config/blvm/pins.json`)
+pub fn default_pin_path() -> PathBuf {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    config_dir.join("blvm").join("pins.json")
+}
+
+/// Check keys and threshold against the pin file and save any change
+///
+/// Uses [`default_pin_path`] unless `pin_file` is given. Call this only once
+/// the keys have verified the artifact, so a failed verification never pins
+/// or updates keys.
+pub fn check_pin(
+    pin_file: Option<&Path>,
+    source: &str,
+    keys: &[PublicKey],
+    threshold: Threshold,
+    accept_change: Option<&str>,
+) -> GovernanceResult<PinOutcome> {
+    let pin_path = pin_file.map_or_else(default_pin_path, Path::to_path_buf);
+    let mut store = PinStore::load(&pin_path)?;
+    let outcome = store.check(source, keys, threshold, accept_change)?;
+    if outcome != PinOutcome::Consistent {
+        store.save()?;
+    }
+    Ok(outcome)
+}
+
+/// Audit record of an accepted key change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyChangeNote {
+    /// When the change was accepted
+    pub accepted_at: DateTime<Utc>,
+    /// What changed
+    pub change: String,
+    /// Operator note
+    pub note: String,
+}
+
+/// Keys and threshold pinned for one artifact source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pin {
+    /// Hex-encoded compressed public keys
+    pub keys: BTreeSet<String>,
+    /// Signature threshold
+    pub threshold: Threshold,
+    /// When the source was first pinned
+    pub pinned_at: DateTime<Utc>,
+    /// Accepted key changes, oldest first
+    #[serde(default)]
+    pub changes: Vec<KeyChangeNote>,
+}
+
+/// Difference between a pin and the keys presented now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChange {
+    /// Keys not in the pin
+    pub added: Vec<String>,
+    /// Pinned keys no longer presented
+    pub removed: Vec<String>,
+    /// Pinned threshold
+    pub old_threshold: Threshold,
+    /// Threshold presented now
+    pub new_threshold: Threshold,
+}
+
+impl KeyChange {
+    fn between(pin: &Pin, keys: &BTreeSet<String>, threshold: Threshold) -> Option<Self> {
+        let change = Self {
+            added: keys.difference(&pin.keys).cloned().collect(),
+            removed: pin.keys.difference(keys).cloned().collect(),
+            old_threshold: pin.threshold,
+            new_threshold: threshold,
+        };
+        let changed = !change.added.is_empty()
+            || !change.removed.is_empty()
+            || change.old_threshold != change.new_threshold;
+        changed.then_some(change)
+    }
+}
+
+impl fmt::Display for KeyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        for key in &self.added {
+            parts.push(format!("NEW KEY {}", key));
+        }
+        for key in &self.removed {
+            parts.push(format!("REMOVED KEY {}", key));
+        }
+        let (old, new) = (self.old_threshold, self.new_threshold);
+        if new.required() < old.required() {
+            parts.push(format!("THRESHOLD LOWERED {} -> {}", old, new));
+        } else if new.required() > old.required() {
+            parts.push(format!("threshold raised {} -> {}", old, new));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Result of checking keys against the pin store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinOutcome {
+    /// First use: the keys were pinned
+    Pinned,
+    /// The keys match the pin
+    Consistent,
+    /// The keys changed and the change was accepted
+    ChangeAccepted(KeyChange),
+}
+
+impl fmt::Display for PinOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PinOutcome::Pinned => write!(f, "pinned on first use"),
+            PinOutcome::Consistent => write!(f, "consistent with pin"),
+            PinOutcome::ChangeAccepted(change) => write!(f, "key change accepted: {}", change),
+        }
+    }
+}
+
+/// Fields covered by the integrity hash
+#[derive(Serialize)]
+struct PinContents<'a> {
+    version: u32,
+    pins: &'a BTreeMap<String, Pin>,
+}
+
+/// On-disk pin file
+#[derive(Serialize, Deserialize)]
+struct PinFile {
+    version: u32,
+    pins: BTreeMap<String, Pin>,
+    integrity: String,
+}
+
+fn integrity_hash(pins: &BTreeMap<String, Pin>) -> GovernanceResult<String> {
+    let contents = serde_json::to_vec(&PinContents {
+        version: PIN_FILE_VERSION,
+        pins,
+    })
+    .map_err(|e| GovernanceError::Serialization(e.to_string()))?;
+    Ok(hex::encode(Sha256::digest(&contents)))
+}
+
+/// Pinned keys for every artifact source, backed by a pin file
+#[derive(Debug, Clone)]
+pub struct PinStore {
+    path: PathBuf,
+    pins: BTreeMap<String, Pin>,
+    /// Audit records added since the last save, by source
+    appended: Vec<(String, KeyChangeNote)>,
+}
+
+impl PinStore {
+    /// Load the pin file, or start empty if it does not exist
+    pub fn load<P: AsRef<Path>>(path: P) -> GovernanceResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                pins: BTreeMap::new(),
+                appended: Vec::new(),
+            });
+        }
+
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            GovernanceError::Serialization(format!(
+                "Failed to read pin file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let file: PinFile = serde_json::from_str(&json).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid pin file {}: {}", path.display(), e))
+        })?;
+
+        if file.version != PIN_FILE_VERSION {
+            return Err(GovernanceError::Serialization(format!(
+                "Unsupported pin file version {} in {} (expected {})",
+                file.version,
+                path.display(),
+                PIN_FILE_VERSION
+            )));
+        }
+        if integrity_hash(&file.pins)? != file.integrity {
+            return Err(GovernanceError::Serialization(format!(
+                "Pin file {} failed its integrity check",
+                path.display()
+            )));
+        }
+
+        Ok(Self {
+            path,
+            pins: file.pins,
+            appended: Vec::new(),
+        })
+    }
+
+    /// Write the pin file, replacing it atomically
+    ///
+    /// Emits an `AuditRecordAppended` event for each key change accepted
+    /// since the last save, once the file is written.
+    pub fn save(&mut self) -> GovernanceResult<()> {
+        let file = PinFile {
+            version: PIN_FILE_VERSION,
+            integrity: integrity_hash(&self.pins)?,
+            pins: self.pins.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| GovernanceError::Serialization(e.to_string()))?;
+
+        write_atomic(&self.path, json.as_bytes()).map_err(|e| {
+            GovernanceError::Serialization(format!(
+                "Failed to write pin file {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+
+        for (source, record) in self.appended.drain(..) {
+            emit_event!(AuditRecordAppended {
+                source: source,
+                accepted_at: record.accepted_at,
+                change: record.change,
+                note: record.note,
+            });
+        }
+        Ok(())
+    }
+
+    /// Pin file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The pin for an artifact source
+    pub fn get(&self, source: &str) -> Option<&Pin> {
+        self.pins.get(source)
+    }
+
+    /// Check keys and threshold against the pin for a source
+    ///
+    /// Pins them on first use. A change is rejected unless `accept_change`
+    /// carries an audit note, in which case the pin is updated. The caller
+    /// saves the store.
+    pub fn check(
+        &mut self,
+        source: &str,
+        keys: &[PublicKey],
+        threshold: Threshold,
+        accept_change: Option<&str>,
+    ) -> GovernanceResult<PinOutcome> {
+        let keys: BTreeSet<String> = keys.iter().map(|key| key.to_string()).collect();
+        let now = Utc::now();
+
+        let Some(pin) = self.pins.get_mut(source) else {
+            self.pins.insert(
+                source.to_string(),
+                Pin {
+                    keys,
+                    threshold,
+                    pinned_at: now,
+                    changes: Vec::new(),
+                },
+            );
+            return Ok(PinOutcome::Pinned);
+        };
+
+        let Some(change) = KeyChange::between(pin, &keys, threshold) else {
+            return Ok(PinOutcome::Consistent);
+        };
+
+        let Some(note) = accept_change else {
+            emit_event!(KeyChangeRejected {
+                source: source.to_string(),
+                change: change.to_string(),
+            });
+            return Err(GovernanceError::SignatureVerification(format!(
+                "KEY CHANGE for {} since first use ({}, {}): {}; rerun with --accept-key-change to accept",
+                source,
+                pin.pinned_at.to_rfc3339(),
+                format_age_at(pin.pinned_at.into(), now.into()),
+                change
+            )));
+        };
+
+        pin.keys = keys;
+        pin.threshold = threshold;
+        let record = KeyChangeNote {
+            accepted_at: now,
+            change: change.to_string(),
+            note: note.to_string(),
+        };
+        pin.changes.push(record.clone());
+        self.appended.push((source.to_string(), record));
+        emit_event!(KeyChangeAccepted {
+            source: source.to_string(),
+            change: change.to_string(),
+            note: note.to_string(),
+        });
+        Ok(PinOutcome::ChangeAccepted(change))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    fn keys(n: usize) -> Vec<PublicKey> {
+        (0..n)
+            .map(|_| GovernanceKeypair::generate().unwrap().public_key())
+            .collect()
+    }
+
+    fn threshold(required: usize, total: usize) -> Threshold {
+        Threshold::new(required, total).unwrap()
+    }
+
+    #[test]
+    fn test_first_use_pins() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pins.json");
+        let keys = keys(3);
+
+        let mut store = PinStore::load(&path).unwrap();
+        assert_eq!(
+            store
+                .check("github.com/org/repo", &keys, threshold(2, 3), None)
+                .unwrap(),
+            PinOutcome::Pinned
+        );
+        store.save().unwrap();
+
+        let store = PinStore::load(&path).unwrap();
+        let pin = store.get("github.com/org/repo").unwrap();
+        assert_eq!(pin.threshold, threshold(2, 3));
+        assert_eq!(pin.keys.len(), 3);
+    }
+
+    #[test]
+    fn test_consistent_second_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pins.json");
+        let keys = keys(3);
+
+        let mut store = PinStore::load(&path).unwrap();
+        store.check("repo", &keys, threshold(2, 3), None).unwrap();
+        store.save().unwrap();
+
+        let mut reordered = keys.clone();
+        reordered.reverse();
+        let mut store = PinStore::load(&path).unwrap();
+        assert_eq!(
+            store
+                .check("repo", &reordered, threshold(2, 3), None)
+                .unwrap(),
+            PinOutcome::Consistent
+        );
+    }
+
+    #[test]
+    fn test_key_change_requires_acceptance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pins.json");
+        let original = keys(3);
+
+        let mut store = PinStore::load(&path).unwrap();
+        store
+            .check("repo", &original, threshold(2, 3), None)
+            .unwrap();
+        store.save().unwrap();
+
+        // One key replaced, threshold lowered
+        let mut substituted = original.clone();
+        substituted[2] = keys(1).remove(0);
+
+        let mut store = PinStore::load(&path).unwrap();
+        let err = store
+            .check("repo", &substituted, threshold(1, 3), None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("NEW KEY"));
+        assert!(err.contains("REMOVED KEY"));
+        assert!(err.contains("THRESHOLD LOWERED 2-of-3 -> 1-of-3"));
+        assert_eq!(store.get("repo").unwrap().keys.len(), 3);
+
+        let outcome = store
+            .check(
+                "repo",
+                &substituted,
+                threshold(1, 3),
+                Some("maintainer rotated key"),
+            )
+            .unwrap();
+        assert!(matches!(outcome, PinOutcome::ChangeAccepted(_)));
+        store.save().unwrap();
+
+        let store = PinStore::load(&path).unwrap();
+        let pin = store.get("repo").unwrap();
+        assert_eq!(pin.threshold, threshold(1, 3));
+        assert!(pin.keys.contains(&substituted[2].to_string()));
+        assert_eq!(pin.changes.len(), 1);
+        assert_eq!(pin.changes[0].note, "maintainer rotated key");
+        assert!(pin.changes[0].change.contains("NEW KEY"));
+    }
+
+    #[test]
+    fn test_integrity_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pins.json");
+
+        let mut store = PinStore::load(&path).unwrap();
+        store
+            .check("repo", &keys(2), threshold(2, 2), None)
+            .unwrap();
+        store.save().unwrap();
+
+        let tampered = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("\"threshold\": \"2-of-2\"", "\"threshold\": \"1-of-2\"");
+        std::fs::write(&path, tampered).unwrap();
+
+        let err = PinStore::load(&path).unwrap_err().to_string();
+        assert!(err.contains("integrity"));
+    }
+}
@@ -0,0 +1,987 @@
+//! # Verification Pipeline
+//!
+//! Artifact verification as an ordered pipeline of stages.
+//!
+//! The built-in checks (hash, signature, revocation, threshold, escalation,
+//! expiry) are stages like any other, so deployments can add their own
+//! checks (for example "the artifact appears in our transparency log")
+//! without forking the verify tools. Each stage records an entry in the shared
+//! [`VerificationReport`] and is either required (its failure fails
+//! verification) or advisory (its failure is reported only).
+//!
+//! Stages declare which stages must run before them; [`PipelineBuilder::build`]
+//! rejects pipelines that violate those constraints or name a stage that is
+//! not in the pipeline. Every pipeline must run the signature and threshold
+//! stages as required, so a policy cannot turn verification off.
+//!
+//! A policy file selects the built-in stages and their requirement levels:
+//!
+//! ```toml
+//! max_signature_age = "52w"
+//! revoked_keys = ["02..."]
+//!
+//! [[stages]]
+//! name = "hash"
+//!
+//! [[stages]]
+//! name = "expiry"
+//! requirement = "advisory"
+//! ```
+//!
+//! `[[escalation]]` tables add threshold escalation rules (see
+//! [`escalation`](crate::governance::escalation)).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::time::Duration;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::escalation::{EscalationRule, EscalationStage};
+use crate::governance::{PublicKey, Signature, SignedAttributes, SignedTimestamp, Threshold};
+use crate::util::humanize::{format_duration, parse_duration};
+
+/// Built-in stage names, in their default order
+pub const BUILTIN_STAGES: [&str; 6] = [
+    "hash",
+    "signature",
+    "revocation",
+    "threshold",
+    "escalation",
+    "expiry",
+];
+
+/// Stages every pipeline must run as required
+pub const MANDATORY_STAGES: [&str; 2] = ["signature", "threshold"];
+
+/// How far in the future a signed timestamp may be
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Outcome of one stage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "detail", rename_all = "lowercase")]
+pub enum StageResult {
+    /// The check passed
+    Pass(String),
+    /// The check failed
+    Fail(String),
+    /// The check did not apply
+    Skipped(String),
+}
+
+impl StageResult {
+    /// Whether the check failed
+    pub fn is_fail(&self) -> bool {
+        matches!(self, StageResult::Fail(_))
+    }
+}
+
+/// Whether a stage's failure fails verification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Requirement {
+    /// Failure fails verification
+    #[default]
+    Required,
+    /// Failure is reported only
+    Advisory,
+}
+
+/// State shared by the stages of one verification
+#[derive(Debug, Clone)]
+pub struct VerificationContext {
+    /// Bytes the signatures cover
+    pub message: Vec<u8>,
+    /// Artifact path or reference
+    pub artifact: String,
+    /// Hex SHA256 of the artifact as computed now
+    pub artifact_hash: String,
+    /// Hashes recorded alongside the signatures
+    pub recorded_hashes: Vec<String>,
+    /// Signatures to verify
+    pub signatures: Vec<Signature>,
+    /// Signatures already found valid under none of the keys while loading
+    /// (see [`SignatureTally`](crate::governance::aggregation::SignatureTally))
+    pub rejected_signatures: usize,
+    /// Maintainer public keys
+    pub public_keys: Vec<PublicKey>,
+    /// Required threshold, if any
+    pub threshold: Option<Threshold>,
+    /// Signing times recorded with the signatures (unsigned, informational)
+    pub signed_at: Vec<DateTime<Utc>>,
+    /// Signing times attested by the signers
+    pub timestamps: Vec<SignedTimestamp>,
+    /// Attributes attested by the signers
+    pub attributes: Vec<SignedAttributes>,
+    /// Whether a nested (team) approval was verified, if the caller checked
+    /// one
+    pub nested_approved: Option<bool>,
+    /// Time to verify at
+    pub now: DateTime<Utc>,
+    /// Indices of the keys with a valid signature (set by the signature stage)
+    pub valid_signers: Vec<usize>,
+    /// Signatures not valid under any key (set by the signature stage)
+    pub invalid_signatures: usize,
+}
+
+impl VerificationContext {
+    /// Create a context for an artifact and the message signed for it
+    pub fn new(message: Vec<u8>, artifact: &str, artifact_hash: &str) -> Self {
+        Self {
+            message,
+            artifact: artifact.to_string(),
+            artifact_hash: artifact_hash.to_string(),
+            recorded_hashes: Vec::new(),
+            signatures: Vec::new(),
+            rejected_signatures: 0,
+            public_keys: Vec::new(),
+            threshold: None,
+            signed_at: Vec::new(),
+            timestamps: Vec::new(),
+            attributes: Vec::new(),
+            nested_approved: None,
+            now: Utc::now(),
+            valid_signers: Vec::new(),
+            invalid_signatures: 0,
+        }
+    }
+}
+
+/// One check in a verification pipeline
+pub trait VerificationStage {
+    /// Stage name, unique within a pipeline
+    fn name(&self) -> &str;
+
+    /// Stages that must run before this one
+    fn after(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Run the check
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult;
+}
+
+/// Checks the artifact hash against the hashes recorded with the signatures
+#[derive(Debug, Clone, Default)]
+pub struct HashStage;
+
+impl VerificationStage for HashStage {
+    fn name(&self) -> &str {
+        "hash"
+    }
+
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult {
+        if ctx.recorded_hashes.is_empty() {
+            return StageResult::Skipped("no recorded hash".to_string());
+        }
+        match ctx
+            .recorded_hashes
+            .iter()
+            .find(|hash| !hash.eq_ignore_ascii_case(&ctx.artifact_hash))
+        {
+            Some(recorded) => StageResult::Fail(format!(
+                "signature records hash {}, artifact hashes to {}",
+                recorded, ctx.artifact_hash
+            )),
+            None => StageResult::Pass(format!("artifact hash {}", ctx.artifact_hash)),
+        }
+    }
+}
+
+/// Verifies each signature against the maintainer keys
+#[derive(Debug, Clone, Default)]
+pub struct SignatureStage;
+
+impl VerificationStage for SignatureStage {
+    fn name(&self) -> &str {
+        "signature"
+    }
+
+    fn after(&self) -> &[&str] {
+        &["hash"]
+    }
+
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult {
+        if ctx.public_keys.is_empty() {
+            return StageResult::Fail("no public keys to verify against".to_string());
+        }
+
+        let mut signers = HashSet::new();
+        let mut invalid = ctx.rejected_signatures;
+        for signature in &ctx.signatures {
+            let signer = ctx.public_keys.iter().position(|key| {
+                crate::governance::verify_signature(signature, &ctx.message, key).unwrap_or(false)
+            });
+            match signer {
+                Some(index) => {
+                    signers.insert(index);
+                }
+                None => invalid += 1,
+            }
+        }
+
+        ctx.valid_signers = signers.into_iter().collect();
+        ctx.valid_signers.sort_unstable();
+        ctx.invalid_signatures = invalid;
+
+        if invalid > 0 {
+            StageResult::Fail(format!("{} signature(s) did not verify", invalid))
+        } else if ctx.valid_signers.is_empty() {
+            StageResult::Fail("no signatures".to_string())
+        } else {
+            StageResult::Pass(format!("{} valid signer(s)", ctx.valid_signers.len()))
+        }
+    }
+}
+
+/// Rejects signatures made with revoked keys
+#[derive(Debug, Clone, Default)]
+pub struct RevocationStage {
+    pub revoked: Vec<PublicKey>,
+}
+
+impl VerificationStage for RevocationStage {
+    fn name(&self) -> &str {
+        "revocation"
+    }
+
+    fn after(&self) -> &[&str] {
+        &["signature"]
+    }
+
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult {
+        if self.revoked.is_empty() {
+            return StageResult::Skipped("no revoked keys".to_string());
+        }
+        let revoked: Vec<&PublicKey> = ctx
+            .valid_signers
+            .iter()
+            .map(|&i| &ctx.public_keys[i])
+            .filter(|key| self.revoked.contains(key))
+            .collect();
+        for key in &revoked {
+            emit_event!(RevokedKeyEncountered {
+                public_key: (*key).clone()
+            });
+        }
+        if revoked.is_empty() {
+            StageResult::Pass("no revoked signers".to_string())
+        } else {
+            let revoked: Vec<String> = revoked.iter().map(|key| key.to_string()).collect();
+            StageResult::Fail(format!("signed with revoked key(s) {}", revoked.join(", ")))
+        }
+    }
+}
+
+/// Requires enough distinct valid signers
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdStage;
+
+impl VerificationStage for ThresholdStage {
+    fn name(&self) -> &str {
+        "threshold"
+    }
+
+    fn after(&self) -> &[&str] {
+        &["signature"]
+    }
+
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult {
+        let signers = ctx.valid_signers.len();
+        let Some(threshold) = ctx.threshold else {
+            return if signers > 0 {
+                StageResult::Pass(format!("{} valid signer(s), no threshold set", signers))
+            } else {
+                StageResult::Fail("no valid signers".to_string())
+            };
+        };
+
+        let (required, total) = (threshold.required(), threshold.total());
+        if ctx.public_keys.len() != total {
+            StageResult::Fail(format!(
+                "Expected {} public keys, got {}",
+                total,
+                ctx.public_keys.len()
+            ))
+        } else if threshold.is_met(signers) {
+            StageResult::Pass(format!("{} of {} (need {})", signers, total, required))
+        } else {
+            StageResult::Fail(format!("{} of {} (need {})", signers, total, required))
+        }
+    }
+}
+
+/// Rejects signatures older than a maximum age
+///
+/// Only signed timestamps count: every valid signer must have attested its
+/// signing time, since the recorded time is not covered by any signature.
+#[derive(Debug, Clone, Default)]
+pub struct ExpiryStage {
+    pub max_age: Option<Duration>,
+}
+
+impl VerificationStage for ExpiryStage {
+    fn name(&self) -> &str {
+        "expiry"
+    }
+
+    fn after(&self) -> &[&str] {
+        &["signature"]
+    }
+
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult {
+        let Some(max_age) = self.max_age else {
+            return StageResult::Skipped("no maximum signature age".to_string());
+        };
+        if ctx.valid_signers.is_empty() {
+            return StageResult::Fail("no valid signers".to_string());
+        }
+
+        // Each signer's latest attested time
+        let mut oldest: Option<DateTime<Utc>> = None;
+        for &index in &ctx.valid_signers {
+            let key = &ctx.public_keys[index];
+            let Some(signed_at) = ctx
+                .timestamps
+                .iter()
+                .filter(|timestamp| timestamp.verify(&ctx.message, key))
+                .map(|timestamp| timestamp.signed_at)
+                .max()
+            else {
+                return StageResult::Fail(format!("signer {} has no signed timestamp", key));
+            };
+            if (signed_at - ctx.now)
+                .to_std()
+                .is_ok_and(|ahead| ahead > MAX_CLOCK_SKEW)
+            {
+                return StageResult::Fail(format!(
+                    "signer {} timestamp {} is in the future",
+                    key,
+                    signed_at.to_rfc3339()
+                ));
+            }
+            oldest = Some(oldest.map_or(signed_at, |oldest| oldest.min(signed_at)));
+        }
+
+        let oldest = oldest.unwrap_or(ctx.now);
+        let age = (ctx.now - oldest).to_std().unwrap_or_default();
+        if age > max_age {
+            StageResult::Fail(format!(
+                "oldest signature is {} old (maximum {})",
+                format_duration(age),
+                format_duration(max_age)
+            ))
+        } else {
+            StageResult::Pass(format!("oldest signature is {} old", format_duration(age)))
+        }
+    }
+}
+
+/// One stage's entry in the report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportEntry {
+    pub stage: String,
+    pub requirement: Requirement,
+    pub result: StageResult,
+}
+
+/// Entries recorded by every stage of a pipeline run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VerificationReport {
+    pub entries: Vec<ReportEntry>,
+}
+
+impl VerificationReport {
+    /// Whether every required stage passed or was skipped
+    pub fn passed(&self) -> bool {
+        !self
+            .entries
+            .iter()
+            .any(|e| e.requirement == Requirement::Required && e.result.is_fail())
+    }
+
+    /// The entry for a stage
+    pub fn entry(&self, stage: &str) -> Option<&ReportEntry> {
+        self.entries.iter().find(|e| e.stage == stage)
+    }
+
+    /// Failed advisory stages
+    pub fn warnings(&self) -> impl Iterator<Item = &ReportEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.requirement == Requirement::Advisory && e.result.is_fail())
+    }
+
+    /// Process exit code: 0 if verification passed, 1 otherwise
+    pub fn exit_code(&self) -> i32 {
+        if self.passed() {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let (status, detail) = match &entry.result {
+                StageResult::Pass(detail) => ("pass", detail),
+                StageResult::Fail(detail) => ("FAIL", detail),
+                StageResult::Skipped(detail) => ("skip", detail),
+            };
+            let advisory = match entry.requirement {
+                Requirement::Required => "",
+                Requirement::Advisory => " (advisory)",
+            };
+            writeln!(f, "  [{}] {}{}: {}", status, entry.stage, advisory, detail)?;
+        }
+        Ok(())
+    }
+}
+
+type StageEntry = (Box<dyn VerificationStage>, Requirement);
+
+/// An ordered set of verification stages
+pub struct Pipeline {
+    stages: Vec<StageEntry>,
+}
+
+impl Pipeline {
+    /// Start building a pipeline
+    pub fn builder() -> PipelineBuilder {
+        PipelineBuilder::new()
+    }
+
+    /// Stage names in run order
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|(stage, _)| stage.name()).collect()
+    }
+
+    /// Run every stage in order
+    pub fn run(&self, ctx: &mut VerificationContext) -> VerificationReport {
+        let entries: Vec<ReportEntry> = self
+            .stages
+            .iter()
+            .map(|(stage, requirement)| ReportEntry {
+                stage: stage.name().to_string(),
+                requirement: *requirement,
+                result: stage.check(ctx),
+            })
+            .collect();
+        for entry in &entries {
+            if let (Requirement::Required, StageResult::Fail(detail)) =
+                (entry.requirement, &entry.result)
+            {
+                emit_event!(PolicyCheckFailed {
+                    stage: entry.stage.clone(),
+                    detail: detail.clone(),
+                });
+            }
+        }
+        VerificationReport { entries }
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("stages", &self.stage_names())
+            .finish()
+    }
+}
+
+/// Builder for a [`Pipeline`]
+#[derive(Default)]
+pub struct PipelineBuilder {
+    stages: Vec<StageEntry>,
+    errors: Vec<String>,
+}
+
+impl PipelineBuilder {
+    /// Start with no stages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start with the built-in stages, all required
+    pub fn with_builtin_stages() -> Self {
+        Self::new()
+            .stage(HashStage, Requirement::Required)
+            .stage(SignatureStage, Requirement::Required)
+            .stage(RevocationStage::default(), Requirement::Required)
+            .stage(ThresholdStage, Requirement::Required)
+            .stage(EscalationStage::default(), Requirement::Required)
+            .stage(ExpiryStage::default(), Requirement::Required)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.stages
+            .iter()
+            .position(|(stage, _)| stage.name() == name)
+    }
+
+    /// Append a stage
+    pub fn stage<S>(mut self, stage: S, requirement: Requirement) -> Self
+    where
+        S: VerificationStage + 'static,
+    {
+        self.stages.push((Box::new(stage), requirement));
+        self
+    }
+
+    /// Insert a stage directly after an existing one
+    pub fn stage_after<S>(mut self, existing: &str, stage: S, requirement: Requirement) -> Self
+    where
+        S: VerificationStage + 'static,
+    {
+        match self.position(existing) {
+            Some(index) => self
+                .stages
+                .insert(index + 1, (Box::new(stage), requirement)),
+            None => self.errors.push(format!(
+                "cannot insert '{}' after unknown stage '{}'",
+                stage.name(),
+                existing
+            )),
+        }
+        self
+    }
+
+    /// Insert a stage directly before an existing one
+    pub fn stage_before<S>(mut self, existing: &str, stage: S, requirement: Requirement) -> Self
+    where
+        S: VerificationStage + 'static,
+    {
+        match self.position(existing) {
+            Some(index) => self.stages.insert(index, (Box::new(stage), requirement)),
+            None => self.errors.push(format!(
+                "cannot insert '{}' before unknown stage '{}'",
+                stage.name(),
+                existing
+            )),
+        }
+        self
+    }
+
+    /// Replace an existing stage, keeping its position and requirement
+    pub fn replace<S>(mut self, stage: S) -> Self
+    where
+        S: VerificationStage + 'static,
+    {
+        match self.position(stage.name()) {
+            Some(index) => self.stages[index].0 = Box::new(stage),
+            None => self
+                .errors
+                .push(format!("cannot replace unknown stage '{}'", stage.name())),
+        }
+        self
+    }
+
+    /// Change the requirement level of a stage
+    pub fn requirement(mut self, name: &str, requirement: Requirement) -> Self {
+        match self.position(name) {
+            Some(index) => self.stages[index].1 = requirement,
+            None => self.errors.push(format!("unknown stage '{}'", name)),
+        }
+        self
+    }
+
+    /// Remove a stage
+    pub fn remove(mut self, name: &str) -> Self {
+        self.stages.retain(|(stage, _)| stage.name() != name);
+        self
+    }
+
+    /// Check stage names and ordering constraints, then build the pipeline
+    pub fn build(self) -> GovernanceResult<Pipeline> {
+        let mut errors = self.errors;
+        let names: Vec<&str> = self.stages.iter().map(|(stage, _)| stage.name()).collect();
+
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                errors.push(format!("stage '{}' appears more than once", name));
+            }
+        }
+
+        for (index, (stage, _)) in self.stages.iter().enumerate() {
+            for before in stage.after() {
+                match names.iter().position(|name| name == before) {
+                    Some(position) if position > index => errors.push(format!(
+                        "stage '{}' must run after '{}'",
+                        stage.name(),
+                        before
+                    )),
+                    Some(_) => {}
+                    None => errors.push(format!(
+                        "stage '{}' must run after '{}', which is not in the pipeline",
+                        stage.name(),
+                        before
+                    )),
+                }
+            }
+        }
+
+        for mandatory in MANDATORY_STAGES {
+            match self
+                .stages
+                .iter()
+                .find(|(stage, _)| stage.name() == mandatory)
+            {
+                Some((_, Requirement::Required)) => {}
+                Some((_, Requirement::Advisory)) => {
+                    errors.push(format!("stage '{}' must be required", mandatory))
+                }
+                None => errors.push(format!("stage '{}' is missing", mandatory)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Invalid verification pipeline: {}",
+                errors.join("; ")
+            )));
+        }
+        Ok(Pipeline {
+            stages: self.stages,
+        })
+    }
+}
+
+/// A stage selected by a policy
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StagePolicy {
+    pub name: String,
+    #[serde(default)]
+    pub requirement: Requirement,
+}
+
+/// Verification policy: which built-in stages run and how they are configured
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct PolicyConfig {
+    /// Stages in run order (default: every built-in stage, required)
+    #[serde(default)]
+    pub stages: Vec<StagePolicy>,
+    /// Maximum signature age for the expiry stage (`52w`, `90d`, ...)
+    #[serde(default)]
+    pub max_signature_age: Option<String>,
+    /// Hex-encoded revoked public keys for the revocation stage
+    #[serde(default)]
+    pub revoked_keys: Vec<String>,
+    /// Threshold escalation rules for the escalation stage
+    #[serde(default)]
+    pub escalation: Vec<EscalationRule>,
+}
+
+impl PolicyConfig {
+    /// Parse a TOML policy
+    pub fn from_toml(toml: &str) -> GovernanceResult<Self> {
+        toml::from_str(toml).map_err(|e| {
+            GovernanceError::Serialization(format!("Invalid verification policy: {}", e))
+        })
+    }
+
+    /// Builder holding the stages this policy selects
+    ///
+    /// Downstream crates add their own stages to the returned builder.
+    pub fn builder(&self) -> GovernanceResult<PipelineBuilder> {
+        let max_age = self
+            .max_signature_age
+            .as_deref()
+            .map(parse_duration)
+            .transpose()
+            .map_err(|e| GovernanceError::InvalidInput(format!("max_signature_age: {}", e)))?;
+        let revoked = self
+            .revoked_keys
+            .iter()
+            .map(|key| {
+                let bytes = hex::decode(key).map_err(|e| {
+                    GovernanceError::InvalidKey(format!("Invalid revoked key hex: {}", e))
+                })?;
+                PublicKey::from_bytes(&bytes)
+            })
+            .collect::<GovernanceResult<Vec<_>>>()?;
+        let escalation = EscalationStage::new(self.escalation.clone())?;
+
+        let selected: Vec<StagePolicy> = if self.stages.is_empty() {
+            BUILTIN_STAGES
+                .iter()
+                .map(|name| StagePolicy {
+                    name: name.to_string(),
+                    requirement: Requirement::Required,
+                })
+                .collect()
+        } else {
+            self.stages.clone()
+        };
+        if !self.escalation.is_empty() && !selected.iter().any(|stage| stage.name == "escalation") {
+            return Err(GovernanceError::InvalidInput(
+                "Escalation rules need the 'escalation' stage".to_string(),
+            ));
+        }
+
+        let mut builder = PipelineBuilder::new();
+        for stage in selected {
+            builder = match stage.name.as_str() {
+                "hash" => builder.stage(HashStage, stage.requirement),
+                "signature" => builder.stage(SignatureStage, stage.requirement),
+                "revocation" => builder.stage(
+                    RevocationStage {
+                        revoked: revoked.clone(),
+                    },
+                    stage.requirement,
+                ),
+                "threshold" => builder.stage(ThresholdStage, stage.requirement),
+                "escalation" => builder.stage(escalation.clone(), stage.requirement),
+                "expiry" => builder.stage(ExpiryStage { max_age }, stage.requirement),
+                other => {
+                    return Err(GovernanceError::InvalidInput(format!(
+                        "Unknown verification stage '{}' (built-in stages: {})",
+                        other,
+                        BUILTIN_STAGES.join(", ")
+                    )))
+                }
+            };
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    fn context(signers: &[&GovernanceKeypair], keys: &[&GovernanceKeypair]) -> VerificationContext {
+        let mut ctx = VerificationContext::new(b"binary:application:abc".to_vec(), "node", "abc");
+        ctx.recorded_hashes = vec!["abc".to_string()];
+        ctx.signatures = signers
+            .iter()
+            .map(|kp| crate::sign_message(kp.secret_key.expose(), &ctx.message).unwrap())
+            .collect();
+        ctx.public_keys = keys.iter().map(|kp| kp.public_key()).collect();
+        ctx
+    }
+
+    #[test]
+    fn test_builtin_pipeline() {
+        let keypairs: Vec<_> = (0..3)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let keys: Vec<_> = keypairs.iter().collect();
+        let pipeline = PipelineBuilder::with_builtin_stages().build().unwrap();
+        assert_eq!(pipeline.stage_names(), BUILTIN_STAGES.to_vec());
+
+        let mut ctx = context(&keys[..2], &keys);
+        ctx.threshold = Some(Threshold::new(2, 3).unwrap());
+        let report = pipeline.run(&mut ctx);
+        assert!(report.passed(), "{}", report);
+        assert_eq!(ctx.valid_signers, vec![0, 1]);
+
+        let mut ctx = context(&keys[..1], &keys);
+        ctx.threshold = Some(Threshold::new(2, 3).unwrap());
+        let report = pipeline.run(&mut ctx);
+        assert!(!report.passed());
+        assert!(report.entry("threshold").unwrap().result.is_fail());
+        assert_eq!(report.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_hash_mismatch() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let mut ctx = context(&[&keypair], &[&keypair]);
+        ctx.recorded_hashes = vec!["def".to_string()];
+
+        let report = PipelineBuilder::with_builtin_stages()
+            .build()
+            .unwrap()
+            .run(&mut ctx);
+        assert!(report.entry("hash").unwrap().result.is_fail());
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn test_ordering_enforced() {
+        let err = PipelineBuilder::new()
+            .stage(SignatureStage, Requirement::Required)
+            .stage(HashStage, Requirement::Required)
+            .build()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("stage 'signature' must run after 'hash'"));
+
+        let err = PipelineBuilder::with_builtin_stages()
+            .stage(HashStage, Requirement::Required)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("more than once"));
+
+        let err = PipelineBuilder::new()
+            .stage_after("hash", ThresholdStage, Requirement::Required)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown stage 'hash'"));
+    }
+
+    /// Signed timestamp by `keypair` for the context's message
+    fn timestamp(
+        ctx: &VerificationContext,
+        keypair: &GovernanceKeypair,
+        age: chrono::Duration,
+    ) -> SignedTimestamp {
+        SignedTimestamp::sign(keypair.secret_key.expose(), &ctx.message, ctx.now - age).unwrap()
+    }
+
+    #[test]
+    fn test_revocation_and_expiry() {
+        let keypairs: Vec<_> = (0..2)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let policy = PolicyConfig::from_toml(&format!(
+            r#"
+            max_signature_age = "1d"
+            revoked_keys = ["{}"]
+
+            [[stages]]
+            name = "hash"
+
+            [[stages]]
+            name = "signature"
+
+            [[stages]]
+            name = "revocation"
+
+            [[stages]]
+            name = "threshold"
+
+            [[stages]]
+            name = "expiry"
+            requirement = "advisory"
+            "#,
+            keypairs[1].public_key()
+        ))
+        .unwrap();
+        let pipeline = policy.builder().unwrap().build().unwrap();
+
+        let mut ctx = context(&[&keypairs[0]], &[&keypairs[0], &keypairs[1]]);
+        ctx.timestamps = vec![timestamp(
+            &ctx,
+            &keypairs[0],
+            chrono::Duration::try_days(2).unwrap(),
+        )];
+        let report = pipeline.run(&mut ctx);
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.warnings().count(), 1);
+
+        let mut ctx = context(&[&keypairs[1]], &[&keypairs[0], &keypairs[1]]);
+        ctx.timestamps = vec![timestamp(&ctx, &keypairs[1], chrono::Duration::zero())];
+        let report = pipeline.run(&mut ctx);
+        assert!(report.entry("revocation").unwrap().result.is_fail());
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn test_expiry_trusts_only_signed_timestamps() {
+        let keypairs: Vec<_> = (0..2)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let expiry = ExpiryStage {
+            max_age: Some(Duration::from_secs(86400)),
+        };
+        let mut ctx = context(&[&keypairs[0]], &[&keypairs[0]]);
+        SignatureStage.check(&mut ctx);
+
+        // A recorded time alone is not enough, however fresh
+        ctx.signed_at = vec![ctx.now];
+        assert!(expiry.check(&mut ctx).is_fail());
+
+        // Nor is a timestamp signed by someone else
+        ctx.timestamps = vec![timestamp(&ctx, &keypairs[1], chrono::Duration::zero())];
+        assert!(expiry.check(&mut ctx).is_fail());
+
+        let week = chrono::Duration::try_weeks(1).unwrap();
+        ctx.timestamps = vec![timestamp(&ctx, &keypairs[0], week)];
+        assert!(expiry.check(&mut ctx).is_fail());
+
+        // Rewriting the time of an old timestamp breaks its signature
+        let mut refreshed = ctx.timestamps[0].clone();
+        refreshed.signed_at = ctx.now;
+        ctx.timestamps = vec![refreshed];
+        assert!(expiry.check(&mut ctx).is_fail());
+
+        ctx.timestamps = vec![timestamp(&ctx, &keypairs[0], chrono::Duration::zero())];
+        assert!(!expiry.check(&mut ctx).is_fail());
+
+        ctx.timestamps = vec![timestamp(&ctx, &keypairs[0], -week)];
+        assert!(expiry.check(&mut ctx).is_fail());
+    }
+
+    #[test]
+    fn test_mandatory_stages() {
+        let err = PipelineBuilder::with_builtin_stages()
+            .requirement("threshold", Requirement::Advisory)
+            .build()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("stage 'threshold' must be required"));
+
+        let err = PipelineBuilder::with_builtin_stages()
+            .remove("revocation")
+            .remove("threshold")
+            .remove("expiry")
+            .remove("signature")
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("stage 'signature' is missing"));
+
+        let err = PipelineBuilder::with_builtin_stages()
+            .remove("hash")
+            .build()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("must run after 'hash', which is not in the pipeline"));
+    }
+
+    #[test]
+    fn test_escalation_policy() {
+        let rules = r#"
+            [[escalation]]
+            name = "consensus-paths"
+            threshold = "2-of-2"
+            when = { paths = "consensus/*" }
+        "#;
+        let keypairs: Vec<_> = (0..2)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let pipeline = PolicyConfig::from_toml(rules)
+            .unwrap()
+            .builder()
+            .unwrap()
+            .build()
+            .unwrap();
+
+        // One signature meets the configured 1-of-2, but not the rule
+        let mut ctx = context(&[&keypairs[0]], &[&keypairs[0], &keypairs[1]]);
+        ctx.threshold = Some(Threshold::new(1, 2).unwrap());
+        let report = pipeline.run(&mut ctx);
+        assert!(!report.entry("threshold").unwrap().result.is_fail());
+        assert!(report.entry("escalation").unwrap().result.is_fail());
+        assert!(!report.passed());
+
+        // Rules without the stage to apply them are a misconfiguration
+        let policy =
+            PolicyConfig::from_toml(&format!("{}\n[[stages]]\nname = \"signature\"\n", rules))
+                .unwrap();
+        assert!(policy.builder().is_err());
+    }
+
+    #[test]
+    fn test_unknown_policy_stage() {
+        let policy = PolicyConfig::from_toml("[[stages]]\nname = \"notary\"\n").unwrap();
+        assert!(policy.builder().is_err());
+    }
+}
@@ -301,7 +301,7 @@ impl PartiallySignedTransaction {
 
     /// Deserialize PSBT from bytes
     pub fn deserialize(data: &[u8]) -> GovernanceResult<Self> {
-        if data.len() < 5 || &data[..4] != &PSBT_MAGIC || data[4] != PSBT_SEPARATOR {
+        if data.len() < 5 || data[..4] != PSBT_MAGIC || data[4] != PSBT_SEPARATOR {
             return Err(GovernanceError::InvalidInput(
                 "Invalid PSBT magic bytes".to_string(),
             ));
@@ -386,8 +386,11 @@ fn serialize_map(result: &mut Vec<u8>, map: &HashMap<Vec<u8>, Vec<u8>>) -> Gover
     Ok(())
 }
 
-/// Deserialize a key-value map
-fn deserialize_map(data: &[u8]) -> GovernanceResult<(HashMap<Vec<u8>, Vec<u8>>, usize)> {
+/// Key-value map of a PSBT section
+type KeyValueMap = HashMap<Vec<u8>, Vec<u8>>;
+
+/// Deserialize a key-value map, returning it with the bytes consumed
+fn deserialize_map(data: &[u8]) -> GovernanceResult<(KeyValueMap, usize)> {
     let mut map = HashMap::new();
     let mut offset = 0;
 
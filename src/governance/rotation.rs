@@ -0,0 +1,348 @@
+//! # Key Rotation
+//!
+//! Statements by which a maintainer hands their seat over to a new key.
+//!
+//! The old key signs `key-rotation:<committee fingerprint>:<sequence>:<old
+//! key>:<new key>:<effective_at>`, so only the holder of the key being
+//! replaced can name its successor; a statement signed by the new key alone
+//! proves nothing. Keys are hex of the compressed form and the time is
+//! RFC3339 with whole seconds in UTC.
+//!
+//! A statement is bound to the committee it rotates: the fingerprint and
+//! the number of rotations already applied to it. Rotations chain: after A
+//! hands over to B, B can hand over to C. Apply them in order with
+//! [`Multisig::apply_rotation`](crate::governance::Multisig::apply_rotation),
+//! which refuses a statement before its `effective_at` and one made for
+//! another committee state, so a statement cannot be replayed.
+
+#[cfg(any(feature = "signing", test))]
+use chrono::SubsecRound;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::messages::join_fields;
+#[cfg(any(feature = "signing", test))]
+use crate::governance::{GovernanceKeypair, Multisig};
+use crate::governance::{PublicKey, Signature};
+
+/// A key's signed hand-over to its successor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRotationStatement {
+    /// Hex [`Multisig::fingerprint`](crate::governance::Multisig::fingerprint)
+    /// of the committee the key is rotated in
+    pub committee_fingerprint: String,
+    /// [`Multisig::rotations`](crate::governance::Multisig::rotations) of
+    /// that committee before this rotation
+    pub sequence: u64,
+    /// The key being replaced, which signs the statement
+    pub old_public_key: PublicKey,
+    /// The key taking its place
+    pub new_public_key: PublicKey,
+    /// When the new key takes over
+    pub effective_at: DateTime<Utc>,
+    /// Signature by the old key over [`signing_bytes`](Self::signing_bytes)
+    pub signature: Signature,
+}
+
+impl KeyRotationStatement {
+    /// Bytes covered by the old key's signature
+    pub fn signing_bytes(
+        committee_fingerprint: &str,
+        sequence: u64,
+        old_public_key: &PublicKey,
+        new_public_key: &PublicKey,
+        effective_at: DateTime<Utc>,
+    ) -> Vec<u8> {
+        join_fields(&[
+            "key-rotation".to_string(),
+            committee_fingerprint.to_string(),
+            sequence.to_string(),
+            old_public_key.to_string(),
+            new_public_key.to_string(),
+            effective_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ])
+    }
+}
+
+#[cfg(any(feature = "signing", test))]
+impl GovernanceKeypair {
+    /// Sign a statement handing this key's seat in `committee` over to
+    /// `new_pubkey` from `effective_at` (truncated to seconds)
+    ///
+    /// The statement applies only to `committee` as it is now.
+    pub fn create_rotation(
+        &self,
+        committee: &Multisig,
+        new_pubkey: &PublicKey,
+        effective_at: DateTime<Utc>,
+    ) -> GovernanceResult<KeyRotationStatement> {
+        let old_public_key = self.public_key();
+        if *new_pubkey == old_public_key {
+            return Err(GovernanceError::InvalidKey(
+                "A key cannot be rotated to itself".to_string(),
+            ));
+        }
+        let effective_at = effective_at.trunc_subsecs(0);
+        let committee_fingerprint = hex::encode(committee.fingerprint());
+        let sequence = committee.rotations();
+        let signature = crate::sign_message(
+            self.secret_key.expose(),
+            &KeyRotationStatement::signing_bytes(
+                &committee_fingerprint,
+                sequence,
+                &old_public_key,
+                new_pubkey,
+                effective_at,
+            ),
+        )?;
+        Ok(KeyRotationStatement {
+            committee_fingerprint,
+            sequence,
+            old_public_key,
+            new_public_key: new_pubkey.clone(),
+            effective_at,
+            signature,
+        })
+    }
+}
+
+/// Check a rotation statement's signature by the old key, returning the new
+/// key
+pub fn verify_rotation(statement: &KeyRotationStatement) -> GovernanceResult<PublicKey> {
+    if statement.new_public_key == statement.old_public_key {
+        return Err(GovernanceError::InvalidKey(
+            "A key cannot be rotated to itself".to_string(),
+        ));
+    }
+    let bytes = KeyRotationStatement::signing_bytes(
+        &statement.committee_fingerprint,
+        statement.sequence,
+        &statement.old_public_key,
+        &statement.new_public_key,
+        statement.effective_at,
+    );
+    if !crate::governance::verify_signature(
+        &statement.signature,
+        &bytes,
+        &statement.old_public_key,
+    )? {
+        return Err(GovernanceError::SignatureVerification(format!(
+            "Key rotation is not signed by the key it replaces ({})",
+            statement.old_public_key.short()
+        )));
+    }
+    Ok(statement.new_public_key.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effective_at() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00.5Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn committee(keys: &[&GovernanceKeypair]) -> Multisig {
+        Multisig::new(
+            keys.len(),
+            keys.len(),
+            keys.iter().map(|k| k.public_key()).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_create_and_verify_rotation() {
+        let old = GovernanceKeypair::generate().unwrap();
+        let new = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let multisig = committee(&[&old, &other]);
+        let statement = old
+            .create_rotation(&multisig, &new.public_key(), effective_at())
+            .unwrap();
+        assert_eq!(verify_rotation(&statement).unwrap(), new.public_key());
+        assert_eq!(
+            statement.committee_fingerprint,
+            hex::encode(multisig.fingerprint())
+        );
+        assert_eq!(statement.sequence, 0);
+        assert_eq!(
+            statement
+                .effective_at
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            "2026-01-01T00:00:00Z"
+        );
+
+        let json = serde_json::to_string(&statement).unwrap();
+        let parsed: KeyRotationStatement = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, statement);
+        assert_eq!(verify_rotation(&parsed).unwrap(), new.public_key());
+
+        // Any change to the statement breaks the signature
+        let mut later = statement.clone();
+        later.effective_at += chrono::Duration::try_seconds(1).unwrap();
+        assert!(matches!(
+            verify_rotation(&later),
+            Err(GovernanceError::SignatureVerification(_))
+        ));
+        let mut redirected = statement.clone();
+        redirected.new_public_key = GovernanceKeypair::generate().unwrap().public_key();
+        assert!(verify_rotation(&redirected).is_err());
+        let mut renumbered = statement.clone();
+        renumbered.sequence = 1;
+        assert!(verify_rotation(&renumbered).is_err());
+
+        assert!(old
+            .create_rotation(&multisig, &old.public_key(), effective_at())
+            .is_err());
+    }
+
+    #[test]
+    fn test_rotation_signed_by_new_key_rejected() {
+        let old = GovernanceKeypair::generate().unwrap();
+        let new = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let multisig = committee(&[&old, &other]);
+
+        // The new key claims the old key's seat on its own
+        let committee_fingerprint = hex::encode(multisig.fingerprint());
+        let bytes = KeyRotationStatement::signing_bytes(
+            &committee_fingerprint,
+            0,
+            &old.public_key(),
+            &new.public_key(),
+            effective_at(),
+        );
+        let forged = KeyRotationStatement {
+            committee_fingerprint,
+            sequence: 0,
+            old_public_key: old.public_key(),
+            new_public_key: new.public_key(),
+            effective_at: effective_at(),
+            signature: crate::sign_message(new.secret_key.expose(), &bytes).unwrap(),
+        };
+        assert!(matches!(
+            verify_rotation(&forged),
+            Err(GovernanceError::SignatureVerification(_))
+        ));
+        assert!(multisig.apply_rotation(&forged, effective_at()).is_err());
+    }
+
+    #[test]
+    fn test_rotation_chain() {
+        let a = GovernanceKeypair::generate().unwrap();
+        let b = GovernanceKeypair::generate().unwrap();
+        let c = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let multisig = committee(&[&other, &a]);
+
+        let a_to_b = a
+            .create_rotation(&multisig, &b.public_key(), effective_at())
+            .unwrap();
+        let after_a = multisig.apply_rotation(&a_to_b, effective_at()).unwrap();
+        assert_eq!(after_a.rotations(), 1);
+        let b_to_c = b
+            .create_rotation(&after_a, &c.public_key(), effective_at())
+            .unwrap();
+
+        // B -> C does not apply before A -> B
+        assert!(matches!(
+            multisig.apply_rotation(&b_to_c, effective_at()),
+            Err(GovernanceError::InvalidMultisig(_))
+        ));
+
+        let rotated = after_a.apply_rotation(&b_to_c, effective_at()).unwrap();
+        assert_eq!(rotated.public_keys(), &[other.public_key(), c.public_key()]);
+        assert_eq!(rotated.m_of_n(), multisig.m_of_n());
+        assert_eq!(rotated.rotations(), 2);
+        // The original is unchanged
+        assert_eq!(
+            multisig.public_keys(),
+            &[other.public_key(), a.public_key()]
+        );
+
+        // A cannot hand over again once it left, and C now signs in its seat
+        assert!(rotated.apply_rotation(&a_to_b, effective_at()).is_err());
+        let message = b"release v1.0.0";
+        let signatures = [
+            crate::sign_message(other.secret_key.expose(), message).unwrap(),
+            crate::sign_message(c.secret_key.expose(), message).unwrap(),
+        ];
+        assert!(rotated.verify(message, &signatures).unwrap());
+
+        // A rotation onto a key already in the set is refused
+        let onto_member = c
+            .create_rotation(&rotated, &other.public_key(), effective_at())
+            .unwrap();
+        assert!(rotated
+            .apply_rotation(&onto_member, effective_at())
+            .is_err());
+
+        // The rotation count survives serialization
+        let json = serde_json::to_string(&rotated).unwrap();
+        let parsed: Multisig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.rotations(), 2);
+        assert!(!serde_json::to_string(&multisig)
+            .unwrap()
+            .contains("rotations"));
+    }
+
+    #[test]
+    fn test_rotation_replay_rejected() {
+        let a = GovernanceKeypair::generate().unwrap();
+        let b = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let multisig = committee(&[&other, &a]);
+
+        let a_to_b = a
+            .create_rotation(&multisig, &b.public_key(), effective_at())
+            .unwrap();
+        let after_a = multisig.apply_rotation(&a_to_b, effective_at()).unwrap();
+        let b_to_a = b
+            .create_rotation(&after_a, &a.public_key(), effective_at())
+            .unwrap();
+        let back = after_a.apply_rotation(&b_to_a, effective_at()).unwrap();
+
+        // The same keys as at the start, but A -> B was spent
+        assert_eq!(back.fingerprint(), multisig.fingerprint());
+        assert!(matches!(
+            back.apply_rotation(&a_to_b, effective_at()),
+            Err(GovernanceError::InvalidMultisig(_))
+        ));
+
+        // A statement for another committee does not apply either
+        let elsewhere = committee(&[&a, &b]);
+        assert!(matches!(
+            elsewhere.apply_rotation(&b_to_a, effective_at()),
+            Err(GovernanceError::InvalidMultisig(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotation_not_applied_before_effective_at() {
+        let old = GovernanceKeypair::generate().unwrap();
+        let new = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let multisig = committee(&[&old, &other]);
+
+        let effective = effective_at().trunc_subsecs(0);
+        let statement = old
+            .create_rotation(&multisig, &new.public_key(), effective)
+            .unwrap();
+        let before = effective - chrono::Duration::try_seconds(1).unwrap();
+        assert!(matches!(
+            multisig.apply_rotation(&statement, before),
+            Err(GovernanceError::InvalidInput(_))
+        ));
+        assert_eq!(
+            multisig
+                .apply_rotation(&statement, effective)
+                .unwrap()
+                .public_keys(),
+            &[new.public_key(), other.public_key()]
+        );
+    }
+}
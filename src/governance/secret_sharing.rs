@@ -0,0 +1,372 @@
+//! # Secret Sharing
+//!
+//! Shamir's secret sharing of a 32-byte secret key over GF(256), so that
+//! any `threshold` of `shares` key holders can rebuild it and fewer learn
+//! nothing about it.
+//!
+//! Each byte of the secret is the constant term of its own random
+//! polynomial of degree `threshold - 1`; share `x` (1 to 255) holds the
+//! polynomials evaluated at `x`. A share is written as hex of the
+//! threshold, the share index and the 32 share bytes:
+//!
+//! ```text
+//! 0201<64 hex digits>
+//! ```
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::paper::GF;
+use crate::util::redact::REDACTED;
+
+/// Most shares one secret can be split into
+pub const MAX_SHARES: usize = 255;
+
+/// Length of an encoded share in bytes: threshold, index and value
+const SHARE_LEN: usize = 34;
+
+/// One key holder's share of a secret
+///
+/// Serialized as hex. `Debug` hides the share value.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SecretShare {
+    threshold: u8,
+    index: u8,
+    value: Zeroizing<[u8; 32]>,
+}
+
+impl SecretShare {
+    /// Shares needed to rebuild the secret
+    pub fn threshold(&self) -> usize {
+        usize::from(self.threshold)
+    }
+
+    /// Share number, from 1
+    pub fn index(&self) -> usize {
+        usize::from(self.index)
+    }
+
+    /// The share as hex
+    pub fn to_hex(&self) -> String {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(SHARE_LEN));
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.extend_from_slice(&*self.value);
+        hex::encode(&*bytes)
+    }
+
+    /// Parse a share from hex
+    pub fn from_hex(hex_str: &str) -> GovernanceResult<Self> {
+        let bytes = Zeroizing::new(hex::decode(hex_str.trim()).map_err(|e| {
+            GovernanceError::InvalidInput(format!("Invalid secret share hex: {}", e))
+        })?);
+        if bytes.len() != SHARE_LEN {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Secret share must be {} bytes, got {}",
+                SHARE_LEN,
+                bytes.len()
+            )));
+        }
+        let (threshold, index) = (bytes[0], bytes[1]);
+        if threshold == 0 || index == 0 {
+            return Err(GovernanceError::InvalidInput(
+                "Secret share threshold and index must be at least 1".to_string(),
+            ));
+        }
+        let mut value = Zeroizing::new([0u8; 32]);
+        value.copy_from_slice(&bytes[2..]);
+        Ok(Self {
+            threshold,
+            index,
+            value,
+        })
+    }
+}
+
+impl fmt::Debug for SecretShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretShare")
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .field("value", &format_args!("{}", REDACTED))
+            .finish()
+    }
+}
+
+impl FromStr for SecretShare {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+impl TryFrom<String> for SecretShare {
+    type Error = GovernanceError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_hex(&s)
+    }
+}
+
+impl From<SecretShare> for String {
+    fn from(share: SecretShare) -> Self {
+        share.to_hex()
+    }
+}
+
+/// Split a secret into `shares` shares, any `threshold` of which rebuild it
+///
+/// Requires `1 <= threshold <= shares <= 255`.
+pub fn split_secret(
+    secret: &[u8; 32],
+    threshold: usize,
+    shares: usize,
+) -> GovernanceResult<Vec<SecretShare>> {
+    split_secret_with_rng(secret, threshold, shares, &mut OsRng)
+}
+
+/// [`split_secret`] with the polynomial coefficients drawn from `rng`
+pub fn split_secret_with_rng<R: RngCore + CryptoRng + ?Sized>(
+    secret: &[u8; 32],
+    threshold: usize,
+    shares: usize,
+    rng: &mut R,
+) -> GovernanceResult<Vec<SecretShare>> {
+    if threshold == 0 || threshold > shares || shares > MAX_SHARES {
+        return Err(GovernanceError::InvalidThreshold {
+            threshold,
+            total: shares,
+        });
+    }
+
+    // coefficients[power - 1][byte] for powers 1 to threshold - 1
+    let mut coefficients = Zeroizing::new(vec![[0u8; 32]; threshold - 1]);
+    for row in coefficients.iter_mut() {
+        rng.try_fill_bytes(&mut row[..])
+            .map_err(|_| GovernanceError::Cryptographic("entropy unavailable".to_string()))?;
+    }
+
+    Ok((1..=shares as u8)
+        .map(|x| {
+            let mut value = Zeroizing::new([0u8; 32]);
+            for (byte, y) in value.iter_mut().enumerate() {
+                // Horner's rule, highest power first
+                *y = coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, row| GF.mul(acc, x) ^ row[byte]);
+                *y = GF.mul(*y, x) ^ secret[byte];
+            }
+            SecretShare {
+                threshold: threshold as u8,
+                index: x,
+                value,
+            }
+        })
+        .collect())
+}
+
+/// Rebuild a secret from its shares
+///
+/// Shares must come from one split. Fails with
+/// [`GovernanceError::InsufficientSignatures`] when fewer than the
+/// threshold are given; extra shares are ignored.
+pub fn combine_shares(shares: &[SecretShare]) -> GovernanceResult<[u8; 32]> {
+    let first = shares
+        .first()
+        .ok_or(GovernanceError::InsufficientSignatures { got: 0, need: 1 })?;
+    let threshold = first.threshold();
+    if shares.iter().any(|share| share.threshold() != threshold) {
+        return Err(GovernanceError::InvalidInput(
+            "Secret shares have different thresholds".to_string(),
+        ));
+    }
+    let mut seen = HashSet::new();
+    if let Some(share) = shares.iter().find(|share| !seen.insert(share.index)) {
+        return Err(GovernanceError::InvalidInput(format!(
+            "Secret share {} given more than once",
+            share.index
+        )));
+    }
+    if shares.len() < threshold {
+        return Err(GovernanceError::InsufficientSignatures {
+            got: shares.len(),
+            need: threshold,
+        });
+    }
+
+    // Lagrange interpolation at x = 0; subtraction is XOR in GF(256)
+    let used = &shares[..threshold];
+    let mut secret = [0u8; 32];
+    for share in used {
+        let basis = used
+            .iter()
+            .filter(|other| other.index != share.index)
+            .fold(1, |acc, other| {
+                GF.mul(acc, GF.mul(other.index, GF.inv(other.index ^ share.index)))
+            });
+        for (s, &y) in secret.iter_mut().zip(share.value.iter()) {
+            *s ^= GF.mul(basis, y);
+        }
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    const SECRET: [u8; 32] = [0x5a; 32];
+
+    #[test]
+    fn test_any_threshold_subset_reconstructs() {
+        let shares = split_secret(&SECRET, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+                    assert_eq!(combine_shares(&subset).unwrap(), SECRET);
+                }
+            }
+        }
+        assert_eq!(combine_shares(&shares).unwrap(), SECRET);
+
+        // 1-of-n: every share is the secret
+        for share in split_secret(&SECRET, 1, 3).unwrap() {
+            assert_eq!(*share.value, SECRET);
+        }
+    }
+
+    #[test]
+    fn test_keypair_round_trip() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let secret = keypair.secret_key_bytes();
+        let shares = split_secret(&secret, 2, 3).unwrap();
+        let restored = combine_shares(&shares[1..]).unwrap();
+        let restored = GovernanceKeypair::from_secret_key(&restored).unwrap();
+        assert_eq!(restored.public_key(), keypair.public_key());
+    }
+
+    #[test]
+    fn test_fewer_than_threshold_fails() {
+        let shares = split_secret(&SECRET, 3, 5).unwrap();
+        assert!(matches!(
+            combine_shares(&shares[..2]),
+            Err(GovernanceError::InsufficientSignatures { got: 2, need: 3 })
+        ));
+        assert!(matches!(
+            combine_shares(&[]),
+            Err(GovernanceError::InsufficientSignatures { got: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_fewer_than_threshold_reveal_nothing() {
+        // Over all choices of the random coefficient, one share of a 2-of-n
+        // split takes every byte value equally often, whatever the secret
+        for secret in [0x00u8, 0x5a, 0xff] {
+            for x in [1u8, 2, 255] {
+                let mut counts = [0usize; 256];
+                for coefficient in 0..=255u8 {
+                    counts[usize::from(GF.mul(coefficient, x) ^ secret)] += 1;
+                }
+                assert!(counts.iter().all(|&count| count == 1));
+            }
+        }
+
+        // And any two shares of a 3-of-n split fit every possible secret:
+        // with a third point (0, guess) they interpolate to that guess
+        let shares = split_secret(&SECRET, 3, 5).unwrap();
+        for guess in [[0u8; 32], [0xff; 32], SECRET] {
+            let fake = SecretShare {
+                threshold: 3,
+                index: 0,
+                value: Zeroizing::new(guess),
+            };
+            let interpolated = interpolate_at(&[&shares[0], &shares[3], &fake], 0);
+            assert_eq!(interpolated, guess);
+        }
+    }
+
+    /// Evaluate the polynomial through `points` at `x`
+    fn interpolate_at(points: &[&SecretShare], x: u8) -> [u8; 32] {
+        let mut result = [0u8; 32];
+        for point in points {
+            let basis = points
+                .iter()
+                .filter(|other| other.index != point.index)
+                .fold(1, |acc, other| {
+                    GF.mul(
+                        acc,
+                        GF.mul(other.index ^ x, GF.inv(other.index ^ point.index)),
+                    )
+                });
+            for (r, &y) in result.iter_mut().zip(point.value.iter()) {
+                *r ^= GF.mul(basis, y);
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_hex_and_serde_round_trip() {
+        let shares = split_secret(&SECRET, 2, 3).unwrap();
+        let hex = shares[1].to_hex();
+        assert_eq!(hex.len(), 68);
+        assert!(hex.starts_with("0202"));
+        assert_eq!(SecretShare::from_hex(&hex).unwrap(), shares[1]);
+        assert_eq!(hex.parse::<SecretShare>().unwrap(), shares[1]);
+
+        let json = serde_json::to_string(&shares).unwrap();
+        let parsed: Vec<SecretShare> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, shares);
+
+        let debug = format!("{:?}", shares[1]);
+        assert!(debug.contains(REDACTED));
+        assert!(!debug.contains(&hex[4..]));
+
+        for bad in [
+            "zz",
+            "0201",
+            &format!("00{}", &hex[2..]),
+            &format!("0200{}", &hex[4..]),
+        ] {
+            assert!(matches!(
+                SecretShare::from_hex(bad),
+                Err(GovernanceError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_invalid_splits_and_combinations() {
+        for (threshold, shares) in [(0, 3), (4, 3), (2, 256)] {
+            assert!(matches!(
+                split_secret(&SECRET, threshold, shares),
+                Err(GovernanceError::InvalidThreshold { .. })
+            ));
+        }
+        assert_eq!(split_secret(&SECRET, 255, 255).unwrap().len(), 255);
+
+        let shares = split_secret(&SECRET, 2, 3).unwrap();
+        let other = split_secret(&SECRET, 3, 3).unwrap();
+        assert!(matches!(
+            combine_shares(&[shares[0].clone(), other[1].clone()]),
+            Err(GovernanceError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            combine_shares(&[shares[0].clone(), shares[0].clone()]),
+            Err(GovernanceError::InvalidInput(_))
+        ));
+    }
+}
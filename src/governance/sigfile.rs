@@ -0,0 +1,610 @@
+//! # Signature File Names
+//!
+//! The naming convention for detached signature files, so signatures from
+//! different maintainers are found next to the artifact they sign:
+//! `<artifact>.<fpr8>.<ext>`, where `fpr8` is the first 8 hex digits of the
+//! signer's fingerprint and `ext` is `sig.json` (this SDK's JSON signature
+//! files), `minisig` (minisign) or `sshsig` (SSH signatures).
+//!
+//! Signer fingerprints are the SHA256 of the compressed public key for JSON
+//! signatures (see [`PublicKey::sha256_fingerprint`]), the key ID as
+//! minisign prints it, and the SHA256 of the public key blob (as
+//! `ssh-keygen -l` prints it) for SSH signatures.
+
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::{GovernanceMessage, PublicKey};
+
+/// Files larger than this are never signature files, and are not read
+const MAX_SIGNATURE_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Detached signature file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SigFormat {
+    /// JSON signature file written by the signing tools
+    Json,
+    /// minisign signature
+    Minisign,
+    /// SSH signature (`ssh-keygen -Y sign`)
+    SshSig,
+}
+
+impl SigFormat {
+    /// Every format
+    pub const ALL: [SigFormat; 3] = [SigFormat::Json, SigFormat::Minisign, SigFormat::SshSig];
+
+    /// File name extension, without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SigFormat::Json => "sig.json",
+            SigFormat::Minisign => "minisig",
+            SigFormat::SshSig => "sshsig",
+        }
+    }
+}
+
+impl fmt::Display for SigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SigFormat::Json => "json",
+            SigFormat::Minisign => "minisign",
+            SigFormat::SshSig => "sshsig",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// File name part for an artifact: its base name, with characters other
+/// than ASCII letters, digits, `.`, `-`, `_` and `+` replaced by `_`
+fn artifact_part(artifact: &str) -> String {
+    let base = artifact.rsplit(['/', '\\']).next().unwrap_or(artifact);
+    base.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Conventional name of a signature file: `<artifact>.<fpr8>.<ext>`
+///
+/// Only the base name of `artifact` is used, so a path names the file next
+/// to it.
+pub fn canonical_name(artifact: &str, signer_fingerprint: &[u8], format: SigFormat) -> String {
+    let fpr8 = hex::encode(&signer_fingerprint[..signer_fingerprint.len().min(4)]);
+    format!(
+        "{}.{}.{}",
+        artifact_part(artifact),
+        fpr8,
+        format.extension()
+    )
+}
+
+/// Artifact name for a signed governance message, for naming its signature
+/// files
+pub fn message_artifact(message: &GovernanceMessage) -> String {
+    match message {
+        GovernanceMessage::Release { version, .. } => format!("release-{}", version),
+        GovernanceMessage::ModuleApproval {
+            module_name,
+            version,
+        }
+        | GovernanceMessage::ModuleBinaryApproval {
+            module_name,
+            version,
+            ..
+        } => format!("module-{}-{}", module_name, version),
+        GovernanceMessage::BudgetDecision { amount, .. } => format!("budget-{}", amount),
+        GovernanceMessage::ConfigUpdate {
+            module_name,
+            config_hash,
+        } => format!(
+            "config-{}-{}",
+            module_name,
+            &config_hash[..config_hash.len().min(8)]
+        ),
+        GovernanceMessage::KeyRotation { new_public_key, .. } => format!(
+            "key-rotation-{}",
+            new_public_key.chars().take(8).collect::<String>()
+        ),
+        GovernanceMessage::Emergency { severity, .. } => {
+            format!("emergency-{}-{}", severity, hex::encode(&message.id()[..4]))
+        }
+        GovernanceMessage::ConfigChange { parameter, .. } => {
+            format!(
+                "config-change-{}-{}",
+                parameter,
+                hex::encode(&message.id()[..4])
+            )
+        }
+        GovernanceMessage::MemberUpdate { action, github, .. } => {
+            format!("member-{}-{}", action, github)
+        }
+    }
+}
+
+/// Split a conventional name into artifact, fingerprint prefix and format
+fn parse_canonical(name: &str) -> Option<(&str, &str, SigFormat)> {
+    SigFormat::ALL.into_iter().find_map(|format| {
+        let stem = name.strip_suffix(format.extension())?.strip_suffix('.')?;
+        let (artifact, fpr8) = stem.rsplit_once('.')?;
+        let is_fpr8 = fpr8.len() == 8
+            && fpr8
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c));
+        (is_fpr8 && !artifact.is_empty()).then_some((artifact, fpr8, format))
+    })
+}
+
+/// What a signature file's content says about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigFileInfo {
+    pub format: SigFormat,
+    /// Signer fingerprint (see the module documentation)
+    pub fingerprint: Vec<u8>,
+    /// Signed artifact, if the file records it
+    pub artifact: Option<String>,
+}
+
+/// Identify a detached signature by its content
+///
+/// Returns `None` for anything that is not a single-signer signature file,
+/// such as key files and aggregates.
+pub fn identify(content: &[u8]) -> Option<SigFileInfo> {
+    let text = std::str::from_utf8(content).ok()?;
+    let trimmed = text.trim_start();
+    if trimmed.starts_with('{') {
+        identify_json(trimmed)
+    } else if trimmed.starts_with("untrusted comment:") {
+        identify_minisign(trimmed)
+    } else if trimmed.starts_with("-----BEGIN SSH SIGNATURE-----") {
+        identify_sshsig(trimmed)
+    } else {
+        None
+    }
+}
+
+fn identify_json(text: &str) -> Option<SigFileInfo> {
+    let json: serde_json::Value = serde_json::from_str(text).ok()?;
+    json.get("signature")?.as_str()?;
+    let public_key = hex::decode(json.get("public_key")?.as_str()?).ok()?;
+    let public_key = PublicKey::from_bytes(&public_key).ok()?;
+    Some(SigFileInfo {
+        format: SigFormat::Json,
+        fingerprint: public_key.sha256_fingerprint().to_vec(),
+        artifact: json
+            .get("file_path")
+            .and_then(|path| path.as_str())
+            .map(artifact_part),
+    })
+}
+
+/// minisign: a comment line, then base64 of algorithm (2 bytes), key ID
+/// (8 bytes, little endian) and signature; the trusted comment usually
+/// names the file
+fn identify_minisign(text: &str) -> Option<SigFileInfo> {
+    let mut lines = text.lines();
+    lines.next()?;
+    let blob = general_purpose::STANDARD
+        .decode(lines.next()?.trim())
+        .ok()?;
+    if blob.len() != 74 {
+        return None;
+    }
+    let mut fingerprint = blob[2..10].to_vec();
+    fingerprint.reverse();
+    let artifact = lines
+        .next()
+        .and_then(|line| line.strip_prefix("trusted comment:"))
+        .and_then(|comment| {
+            comment
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("file:"))
+        })
+        .map(artifact_part);
+    Some(SigFileInfo {
+        format: SigFormat::Minisign,
+        fingerprint,
+        artifact,
+    })
+}
+
+/// SSH signature: armored `SSHSIG` magic, version, then the public key blob
+fn identify_sshsig(text: &str) -> Option<SigFileInfo> {
+    let armored: String = text
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.starts_with("-----END SSH SIGNATURE-----"))
+        .collect();
+    let blob = general_purpose::STANDARD.decode(armored.trim()).ok()?;
+    let rest = blob.strip_prefix(b"SSHSIG")?.get(4..)?;
+    let length = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+    let public_key = rest.get(4..4usize.checked_add(length)?)?;
+    Some(SigFileInfo {
+        format: SigFormat::SshSig,
+        fingerprint: Sha256::digest(public_key).to_vec(),
+        artifact: None,
+    })
+}
+
+/// Artifact of a signature file named outside the convention: its name
+/// without the signature extension
+fn artifact_from_file_name(name: &str) -> String {
+    if let Some((artifact, _, _)) = parse_canonical(name) {
+        return artifact.to_string();
+    }
+    [".sig.json", ".minisig", ".sshsig", ".sig", ".asc", ".json"]
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension))
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(name)
+        .to_string()
+}
+
+fn read_dir(dir: &Path) -> GovernanceResult<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        GovernanceError::InvalidInput(format!("Failed to read {}: {}", dir.display(), e))
+    })?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| {
+                GovernanceError::InvalidInput(format!("Failed to read {}: {}", dir.display(), e))
+            })?
+            .path();
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Signature files in `dir` named by the convention for `artifact`
+pub fn discover(dir: &Path, artifact: &str) -> GovernanceResult<Vec<PathBuf>> {
+    let artifact = artifact_part(artifact);
+    Ok(read_dir(dir)?
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(parse_canonical)
+                .is_some_and(|(name, _, _)| name == artifact)
+        })
+        .collect())
+}
+
+/// One planned rename
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub format: SigFormat,
+}
+
+impl fmt::Display for Rename {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |path: &Path| {
+            path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into(),
+            )
+        };
+        write!(
+            f,
+            "{} -> {} ({})",
+            name(&self.from),
+            name(&self.to),
+            self.format
+        )
+    }
+}
+
+/// Renames bringing the signature files in `dir` to the convention
+///
+/// Files are identified by content; other files are left alone. Fails,
+/// listing both paths, if two files would get the same name or a rename
+/// would replace a file that stays.
+pub fn plan_renames(dir: &Path) -> GovernanceResult<Vec<Rename>> {
+    let mut targets: BTreeMap<PathBuf, PathBuf> = BTreeMap::new();
+    let mut renames = Vec::new();
+    let files = read_dir(dir)?;
+    for path in &files {
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.len() <= MAX_SIGNATURE_FILE_SIZE => {}
+            _ => continue,
+        }
+        let Ok(content) = std::fs::read(path) else {
+            continue;
+        };
+        let Some(info) = identify(&content) else {
+            continue;
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let artifact = info
+            .artifact
+            .unwrap_or_else(|| artifact_from_file_name(&name));
+        let target = dir.join(canonical_name(&artifact, &info.fingerprint, info.format));
+
+        if let Some(other) = targets.insert(target.clone(), path.clone()) {
+            return Err(GovernanceError::InvalidInput(format!(
+                "{} and {} would both be named {}",
+                other.display(),
+                path.display(),
+                target.display()
+            )));
+        }
+        if &target != path {
+            renames.push(Rename {
+                from: path.clone(),
+                to: target,
+                format: info.format,
+            });
+        }
+    }
+    // A target taken by a file that is not renamed away
+    for rename in &renames {
+        let renamed_away = renames.iter().any(|other| other.from == rename.to);
+        if files.contains(&rename.to) && !renamed_away {
+            return Err(GovernanceError::InvalidInput(format!(
+                "{} and {} would both be named {}",
+                rename.to.display(),
+                rename.from.display(),
+                rename.to.display()
+            )));
+        }
+    }
+    // Renames onto files that are renamed away themselves would need an
+    // order; refuse them rather than risk a clobbered file
+    if let Some(rename) = renames
+        .iter()
+        .find(|rename| renames.iter().any(|other| other.from == rename.to))
+    {
+        return Err(GovernanceError::InvalidInput(format!(
+            "{} would be renamed to {}, which is renamed itself; rename in two steps",
+            rename.from.display(),
+            rename.to.display()
+        )));
+    }
+    Ok(renames)
+}
+
+/// Carry out planned renames, refusing to replace any existing file
+pub fn apply_renames(renames: &[Rename]) -> GovernanceResult<()> {
+    for rename in renames {
+        if rename.to.exists() {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Not renaming {}: {} exists",
+                rename.from.display(),
+                rename.to.display()
+            )));
+        }
+        std::fs::rename(&rename.from, &rename.to).map_err(|e| {
+            GovernanceError::InvalidInput(format!(
+                "Failed to rename {} to {}: {}",
+                rename.from.display(),
+                rename.to.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    fn json_signature(keypair: &GovernanceKeypair, file_path: Option<&str>) -> String {
+        let mut json = serde_json::json!({
+            "signature": "00".repeat(64),
+            "public_key": keypair.public_key().to_string(),
+        });
+        if let Some(file_path) = file_path {
+            json["file_path"] = serde_json::json!(file_path);
+        }
+        json.to_string()
+    }
+
+    fn minisig(key_id: [u8; 8], file: &str) -> String {
+        let mut blob = b"Ed".to_vec();
+        blob.extend_from_slice(&key_id);
+        blob.extend_from_slice(&[7; 64]);
+        format!(
+            "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: timestamp:1700000000\tfile:{}\n{}\n",
+            general_purpose::STANDARD.encode(blob),
+            file,
+            general_purpose::STANDARD.encode([9; 64])
+        )
+    }
+
+    fn sshsig(public_key: &[u8]) -> String {
+        let mut blob = b"SSHSIG".to_vec();
+        blob.extend_from_slice(&1u32.to_be_bytes());
+        blob.extend_from_slice(&(public_key.len() as u32).to_be_bytes());
+        blob.extend_from_slice(public_key);
+        blob.extend_from_slice(&[0; 16]);
+        format!(
+            "-----BEGIN SSH SIGNATURE-----\n{}\n-----END SSH SIGNATURE-----\n",
+            general_purpose::STANDARD.encode(blob)
+        )
+    }
+
+    #[test]
+    fn test_canonical_names() {
+        let fingerprint = [0xab, 0xcd, 0x01, 0x23, 0x45];
+        assert_eq!(
+            canonical_name("dist/blvm-1.0.tar.gz", &fingerprint, SigFormat::Json),
+            "blvm-1.0.tar.gz.abcd0123.sig.json"
+        );
+        assert_eq!(
+            canonical_name("blvm-1.0.tar.gz", &fingerprint, SigFormat::Minisign),
+            "blvm-1.0.tar.gz.abcd0123.minisig"
+        );
+        assert_eq!(
+            canonical_name("blvm:v1 image", &fingerprint, SigFormat::SshSig),
+            "blvm_v1_image.abcd0123.sshsig"
+        );
+        for format in SigFormat::ALL {
+            let name = canonical_name("artifact.bin", &fingerprint, format);
+            assert_eq!(
+                parse_canonical(&name),
+                Some(("artifact.bin", "abcd0123", format))
+            );
+        }
+        assert_eq!(parse_canonical("artifact.sig.json"), None);
+        assert_eq!(parse_canonical("sig-alice.json"), None);
+        assert_eq!(
+            message_artifact(&GovernanceMessage::Release {
+                version: "v1.0.0".to_string(),
+                commit_hash: "abc123".to_string(),
+            }),
+            "release-v1.0.0"
+        );
+        assert_eq!(
+            message_artifact(&GovernanceMessage::KeyRotation {
+                old_public_key: "02aa".to_string(),
+                new_public_key: "03bbccddeeff".to_string(),
+                reason: "compromised".to_string(),
+            }),
+            "key-rotation-03bbccdd"
+        );
+        let emergency = message_artifact(&GovernanceMessage::Emergency {
+            severity: crate::governance::EmergencySeverity::High,
+            description: "bug".to_string(),
+            required_action: "upgrade".to_string(),
+        });
+        assert!(emergency.starts_with("emergency-high-"), "{}", emergency);
+        assert_eq!(
+            message_artifact(&GovernanceMessage::MemberUpdate {
+                action: crate::governance::MemberAction::Add,
+                github: "alice".to_string(),
+                public_key: "02aa".to_string(),
+                team_id: "core".to_string(),
+            }),
+            "member-add-alice"
+        );
+    }
+
+    #[test]
+    fn test_identify() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let info = identify(json_signature(&keypair, Some("dist/blvm.tar.gz")).as_bytes()).unwrap();
+        assert_eq!(info.format, SigFormat::Json);
+        assert_eq!(info.fingerprint, keypair.public_key().sha256_fingerprint());
+        assert_eq!(info.artifact.as_deref(), Some("blvm.tar.gz"));
+
+        let info = identify(minisig([1, 2, 3, 4, 5, 6, 7, 8], "blvm.tar.gz").as_bytes()).unwrap();
+        assert_eq!(info.format, SigFormat::Minisign);
+        assert_eq!(info.fingerprint, [8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(info.artifact.as_deref(), Some("blvm.tar.gz"));
+
+        let info = identify(sshsig(b"ssh-ed25519 key blob").as_bytes()).unwrap();
+        assert_eq!(info.format, SigFormat::SshSig);
+        assert_eq!(
+            info.fingerprint,
+            Sha256::digest(b"ssh-ed25519 key blob").to_vec()
+        );
+
+        // Key files and other content are not signatures
+        let key_file = serde_json::json!({
+            "public_key": keypair.public_key().to_string(),
+            "secret_key": "00",
+        });
+        assert_eq!(identify(key_file.to_string().as_bytes()), None);
+        assert_eq!(identify(b"binary\x00data"), None);
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().unwrap();
+        let (alice, bob) = ([1u8; 32], [2u8; 32]);
+        for name in [
+            canonical_name("blvm.tar.gz", &alice, SigFormat::Json),
+            canonical_name("blvm.tar.gz", &bob, SigFormat::Minisign),
+            canonical_name("other.tar.gz", &alice, SigFormat::Json),
+            "blvm.tar.gz.sig".to_string(),
+            "blvm.tar.gz".to_string(),
+        ] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        let found = discover(dir.path(), "dist/blvm.tar.gz").unwrap();
+        let names: Vec<_> = found
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "blvm.tar.gz.01010101.sig.json",
+                "blvm.tar.gz.02020202.minisig"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_messy_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let alice = GovernanceKeypair::generate().unwrap();
+        let bob = GovernanceKeypair::generate().unwrap();
+        let write =
+            |name: &str, content: &str| std::fs::write(dir.path().join(name), content).unwrap();
+        write(
+            "sig-alice.json",
+            &json_signature(&alice, Some("dist/blvm.tar.gz")),
+        );
+        write("blvm.tar.gz.asc", &json_signature(&bob, None));
+        write("artifact.sig", &minisig([1; 8], "blvm.tar.gz"));
+        write("blvm.tar.gz", "the artifact");
+        write(
+            "alice.key.json",
+            &serde_json::json!({ "secret_key": "00" }).to_string(),
+        );
+        let fpr8 = |keypair: &GovernanceKeypair| {
+            hex::encode(&keypair.public_key().sha256_fingerprint()[..4])
+        };
+
+        // Planning renames nothing
+        let renames = plan_renames(dir.path()).unwrap();
+        assert_eq!(renames.len(), 3);
+        assert!(dir.path().join("sig-alice.json").exists());
+        let targets: Vec<_> = renames
+            .iter()
+            .map(|rename| rename.to.file_name().unwrap().to_str().unwrap().to_string())
+            .collect();
+        assert!(targets.contains(&format!("blvm.tar.gz.{}.sig.json", fpr8(&alice))));
+        assert!(targets.contains(&format!("blvm.tar.gz.{}.sig.json", fpr8(&bob))));
+        assert!(targets.contains(&"blvm.tar.gz.01010101.minisig".to_string()));
+
+        apply_renames(&renames).unwrap();
+        assert!(!dir.path().join("sig-alice.json").exists());
+        assert!(dir.path().join("blvm.tar.gz").exists());
+        assert!(dir.path().join("alice.key.json").exists());
+        assert_eq!(discover(dir.path(), "blvm.tar.gz").unwrap().len(), 3);
+        // Already conventional
+        assert!(plan_renames(dir.path()).unwrap().is_empty());
+
+        // A second signature by Alice for the same artifact collides
+        write(
+            "alice-again.json",
+            &json_signature(&alice, Some("blvm.tar.gz")),
+        );
+        let err = plan_renames(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("alice-again.json"), "{}", err);
+        assert!(
+            err.contains(&format!("blvm.tar.gz.{}.sig.json", fpr8(&alice))),
+            "{}",
+            err
+        );
+    }
+}
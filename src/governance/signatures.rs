@@ -1,16 +1,45 @@
 //! # Governance Signatures
 //!
 //! Signature creation and verification for governance operations.
+//! Creating signatures needs the `signing` feature.
+//!
+//! ECDSA is the default; BIP340 Schnorr signatures ([`SchnorrSignature`])
+//! are available for protocols built on Taproot keys. The two are not
+//! interchangeable: a signature only verifies with its own algorithm.
 
-use rand::rngs::OsRng;
-use secp256k1::{ecdsa::Signature as Secp256k1Signature, Message, Secp256k1, SecretKey};
-use sha2::Digest;
+use hmac::{Hmac, Mac};
+#[cfg(any(feature = "signing", test))]
+use rand::{rngs::OsRng, RngCore};
+use secp256k1::constants::CURVE_ORDER;
+use secp256k1::{ecdsa::Signature as Secp256k1Signature, schnorr, Message};
+#[cfg(any(feature = "signing", test))]
+use secp256k1::{Keypair, Secp256k1, SecretKey, Signing};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt;
 
+#[cfg(any(feature = "signing", test))]
+use crate::governance::context::signing_context;
+use crate::governance::context::verification_context;
 use crate::governance::error::{GovernanceError, GovernanceResult};
 
+type HmacSha256 = Hmac<Sha256>;
+
+/// Byte encoding of a signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigEncoding {
+    /// 64 bytes, `r || s`; read with [`Signature::from_bytes`]
+    Compact,
+    /// DER; read with [`Signature::from_der_bytes`] or
+    /// [`Signature::from_der_strict`]
+    Der,
+}
+
 /// A governance signature
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Serialized as the lowercase hex of its 64-byte compact form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct Signature {
     pub(crate) inner: Secp256k1Signature,
 }
@@ -25,6 +54,56 @@ impl Signature {
         Ok(Self { inner: signature })
     }
 
+    /// Create a signature from DER bytes, as Bitcoin consensus reads them
+    ///
+    /// Enforces the BIP66 encoding rules: a `0x30` sequence whose length
+    /// matches, two `0x02` integers that are neither empty, negative nor
+    /// zero-padded, and no trailing data. `r` or `s` values outside the
+    /// curve order are rejected too. The bytes carry no sighash type.
+    pub fn from_der_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
+        check_canonical_der(bytes).map_err(|reason| {
+            GovernanceError::InvalidSignatureFormat(format!(
+                "Non-canonical DER signature: {}",
+                reason
+            ))
+        })?;
+        let signature = Self::from_der_lax(bytes)?;
+
+        // Out-of-range integers parse as zero and re-encode differently
+        if signature.to_der_bytes() != bytes {
+            return Err(GovernanceError::InvalidSignatureFormat(
+                "Non-canonical DER signature: integer out of range".to_string(),
+            ));
+        }
+        Ok(signature)
+    }
+
+    /// Create a signature from canonical DER bytes only
+    ///
+    /// The same as [`from_der_bytes`](Self::from_der_bytes), which is
+    /// strict as well.
+    pub fn from_der_strict(bytes: &[u8]) -> GovernanceResult<Self> {
+        Self::from_der_bytes(bytes)
+    }
+
+    /// Create a signature with libsecp256k1's DER parser
+    ///
+    /// It accepts some encodings BIP66 rejects; notably negative integers
+    /// parse as zero instead of failing. Only for reading signatures from
+    /// ecosystems that produce such encodings.
+    pub fn from_der_lax(bytes: &[u8]) -> GovernanceResult<Self> {
+        let signature = Secp256k1Signature::from_der(bytes).map_err(|e| {
+            GovernanceError::InvalidSignatureFormat(format!("Invalid DER signature: {}", e))
+        })?;
+
+        Ok(Self { inner: signature })
+    }
+
+    /// Convert a strict DER signature to the 64-byte compact form
+    pub fn to_compact_from_der(bytes: &[u8]) -> GovernanceResult<[u8; 64]> {
+        Ok(Self::from_der_bytes(bytes)?.to_bytes())
+    }
+
     /// Get the signature bytes
     pub fn to_bytes(&self) -> [u8; 64] {
         self.inner.serialize_compact()
@@ -34,6 +113,46 @@ impl Signature {
     pub fn to_der_bytes(&self) -> Vec<u8> {
         self.inner.serialize_der().to_vec()
     }
+
+    /// Whether `s` is in the lower half of the curve order, as Bitcoin
+    /// requires and verification expects
+    pub fn is_low_s(&self) -> bool {
+        let mut normalized = self.inner;
+        normalized.normalize_s();
+        normalized == self.inner
+    }
+
+    /// Whether the signature is in its high-S form
+    ///
+    /// Anyone can flip `s` to `n - s` without the key; verification only
+    /// accepts the low-S form, so such signatures need
+    /// [`normalize_s`](Self::normalize_s) first.
+    pub fn is_malleable(&self) -> bool {
+        !self.is_low_s()
+    }
+
+    /// Normalize `s` to the lower half of the curve order
+    ///
+    /// Returns whether the signature changed.
+    pub fn normalize_s(&mut self) -> bool {
+        let original = self.inner;
+        self.inner.normalize_s();
+        self.inner != original
+    }
+
+    /// Encode the signature for another ecosystem, losslessly
+    ///
+    /// `s` is normalized to the lower half of the curve order first, as
+    /// Bitcoin requires, so the output read back with the matching
+    /// constructor verifies the same message and key.
+    pub fn reencode(&self, encoding: SigEncoding) -> Vec<u8> {
+        let mut inner = self.inner;
+        inner.normalize_s();
+        match encoding {
+            SigEncoding::Compact => inner.serialize_compact().to_vec(),
+            SigEncoding::Der => inner.serialize_der().to_vec(),
+        }
+    }
 }
 
 impl fmt::Display for Signature {
@@ -42,28 +161,151 @@ impl fmt::Display for Signature {
     }
 }
 
+/// Parses the hex of the compact form
+impl std::str::FromStr for Signature {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|e| {
+            GovernanceError::InvalidSignatureFormat(format!("Invalid signature hex: {}", e))
+        })?;
+        if bytes.len() != 64 {
+            return Err(GovernanceError::InvalidSignatureFormat(format!(
+                "Signature must be 64 bytes (r || s), got {}",
+                bytes.len()
+            )));
+        }
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl TryFrom<String> for Signature {
+    type Error = GovernanceError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Signature> for String {
+    fn from(signature: Signature) -> Self {
+        signature.to_string()
+    }
+}
+
+/// A BIP340 Schnorr governance signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchnorrSignature {
+    pub(crate) inner: schnorr::Signature,
+}
+
+impl SchnorrSignature {
+    /// Create a signature from its 64 bytes
+    pub fn from_bytes(bytes: &[u8]) -> GovernanceResult<Self> {
+        let signature = schnorr::Signature::from_slice(bytes).map_err(|e| {
+            GovernanceError::InvalidSignatureFormat(format!("Invalid Schnorr signature: {}", e))
+        })?;
+
+        Ok(Self { inner: signature })
+    }
+
+    /// Get the signature bytes
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(self.inner.as_ref());
+        bytes
+    }
+}
+
+impl fmt::Display for SchnorrSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+/// Check the BIP66 strict DER rules, without a trailing sighash byte
+///
+/// `0x30 <len> 0x02 <len R> <R> 0x02 <len S> <S>`, where the lengths are
+/// single bytes matching the content exactly and each integer is positive
+/// with no unnecessary leading zero.
+fn check_canonical_der(sig: &[u8]) -> Result<(), &'static str> {
+    if sig.len() < 8 || sig.len() > 72 {
+        return Err("invalid length");
+    }
+    if sig[0] != 0x30 {
+        return Err("not a sequence");
+    }
+    if sig[1] as usize != sig.len() - 2 {
+        return Err("sequence length does not match");
+    }
+
+    let len_r = sig[3] as usize;
+    if 5 + len_r >= sig.len() {
+        return Err("R length exceeds signature");
+    }
+    let len_s = sig[5 + len_r] as usize;
+    if len_r + len_s + 6 != sig.len() {
+        return Err("integer lengths do not match sequence length");
+    }
+
+    for (marker, len, value) in [(2, len_r, 4), (len_r + 4, len_s, len_r + 6)] {
+        if sig[marker] != 0x02 {
+            return Err("integer marker missing");
+        }
+        if len == 0 {
+            return Err("zero-length integer");
+        }
+        if sig[value] & 0x80 != 0 {
+            return Err("negative integer");
+        }
+        if len > 1 && sig[value] == 0x00 && sig[value + 1] & 0x80 == 0 {
+            return Err("integer has unnecessary zero padding");
+        }
+    }
+    Ok(())
+}
+
 /// Sign a message with a secret key
+#[cfg(any(feature = "signing", test))]
 pub fn sign_message(secret_key: &SecretKey, message: &[u8]) -> GovernanceResult<Signature> {
-    let secp = Secp256k1::new();
-    let _rng = OsRng;
+    sign_message_with_ctx(signing_context(), secret_key, message)
+}
 
+/// Sign a message with a secret key, using a caller-managed context
+///
+/// [`sign_message`] uses a context shared by the crate; this is for callers
+/// that keep their own, e.g. with custom randomization.
+#[cfg(any(feature = "signing", test))]
+pub fn sign_message_with_ctx<C: Signing>(
+    secp: &Secp256k1<C>,
+    secret_key: &SecretKey,
+    message: &[u8],
+) -> GovernanceResult<Signature> {
     // Hash the message using SHA256 (Bitcoin standard)
     let message_hash = sha2::Sha256::digest(message);
     let message = Message::from_digest_slice(&message_hash)
         .map_err(|e| GovernanceError::Cryptographic(format!("Invalid message hash: {}", e)))?;
 
-    let signature = secp.sign_ecdsa(&message, secret_key);
+    // libsecp256k1 signs low-S already; normalize so the guarantee does not
+    // rest on the backend
+    let mut signature = Signature {
+        inner: secp.sign_ecdsa(&message, secret_key),
+    };
+    signature.normalize_s();
 
-    Ok(Signature { inner: signature })
+    Ok(signature)
 }
 
 /// Verify a signature against a message and public key
+///
+/// Strict about `s`: a high-S signature does not verify, so each signature
+/// has exactly one accepted encoding. [`verify_signature_lax`] accepts both.
 pub fn verify_signature(
     signature: &Signature,
     message: &[u8],
     public_key: &crate::governance::PublicKey,
 ) -> GovernanceResult<bool> {
-    let secp = Secp256k1::new();
+    let secp = verification_context();
 
     // Hash the message using SHA256 (Bitcoin standard)
     let message_hash = sha2::Sha256::digest(message);
@@ -75,6 +317,174 @@ pub fn verify_signature(
     Ok(result.is_ok())
 }
 
+/// Like [`verify_signature`], accepting high-S signatures too
+///
+/// For signatures from signers that do not normalize `s`; a signature and
+/// its high-S twin both verify.
+pub fn verify_signature_lax(
+    signature: &Signature,
+    message: &[u8],
+    public_key: &crate::governance::PublicKey,
+) -> GovernanceResult<bool> {
+    let mut normalized = signature.clone();
+    normalized.normalize_s();
+    verify_signature(&normalized, message, public_key)
+}
+
+/// Sign a message with an RFC6979 nonce this crate derives itself
+///
+/// The nonce is [`compute_rfc6979_nonce`] over the SHA256 of the message,
+/// and the signature is refused unless its `r` is the x coordinate of that
+/// nonce times the generator, so the nonce libsecp256k1 used is checked
+/// rather than assumed. Without extra entropy the signature is the one
+/// [`sign_message`] produces; extra entropy, as libsecp256k1 takes it, must
+/// be 32 bytes.
+#[cfg(any(feature = "signing", test))]
+pub fn sign_message_rfc6979(
+    secret_key: &SecretKey,
+    message: &[u8],
+    extra_entropy: Option<&[u8]>,
+) -> GovernanceResult<Signature> {
+    let secp = signing_context();
+
+    let message_hash: [u8; 32] = Sha256::digest(message).into();
+    let nonce = compute_rfc6979_nonce(&secret_key.secret_bytes(), &message_hash, extra_entropy);
+    let message = Message::from_digest(message_hash);
+
+    let inner = match extra_entropy {
+        None => secp.sign_ecdsa(&message, secret_key),
+        Some(extra) => {
+            let extra: &[u8; 32] = extra.try_into().map_err(|_| {
+                GovernanceError::Cryptographic(format!(
+                    "Extra entropy must be 32 bytes, got {}",
+                    extra.len()
+                ))
+            })?;
+            secp.sign_ecdsa_with_noncedata(&message, secret_key, extra)
+        }
+    };
+
+    let nonce_key = SecretKey::from_slice(&nonce)
+        .map_err(|e| GovernanceError::Cryptographic(format!("Invalid nonce: {}", e)))?;
+    let nonce_point = secp256k1::PublicKey::from_secret_key(secp, &nonce_key).serialize();
+    let mut nonce_x = [0u8; 32];
+    nonce_x.copy_from_slice(&nonce_point[1..]);
+    if reduce_scalar(nonce_x)[..] != inner.serialize_compact()[..32] {
+        return Err(GovernanceError::Cryptographic(
+            "Signature nonce does not match RFC6979".to_string(),
+        ));
+    }
+
+    let mut signature = Signature { inner };
+    signature.normalize_s();
+    Ok(signature)
+}
+
+/// The RFC6979 (section 3.2) deterministic ECDSA nonce for secp256k1 and
+/// HMAC-SHA256
+///
+/// `extra_entropy` is the additional data `k'` of section 3.6, appended to
+/// the key and hash when seeding; libsecp256k1 derives the same nonce for
+/// 32 bytes of extra data. The message hash is reduced modulo the curve
+/// order first. Exposed to audit nonce generation, not to sign with.
+pub fn compute_rfc6979_nonce(
+    secret_key_bytes: &[u8; 32],
+    message_hash: &[u8; 32],
+    extra_entropy: Option<&[u8]>,
+) -> [u8; 32] {
+    let hmac = |key: &[u8; 32], parts: &[&[u8]]| -> [u8; 32] {
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().into()
+    };
+
+    let hash = reduce_scalar(*message_hash);
+    let extra = extra_entropy.unwrap_or_default();
+    let mut v = [0x01u8; 32];
+    let mut k = [0x00u8; 32];
+
+    k = hmac(&k, &[&v, &[0x00], secret_key_bytes, &hash, extra]);
+    v = hmac(&k, &[&v]);
+    k = hmac(&k, &[&v, &[0x01], secret_key_bytes, &hash, extra]);
+    v = hmac(&k, &[&v]);
+
+    loop {
+        v = hmac(&k, &[&v]);
+        if v != [0u8; 32] && v < CURVE_ORDER {
+            return v;
+        }
+        k = hmac(&k, &[&v, &[0x00]]);
+        v = hmac(&k, &[&v]);
+    }
+}
+
+/// A 256-bit big-endian integer modulo the curve order
+///
+/// One subtraction suffices, as the order is above 2^255.
+pub(crate) fn reduce_scalar(value: [u8; 32]) -> [u8; 32] {
+    if value < CURVE_ORDER {
+        return value;
+    }
+    let mut reduced = [0u8; 32];
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let mut byte = value[i] as i16 - CURVE_ORDER[i] as i16 - borrow;
+        borrow = (byte < 0) as i16;
+        if byte < 0 {
+            byte += 256;
+        }
+        reduced[i] = byte as u8;
+    }
+    reduced
+}
+
+/// Sign a message with a secret key using BIP340 Schnorr
+///
+/// The message is hashed with SHA256, as for [`sign_message`]. Fresh
+/// auxiliary randomness is drawn for each signature.
+#[cfg(any(feature = "signing", test))]
+pub fn sign_message_schnorr(
+    secret_key: &SecretKey,
+    message: &[u8],
+) -> GovernanceResult<SchnorrSignature> {
+    let secp = signing_context();
+
+    let message_hash = sha2::Sha256::digest(message);
+    let message = Message::from_digest_slice(&message_hash)
+        .map_err(|e| GovernanceError::Cryptographic(format!("Invalid message hash: {}", e)))?;
+
+    let mut aux_rand = [0u8; 32];
+    OsRng
+        .try_fill_bytes(&mut aux_rand)
+        .map_err(|e| GovernanceError::Cryptographic(format!("entropy unavailable: {}", e)))?;
+    let keypair = Keypair::from_secret_key(secp, secret_key);
+    let signature = secp.sign_schnorr_with_aux_rand(&message, &keypair, &aux_rand);
+
+    Ok(SchnorrSignature { inner: signature })
+}
+
+/// Verify a Schnorr signature against a message and public key
+///
+/// Only the x coordinate of the key is used, as BIP340 requires.
+pub fn verify_schnorr(
+    signature: &SchnorrSignature,
+    message: &[u8],
+    public_key: &crate::governance::PublicKey,
+) -> GovernanceResult<bool> {
+    let secp = verification_context();
+
+    let message_hash = sha2::Sha256::digest(message);
+    let message = Message::from_digest_slice(&message_hash)
+        .map_err(|e| GovernanceError::Cryptographic(format!("Invalid message hash: {}", e)))?;
+
+    let (x_only, _) = public_key.inner.x_only_public_key();
+    let result = secp.verify_schnorr(&signature.inner, &message, &x_only);
+
+    Ok(result.is_ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,30 +495,140 @@ mod tests {
         let keypair = GovernanceKeypair::generate().unwrap();
         let message = b"test message";
 
-        let signature = sign_message(&keypair.secret_key, message).unwrap();
+        let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
         let verified = verify_signature(&signature, message, &keypair.public_key()).unwrap();
 
         assert!(verified);
     }
 
+    #[test]
+    fn test_sign_with_own_context() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let secp = Secp256k1::signing_only();
+
+        let signature =
+            sign_message_with_ctx(&secp, keypair.secret_key.expose(), b"test message").unwrap();
+        assert!(verify_signature(&signature, b"test message", &keypair.public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_rfc6979_nonce_vectors() {
+        let nonce = |key: &str, message: &[u8], extra: Option<&[u8]>| {
+            let key: [u8; 32] = hex::decode(key).unwrap().try_into().unwrap();
+            let hash: [u8; 32] = Sha256::digest(message).into();
+            hex::encode(compute_rfc6979_nonce(&key, &hash, extra))
+        };
+
+        // RFC6979 A.2.5 (P-256, SHA-256): no value reaches either curve's
+        // order, so secp256k1 derives the same nonces
+        let key = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+        assert_eq!(
+            nonce(key, b"sample", None),
+            "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60"
+        );
+        assert_eq!(
+            nonce(key, b"test", None),
+            "d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0"
+        );
+
+        // secp256k1 vectors in common use
+        let one = "0000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            nonce(one, b"Satoshi Nakamoto", None),
+            "8f8a276c19f4149656b280621e358cce24f5f52542772691ee69063b74f15d15"
+        );
+        assert_eq!(
+            nonce(
+                one,
+                b"All those moments will be lost in time, like tears in rain. Time to die...",
+                None
+            ),
+            "38aa22d72376b4dbc472e06c3ba403ee0a394da63fc58d88686c611aba98d6b3"
+        );
+        let max = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140";
+        assert_eq!(
+            nonce(max, b"Satoshi Nakamoto", None),
+            "33a19b60e25fb6f4435af53a3d42d493644827367e6453928554f43e49aa6f90"
+        );
+    }
+
+    #[test]
+    fn test_sign_message_rfc6979() {
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        let keypair = GovernanceKeypair::from_secret_key(&key).unwrap();
+        let secret_key = keypair.secret_key.expose();
+        let message = b"Satoshi Nakamoto";
+
+        let signature = sign_message_rfc6979(secret_key, message, None).unwrap();
+        assert_eq!(
+            hex::encode(signature.to_bytes()),
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8\
+             2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+        // The same signature sign_message produces
+        assert_eq!(signature, sign_message(secret_key, message).unwrap());
+
+        let extra = [0x01u8; 32];
+        let signature = sign_message_rfc6979(secret_key, message, Some(&extra)).unwrap();
+        assert_eq!(
+            hex::encode(signature.to_bytes()),
+            "bb6cf569458d507451271380d2863dad30355387836d5c3287a4efbd5ed1ad8e\
+             4bb4b7899e803f760fe89027e55f5d93768983d6e28af4b5722f6226b345380e"
+        );
+        assert!(verify_signature(&signature, message, &keypair.public_key()).unwrap());
+
+        assert!(sign_message_rfc6979(secret_key, message, Some(&[0x01; 16])).is_err());
+    }
+
     #[test]
     fn test_signature_serialization() {
         let keypair = GovernanceKeypair::generate().unwrap();
         let message = b"test message";
 
-        let signature = sign_message(&keypair.secret_key, message).unwrap();
+        let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
         let bytes = signature.to_bytes();
 
         let reconstructed = Signature::from_bytes(&bytes).unwrap();
         assert_eq!(signature, reconstructed);
     }
 
+    #[test]
+    fn test_signature_serde() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Holder {
+            signature: Signature,
+        }
+
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let signature = sign_message(keypair.secret_key.expose(), b"test message").unwrap();
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(signature.to_bytes())));
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), signature);
+
+        let holder = Holder { signature };
+        let toml = toml::to_string(&holder).unwrap();
+        assert_eq!(toml::from_str::<Holder>(&toml).unwrap(), holder);
+
+        let rejects = |hex_signature: String, reason: &str| {
+            let err = serde_json::from_value::<Signature>(hex_signature.into()).unwrap_err();
+            assert!(err.to_string().contains(reason), "{}", err);
+        };
+        rejects("zz".to_string(), "Invalid signature hex");
+        rejects("ab".repeat(70), "must be 64 bytes");
+        // r above the curve order
+        rejects(
+            format!("{}{}", "ff".repeat(32), "01".repeat(32)),
+            "Invalid signature",
+        );
+    }
+
     #[test]
     fn test_invalid_signature() {
         let keypair = GovernanceKeypair::generate().unwrap();
         let message = b"test message";
 
-        let signature = sign_message(&keypair.secret_key, message).unwrap();
+        let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
         let wrong_message = b"wrong message";
 
         let verified = verify_signature(&signature, wrong_message, &keypair.public_key()).unwrap();
@@ -121,4 +641,270 @@ mod tests {
         let result = Signature::from_bytes(&invalid_bytes);
         assert!(result.is_err());
     }
+
+    /// DER with an unnecessary zero byte in front of R
+    fn padded_der(der: &[u8]) -> Vec<u8> {
+        let mut padded = der.to_vec();
+        padded[1] += 1;
+        padded[3] += 1;
+        padded.insert(4, 0x00);
+        padded
+    }
+
+    #[test]
+    fn test_strict_der() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let signature = sign_message(keypair.secret_key.expose(), b"test message").unwrap();
+        let der = signature.to_der_bytes();
+
+        assert_eq!(Signature::from_der_strict(&der).unwrap(), signature);
+        assert_eq!(Signature::from_der_bytes(&der).unwrap(), signature);
+
+        // libsecp256k1 rejects padding too; strict names the rule broken
+        let padded = padded_der(&der);
+        let err = Signature::from_der_strict(&padded).unwrap_err();
+        assert!(err.to_string().contains("zero padding"), "{}", err);
+        assert!(Signature::from_der_lax(&padded).is_err());
+
+        // A negative R: lax parses it (as zero), strict rejects it
+        let mut negative = vec![0x30, 0x25, 0x02, 0x20, 0x80];
+        negative.extend([0x01; 31]);
+        negative.extend([0x02, 0x01, 0x01]);
+        assert!(Signature::from_der_lax(&negative).is_ok());
+        let err = Signature::from_der_bytes(&negative).unwrap_err();
+        assert!(err.to_string().contains("negative integer"), "{}", err);
+
+        // Trailing data
+        let mut trailing = der.clone();
+        trailing.push(0x01);
+        assert!(Signature::from_der_strict(&trailing).is_err());
+
+        // Truncated anywhere
+        for len in 0..der.len() {
+            assert!(
+                matches!(
+                    Signature::from_der_bytes(&der[..len]),
+                    Err(GovernanceError::InvalidSignatureFormat(_))
+                ),
+                "{} bytes",
+                len
+            );
+        }
+    }
+
+    /// DER sequence of two integers with the given encoded values
+    fn der(r: &[u8], s: &[u8]) -> Vec<u8> {
+        let mut der = vec![0x30, (r.len() + s.len() + 4) as u8, 0x02, r.len() as u8];
+        der.extend(r);
+        der.extend([0x02, s.len() as u8]);
+        der.extend(s);
+        der
+    }
+
+    #[test]
+    fn test_bip66_rejection_rules() {
+        let valid = der(&[0x01; 32], &[0x02; 32]);
+        let signature = Signature::from_der_bytes(&valid).unwrap();
+        assert_eq!(
+            Signature::to_compact_from_der(&valid).unwrap(),
+            signature.to_bytes()
+        );
+        // High bits need exactly one zero byte in front
+        assert!(Signature::from_der_bytes(&der(&[0x00, 0x80], &[0x01])).is_ok());
+
+        let reject = |bytes: &[u8], rule: &str| {
+            let err = Signature::from_der_bytes(bytes).unwrap_err();
+            assert!(
+                matches!(err, GovernanceError::InvalidSignatureFormat(_)),
+                "{:?}",
+                err
+            );
+            assert!(err.to_string().contains(rule), "{}: {}", rule, err);
+            assert!(Signature::to_compact_from_der(bytes).is_err());
+        };
+
+        // Sequence tag
+        let mut wrong_tag = valid.clone();
+        wrong_tag[0] = 0x31;
+        reject(&wrong_tag, "not a sequence");
+
+        // Lengths that overflow the input or the maximum size
+        let mut overflow = valid.clone();
+        overflow[1] += 1;
+        reject(&overflow, "sequence length");
+        let mut r_overflow = valid.clone();
+        r_overflow[3] = 0x7f;
+        reject(&r_overflow, "R length exceeds");
+        let mut s_overflow = valid.clone();
+        s_overflow[37] = 0x21;
+        reject(&s_overflow, "integer lengths");
+        reject(&der(&[0x01; 34], &[0x01; 34]), "invalid length");
+        reject(&valid[..7], "invalid length");
+
+        // Integer tags
+        let mut r_tag = valid.clone();
+        r_tag[2] = 0x03;
+        reject(&r_tag, "integer marker");
+        let mut s_tag = valid.clone();
+        s_tag[36] = 0x04;
+        reject(&s_tag, "integer marker");
+
+        // Unnecessary zero padding
+        reject(&der(&[0x00, 0x01], &[0x01]), "zero padding");
+        reject(&der(&[0x01], &[0x00, 0x7f]), "zero padding");
+
+        // Negative integers
+        reject(&der(&[0x80], &[0x01]), "negative integer");
+        reject(&der(&[0x01], &[0xff, 0x01]), "negative integer");
+
+        // Empty integers
+        reject(&der(&[], &[0x01, 0x01, 0x01]), "zero-length integer");
+        reject(&der(&[0x01, 0x01, 0x01], &[]), "zero-length integer");
+
+        // Values at or above the curve order
+        reject(
+            &der(
+                &[0x00]
+                    .iter()
+                    .chain(&[0xff; 32])
+                    .copied()
+                    .collect::<Vec<_>>(),
+                &[0x01],
+            ),
+            "out of range",
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_der_parsing_never_panics(bytes in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..80)) {
+            // Whatever parses strictly is its own canonical encoding
+            if let Ok(signature) = Signature::from_der_bytes(&bytes) {
+                proptest::prop_assert_eq!(signature.to_der_bytes(), bytes.clone());
+            }
+            let _ = Signature::from_der_lax(&bytes);
+        }
+    }
+
+    /// The same signature with `s` replaced by `n - s`
+    fn high_s(signature: &Signature) -> [u8; 64] {
+        const ORDER: [u8; 32] = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c,
+            0xd0, 0x36, 0x41, 0x41,
+        ];
+        let mut bytes = signature.to_bytes();
+        let mut borrow = 0i16;
+        for (s, n) in bytes[32..].iter_mut().zip(ORDER).rev() {
+            let diff = n as i16 - *s as i16 - borrow;
+            borrow = i16::from(diff < 0);
+            *s = diff.rem_euclid(256) as u8;
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_reencode_roundtrip() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let message = b"test message";
+        let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
+
+        // A high-S signature from another ecosystem
+        let foreign = Signature::from_bytes(&high_s(&signature)).unwrap();
+        assert_ne!(foreign.to_bytes(), signature.to_bytes());
+        assert!(!verify_signature(&foreign, message, &keypair.public_key()).unwrap());
+
+        // compact -> DER -> compact gives the normalized original
+        let der = foreign.reencode(SigEncoding::Der);
+        let from_der = Signature::from_der_strict(&der).unwrap();
+        let compact = from_der.reencode(SigEncoding::Compact);
+        assert_eq!(compact, signature.to_bytes().to_vec());
+
+        let decoded = Signature::from_bytes(&compact).unwrap();
+        assert!(verify_signature(&decoded, message, &keypair.public_key()).unwrap());
+        assert!(verify_signature(&from_der, message, &keypair.public_key()).unwrap());
+
+        // Low-S signatures are unchanged
+        assert_eq!(
+            signature.reencode(SigEncoding::Der),
+            signature.to_der_bytes()
+        );
+    }
+
+    #[test]
+    fn test_low_s_normalization() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let message = b"test message";
+        let mut signature = sign_message(keypair.secret_key.expose(), message).unwrap();
+        assert!(signature.is_low_s() && !signature.is_malleable());
+        assert!(!signature.normalize_s());
+
+        let mut flipped = Signature::from_bytes(&high_s(&signature)).unwrap();
+        assert!(flipped.is_malleable() && !flipped.is_low_s());
+        assert!(!verify_signature(&flipped, message, &keypair.public_key()).unwrap());
+
+        assert!(flipped.normalize_s());
+        assert!(!flipped.is_malleable());
+        assert_eq!(flipped, signature);
+        let bytes = flipped.to_bytes();
+        let decoded = Signature::from_bytes(&bytes).unwrap();
+        assert!(verify_signature(&decoded, message, &keypair.public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_high_s_strict_and_lax() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let message = b"test message";
+        let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
+        let flipped = Signature::from_bytes(&high_s(&signature)).unwrap();
+
+        // Strict rejects the high-S twin, lax accepts both
+        assert!(!verify_signature(&flipped, message, &keypair.public_key()).unwrap());
+        assert!(verify_signature_lax(&flipped, message, &keypair.public_key()).unwrap());
+        assert!(verify_signature_lax(&signature, message, &keypair.public_key()).unwrap());
+        assert!(!verify_signature_lax(&flipped, b"other", &keypair.public_key()).unwrap());
+
+        // Normalizing is idempotent
+        let mut normalized = flipped.clone();
+        assert!(normalized.normalize_s());
+        let once = normalized.clone();
+        assert!(!normalized.normalize_s());
+        assert_eq!(normalized, once);
+        assert_eq!(normalized, signature);
+    }
+
+    #[test]
+    fn test_schnorr_sign_and_verify() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let message = b"test message";
+
+        let signature = sign_message_schnorr(keypair.secret_key.expose(), message).unwrap();
+        assert!(verify_schnorr(&signature, message, &keypair.public_key()).unwrap());
+        assert!(!verify_schnorr(&signature, b"wrong message", &keypair.public_key()).unwrap());
+
+        let other = GovernanceKeypair::generate().unwrap();
+        assert!(!verify_schnorr(&signature, message, &other.public_key()).unwrap());
+
+        let reconstructed = SchnorrSignature::from_bytes(&signature.to_bytes()).unwrap();
+        assert_eq!(reconstructed, signature);
+        assert_eq!(signature.to_string(), hex::encode(signature.to_bytes()));
+        assert!(SchnorrSignature::from_bytes(&[0u8; 63]).is_err());
+    }
+
+    #[test]
+    fn test_ecdsa_and_schnorr_not_interchangeable() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let message = b"test message";
+
+        // Both encode as 64 bytes, so each parses as the other
+        let ecdsa = sign_message(keypair.secret_key.expose(), message).unwrap();
+        if let Ok(as_schnorr) = SchnorrSignature::from_bytes(&ecdsa.to_bytes()) {
+            assert!(!verify_schnorr(&as_schnorr, message, &keypair.public_key()).unwrap());
+        }
+
+        let schnorr = sign_message_schnorr(keypair.secret_key.expose(), message).unwrap();
+        if let Ok(as_ecdsa) = Signature::from_bytes(&schnorr.to_bytes()) {
+            assert!(!verify_signature(&as_ecdsa, message, &keypair.public_key()).unwrap());
+        }
+    }
 }
@@ -0,0 +1,194 @@
+//! # Governance Signers
+//!
+//! Signing behind a trait, so callers that sign do not need the secret key.
+//!
+//! A [`GovernanceKeypair`] signs in process. A [`CommandSigner`] hands the
+//! message hash to an external program (a hardware wallet bridge, an HSM
+//! client, a remote signing service) and reads the signature back, so the
+//! secret never enters this process.
+//!
+//! The external program receives the lowercase hex SHA256 of the message on
+//! stdin, followed by a newline, and writes the 64-byte compact signature as
+//! hex on stdout. The signature is normalized to low-S and checked against
+//! the signer's public key before it is returned.
+
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+#[cfg(any(feature = "signing", test))]
+use crate::governance::GovernanceKeypair;
+use crate::governance::{PublicKey, Signature};
+
+/// Something that signs governance messages with one key
+pub trait GovernanceSigner {
+    /// The key signatures verify against
+    fn public_key(&self) -> PublicKey;
+
+    /// Sign `msg`, hashed with SHA256 like [`crate::sign_message`]
+    fn sign(&self, msg: &[u8]) -> GovernanceResult<Signature>;
+}
+
+#[cfg(any(feature = "signing", test))]
+impl GovernanceSigner for GovernanceKeypair {
+    fn public_key(&self) -> PublicKey {
+        GovernanceKeypair::public_key(self)
+    }
+
+    fn sign(&self, msg: &[u8]) -> GovernanceResult<Signature> {
+        crate::sign_message(self.secret_key.expose(), msg)
+    }
+}
+
+/// Signs by running an external program
+///
+/// The program is run once per signature, without a shell.
+#[derive(Debug, Clone)]
+pub struct CommandSigner {
+    program: String,
+    args: Vec<String>,
+    public_key: PublicKey,
+}
+
+impl CommandSigner {
+    /// Sign with `program`, whose signatures verify against `public_key`
+    pub fn new(program: impl Into<String>, public_key: PublicKey) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            public_key,
+        }
+    }
+
+    /// Pass `args` to the program
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The program run for each signature
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Run the program on `hash`, returning what it wrote to stdout
+    fn run(&self, hash: &[u8]) -> GovernanceResult<String> {
+        let failed = |e: std::io::Error| {
+            GovernanceError::Cryptographic(format!("Signer `{}` failed: {}", self.program, e))
+        };
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(failed)?;
+        // A program that exits without reading the hash is judged by its
+        // output, not by the broken pipe
+        if let Some(mut stdin) = child.stdin.take() {
+            match writeln!(stdin, "{}", hex::encode(hash)) {
+                Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(failed(e)),
+                _ => {}
+            }
+        }
+        let output = child.wait_with_output().map_err(failed)?;
+        if !output.status.success() {
+            return Err(GovernanceError::Cryptographic(format!(
+                "Signer `{}` failed: {}",
+                self.program, output.status
+            )));
+        }
+        String::from_utf8(output.stdout).map_err(|_| {
+            GovernanceError::InvalidSignatureFormat(format!(
+                "Signer `{}` wrote non-UTF-8 output",
+                self.program
+            ))
+        })
+    }
+}
+
+impl GovernanceSigner for CommandSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, msg: &[u8]) -> GovernanceResult<Signature> {
+        let stdout = self.run(&Sha256::digest(msg))?;
+        let bytes = hex::decode(stdout.trim()).map_err(|e| {
+            GovernanceError::InvalidSignatureFormat(format!(
+                "Signer `{}` wrote invalid hex: {}",
+                self.program, e
+            ))
+        })?;
+        let mut signature = Signature::from_bytes(&bytes)?;
+        signature.normalize_s();
+        if !crate::governance::verify_signature(&signature, msg, &self.public_key)? {
+            return Err(GovernanceError::SignatureVerification(format!(
+                "Signer `{}` returned a signature that does not verify against {}",
+                self.program,
+                self.public_key.short()
+            )));
+        }
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypair_signer() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let signer: &dyn GovernanceSigner = &keypair;
+        let signature = signer.sign(b"release").unwrap();
+        assert_eq!(signer.public_key(), keypair.public_key());
+        assert!(
+            crate::governance::verify_signature(&signature, b"release", &keypair.public_key())
+                .unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_signer() {
+        // The program sees only the hash, never the secret; this one
+        // answers with a signature made here
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let expected = crate::sign_message(keypair.secret_key.expose(), b"release").unwrap();
+        let script = format!(
+            "read hash; [ \"$hash\" = {} ] && echo {}",
+            hex::encode(Sha256::digest(b"release")),
+            hex::encode(expected.to_bytes())
+        );
+        let signer =
+            CommandSigner::new("sh", keypair.public_key()).with_args(["-c", script.as_str()]);
+        assert_eq!(signer.sign(b"release").unwrap(), expected);
+
+        // Signatures for another message or key are refused
+        assert!(matches!(
+            signer.sign(b"other"),
+            Err(GovernanceError::Cryptographic(_))
+        ));
+        let other = GovernanceKeypair::generate().unwrap();
+        let signer =
+            CommandSigner::new("sh", other.public_key()).with_args(["-c", script.as_str()]);
+        assert!(matches!(
+            signer.sign(b"release"),
+            Err(GovernanceError::SignatureVerification(_))
+        ));
+
+        let signer = CommandSigner::new("sh", keypair.public_key()).with_args(["-c", "echo zz"]);
+        assert!(matches!(
+            signer.sign(b"release"),
+            Err(GovernanceError::InvalidSignatureFormat(_))
+        ));
+        let signer = CommandSigner::new("/nonexistent/signer", keypair.public_key());
+        assert!(signer.sign(b"release").is_err());
+    }
+}
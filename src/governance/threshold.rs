@@ -0,0 +1,162 @@
+//! # Thresholds
+//!
+//! An "m of n" signature threshold, written `N-of-M` (e.g. `3-of-5`).
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+
+/// Signatures required out of the total number of keys
+///
+/// Always valid: `1 <= required <= total`. Serialized as `N-of-M`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Threshold {
+    required: usize,
+    total: usize,
+}
+
+impl Threshold {
+    /// Create a threshold, rejecting `required == 0` or `required > total`
+    pub fn new(required: usize, total: usize) -> GovernanceResult<Self> {
+        if required == 0 || required > total {
+            return Err(GovernanceError::InvalidThreshold {
+                threshold: required,
+                total,
+            });
+        }
+        Ok(Self { required, total })
+    }
+
+    /// Signatures required
+    pub fn required(&self) -> usize {
+        self.required
+    }
+
+    /// Total number of keys
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Whether `signatures` valid signatures meet the threshold
+    pub fn is_met(&self, signatures: usize) -> bool {
+        signatures >= self.required
+    }
+
+    /// Check that the committee has exactly `total` keys
+    ///
+    /// A `6-of-7` threshold over five keys is a misconfiguration, even though
+    /// six signatures could never be collected anyway.
+    pub fn check_committee(&self, public_keys: usize) -> GovernanceResult<()> {
+        if public_keys != self.total {
+            return Err(GovernanceError::InvalidMultisig(format!(
+                "threshold {} expects {} public keys, but {} were provided",
+                self, self.total, public_keys
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-of-{}", self.required, self.total)
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = GovernanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            GovernanceError::InvalidInput(format!(
+                "Invalid threshold '{}': expected format 'N-of-M'",
+                s
+            ))
+        };
+        let (required, total) = s.trim().split_once("-of-").ok_or_else(invalid)?;
+        let required = required.parse().map_err(|_| invalid())?;
+        let total = total.parse().map_err(|_| invalid())?;
+        Self::new(required, total)
+    }
+}
+
+impl TryFrom<String> for Threshold {
+    type Error = GovernanceError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Threshold> for String {
+    fn from(threshold: Threshold) -> Self {
+        threshold.to_string()
+    }
+}
+
+impl TryFrom<(usize, usize)> for Threshold {
+    type Error = GovernanceError;
+
+    fn try_from((required, total): (usize, usize)) -> Result<Self, Self::Error> {
+        Self::new(required, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_display_roundtrip() {
+        for s in ["1-of-1", "3-of-5", "6-of-7"] {
+            let threshold: Threshold = s.parse().unwrap();
+            assert_eq!(threshold.to_string(), s);
+        }
+
+        let threshold: Threshold = " 2-of-3 ".parse().unwrap();
+        assert_eq!((threshold.required(), threshold.total()), (2, 3));
+        assert!(threshold.is_met(2));
+        assert!(!threshold.is_met(1));
+    }
+
+    #[test]
+    fn test_invalid_thresholds() {
+        for s in ["0-of-5", "6-of-5", "3-5", "3/5", "a-of-5", "3-of-", ""] {
+            assert!(s.parse::<Threshold>().is_err(), "{}", s);
+        }
+        assert!(matches!(
+            Threshold::new(4, 3),
+            Err(GovernanceError::InvalidThreshold {
+                threshold: 4,
+                total: 3
+            })
+        ));
+        assert!(Threshold::try_from((0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_committee_size_mismatch() {
+        let threshold: Threshold = "6-of-7".parse().unwrap();
+        let err = threshold.check_committee(5).unwrap_err();
+        assert!(matches!(err, GovernanceError::InvalidMultisig(_)));
+        assert_eq!(
+            err.to_string(),
+            "Invalid multisig configuration: threshold 6-of-7 expects 7 public keys, but 5 were provided"
+        );
+        assert!(threshold.check_committee(7).is_ok());
+    }
+
+    #[test]
+    fn test_serde() {
+        let threshold = Threshold::new(2, 3).unwrap();
+        assert_eq!(serde_json::to_string(&threshold).unwrap(), "\"2-of-3\"");
+        assert_eq!(
+            serde_json::from_str::<Threshold>("\"2-of-3\"").unwrap(),
+            threshold
+        );
+        assert!(serde_json::from_str::<Threshold>("\"4-of-3\"").is_err());
+    }
+}
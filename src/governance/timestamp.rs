@@ -0,0 +1,153 @@
+//! # Signed Timestamps
+//!
+//! A signer's own attestation of when they signed a message.
+//!
+//! The signing time recorded next to a signature (`created_at`, `signed_at`)
+//! is not covered by the signature, so anyone relaying the file can change
+//! it. A signed timestamp binds the time to the message: the signer signs
+//! `timestamp:<sha256(message)>:<time>` with the same key. Signature expiry
+//! only trusts signed timestamps, so a third party cannot make an old
+//! signature look fresh.
+//!
+//! The time is written as RFC3339 with whole seconds in UTC.
+
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+#[cfg(any(feature = "signing", test))]
+use secp256k1::SecretKey;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::history;
+use crate::governance::messages::join_fields;
+use crate::governance::signer::GovernanceSigner;
+use crate::governance::{PublicKey, Signature};
+
+/// Signing time attested by the signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTimestamp {
+    /// When the message was signed
+    pub signed_at: DateTime<Utc>,
+    /// Signature over [`signing_bytes`](Self::signing_bytes)
+    pub signature: Signature,
+}
+
+impl SignedTimestamp {
+    /// Bytes covered by the timestamp signature
+    pub fn signing_bytes(message: &[u8], signed_at: DateTime<Utc>) -> Vec<u8> {
+        join_fields(&[
+            "timestamp".to_string(),
+            hex::encode(Sha256::digest(message)),
+            signed_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ])
+    }
+
+    /// Attest that `message` was signed at `signed_at` (truncated to seconds)
+    #[cfg(any(feature = "signing", test))]
+    pub fn sign(
+        secret_key: &SecretKey,
+        message: &[u8],
+        signed_at: DateTime<Utc>,
+    ) -> GovernanceResult<Self> {
+        let signed_at = signed_at.trunc_subsecs(0);
+        Ok(Self {
+            signed_at,
+            signature: crate::sign_message(secret_key, &Self::signing_bytes(message, signed_at))?,
+        })
+    }
+
+    /// Like [`sign`](Self::sign), with the key behind `signer`
+    pub fn sign_with(
+        signer: &dyn GovernanceSigner,
+        message: &[u8],
+        signed_at: DateTime<Utc>,
+    ) -> GovernanceResult<Self> {
+        let signed_at = signed_at.trunc_subsecs(0);
+        Ok(Self {
+            signed_at,
+            signature: signer.sign(&Self::signing_bytes(message, signed_at))?,
+        })
+    }
+
+    /// Whether the timestamp was signed by `public_key` for `message`
+    pub fn verify(&self, message: &[u8], public_key: &PublicKey) -> bool {
+        crate::governance::verify_signature(
+            &self.signature,
+            &Self::signing_bytes(message, self.signed_at),
+            public_key,
+        )
+        .unwrap_or(false)
+    }
+
+    /// Read the timestamp of a signature file or aggregate entry
+    ///
+    /// Takes the hex `timestamp_signature` field and the recorded signing
+    /// time (`signed_at`, `created_at` or `metadata.signed_at`). Returns
+    /// `None` when the entry has no timestamp signature.
+    pub fn from_entry(entry: &Value) -> GovernanceResult<Option<Self>> {
+        let Some(signature_hex) = entry.get("timestamp_signature").and_then(Value::as_str) else {
+            return Ok(None);
+        };
+        let bytes = hex::decode(signature_hex).map_err(|e| {
+            GovernanceError::InvalidSignatureFormat(format!("Invalid timestamp signature: {}", e))
+        })?;
+        let signed_at = entry
+            .get("signed_at")
+            .and_then(Value::as_str)
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+            .or_else(|| history::signed_at(entry))
+            .ok_or_else(|| {
+                GovernanceError::MessageFormat(
+                    "timestamp signature without a signing time".to_string(),
+                )
+            })?;
+
+        Ok(Some(Self {
+            signed_at,
+            signature: Signature::from_bytes(&bytes)?,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceKeypair;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let other = GovernanceKeypair::generate().unwrap();
+        let message = b"binary:application:abc";
+        let timestamp =
+            SignedTimestamp::sign(keypair.secret_key.expose(), message, Utc::now()).unwrap();
+
+        assert!(timestamp.verify(message, &keypair.public_key()));
+        assert!(!timestamp.verify(message, &other.public_key()));
+        assert!(!timestamp.verify(b"binary:application:def", &keypair.public_key()));
+
+        let mut backdated = timestamp.clone();
+        backdated.signed_at -= chrono::Duration::try_days(1).unwrap();
+        assert!(!backdated.verify(message, &keypair.public_key()));
+    }
+
+    #[test]
+    fn test_from_entry() {
+        let keypair = GovernanceKeypair::generate().unwrap();
+        let timestamp =
+            SignedTimestamp::sign(keypair.secret_key.expose(), b"m", Utc::now()).unwrap();
+        let signature_hex = hex::encode(timestamp.signature.to_bytes());
+
+        let file = serde_json::json!({
+            "created_at": timestamp.signed_at.to_rfc3339(),
+            "timestamp_signature": signature_hex,
+        });
+        assert_eq!(SignedTimestamp::from_entry(&file).unwrap(), Some(timestamp));
+
+        let no_time = serde_json::json!({ "timestamp_signature": signature_hex });
+        assert!(SignedTimestamp::from_entry(&no_time).is_err());
+        let unsigned = serde_json::json!({ "signed_at": "2024-01-01T00:00:00Z" });
+        assert_eq!(SignedTimestamp::from_entry(&unsigned).unwrap(), None);
+    }
+}
@@ -0,0 +1,1077 @@
+//! # Trust Store
+//!
+//! The governance trust material a verifying service runs with (committee,
+//! revocation list, signer registry and verification policy) as one
+//! snapshot, reloadable without restarting the service.
+//!
+//! A [`TrustHandle`] is a cheap, clonable handle to the active
+//! [`TrustStore`]. Verifiers call [`TrustHandle::load`] once per
+//! verification and see a consistent snapshot; a reload swaps in a new
+//! snapshot for every later `load`, while verifications already holding the
+//! old one finish with it.
+//!
+//! A reload is all-or-nothing: the committee and revocation documents must
+//! be signed by a threshold of the *active* committee and must not roll
+//! back to an older serial, and the signer registry and policy must parse
+//! and build. If any piece fails, the active store is kept.
+//!
+//! Source files (see [`TrustSources::in_dir`]):
+//! - `committee.json`: a [`SignedTrustDocument`] of kind `committee`, whose
+//!   body holds the `threshold` (`N-of-M`), hex `public_keys` and an
+//!   optional `nested` team configuration
+//! - `revocations.json`: a [`SignedTrustDocument`] of kind `revocations`,
+//!   whose body holds hex `revoked_keys`
+//! - `signers.json` (optional): a serialized [`SignerRegistry`]
+//! - `policy.toml` (optional): a [`PolicyConfig`]
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use crate::governance::error::{GovernanceError, GovernanceResult};
+use crate::governance::messages::join_fields;
+use crate::governance::nested_multisig::{NestedMultisig, Team, TeamMaintainer};
+use crate::governance::pipeline::{PipelineBuilder, PolicyConfig};
+#[cfg(any(feature = "signing", test))]
+use crate::governance::GovernanceKeypair;
+use crate::governance::{Multisig, PublicKey, Signature, SignerRegistry, Threshold};
+
+/// Kind of the signed committee document
+pub const COMMITTEE_KIND: &str = "committee";
+/// Kind of the signed revocation list document
+pub const REVOCATIONS_KIND: &str = "revocations";
+
+/// A trust document signed by the committee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTrustDocument {
+    /// What the body is (`committee` or `revocations`)
+    pub kind: String,
+    /// Increases with every new version; a reload never goes back
+    pub serial: u64,
+    /// The document
+    pub body: Value,
+    /// Hex-encoded signatures over [`Self::signing_bytes`]
+    pub signatures: Vec<String>,
+}
+
+impl SignedTrustDocument {
+    /// Sign a document with each of `keypairs`
+    #[cfg(any(feature = "signing", test))]
+    pub fn sign(
+        kind: &str,
+        serial: u64,
+        body: Value,
+        keypairs: &[&GovernanceKeypair],
+    ) -> GovernanceResult<Self> {
+        let message = Self::signing_bytes(kind, serial, &body)?;
+        let signatures = keypairs
+            .iter()
+            .map(|keypair| {
+                crate::sign_message(keypair.secret_key.expose(), &message)
+                    .map(|signature| hex::encode(signature.to_bytes()))
+            })
+            .collect::<GovernanceResult<_>>()?;
+        Ok(Self {
+            kind: kind.to_string(),
+            serial,
+            body,
+            signatures,
+        })
+    }
+
+    /// Bytes the committee signs: `blvm-trust:<kind>:<serial>:<sha256 of body JSON>`
+    ///
+    /// The body is hashed as compact JSON with sorted object keys.
+    pub fn signing_bytes(kind: &str, serial: u64, body: &Value) -> GovernanceResult<Vec<u8>> {
+        let body =
+            serde_json::to_vec(body).map_err(|e| GovernanceError::Serialization(e.to_string()))?;
+        Ok(join_fields(&[
+            "blvm-trust".to_string(),
+            kind.to_string(),
+            serial.to_string(),
+            hex::encode(Sha256::digest(body)),
+        ]))
+    }
+
+    fn check_kind(&self, kind: &str) -> GovernanceResult<()> {
+        if self.kind != kind {
+            return Err(GovernanceError::InvalidInput(format!(
+                "Expected a {} document, got {}",
+                kind, self.kind
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the kind and that a threshold of `committee` signed
+    pub fn verify(&self, kind: &str, committee: &Multisig) -> GovernanceResult<()> {
+        self.verify_excluding(kind, committee, &[])
+    }
+
+    /// Like [`verify`](Self::verify), but signatures by `excluded` keys,
+    /// e.g. revoked ones, do not count toward the threshold
+    pub fn verify_excluding(
+        &self,
+        kind: &str,
+        committee: &Multisig,
+        excluded: &[PublicKey],
+    ) -> GovernanceResult<()> {
+        self.check_kind(kind)?;
+        let signatures = self
+            .signatures
+            .iter()
+            .map(|signature| {
+                let bytes = hex::decode(signature).map_err(|e| {
+                    GovernanceError::InvalidSignatureFormat(format!(
+                        "Invalid {} signature hex: {}",
+                        kind, e
+                    ))
+                })?;
+                Signature::from_bytes(&bytes)
+            })
+            .collect::<GovernanceResult<Vec<_>>>()?;
+        let message = Self::signing_bytes(&self.kind, self.serial, &self.body)?;
+        let keys = committee.public_keys();
+        let signed: HashSet<usize> = committee
+            .collect_valid_signatures(&message, &signatures)?
+            .into_iter()
+            .filter(|&index| !excluded.contains(&keys[index]))
+            .collect();
+        if !committee.m_of_n().is_met(signed.len()) {
+            return Err(GovernanceError::SignatureVerification(format!(
+                "{} document signed by {} of the committee, {} required",
+                kind,
+                signed.len(),
+                committee.threshold()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Body of a committee document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitteeBody {
+    pub threshold: Threshold,
+    /// Hex-encoded compressed public keys
+    pub public_keys: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nested: Option<NestedBody>,
+}
+
+/// Team configuration of a committee document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NestedBody {
+    pub teams_required: usize,
+    pub maintainers_per_team_required: usize,
+    pub teams: Vec<TeamBody>,
+}
+
+/// One team of a committee document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamBody {
+    pub id: String,
+    pub name: String,
+    /// `(github, hex public key)` of each maintainer
+    pub maintainers: Vec<(String, String)>,
+}
+
+/// Body of a revocation list document
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationsBody {
+    /// Hex-encoded compressed public keys
+    pub revoked_keys: Vec<String>,
+}
+
+fn parse_key(hex_key: &str) -> GovernanceResult<PublicKey> {
+    let bytes = hex::decode(hex_key)
+        .map_err(|e| GovernanceError::InvalidKey(format!("Invalid public key hex: {}", e)))?;
+    PublicKey::from_bytes(&bytes)
+}
+
+impl CommitteeBody {
+    fn build(&self) -> GovernanceResult<(Multisig, Option<NestedMultisig>)> {
+        let public_keys = self
+            .public_keys
+            .iter()
+            .map(|key| parse_key(key))
+            .collect::<GovernanceResult<Vec<_>>>()?;
+        let multisig = Multisig::with_threshold(self.threshold, public_keys)?;
+        let nested = match &self.nested {
+            Some(nested) => {
+                let teams = nested
+                    .teams
+                    .iter()
+                    .map(|team| {
+                        Ok(Team {
+                            id: team.id.clone(),
+                            name: team.name.clone(),
+                            maintainers: team
+                                .maintainers
+                                .iter()
+                                .map(|(github, key)| {
+                                    Ok(TeamMaintainer {
+                                        github: github.clone(),
+                                        public_key: parse_key(key)?,
+                                    })
+                                })
+                                .collect::<GovernanceResult<_>>()?,
+                        })
+                    })
+                    .collect::<GovernanceResult<Vec<_>>>()?;
+                Some(NestedMultisig::new(
+                    teams,
+                    nested.teams_required,
+                    nested.maintainers_per_team_required,
+                )?)
+            }
+            None => None,
+        };
+        Ok((multisig, nested))
+    }
+}
+
+/// Where trust material is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustSources {
+    /// Signed committee document
+    pub committee: PathBuf,
+    /// Signed revocation list document
+    pub revocations: PathBuf,
+    /// Signer registry JSON; an empty registry without one
+    pub signers: Option<PathBuf>,
+    /// Policy TOML; the default policy without one
+    pub policy: Option<PathBuf>,
+}
+
+impl TrustSources {
+    /// The standard file names in `dir`; `signers.json` and `policy.toml`
+    /// are used only if they exist
+    pub fn in_dir(dir: &Path) -> Self {
+        let optional = |name: &str| Some(dir.join(name)).filter(|path| path.exists());
+        Self {
+            committee: dir.join("committee.json"),
+            revocations: dir.join("revocations.json"),
+            signers: optional("signers.json"),
+            policy: optional("policy.toml"),
+        }
+    }
+
+    /// Every source file, for watching
+    pub fn paths(&self) -> Vec<&Path> {
+        [Some(&self.committee), Some(&self.revocations)]
+            .into_iter()
+            .chain([self.signers.as_ref(), self.policy.as_ref()])
+            .flatten()
+            .map(PathBuf::as_path)
+            .collect()
+    }
+}
+
+fn read_source(path: &Path) -> GovernanceResult<String> {
+    std::fs::read_to_string(path).map_err(|e| {
+        GovernanceError::InvalidInput(format!("Failed to read {}: {}", path.display(), e))
+    })
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> GovernanceResult<T> {
+    serde_json::from_str(&read_source(path)?)
+        .map_err(|e| GovernanceError::Serialization(format!("Invalid {}: {}", path.display(), e)))
+}
+
+fn body<T: serde::de::DeserializeOwned>(document: &SignedTrustDocument) -> GovernanceResult<T> {
+    serde_json::from_value(document.body.clone()).map_err(|e| {
+        GovernanceError::Serialization(format!("Invalid {} document: {}", document.kind, e))
+    })
+}
+
+/// One difference between two trust stores
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustChange {
+    /// The committee changed (threshold, keys or teams)
+    Committee {
+        from: Threshold,
+        to: Threshold,
+        added: Vec<PublicKey>,
+        removed: Vec<PublicKey>,
+    },
+    /// Keys were revoked or un-revoked
+    Revocations {
+        added: Vec<PublicKey>,
+        removed: Vec<PublicKey>,
+    },
+    /// Signer identities were added, removed or re-verified (hex keys)
+    Signers {
+        added: Vec<String>,
+        removed: Vec<String>,
+        updated: Vec<String>,
+    },
+    /// The verification policy changed
+    Policy,
+}
+
+impl fmt::Display for TrustChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrustChange::Committee {
+                from,
+                to,
+                added,
+                removed,
+            } => write!(
+                f,
+                "committee {} -> {} ({} key(s) added, {} removed)",
+                from,
+                to,
+                added.len(),
+                removed.len()
+            ),
+            TrustChange::Revocations { added, removed } => write!(
+                f,
+                "revocations: {} key(s) revoked, {} un-revoked",
+                added.len(),
+                removed.len()
+            ),
+            TrustChange::Signers {
+                added,
+                removed,
+                updated,
+            } => write!(
+                f,
+                "signers: {} added, {} removed, {} updated",
+                added.len(),
+                removed.len(),
+                updated.len()
+            ),
+            TrustChange::Policy => write!(f, "policy changed"),
+        }
+    }
+}
+
+/// Keys in `new` but not `old`, and in `old` but not `new`
+fn key_diff(old: &[PublicKey], new: &[PublicKey]) -> (Vec<PublicKey>, Vec<PublicKey>) {
+    let added = new.iter().filter(|key| !old.contains(key)).cloned();
+    let removed = old.iter().filter(|key| !new.contains(key)).cloned();
+    (added.collect(), removed.collect())
+}
+
+/// A snapshot of the trust material a verifier runs with
+#[derive(Debug, Clone)]
+pub struct TrustStore {
+    generation: u64,
+    committee_body: CommitteeBody,
+    committee_serial: u64,
+    multisig: Multisig,
+    nested: Option<NestedMultisig>,
+    revocations_serial: u64,
+    revoked: Vec<PublicKey>,
+    signers: SignerRegistry,
+    policy: PolicyConfig,
+}
+
+impl TrustStore {
+    /// Bootstrap a store from trusted local material
+    ///
+    /// The initial committee is the root of trust: its document's
+    /// signatures are not checked, only its content. Every later
+    /// [`reload`](Self::reload) must be signed by the active committee.
+    pub fn new(
+        committee: &SignedTrustDocument,
+        revocations: &SignedTrustDocument,
+        signers: SignerRegistry,
+        policy: PolicyConfig,
+    ) -> GovernanceResult<Self> {
+        Self::assemble(0, committee, revocations, signers, policy)
+    }
+
+    /// Bootstrap a store from `sources`, trusting the committee document
+    pub fn open(sources: &TrustSources) -> GovernanceResult<Self> {
+        let (signers, policy) = read_unsigned(sources)?;
+        Self::new(
+            &read_json(&sources.committee)?,
+            &read_json(&sources.revocations)?,
+            signers,
+            policy,
+        )
+    }
+
+    /// Read and validate every source, returning the store that replaces
+    /// this one
+    ///
+    /// Fails, without partial results, if any document is not signed by a
+    /// threshold of this store's non-revoked committee keys, rolls back its
+    /// serial, or does not parse or validate.
+    pub fn reload(&self, sources: &TrustSources) -> GovernanceResult<Self> {
+        let committee: SignedTrustDocument = read_json(&sources.committee)?;
+        let revocations: SignedTrustDocument = read_json(&sources.revocations)?;
+        let (signers, policy) = read_unsigned(sources)?;
+
+        // A revoked key must not help replace the committee or lift its
+        // own revocation
+        committee.verify_excluding(COMMITTEE_KIND, &self.multisig, &self.revoked)?;
+        revocations.verify_excluding(REVOCATIONS_KIND, &self.multisig, &self.revoked)?;
+        for (document, active) in [
+            (&committee, self.committee_serial),
+            (&revocations, self.revocations_serial),
+        ] {
+            if document.serial < active {
+                return Err(GovernanceError::InvalidInput(format!(
+                    "{} document serial {} is older than the active {}",
+                    document.kind, document.serial, active
+                )));
+            }
+        }
+
+        Self::assemble(
+            self.generation + 1,
+            &committee,
+            &revocations,
+            signers,
+            policy,
+        )
+    }
+
+    fn assemble(
+        generation: u64,
+        committee: &SignedTrustDocument,
+        revocations: &SignedTrustDocument,
+        signers: SignerRegistry,
+        policy: PolicyConfig,
+    ) -> GovernanceResult<Self> {
+        committee.check_kind(COMMITTEE_KIND)?;
+        revocations.check_kind(REVOCATIONS_KIND)?;
+        let committee_body: CommitteeBody = body(committee)?;
+        let (multisig, nested) = committee_body.build()?;
+        let revoked = body::<RevocationsBody>(revocations)?
+            .revoked_keys
+            .iter()
+            .map(|key| parse_key(key))
+            .collect::<GovernanceResult<Vec<_>>>()?;
+        let store = Self {
+            generation,
+            committee_body,
+            committee_serial: committee.serial,
+            multisig,
+            nested,
+            revocations_serial: revocations.serial,
+            revoked,
+            signers,
+            policy,
+        };
+        store.pipeline()?;
+        Ok(store)
+    }
+
+    /// Reloads since the store was bootstrapped
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The committee
+    pub fn multisig(&self) -> &Multisig {
+        &self.multisig
+    }
+
+    /// The team configuration, if the committee has one
+    pub fn nested(&self) -> Option<&NestedMultisig> {
+        self.nested.as_ref()
+    }
+
+    /// Revoked keys
+    pub fn revoked(&self) -> &[PublicKey] {
+        &self.revoked
+    }
+
+    /// Whether `public_key` is revoked
+    pub fn is_revoked(&self, public_key: &PublicKey) -> bool {
+        self.revoked.contains(public_key)
+    }
+
+    /// Verified signer identities
+    pub fn signers(&self) -> &SignerRegistry {
+        &self.signers
+    }
+
+    /// The verification policy
+    pub fn policy(&self) -> &PolicyConfig {
+        &self.policy
+    }
+
+    /// Whether distinct, non-revoked committee keys signed `message` up to
+    /// the threshold
+    pub fn verify(&self, message: &[u8], signatures: &[Signature]) -> GovernanceResult<bool> {
+        let keys = self.multisig.public_keys();
+        let signed: HashSet<usize> = self
+            .multisig
+            .collect_valid_signatures(message, signatures)?
+            .into_iter()
+            .filter(|&index| !self.is_revoked(&keys[index]))
+            .collect();
+        Ok(self.multisig.m_of_n().is_met(signed.len()))
+    }
+
+    /// Pipeline builder for the policy, with the store's revocations added
+    /// to the revocation stage
+    pub fn pipeline(&self) -> GovernanceResult<PipelineBuilder> {
+        let mut policy = self.policy.clone();
+        policy
+            .revoked_keys
+            .extend(self.revoked.iter().map(PublicKey::to_string));
+        policy.builder()
+    }
+
+    /// What changed from `self` to `next`
+    pub fn changes(&self, next: &TrustStore) -> Vec<TrustChange> {
+        let mut changes = Vec::new();
+        if self.committee_body != next.committee_body {
+            let (added, removed) =
+                key_diff(self.multisig.public_keys(), next.multisig.public_keys());
+            changes.push(TrustChange::Committee {
+                from: self.multisig.m_of_n(),
+                to: next.multisig.m_of_n(),
+                added,
+                removed,
+            });
+        }
+        let (added, removed) = key_diff(&self.revoked, &next.revoked);
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(TrustChange::Revocations { added, removed });
+        }
+        let keys = |store: &TrustStore| -> BTreeSet<String> {
+            store
+                .signers
+                .iter()
+                .map(|id| id.public_key.clone())
+                .collect()
+        };
+        let (old, new) = (keys(self), keys(next));
+        let added: Vec<String> = new.difference(&old).cloned().collect();
+        let removed: Vec<String> = old.difference(&new).cloned().collect();
+        let updated: Vec<String> = old
+            .intersection(&new)
+            .filter(|key| {
+                let find = |store: &TrustStore| {
+                    store
+                        .signers
+                        .iter()
+                        .find(|id| &&id.public_key == key)
+                        .cloned()
+                };
+                find(self) != find(next)
+            })
+            .cloned()
+            .collect();
+        if !added.is_empty() || !removed.is_empty() || !updated.is_empty() {
+            changes.push(TrustChange::Signers {
+                added,
+                removed,
+                updated,
+            });
+        }
+        if self.policy != next.policy {
+            changes.push(TrustChange::Policy);
+        }
+        changes
+    }
+}
+
+fn read_unsigned(sources: &TrustSources) -> GovernanceResult<(SignerRegistry, PolicyConfig)> {
+    let signers = match &sources.signers {
+        Some(path) => read_json(path)?,
+        None => SignerRegistry::new(),
+    };
+    let policy = match &sources.policy {
+        Some(path) => PolicyConfig::from_toml(&read_source(path)?)?,
+        None => PolicyConfig::default(),
+    };
+    Ok((signers, policy))
+}
+
+#[derive(Debug)]
+struct Shared {
+    current: RwLock<Arc<TrustStore>>,
+    /// Serializes reloads, so each is validated against the store it replaces
+    reload_lock: Mutex<()>,
+}
+
+/// Cheap, clonable handle to the active [`TrustStore`]
+#[derive(Debug, Clone)]
+pub struct TrustHandle {
+    shared: Arc<Shared>,
+}
+
+impl TrustHandle {
+    /// Make `store` the active store
+    pub fn new(store: TrustStore) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                current: RwLock::new(Arc::new(store)),
+                reload_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// The active store
+    ///
+    /// Take one snapshot per verification; a reload does not affect a
+    /// snapshot already taken.
+    pub fn load(&self) -> Arc<TrustStore> {
+        Arc::clone(
+            &self
+                .shared
+                .current
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Validate `sources` against the active store and swap the result in
+    ///
+    /// Returns what changed; nothing is swapped if nothing changed. On
+    /// error the active store stays in place. Emits `TrustStoreReloaded`
+    /// or `TrustStoreReloadRejected`.
+    pub fn reload(&self, sources: &TrustSources) -> GovernanceResult<Vec<TrustChange>> {
+        let _reloading = self
+            .shared
+            .reload_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let active = self.load();
+        let next = match active.reload(sources) {
+            Ok(next) => next,
+            Err(e) => {
+                emit_event!(TrustStoreReloadRejected {
+                    generation: active.generation(),
+                    reason: e.to_string()
+                });
+                return Err(e);
+            }
+        };
+        let changes = active.changes(&next);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+        let generation = next.generation();
+        *self
+            .shared
+            .current
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Arc::new(next);
+        emit_event!(TrustStoreReloaded {
+            generation: generation,
+            changes: changes.clone()
+        });
+        Ok(changes)
+    }
+}
+
+/// Reloads a [`TrustHandle`] whenever its source files change
+///
+/// Polls the files' modification times and sizes; stops when dropped.
+/// Failed reloads leave the active store in place and are reported through
+/// the `TrustStoreReloadRejected` event.
+///
+/// Available with the `watch` feature.
+#[cfg(feature = "watch")]
+#[derive(Debug)]
+pub struct TrustWatcher {
+    stop: Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(feature = "watch")]
+impl TrustWatcher {
+    /// Watch `sources`, checking every `interval`
+    pub fn spawn(
+        handle: TrustHandle,
+        sources: TrustSources,
+        interval: std::time::Duration,
+    ) -> Self {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let stamps = |sources: &TrustSources| -> Vec<Option<(std::time::SystemTime, u64)>> {
+            sources
+                .paths()
+                .into_iter()
+                .map(|path| {
+                    let metadata = std::fs::metadata(path).ok()?;
+                    Some((metadata.modified().ok()?, metadata.len()))
+                })
+                .collect()
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = std::thread::spawn(move || {
+            let mut seen = stamps(&sources);
+            while !stopped.load(Ordering::Relaxed) {
+                std::thread::sleep(interval);
+                let current = stamps(&sources);
+                if current != seen {
+                    seen = current;
+                    // Rejections are reported by the event; keep watching
+                    let _ = handle.reload(&sources);
+                }
+            }
+        });
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(feature = "watch")]
+impl Drop for TrustWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    struct Fixture {
+        dir: tempfile::TempDir,
+        committee: Vec<GovernanceKeypair>,
+    }
+
+    fn keypairs(n: usize) -> Vec<GovernanceKeypair> {
+        (0..n)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect()
+    }
+
+    fn committee_body(threshold: &str, keys: &[GovernanceKeypair]) -> Value {
+        serde_json::to_value(CommitteeBody {
+            threshold: threshold.parse().unwrap(),
+            public_keys: keys.iter().map(|k| k.public_key().to_string()).collect(),
+            nested: None,
+        })
+        .unwrap()
+    }
+
+    fn revocations_body(keys: &[PublicKey]) -> Value {
+        serde_json::to_value(RevocationsBody {
+            revoked_keys: keys.iter().map(PublicKey::to_string).collect(),
+        })
+        .unwrap()
+    }
+
+    fn write(path: &Path, document: &SignedTrustDocument) {
+        std::fs::write(path, serde_json::to_string(document).unwrap()).unwrap();
+    }
+
+    impl Fixture {
+        /// A 2-of-3 committee with no revocations
+        fn new() -> Self {
+            let fixture = Self {
+                dir: tempfile::tempdir().unwrap(),
+                committee: keypairs(3),
+            };
+            let signers: Vec<_> = fixture.committee.iter().collect();
+            let sources = fixture.sources();
+            let body = committee_body("2-of-3", &fixture.committee);
+            write(
+                &sources.committee,
+                &SignedTrustDocument::sign(COMMITTEE_KIND, 1, body, &signers).unwrap(),
+            );
+            write(
+                &sources.revocations,
+                &SignedTrustDocument::sign(REVOCATIONS_KIND, 1, revocations_body(&[]), &signers)
+                    .unwrap(),
+            );
+            fixture
+        }
+
+        fn sources(&self) -> TrustSources {
+            TrustSources::in_dir(self.dir.path())
+        }
+
+        fn signers(&self) -> Vec<&GovernanceKeypair> {
+            self.committee[..2].iter().collect()
+        }
+
+        fn handle(&self) -> TrustHandle {
+            TrustHandle::new(TrustStore::open(&self.sources()).unwrap())
+        }
+    }
+
+    fn sign_all(keypairs: &[GovernanceKeypair], message: &[u8]) -> Vec<Signature> {
+        keypairs
+            .iter()
+            .map(|k| crate::sign_message(k.secret_key.expose(), message).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_hot_swap_seen_on_next_call() {
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        let successor = keypairs(2);
+        let message = b"release v1.2.3";
+        let signatures = sign_all(&successor, message);
+
+        let (to_verifier, calls) = mpsc::channel::<()>();
+        let (results, from_verifier) = mpsc::channel();
+        let verifier = {
+            let handle = handle.clone();
+            let signatures = signatures.clone();
+            std::thread::spawn(move || {
+                for () in calls {
+                    let store = handle.load();
+                    let verified = store.verify(message, &signatures).unwrap();
+                    results.send((store.generation(), verified)).unwrap();
+                }
+            })
+        };
+
+        to_verifier.send(()).unwrap();
+        assert_eq!(from_verifier.recv().unwrap(), (0, false));
+
+        // Rotate to a 2-of-2 committee of new keys, signed by the old one
+        let body = committee_body("2-of-2", &successor);
+        let document =
+            SignedTrustDocument::sign(COMMITTEE_KIND, 2, body, &fixture.signers()).unwrap();
+        write(&fixture.sources().committee, &document);
+        let changes = handle.reload(&fixture.sources()).unwrap();
+        assert!(matches!(changes[..], [TrustChange::Committee { .. }]));
+
+        to_verifier.send(()).unwrap();
+        assert_eq!(from_verifier.recv().unwrap(), (1, true));
+        drop(to_verifier);
+        verifier.join().unwrap();
+    }
+
+    #[test]
+    fn test_partially_invalid_reload_keeps_active_store() {
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        let before = handle.load();
+
+        // A valid committee rotation, but a revocation list signed by outsiders
+        let successor = keypairs(2);
+        let body = committee_body("2-of-2", &successor);
+        let document =
+            SignedTrustDocument::sign(COMMITTEE_KIND, 2, body, &fixture.signers()).unwrap();
+        write(&fixture.sources().committee, &document);
+        let revoked = fixture.committee[2].public_key();
+        let outsiders = keypairs(2);
+        let document = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            2,
+            revocations_body(std::slice::from_ref(&revoked)),
+            &outsiders.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+        write(&fixture.sources().revocations, &document);
+
+        assert!(handle.reload(&fixture.sources()).is_err());
+        let after = handle.load();
+        assert!(Arc::ptr_eq(&before, &after));
+        assert_eq!(after.generation(), 0);
+        assert_eq!(after.multisig().total(), 3);
+        assert!(!after.is_revoked(&revoked));
+
+        // An unparseable policy rejects otherwise valid documents too
+        let signers = fixture.signers();
+        let document =
+            SignedTrustDocument::sign(REVOCATIONS_KIND, 2, revocations_body(&[]), &signers)
+                .unwrap();
+        write(&fixture.sources().revocations, &document);
+        std::fs::write(fixture.dir.path().join("policy.toml"), "stages = 7").unwrap();
+        assert!(handle.reload(&fixture.sources()).is_err());
+        assert!(Arc::ptr_eq(&before, &handle.load()));
+    }
+
+    #[test]
+    fn test_rollback_rejected() {
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        let document = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            0,
+            revocations_body(&[]),
+            &fixture.signers(),
+        )
+        .unwrap();
+        write(&fixture.sources().revocations, &document);
+        assert!(handle.reload(&fixture.sources()).is_err());
+
+        // The same material again is accepted, with nothing to swap
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        assert!(handle.reload(&fixture.sources()).unwrap().is_empty());
+        assert_eq!(handle.load().generation(), 0);
+    }
+
+    #[test]
+    fn test_revoked_signers_do_not_count() {
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        let message = b"release v1.2.3";
+        let signatures = sign_all(&fixture.committee[..2], message);
+        assert!(handle.load().verify(message, &signatures).unwrap());
+
+        let revoked = fixture.committee[0].public_key();
+        let mut store = (*handle.load()).clone();
+        store.revoked.push(revoked);
+        assert!(!store.verify(message, &signatures).unwrap());
+    }
+
+    #[test]
+    fn test_revoked_signers_cannot_reload() {
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        let revoked = fixture.committee[0].public_key();
+        let revoking = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            2,
+            revocations_body(std::slice::from_ref(&revoked)),
+            &fixture.signers(),
+        )
+        .unwrap();
+        write(&fixture.sources().revocations, &revoking);
+        handle.reload(&fixture.sources()).unwrap();
+        assert!(handle.load().is_revoked(&revoked));
+
+        // The revoked key and one other reach 2-of-3 only if the revoked
+        // key counts, so neither lifting the revocation nor replacing the
+        // committee is accepted
+        let document = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            3,
+            revocations_body(&[]),
+            &fixture.signers(),
+        )
+        .unwrap();
+        write(&fixture.sources().revocations, &document);
+        assert!(matches!(
+            handle.reload(&fixture.sources()),
+            Err(GovernanceError::SignatureVerification(_))
+        ));
+        assert!(handle.load().is_revoked(&revoked));
+
+        write(&fixture.sources().revocations, &revoking);
+        let body = committee_body("2-of-2", &keypairs(2));
+        let document =
+            SignedTrustDocument::sign(COMMITTEE_KIND, 2, body, &fixture.signers()).unwrap();
+        write(&fixture.sources().committee, &document);
+        assert!(handle.reload(&fixture.sources()).is_err());
+        assert_eq!(handle.load().multisig().total(), 3);
+
+        // The two keys still in good standing can
+        let document = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            3,
+            revocations_body(&[]),
+            &fixture.committee[1..].iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+        write(&fixture.sources().revocations, &document);
+        let committee = SignedTrustDocument::sign(
+            COMMITTEE_KIND,
+            1,
+            committee_body("2-of-3", &fixture.committee),
+            &fixture.committee[1..].iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+        write(&fixture.sources().committee, &committee);
+        handle.reload(&fixture.sources()).unwrap();
+        assert!(!handle.load().is_revoked(&revoked));
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_reload_event() {
+        use crate::governance::events::{self, GovernanceEvent};
+
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        let mut receiver = events::subscribe();
+
+        let revoked = keypairs(1)[0].public_key();
+        let document = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            2,
+            revocations_body(std::slice::from_ref(&revoked)),
+            &fixture.signers(),
+        )
+        .unwrap();
+        write(&fixture.sources().revocations, &document);
+        std::fs::write(
+            fixture.dir.path().join("policy.toml"),
+            "max_signature_age = \"90d\"",
+        )
+        .unwrap();
+        handle.reload(&fixture.sources()).unwrap();
+
+        let expected = GovernanceEvent::TrustStoreReloaded {
+            generation: 1,
+            changes: vec![
+                TrustChange::Revocations {
+                    added: vec![revoked],
+                    removed: vec![],
+                },
+                TrustChange::Policy,
+            ],
+        };
+        // Other tests emit events concurrently; look for ours
+        while let Ok(event) = receiver.try_recv() {
+            if event == expected {
+                return;
+            }
+        }
+        panic!("no TrustStoreReloaded event for the reload");
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watcher_reloads_on_change() {
+        let fixture = Fixture::new();
+        let handle = fixture.handle();
+        let _watcher = TrustWatcher::spawn(
+            handle.clone(),
+            fixture.sources(),
+            std::time::Duration::from_millis(10),
+        );
+
+        let outsiders = keypairs(2);
+        let document = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            2,
+            revocations_body(&[fixture.committee[2].public_key()]),
+            &outsiders.iter().collect::<Vec<_>>(),
+        )
+        .unwrap();
+        // A list not signed by the committee is rejected; then a valid one
+        // is picked up
+        write(&fixture.sources().revocations, &document);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(handle.load().generation(), 0);
+
+        let document = SignedTrustDocument::sign(
+            REVOCATIONS_KIND,
+            3,
+            revocations_body(&[keypairs(1)[0].public_key()]),
+            &fixture.signers(),
+        )
+        .unwrap();
+        write(&fixture.sources().revocations, &document);
+        for _ in 0..200 {
+            if handle.load().generation() == 1 {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("watcher did not reload");
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::governance::error::{GovernanceError, GovernanceResult};
 use crate::governance::{PublicKey, Signature};
+use rayon::prelude::*;
 
 /// Verify a signature against a message and public key
 pub fn verify_signature(
@@ -20,9 +21,9 @@ pub fn verify_signature_hash(
     message_hash: &[u8],
     public_key: &PublicKey,
 ) -> GovernanceResult<bool> {
-    use secp256k1::{Message, Secp256k1};
+    use secp256k1::Message;
 
-    let secp = Secp256k1::new();
+    let secp = crate::governance::context::verification_context();
 
     let message = Message::from_digest_slice(message_hash)
         .map_err(|e| GovernanceError::Cryptographic(format!("Invalid message hash: {}", e)))?;
@@ -54,6 +55,52 @@ pub fn verify_multiple_signatures(
     Ok(results)
 }
 
+/// Verify many independent `(signature, message, key)` triples in parallel
+///
+/// Results are in input order, as [`verify_signature`] would give them one
+/// by one; the first error fails the whole batch.
+pub fn verify_signatures_batch(
+    pairs: &[(&Signature, &[u8], &PublicKey)],
+) -> GovernanceResult<Vec<bool>> {
+    pairs
+        .par_iter()
+        .map(|(signature, message, public_key)| verify_signature(signature, message, public_key))
+        .collect()
+}
+
+/// Whether every `(signature, message, key)` item verifies
+///
+/// libsecp256k1 has no batch verification for ECDSA, so the items are
+/// verified independently on the `rayon` pool: O(n) verifications for n
+/// items, spread over the threads, and every item is checked even after one
+/// fails. Use [`batch_verify_fail_fast`] to stop at the first failure. The
+/// result is the one the items would give verified one by one; the first
+/// error fails the whole batch.
+pub fn batch_verify(items: &[(Signature, &[u8], PublicKey)]) -> GovernanceResult<bool> {
+    let results: Vec<bool> = items
+        .par_iter()
+        .map(|(signature, message, public_key)| verify_signature(signature, message, public_key))
+        .collect::<GovernanceResult<_>>()?;
+    Ok(results.into_iter().all(|valid| valid))
+}
+
+/// Like [`batch_verify`], stopping at the first item that fails
+///
+/// Still O(n) in the worst case, but a batch with an invalid signature is
+/// rejected as soon as any thread finds one. Which failure is found first
+/// is not specified, so an error may be returned where [`batch_verify`]
+/// returns `Ok(false)`, or the other way round.
+pub fn batch_verify_fail_fast(items: &[(Signature, &[u8], PublicKey)]) -> GovernanceResult<bool> {
+    let failure = items
+        .par_iter()
+        .map(|(signature, message, public_key)| verify_signature(signature, message, public_key))
+        .find_any(|result| !matches!(result, Ok(true)));
+    match failure {
+        None => Ok(true),
+        Some(result) => result,
+    }
+}
+
 /// Verify a signature against a specific public key
 pub fn verify_signature_with_key(
     signature: &Signature,
@@ -74,7 +121,7 @@ mod tests {
         let keypair = GovernanceKeypair::generate().unwrap();
         let message = b"test message";
 
-        let signature = crate::sign_message(&keypair.secret_key, message).unwrap();
+        let signature = crate::sign_message(keypair.secret_key.expose(), message).unwrap();
         let verified = verify_signature(&signature, message, &keypair.public_key()).unwrap();
 
         assert!(verified);
@@ -86,7 +133,7 @@ mod tests {
         let message = b"test message";
         let message_hash = sha2::Sha256::digest(message);
 
-        let signature = crate::sign_message(&keypair.secret_key, message).unwrap();
+        let signature = crate::sign_message(keypair.secret_key.expose(), message).unwrap();
         let verified =
             verify_signature_hash(&signature, &message_hash, &keypair.public_key()).unwrap();
 
@@ -103,7 +150,7 @@ mod tests {
 
         let signatures: Vec<_> = keypairs
             .iter()
-            .map(|kp| crate::sign_message(&kp.secret_key, message).unwrap())
+            .map(|kp| crate::sign_message(kp.secret_key.expose(), message).unwrap())
             .collect();
 
         let results = verify_multiple_signatures(&signatures, message, &public_keys).unwrap();
@@ -112,13 +159,95 @@ mod tests {
         assert!(results.iter().all(|&verified| verified));
     }
 
+    #[test]
+    fn test_verify_signatures_batch() {
+        let keypairs: Vec<_> = (0..4)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let public_keys: Vec<_> = keypairs.iter().map(|kp| kp.public_key()).collect();
+        let messages: Vec<Vec<u8>> = (0..64)
+            .map(|i| format!("message {}", i).into_bytes())
+            .collect();
+        let signatures: Vec<_> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| {
+                crate::sign_message(keypairs[i % 4].secret_key.expose(), message).unwrap()
+            })
+            .collect();
+
+        // Every third triple pairs the signature with the wrong key
+        let pairs: Vec<_> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| {
+                let key = if i % 3 == 0 { (i + 1) % 4 } else { i % 4 };
+                (&signatures[i], message.as_slice(), &public_keys[key])
+            })
+            .collect();
+        let serial: Vec<bool> = pairs
+            .iter()
+            .map(|(signature, message, key)| verify_signature(signature, message, key).unwrap())
+            .collect();
+
+        let batch = verify_signatures_batch(&pairs).unwrap();
+        assert_eq!(batch, serial);
+        assert_eq!(batch.iter().filter(|&&valid| !valid).count(), 22);
+        assert!(verify_signatures_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_batch_verify_500() {
+        let keypairs: Vec<_> = (0..10)
+            .map(|_| GovernanceKeypair::generate().unwrap())
+            .collect();
+        let messages: Vec<Vec<u8>> = (0..500)
+            .map(|i| format!("release:v1.0.{}:abc123", i).into_bytes())
+            .collect();
+        let mut items: Vec<_> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| {
+                let keypair = &keypairs[i % 10];
+                (
+                    crate::sign_message(keypair.secret_key.expose(), message).unwrap(),
+                    message.as_slice(),
+                    keypair.public_key(),
+                )
+            })
+            .collect();
+        let sequential = |items: &[(Signature, &[u8], PublicKey)]| {
+            items
+                .iter()
+                .all(|(signature, message, key)| verify_signature(signature, message, key).unwrap())
+        };
+
+        assert!(sequential(&items));
+        assert!(batch_verify(&items).unwrap());
+        assert!(batch_verify_fail_fast(&items).unwrap());
+
+        // Mixed valid and invalid: a wrong key, then a signature moved to
+        // another message
+        items[137].2 = keypairs[0].public_key();
+        items[400].0 = items[401].0.clone();
+        assert!(!sequential(&items));
+        assert!(!batch_verify(&items).unwrap());
+        assert!(!batch_verify_fail_fast(&items).unwrap());
+
+        for valid in 0..3 {
+            let items = &items[..valid];
+            assert!(batch_verify(items).unwrap());
+            assert_eq!(batch_verify_fail_fast(items).unwrap(), sequential(items));
+        }
+    }
+
     #[test]
     fn test_verify_signature_with_wrong_key() {
         let keypair1 = GovernanceKeypair::generate().unwrap();
         let keypair2 = GovernanceKeypair::generate().unwrap();
         let message = b"test message";
 
-        let signature = crate::sign_message(&keypair1.secret_key, message).unwrap();
+        let signature = crate::sign_message(keypair1.secret_key.expose(), message).unwrap();
         let verified = verify_signature(&signature, message, &keypair2.public_key()).unwrap();
 
         assert!(!verified);
@@ -30,11 +30,12 @@
 //! ## Quick Start
 //!
 //! ```rust
-//! use bllvm_sdk::{
+//! # #[cfg(feature = "signing")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use blvm_sdk::{
 //!     GovernanceKeypair, GovernanceMessage, Multisig, sign_message
 //! };
 //!
-//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! // Generate a keypair
 //! let keypair = GovernanceKeypair::generate()?;
 //!
@@ -45,7 +46,7 @@
 //! };
 //!
 //! // Sign the message
-//! let signature = sign_message(&keypair.secret_key, &message.to_signing_bytes())?;
+//! let signature = sign_message(keypair.secret_key.expose(), &message.to_signing_bytes())?;
 //!
 //! // Verify with multisig (example with 1-of-1)
 //! let maintainer_keys = vec![keypair.public_key()];
@@ -54,21 +55,83 @@
 //! assert!(valid);
 //! # Ok(())
 //! # }
+//! # #[cfg(not(feature = "signing"))]
+//! # fn main() {}
 //! ```
+//!
+//! ## Verify-Only Builds
+//!
+//! Signing is the default `signing` feature. Built with
+//! `--no-default-features` (adding back `events` or other features as
+//! needed), the SDK cannot sign: `sign_message`, the keypair types, the
+//! other signing constructors, BIP32/BIP39/BIP44 key derivation, the signing
+//! journal and the `blvm-keygen`, `blvm-sign` and `blvm-sign-binary` tools
+//! are compiled out. Public keys, signature parsing, multisig and nested
+//! verification, message encoding and the verification tools remain.
+
+/// Items compiled out of verify-only builds; each of these fails to compile
+/// without the `signing` feature
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::sign_message;
+/// ```
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::governance::GovernanceKeypair::generate;
+/// ```
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::governance::ed25519::Ed25519Keypair::generate;
+/// ```
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::governance::SignedTimestamp::sign;
+/// ```
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::governance::SignedAttributes::sign;
+/// ```
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::governance::SignedPayload::sign;
+/// ```
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::governance::bip32::derive_master_key;
+/// ```
+///
+/// ```compile_fail
+/// let _ = blvm_sdk::governance::journal::SigningJournal::open;
+/// ```
+///
+/// while verification is still there:
+///
+/// ```
+/// let _ = blvm_sdk::verify_signature;
+/// let _ = blvm_sdk::governance::Multisig::verify;
+/// let _ = blvm_sdk::governance::nested_multisig::NestedMultisig::verify_packets;
+/// let _ = blvm_sdk::governance::SignedTimestamp::verify;
+/// ```
+#[cfg(all(doctest, not(feature = "signing")))]
+pub struct VerifyOnlyBuild;
 
 pub mod cli;
 pub mod composition;
 pub mod governance;
 pub mod module;
+pub mod util;
 
 // Re-export main types for convenience
+#[cfg(any(feature = "signing", test))]
+pub use governance::GovernanceKeypair;
 pub use governance::{
-    GovernanceError, GovernanceKeypair, GovernanceMessage, GovernanceResult, Multisig, PublicKey,
-    Signature,
+    GovernanceError, GovernanceMessage, GovernanceResult, Multisig, PublicKey, Signature,
 };
 
 // Re-export governance functions
-pub use governance::signatures::{sign_message, verify_signature};
+#[cfg(any(feature = "signing", test))]
+pub use governance::signatures::sign_message;
+pub use governance::signatures::verify_signature;
 
 // Re-export composition framework
 pub use composition::{
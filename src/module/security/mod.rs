@@ -7,4 +7,4 @@
 
 pub mod permissions;
 
-pub use permissions::{Permission, PermissionSet};
+pub use permissions::{permissions_from_capabilities, Permission, PermissionSet};
@@ -4,4 +4,18 @@
 //!
 //! Permission types and sets for module access control.
 
-pub use blvm_node::module::security::permissions::{Permission, PermissionSet};
+pub use blvm_node::module::security::permissions::{
+    parse_permission_string, Permission, PermissionSet,
+};
+
+/// Build the permission set named by a module's declared capabilities
+///
+/// Capabilities that do not name a permission are ignored.
+pub fn permissions_from_capabilities(capabilities: &[String]) -> PermissionSet {
+    PermissionSet::from_vec(
+        capabilities
+            .iter()
+            .filter_map(|capability| parse_permission_string(capability))
+            .collect(),
+    )
+}
@@ -0,0 +1,199 @@
+//! # Cancellation
+//!
+//! A cheap, cloneable flag for cancelling long-running operations.
+//!
+//! Operations that accept a [`CancelToken`] check it only at documented
+//! cancellation points and unwind to a consistent state before returning, so
+//! tripping the token never leaves work half-done. Clones share the same
+//! flag; once cancelled, a token stays cancelled.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::Notify;
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// Shared cancellation flag
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<Inner>);
+
+static INTERRUPT: OnceLock<CancelToken> = OnceLock::new();
+
+impl CancelToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation, waking every task in [`cancelled`](Self::cancelled)
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    /// Whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// `Interrupted` error if cancellation was requested
+    ///
+    /// For cancellation points in code that returns `io::Result`.
+    pub fn check(&self, what: &str) -> io::Result<()> {
+        if self.is_cancelled() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                format!("{} cancelled", what),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Wait until cancellation is requested
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            tokio::pin!(notified);
+            // Register before checking the flag so a cancel in between wakes us
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Token cancelled by Ctrl-C (SIGINT)
+    ///
+    /// The first Ctrl-C prints `cancelling…` to stderr and trips the token;
+    /// the default action is restored, so a second Ctrl-C terminates the
+    /// process. The handler is installed on first use; later calls return
+    /// the same token.
+    #[cfg(unix)]
+    pub fn on_interrupt() -> io::Result<Self> {
+        use std::io::{Read, Write};
+        use std::os::fd::FromRawFd;
+        use std::sync::atomic::AtomicI32;
+
+        // Write end of the pipe the handler wakes the watcher thread through
+        static WAKE_FD: AtomicI32 = AtomicI32::new(-1);
+
+        // Only async-signal-safe calls: signal(2) and write(2)
+        extern "C" fn handle_interrupt(_: libc::c_int) {
+            // SAFETY: restoring the default disposition is async-signal-safe.
+            unsafe { libc::signal(libc::SIGINT, libc::SIG_DFL) };
+            let fd = WAKE_FD.load(Ordering::SeqCst);
+            if fd >= 0 {
+                // SAFETY: writes one byte from a live stack buffer.
+                unsafe { libc::write(fd, [1u8].as_ptr().cast(), 1) };
+            }
+        }
+
+        if let Some(token) = INTERRUPT.get() {
+            return Ok(token.clone());
+        }
+
+        let mut fds = [0; 2];
+        // SAFETY: `fds` has room for the two descriptors pipe(2) returns.
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: both descriptors were just created and are owned here.
+        let (mut wake_rx, wake_tx) = unsafe {
+            (
+                std::fs::File::from_raw_fd(fds[0]),
+                std::fs::File::from_raw_fd(fds[1]),
+            )
+        };
+
+        let token = INTERRUPT.get_or_init(CancelToken::new).clone();
+        let watched = token.clone();
+        std::thread::Builder::new()
+            .name("interrupt".to_string())
+            .spawn(move || {
+                if wake_rx.read(&mut [0u8]).is_ok_and(|n| n == 1) {
+                    let _ = writeln!(io::stderr(), "cancelling…");
+                    watched.cancel();
+                }
+            })?;
+
+        // The write end stays open for the life of the process
+        WAKE_FD.store(
+            std::os::fd::IntoRawFd::into_raw_fd(wake_tx),
+            Ordering::SeqCst,
+        );
+        let handler = handle_interrupt as extern "C" fn(libc::c_int);
+        // SAFETY: the handler only makes async-signal-safe calls.
+        if unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) } == libc::SIG_ERR {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(token)
+    }
+
+    /// Token cancelled by Ctrl-C (unsupported on this platform)
+    #[cfg(not(unix))]
+    pub fn on_interrupt() -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Ctrl-C cancellation is not supported on this platform",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_clones_share_cancellation() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+
+        let waiter = tokio::spawn(async move { clone.cancelled().await });
+        token.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(token.is_cancelled());
+    }
+}
+
+#[cfg(all(test, unix))]
+mod interrupt_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_interrupt_trips_token_once() {
+        let token = CancelToken::on_interrupt().unwrap();
+        assert!(Arc::ptr_eq(
+            &CancelToken::on_interrupt().unwrap().0,
+            &token.0
+        ));
+
+        let waiter = tokio::spawn({
+            let token = token.clone();
+            async move { token.cancelled().await }
+        });
+        // SAFETY: raising SIGINT runs the installed handler in this process.
+        unsafe { libc::raise(libc::SIGINT) };
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(token.is_cancelled());
+        assert!(token.check("hashing").is_err());
+
+        // The default action is back for a second Ctrl-C
+        // SAFETY: querying the disposition with a null new action changes nothing.
+        let mut current: libc::sigaction = unsafe { std::mem::zeroed() };
+        unsafe { libc::sigaction(libc::SIGINT, std::ptr::null(), &mut current) };
+        assert_eq!(current.sa_sigaction, libc::SIG_DFL);
+    }
+}
@@ -0,0 +1,209 @@
+//! # File IO
+//!
+//! Crash-safe replacement of files that hold state, and reads that ride out
+//! transient errors.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Attempts of [`read_with_retry`] before a transient error is returned
+const READ_ATTEMPTS: u32 = 4;
+
+/// Backoff before the first retry; doubled after each failed attempt
+const READ_INITIAL_BACKOFF: Duration = Duration::from_millis(20);
+
+/// Replace a file's contents atomically
+///
+/// The contents are written and synced to a temporary file in the same
+/// directory, which is then renamed over `path`, so readers see either the
+/// old or the new contents and an interrupted write leaves the old file
+/// intact. Missing parent directories are created.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(contents))
+}
+
+/// Replace a file atomically with contents streamed by `write`
+///
+/// Like [`write_atomic`]. `path` may be read while `write` runs: it is only
+/// replaced once `write` succeeds, and left untouched if it fails.
+pub fn write_atomic_with<T, E>(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> Result<T, E>,
+) -> Result<T, E>
+where
+    E: From<io::Error>,
+{
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let temp_path = parent.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = File::create(&temp_path)
+        .map_err(E::from)
+        .and_then(|mut file| {
+            let written = write(&mut file)?;
+            file.sync_all()?;
+            Ok(written)
+        })
+        .and_then(|written| {
+            fs::rename(&temp_path, path)?;
+            Ok(written)
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Whether an IO error may go away when the operation is retried
+///
+/// `Interrupted` and `WouldBlock` are transient; everything else, such as
+/// `NotFound` or `PermissionDenied`, is permanent.
+pub fn is_transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock
+    )
+}
+
+/// Read everything from a reader, retrying transient errors
+///
+/// Network filesystems can fail a read with an error that succeeds when
+/// retried. On a [transient](is_transient) error the read starts over from
+/// a reader freshly returned by `open`, a few times with a short backoff;
+/// permanent errors are returned at once.
+pub fn read_with_retry<R: Read>(mut open: impl FnMut() -> io::Result<R>) -> io::Result<Vec<u8>> {
+    let mut backoff = READ_INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let mut contents = Vec::new();
+        match open().and_then(|mut reader| reader.read_to_end(&mut contents)) {
+            Ok(_) => return Ok(contents),
+            Err(e) if is_transient(&e) && attempt < READ_ATTEMPTS => {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Read a file, retrying transient errors (see [`read_with_retry`])
+pub fn read_file_with_retry(path: &Path) -> io::Result<Vec<u8>> {
+    read_with_retry(|| File::open(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_write_atomic_replaces_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("state.json");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        let leftovers: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, vec!["state.json"]);
+    }
+
+    #[test]
+    fn test_write_atomic_with_keeps_file_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aggregate.json");
+        write_atomic(&path, b"old").unwrap();
+
+        // The old contents stay readable while the new ones are written
+        let copied = write_atomic_with(&path, |file| {
+            let old = fs::read(&path)?;
+            file.write_all(&old)?;
+            file.write_all(b" and new")?;
+            Ok::<_, io::Error>(old.len())
+        })
+        .unwrap();
+        assert_eq!(copied, 3);
+        assert_eq!(fs::read(&path).unwrap(), b"old and new");
+
+        let failed = write_atomic_with(&path, |file| {
+            file.write_all(b"partial")?;
+            Err::<(), _>(io::Error::other("interrupted"))
+        });
+        assert!(failed.is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"old and new");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    /// Reads `contents`, failing while `failures` is above zero
+    struct FlakyReader<'a> {
+        contents: &'a [u8],
+        failures: &'a Cell<u32>,
+    }
+
+    impl Read for FlakyReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.failures.get() > 0 {
+                self.failures.set(self.failures.get() - 1);
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            self.contents.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_read_with_retry() {
+        let failures = Cell::new(2);
+        let mut opens = 0;
+        let contents = read_with_retry(|| {
+            opens += 1;
+            Ok(FlakyReader {
+                contents: b"signature",
+                failures: &failures,
+            })
+        })
+        .unwrap();
+        assert_eq!(contents, b"signature");
+        assert_eq!(opens, 3);
+
+        // Permanent errors are not retried
+        let mut opens = 0;
+        let err = read_with_retry(|| -> io::Result<&[u8]> {
+            opens += 1;
+            Err(io::ErrorKind::NotFound.into())
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(opens, 1);
+
+        // Retries are bounded
+        let failures = Cell::new(u32::MAX);
+        let err = read_with_retry(|| {
+            Ok(FlakyReader {
+                contents: b"",
+                failures: &failures,
+            })
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        assert_eq!(failures.get(), u32::MAX - READ_ATTEMPTS);
+    }
+}
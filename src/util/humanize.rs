@@ -0,0 +1,366 @@
+//! # Humanization
+//!
+//! Parsing and human-readable formatting of durations, timestamps and amounts.
+//!
+//! Policy files, composition specs and CLI flags parse durations and
+//! timestamps here. Text output uses the formatters; JSON output keeps
+//! machine formats (RFC3339 strings, integer satoshis, seconds).
+
+use chrono::{DateTime, FixedOffset};
+use std::time::{Duration, SystemTime};
+
+/// Duration and timestamp parsing errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HumanizeError {
+    #[error("Invalid format: {0}")]
+    InvalidFormat(String),
+    #[error("Invalid value: {0}")]
+    InvalidValue(String),
+}
+
+/// Satoshis per bitcoin
+pub const SATS_PER_BTC: u64 = 100_000_000;
+
+/// Duration units, largest first, with their length in seconds
+const DURATION_UNITS: [(char, u64); 5] = [
+    ('w', 7 * 86_400),
+    ('d', 86_400),
+    ('h', 3_600),
+    ('m', 60),
+    ('s', 1),
+];
+
+/// Parse a duration such as `30d`, `2h15m` or `90s`
+///
+/// A duration is one or more `<number><unit>` components with units
+/// `w`, `d`, `h`, `m`, `s`, each used at most once and in descending order.
+pub fn parse_duration(value: &str) -> Result<Duration, HumanizeError> {
+    if value.is_empty() {
+        return Err(HumanizeError::InvalidFormat(
+            "Duration cannot be empty".to_string(),
+        ));
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    let mut last_unit: Option<usize> = None;
+
+    for (pos, c) in value.char_indices() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit_index = DURATION_UNITS
+            .iter()
+            .position(|(u, _)| *u == c)
+            .ok_or_else(|| {
+                HumanizeError::InvalidFormat(format!(
+                    "Invalid duration '{}': unknown unit '{}' at position {}",
+                    value, c, pos
+                ))
+            })?;
+
+        if number.is_empty() {
+            return Err(HumanizeError::InvalidFormat(format!(
+                "Invalid duration '{}': missing number before '{}' at position {}",
+                value, c, pos
+            )));
+        }
+
+        if last_unit.is_some_and(|last| unit_index <= last) {
+            return Err(HumanizeError::InvalidFormat(format!(
+                "Invalid duration '{}': unit '{}' at position {} out of order",
+                value, c, pos
+            )));
+        }
+
+        let amount: u64 = number.parse().map_err(|e| {
+            HumanizeError::InvalidValue(format!("Invalid duration '{}': {}", value, e))
+        })?;
+        total = amount
+            .checked_mul(DURATION_UNITS[unit_index].1)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| {
+                HumanizeError::InvalidValue(format!("Duration '{}' is too large", value))
+            })?;
+
+        number.clear();
+        last_unit = Some(unit_index);
+    }
+
+    if !number.is_empty() {
+        return Err(HumanizeError::InvalidFormat(format!(
+            "Invalid duration '{}': missing unit at position {}",
+            value,
+            value.len()
+        )));
+    }
+
+    Ok(Duration::from_secs(total))
+}
+
+/// Format a duration in the compact form accepted by [`parse_duration`]
+pub fn format_duration(duration: Duration) -> String {
+    let mut remaining = duration.as_secs();
+    if remaining == 0 {
+        return "0s".to_string();
+    }
+
+    let mut output = String::new();
+    for (unit, secs) in DURATION_UNITS {
+        if remaining >= secs {
+            output.push_str(&format!("{}{}", remaining / secs, unit));
+            remaining %= secs;
+        }
+    }
+    output
+}
+
+/// Parse a strict RFC3339 timestamp
+///
+/// The UTC offset (`Z` or `+HH:MM`) is required. Errors name the position of
+/// the first offending character.
+pub fn parse_timestamp(value: &str) -> Result<DateTime<FixedOffset>, HumanizeError> {
+    let bytes = value.as_bytes();
+    let mut pos = 0;
+
+    let expect = |pos: usize, what: &str, ok: bool| -> Result<(), HumanizeError> {
+        if ok {
+            Ok(())
+        } else {
+            Err(HumanizeError::InvalidFormat(format!(
+                "Invalid timestamp '{}': expected {} at position {}",
+                value, what, pos
+            )))
+        }
+    };
+
+    // Date and time: YYYY-MM-DDTHH:MM:SS
+    for c in "dddd-dd-ddTdd:dd:dd".chars() {
+        let actual = bytes.get(pos).copied();
+        match c {
+            'd' => expect(pos, "digit", actual.is_some_and(|b| b.is_ascii_digit()))?,
+            'T' => expect(pos, "'T'", matches!(actual, Some(b'T') | Some(b't')))?,
+            sep => expect(pos, &format!("'{}'", sep), actual == Some(sep as u8))?,
+        }
+        pos += 1;
+    }
+
+    // Optional fractional seconds
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        expect(
+            pos,
+            "digit",
+            bytes.get(pos).is_some_and(|b| b.is_ascii_digit()),
+        )?;
+        while bytes.get(pos).is_some_and(|b| b.is_ascii_digit()) {
+            pos += 1;
+        }
+    }
+
+    // Mandatory offset
+    match bytes.get(pos) {
+        Some(b'Z') | Some(b'z') => pos += 1,
+        Some(b'+') | Some(b'-') => {
+            pos += 1;
+            for c in "dd:dd".chars() {
+                let actual = bytes.get(pos).copied();
+                match c {
+                    'd' => expect(pos, "digit", actual.is_some_and(|b| b.is_ascii_digit()))?,
+                    sep => expect(pos, &format!("'{}'", sep), actual == Some(sep as u8))?,
+                }
+                pos += 1;
+            }
+        }
+        _ => expect(pos, "UTC offset ('Z' or '+HH:MM')", false)?,
+    }
+
+    expect(pos, "end of timestamp", pos == bytes.len())?;
+
+    DateTime::parse_from_rfc3339(value)
+        .map_err(|e| HumanizeError::InvalidValue(format!("Invalid timestamp '{}': {}", value, e)))
+}
+
+/// Format how long ago a point in time was, relative to now
+pub fn format_age(time: SystemTime) -> String {
+    format_age_at(time, SystemTime::now())
+}
+
+/// Format how long ago `time` was, relative to `now` (e.g. "3 days ago")
+pub fn format_age_at(time: SystemTime, now: SystemTime) -> String {
+    let (elapsed, future) = match now.duration_since(time) {
+        Ok(elapsed) => (elapsed, false),
+        Err(e) => (e.duration(), true),
+    };
+
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        return "just now".to_string();
+    }
+
+    let (amount, unit) = match secs {
+        s if s < 3_600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3_600, "hour"),
+        s if s < 30 * 86_400 => (s / 86_400, "day"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+
+    if future {
+        format!("in {} {}{}", amount, unit, plural)
+    } else {
+        format!("{} {}{} ago", amount, unit, plural)
+    }
+}
+
+/// Format a satoshi amount with thousands separators (e.g. "1,000,000 sat")
+pub fn format_sats(amount: u64) -> String {
+    let digits = amount.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && i % 3 == digits.len() % 3 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{} sat", grouped)
+}
+
+/// Format a satoshi amount in BTC with 8 decimal places (e.g. "0.01000000 BTC")
+pub fn format_btc(amount: u64) -> String {
+    format!("{}.{:08} BTC", amount / SATS_PER_BTC, amount % SATS_PER_BTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_accepted() {
+        let cases = [
+            ("90s", 90),
+            ("15m", 900),
+            ("2h", 7_200),
+            ("2h15m", 8_100),
+            ("30d", 2_592_000),
+            ("1w2d", 777_600),
+            ("1d2h3m4s", 93_784),
+            ("0s", 0),
+        ];
+        for (input, secs) in cases {
+            assert_eq!(
+                parse_duration(input).unwrap(),
+                Duration::from_secs(secs),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_rejected() {
+        let cases = [
+            "", "30", "d", "2x", "15m2h", "2h2h", "-1d", "1.5h", "2 h", "h15m",
+        ];
+        for input in cases {
+            assert!(parse_duration(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_duration_round_trip() {
+        let cases = [
+            ("90s", "1m30s"),
+            ("2h15m", "2h15m"),
+            ("30d", "4w2d"),
+            ("1w2d", "1w2d"),
+            ("1d2h3m4s", "1d2h3m4s"),
+            ("0s", "0s"),
+        ];
+        for (input, formatted) in cases {
+            let duration = parse_duration(input).unwrap();
+            assert_eq!(format_duration(duration), formatted);
+            assert_eq!(parse_duration(formatted).unwrap(), duration);
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp_accepted() {
+        let cases = [
+            "2024-01-15T10:30:00Z",
+            "2024-01-15t10:30:00z",
+            "2024-01-15T10:30:00.123Z",
+            "2024-01-15T10:30:00+02:00",
+            "2024-01-15T10:30:00-05:30",
+        ];
+        for input in cases {
+            assert!(parse_timestamp(input).is_ok(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp_rejected() {
+        let cases = [
+            ("2024-01-15T10:30:00", 19),
+            ("2024-01-15 10:30:00Z", 10),
+            ("2024-1-15T10:30:00Z", 6),
+            ("2024-01-15T10:30:00.Z", 20),
+            ("2024-01-15T10:30:00+0200", 22),
+            ("2024-01-15T10:30:00Zjunk", 20),
+        ];
+        for (input, pos) in cases {
+            let err = parse_timestamp(input).unwrap_err().to_string();
+            assert!(
+                err.contains(&format!("position {}", pos)),
+                "{}: {}",
+                input,
+                err
+            );
+        }
+
+        // Structurally valid but out of range
+        assert!(parse_timestamp("2024-13-15T10:30:00Z").is_err());
+    }
+
+    #[test]
+    fn test_timestamp_round_trip() {
+        let parsed = parse_timestamp("2024-01-15T10:30:00+02:00").unwrap();
+        assert_eq!(parse_timestamp(&parsed.to_rfc3339()).unwrap(), parsed);
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let cases = [
+            (0, "just now"),
+            (59, "just now"),
+            (60, "1 minute ago"),
+            (7_200, "2 hours ago"),
+            (3 * 86_400, "3 days ago"),
+            (60 * 86_400, "2 months ago"),
+            (400 * 86_400, "1 year ago"),
+        ];
+        for (secs, expected) in cases {
+            assert_eq!(
+                format_age_at(now - Duration::from_secs(secs), now),
+                expected
+            );
+        }
+        assert_eq!(
+            format_age_at(now + Duration::from_secs(7_200), now),
+            "in 2 hours"
+        );
+    }
+
+    #[test]
+    fn test_format_amounts() {
+        assert_eq!(format_sats(0), "0 sat");
+        assert_eq!(format_sats(999), "999 sat");
+        assert_eq!(format_sats(1_000_000), "1,000,000 sat");
+        assert_eq!(format_btc(1_000_000), "0.01000000 BTC");
+        assert_eq!(format_btc(150_000_000), "1.50000000 BTC");
+    }
+}
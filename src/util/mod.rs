@@ -0,0 +1,12 @@
+//! # Utilities
+//!
+//! Shared building blocks for the CLI tools and library APIs.
+
+pub mod cancel;
+pub mod fs;
+pub mod humanize;
+pub mod parallel;
+pub mod redact;
+
+pub use cancel::CancelToken;
+pub use redact::Redacted;
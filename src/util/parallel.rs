@@ -0,0 +1,540 @@
+//! # Parallel Scheduler
+//!
+//! Quota-aware scheduler for batch operations that hash many large files.
+//!
+//! Concurrency is limited both by worker count and by the total size of the
+//! jobs in flight, so a handful of multi-gigabyte files cannot saturate the
+//! disk. Jobs are started largest-first to minimize tail latency. A job larger
+//! than the byte cap is started once nothing else is in flight.
+//!
+//! Cancellation stops scheduling new jobs; jobs already running receive the
+//! [`CancelToken`] and may finish or abort early.
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+pub use crate::util::cancel::CancelToken;
+
+/// In-flight bytes allowed per worker by default
+const DEFAULT_BYTES_PER_WORKER: u64 = 256 * 1024 * 1024;
+
+/// How often a waiting scheduler re-checks cancellation
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Read buffer size used by [`hash_jobs`]
+const HASH_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Concurrency limits for [`run`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum number of jobs running at once
+    pub max_workers: usize,
+    /// Maximum total size of the jobs running at once
+    pub max_in_flight_bytes: u64,
+}
+
+impl Default for Limits {
+    /// One worker per available CPU, 256 MiB in flight per worker
+    fn default() -> Self {
+        let max_workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self {
+            max_workers,
+            max_in_flight_bytes: max_workers as u64 * DEFAULT_BYTES_PER_WORKER,
+        }
+    }
+}
+
+impl Limits {
+    /// Limits with optional overrides
+    ///
+    /// Unset values take their defaults; the default byte cap scales with
+    /// the worker count.
+    pub fn new(max_workers: Option<usize>, max_in_flight_bytes: Option<u64>) -> Self {
+        let max_workers = max_workers.unwrap_or_else(|| Self::default().max_workers);
+        Self {
+            max_workers,
+            max_in_flight_bytes: max_in_flight_bytes
+                .unwrap_or(max_workers as u64 * DEFAULT_BYTES_PER_WORKER),
+        }
+    }
+}
+
+/// Work passed to a job
+pub type JobFn<T> = Box<dyn FnOnce(&CancelToken) -> T + Send>;
+
+/// A unit of work with a known size
+pub struct Job<T> {
+    /// Name used in the report (usually the file path)
+    pub name: String,
+    /// Size counted against [`Limits::max_in_flight_bytes`]
+    pub bytes: u64,
+    /// The work itself
+    pub work: JobFn<T>,
+}
+
+impl<T> Job<T> {
+    /// Create a job
+    pub fn new<F>(name: impl Into<String>, bytes: u64, work: F) -> Self
+    where
+        F: FnOnce(&CancelToken) -> T + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            bytes,
+            work: Box::new(work),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Job<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Job")
+            .field("name", &self.name)
+            .field("bytes", &self.bytes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Result and timing of one job
+#[derive(Debug)]
+pub struct JobReport<T> {
+    /// Job name
+    pub name: String,
+    /// Job size
+    pub bytes: u64,
+    /// Output, or `None` if the job was never started
+    pub output: Option<T>,
+    /// Delay between the start of the run and the start of this job
+    pub started_after: Duration,
+    /// Time the job took to run
+    pub elapsed: Duration,
+}
+
+impl<T> JobReport<T> {
+    /// Timing as a JSON object (`name`, `bytes`, `started_after`, `elapsed`,
+    /// durations in seconds; `skipped` for jobs never started)
+    pub fn to_json(&self) -> serde_json::Value {
+        match self.output {
+            Some(_) => serde_json::json!({
+                "name": self.name,
+                "bytes": self.bytes,
+                "started_after": self.started_after.as_secs_f64(),
+                "elapsed": self.elapsed.as_secs_f64(),
+            }),
+            None => serde_json::json!({
+                "name": self.name,
+                "bytes": self.bytes,
+                "skipped": true,
+            }),
+        }
+    }
+}
+
+/// Final report of a [`run`]
+#[derive(Debug)]
+pub struct Report<T> {
+    /// Per-job results in the order the jobs were started, followed by
+    /// jobs skipped after cancellation
+    pub jobs: Vec<JobReport<T>>,
+    /// Whether scheduling stopped early because of cancellation
+    pub cancelled: bool,
+    /// Total wall-clock time
+    pub elapsed: Duration,
+}
+
+impl<T> Report<T> {
+    /// Number of jobs that were never started
+    pub fn skipped(&self) -> usize {
+        self.jobs.iter().filter(|j| j.output.is_none()).count()
+    }
+}
+
+impl<T> fmt::Display for Report<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for job in &self.jobs {
+            match job.output {
+                Some(_) => writeln!(
+                    f,
+                    "{:>12} bytes  {:>9.3}s  (started +{:.3}s)  {}",
+                    job.bytes,
+                    job.elapsed.as_secs_f64(),
+                    job.started_after.as_secs_f64(),
+                    job.name
+                )?,
+                None => writeln!(
+                    f,
+                    "{:>12} bytes  {:>10}  {}",
+                    job.bytes, "skipped", job.name
+                )?,
+            }
+        }
+        write!(
+            f,
+            "{} job(s) in {:.3}s",
+            self.jobs.len() - self.skipped(),
+            self.elapsed.as_secs_f64()
+        )?;
+        if self.cancelled {
+            write!(f, " (cancelled, {} skipped)", self.skipped())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct InFlight {
+    jobs: usize,
+    bytes: u64,
+}
+
+/// Run jobs under the given limits
+pub fn run<T: Send>(jobs: Vec<Job<T>>, limits: &Limits) -> Report<T> {
+    run_with_cancel(jobs, limits, &CancelToken::new())
+}
+
+/// Run jobs under the given limits until finished or cancelled
+pub fn run_with_cancel<T: Send>(
+    mut jobs: Vec<Job<T>>,
+    limits: &Limits,
+    cancel: &CancelToken,
+) -> Report<T> {
+    let max_workers = limits.max_workers.max(1);
+    let started = Instant::now();
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.bytes));
+
+    let in_flight = Mutex::new(InFlight::default());
+    let finished = Condvar::new();
+    let reports = Mutex::new(Vec::with_capacity(jobs.len()));
+    let mut skipped = Vec::new();
+
+    std::thread::scope(|scope| {
+        let mut pending = jobs.into_iter();
+        for job in pending.by_ref() {
+            let mut state = in_flight.lock().unwrap();
+            loop {
+                if cancel.is_cancelled() {
+                    break;
+                }
+                let over_bytes = state.bytes > 0
+                    && state.bytes.saturating_add(job.bytes) > limits.max_in_flight_bytes;
+                if state.jobs < max_workers && !over_bytes {
+                    break;
+                }
+                state = finished
+                    .wait_timeout(state, CANCEL_POLL_INTERVAL)
+                    .unwrap()
+                    .0;
+            }
+            if cancel.is_cancelled() {
+                skipped.push(job);
+                break;
+            }
+            state.jobs += 1;
+            state.bytes += job.bytes;
+            drop(state);
+
+            // Reserve the report slot so reports stay in start order
+            let slot = {
+                let mut reports = reports.lock().unwrap();
+                reports.push(None);
+                reports.len() - 1
+            };
+            let started_after = started.elapsed();
+            let (in_flight, finished, reports) = (&in_flight, &finished, &reports);
+            scope.spawn(move || {
+                let job_started = Instant::now();
+                let output = (job.work)(cancel);
+                let report = JobReport {
+                    name: job.name,
+                    bytes: job.bytes,
+                    output: Some(output),
+                    started_after,
+                    elapsed: job_started.elapsed(),
+                };
+                reports.lock().unwrap()[slot] = Some(report);
+
+                let mut state = in_flight.lock().unwrap();
+                state.jobs -= 1;
+                state.bytes -= job.bytes;
+                finished.notify_all();
+            });
+        }
+        skipped.extend(pending);
+    });
+
+    let mut jobs: Vec<JobReport<T>> = reports
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    let cancelled = !skipped.is_empty();
+    jobs.extend(skipped.into_iter().map(|job| JobReport {
+        name: job.name,
+        bytes: job.bytes,
+        output: None,
+        started_after: Duration::ZERO,
+        elapsed: Duration::ZERO,
+    }));
+
+    Report {
+        jobs,
+        cancelled,
+        elapsed: started.elapsed(),
+    }
+}
+
+/// Build SHA256 hashing jobs for a set of files
+///
+/// Each job yields the hex digest, or an `Interrupted` error if cancelled
+/// while hashing. Errors name the file.
+pub fn hash_jobs(paths: &[PathBuf]) -> io::Result<Vec<Job<io::Result<String>>>> {
+    paths
+        .iter()
+        .map(|path| {
+            let with_path =
+                |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+            let bytes = std::fs::metadata(path).map_err(with_path)?.len();
+            let path = path.clone();
+            Ok(Job::new(path.display().to_string(), bytes, move |cancel| {
+                hash_file(&path, cancel).map_err(|e| match e.kind() {
+                    io::ErrorKind::Interrupted => e,
+                    _ => io::Error::new(e.kind(), format!("{}: {}", path.display(), e)),
+                })
+            }))
+        })
+        .collect()
+}
+
+/// SHA256 of a file, checking `cancel` between chunks
+fn hash_file(path: &Path, cancel: &CancelToken) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_CHUNK_SIZE];
+    loop {
+        if cancel.is_cancelled() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                format!("hashing {} cancelled", path.display()),
+            ));
+        }
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Hash files under the given limits until finished or cancelled
+///
+/// Returns the digests in the order of `paths` and the timing report.
+/// Fails with the first hashing error, or `Interrupted` if cancelled.
+pub fn hash_files(
+    paths: &[PathBuf],
+    limits: &Limits,
+    cancel: &CancelToken,
+) -> io::Result<(Vec<String>, Report<()>)> {
+    let jobs = hash_jobs(paths)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, job)| {
+            let work = job.work;
+            Job::new(job.name, job.bytes, move |cancel| (index, work(cancel)))
+        })
+        .collect();
+    let report = run_with_cancel(jobs, limits, cancel);
+    if report.cancelled {
+        return Err(io::Error::new(
+            io::ErrorKind::Interrupted,
+            format!("hashing cancelled, {} file(s) skipped", report.skipped()),
+        ));
+    }
+
+    let mut hashes = vec![String::new(); paths.len()];
+    let mut jobs = Vec::with_capacity(report.jobs.len());
+    for job in report.jobs {
+        if let Some((index, hash)) = job.output {
+            hashes[index] = hash?;
+        }
+        jobs.push(JobReport {
+            name: job.name,
+            bytes: job.bytes,
+            output: Some(()),
+            started_after: job.started_after,
+            elapsed: job.elapsed,
+        });
+    }
+
+    Ok((
+        hashes,
+        Report {
+            jobs,
+            cancelled: false,
+            elapsed: report.elapsed,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Running job count, running bytes and their observed peaks
+    type Tracker = Arc<(AtomicUsize, AtomicUsize, Mutex<(usize, u64)>)>;
+
+    /// Sleep-based job that records peak concurrency and in-flight bytes
+    fn sleep_job(name: &str, bytes: u64, sleep: Duration, active: &Tracker) -> Job<()> {
+        let active = Arc::clone(active);
+        Job::new(name, bytes, move |_| {
+            let (jobs, in_bytes, peak) = &*active;
+            let now_jobs = jobs.fetch_add(1, Ordering::SeqCst) + 1;
+            let now_bytes = in_bytes.fetch_add(bytes as usize, Ordering::SeqCst) + bytes as usize;
+            {
+                let mut peak = peak.lock().unwrap();
+                peak.0 = peak.0.max(now_jobs);
+                peak.1 = peak.1.max(now_bytes as u64);
+            }
+            std::thread::sleep(sleep);
+            in_bytes.fetch_sub(bytes as usize, Ordering::SeqCst);
+            jobs.fetch_sub(1, Ordering::SeqCst);
+        })
+    }
+
+    fn tracker() -> Tracker {
+        Arc::new((AtomicUsize::new(0), AtomicUsize::new(0), Mutex::new((0, 0))))
+    }
+
+    #[test]
+    fn test_worker_cap() {
+        let active = tracker();
+        let jobs = (0..8)
+            .map(|i| sleep_job(&format!("job{}", i), 1, Duration::from_millis(50), &active))
+            .collect();
+        let limits = Limits {
+            max_workers: 3,
+            max_in_flight_bytes: u64::MAX,
+        };
+
+        let report = run(jobs, &limits);
+        assert_eq!(report.jobs.len(), 8);
+        assert!(!report.cancelled);
+        assert_eq!(active.2.lock().unwrap().0, 3);
+    }
+
+    #[test]
+    fn test_byte_cap() {
+        let active = tracker();
+        let jobs = [60, 50, 40, 30, 20, 10]
+            .iter()
+            .map(|&bytes| sleep_job("job", bytes, Duration::from_millis(50), &active))
+            .collect();
+        let limits = Limits {
+            max_workers: 8,
+            max_in_flight_bytes: 100,
+        };
+
+        run(jobs, &limits);
+        assert!(active.2.lock().unwrap().1 <= 100);
+    }
+
+    #[test]
+    fn test_oversized_job_runs_alone() {
+        let active = tracker();
+        let jobs = vec![
+            sleep_job("big", 500, Duration::from_millis(50), &active),
+            sleep_job("small", 10, Duration::from_millis(50), &active),
+        ];
+        let limits = Limits {
+            max_workers: 4,
+            max_in_flight_bytes: 100,
+        };
+
+        let report = run(jobs, &limits);
+        assert_eq!(report.skipped(), 0);
+        assert_eq!(active.2.lock().unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_largest_first() {
+        let jobs = [5u64, 40, 10, 30, 20]
+            .iter()
+            .map(|&bytes| Job::new(format!("job{}", bytes), bytes, move |_| bytes))
+            .collect();
+        let limits = Limits {
+            max_workers: 1,
+            max_in_flight_bytes: u64::MAX,
+        };
+
+        let report = run(jobs, &limits);
+        let order: Vec<u64> = report.jobs.iter().map(|j| j.output.unwrap()).collect();
+        assert_eq!(order, vec![40, 30, 20, 10, 5]);
+    }
+
+    #[test]
+    fn test_cancel_stops_scheduling() {
+        let cancel = CancelToken::new();
+        let jobs = (0..5u64)
+            .map(|i| {
+                let cancel = cancel.clone();
+                Job::new(format!("job{}", i), 10 - i, move |_| {
+                    cancel.cancel();
+                    std::thread::sleep(Duration::from_millis(20));
+                })
+            })
+            .collect();
+        let limits = Limits {
+            max_workers: 1,
+            max_in_flight_bytes: u64::MAX,
+        };
+
+        let report = run_with_cancel(jobs, &limits, &cancel);
+        assert!(report.cancelled);
+        assert_eq!(report.jobs.len(), 5);
+        assert_eq!(report.skipped(), 4);
+        assert!(report.jobs[0].output.is_some());
+        assert!(report.to_string().contains("cancelled, 4 skipped"));
+    }
+
+    #[test]
+    fn test_hash_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|i| {
+                let path = dir.path().join(format!("file{}", i));
+                std::fs::write(&path, vec![b'x'; 1000 * (i + 1)]).unwrap();
+                path
+            })
+            .collect();
+
+        let report = run(hash_jobs(&paths).unwrap(), &Limits::default());
+        assert_eq!(report.jobs[0].bytes, 3000);
+        let expected = hex::encode(Sha256::digest(vec![b'x'; 3000]));
+        assert_eq!(
+            report.jobs[0].output.as_ref().unwrap().as_ref().unwrap(),
+            &expected
+        );
+
+        // hash_files keeps the input order even though it starts largest-first
+        let (hashes, report) =
+            hash_files(&paths, &Limits::new(Some(2), None), &CancelToken::new()).unwrap();
+        assert_eq!(hashes[2], expected);
+        assert_eq!(hashes[0], hex::encode(Sha256::digest(vec![b'x'; 1000])));
+        assert_eq!(report.jobs[0].to_json()["bytes"], 3000);
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let err = hash_files(&paths, &Limits::default(), &cancel).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+}
@@ -0,0 +1,153 @@
+//! # Redaction
+//!
+//! [`Redacted`] holds a secret (a passphrase, key hex, a shared secret) so it
+//! cannot leak through output, logs or error messages: its `Display`,
+//! `Debug` and serialized forms are all [`REDACTED`], and reading the value
+//! takes an explicit [`Redacted::expose`].
+//!
+//! Renderers of free-form data (signature metadata, module configuration)
+//! use [`redact_json`] to replace the values of secret-named fields with the
+//! same marker, so users can see a value exists without seeing it.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// Placeholder shown instead of a secret
+pub const REDACTED: &str = "<redacted>";
+
+/// Parts of field names that mark the field as secret
+const SECRET_NAME_PARTS: [&str; 6] = [
+    "secret",
+    "passphrase",
+    "password",
+    "token",
+    "private",
+    "mnemonic",
+];
+
+/// A secret value, shown as [`REDACTED`]
+///
+/// Deserializes from the plain value, so configuration and key files need
+/// no special syntax.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Redacted<T>(T);
+
+impl<T> Redacted<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// The secret value
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Unwrap the secret value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Redacted<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> Serialize for Redacted<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Redacted<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+/// Parses the plain value, e.g. from a command-line argument
+impl<T: FromStr> FromStr for Redacted<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+/// Whether a field name marks its value as secret
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// A copy of `value` with the values of secret-named fields replaced by
+/// [`REDACTED`], at any depth
+pub fn redact_json(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| {
+                    let value = if is_secret_name(key) {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_json(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_json).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redacted_formats() {
+        let secret: Redacted<String> = "hunter2".parse().unwrap();
+        assert_eq!(secret.to_string(), REDACTED);
+        assert_eq!(format!("{:?}", Some(&secret)), "Some(<redacted>)");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"<redacted>\"");
+        assert_eq!(secret.expose(), "hunter2");
+
+        let parsed: Redacted<String> = serde_json::from_str("\"hunter2\"").unwrap();
+        assert_eq!(parsed, secret);
+    }
+
+    #[test]
+    fn test_redact_json() {
+        let value = json!({
+            "version": "1.0",
+            "rpc": {"user": "alice", "rpcPassword": "hunter2"},
+            "tokens": ["a", "b"],
+        });
+        assert_eq!(
+            redact_json(&value),
+            json!({
+                "version": "1.0",
+                "rpc": {"user": "alice", "rpcPassword": REDACTED},
+                "tokens": REDACTED,
+            })
+        );
+    }
+}
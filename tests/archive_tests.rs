@@ -0,0 +1,258 @@
+//! # Release Archive Tests
+//!
+//! Tests for verifying release archives that embed their own aggregate
+//! signature file and manifest.
+
+#![cfg(feature = "signing")]
+
+use blvm_sdk::governance::archive::{EmbeddedSignatures, ReleaseArchive};
+use blvm_sdk::governance::manifest::{Manifest, MemberMismatch};
+use blvm_sdk::governance::{GovernanceKeypair, Multisig};
+use blvm_sdk::sign_message;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+
+const SIGNATURE_MEMBER: &str = "release/SIGNATURES.json";
+
+/// Member path and contents
+type Member = (&'static str, Vec<u8>);
+/// Writes members into an archive file
+type ArchiveWriter = fn(&Path, &[Member]);
+
+fn release_members() -> Vec<Member> {
+    vec![
+        (
+            "release/bin/blvm-node",
+            vec![0x7f, b'E', b'L', b'F', 1, 2, 3],
+        ),
+        ("release/README.md", b"# blvm-node v1.0.0\n".to_vec()),
+    ]
+}
+
+/// Aggregate signature file over the members' manifest, signed 2-of-3
+fn embedded_aggregate(members: &[Member], keypairs: &[GovernanceKeypair]) -> serde_json::Value {
+    let mut manifest = Manifest::new();
+    for (path, data) in members {
+        manifest.insert(path, &hex::encode(Sha256::digest(data)));
+    }
+    let message = manifest.signing_bytes(Some("v1.0.0"));
+
+    let signatures: Vec<_> = keypairs[..2]
+        .iter()
+        .map(|kp| {
+            serde_json::json!({
+                "signature": hex::encode(sign_message(kp.secret_key.expose(), &message).unwrap().to_bytes()),
+                "public_key": hex::encode(kp.public_key().to_bytes()),
+                "signed_at": "2024-06-01T00:00:00Z",
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "version": "1.0",
+        "signature_count": signatures.len(),
+        "signatures": signatures,
+        "threshold": "2-of-3",
+        "manifest": manifest.entries(),
+    })
+}
+
+fn write_tar_gz(path: &Path, members: &[Member]) {
+    let encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(path).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    for (name, data) in members {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, format!("./{}", name), data.as_slice())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+fn write_zip(path: &Path, members: &[Member]) {
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    writer
+        .add_directory("release/", Default::default())
+        .unwrap();
+    for (name, data) in members {
+        writer.start_file(*name, Default::default()).unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+/// Build an archive holding the members plus an aggregate signed over the
+/// untampered members
+fn build_archive(
+    dir: &Path,
+    file_name: &str,
+    write: ArchiveWriter,
+    tamper: bool,
+) -> (ReleaseArchive, Multisig) {
+    let keypairs: Vec<_> = (0..3)
+        .map(|_| GovernanceKeypair::generate().unwrap())
+        .collect();
+    let multisig =
+        Multisig::new(2, 3, keypairs.iter().map(|kp| kp.public_key()).collect()).unwrap();
+
+    let mut members = release_members();
+    let aggregate = embedded_aggregate(&members, &keypairs);
+    if tamper {
+        members[0].1.push(0x90);
+    }
+    members.push((SIGNATURE_MEMBER, serde_json::to_vec(&aggregate).unwrap()));
+
+    let path = dir.join(file_name);
+    write(&path, &members);
+    (ReleaseArchive::open(&path).unwrap(), multisig)
+}
+
+fn verify(archive: &ReleaseArchive, multisig: &Multisig) -> Vec<MemberMismatch> {
+    let embedded =
+        EmbeddedSignatures::from_json(&archive.read_member(SIGNATURE_MEMBER).unwrap()).unwrap();
+    assert_eq!(embedded.signatures.len(), 2);
+    assert!(multisig
+        .verify(
+            &embedded.manifest.signing_bytes(Some("v1.0.0")),
+            &embedded.signatures
+        )
+        .unwrap());
+
+    archive
+        .verify_members(SIGNATURE_MEMBER, &embedded.manifest)
+        .unwrap()
+}
+
+fn dir_entries(dir: &Path) -> usize {
+    std::fs::read_dir(dir).unwrap().count()
+}
+
+#[test]
+fn test_tar_gz_embedded_aggregate() {
+    let dir = TempDir::new().unwrap();
+    let (archive, multisig) = build_archive(dir.path(), "release.tar.gz", write_tar_gz, false);
+
+    assert!(verify(&archive, &multisig).is_empty());
+    assert_eq!(dir_entries(dir.path()), 1);
+}
+
+#[test]
+fn test_zip_embedded_aggregate() {
+    let dir = TempDir::new().unwrap();
+    let (archive, multisig) = build_archive(dir.path(), "release.zip", write_zip, false);
+
+    assert!(verify(&archive, &multisig).is_empty());
+    assert_eq!(dir_entries(dir.path()), 1);
+}
+
+#[test]
+fn test_tampered_member_detected_without_extraction() {
+    for (file_name, write) in [
+        ("release.tar.gz", write_tar_gz as ArchiveWriter),
+        ("release.zip", write_zip),
+    ] {
+        let dir = TempDir::new().unwrap();
+        let (archive, multisig) = build_archive(dir.path(), file_name, write, true);
+
+        // The signatures still cover the manifest; the member no longer matches it
+        let mismatches = verify(&archive, &multisig);
+        assert_eq!(mismatches.len(), 1, "{}", file_name);
+        assert!(matches!(
+            &mismatches[0],
+            MemberMismatch::Modified { path, .. } if path == "release/bin/blvm-node"
+        ));
+        assert_eq!(dir_entries(dir.path()), 1);
+    }
+}
+
+#[test]
+fn test_missing_signature_member() {
+    let dir = TempDir::new().unwrap();
+    let (archive, _) = build_archive(dir.path(), "release.zip", write_zip, false);
+
+    let err = archive.read_member("release/OTHER.json").unwrap_err();
+    assert!(err.to_string().contains("not found"));
+}
+
+/// Write a gzip-compressed tar with the member names as given, plus an
+/// optional link entry `(name, type, target)`
+fn write_raw_tar_gz(path: &Path, members: &[Member], link: Option<(&str, tar::EntryType, &str)>) {
+    let encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(path).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    for (name, data) in members {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, data.as_slice())
+            .unwrap();
+    }
+    if let Some((name, entry_type, target)) = link {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_size(0);
+        header.set_mode(0o644);
+        builder.append_link(&mut header, name, target).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+fn hash_members_error(path: &Path) -> String {
+    ReleaseArchive::open(path)
+        .unwrap()
+        .hash_members(SIGNATURE_MEMBER)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_special_members_rejected() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("release.tar.gz");
+
+    for (entry_type, kind) in [
+        (tar::EntryType::Symlink, "Symlink"),
+        (tar::EntryType::Link, "Link"),
+    ] {
+        let link = ("release/bin/node", entry_type, "release/bin/blvm-node");
+        write_raw_tar_gz(&path, &release_members(), Some(link));
+        let err = hash_members_error(&path);
+        assert!(err.contains("not a regular file"), "{}", err);
+        assert!(err.contains(kind), "{}", err);
+    }
+
+    let path = dir.path().join("release.zip");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    writer
+        .add_symlink("release/bin/node", "/etc/passwd", Default::default())
+        .unwrap();
+    writer.finish().unwrap();
+    let err = hash_members_error(&path);
+    assert!(err.contains("not a regular file"), "{}", err);
+}
+
+#[test]
+fn test_duplicate_members_rejected() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("release.tar.gz");
+
+    // "./release/README.md" and "release/README.md" name the same member
+    let mut members = release_members();
+    members.push(("./release/README.md", b"# replaced\n".to_vec()));
+    write_raw_tar_gz(&path, &members, None);
+
+    let err = hash_members_error(&path);
+    assert!(err.contains("more than once"), "{}", err);
+}
@@ -0,0 +1,1232 @@
+//! # CLI Tests
+//!
+//! Tests that run the command-line tools end to end.
+
+#![cfg(feature = "signing")]
+
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// Run a tool from this package in `dir`
+fn run(bin: &str, dir: &Path, args: &[&str]) -> Output {
+    Command::new(bin)
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_history_requires_as_of() {
+    let dir = TempDir::new().unwrap();
+    let output = run(
+        env!("CARGO_BIN_EXE_blvm-verify"),
+        dir.path(),
+        &[
+            "--signatures",
+            "sig.json",
+            "--history",
+            "history.json",
+            "release",
+            "--version",
+            "v1.0.0",
+            "--commit",
+            "abc123",
+        ],
+    );
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--as-of"));
+}
+
+/// Generate a key and sign the v1.0.0 release with it
+fn sign_release(dir: &Path, name: &str) {
+    let key = format!("{}.key.json", name);
+    let signature = format!("{}.sig.json", name);
+    let keygen = run(env!("CARGO_BIN_EXE_blvm-keygen"), dir, &["-o", &key]);
+    assert!(keygen.status.success());
+    let sign = run(
+        env!("CARGO_BIN_EXE_blvm-sign"),
+        dir,
+        &[
+            "-k",
+            &key,
+            "-o",
+            &signature,
+            "release",
+            "--version",
+            "v1.0.0",
+            "--commit",
+            "abc123",
+        ],
+    );
+    assert!(sign.status.success());
+    let public_key = public_key(dir, name);
+    assert!(String::from_utf8_lossy(&sign.stdout).contains(&format!(
+        "Public key: {} ({})",
+        public_key,
+        public_key.short()
+    )));
+}
+
+/// Public key of the key generated by [`sign_release`]
+fn public_key(dir: &Path, name: &str) -> blvm_sdk::governance::PublicKey {
+    let key_file: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(dir.join(format!("{}.key.json", name))).unwrap())
+            .unwrap();
+    let bytes = hex::decode(key_file["public_key"].as_str().unwrap()).unwrap();
+    blvm_sdk::governance::PublicKey::from_bytes(&bytes).unwrap()
+}
+
+/// Verify the v1.0.0 release with --tofu and the given extra arguments
+fn verify_tofu(dir: &Path, signatures: &str, pubkeys: &str, extra: &[&str]) -> Output {
+    let mut args = vec![
+        "-s",
+        signatures,
+        "-p",
+        pubkeys,
+        "--tofu",
+        "github.com/btcdecoded/blvm",
+        "--pin-file",
+        "pins.json",
+    ];
+    args.extend_from_slice(extra);
+    args.extend_from_slice(&["release", "--version", "v1.0.0", "--commit", "abc123"]);
+    run(env!("CARGO_BIN_EXE_blvm-verify"), dir, &args)
+}
+
+#[test]
+fn test_tofu_pins_and_rejects_key_change() {
+    let dir = TempDir::new().unwrap();
+    sign_release(dir.path(), "alice");
+    sign_release(dir.path(), "bob");
+
+    let first = verify_tofu(dir.path(), "alice.sig.json", "alice.key.json", &[]);
+    assert!(first.status.success());
+    assert!(String::from_utf8_lossy(&first.stdout).contains("pinned on first use"));
+
+    let changed = verify_tofu(dir.path(), "bob.sig.json", "bob.key.json", &[]);
+    assert!(!changed.status.success());
+    assert!(String::from_utf8_lossy(&changed.stderr).contains("KEY CHANGE"));
+
+    let accepted = verify_tofu(
+        dir.path(),
+        "bob.sig.json",
+        "bob.key.json",
+        &["--accept-key-change", "rotated to bob"],
+    );
+    assert!(accepted.status.success());
+    assert!(String::from_utf8_lossy(&accepted.stdout).contains("accepted key change"));
+
+    let pins = std::fs::read_to_string(dir.path().join("pins.json")).unwrap();
+    assert!(pins.contains("rotated to bob"));
+}
+
+#[test]
+fn test_tofu_does_not_pin_invalid_signatures() {
+    let dir = TempDir::new().unwrap();
+    sign_release(dir.path(), "alice");
+    sign_release(dir.path(), "mallory");
+
+    // Alice's signature meets the default threshold, Mallory's does not verify
+    let output = verify_tofu(
+        dir.path(),
+        "alice.sig.json,mallory.sig.json",
+        "alice.key.json",
+        &[],
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Invalid signatures: 1"));
+    assert!(!dir.path().join("pins.json").exists());
+}
+
+#[test]
+fn test_expiry_requires_signed_timestamp() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("node.bin"), b"node binary").unwrap();
+    std::fs::write(
+        dir.path().join("policy.toml"),
+        "max_signature_age = \"1w\"\n\n[[stages]]\nname = \"hash\"\n\n[[stages]]\nname = \"signature\"\n\n[[stages]]\nname = \"threshold\"\n\n[[stages]]\nname = \"expiry\"\n",
+    )
+    .unwrap();
+    let keygen = run(
+        env!("CARGO_BIN_EXE_blvm-keygen"),
+        dir.path(),
+        &["-o", "key.json"],
+    );
+    assert!(keygen.status.success());
+    let sign = run(
+        env!("CARGO_BIN_EXE_blvm-sign-binary"),
+        dir.path(),
+        &[
+            "-k", "key.json", "-o", "sig.json", "binary", "--file", "node.bin",
+        ],
+    );
+    assert!(sign.status.success());
+
+    let verify = || {
+        run(
+            env!("CARGO_BIN_EXE_blvm-verify-binary"),
+            dir.path(),
+            &[
+                "-s",
+                "sig.json",
+                "-p",
+                "key.json",
+                "--policy",
+                "policy.toml",
+                "binary",
+                "--file",
+                "node.bin",
+            ],
+        )
+    };
+    assert!(verify().status.success());
+
+    // Without the timestamp signature the recorded time is not trusted
+    let path = dir.path().join("sig.json");
+    let mut signature: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    signature
+        .as_object_mut()
+        .unwrap()
+        .remove("timestamp_signature");
+    std::fs::write(&path, signature.to_string()).unwrap();
+
+    let unsigned = verify();
+    assert!(!unsigned.status.success());
+    assert!(String::from_utf8_lossy(&unsigned.stdout).contains("expiry"));
+}
+
+#[test]
+fn test_sign_and_verify_manifest_bins() {
+    let dir = TempDir::new().unwrap();
+    let release = dir.path().join("release");
+    std::fs::create_dir_all(release.join("bin")).unwrap();
+    std::fs::write(release.join("bin/blvm-node"), b"\x7fELF node").unwrap();
+    std::fs::write(release.join("README.md"), b"# blvm v1.0.0\n").unwrap();
+    let keygen = run(
+        env!("CARGO_BIN_EXE_blvm-keygen"),
+        dir.path(),
+        &["-o", "key.json"],
+    );
+    assert!(keygen.status.success());
+
+    let sign = run(
+        env!("CARGO_BIN_EXE_blvm-sign-binary"),
+        dir.path(),
+        &[
+            "-k",
+            "key.json",
+            "-o",
+            "release/SHA256SUMS.sig.json",
+            "manifest",
+            "--dir",
+            "release",
+            "--version",
+            "v1.0.0",
+        ],
+    );
+    assert!(
+        sign.status.success(),
+        "{}",
+        String::from_utf8_lossy(&sign.stderr)
+    );
+    let sums = std::fs::read_to_string(release.join("SHA256SUMS")).unwrap();
+    assert_eq!(sums.lines().count(), 2, "{}", sums);
+    assert!(sums.contains("  README.md\n") && sums.contains("  bin/blvm-node\n"));
+
+    let verify = || {
+        run(
+            env!("CARGO_BIN_EXE_blvm-verify-binary"),
+            dir.path(),
+            &[
+                "-s",
+                "release/SHA256SUMS.sig.json",
+                "-p",
+                "key.json",
+                "manifest",
+                "--file",
+                "release/SHA256SUMS",
+                "--version",
+                "v1.0.0",
+            ],
+        )
+    };
+    let verified = verify();
+    assert!(
+        verified.status.success(),
+        "{}",
+        String::from_utf8_lossy(&verified.stdout)
+    );
+
+    std::fs::write(release.join("bin/blvm-node"), b"\x7fELF patched").unwrap();
+    let tampered = verify();
+    assert!(!tampered.status.success());
+    assert!(String::from_utf8_lossy(&tampered.stdout).contains("bin/blvm-node"));
+}
+
+#[test]
+fn test_aggregate_skips_duplicates_and_merges_into_output() {
+    let dir = TempDir::new().unwrap();
+    sign_release(dir.path(), "alice");
+    sign_release(dir.path(), "bob");
+    let aggregate = |args: &[&str]| {
+        run(
+            env!("CARGO_BIN_EXE_blvm-aggregate-signatures"),
+            dir.path(),
+            args,
+        )
+    };
+
+    let first = aggregate(&[
+        "-s",
+        "alice.sig.json,alice.sig.json",
+        "-o",
+        "aggregate.json",
+    ]);
+    assert!(first.status.success());
+    let stdout = String::from_utf8_lossy(&first.stdout);
+    assert!(stdout.contains("Aggregated 1 signatures"), "{}", stdout);
+    assert!(stdout.contains("Skipped 1 duplicate"), "{}", stdout);
+    // The signer is listed by fingerprint next to the full key
+    let alice = public_key(dir.path(), "alice");
+    assert!(
+        stdout.contains(&format!("{}  {}", alice.short(), alice)),
+        "{}",
+        stdout
+    );
+
+    // The aggregate is both merged in and replaced
+    let merged = aggregate(&[
+        "-s",
+        "bob.sig.json",
+        "-m",
+        "aggregate.json,aggregate.json",
+        "-o",
+        "aggregate.json",
+        "--format",
+        "json",
+    ]);
+    assert!(merged.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&merged.stdout).unwrap();
+    assert_eq!(result["signature_count"], 2);
+    assert_eq!(result["duplicates_skipped"], 1);
+
+    let verify = run(
+        env!("CARGO_BIN_EXE_blvm-verify"),
+        dir.path(),
+        &[
+            "-s",
+            "aggregate.json",
+            "-p",
+            "alice.key.json,bob.key.json",
+            "-t",
+            "2-of-2",
+            "release",
+            "--version",
+            "v1.0.0",
+            "--commit",
+            "abc123",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&verify.stdout);
+    assert!(verify.status.success(), "{}", stdout);
+    assert!(stdout.contains("Valid signatures: 2"), "{}", stdout);
+}
+
+#[test]
+fn test_aggregate_rejects_threshold_committee_mismatch() {
+    let dir = TempDir::new().unwrap();
+    let names = ["alice", "bob", "carol", "dave", "erin"];
+    for name in names {
+        sign_release(dir.path(), name);
+    }
+    let signatures = names.map(|name| format!("{}.sig.json", name)).join(",");
+    let aggregate = |pubkeys: &str| {
+        run(
+            env!("CARGO_BIN_EXE_blvm-aggregate-signatures"),
+            dir.path(),
+            &["-s", &signatures, "-t", "6-of-7", "-p", pubkeys],
+        )
+    };
+
+    let pubkeys = names.map(|name| format!("{}.key.json", name)).join(",");
+    let output = aggregate(&pubkeys);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("expects 7 public keys, but 5"),
+        "{}",
+        stderr
+    );
+
+    // Listing a key file twice does not pad the committee
+    let padded = format!("{},alice.key.json,bob.key.json", pubkeys);
+    let output = aggregate(&padded);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("expects 7 public keys, but 5"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_aggregate_max_signatures() {
+    let dir = TempDir::new().unwrap();
+    for name in ["alice", "bob", "carol"] {
+        sign_release(dir.path(), name);
+    }
+    let aggregate = |args: &[&str]| {
+        run(
+            env!("CARGO_BIN_EXE_blvm-aggregate-signatures"),
+            dir.path(),
+            args,
+        )
+    };
+    let files = "alice.sig.json,bob.sig.json,carol.sig.json";
+
+    // Too many files: refused before the output is written
+    let output = aggregate(&["-s", files, "-o", "too-many.json", "--max-signatures", "2"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("More than 2 signatures"), "{}", stderr);
+    assert!(!dir.path().join("too-many.json").exists());
+
+    // At the cap
+    let output = aggregate(&["-s", files, "-o", "aggregate.json", "--max-signatures", "3"]);
+    assert!(output.status.success());
+
+    // Merging past the cap aborts and keeps the existing output
+    let before = std::fs::read(dir.path().join("aggregate.json")).unwrap();
+    let output = aggregate(&[
+        "-s",
+        "alice.sig.json",
+        "-m",
+        "aggregate.json",
+        "-o",
+        "aggregate.json",
+        "--max-signatures",
+        "2",
+    ]);
+    assert!(!output.status.success());
+    assert_eq!(
+        std::fs::read(dir.path().join("aggregate.json")).unwrap(),
+        before
+    );
+}
+
+#[test]
+fn test_aggregate_rejects_incomplete_metadata() {
+    let dir = TempDir::new().unwrap();
+    let keygen = run(
+        env!("CARGO_BIN_EXE_blvm-keygen"),
+        dir.path(),
+        &["-o", "key.json"],
+    );
+    assert!(keygen.status.success());
+    std::fs::write(dir.path().join("node.bin"), b"node binary").unwrap();
+    let sign = run(
+        env!("CARGO_BIN_EXE_blvm-sign-binary"),
+        dir.path(),
+        &[
+            "-k", "key.json", "-o", "sig.json", "binary", "--file", "node.bin",
+        ],
+    );
+    assert!(sign.status.success());
+    let aggregate = |file: &str| {
+        run(
+            env!("CARGO_BIN_EXE_blvm-aggregate-signatures"),
+            dir.path(),
+            &["-s", file, "-o", "aggregate.json"],
+        )
+    };
+    assert!(aggregate("sig.json").status.success());
+
+    let mut signature: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(dir.path().join("sig.json")).unwrap()).unwrap();
+    signature["metadata"]
+        .as_object_mut()
+        .unwrap()
+        .remove("file_hash");
+    std::fs::write(dir.path().join("bad.json"), signature.to_string()).unwrap();
+    let output = aggregate("bad.json");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("bad.json") && stderr.contains("file_hash"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_dead_notify_url_does_not_change_exit_status() {
+    let dir = TempDir::new().unwrap();
+    sign_release(dir.path(), "alice");
+    let dead = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let url = format!("http://{}/hook", dead);
+
+    let output = run(
+        env!("CARGO_BIN_EXE_blvm-verify"),
+        dir.path(),
+        &[
+            "-s",
+            "alice.sig.json",
+            "-p",
+            "alice.key.json",
+            "--notify-url",
+            &url,
+            "release",
+            "--version",
+            "v1.0.0",
+            "--commit",
+            "abc123",
+        ],
+    );
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning"), "{}", stderr);
+}
+
+#[test]
+fn test_paper_backup_restores_key_without_printing_it() {
+    let dir = TempDir::new().unwrap();
+    let keygen = env!("CARGO_BIN_EXE_blvm-keygen");
+    assert!(run(keygen, dir.path(), &["-o", "alice.key.json"])
+        .status
+        .success());
+    let key: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("alice.key.json")).unwrap())
+            .unwrap();
+    let secret_key = key["secret_key"].as_str().unwrap();
+
+    let backup = run(
+        keygen,
+        dir.path(),
+        &[
+            "paper-backup",
+            "alice.key.json",
+            "--parity",
+            "2",
+            "--document",
+            "alice.paper.txt",
+        ],
+    );
+    assert!(backup.status.success());
+
+    // Lose two of the chunk lines
+    let document = std::fs::read_to_string(dir.path().join("alice.paper.txt")).unwrap();
+    let mut chunks = document.lines().filter(|line| line.starts_with("BLVM1:"));
+    chunks.next();
+    chunks.next();
+    let remaining: Vec<&str> = chunks.collect();
+    std::fs::write(dir.path().join("typed.txt"), remaining.join("\n")).unwrap();
+
+    let restore = run(
+        keygen,
+        dir.path(),
+        &["paper-restore", "typed.txt", "-o", "restored.key.json"],
+    );
+    assert!(restore.status.success());
+    let stdout = String::from_utf8_lossy(&restore.stdout);
+    assert!(
+        stdout.contains("Missing or damaged chunks: 1, 2"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains(secret_key));
+    assert!(!String::from_utf8_lossy(&restore.stderr).contains(secret_key));
+
+    let restored: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dir.path().join("restored.key.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(restored["secret_key"], key["secret_key"]);
+    assert_eq!(restored["public_key"], key["public_key"]);
+
+    // One more lost chunk is past the parity
+    std::fs::write(dir.path().join("typed.txt"), remaining[1..].join("\n")).unwrap();
+    let restore = run(
+        keygen,
+        dir.path(),
+        &["paper-restore", "typed.txt", "-o", "again.key.json"],
+    );
+    assert!(!restore.status.success());
+    let stderr = String::from_utf8_lossy(&restore.stderr);
+    assert!(stderr.contains("3 chunks missing"), "{}", stderr);
+    assert!(!dir.path().join("again.key.json").exists());
+}
+
+#[test]
+fn test_explain_prints_committee_without_signatures() {
+    use sha2::{Digest, Sha256};
+
+    let dir = TempDir::new().unwrap();
+    let keygen = env!("CARGO_BIN_EXE_blvm-keygen");
+    let mut fingerprints = Vec::new();
+    for name in ["alice", "bob"] {
+        let key = format!("{}.key.json", name);
+        assert!(run(keygen, dir.path(), &["-o", &key]).status.success());
+        let key: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join(&key)).unwrap()).unwrap();
+        let public_key = hex::decode(key["public_key"].as_str().unwrap()).unwrap();
+        fingerprints.push(hex::encode(Sha256::digest(public_key)));
+    }
+    std::fs::write(dir.path().join("garbage.sig.json"), "not a signature").unwrap();
+
+    let release = ["release", "--version", "v1.0.0", "--commit", "abc123"];
+    for signatures in [&[][..], &["-s", "garbage.sig.json"][..]] {
+        let mut args = vec![
+            "--explain",
+            "-t",
+            "2-of-2",
+            "-p",
+            "alice.key.json,bob.key.json",
+        ];
+        args.extend_from_slice(signatures);
+        args.extend_from_slice(&release);
+        let output = run(env!("CARGO_BIN_EXE_blvm-verify"), dir.path(), &args);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Threshold: 2-of-2"), "{}", stdout);
+        for fingerprint in &fingerprints {
+            assert!(stdout.contains(fingerprint.as_str()), "{}", stdout);
+        }
+    }
+}
+
+#[test]
+fn test_signatures_default_to_conventional_names_and_rename() {
+    use blvm_sdk::governance::sigfile::discover;
+
+    let dir = TempDir::new().unwrap();
+    let keygen = env!("CARGO_BIN_EXE_blvm-keygen");
+    let inspect = env!("CARGO_BIN_EXE_blvm-inspect");
+    for name in ["alice", "bob"] {
+        let key = format!("{}.key.json", name);
+        assert!(run(keygen, dir.path(), &["-o", &key]).status.success());
+    }
+    std::fs::write(dir.path().join("node.bin"), b"node binary").unwrap();
+
+    // Without --output the signature gets the conventional name
+    let sign = run(
+        env!("CARGO_BIN_EXE_blvm-sign-binary"),
+        dir.path(),
+        &["-k", "alice.key.json", "binary", "--file", "node.bin"],
+    );
+    assert!(sign.status.success());
+    assert_eq!(discover(dir.path(), "node.bin").unwrap().len(), 1);
+
+    // Bob names his own way
+    let sign = run(
+        env!("CARGO_BIN_EXE_blvm-sign-binary"),
+        dir.path(),
+        &[
+            "-k",
+            "bob.key.json",
+            "-o",
+            "sig-bob.json",
+            "binary",
+            "--file",
+            "node.bin",
+        ],
+    );
+    assert!(sign.status.success());
+
+    let dry_run = run(inspect, dir.path(), &["rename"]);
+    assert!(dry_run.status.success());
+    let stdout = String::from_utf8_lossy(&dry_run.stdout);
+    assert!(stdout.contains("sig-bob.json -> node.bin."), "{}", stdout);
+    assert!(dir.path().join("sig-bob.json").exists());
+
+    let apply = run(inspect, dir.path(), &["rename", "--apply"]);
+    assert!(apply.status.success());
+    assert!(!dir.path().join("sig-bob.json").exists());
+    assert_eq!(discover(dir.path(), "node.bin").unwrap().len(), 2);
+    assert!(dir.path().join("alice.key.json").exists());
+
+    // A copy of a signature collides with the original; both are listed
+    let original = discover(dir.path(), "node.bin").unwrap().remove(0);
+    std::fs::copy(&original, dir.path().join("copy.json")).unwrap();
+    let collision = run(inspect, dir.path(), &["rename", "--apply"]);
+    assert!(!collision.status.success());
+    let stderr = String::from_utf8_lossy(&collision.stderr);
+    assert!(stderr.contains("copy.json"), "{}", stderr);
+    let original = original.file_name().unwrap().to_str().unwrap();
+    assert!(stderr.contains(original), "{}", stderr);
+    assert!(dir.path().join("copy.json").exists());
+}
+
+#[test]
+fn test_resigning_with_different_metadata_requires_supersede() {
+    use blvm_sdk::governance::attributes::SignedAttributes;
+    use blvm_sdk::governance::journal::SigningJournal;
+    use blvm_sdk::governance::{PublicKey, SigningTarget};
+    use sha2::{Digest, Sha256};
+
+    let dir = TempDir::new().unwrap();
+    let keygen = run(
+        env!("CARGO_BIN_EXE_blvm-keygen"),
+        dir.path(),
+        &["-o", "key.json"],
+    );
+    assert!(keygen.status.success());
+    std::fs::write(dir.path().join("node.bin"), b"node binary").unwrap();
+    let sign = |version: &str, extra: &[&str]| {
+        let mut args = vec!["-k", "key.json", "-o", "sig.json"];
+        args.extend_from_slice(extra);
+        args.extend_from_slice(&["binary", "--file", "node.bin", "--version", version]);
+        run(env!("CARGO_BIN_EXE_blvm-sign-binary"), dir.path(), &args)
+    };
+
+    // Signing the same payload twice is fine
+    assert!(sign("1.0", &[]).status.success());
+    assert!(sign("1.0", &[]).status.success());
+
+    // A corrected version needs --supersede
+    let blocked = sign("1.0.1", &[]);
+    assert!(!blocked.status.success());
+    let stderr = String::from_utf8_lossy(&blocked.stderr);
+    assert!(stderr.contains("--supersede"), "{}", stderr);
+    let journal = SigningJournal::open(&dir.path().join("key.json.journal")).unwrap();
+    assert_eq!(journal.entries().len(), 2);
+
+    let superseding = sign("1.0.1", &["--supersede"]);
+    assert!(
+        superseding.status.success(),
+        "{}",
+        String::from_utf8_lossy(&superseding.stderr)
+    );
+
+    // The note names the old message and is signed with the new one
+    let file_hash = hex::encode(Sha256::digest(b"node binary"));
+    let message = |version: &str| {
+        SigningTarget::Binary {
+            binary_type: "application".to_string(),
+            file_hash: file_hash.clone(),
+            version: Some(version.to_string()),
+            commit: None,
+        }
+        .to_signing_bytes()
+    };
+    let signature: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("sig.json")).unwrap())
+            .unwrap();
+    let attributes = SignedAttributes::from_entry(&signature).unwrap().unwrap();
+    assert_eq!(
+        attributes.attributes["supersedes"],
+        hex::encode(Sha256::digest(message("1.0")))
+    );
+    let public_key =
+        PublicKey::from_bytes(&hex::decode(signature["public_key"].as_str().unwrap()).unwrap())
+            .unwrap();
+    assert!(attributes.verify(&message("1.0.1"), &public_key));
+
+    let journal = SigningJournal::open(&dir.path().join("key.json.journal")).unwrap();
+    assert_eq!(journal.entries().len(), 3);
+    assert_eq!(
+        journal.entries()[2].record.supersedes,
+        vec![journal.entries()[0].record.message_digest.clone()]
+    );
+
+    // Once superseded, the corrected version signs again silently
+    assert!(sign("1.0.1", &[]).status.success());
+}
+
+#[test]
+fn test_secrets_not_printed() {
+    const CANARY: &str = "CANARY-5e1f0a77c3d94b2a-do-not-print";
+    let dir = TempDir::new().unwrap();
+    let printed = |output: &Output| {
+        format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )
+    };
+
+    for format in ["text", "json"] {
+        let keygen = run(
+            env!("CARGO_BIN_EXE_blvm-keygen"),
+            dir.path(),
+            &["-o", "key.json", "--seed", CANARY, "--format", format],
+        );
+        assert!(keygen.status.success());
+        assert!(!printed(&keygen).contains(CANARY));
+    }
+
+    // A key file whose secret is not a key
+    std::fs::write(
+        dir.path().join("bad.key.json"),
+        format!("{{\"secret_key\": \"{}\"}}", CANARY),
+    )
+    .unwrap();
+    let sign = run(
+        env!("CARGO_BIN_EXE_blvm-sign"),
+        dir.path(),
+        &[
+            "-k",
+            "bad.key.json",
+            "-o",
+            "sig.json",
+            "release",
+            "--version",
+            "v1.0.0",
+            "--commit",
+            "abc123",
+        ],
+    );
+    assert!(!sign.status.success());
+    let output = printed(&sign);
+    assert!(output.contains("bad.key.json"), "{}", output);
+    assert!(!output.contains(CANARY), "{}", output);
+}
+
+#[test]
+fn test_signing_a_key_file_requires_force() {
+    let dir = TempDir::new().unwrap();
+    let keygen = run(
+        env!("CARGO_BIN_EXE_blvm-keygen"),
+        dir.path(),
+        &["-o", "key.json"],
+    );
+    assert!(keygen.status.success());
+    let sign = |target: &str, force: bool| {
+        let mut args = vec!["-k", "key.json", "-o", "key.sig.json"];
+        if force {
+            args.push("--force");
+        }
+        args.extend([target, "--file", "key.json"]);
+        run(env!("CARGO_BIN_EXE_blvm-sign-binary"), dir.path(), &args)
+    };
+
+    for target in ["binary", "checksums"] {
+        let refused = sign(target, false);
+        assert!(!refused.status.success());
+        assert!(String::from_utf8_lossy(&refused.stderr).contains("--force"));
+        assert!(!dir.path().join("key.sig.json").exists());
+    }
+
+    assert!(sign("binary", true).status.success());
+}
+
+#[test]
+fn test_encrypted_key_file() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("release.tar.gz"), b"release").unwrap();
+    let with_passphrase = |passphrase: Option<&str>, bin: &str, args: &[&str]| {
+        let mut command = Command::new(bin);
+        command.current_dir(dir.path()).args(args);
+        command.env_remove("BLLVM_KEY_PASSPHRASE");
+        if let Some(passphrase) = passphrase {
+            command.env("BLLVM_KEY_PASSPHRASE", passphrase);
+        }
+        command.output().unwrap()
+    };
+
+    let keygen = with_passphrase(
+        Some("hunter2"),
+        env!("CARGO_BIN_EXE_blvm-keygen"),
+        &["-o", "key.json", "--encrypt"],
+    );
+    assert!(keygen.status.success());
+    let key_file = std::fs::read_to_string(dir.path().join("key.json")).unwrap();
+    assert!(key_file.contains("ciphertext") && !key_file.contains("secret_key"));
+
+    let sign = |passphrase| {
+        with_passphrase(
+            passphrase,
+            env!("CARGO_BIN_EXE_blvm-sign-binary"),
+            &[
+                "-k",
+                "key.json",
+                "-o",
+                "release.sig.json",
+                "binary",
+                "--file",
+                "release.tar.gz",
+            ],
+        )
+    };
+
+    let wrong = sign(Some("hunter3"));
+    assert!(!wrong.status.success());
+    assert!(String::from_utf8_lossy(&wrong.stderr).contains("Wrong passphrase"));
+
+    // No terminal to prompt on
+    let missing = sign(None);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("BLLVM_KEY_PASSPHRASE"));
+
+    assert!(sign(Some("hunter2")).status.success());
+    assert!(dir.path().join("release.sig.json").exists());
+}
+
+#[test]
+fn test_sign_with_migrated_keystore() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("release.tar.gz"), b"release").unwrap();
+    let keygen = env!("CARGO_BIN_EXE_blvm-keygen");
+    assert!(run(keygen, dir.path(), &["-o", "alice.key.json"])
+        .status
+        .success());
+
+    let sign = |key: &str, output: &str| {
+        run(
+            env!("CARGO_BIN_EXE_blvm-sign-binary"),
+            dir.path(),
+            &[
+                "-k",
+                key,
+                "-o",
+                output,
+                "binary",
+                "--file",
+                "release.tar.gz",
+            ],
+        )
+    };
+
+    // The earlier format still signs, with a deprecation notice
+    let legacy = sign("alice.key.json", "legacy.sig.json");
+    assert!(legacy.status.success());
+    let stderr = String::from_utf8_lossy(&legacy.stderr);
+    assert!(
+        stderr.contains("deprecated key file format") && stderr.contains("blvm-keygen migrate"),
+        "{}",
+        stderr
+    );
+
+    let migrate = run(
+        keygen,
+        dir.path(),
+        &[
+            "-f",
+            "json",
+            "migrate",
+            "alice.key.json",
+            "-o",
+            "alice.keystore.json",
+            "--team",
+            "core",
+        ],
+    );
+    assert!(migrate.status.success());
+    let migrated: serde_json::Value = serde_json::from_slice(&migrate.stdout).unwrap();
+    assert_eq!(migrated["label"], "alice");
+    assert_eq!(migrated["team_id"], "core");
+    let keystore: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dir.path().join("alice.keystore.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(keystore["format_version"], 1);
+    assert_eq!(keystore["public_key"], migrated["public_key"]);
+
+    let signed = sign("alice.keystore.json", "keystore.sig.json");
+    assert!(
+        signed.status.success(),
+        "{}",
+        String::from_utf8_lossy(&signed.stderr)
+    );
+    assert!(!String::from_utf8_lossy(&signed.stderr).contains("deprecated"));
+    let signature = |name: &str| -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join(name)).unwrap()).unwrap()
+    };
+    assert_eq!(
+        signature("keystore.sig.json")["public_key"],
+        signature("legacy.sig.json")["public_key"]
+    );
+
+    // A keystore is a secret key too, and is already migrated
+    let refused = run(
+        env!("CARGO_BIN_EXE_blvm-sign-binary"),
+        dir.path(),
+        &[
+            "-k",
+            "alice.keystore.json",
+            "-o",
+            "secret.sig.json",
+            "binary",
+            "--file",
+            "alice.keystore.json",
+        ],
+    );
+    assert!(!refused.status.success());
+    let again = run(
+        keygen,
+        dir.path(),
+        &["migrate", "alice.keystore.json", "-o", "again.json"],
+    );
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("already a keystore"));
+}
+
+#[test]
+fn test_verify_shows_multisig_address() {
+    use blvm_sdk::cli::input::load_public_keys;
+    use blvm_sdk::governance::Multisig;
+
+    let dir = TempDir::new().unwrap();
+    sign_release(dir.path(), "alice");
+    sign_release(dir.path(), "bob");
+
+    let verify = run(
+        env!("CARGO_BIN_EXE_blvm-verify"),
+        dir.path(),
+        &[
+            "-f",
+            "json",
+            "-s",
+            "alice.sig.json,bob.sig.json",
+            "-p",
+            "alice.key.json,bob.key.json",
+            "-t",
+            "2-of-2",
+            "--show-address",
+            "testnet",
+            "release",
+            "--version",
+            "v1.0.0",
+            "--commit",
+            "abc123",
+        ],
+    );
+    assert!(verify.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&verify.stdout).unwrap();
+
+    let keys = [
+        dir.path().join("alice.key.json"),
+        dir.path().join("bob.key.json"),
+    ]
+    .map(|path| path.to_string_lossy().into_owned());
+    let multisig = Multisig::new(2, 2, load_public_keys(&keys).unwrap()).unwrap();
+    let expected = multisig
+        .to_address(bitcoin::Network::Testnet)
+        .unwrap()
+        .to_string();
+    assert_eq!(report["multisig_address"], expected.as_str());
+}
+
+#[test]
+fn test_generate_keypair_with_mnemonic() {
+    use blvm_sdk::governance::GovernanceKeypair;
+
+    let dir = TempDir::new().unwrap();
+    let generate = |args: &[&str]| {
+        run(
+            env!("CARGO_BIN_EXE_blvm-generate-keypair"),
+            dir.path(),
+            args,
+        )
+    };
+
+    // The mnemonic is printed and nothing is written to disk
+    let output = generate(&["--words", "12", "-f", "json"]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mnemonic = report["mnemonic"].as_str().unwrap();
+    assert_eq!(mnemonic.split(' ').count(), 12);
+    let recovered = GovernanceKeypair::from_mnemonic(mnemonic, "").unwrap();
+    assert_eq!(
+        report["public_key"],
+        hex::encode(recovered.public_key_bytes()).as_str()
+    );
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+    // With --output the mnemonic goes to the file only
+    let output = generate(&["-o", "backup.txt"]);
+    assert!(output.status.success());
+    let mnemonic = std::fs::read_to_string(dir.path().join("backup.txt")).unwrap();
+    assert_eq!(mnemonic.split_whitespace().count(), 24);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains(mnemonic.trim()));
+    assert!(stdout.contains("Mnemonic saved to: backup.txt"));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    // The secret key is written only when asked for
+    let output = generate(&["-o", "second.txt", "--export-key", "key.json"]);
+    assert!(output.status.success());
+    let key_file = std::fs::read_to_string(dir.path().join("key.json")).unwrap();
+    let mnemonic = std::fs::read_to_string(dir.path().join("second.txt")).unwrap();
+    let recovered = GovernanceKeypair::from_mnemonic(&mnemonic, "").unwrap();
+    assert!(key_file.contains(&hex::encode(recovered.secret_key_bytes())));
+
+    assert!(!generate(&["--words", "13"]).status.success());
+}
+
+#[test]
+fn test_verify_with_governance_config() {
+    let dir = TempDir::new().unwrap();
+    for name in ["alice", "bob", "carol"] {
+        sign_release(dir.path(), name);
+    }
+    let mut config = "threshold = \"2-of-3\"\n".to_string();
+    for name in ["alice", "bob", "carol"] {
+        config.push_str(&format!(
+            "\n[[members]]\ngithub = \"{}\"\npublic_key = \"{}\"\n",
+            name,
+            public_key(dir.path(), name)
+        ));
+    }
+    config.push_str(
+        "\n[nested]\nteams_required = 2\nmaintainers_per_team_required = 1\n\
+         \n[[nested.teams]]\nid = \"core\"\nmembers = [\"alice\", \"bob\"]\n\
+         \n[[nested.teams]]\nid = \"infra\"\nmembers = [\"carol\"]\n",
+    );
+    std::fs::write(dir.path().join("governance.toml"), config).unwrap();
+
+    let verify = |signatures: &str, extra: &[&str]| {
+        let mut args = vec!["-f", "json", "-s", signatures];
+        args.extend_from_slice(extra);
+        args.extend_from_slice(&["release", "--version", "v1.0.0", "--commit", "abc123"]);
+        run(env!("CARGO_BIN_EXE_blvm-verify"), dir.path(), &args)
+    };
+    let report = |output: Output| -> serde_json::Value {
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    // Two signatures meet 2-of-3, but only the core team approved
+    let config = ["--governance-config", "governance.toml"];
+    let partial = report(verify("alice.sig.json,bob.sig.json", &config));
+    assert_eq!(partial["valid_signatures"], 2);
+    assert_eq!(partial["teams_approved"], 1);
+    assert_eq!(partial["teams_required"], 2);
+    assert_eq!(partial["threshold_met"], false);
+
+    let full = report(verify("alice.sig.json,carol.sig.json", &config));
+    assert_eq!(full["teams_approved"], 2);
+    assert_eq!(full["threshold_met"], true);
+
+    // The config replaces --pubkeys and --threshold
+    let conflict = verify(
+        "alice.sig.json",
+        &[
+            "--governance-config",
+            "governance.toml",
+            "-p",
+            "alice.key.json",
+        ],
+    );
+    assert!(!conflict.status.success());
+    assert!(String::from_utf8_lossy(&conflict.stderr).contains("--governance-config"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sign_binary_with_signer_command() {
+    use secp256k1::{Message, Secp256k1, SecretKey};
+    use std::io::{BufRead, BufReader, Write};
+
+    // The secret stays in this process, like in an HSM: the signer command
+    // passes each hash through a pair of FIFOs and waits for the signature
+    let dir = TempDir::new().unwrap();
+    let secret = SecretKey::new(&mut rand::rngs::OsRng);
+    let public_key = blvm_sdk::governance::PublicKey::from_bytes(
+        &secret.public_key(&Secp256k1::new()).serialize(),
+    )
+    .unwrap();
+    for fifo in ["request", "response"] {
+        let made = Command::new("mkfifo")
+            .arg(dir.path().join(fifo))
+            .status()
+            .unwrap();
+        assert!(made.success());
+    }
+    let (request, response) = (dir.path().join("request"), dir.path().join("response"));
+    std::thread::spawn(move || loop {
+        let mut hash = String::new();
+        BufReader::new(std::fs::File::open(&request).unwrap())
+            .read_line(&mut hash)
+            .unwrap();
+        let digest: [u8; 32] = hex::decode(hash.trim()).unwrap().try_into().unwrap();
+        let signature = Secp256k1::new().sign_ecdsa(&Message::from_digest(digest), &secret);
+        let mut out = std::fs::File::create(&response).unwrap();
+        writeln!(out, "{}", hex::encode(signature.serialize_compact())).unwrap();
+    });
+
+    std::fs::write(dir.path().join("node.bin"), b"node binary").unwrap();
+    let pubkey = public_key.to_string();
+    let sign = |pubkey: &str| {
+        run(
+            env!("CARGO_BIN_EXE_blvm-sign-binary"),
+            dir.path(),
+            &[
+                "--signer-command",
+                "sh",
+                "--signer-arg",
+                "-c",
+                "--signer-arg",
+                "read hash; echo \"$hash\" > request; cat response",
+                "--pubkey",
+                pubkey,
+                "-o",
+                "sig.json",
+                "binary",
+                "--file",
+                "node.bin",
+            ],
+        )
+    };
+
+    let signed = sign(&pubkey);
+    assert!(signed.status.success(), "{:?}", signed);
+    assert!(dir
+        .path()
+        .join(format!("{}.journal", public_key.short()))
+        .exists());
+    std::fs::write(
+        dir.path().join("signer.pub.json"),
+        serde_json::json!({ "public_key": pubkey }).to_string(),
+    )
+    .unwrap();
+    let verify = run(
+        env!("CARGO_BIN_EXE_blvm-verify-binary"),
+        dir.path(),
+        &[
+            "-s",
+            "sig.json",
+            "-p",
+            "signer.pub.json",
+            "binary",
+            "--file",
+            "node.bin",
+        ],
+    );
+    assert!(verify.status.success(), "{:?}", verify);
+
+    // Signatures that do not verify against --pubkey are refused
+    let other = blvm_sdk::governance::GovernanceKeypair::generate()
+        .unwrap()
+        .public_key()
+        .to_string();
+    std::fs::remove_file(dir.path().join("sig.json")).unwrap();
+    let refused = sign(&other);
+    assert!(!refused.status.success());
+    assert!(!dir.path().join("sig.json").exists());
+
+    // A key file and a signer command do not mix
+    let both = run(
+        env!("CARGO_BIN_EXE_blvm-sign-binary"),
+        dir.path(),
+        &[
+            "-k",
+            "key.json",
+            "--signer-command",
+            "sh",
+            "--pubkey",
+            &pubkey,
+            "binary",
+            "--file",
+            "node.bin",
+        ],
+    );
+    assert!(!both.status.success());
+}
@@ -2,13 +2,19 @@
 //!
 //! Tests for node composition, module registry, lifecycle, and configuration.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::composition::config::NodeMetadata;
 use blvm_sdk::composition::schema::validate_config_schema;
 use blvm_sdk::composition::validation::validate_composition;
 use blvm_sdk::composition::{
-    ModuleHealth, ModuleLifecycle, ModuleRegistry, ModuleSource, ModuleSpec, ModuleStatus,
-    NetworkType, NodeComposer, NodeConfig, NodeSpec, NodeStatus, Result, ValidationResult,
+    CompositionError, DataDirConfig, ModuleHealth, ModuleLifecycle, ModuleRegistry, ModuleSource,
+    ModuleSpec, ModuleStatus, NetworkType, NodeComposer, NodeConfig, NodeSpec, NodeStatus, Probe,
+    SandboxConfig, ServiceConfig, ValidationResult,
 };
+use blvm_sdk::governance::oci::{sign_image, OciImageRef};
+use blvm_sdk::governance::{GovernanceKeypair, Multisig};
+use blvm_sdk::util::CancelToken;
 use std::collections::HashMap;
 use tempfile::TempDir;
 
@@ -25,7 +31,7 @@ fn create_temp_modules_dir() -> TempDir {
 fn test_module_registry_creation() {
     // Test creating a module registry
     let temp_dir = create_temp_modules_dir();
-    let registry = ModuleRegistry::new(temp_dir.path());
+    let _registry = ModuleRegistry::new(temp_dir.path());
 
     // Registry should be created
     // Note: We can't easily test discovery without actual modules, but we can test structure
@@ -66,6 +72,20 @@ fn test_module_registry_install_from_path() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_module_registry_install_cancelled() {
+    // A cancelled install leaves the registry unchanged
+    let temp_dir = create_temp_modules_dir();
+    let mut registry = ModuleRegistry::new(temp_dir.path());
+    let cancel = CancelToken::new();
+    cancel.cancel();
+
+    let source = ModuleSource::Path(temp_dir.path().to_path_buf());
+    let result = registry.install_module_with_cancel(source, &cancel);
+    assert!(matches!(result, Err(CompositionError::Cancelled(_))));
+    assert!(registry.list_modules().is_empty());
+}
+
 // ============================================================================
 // Phase 2: ModuleLifecycle Tests
 // ============================================================================
@@ -75,7 +95,7 @@ fn test_module_lifecycle_creation() {
     // Test creating a module lifecycle manager
     let temp_dir = create_temp_modules_dir();
     let registry = ModuleRegistry::new(temp_dir.path());
-    let lifecycle = ModuleLifecycle::new(registry);
+    let _lifecycle = ModuleLifecycle::new(registry);
 
     // Lifecycle should be created
     // Note: We can't easily test start/stop without actual modules
@@ -107,6 +127,7 @@ fn test_node_config_creation() {
             network: "testnet".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     assert_eq!(config.node.name, "test-node");
@@ -123,6 +144,7 @@ fn test_node_config_to_spec() {
             network: "mainnet".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     let spec = config.to_spec().unwrap();
@@ -140,6 +162,7 @@ fn test_node_config_to_spec_testnet() {
             network: "testnet".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     let spec = config.to_spec().unwrap();
@@ -156,6 +179,7 @@ fn test_node_config_to_spec_regtest() {
             network: "regtest".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     let spec = config.to_spec().unwrap();
@@ -172,6 +196,7 @@ fn test_node_config_invalid_network() {
             network: "invalid".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     let result = config.to_spec();
@@ -209,12 +234,20 @@ fn test_node_spec_with_modules() {
                 version: Some("1.0.0".to_string()),
                 enabled: true,
                 config: HashMap::new(),
+                external_dependencies: Vec::new(),
+                sandbox: SandboxConfig::default(),
+                service: ServiceConfig::default(),
+                data: DataDirConfig::default(),
             },
             ModuleSpec {
                 name: "module2".to_string(),
                 version: None,
                 enabled: false,
                 config: HashMap::new(),
+                external_dependencies: Vec::new(),
+                sandbox: SandboxConfig::default(),
+                service: ServiceConfig::default(),
+                data: DataDirConfig::default(),
             },
         ],
     };
@@ -236,6 +269,10 @@ fn test_module_spec_creation() {
         version: Some("1.0.0".to_string()),
         enabled: true,
         config: HashMap::new(),
+        external_dependencies: Vec::new(),
+        sandbox: SandboxConfig::default(),
+        service: ServiceConfig::default(),
+        data: DataDirConfig::default(),
     };
 
     assert_eq!(module_spec.name, "test-module");
@@ -251,6 +288,10 @@ fn test_module_spec_disabled() {
         version: None,
         enabled: false,
         config: HashMap::new(),
+        external_dependencies: Vec::new(),
+        sandbox: SandboxConfig::default(),
+        service: ServiceConfig::default(),
+        data: DataDirConfig::default(),
     };
 
     assert!(!module_spec.enabled);
@@ -268,6 +309,10 @@ fn test_module_spec_with_config() {
         version: None,
         enabled: true,
         config,
+        external_dependencies: Vec::new(),
+        sandbox: SandboxConfig::default(),
+        service: ServiceConfig::default(),
+        data: DataDirConfig::default(),
     };
 
     assert_eq!(module_spec.config.len(), 2);
@@ -356,6 +401,7 @@ fn test_validate_config_schema_valid() {
             network: "mainnet".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     let result = validate_config_schema(&config).unwrap();
@@ -373,6 +419,7 @@ fn test_validate_config_schema_empty_name() {
             network: "mainnet".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     let result = validate_config_schema(&config).unwrap();
@@ -390,6 +437,7 @@ fn test_validate_config_schema_invalid_network() {
             network: "invalid".to_string(),
         },
         modules: HashMap::new(),
+        features: HashMap::new(),
     };
 
     let result = validate_config_schema(&config).unwrap();
@@ -408,6 +456,11 @@ fn test_validate_config_schema_module_warning() {
             enabled: true,
             version: None,
             config: HashMap::new(),
+            external_dependencies: Vec::new(),
+            sandbox: SandboxConfig::default(),
+            service: ServiceConfig::default(),
+            data: DataDirConfig::default(),
+            required_features: Vec::new(),
         },
     );
 
@@ -418,6 +471,7 @@ fn test_validate_config_schema_module_warning() {
             network: "mainnet".to_string(),
         },
         modules,
+        features: HashMap::new(),
     };
 
     let result = validate_config_schema(&config).unwrap();
@@ -462,6 +516,10 @@ fn test_validate_composition_nonexistent_module() {
             version: None,
             enabled: true,
             config: HashMap::new(),
+            external_dependencies: Vec::new(),
+            sandbox: SandboxConfig::default(),
+            service: ServiceConfig::default(),
+            data: DataDirConfig::default(),
         }],
     };
 
@@ -486,6 +544,10 @@ fn test_validate_composition_disabled_module() {
             version: None,
             enabled: false, // Disabled, should be skipped
             config: HashMap::new(),
+            external_dependencies: Vec::new(),
+            sandbox: SandboxConfig::default(),
+            service: ServiceConfig::default(),
+            data: DataDirConfig::default(),
         }],
     };
 
@@ -581,6 +643,68 @@ fn test_module_source_git() {
     }
 }
 
+const IMAGE_DIGEST: &str =
+    "sha256:4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945";
+
+fn image_source(signatures: Vec<String>) -> ModuleSource {
+    ModuleSource::Oci {
+        repository: "ghcr.io/btcdecoded/lightning".to_string(),
+        digest: IMAGE_DIGEST.to_string(),
+        tag: Some("v1.0.0".to_string()),
+        signatures,
+    }
+}
+
+#[test]
+fn test_module_source_oci_unsigned_rejected() {
+    // Unsigned image-sourced modules must not be registered
+    let temp_dir = create_temp_modules_dir();
+    let keypair = GovernanceKeypair::generate().unwrap();
+    let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+    let mut registry = ModuleRegistry::new(temp_dir.path()).with_image_multisig(multisig);
+
+    let result = registry.install_module(image_source(vec![]));
+    assert!(result.is_err());
+    assert!(registry.get_module("lightning", None).is_err());
+}
+
+#[test]
+fn test_module_source_oci_signed_registered() {
+    // Image-sourced modules with threshold approval are registered
+    let temp_dir = create_temp_modules_dir();
+    let keypair = GovernanceKeypair::generate().unwrap();
+    let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+    let mut registry = ModuleRegistry::new(temp_dir.path()).with_image_multisig(multisig);
+
+    let image =
+        OciImageRef::new("ghcr.io/btcdecoded/lightning", IMAGE_DIGEST, Some("v1.0.0")).unwrap();
+    let signature = sign_image(keypair.secret_key.expose(), &image).unwrap();
+
+    let info = registry
+        .install_module(image_source(vec![hex::encode(signature.to_bytes())]))
+        .unwrap();
+    assert_eq!(info.name, "lightning");
+    assert_eq!(info.version, "v1.0.0");
+    assert!(registry.get_module("lightning", Some("v1.0.0")).is_ok());
+}
+
+#[test]
+fn test_module_source_oci_wrong_signer_rejected() {
+    // Signatures from keys outside the multisig are rejected
+    let temp_dir = create_temp_modules_dir();
+    let keypair = GovernanceKeypair::generate().unwrap();
+    let outsider = GovernanceKeypair::generate().unwrap();
+    let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
+    let mut registry = ModuleRegistry::new(temp_dir.path()).with_image_multisig(multisig);
+
+    let image =
+        OciImageRef::new("ghcr.io/btcdecoded/lightning", IMAGE_DIGEST, Some("v1.0.0")).unwrap();
+    let signature = sign_image(outsider.secret_key.expose(), &image).unwrap();
+
+    let result = registry.install_module(image_source(vec![hex::encode(signature.to_bytes())]));
+    assert!(result.is_err());
+}
+
 // ============================================================================
 // Phase 12: ValidationResult Tests
 // ============================================================================
@@ -613,3 +737,58 @@ fn test_validation_result_invalid() {
     assert_eq!(result.errors.len(), 2);
     assert_eq!(result.warnings.len(), 1);
 }
+
+#[test]
+fn test_node_config_external_dependencies() {
+    // External dependencies are parsed from TOML and carried into the spec
+    let config: NodeConfig = toml::from_str(
+        r#"
+        [node]
+        name = "test-node"
+        network = "regtest"
+
+        [modules.lightning]
+        version = "0.1.0"
+
+        [[modules.lightning.external_dependencies]]
+        name = "bitcoind-rpc"
+        deadline = "2m"
+        probe = { type = "tcp", address = "127.0.0.1:18443" }
+        "#,
+    )
+    .unwrap();
+
+    assert!(validate_config_schema(&config).unwrap().valid);
+
+    let spec = config.to_spec().unwrap();
+    let dependencies = &spec.modules[0].external_dependencies;
+    assert_eq!(dependencies.len(), 1);
+    assert_eq!(dependencies[0].name, "bitcoind-rpc");
+    assert_eq!(
+        dependencies[0].probe,
+        Probe::Tcp {
+            address: "127.0.0.1:18443".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_node_config_invalid_external_dependency() {
+    // Malformed probe durations are schema errors
+    let config: NodeConfig = toml::from_str(
+        r#"
+        [node]
+        name = "test-node"
+        network = "regtest"
+
+        [[modules.lightning.external_dependencies]]
+        name = "bitcoind-rpc"
+        deadline = "eventually"
+        probe = { type = "tcp", address = "127.0.0.1:18443" }
+        "#,
+    )
+    .unwrap();
+
+    let result = validate_config_schema(&config).unwrap();
+    assert!(!result.valid);
+}
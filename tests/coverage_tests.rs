@@ -2,6 +2,8 @@
 //!
 //! Additional tests to reach 90%+ coverage on governance crypto code.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::cli::input::{parse_base64, parse_comma_separated, parse_hex, parse_threshold};
 use blvm_sdk::cli::output::{OutputFormat, OutputFormatter};
 use blvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Multisig, PublicKey, Signature};
@@ -35,7 +37,7 @@ fn test_governance_keypair_serialization_edge_cases() {
 fn test_signature_der_serialization() {
     let keypair = GovernanceKeypair::generate().unwrap();
     let message = b"der serialization test";
-    let signature = sign_message(&keypair.secret_key, message).unwrap();
+    let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     let der_bytes = signature.to_der_bytes();
     assert!(!der_bytes.is_empty());
@@ -50,7 +52,7 @@ fn test_multisig_edge_cases() {
     let multisig = Multisig::new(1, 1, vec![keypair.public_key()]).unwrap();
 
     let message = b"1-of-1 test";
-    let signature = sign_message(&keypair.secret_key, message).unwrap();
+    let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     assert!(multisig.verify(message, &[signature]).unwrap());
 }
@@ -67,7 +69,7 @@ fn test_multisig_all_signatures() {
     let message = b"all signatures test";
     let signatures: Vec<_> = keypairs
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, message).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), message).unwrap())
         .collect();
 
     assert!(multisig.verify(message, &signatures).unwrap());
@@ -89,7 +91,7 @@ fn test_governance_message_edge_cases() {
         purpose: "测试".to_string(),
     };
     let signing_bytes = message.to_signing_bytes();
-    assert!(signing_bytes.len() > 0);
+    assert!(!signing_bytes.is_empty());
 }
 
 #[test]
@@ -143,16 +145,16 @@ fn test_cli_output_edge_cases() {
 fn test_verification_edge_cases() {
     let keypair = GovernanceKeypair::generate().unwrap();
     let message = b"verification edge case";
-    let _signature = sign_message(&keypair.secret_key, message).unwrap();
+    let _signature = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     // Test with empty message
     let empty_message = b"";
-    let empty_signature = sign_message(&keypair.secret_key, empty_message).unwrap();
+    let empty_signature = sign_message(keypair.secret_key.expose(), empty_message).unwrap();
     assert!(verify_signature(&empty_signature, empty_message, &keypair.public_key()).unwrap());
 
     // Test with very long message
     let long_message = vec![0u8; 10000];
-    let long_signature = sign_message(&keypair.secret_key, &long_message).unwrap();
+    let long_signature = sign_message(keypair.secret_key.expose(), &long_message).unwrap();
     assert!(verify_signature(&long_signature, &long_message, &keypair.public_key()).unwrap());
 }
 
@@ -173,7 +175,7 @@ fn test_multisig_collect_valid_signatures_edge_cases() {
     // Test with all valid signatures
     let signatures: Vec<_> = keypairs
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, message).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), message).unwrap())
         .collect();
     let valid_indices = multisig
         .collect_valid_signatures(message, &signatures)
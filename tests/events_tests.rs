@@ -0,0 +1,196 @@
+//! # Governance Event Tests
+//!
+//! Tests for governance events emitted by verification.
+
+#![cfg(all(feature = "events", feature = "signing"))]
+
+use blvm_sdk::governance::events::{self, GovernanceEvent};
+use blvm_sdk::governance::nested_multisig::{NestedMultisig, Team, TeamMaintainer};
+use blvm_sdk::governance::pin::PinStore;
+use blvm_sdk::governance::pipeline::{PipelineBuilder, RevocationStage, VerificationContext};
+use blvm_sdk::governance::{GovernanceKeypair, Multisig, Threshold};
+use blvm_sdk::sign_message;
+use std::sync::Mutex;
+
+/// The event hub is process-wide; serialize the tests that subscribe to it
+static HUB: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_failed_multisig_verification_event() {
+    let _guard = HUB.lock().unwrap();
+    let keypairs: Vec<_> = (0..3)
+        .map(|_| GovernanceKeypair::generate().unwrap())
+        .collect();
+    let multisig =
+        Multisig::new(2, 3, keypairs.iter().map(|kp| kp.public_key()).collect()).unwrap();
+    let mut events = events::subscribe();
+
+    // One valid signature and one over a different message
+    let message = b"release:v1.0.0";
+    let signatures = vec![
+        sign_message(keypairs[0].secret_key.expose(), message).unwrap(),
+        sign_message(keypairs[1].secret_key.expose(), b"release:v0.9.0").unwrap(),
+    ];
+    assert!(!multisig.verify(message, &signatures).unwrap());
+
+    assert_eq!(
+        events.try_recv().unwrap(),
+        GovernanceEvent::MultisigVerificationFailed {
+            threshold: Threshold::new(2, 3).unwrap(),
+            signatures: 2,
+            valid_signatures: 1,
+        }
+    );
+    assert!(events.try_recv().is_err());
+
+    // Too few signatures to check at all
+    assert!(multisig.verify(message, &signatures[..1]).is_err());
+    assert_eq!(
+        events.try_recv().unwrap(),
+        GovernanceEvent::MultisigVerificationFailed {
+            threshold: Threshold::new(2, 3).unwrap(),
+            signatures: 1,
+            valid_signatures: 0,
+        }
+    );
+}
+
+#[test]
+fn test_revoked_key_events() {
+    let _guard = HUB.lock().unwrap();
+    let keypair = GovernanceKeypair::generate().unwrap();
+    let pipeline = PipelineBuilder::with_builtin_stages()
+        .replace(RevocationStage {
+            revoked: vec![keypair.public_key()],
+        })
+        .build()
+        .unwrap();
+
+    let mut ctx = VerificationContext::new(b"binary:application:aaaa".to_vec(), "node", "aaaa");
+    ctx.signatures = vec![sign_message(keypair.secret_key.expose(), &ctx.message).unwrap()];
+    ctx.public_keys = vec![keypair.public_key()];
+
+    let mut events = events::subscribe();
+    let report = pipeline.run(&mut ctx);
+    assert!(!report.passed());
+
+    assert_eq!(
+        events.try_recv().unwrap(),
+        GovernanceEvent::RevokedKeyEncountered {
+            public_key: keypair.public_key(),
+        }
+    );
+    match events.try_recv().unwrap() {
+        GovernanceEvent::PolicyCheckFailed { stage, detail } => {
+            assert_eq!(stage, "revocation");
+            assert!(detail.contains(&keypair.public_key().to_string()));
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn test_failed_nested_multisig_event() {
+    let _guard = HUB.lock().unwrap();
+    let keypairs: Vec<_> = (0..4)
+        .map(|_| GovernanceKeypair::generate().unwrap())
+        .collect();
+    let teams: Vec<Team> = keypairs
+        .chunks(2)
+        .enumerate()
+        .map(|(t, members)| Team {
+            id: format!("team{}", t),
+            name: format!("Team {}", t),
+            maintainers: members
+                .iter()
+                .enumerate()
+                .map(|(m, kp)| TeamMaintainer {
+                    github: format!("team{}-maintainer{}", t, m),
+                    public_key: kp.public_key(),
+                })
+                .collect(),
+        })
+        .collect();
+    let multisig = NestedMultisig::new(teams, 2, 2).unwrap();
+    let mut events = events::subscribe();
+
+    // Team 0 approves; team 1 has only one of its two maintainers
+    let message = b"release:v1.0.0";
+    let signatures: Vec<_> = [
+        (0, "team0-maintainer0"),
+        (1, "team0-maintainer1"),
+        (2, "team1-maintainer0"),
+    ]
+    .iter()
+    .map(|&(i, github)| {
+        (
+            github.to_string(),
+            sign_message(keypairs[i].secret_key.expose(), message).unwrap(),
+        )
+    })
+    .collect();
+    assert!(
+        !multisig
+            .verify(message, &signatures)
+            .unwrap()
+            .inter_team_approved
+    );
+
+    assert_eq!(
+        events.try_recv().unwrap(),
+        GovernanceEvent::NestedMultisigVerificationFailed {
+            teams_approved: 1,
+            teams_required: 2,
+            maintainers_approved: 2,
+            maintainers_required: 4,
+        }
+    );
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn test_audit_record_event_after_save() {
+    let _guard = HUB.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let keys: Vec<_> = (0..2)
+        .map(|_| GovernanceKeypair::generate().unwrap().public_key())
+        .collect();
+    let mut store = PinStore::load(dir.path().join("pins.json")).unwrap();
+    store
+        .check("repo", &keys[..1], Threshold::new(1, 1).unwrap(), None)
+        .unwrap();
+    store.save().unwrap();
+
+    let mut events = events::subscribe();
+    store
+        .check(
+            "repo",
+            &keys,
+            Threshold::new(1, 2).unwrap(),
+            Some("added bob"),
+        )
+        .unwrap();
+    match events.try_recv().unwrap() {
+        GovernanceEvent::KeyChangeAccepted { source, note, .. } => {
+            assert_eq!((source.as_str(), note.as_str()), ("repo", "added bob"));
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+    // The audit record is reported once it is on disk
+    assert!(events.try_recv().is_err());
+
+    store.save().unwrap();
+    let recorded = store.get("repo").unwrap().changes[0].clone();
+    assert_eq!(
+        events.try_recv().unwrap(),
+        GovernanceEvent::AuditRecordAppended {
+            source: "repo".to_string(),
+            accepted_at: recorded.accepted_at,
+            change: recorded.change,
+            note: "added bob".to_string(),
+        }
+    );
+    store.save().unwrap();
+    assert!(events.try_recv().is_err());
+}
@@ -2,6 +2,8 @@
 //!
 //! Comprehensive integration tests for governance crypto operations.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Multisig};
 use blvm_sdk::sign_message;
 
@@ -25,7 +27,7 @@ fn test_complete_governance_workflow() {
     // Sign with 3 keys (meets threshold)
     let signatures: Vec<_> = keypairs[0..3]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     // Verify multisig
@@ -51,7 +53,7 @@ fn test_insufficient_signatures() {
     // Sign with only 2 keys (below threshold)
     let signatures: Vec<_> = keypairs[0..2]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let result = multisig.verify(&message.to_signing_bytes(), &signatures);
@@ -67,7 +69,8 @@ fn test_different_message_types() {
         version: "v1.0.0".to_string(),
         commit_hash: "abc123".to_string(),
     };
-    let release_sig = sign_message(&keypair.secret_key, &release_msg.to_signing_bytes()).unwrap();
+    let release_sig =
+        sign_message(keypair.secret_key.expose(), &release_msg.to_signing_bytes()).unwrap();
     assert!(blvm_sdk::governance::verify_signature(
         &release_sig,
         &release_msg.to_signing_bytes(),
@@ -80,7 +83,8 @@ fn test_different_message_types() {
         module_name: "lightning".to_string(),
         version: "v2.0.0".to_string(),
     };
-    let module_sig = sign_message(&keypair.secret_key, &module_msg.to_signing_bytes()).unwrap();
+    let module_sig =
+        sign_message(keypair.secret_key.expose(), &module_msg.to_signing_bytes()).unwrap();
     assert!(blvm_sdk::governance::verify_signature(
         &module_sig,
         &module_msg.to_signing_bytes(),
@@ -93,7 +97,8 @@ fn test_different_message_types() {
         amount: 1000000,
         purpose: "development".to_string(),
     };
-    let budget_sig = sign_message(&keypair.secret_key, &budget_msg.to_signing_bytes()).unwrap();
+    let budget_sig =
+        sign_message(keypair.secret_key.expose(), &budget_msg.to_signing_bytes()).unwrap();
     assert!(blvm_sdk::governance::verify_signature(
         &budget_sig,
         &budget_msg.to_signing_bytes(),
@@ -113,7 +118,8 @@ fn test_signature_cross_verification() {
     };
 
     // Sign with keypair1
-    let signature = sign_message(&keypair1.secret_key, &message.to_signing_bytes()).unwrap();
+    let signature =
+        sign_message(keypair1.secret_key.expose(), &message.to_signing_bytes()).unwrap();
 
     // Verify with keypair1 (should succeed)
     assert!(blvm_sdk::governance::verify_signature(
@@ -149,7 +155,7 @@ fn test_multisig_edge_cases() {
     // Sign with exactly 6 keys (meets threshold)
     let signatures: Vec<_> = keypairs[0..6]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let verified = multisig
@@ -160,7 +166,7 @@ fn test_multisig_edge_cases() {
     // Sign with 7 keys (above threshold, should still work)
     let signatures: Vec<_> = keypairs
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let verified = multisig
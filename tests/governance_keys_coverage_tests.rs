@@ -1,5 +1,7 @@
 //! Additional tests for governance keys module to improve coverage.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::governance::{GovernanceError, GovernanceKeypair, PublicKey};
 
 #[test]
@@ -9,6 +11,8 @@ fn test_governance_keypair_debug_format() {
     assert!(debug_str.contains("GovernanceKeypair"));
     assert!(debug_str.contains("secret_key"));
     assert!(debug_str.contains("public_key"));
+    assert!(debug_str.contains(&keypair.public_key().to_string()));
+    assert!(!debug_str.contains(&hex::encode(keypair.secret_key_bytes())));
 }
 
 #[test]
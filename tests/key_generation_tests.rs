@@ -2,6 +2,8 @@
 //!
 //! Tests for key generation edge cases and validation.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::governance::GovernanceKeypair;
 
 #[test]
@@ -17,7 +19,7 @@ fn test_keypair_generation_randomness() {
     assert_eq!(unique_keys.len(), public_keys.len());
 
     // All secret keys should be unique
-    let secret_keys: Vec<_> = keypairs.iter().map(|kp| kp.secret_key_bytes()).collect();
+    let secret_keys: Vec<_> = keypairs.iter().map(|kp| *kp.secret_key_bytes()).collect();
     let unique_secrets: std::collections::HashSet<_> = secret_keys.iter().collect();
     assert_eq!(unique_secrets.len(), secret_keys.len());
 }
@@ -46,7 +48,7 @@ fn test_keypair_serialization_roundtrip() {
 
     // Test secret key serialization
     let secret_bytes = keypair.secret_key_bytes();
-    let reconstructed_keypair = GovernanceKeypair::from_secret_key(&secret_bytes).unwrap();
+    let reconstructed_keypair = GovernanceKeypair::from_secret_key(&*secret_bytes).unwrap();
     assert_eq!(keypair.public_key(), reconstructed_keypair.public_key());
 }
 
@@ -85,7 +87,10 @@ fn test_keypair_consistency() {
     let keypair = GovernanceKeypair::generate().unwrap();
 
     // Public key should be derivable from secret key
-    let derived_pubkey = keypair.secret_key.public_key(&secp256k1::Secp256k1::new());
+    let derived_pubkey = keypair
+        .secret_key
+        .expose()
+        .public_key(&secp256k1::Secp256k1::new());
     assert_eq!(keypair.public_key().inner, derived_pubkey);
 }
 
@@ -114,3 +119,27 @@ fn test_keypair_equality() {
     // Same keypair should be equal to itself
     assert_eq!(keypair1.public_key(), keypair1.public_key());
 }
+
+#[test]
+fn test_encrypted_key_fixture() {
+    // test-maintainer-1.key, encrypted under a reduced scrypt cost
+    let fixture = include_str!("fixtures/encrypted-key.json");
+
+    let keypair =
+        GovernanceKeypair::from_encrypted_json(fixture, "correct horse battery staple").unwrap();
+    assert_eq!(
+        hex::encode(keypair.secret_key_bytes()),
+        "d0c999af2e0ef68cff496fab5bbcf78d5967c90fc4c91c1c994b576a146ee7cb"
+    );
+    assert_eq!(
+        keypair.public_key().to_string(),
+        "0335573cdcac8f622dddc6a6799050d3739b016f389b0c74f954e27c9d07797363"
+    );
+
+    let err = GovernanceKeypair::from_encrypted_json(fixture, "Correct horse battery staple")
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        blvm_sdk::governance::GovernanceError::InvalidKey(_)
+    ));
+}
@@ -0,0 +1,90 @@
+//! # Manifest Signing Tests
+//!
+//! Tests for signing many release files through one manifest signature.
+
+#![cfg(feature = "signing")]
+
+use blvm_sdk::governance::manifest::{Manifest, MemberMismatch};
+use blvm_sdk::governance::GovernanceKeypair;
+use blvm_sdk::{sign_message, verify_signature};
+use std::fs;
+use tempfile::TempDir;
+
+/// A release directory with three files, one in a subdirectory
+fn release_dir() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("bin")).unwrap();
+    fs::write(dir.path().join("bin/blvm-node"), b"\x7fELF node").unwrap();
+    fs::write(dir.path().join("bin/blvm-cli"), b"\x7fELF cli").unwrap();
+    fs::write(dir.path().join("README.md"), b"# blvm v1.0.0\n").unwrap();
+    dir
+}
+
+#[test]
+fn test_sign_and_verify_manifest() {
+    let dir = release_dir();
+    let manifest_path = dir.path().join("SHA256SUMS");
+    let keypair = GovernanceKeypair::generate().unwrap();
+
+    // Sign: hash the files, write the manifest, sign its digest once
+    let manifest = Manifest::from_dir(dir.path(), &[&manifest_path]).unwrap();
+    assert_eq!(
+        manifest.entries().keys().collect::<Vec<_>>(),
+        ["README.md", "bin/blvm-cli", "bin/blvm-node"]
+    );
+    fs::write(&manifest_path, manifest.to_sums()).unwrap();
+    let signature = sign_message(
+        keypair.secret_key.expose(),
+        &manifest.signing_bytes(Some("v1.0.0")),
+    )
+    .unwrap();
+
+    // Re-signing must not pick up the manifest itself
+    assert_eq!(
+        Manifest::from_dir(dir.path(), &[&manifest_path]).unwrap(),
+        manifest
+    );
+
+    // Verify: manifest against the signature, files against the manifest
+    let loaded = Manifest::parse(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    assert!(verify_signature(
+        &signature,
+        &loaded.signing_bytes(Some("v1.0.0")),
+        &keypair.public_key()
+    )
+    .unwrap());
+    assert!(loaded.verify_files(dir.path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_tampered_file_detected() {
+    let dir = release_dir();
+    let keypair = GovernanceKeypair::generate().unwrap();
+    let no_exclusions: &[&str] = &[];
+
+    let manifest = Manifest::from_dir(dir.path(), no_exclusions).unwrap();
+    let signature =
+        sign_message(keypair.secret_key.expose(), &manifest.signing_bytes(None)).unwrap();
+
+    fs::write(dir.path().join("bin/blvm-cli"), b"\x7fELF backdoored cli").unwrap();
+
+    // The manifest signature still verifies; the tampered file does not match it
+    assert!(verify_signature(
+        &signature,
+        &manifest.signing_bytes(None),
+        &keypair.public_key()
+    )
+    .unwrap());
+    let mismatches = manifest.verify_files(dir.path()).unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert!(matches!(
+        &mismatches[0],
+        MemberMismatch::Modified { path, .. } if path == "bin/blvm-cli"
+    ));
+
+    fs::remove_file(dir.path().join("README.md")).unwrap();
+    assert!(manifest
+        .verify_files(dir.path())
+        .unwrap()
+        .contains(&MemberMismatch::Missing("README.md".to_string())));
+}
@@ -1,5 +1,7 @@
 //! Additional tests for multisig module to improve coverage.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::governance::{GovernanceError, GovernanceKeypair, Multisig};
 use blvm_sdk::sign_message;
 
@@ -111,7 +113,7 @@ fn test_multisig_verify_edge_cases() {
     let signatures: Vec<_> = (0..10)
         .map(|i| {
             let keypair = &keypairs[i % 5];
-            sign_message(&keypair.secret_key, message).unwrap()
+            sign_message(keypair.secret_key.expose(), message).unwrap()
         })
         .collect();
 
@@ -138,7 +140,7 @@ fn test_multisig_collect_valid_signatures_edge_cases() {
     // Test with all valid signatures
     let signatures: Vec<_> = keypairs
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, message).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), message).unwrap())
         .collect();
 
     let result = multisig.collect_valid_signatures(message, &signatures);
@@ -150,7 +152,8 @@ fn test_multisig_collect_valid_signatures_edge_cases() {
     // Test with mixed valid/invalid signatures
     let mut mixed_signatures = signatures.clone();
     // Replace one signature with an invalid one
-    mixed_signatures[2] = sign_message(&keypairs[0].secret_key, b"different message").unwrap();
+    mixed_signatures[2] =
+        sign_message(keypairs[0].secret_key.expose(), b"different message").unwrap();
 
     let result = multisig.collect_valid_signatures(message, &mixed_signatures);
     assert!(result.is_ok());
@@ -224,7 +227,7 @@ fn test_multisig_single_key() {
     assert_eq!(multisig.total(), 1);
 
     let message = b"test message";
-    let signature = sign_message(&keypair.secret_key, message).unwrap();
+    let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
     let result = multisig.verify(message, &[signature]);
     assert!(result.is_ok());
     assert!(result.unwrap());
@@ -2,6 +2,8 @@
 //!
 //! Tests for multisig threshold validation and signature collection.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::governance::{GovernanceKeypair, GovernanceMessage, Multisig};
 use blvm_sdk::sign_message;
 
@@ -73,7 +75,7 @@ fn test_multisig_verification() {
     // Sign with 3 keys (meets threshold)
     let signatures: Vec<_> = keypairs[0..3]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let verified = multisig
@@ -98,7 +100,7 @@ fn test_multisig_insufficient_signatures() {
     // Sign with only 2 keys (below threshold)
     let signatures: Vec<_> = keypairs[0..2]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let result = multisig.verify(&message.to_signing_bytes(), &signatures);
@@ -121,7 +123,7 @@ fn test_multisig_excess_signatures() {
     // Sign with all 5 keys (above threshold, should still work)
     let signatures: Vec<_> = keypairs
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let verified = multisig
@@ -146,7 +148,7 @@ fn test_multisig_collect_valid_signatures() {
     // Sign with 3 keys
     let signatures: Vec<_> = keypairs[0..3]
         .iter()
-        .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+        .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
         .collect();
 
     let valid_indices = multisig
@@ -175,7 +177,7 @@ fn test_multisig_mixed_valid_invalid_signatures() {
 
     // Add 2 valid signatures
     for kp in &keypairs[0..2] {
-        let sig = sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap();
+        let sig = sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap();
         signatures.push(sig);
     }
 
@@ -184,12 +186,16 @@ fn test_multisig_mixed_valid_invalid_signatures() {
         version: "v2.0.0".to_string(),
         commit_hash: "def456".to_string(),
     };
-    let invalid_sig =
-        sign_message(&keypairs[2].secret_key, &wrong_message.to_signing_bytes()).unwrap();
+    let invalid_sig = sign_message(
+        keypairs[2].secret_key.expose(),
+        &wrong_message.to_signing_bytes(),
+    )
+    .unwrap();
     signatures.push(invalid_sig);
 
     // Add 1 more valid signature
-    let valid_sig = sign_message(&keypairs[3].secret_key, &message.to_signing_bytes()).unwrap();
+    let valid_sig =
+        sign_message(keypairs[3].secret_key.expose(), &message.to_signing_bytes()).unwrap();
     signatures.push(valid_sig);
 
     let valid_indices = multisig
@@ -221,7 +227,7 @@ fn test_multisig_edge_case_thresholds() {
         // Sign with exactly the threshold number of keys
         let signatures: Vec<_> = keypairs[0..threshold]
             .iter()
-            .map(|kp| sign_message(&kp.secret_key, &message.to_signing_bytes()).unwrap())
+            .map(|kp| sign_message(kp.secret_key.expose(), &message.to_signing_bytes()).unwrap())
             .collect();
 
         let verified = multisig
@@ -0,0 +1,110 @@
+//! # Verification Pipeline Tests
+//!
+//! Tests for custom verification stages registered by a downstream crate.
+
+#![cfg(feature = "signing")]
+
+use blvm_sdk::governance::pipeline::{
+    PipelineBuilder, PolicyConfig, Requirement, StageResult, VerificationContext, VerificationStage,
+};
+use blvm_sdk::governance::GovernanceKeypair;
+use blvm_sdk::sign_message;
+use std::collections::HashSet;
+
+/// Requires the artifact hash to appear in an internal transparency log
+struct TransparencyLogStage {
+    logged: HashSet<String>,
+}
+
+impl VerificationStage for TransparencyLogStage {
+    fn name(&self) -> &str {
+        "transparency-log"
+    }
+
+    fn after(&self) -> &[&str] {
+        &["hash"]
+    }
+
+    fn check(&self, ctx: &mut VerificationContext) -> StageResult {
+        if self.logged.contains(&ctx.artifact_hash) {
+            StageResult::Pass("artifact is logged".to_string())
+        } else {
+            StageResult::Fail(format!("{} is not in the log", ctx.artifact_hash))
+        }
+    }
+}
+
+fn log_stage(hashes: &[&str]) -> TransparencyLogStage {
+    TransparencyLogStage {
+        logged: hashes.iter().map(|h| h.to_string()).collect(),
+    }
+}
+
+fn signed_context(artifact_hash: &str) -> VerificationContext {
+    let keypair = GovernanceKeypair::generate().unwrap();
+    let message = format!("binary:application:{}", artifact_hash).into_bytes();
+
+    let mut ctx = VerificationContext::new(message, "blvm-node", artifact_hash);
+    ctx.recorded_hashes = vec![artifact_hash.to_string()];
+    ctx.signatures = vec![sign_message(keypair.secret_key.expose(), &ctx.message).unwrap()];
+    ctx.public_keys = vec![keypair.public_key()];
+    ctx
+}
+
+#[test]
+fn test_custom_stage_pass() {
+    let pipeline = PipelineBuilder::with_builtin_stages()
+        .stage_after("hash", log_stage(&["aaaa"]), Requirement::Required)
+        .build()
+        .unwrap();
+    assert_eq!(pipeline.stage_names()[1], "transparency-log");
+
+    let report = pipeline.run(&mut signed_context("aaaa"));
+    assert_eq!(
+        report.entry("transparency-log").unwrap().result,
+        StageResult::Pass("artifact is logged".to_string())
+    );
+    assert!(report.passed());
+    assert_eq!(report.exit_code(), 0);
+}
+
+#[test]
+fn test_custom_stage_required_failure() {
+    let pipeline = PipelineBuilder::with_builtin_stages()
+        .stage_after("hash", log_stage(&["aaaa"]), Requirement::Required)
+        .build()
+        .unwrap();
+
+    let report = pipeline.run(&mut signed_context("bbbb"));
+    assert!(report.entry("transparency-log").unwrap().result.is_fail());
+    assert!(!report.entry("signature").unwrap().result.is_fail());
+    assert!(!report.passed());
+    assert_eq!(report.exit_code(), 1);
+    assert!(report.to_string().contains("[FAIL] transparency-log"));
+}
+
+#[test]
+fn test_custom_stage_advisory_failure() {
+    let pipeline = PolicyConfig::default()
+        .builder()
+        .unwrap()
+        .stage(log_stage(&[]), Requirement::Advisory)
+        .build()
+        .unwrap();
+
+    let report = pipeline.run(&mut signed_context("cccc"));
+    assert!(report.passed());
+    assert_eq!(report.exit_code(), 0);
+    assert_eq!(report.warnings().count(), 1);
+}
+
+#[test]
+fn test_custom_stage_ordering_enforced() {
+    let err = PipelineBuilder::with_builtin_stages()
+        .stage_before("hash", log_stage(&[]), Requirement::Required)
+        .build()
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("stage 'transparency-log' must run after 'hash'"));
+}
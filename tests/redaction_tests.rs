@@ -0,0 +1,110 @@
+//! # Redaction Tests
+//!
+//! Leak hunt: secrets planted as a canary in configuration, key files and
+//! inputs must never show up in formatted output, errors or serialized
+//! reports.
+
+use blvm_sdk::cli::input::load_secret_key;
+use blvm_sdk::cli::inspect::{diff_signature_files, SignatureFile};
+use blvm_sdk::util::redact::REDACTED;
+use blvm_sdk::util::Redacted;
+use serde_json::json;
+use tempfile::TempDir;
+
+const CANARY: &str = "CANARY-5e1f0a77c3d94b2a-do-not-print";
+
+fn assert_no_canary(what: &str, output: &str) {
+    assert!(
+        !output.contains(CANARY) && !output.contains("5e1f0a77c3d94b2a"),
+        "secret leaked through {}: {}",
+        what,
+        output
+    );
+}
+
+#[test]
+fn test_redacted_value_never_formats() {
+    let secret: Redacted<String> = CANARY.parse().unwrap();
+    let holder = Some(vec![secret.clone()]);
+
+    assert_no_canary("Display", &secret.to_string());
+    assert_no_canary("Debug", &format!("{:?} {:#?}", holder, holder));
+    assert_no_canary("JSON", &serde_json::to_string(&holder).unwrap());
+    assert_no_canary(
+        "TOML",
+        &toml::to_string(&json!({ "token": secret })).unwrap(),
+    );
+    assert_eq!(secret.to_string(), REDACTED);
+    assert_eq!(secret.expose(), CANARY);
+}
+
+#[cfg(feature = "net")]
+#[test]
+fn test_notify_secret_redacted() {
+    use blvm_sdk::governance::notify::{load_secret, NotifyTarget};
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("secret");
+    std::fs::write(&path, format!("{}\n", CANARY)).unwrap();
+    let target = NotifyTarget {
+        url: "https://example.com/hook".to_string(),
+        secret: Some(load_secret(&path).unwrap()),
+    };
+
+    assert_no_canary("notify target", &format!("{:?}", target));
+}
+
+#[test]
+fn test_key_file_errors_redacted() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("key.json");
+    std::fs::write(
+        &path,
+        json!({ "public_key": "00", "secret_key": CANARY }).to_string(),
+    )
+    .unwrap();
+
+    let err = load_secret_key(path.to_str().unwrap()).unwrap_err();
+    assert_no_canary("key file error", &format!("{} {:?}", err, err));
+}
+
+#[test]
+fn test_inspect_diff_redacted() {
+    let file = |token: &str| {
+        SignatureFile::from_value(&json!({
+            "signature": "00",
+            "metadata": {
+                "version": "1.0",
+                "api_token": token,
+                "build": {"signing_password": token},
+            },
+        }))
+        .unwrap()
+    };
+    let diff = diff_signature_files(&file(CANARY), &file("other"));
+
+    // The difference is reported, with a placeholder for its value
+    assert!(!diff.is_equivalent());
+    let text = diff.to_string();
+    assert!(text.contains("'api_token'") && text.contains(REDACTED));
+    assert_no_canary("diff text", &text);
+    assert_no_canary("diff JSON", &diff.to_json().to_string());
+    assert_no_canary("diff Debug", &format!("{:?}", diff));
+}
+
+#[cfg(feature = "signing")]
+#[test]
+fn test_signing_secrets_redacted() {
+    use blvm_sdk::governance::bip39::{generate_mnemonic, mnemonic_to_entropy, EntropyStrength};
+    use blvm_sdk::governance::GovernanceKeypair;
+
+    let mut mnemonic = generate_mnemonic(EntropyStrength::Bits128).unwrap();
+    mnemonic[3] = CANARY.to_string();
+    let err = mnemonic_to_entropy(&mnemonic).unwrap_err();
+    assert_no_canary("mnemonic error", &format!("{} {:?}", err, err));
+
+    let keypair = GovernanceKeypair::generate().unwrap();
+    let secret_hex = hex::encode(keypair.secret_key_bytes());
+    let debug = format!("{:?} {}", keypair, keypair);
+    assert!(!debug.contains(&secret_hex), "{}", debug);
+}
@@ -2,6 +2,8 @@
 //!
 //! Tests for signature creation and verification.
 
+#![cfg(feature = "signing")]
+
 use blvm_sdk::governance::{GovernanceKeypair, Signature};
 use blvm_sdk::sign_message;
 
@@ -10,7 +12,7 @@ fn test_signature_creation_and_verification() {
     let keypair = GovernanceKeypair::generate().unwrap();
     let message = b"test message";
 
-    let signature = sign_message(&keypair.secret_key, message).unwrap();
+    let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     let verified =
         blvm_sdk::governance::verify_signature(&signature, message, &keypair.public_key()).unwrap();
@@ -23,7 +25,7 @@ fn test_signature_serialization_roundtrip() {
     let keypair = GovernanceKeypair::generate().unwrap();
     let message = b"test message";
 
-    let signature = sign_message(&keypair.secret_key, message).unwrap();
+    let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     // Serialize and deserialize
     let signature_bytes = signature.to_bytes();
@@ -45,9 +47,9 @@ fn test_signature_with_different_messages() {
     let message1 = b"message 1";
     let message2 = b"message 2";
 
-    let signature1 = sign_message(&keypair.secret_key, message1).unwrap();
+    let signature1 = sign_message(keypair.secret_key.expose(), message1).unwrap();
 
-    let signature2 = sign_message(&keypair.secret_key, message2).unwrap();
+    let signature2 = sign_message(keypair.secret_key.expose(), message2).unwrap();
 
     // Signatures should be different
     assert_ne!(signature1, signature2);
@@ -80,7 +82,7 @@ fn test_signature_with_different_keys() {
     let keypair2 = GovernanceKeypair::generate().unwrap();
     let message = b"test message";
 
-    let signature = sign_message(&keypair1.secret_key, message).unwrap();
+    let signature = sign_message(keypair1.secret_key.expose(), message).unwrap();
 
     // Should verify with keypair1
     assert!(
@@ -101,9 +103,9 @@ fn test_signature_deterministic() {
     let message = b"deterministic test";
 
     // Sign the same message multiple times
-    let signature1 = sign_message(&keypair.secret_key, message).unwrap();
+    let signature1 = sign_message(keypair.secret_key.expose(), message).unwrap();
 
-    let signature2 = sign_message(&keypair.secret_key, message).unwrap();
+    let signature2 = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     // Signatures may be the same or different (implementation dependent)
     // Both should be valid regardless
@@ -143,7 +145,7 @@ fn test_signature_display_format() {
     let keypair = GovernanceKeypair::generate().unwrap();
     let message = b"display test";
 
-    let signature = sign_message(&keypair.secret_key, message).unwrap();
+    let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     let display_str = format!("{}", signature);
     let expected_hex = hex::encode(signature.to_bytes());
@@ -158,7 +160,7 @@ fn test_signature_der_format() {
     let keypair = GovernanceKeypair::generate().unwrap();
     let message = b"der test";
 
-    let signature = sign_message(&keypair.secret_key, message).unwrap();
+    let signature = sign_message(keypair.secret_key.expose(), message).unwrap();
 
     let der_bytes = signature.to_der_bytes();
 
@@ -169,3 +171,43 @@ fn test_signature_der_format() {
     assert!(der_bytes.len() >= 70); // Minimum DER signature length
     assert!(der_bytes.len() <= 72); // Maximum DER signature length
 }
+
+#[test]
+fn test_signature_file_fixture() {
+    use blvm_sdk::governance::aggregation::SignatureFile;
+    use blvm_sdk::governance::SignatureRecord;
+
+    // Written by blvm-sign-binary before signature files were typed: key 1
+    // signing "Satoshi Nakamoto", with the hex of "unknown" as signer
+    let fixture = include_str!("fixtures/binary-signature.json");
+
+    let record = SignatureRecord::from_json(fixture.as_bytes()).unwrap();
+    let public_key = record.public_key.clone().unwrap();
+    assert_eq!(
+        public_key.to_string(),
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+    );
+    assert!(blvm_sdk::governance::verify_signature(
+        &record.signature,
+        b"Satoshi Nakamoto",
+        &public_key
+    )
+    .unwrap());
+    assert_eq!(
+        record.signer.as_deref(),
+        Some(hex::encode("unknown").as_str())
+    );
+    assert_eq!(
+        record.metadata.as_ref().unwrap().binary_type.as_deref(),
+        Some("consensus")
+    );
+    assert!(record.timestamp_signature.is_some());
+
+    // Written back, it reads the same, typed or not
+    let json = record.to_json_pretty();
+    assert_eq!(SignatureRecord::from_json(json.as_bytes()).unwrap(), record);
+    assert_eq!(
+        SignatureFile::from_json(json.as_bytes()).unwrap(),
+        SignatureFile::from_json(fixture.as_bytes()).unwrap()
+    );
+}
@@ -0,0 +1,116 @@
+//! # Verify-Only Tests
+//!
+//! Verification against fixed signatures, so it also runs in verify-only
+//! builds (`--no-default-features`), which cannot create signatures.
+
+use blvm_sdk::governance::nested_multisig::{NestedMultisig, Team, TeamMaintainer};
+use blvm_sdk::governance::{GovernanceMessage, Multisig, PublicKey, Signature};
+use blvm_sdk::verify_signature;
+
+/// Compressed public keys and compact signatures of `RELEASE:v1.0.0:abc123`
+const SIGNERS: [(&str, &str); 3] = [
+    (
+        "039997a497d964fc1a62885b05a51166a65a90df00492c8d7cf61d6accf54803be",
+        "50ab92dd27722cc9fb18fe8a1325aa206931d07630dfa8ce6a6c52a149e0b2c40f83e50761ba7c5a4994c785040d6b78c3c63d0cd800a3124183f6078aa00fb6",
+    ),
+    (
+        "024edfcf9dfe6c0b5c83d1ab3f78d1b39a46ebac6798e08e19761f5ed89ec83c10",
+        "49f19016aea3f536e953994392932ee96c8dcfe6b2c24f70ab024aef71dfa8b06f11930ced4d7353695c8df7f3b86f5d5fdcb008a3878fd0012af65ed2844cb4",
+    ),
+    (
+        "029094567ba7245794198952f68e5723ac5866ad2f67dd97223db40e14c15b092e",
+        "06c2236f6f0b9a1a395b76b0faec984845d1e7cb775962a5cb800b7b9db383092d78b09e74dbee7264a637c1c40994ae2da5f92b772613dc55f5d631d83fd6ff",
+    ),
+];
+
+/// DER encoding of the first signature
+const FIRST_DER: &str = "3044022050ab92dd27722cc9fb18fe8a1325aa206931d07630dfa8ce6a6c52a149e0b2c402200f83e50761ba7c5a4994c785040d6b78c3c63d0cd800a3124183f6078aa00fb6";
+
+fn message() -> Vec<u8> {
+    GovernanceMessage::Release {
+        version: "v1.0.0".to_string(),
+        commit_hash: "abc123".to_string(),
+    }
+    .to_signing_bytes()
+}
+
+fn public_keys() -> Vec<PublicKey> {
+    SIGNERS
+        .iter()
+        .map(|(key, _)| PublicKey::from_bytes(&hex::decode(key).unwrap()).unwrap())
+        .collect()
+}
+
+fn signatures() -> Vec<Signature> {
+    SIGNERS
+        .iter()
+        .map(|(_, signature)| Signature::from_bytes(&hex::decode(signature).unwrap()).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_fixed_signatures_verify() {
+    let keys = public_keys();
+    for (signature, key) in signatures().iter().zip(&keys) {
+        assert!(verify_signature(signature, &message(), key).unwrap());
+        assert!(!verify_signature(signature, b"RELEASE:v1.0.1:abc123", key).unwrap());
+    }
+    assert!(!verify_signature(&signatures()[0], &message(), &keys[1]).unwrap());
+
+    let der = Signature::from_der_strict(&hex::decode(FIRST_DER).unwrap()).unwrap();
+    assert_eq!(der, signatures()[0]);
+}
+
+#[test]
+fn test_multisig_with_fixed_signatures() {
+    let multisig = Multisig::new(2, 3, public_keys()).unwrap();
+    let signatures = signatures();
+
+    assert!(multisig.verify(&message(), &signatures[..2]).unwrap());
+    assert_eq!(
+        multisig
+            .collect_valid_signatures(&message(), &signatures[1..])
+            .unwrap(),
+        vec![1, 2]
+    );
+    assert!(multisig.verify(&message(), &signatures[..1]).is_err());
+}
+
+#[test]
+fn test_nested_multisig_with_fixed_signatures() {
+    let keys = public_keys();
+    let maintainer = |github: &str, key: &PublicKey| TeamMaintainer {
+        github: github.to_string(),
+        public_key: key.clone(),
+    };
+    let teams = vec![
+        Team {
+            id: "a".to_string(),
+            name: "Team A".to_string(),
+            maintainers: vec![maintainer("alice", &keys[0]), maintainer("bob", &keys[1])],
+        },
+        Team {
+            id: "b".to_string(),
+            name: "Team B".to_string(),
+            maintainers: vec![maintainer("carol", &keys[2])],
+        },
+    ];
+    let nested = NestedMultisig::new(teams, 2, 1).unwrap();
+    let signatures = signatures();
+
+    let result = nested
+        .verify(
+            &message(),
+            &[
+                ("alice".to_string(), signatures[0].clone()),
+                ("carol".to_string(), signatures[2].clone()),
+            ],
+        )
+        .unwrap();
+    assert!(result.inter_team_approved);
+
+    let result = nested
+        .verify(&message(), &[("bob".to_string(), signatures[1].clone())])
+        .unwrap();
+    assert!(!result.inter_team_approved);
+}